@@ -0,0 +1,235 @@
+use crate::{
+    common::{ensure_dir_exists, strip_off_newlines},
+    SECRETS_DIR_FLAG, VALIDATOR_DIR_FLAG,
+};
+use clap::{App, Arg, ArgMatches};
+use eth2_keystore::{Error as KeystoreError, Keystore, PlainText};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs::{self, File};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use types::Keypair;
+use validator_dir::Builder as ValidatorDirBuilder;
+
+pub const CMD: &str = "import";
+pub const DIRECTORY_FLAG: &str = "directory";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Imports the EIP-2335 keystores in a directory (such as the output of the \
+            eth2.0-deposit-cli) into the validator client data directory, registering each \
+            of them with its slashing protection database. The validator client must be \
+            stopped.",
+        )
+        .arg(
+            Arg::with_name(DIRECTORY_FLAG)
+                .long(DIRECTORY_FLAG)
+                .value_name("KEYSTORES_DIRECTORY")
+                .help(
+                    "A directory containing EIP-2335 keystores. Any file which is not a valid \
+                    keystore (e.g., a deposit data file) is ignored.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_PATH")
+                .help(
+                    "A path to a file containing the password which will unlock all of the \
+                    keystores. If absent, each password is read from stdin.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path where the validator directories will be created. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path where the validator keystore passwords will be stored. \
+                    Defaults to ~/.lighthouse/secrets",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let keystores_dir: PathBuf = clap_utils::parse_required(matches, DIRECTORY_FLAG)?;
+    let password_file: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let secrets_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        SECRETS_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("secrets"),
+    )?;
+
+    ensure_dir_exists(&validator_dir)?;
+    ensure_dir_exists(&secrets_dir)?;
+
+    let keystores = read_keystores(&keystores_dir)?;
+    if keystores.is_empty() {
+        return Err(format!(
+            "No EIP-2335 keystores found in {:?}",
+            keystores_dir
+        ));
+    }
+
+    let file_password = password_file
+        .map(|path| {
+            fs::read(&path)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))
+                .map(|bytes| PlainText::from(strip_off_newlines(bytes)))
+        })
+        .transpose()?;
+
+    let slashing_db_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_db = SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection DB {:?}: {:?}",
+            slashing_db_path, e
+        )
+    })?;
+
+    // The `eth2.0-deposit-cli` encrypts all keystores with the same password, so any password
+    // which has already unlocked a keystore is tried before prompting for another.
+    let mut known_passwords = vec![];
+    let mut stdin = io::stdin();
+
+    let n = keystores.len();
+    for (i, (path, keystore)) in keystores.into_iter().enumerate() {
+        let dest = validator_dir.join(format!("0x{}", keystore.pubkey()));
+        if dest.exists() {
+            eprintln!(
+                "Skipping {:?}, validator 0x{} already exists",
+                path,
+                keystore.pubkey()
+            );
+            continue;
+        }
+
+        let (keypair, password) = match &file_password {
+            Some(password) => decrypt_keystore(&keystore, password)
+                .map_err(|e| format!("Unable to decrypt {:?}: {:?}", path, e))
+                .map(|keypair| (keypair, password.clone()))?,
+            None => unlock_from_stdin(&path, &keystore, &mut known_passwords, &mut stdin)?,
+        };
+
+        // The validator is registered before its keystore is copied, so that the validator
+        // client can never load it without slashing protection. A newly registered validator
+        // has no signing history, permitting it to sign any message from genesis onwards.
+        slashing_db.register_validator(&keypair.pk).map_err(|e| {
+            format!(
+                "Unable to register 0x{} with slashing protection: {:?}",
+                keystore.pubkey(),
+                e
+            )
+        })?;
+
+        let voting_pubkey = keystore.pubkey().to_string();
+
+        ValidatorDirBuilder::new(validator_dir.clone(), secrets_dir.clone())
+            .voting_keystore(keystore, password.as_bytes())
+            .store_withdrawal_keystore(false)
+            .build()
+            .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
+
+        println!("{}/{}\t0x{}", i + 1, n, voting_pubkey);
+    }
+
+    Ok(())
+}
+
+/// Returns all files in `dir` which can be parsed as an EIP-2335 keystore, sorted by path.
+fn read_keystores(dir: &Path) -> Result<Vec<(PathBuf, Keystore)>, String> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let keystore = File::open(&path)
+                .ok()
+                .and_then(|file| Keystore::from_json_reader(file).ok())?;
+            Some((path, keystore))
+        })
+        .collect())
+}
+
+/// Decrypts `keystore` with `password`.
+fn decrypt_keystore(keystore: &Keystore, password: &PlainText) -> Result<Keypair, KeystoreError> {
+    keystore.decrypt_keypair(password.as_bytes())
+}
+
+/// Unlocks `keystore` with one of the `known_passwords`, or else with a password read from
+/// `stdin`. The password is read again until it unlocks the keystore.
+///
+/// If `stdin` is a terminal, the password is not echoed as it is typed.
+fn unlock_from_stdin(
+    path: &Path,
+    keystore: &Keystore,
+    known_passwords: &mut Vec<PlainText>,
+    stdin: &mut io::Stdin,
+) -> Result<(Keypair, PlainText), String> {
+    for password in known_passwords.iter() {
+        if let Ok(keypair) = decrypt_keystore(keystore, password) {
+            return Ok((keypair, password.clone()));
+        }
+    }
+
+    loop {
+        eprint!("Enter the password for {:?}: ", path);
+        io::stderr()
+            .flush()
+            .map_err(|e| format!("Unable to flush stderr: {:?}", e))?;
+
+        let line = if atty::is(atty::Stream::Stdin) {
+            rpassword::read_password()
+                .map_err(|e| format!("Unable to read password from stdin: {:?}", e))?
+        } else {
+            let mut line = String::new();
+            let bytes_read = stdin
+                .lock()
+                .read_line(&mut line)
+                .map_err(|e| format!("Unable to read password from stdin: {:?}", e))?;
+            if bytes_read == 0 {
+                return Err(format!("No password provided for {:?}", path));
+            }
+            line
+        };
+
+        let password = PlainText::from(strip_off_newlines(line.into_bytes()));
+        match decrypt_keystore(keystore, &password) {
+            Ok(keypair) => {
+                known_passwords.push(password.clone());
+                return Ok((keypair, password));
+            }
+            Err(KeystoreError::InvalidPassword) => eprintln!("Invalid password, try again."),
+            Err(e) => return Err(format!("Unable to decrypt {:?}: {:?}", path, e)),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Provides an LRU cache of the active validator indices and total active balance of an epoch.
+//!
+//! Determining the active validators of an epoch requires iterating the entire validator
+//! registry, which is wasteful when fork choice, validator duties and the HTTP API all need the
+//! same set. Validator activations, exits and effective balance changes only take effect at epoch
+//! boundaries, therefore the active validators of an epoch are decided by the block at the last
+//! slot of the previous epoch. The cache is keyed by the epoch and the root of that block.
+use crate::errors::BeaconChainError as Error;
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use lru::LruCache;
+use std::cmp;
+use std::sync::Arc;
+use types::{BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, Hash256, RelativeEpoch};
+
+/// The size of the LRU cache that stores the active validators of an epoch.
+///
+/// Each entry should be approximately `8 * 100,000 = 800,000` bytes in size with 100k active
+/// validators, so the cache should be approx `16 * 800,000 = 12.8 MB`.
+const CACHE_SIZE: usize = 16;
+
+/// The active validators of some epoch.
+#[derive(Debug, PartialEq)]
+pub struct ActiveValidators {
+    /// The indices of the active validators, in ascending order.
+    pub indices: Vec<usize>,
+    /// The sum of the effective balances of the active validators (with a minimum of
+    /// `spec.effective_balance_increment`, as per `get_total_active_balance`).
+    pub total_active_balance: u64,
+}
+
+impl ActiveValidators {
+    /// Computes the active validators of the current epoch of `state`.
+    ///
+    /// The indices are read from the committee cache of `state` if it is built, otherwise the
+    /// validator registry is iterated.
+    pub fn from_state<E: EthSpec>(
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<Self, BeaconStateError> {
+        let mut indices = match state.get_cached_active_validator_indices(RelativeEpoch::Current) {
+            Ok(indices) => indices.to_vec(),
+            Err(_) => state.get_active_validator_indices(state.current_epoch(), spec)?,
+        };
+        indices.sort_unstable();
+
+        let total_active_balance = cmp::max(
+            spec.effective_balance_increment,
+            state.get_total_balance(&indices, spec)?,
+        );
+
+        Ok(Self {
+            indices,
+            total_active_balance,
+        })
+    }
+
+    /// Returns `true` if the validator at `validator_index` is active.
+    pub fn is_active(&self, validator_index: usize) -> bool {
+        self.indices.binary_search(&validator_index).is_ok()
+    }
+}
+
+/// Provides an LRU cache for `ActiveValidators`, keyed by epoch and the root of the block at the
+/// last slot of the previous epoch.
+pub struct ActiveValidatorsCache {
+    cache: LruCache<(Epoch, Hash256), Arc<ActiveValidators>>,
+}
+
+impl ActiveValidatorsCache {
+    pub fn new() -> Self {
+        Self {
+            cache: LruCache::new(CACHE_SIZE),
+        }
+    }
+
+    pub fn get(&mut self, epoch: Epoch, root: Hash256) -> Option<Arc<ActiveValidators>> {
+        let opt = self.cache.get(&(epoch, root)).cloned();
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::ACTIVE_VALIDATORS_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::ACTIVE_VALIDATORS_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    pub fn insert(
+        &mut self,
+        epoch: Epoch,
+        root: Hash256,
+        active_validators: Arc<ActiveValidators>,
+    ) {
+        let key = (epoch, root);
+
+        if !self.cache.contains(&key) {
+            self.cache.put(key, active_validators);
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the active validators of the current epoch of `state`, reading them from the
+    /// `active_validators_cache` if possible.
+    pub fn active_validators(
+        &self,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Arc<ActiveValidators>, Error> {
+        let epoch = state.current_epoch();
+        let decision_root = if epoch == T::EthSpec::genesis_epoch() {
+            self.genesis_block_root
+        } else {
+            *state.get_block_root(epoch.start_slot(T::EthSpec::slots_per_epoch()) - 1)?
+        };
+
+        if let Some(active_validators) = self
+            .active_validators_cache
+            .lock()
+            .get(epoch, decision_root)
+        {
+            return Ok(active_validators);
+        }
+
+        let active_validators = Arc::new(ActiveValidators::from_state(state, &self.spec)?);
+        self.active_validators_cache
+            .lock()
+            .insert(epoch, decision_root, active_validators.clone());
+
+        Ok(active_validators)
+    }
+}
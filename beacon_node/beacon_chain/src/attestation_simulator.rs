@@ -0,0 +1,203 @@
+//! Produces an unsigned "simulated" attestation each slot and later checks whether it would have
+//! been correct and included in the canonical chain.
+//!
+//! This allows operators to measure how well their node would perform for a validator (i.e., how
+//! quickly it imports blocks and how well it tracks the canonical head), without running one.
+//!
+//! A simulated attestation for `slot` is checked once it can no longer be included in a block
+//! (i.e., after `slot + SLOTS_PER_EPOCH`). Its votes are compared against the canonical chain at
+//! that time:
+//!
+//! - **head**: the canonical block root at `slot` matches `beacon_block_root`.
+//! - **target**: the canonical block root at the start of the target epoch matches `target.root`.
+//! - **source**: the canonical block root at the start of the source epoch matches `source.root`.
+//! - **inclusion**: a canonical block within the inclusion window contains an attestation with the
+//!   same `AttestationData`. Such an attestation from a real validator would have been aggregated
+//!   and included.
+
+use crate::metrics;
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use slog::{debug, Logger};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use types::{AttestationData, EthSpec, Hash256, Slot};
+
+/// The committee index used for simulated attestations. The committee does not affect the votes
+/// of an attestation, so any index is as good as another.
+const SIMULATED_COMMITTEE_INDEX: u64 = 0;
+
+/// The results of checking a simulated attestation against the canonical chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulatedAttestationOutcome {
+    pub slot: Slot,
+    pub head_hit: bool,
+    pub target_hit: bool,
+    pub source_hit: bool,
+    pub included: bool,
+}
+
+/// Tracks the simulated attestations which have not yet been checked.
+pub struct AttestationSimulator<T: BeaconChainTypes> {
+    chain: Arc<BeaconChain<T>>,
+    pending: BTreeMap<Slot, AttestationData>,
+    log: Logger,
+}
+
+impl<T: BeaconChainTypes> AttestationSimulator<T> {
+    pub fn new(chain: Arc<BeaconChain<T>>, log: Logger) -> Self {
+        Self {
+            chain,
+            pending: BTreeMap::new(),
+            log,
+        }
+    }
+
+    /// Produces a simulated attestation for `slot` using the current head of the chain.
+    ///
+    /// Should be called at the time a validator would attest (i.e., one third of the way through
+    /// `slot`).
+    pub fn produce(&mut self, slot: Slot) {
+        match self
+            .chain
+            .produce_unaggregated_attestation(slot, SIMULATED_COMMITTEE_INDEX)
+        {
+            Ok(attestation) => {
+                metrics::inc_counter(&metrics::ATTESTATION_SIMULATOR_PRODUCED);
+                self.pending.insert(slot, attestation.data);
+            }
+            Err(e) => debug!(
+                self.log,
+                "Unable to produce simulated attestation";
+                "slot" => slot,
+                "error" => format!("{:?}", e),
+            ),
+        }
+    }
+
+    /// Checks each pending attestation which can no longer be included in a block at
+    /// `current_slot`, updating the metrics and returning the outcomes.
+    pub fn check(&mut self, current_slot: Slot) -> Vec<SimulatedAttestationOutcome> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let last_includable_slot = |slot: Slot| slot + slots_per_epoch;
+
+        let ready = self
+            .pending
+            .keys()
+            .take_while(|slot| last_includable_slot(**slot) < current_slot)
+            .copied()
+            .collect::<Vec<_>>();
+
+        ready
+            .into_iter()
+            .filter_map(|slot| {
+                let data = self.pending.remove(&slot)?;
+                match self.check_attestation(&data) {
+                    Ok(outcome) => {
+                        observe_outcome(&outcome);
+                        Some(outcome)
+                    }
+                    Err(e) => {
+                        debug!(
+                            self.log,
+                            "Unable to check simulated attestation";
+                            "slot" => slot,
+                            "error" => format!("{:?}", e),
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn check_attestation(
+        &self,
+        data: &AttestationData,
+    ) -> Result<SimulatedAttestationOutcome, BeaconChainError> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let first_inclusion_slot = data.slot + 1;
+        let last_inclusion_slot = data.slot + slots_per_epoch;
+        let target_slot = data.target.epoch.start_slot(slots_per_epoch);
+        let source_slot = data.source.epoch.start_slot(slots_per_epoch);
+
+        // Collect the canonical block roots from the source slot onwards in a single pass.
+        let mut canonical_roots = BTreeMap::new();
+        for result in self.chain.rev_iter_block_roots()? {
+            let (root, slot) = result?;
+            if slot < source_slot {
+                break;
+            }
+            if slot <= last_inclusion_slot {
+                canonical_roots.insert(slot, root);
+            }
+        }
+
+        let root_at = |slot: Slot| canonical_roots.get(&slot).copied();
+
+        // The iterator repeats the previous block root for skip slots, so only load each block
+        // once.
+        let inclusion_roots = canonical_roots
+            .range(first_inclusion_slot..=last_inclusion_slot)
+            .map(|(_, root)| *root)
+            .filter(|root| Some(*root) != root_at(data.slot))
+            .collect::<HashSet<Hash256>>();
+
+        let mut included = false;
+        for root in inclusion_roots {
+            if let Some(block) = self.chain.get_block(&root)? {
+                if block
+                    .message
+                    .body
+                    .attestations
+                    .iter()
+                    .any(|attestation| attestation.data == *data)
+                {
+                    included = true;
+                    break;
+                }
+            }
+        }
+
+        Ok(SimulatedAttestationOutcome {
+            slot: data.slot,
+            head_hit: root_at(data.slot) == Some(data.beacon_block_root),
+            target_hit: root_at(target_slot) == Some(data.target.root),
+            // The genesis checkpoint has a zero root, which is always correct.
+            source_hit: data.source.epoch == 0 || root_at(source_slot) == Some(data.source.root),
+            included,
+        })
+    }
+}
+
+fn observe_outcome(outcome: &SimulatedAttestationOutcome) {
+    let observe = |hit: bool,
+                   hits: &metrics::Result<metrics::IntCounter>,
+                   misses: &metrics::Result<metrics::IntCounter>| {
+        if hit {
+            metrics::inc_counter(hits);
+        } else {
+            metrics::inc_counter(misses);
+        }
+    };
+
+    observe(
+        outcome.head_hit,
+        &metrics::ATTESTATION_SIMULATOR_HEAD_HIT,
+        &metrics::ATTESTATION_SIMULATOR_HEAD_MISS,
+    );
+    observe(
+        outcome.target_hit,
+        &metrics::ATTESTATION_SIMULATOR_TARGET_HIT,
+        &metrics::ATTESTATION_SIMULATOR_TARGET_MISS,
+    );
+    observe(
+        outcome.source_hit,
+        &metrics::ATTESTATION_SIMULATOR_SOURCE_HIT,
+        &metrics::ATTESTATION_SIMULATOR_SOURCE_MISS,
+    );
+    observe(
+        outcome.included,
+        &metrics::ATTESTATION_SIMULATOR_INCLUSION_HIT,
+        &metrics::ATTESTATION_SIMULATOR_INCLUSION_MISS,
+    );
+}
@@ -28,9 +28,9 @@
 
 use crate::{
     beacon_chain::{
-        ATTESTATION_CACHE_LOCK_TIMEOUT, HEAD_LOCK_TIMEOUT, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
-        VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
+        ATTESTATION_CACHE_LOCK_TIMEOUT, HEAD_LOCK_TIMEOUT, VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
     },
+    committee_pubkey_cache::{aggregate_pubkeys, CommitteeId},
     metrics,
     observed_attestations::ObserveOutcome,
     observed_attesters::Error as ObservedAttestersError,
@@ -44,8 +44,10 @@ use state_processing::{
     per_block_processing::errors::AttestationValidationError,
     per_slot_processing,
     signature_sets::{
+        indexed_attestation_signature_set_from_aggregate_pubkey,
         indexed_attestation_signature_set_from_pubkeys,
         signed_aggregate_selection_proof_signature_set, signed_aggregate_signature_set,
+        Error as SignatureSetError,
     },
 };
 use std::borrow::Cow;
@@ -239,8 +241,8 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
             return Err(Error::EmptyAggregationBitfield);
         }
 
-        let indexed_attestation =
-            map_attestation_committee(chain, attestation, |(committee, _)| {
+        let (indexed_attestation, committee_id) =
+            map_attestation_committee(chain, attestation, |(committee, _, committee_id)| {
                 // Note: this clones the signature which is known to be a relatively slow operation.
                 //
                 // Future optimizations should remove this clone.
@@ -259,12 +261,19 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
                     return Err(Error::AggregatorNotInCommittee { aggregator_index });
                 }
 
+                // Ensure the aggregation bitfield is the same length as the committee.
                 get_indexed_attestation(committee.committee, &attestation)
-                    .map_err(|e| BeaconChainError::from(e).into())
+                    .map(|indexed_attestation| (indexed_attestation, committee_id))
+                    .map_err(Error::Invalid)
             })?;
 
         // Ensure that all signatures are valid.
-        if !verify_signed_aggregate_signatures(chain, &signed_aggregate, &indexed_attestation)? {
+        if !verify_signed_aggregate_signatures(
+            chain,
+            &signed_aggregate,
+            &indexed_attestation,
+            committee_id,
+        )? {
             return Err(Error::InvalidSignature);
         }
 
@@ -295,6 +304,14 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
             });
         }
 
+        chain
+            .observed_activity
+            .observe_validator(attestation.data.target.epoch, aggregator_index);
+        chain.observed_activity.observe_validators(
+            attestation.data.target.epoch,
+            indexed_attestation.attesting_indices.iter().copied(),
+        );
+
         Ok(VerifiedAggregatedAttestation {
             signed_aggregate,
             indexed_attestation,
@@ -398,6 +415,10 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
             });
         }
 
+        chain
+            .observed_activity
+            .observe_validator(attestation.data.target.epoch, validator_index);
+
         Ok(Self {
             attestation,
             indexed_attestation,
@@ -462,7 +483,7 @@ pub fn verify_propagation_slot_range<T: BeaconChainTypes>(
 
     let latest_permissible_slot = chain
         .slot_clock
-        .now_with_future_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+        .now_with_future_tolerance(chain.config.maximum_gossip_clock_disparity(&chain.spec))
         .ok_or_else(|| BeaconChainError::UnableToReadSlot)?;
     if attestation_slot > latest_permissible_slot {
         return Err(Error::FutureSlot {
@@ -474,9 +495,9 @@ pub fn verify_propagation_slot_range<T: BeaconChainTypes>(
     // Taking advantage of saturating subtraction on `Slot`.
     let earliest_permissible_slot = chain
         .slot_clock
-        .now_with_past_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+        .now_with_past_tolerance(chain.config.maximum_gossip_clock_disparity(&chain.spec))
         .ok_or_else(|| BeaconChainError::UnableToReadSlot)?
-        - T::EthSpec::slots_per_epoch();
+        - chain.spec.attestation_propagation_slot_range;
     if attestation_slot < earliest_permissible_slot {
         return Err(Error::PastSlot {
             attestation_slot,
@@ -504,7 +525,7 @@ pub fn verify_attestation_signature<T: BeaconChainTypes>(
         .canonical_head
         .try_read_for(HEAD_LOCK_TIMEOUT)
         .ok_or_else(|| BeaconChainError::CanonicalHeadLockTimeout)
-        .map(|head| head.beacon_state.fork.clone())?;
+        .map(|head| head.snapshot.beacon_state.fork.clone())?;
 
     let signature_set = indexed_attestation_signature_set_from_pubkeys(
         |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
@@ -535,6 +556,9 @@ pub fn verify_attestation_signature<T: BeaconChainTypes>(
 /// - `signed_aggregate.message.selection_proof`
 /// - `signed_aggregate.message.aggregate.signature`
 ///
+/// If every member of the committee identified by `committee_id` participated in the aggregate,
+/// the last signature is verified against the committee's cached aggregate public key.
+///
 /// # Returns
 ///
 /// - `Ok(true)`: if all signatures are valid.
@@ -544,6 +568,7 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     signed_aggregate: &SignedAggregateAndProof<T::EthSpec>,
     indexed_attestation: &IndexedAttestation<T::EthSpec>,
+    committee_id: CommitteeId,
 ) -> Result<bool, Error> {
     let pubkey_cache = chain
         .validator_pubkey_cache
@@ -559,9 +584,9 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         .canonical_head
         .try_read_for(HEAD_LOCK_TIMEOUT)
         .ok_or_else(|| BeaconChainError::CanonicalHeadLockTimeout)
-        .map(|head| head.beacon_state.fork.clone())?;
+        .map(|head| head.snapshot.beacon_state.fork.clone())?;
 
-    let signature_sets = vec![
+    let mut signature_sets = vec![
         signed_aggregate_selection_proof_signature_set(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
             &signed_aggregate,
@@ -578,6 +603,53 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
             &chain.spec,
         )
         .map_err(BeaconChainError::SignatureSetError)?,
+    ];
+
+    // The aggregation bits are known to be the same length as the committee.
+    let is_full_committee = indexed_attestation.attesting_indices.len()
+        == signed_aggregate.message.aggregate.aggregation_bits.len();
+
+    let attestation_signature_set = if is_full_committee {
+        let cached_pubkey = chain
+            .committee_pubkey_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
+            .get(&committee_id);
+
+        let aggregate_pubkey = if let Some(aggregate_pubkey) = cached_pubkey {
+            aggregate_pubkey
+        } else {
+            let pubkeys = indexed_attestation
+                .attesting_indices
+                .iter()
+                .map(|&validator_index| {
+                    pubkey_cache.get(validator_index as usize).ok_or_else(|| {
+                        BeaconChainError::SignatureSetError(SignatureSetError::ValidatorUnknown(
+                            validator_index,
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let aggregate_pubkey = aggregate_pubkeys(pubkeys);
+
+            chain
+                .committee_pubkey_cache
+                .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
+                .insert(committee_id, &aggregate_pubkey);
+
+            aggregate_pubkey
+        };
+
+        indexed_attestation_signature_set_from_aggregate_pubkey(
+            &aggregate_pubkey,
+            &indexed_attestation.signature,
+            &indexed_attestation,
+            &fork,
+            chain.genesis_validators_root,
+            &chain.spec,
+        )
+    } else {
         indexed_attestation_signature_set_from_pubkeys(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
             &indexed_attestation.signature,
@@ -586,8 +658,10 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
             chain.genesis_validators_root,
             &chain.spec,
         )
-        .map_err(BeaconChainError::SignatureSetError)?,
-    ];
+        .map_err(BeaconChainError::SignatureSetError)?
+    };
+
+    signature_sets.push(attestation_signature_set);
 
     Ok(verify_signature_sets(signature_sets))
 }
@@ -601,14 +675,15 @@ pub fn obtain_indexed_attestation_and_committees_per_slot<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     attestation: &Attestation<T::EthSpec>,
 ) -> Result<(IndexedAttestation<T::EthSpec>, CommitteesPerSlot), Error> {
-    map_attestation_committee(chain, attestation, |(committee, committees_per_slot)| {
+    map_attestation_committee(chain, attestation, |(committee, committees_per_slot, _)| {
         get_indexed_attestation(committee.committee, &attestation)
             .map(|attestation| (attestation, committees_per_slot))
-            .map_err(|e| BeaconChainError::from(e).into())
+            .map_err(Error::Invalid)
     })
 }
 
-/// Runs the `map_fn` with the committee and committee count per slot for the given `attestation`.
+/// Runs the `map_fn` with the committee, committee count per slot and committee identifier for the
+/// given `attestation`.
 ///
 /// This function exists in this odd "map" pattern because efficiently obtaining the committee for
 /// an attestation can be complex. It might involve reading straight from the
@@ -624,11 +699,17 @@ pub fn map_attestation_committee<'a, T, F, R>(
 ) -> Result<R, Error>
 where
     T: BeaconChainTypes,
-    F: Fn((BeaconCommittee, CommitteesPerSlot)) -> Result<R, Error>,
+    F: Fn((BeaconCommittee, CommitteesPerSlot, CommitteeId)) -> Result<R, Error>,
 {
     let attestation_epoch = attestation.data.slot.epoch(T::EthSpec::slots_per_epoch());
     let target = &attestation.data.target;
 
+    // The target epoch bounds the number of slots skipped when loading the target state below,
+    // so it must not be chosen independently of the (already verified) attestation slot.
+    if target.epoch != attestation_epoch {
+        return Err(Error::BadTargetEpoch);
+    }
+
     // Attestation target must be for a known block.
     //
     // We use fork choice to find the target root, which means that we reject any attestation
@@ -636,11 +717,29 @@ where
     // processing an attestation that does not include our latest finalized block in its chain.
     //
     // We do not delay consideration for later, we simply drop the attestation.
-    let target_block = chain
-        .fork_choice
-        .read()
-        .get_block(&target.root)
-        .ok_or_else(|| Error::UnknownTargetRoot(target.root))?;
+    let (target_block, shuffling_decision_root) = {
+        let fork_choice = chain.fork_choice.read();
+
+        let target_block = fork_choice
+            .get_block(&target.root)
+            .ok_or_else(|| Error::UnknownTargetRoot(target.root))?;
+
+        // Key the shuffling by the block which decided it, rather than the target, so that
+        // attestations to any fork which shares that block can use the same shuffling. If the
+        // decision block has been pruned the target root still uniquely identifies the shuffling.
+        let shuffling_decision_root = fork_choice
+            .attester_shuffling_decision_root(target.root, attestation_epoch)
+            .unwrap_or(target.root);
+
+        (target_block, shuffling_decision_root)
+    };
+
+    let committee_id = CommitteeId {
+        shuffling_epoch: attestation_epoch,
+        shuffling_decision_root,
+        slot: attestation.data.slot,
+        index: attestation.data.index,
+    };
 
     // Obtain the shuffling cache, timing how long we wait.
     let cache_wait_timer =
@@ -653,11 +752,11 @@ where
 
     metrics::stop_timer(cache_wait_timer);
 
-    if let Some(committee_cache) = shuffling_cache.get(attestation_epoch, target.root) {
+    if let Some(committee_cache) = shuffling_cache.get(attestation_epoch, shuffling_decision_root) {
         let committees_per_slot = committee_cache.committees_per_slot();
         committee_cache
             .get_beacon_committee(attestation.data.slot, attestation.data.index)
-            .map(|committee| map_fn((committee, committees_per_slot)))
+            .map(|committee| map_fn((committee, committees_per_slot, committee_id)))
             .unwrap_or_else(|| {
                 Err(Error::NoCommitteeForSlotAndIndex {
                     slot: attestation.data.slot,
@@ -674,30 +773,57 @@ where
             "Attestation processing cache miss";
             "attn_epoch" => attestation_epoch.as_u64(),
             "target_block_epoch" => target_block.slot.epoch(T::EthSpec::slots_per_epoch()).as_u64(),
+            "shuffling_decision_root" => format!("{:?}", shuffling_decision_root),
         );
 
-        let state_read_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
-
-        let mut state = chain
-            .get_state(&target_block.state_root, Some(target_block.slot))?
-            .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
-
-        metrics::stop_timer(state_read_timer);
-        let state_skip_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
-
-        while state.current_epoch() + 1 < attestation_epoch {
-            // Here we tell `per_slot_processing` to skip hashing the state and just
-            // use the zero hash instead.
-            //
-            // The state roots are not useful for the shuffling, so there's no need to
-            // compute them.
-            per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
-                .map_err(|e| BeaconChainError::from(e))?;
-        }
+        // Attestations with an old target are likely to arrive in bursts, so keep the target's
+        // epoch-boundary state around to avoid loading and skipping it repeatedly.
+        let cached_state = chain
+            .checkpoint_state_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
+            .get(target);
+
+        let mut state = if let Some(state) = cached_state {
+            state
+        } else {
+            let state_read_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_READ_TIMES);
+
+            let mut state = chain
+                .get_state(&target_block.state_root, Some(target_block.slot))?
+                .ok_or_else(|| BeaconChainError::MissingBeaconState(target_block.state_root))?;
+
+            metrics::stop_timer(state_read_timer);
+            let state_skip_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_STATE_SKIP_TIMES);
+
+            let target_slot = target.epoch.start_slot(T::EthSpec::slots_per_epoch());
+            while state.slot < target_slot {
+                // Here we tell `per_slot_processing` to skip hashing the state and just
+                // use the zero hash instead.
+                //
+                // The state roots are not useful for the shuffling, so there's no need to
+                // compute them.
+                per_slot_processing(&mut state, Some(Hash256::zero()), &chain.spec)
+                    .map_err(|e| BeaconChainError::from(e))?;
+            }
+
+            metrics::stop_timer(state_skip_timer);
+
+            // A target block from a later slot than the start of the target epoch does not have
+            // an epoch-boundary state for the target checkpoint.
+            if state.slot == target_slot {
+                chain
+                    .checkpoint_state_cache
+                    .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                    .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
+                    .insert(*target, &state);
+            }
+
+            state
+        };
 
-        metrics::stop_timer(state_skip_timer);
         let committee_building_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_COMMITTEE_BUILDING_TIMES);
 
@@ -716,14 +842,14 @@ where
             .shuffling_cache
             .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
             .ok_or_else(|| BeaconChainError::AttestationCacheLockTimeout)?
-            .insert(attestation_epoch, target.root, committee_cache);
+            .insert(attestation_epoch, shuffling_decision_root, committee_cache);
 
         metrics::stop_timer(committee_building_timer);
 
         let committees_per_slot = committee_cache.committees_per_slot();
         committee_cache
             .get_beacon_committee(attestation.data.slot, attestation.data.index)
-            .map(|committee| map_fn((committee, committees_per_slot)))
+            .map(|committee| map_fn((committee, committees_per_slot, committee_id)))
             .unwrap_or_else(|| {
                 Err(Error::NoCommitteeForSlotAndIndex {
                     slot: attestation.data.slot,
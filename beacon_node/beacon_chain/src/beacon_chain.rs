@@ -1,3 +1,4 @@
+use crate::active_validators_cache::ActiveValidatorsCache;
 use crate::attestation_verification::{
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
@@ -6,6 +7,10 @@ use crate::block_verification::{
     check_block_relevancy, get_block_root, signature_verify_chain_segment, BlockError,
     FullyVerifiedBlock, GossipVerifiedBlock, IntoFullyVerifiedBlock,
 };
+use crate::canonical_head::{CanonicalHead, ForkChoiceView};
+use crate::chain_config::ChainConfig;
+use crate::checkpoint_state_cache::CheckpointStateCache;
+use crate::committee_pubkey_cache::CommitteePubkeyCache;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::events::{EventHandler, EventKind};
@@ -13,22 +18,25 @@ use crate::head_tracker::HeadTracker;
 use crate::metrics;
 use crate::migrate::Migrate;
 use crate::naive_aggregation_pool::{Error as NaiveAggregationError, NaiveAggregationPool};
+use crate::observed_activity::ObservedActivity;
 use crate::observed_attestations::{Error as AttestationObservationError, ObservedAttestations};
 use crate::observed_attesters::{ObservedAggregators, ObservedAttesters};
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::proposal_head_votes::ProposalHeadVotes;
+use crate::proposer_preparation::ProposerPreparation;
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::SnapshotCache;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::BeaconForkChoiceStore;
 use crate::BeaconSnapshot;
-use fork_choice::ForkChoice;
+use fork_choice::{ForkChoice, ForkChoiceStore};
 use itertools::process_results;
 use operation_pool::{OperationPool, PersistedOperationPool};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::{
@@ -79,14 +87,12 @@ pub enum ChainSegmentResult {
     Failed {
         imported_blocks: usize,
         error: BlockError,
+        /// The root of the block which caused the error, if the error can be attributed to a
+        /// single block.
+        failed_block_root: Option<Hash256>,
     },
 }
 
-/// The accepted clock drift for nodes gossiping blocks and attestations. See:
-///
-/// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/p2p-interface.md#configuration
-pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
-
 #[derive(Debug, PartialEq)]
 pub enum AttestationProcessingOutcome {
     Processed,
@@ -161,6 +167,8 @@ pub trait BeaconChainTypes: Send + Sync + 'static {
 /// operations and chooses a canonical head.
 pub struct BeaconChain<T: BeaconChainTypes> {
     pub spec: ChainSpec,
+    /// Configuration which is local to this node.
+    pub config: ChainConfig,
     /// Persistent storage for blocks, states, etc. Typically an on-disk store, such as LevelDB.
     pub store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
     /// Database migrator for running background maintenance on the store.
@@ -191,10 +199,17 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub observed_proposer_slashings: ObservedOperations<ProposerSlashing, T::EthSpec>,
     /// Maintains a record of which validators we've seen attester slashings for.
     pub observed_attester_slashings: ObservedOperations<AttesterSlashing<T::EthSpec>, T::EthSpec>,
+    /// Maintains a record of which validators have been seen to attest, aggregate or propose in
+    /// recent epochs, for liveness queries.
+    pub observed_activity: ObservedActivity,
+    /// Maintains the blocks proposed by our validators which have not yet been checked for head
+    /// votes.
+    pub proposal_head_votes: ProposalHeadVotes,
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
-    /// Stores a "snapshot" of the chain at the time the head-of-the-chain block was received.
-    pub(crate) canonical_head: TimeoutRwLock<BeaconSnapshot<T::EthSpec>>,
+    /// Stores a "snapshot" of the chain at the time the head-of-the-chain block was received,
+    /// along with the view of fork choice that selected it.
+    pub(crate) canonical_head: TimeoutRwLock<CanonicalHead<T::EthSpec>>,
     /// The root of the genesis block.
     pub genesis_block_root: Hash256,
     /// The root of the list of genesis validators, used during syncing.
@@ -210,10 +225,18 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) head_tracker: Arc<HeadTracker>,
     /// A cache dedicated to block processing.
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
-    /// Caches the shuffling for a given epoch and state root.
+    /// Caches the shuffling for a given epoch and the root of the block which decided it.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
+    /// Caches the aggregate public keys of entire committees, keyed by shuffling and committee.
+    pub(crate) committee_pubkey_cache: TimeoutRwLock<CommitteePubkeyCache>,
+    /// Caches the active validators for a given epoch and the root of the block which decided them.
+    pub(crate) active_validators_cache: Mutex<ActiveValidatorsCache>,
+    /// Caches the epoch-boundary states of recent attestation target checkpoints.
+    pub(crate) checkpoint_state_cache: TimeoutRwLock<CheckpointStateCache<T::EthSpec>>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache>,
+    /// The validators registered for proposer preparation and any state prepared for them.
+    pub(crate) proposer_preparation: Mutex<ProposerPreparation<T::EthSpec>>,
     /// A list of any hard-coded forks that have been disabled.
     pub disabled_forks: Vec<String>,
     /// Logging to CLI, etc.
@@ -235,17 +258,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// We want to ensure that the head never out dates the fork choice to avoid having references
     /// to blocks that do not exist in fork choice.
+    ///
+    /// The head is persisted as not having been shut down cleanly, see
+    /// `PersistedBeaconChain::shutdown_clean`.
     pub fn persist_head_and_fork_choice(&self) -> Result<(), Error> {
+        self.persist_head_and_fork_choice_internal(false)
+    }
+
+    fn persist_head_and_fork_choice_internal(&self, shutdown_clean: bool) -> Result<(), Error> {
         let canonical_head_block_root = self
             .canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)?
+            .snapshot
             .beacon_block_root;
 
         let persisted_head = PersistedBeaconChain {
             canonical_head_block_root,
             genesis_block_root: self.genesis_block_root,
             ssz_head_tracker: self.head_tracker.to_ssz_container(),
+            shutdown_clean,
         };
 
         let fork_choice_timer = metrics::start_timer(&metrics::PERSIST_FORK_CHOICE);
@@ -430,12 +462,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         slot: Slot,
     ) -> Result<Option<SignedBeaconBlock<T::EthSpec>>, Error> {
-        let root = process_results(self.rev_iter_block_roots()?, |mut iter| {
-            iter.find(|(_, this_slot)| *this_slot == slot)
-                .map(|(root, _)| root)
-        })?;
-
-        if let Some(block_root) = root {
+        if let Some(block_root) = self.root_at_slot(slot)? {
             Ok(self.store.get_item(&block_root)?)
         } else {
             Ok(None)
@@ -477,28 +504,28 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)
-            .map(|v| v.clone_with_only_committee_caches())
+            .map(|v| v.snapshot.clone_with_only_committee_caches())
     }
 
     /// Returns info representing the head block and state.
     ///
     /// A summarized version of `Self::head` that involves less cloning.
     pub fn head_info(&self) -> Result<HeadInfo, Error> {
-        let head = self
-            .canonical_head
+        self.canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)
+            .map(|head| head.head_info())
+    }
 
-        Ok(HeadInfo {
-            slot: head.beacon_block.slot(),
-            block_root: head.beacon_block_root,
-            state_root: head.beacon_state_root,
-            current_justified_checkpoint: head.beacon_state.current_justified_checkpoint.clone(),
-            finalized_checkpoint: head.beacon_state.finalized_checkpoint.clone(),
-            fork: head.beacon_state.fork.clone(),
-            genesis_time: head.beacon_state.genesis_time,
-            genesis_validators_root: head.beacon_state.genesis_validators_root,
-        })
+    /// Returns the view of fork choice at the time the current head was selected.
+    ///
+    /// Unlike reading from `self.fork_choice` directly, the returned value is always consistent
+    /// with `Self::head` and `Self::head_info`.
+    pub fn fork_choice_view(&self) -> Result<ForkChoiceView, Error> {
+        self.canonical_head
+            .try_read_for(HEAD_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)
+            .map(|head| head.fork_choice_view)
     }
 
     /// Returns the current heads of the `BeaconChain`. For the canonical head, see `Self::head`.
@@ -609,7 +636,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn best_slot(&self) -> Result<Slot, Error> {
         self.canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
-            .map(|head| head.beacon_block.slot())
+            .map(|head| head.snapshot.beacon_block.slot())
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)
     }
 
@@ -659,6 +686,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// Returns None if a block doesn't exist at the slot.
     pub fn root_at_slot(&self, target_slot: Slot) -> Result<Option<Hash256>, Error> {
+        // Finalized roots can be read directly from the freezer, without iterating.
+        if let Some(root) = self.store.get_cold_block_root(target_slot)? {
+            return Ok(Some(root));
+        }
+
         process_results(self.rev_iter_block_roots()?, |mut iter| {
             iter.find(|(_, slot)| *slot == target_slot)
                 .map(|(root, _)| root)
@@ -761,10 +793,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<Attestation<T::EthSpec>, Error> {
         // Note: we're taking a lock on the head. The work involved here should be trivial enough
         // that the lock should not be held for long.
-        let head = self
+        let head_lock = self
             .canonical_head
             .try_read_for(HEAD_LOCK_TIMEOUT)
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+        let head = &head_lock.snapshot;
 
         if slot >= head.beacon_block.slot() {
             self.produce_unaggregated_attestation_for_block(
@@ -791,7 +824,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             // Avoid holding a lock on the head whilst doing database reads. Good boi functions
             // don't hog locks.
-            drop(head);
+            drop(head_lock);
 
             let mut state = self
                 .get_state(&state_root, Some(slot))?
@@ -985,6 +1018,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .canonical_head
                 .try_read_for(HEAD_LOCK_TIMEOUT)
                 .ok_or_else(|| Error::CanonicalHeadLockTimeout)?
+                .snapshot
                 .beacon_state
                 .fork
                 .clone();
@@ -1080,7 +1114,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         exit: SignedVoluntaryExit,
     ) -> Result<ObservationOutcome<SignedVoluntaryExit>, Error> {
-        // NOTE: this could be more efficient if it avoided cloning the head state
+        // Avoid cloning the head state for exits which are already known.
+        if self.observed_voluntary_exits.is_known(&exit) {
+            return Ok(ObservationOutcome::AlreadyKnown);
+        }
+
         let wall_clock_state = self.wall_clock_state()?;
         Ok(self
             .observed_voluntary_exits
@@ -1099,6 +1137,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         proposer_slashing: ProposerSlashing,
     ) -> Result<ObservationOutcome<ProposerSlashing>, Error> {
+        if self
+            .observed_proposer_slashings
+            .is_known(&proposer_slashing)
+        {
+            return Ok(ObservationOutcome::AlreadyKnown);
+        }
+
         let wall_clock_state = self.wall_clock_state()?;
         Ok(self.observed_proposer_slashings.verify_and_observe(
             proposer_slashing,
@@ -1119,6 +1164,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         attester_slashing: AttesterSlashing<T::EthSpec>,
     ) -> Result<ObservationOutcome<AttesterSlashing<T::EthSpec>>, Error> {
+        if self
+            .observed_attester_slashings
+            .is_known(&attester_slashing)
+        {
+            return Ok(ObservationOutcome::AlreadyKnown);
+        }
+
         let wall_clock_state = self.wall_clock_state()?;
         Ok(self.observed_attester_slashings.verify_and_observe(
             attester_slashing,
@@ -1148,10 +1200,39 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// blocks might be imported.
     ///
     /// This method is generally much more efficient than importing each block using
-    /// `Self::process_block`.
+    /// `Self::process_block`. Fork choice is persisted once, after the whole segment has been
+    /// processed, rather than after each block.
     pub fn process_chain_segment(
         &self,
         chain_segment: Vec<SignedBeaconBlock<T::EthSpec>>,
+    ) -> ChainSegmentResult {
+        let result = self.import_chain_segment(chain_segment);
+
+        let imported_blocks = match &result {
+            ChainSegmentResult::Successful { imported_blocks }
+            | ChainSegmentResult::Failed {
+                imported_blocks, ..
+            } => *imported_blocks,
+        };
+        if imported_blocks > 0 {
+            if let Err(e) = self.persist_head_and_fork_choice() {
+                error!(
+                    self.log,
+                    "Unable to persist fork choice";
+                    "error" => format!("{:?}", e),
+                    "location" => "chain segment",
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Verifies and imports the blocks of `chain_segment`, as described by
+    /// `Self::process_chain_segment`.
+    fn import_chain_segment(
+        &self,
+        chain_segment: Vec<SignedBeaconBlock<T::EthSpec>>,
     ) -> ChainSegmentResult {
         let mut filtered_chain_segment = Vec::with_capacity(chain_segment.len());
         let mut imported_blocks = 0;
@@ -1178,6 +1259,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error: BlockError::NonLinearParentRoots,
+                        failed_block_root: Some(block_root),
                     };
                 }
 
@@ -1186,6 +1268,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error: BlockError::NonLinearSlots,
+                        failed_block_root: Some(block_root),
                     };
                 }
             }
@@ -1217,6 +1300,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error: BlockError::BeaconChainError(e),
+                        failed_block_root: None,
                     }
                 }
                 // If the block was decided to be irrelevant for any other reason, don't include
@@ -1244,25 +1328,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let mut blocks = filtered_chain_segment.split_off(last_index);
             std::mem::swap(&mut blocks, &mut filtered_chain_segment);
 
-            // Verify the signature of the blocks, returning early if the signature is invalid.
+            // Verify the signatures of all the blocks in a single batch.
+            //
+            // If the batch is invalid it is not known which block(s) caused the failure, so fall
+            // back to verifying and importing each block individually. This imports any valid
+            // blocks prior to the invalid one and identifies the invalid block.
             let signature_verified_blocks = match signature_verify_chain_segment(blocks, self) {
                 Ok(blocks) => blocks,
-                Err(error) => {
+                Err((BlockError::InvalidSignature, blocks)) => {
+                    for (block_root, block) in blocks {
+                        match self.process_block(block) {
+                            Ok(_) => imported_blocks += 1,
+                            Err(error) => {
+                                return ChainSegmentResult::Failed {
+                                    imported_blocks,
+                                    error,
+                                    failed_block_root: Some(block_root),
+                                }
+                            }
+                        }
+                    }
+                    continue;
+                }
+                Err((error, blocks)) => {
+                    let failed_block_root = blocks.first().map(|(block_root, _)| *block_root);
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error,
-                    }
+                        failed_block_root,
+                    };
                 }
             };
 
             // Import the blocks into the chain.
             for signature_verified_block in signature_verified_blocks {
+                let block_root = signature_verified_block.block_root();
                 match self.process_block(signature_verified_block) {
                     Ok(_) => imported_blocks += 1,
                     Err(error) => {
                         return ChainSegmentResult::Failed {
                             imported_blocks,
                             error,
+                            failed_block_root: Some(block_root),
                         }
                     }
                 }
@@ -1462,16 +1569,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             let committee_cache = state.committee_cache(RelativeEpoch::Current)?;
 
-            let epoch_start_slot = state
+            // See `ForkChoice::attester_shuffling_decision_root`.
+            let decision_slot = state
                 .current_epoch()
-                .start_slot(T::EthSpec::slots_per_epoch());
-            let target_root = if state.slot == epoch_start_slot {
-                block_root
-            } else {
-                *state.get_block_root(epoch_start_slot)?
-            };
+                .saturating_sub(1_u64)
+                .start_slot(T::EthSpec::slots_per_epoch())
+                .saturating_sub(1_u64);
+            let decision_root = *state.get_block_root(decision_slot)?;
 
-            shuffling_cache.insert(state.current_epoch(), target_root, committee_cache);
+            shuffling_cache.insert(state.current_epoch(), decision_root, committee_cache);
+            drop(shuffling_cache);
+
+            self.active_validators(&state)?;
         }
 
         let mut fork_choice = self.fork_choice.write();
@@ -1481,10 +1590,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
             fork_choice
-                .on_block(current_slot, block, block_root, &state)
+                .on_block(current_slot, block, block_root, &state, &self.spec)
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
         }
 
+        self.observed_activity.observe_validator(
+            block.slot.epoch(T::EthSpec::slots_per_epoch()),
+            block.proposer_index,
+        );
+
         // Register each attestation in the block with the fork choice service.
         for attestation in &block.body.attestations[..] {
             let _fork_choice_attestation_timer =
@@ -1495,6 +1609,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let indexed_attestation = get_indexed_attestation(committee.committee, attestation)
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
 
+            self.observed_activity.observe_validators(
+                attestation.data.target.epoch,
+                indexed_attestation.attesting_indices.iter().copied(),
+            );
+
             match fork_choice.on_attestation(current_slot, &indexed_attestation) {
                 Ok(()) => Ok(()),
                 // Ignore invalid attestations whilst importing attestations from a block. The
@@ -1509,6 +1628,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             block.body.attestations.len() as f64,
         );
 
+        for outcome in self.proposal_head_votes.process_block(block, &state) {
+            info!(
+                self.log,
+                "Head votes for proposed block";
+                "percentage" => outcome.head_vote_percentage(),
+                "attesters" => outcome.attesters,
+                "head_votes" => outcome.head_votes,
+                "block_root" => format!("{}", outcome.block_root),
+                "slot" => outcome.slot,
+            );
+        }
+
         let db_write_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_WRITE);
 
         // Store all the states between the parent block state and this block's slot, the block and state.
@@ -1564,10 +1695,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         randao_reveal: Signature,
         slot: Slot,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
-        let state = self
-            .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
+        // Use the state prepared ahead of the slot, if it is still valid.
+        let prepared_state = self
+            .take_prepared_proposer_state(slot)
             .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
 
+        let state = match prepared_state {
+            Some(state) => state,
+            None => self
+                .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
+                .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?,
+        };
+
         self.produce_block_on_state(state, slot, randao_reveal)
     }
 
@@ -1698,14 +1837,75 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         result
     }
 
+    /// Records the time between the start of `head_slot` and the block becoming the head.
+    ///
+    /// Logs a warning if a block from the current slot becomes the head after the attestation
+    /// deadline (one third of the way through the slot), since attesters are likely to have voted
+    /// for its parent instead.
+    fn observe_head_block_delay(&self, head_block_root: Hash256, head_slot: Slot) {
+        let delay = match self.slot_clock.duration_since_start_of(head_slot) {
+            Some(delay) => delay,
+            None => return,
+        };
+
+        metrics::observe(
+            &metrics::BEACON_BLOCK_HEAD_SLOT_START_DELAY_TIME,
+            delay.as_secs_f64(),
+        );
+
+        // Blocks from prior slots are expected to arrive late whilst syncing, don't warn about
+        // them.
+        let attestation_deadline = self.slot_clock.unagg_attestation_production_delay();
+        if self.slot_clock.now() == Some(head_slot) && delay > attestation_deadline {
+            warn!(
+                self.log,
+                "Delayed head block";
+                "msg" => "the block was imported after the attestation deadline",
+                "delay_ms" => delay.as_millis() as u64,
+                "block_root" => format!("{}", head_block_root),
+                "slot" => head_slot,
+            );
+        }
+    }
+
     fn fork_choice_internal(&self) -> Result<(), Error> {
-        // Determine the root of the block that is the head of the chain.
-        let beacon_block_root = self.fork_choice.write().get_head(self.slot()?)?;
+        // Determine the root of the block that is the head of the chain, along with the fork
+        // choice checkpoints that were used to select it.
+        let fork_choice_view = {
+            let mut fork_choice = self.fork_choice.write();
+
+            let find_head_timer = metrics::start_timer(&metrics::FORK_CHOICE_FIND_HEAD_TIMES);
+            let head_block_root = fork_choice.get_head(self.slot()?)?;
+            metrics::stop_timer(find_head_timer);
+
+            ForkChoiceView {
+                head_block_root,
+                justified_checkpoint: *fork_choice.fc_store().justified_checkpoint(),
+                finalized_checkpoint: *fork_choice.fc_store().finalized_checkpoint(),
+            }
+        };
+        let beacon_block_root = fork_choice_view.head_block_root;
 
-        let current_head = self.head_info()?;
-        let old_finalized_root = current_head.finalized_checkpoint.root;
+        // Finalization is detected using the checkpoints of the head state, since `HeadInfo`
+        // reports those of fork choice.
+        let (current_head, old_finalized_checkpoint) = {
+            let canonical_head = self
+                .canonical_head
+                .try_read_for(HEAD_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+            (
+                canonical_head.head_info(),
+                canonical_head.snapshot.beacon_state.finalized_checkpoint,
+            )
+        };
+        let old_finalized_root = old_finalized_checkpoint.root;
 
         if beacon_block_root == current_head.block_root {
+            // The head block is unchanged, however the fork choice checkpoints may have moved.
+            self.canonical_head
+                .try_write_for(HEAD_LOCK_TIMEOUT)
+                .ok_or_else(|| Error::CanonicalHeadLockTimeout)?
+                .fork_choice_view = fork_choice_view;
             return Ok(());
         }
 
@@ -1783,7 +1983,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         };
 
-        let old_finalized_epoch = current_head.finalized_checkpoint.epoch;
+        self.observe_head_block_delay(beacon_block_root, new_head.beacon_block.slot());
+
+        let old_finalized_epoch = old_finalized_checkpoint.epoch;
         let new_finalized_epoch = new_head.beacon_state.finalized_checkpoint.epoch;
         let finalized_root = new_head.beacon_state.finalized_checkpoint.root;
 
@@ -1808,11 +2010,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let update_head_timer = metrics::start_timer(&metrics::UPDATE_HEAD_TIMES);
 
         // Update the snapshot that stores the head of the chain at the time it received the
-        // block.
+        // block. This is the only place where the head is replaced, and it is replaced in its
+        // entirety to ensure readers never observe a partially-updated head.
         *self
             .canonical_head
             .try_write_for(HEAD_LOCK_TIMEOUT)
-            .ok_or_else(|| Error::CanonicalHeadLockTimeout)? = new_head;
+            .ok_or_else(|| Error::CanonicalHeadLockTimeout)? =
+            CanonicalHead::new(new_head, fork_choice_view);
 
         metrics::stop_timer(update_head_timer);
 
@@ -1903,8 +2107,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .get_state(&finalized_block.state_root, Some(finalized_block.slot))?
                 .ok_or_else(|| Error::MissingBeaconState(finalized_block.state_root))?;
 
-            self.op_pool
-                .prune_all(&finalized_state, self.head_info()?.fork);
+            metrics::scrape_op_pool_sizes(self, &metrics::OP_POOL_SIZE_BEFORE_PRUNE);
+            {
+                let head = self
+                    .canonical_head
+                    .try_read_for(HEAD_LOCK_TIMEOUT)
+                    .ok_or_else(|| Error::CanonicalHeadLockTimeout)?;
+                self.op_pool
+                    .prune_all(&finalized_state, &head.snapshot.beacon_state, &self.spec);
+            }
+            metrics::scrape_op_pool_sizes(self, &metrics::OP_POOL_SIZE_AFTER_PRUNE);
 
             // TODO: configurable max finality distance
             let max_finality_distance = 0;
@@ -2005,6 +2217,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .try_read_for(HEAD_LOCK_TIMEOUT)
             .ok_or_else(|| Error::CanonicalHeadLockTimeout)
             .unwrap()
+            .snapshot
             .beacon_block_root;
         let mut visited: HashSet<Hash256> = HashSet::new();
         let mut finalized_blocks: HashSet<Hash256> = HashSet::new();
@@ -2095,10 +2308,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
 impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
     fn drop(&mut self) {
+        // Persist the head last so that it is only marked as cleanly shut down once everything
+        // else has been written.
         let drop = || -> Result<(), Error> {
-            self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
-            self.persist_eth1_cache()
+            self.persist_eth1_cache()?;
+            self.persist_head_and_fork_choice_internal(true)
         };
 
         if let Err(e) = drop() {
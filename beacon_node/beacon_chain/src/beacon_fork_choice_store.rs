@@ -11,8 +11,8 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use store::{Error as StoreError, HotColdDB, ItemStore};
 use types::{
-    BeaconBlock, BeaconState, BeaconStateError, Checkpoint, EthSpec, Hash256, SignedBeaconBlock,
-    Slot,
+    BeaconBlock, BeaconState, BeaconStateError, Checkpoint, EthSpec, Hash256, RelativeEpoch,
+    SignedBeaconBlock, Slot,
 };
 
 #[derive(Debug)]
@@ -43,7 +43,21 @@ const MAX_BALANCE_CACHE_SIZE: usize = 4;
 ///
 /// Any validator who is not active in the epoch of the given `state` is assigned a balance of
 /// zero.
+///
+/// If the committee cache for the current epoch is built, the active validator indices are read
+/// from it rather than checking the activity of every validator in the registry.
 pub fn get_effective_balances<T: EthSpec>(state: &BeaconState<T>) -> Vec<u64> {
+    if let Ok(active_indices) = state.get_cached_active_validator_indices(RelativeEpoch::Current) {
+        let mut balances = vec![0; state.validators.len()];
+        for &i in active_indices {
+            if let (Some(balance), Some(validator)) = (balances.get_mut(i), state.validators.get(i))
+            {
+                *balance = validator.effective_balance;
+            }
+        }
+        return balances;
+    }
+
     state
         .validators
         .iter()
@@ -146,12 +160,10 @@ impl BalancesCache {
             .position(|item| item.block_root == block_root)
     }
 
-    /// Get the balances for the given `block_root`, if any.
-    ///
-    /// If some balances are found, they are removed from the cache.
-    pub fn get(&mut self, block_root: Hash256) -> Option<Vec<u64>> {
+    /// Get a reference to the balances for the given `block_root`, if any.
+    pub fn peek(&self, block_root: Hash256) -> Option<&[u64]> {
         let i = self.position(block_root)?;
-        Some(self.items.remove(i).balances)
+        Some(&self.items[i].balances)
     }
 }
 
@@ -261,6 +273,27 @@ where
             _phantom: PhantomData,
         })
     }
+
+    /// Load the effective balances of the state of the block at `block_root` from the database.
+    ///
+    /// This is the slow path which is used when the balances are not present in the
+    /// `BalancesCache`.
+    fn load_balances(&self, block_root: Hash256) -> Result<Vec<u64>, Error> {
+        let block = self
+            .store
+            .get_item::<SignedBeaconBlock<E>>(&block_root)
+            .map_err(Error::FailedToReadBlock)?
+            .ok_or_else(|| Error::MissingBlock(block_root))?
+            .message;
+
+        let state = self
+            .store
+            .get_state(&block.state_root, Some(block.slot))
+            .map_err(Error::FailedToReadState)?
+            .ok_or_else(|| Error::MissingState(block.state_root))?;
+
+        Ok(get_effective_balances(&state))
+    }
 }
 
 impl<E, Hot, Cold> ForkChoiceStore<E> for BeaconForkChoiceStore<E, Hot, Cold>
@@ -296,6 +329,20 @@ where
         &self.justified_balances
     }
 
+    fn checkpoint_balances(&self, checkpoint: &Checkpoint) -> Result<Vec<u64>, Error> {
+        if *checkpoint == self.justified_checkpoint {
+            return Ok(self.justified_balances.clone());
+        }
+
+        if let Some(balances) = self.balances_cache.peek(checkpoint.root) {
+            metrics::inc_counter(&metrics::BALANCES_CACHE_HITS);
+            Ok(balances.to_vec())
+        } else {
+            metrics::inc_counter(&metrics::BALANCES_CACHE_MISSES);
+            self.load_balances(checkpoint.root)
+        }
+    }
+
     fn best_justified_checkpoint(&self) -> &Checkpoint {
         &self.best_justified_checkpoint
     }
@@ -309,29 +356,9 @@ where
     }
 
     fn set_justified_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<(), Error> {
+        self.justified_balances = self.checkpoint_balances(&checkpoint)?;
         self.justified_checkpoint = checkpoint;
 
-        if let Some(balances) = self.balances_cache.get(self.justified_checkpoint.root) {
-            metrics::inc_counter(&metrics::BALANCES_CACHE_HITS);
-            self.justified_balances = balances;
-        } else {
-            metrics::inc_counter(&metrics::BALANCES_CACHE_MISSES);
-            let justified_block = self
-                .store
-                .get_item::<SignedBeaconBlock<E>>(&self.justified_checkpoint.root)
-                .map_err(Error::FailedToReadBlock)?
-                .ok_or_else(|| Error::MissingBlock(self.justified_checkpoint.root))?
-                .message;
-
-            self.justified_balances = self
-                .store
-                .get_state(&justified_block.state_root, Some(justified_block.slot))
-                .map_err(Error::FailedToReadState)?
-                .ok_or_else(|| Error::MissingState(justified_block.state_root))?
-                .balances
-                .into();
-        }
-
         Ok(())
     }
 
@@ -350,3 +377,25 @@ pub struct PersistedForkChoiceStore {
     justified_balances: Vec<u64>,
     best_justified_checkpoint: Checkpoint,
 }
+
+impl PersistedForkChoiceStore {
+    pub fn time(&self) -> Slot {
+        self.time
+    }
+
+    pub fn finalized_checkpoint(&self) -> &Checkpoint {
+        &self.finalized_checkpoint
+    }
+
+    pub fn justified_checkpoint(&self) -> &Checkpoint {
+        &self.justified_checkpoint
+    }
+
+    pub fn justified_balances(&self) -> &[u64] {
+        &self.justified_balances
+    }
+
+    pub fn best_justified_checkpoint(&self) -> &Checkpoint {
+        &self.best_justified_checkpoint
+    }
+}
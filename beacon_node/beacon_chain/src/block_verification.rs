@@ -42,10 +42,7 @@
 //! ```
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
-    beacon_chain::{
-        BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
-        VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
-    },
+    beacon_chain::{BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT},
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BeaconSnapshot,
 };
 use parking_lot::RwLockReadGuard;
@@ -171,25 +168,59 @@ impl From<DBError> for BlockError {
     }
 }
 
+/// An ordered list of blocks and their roots, as processed by `signature_verify_chain_segment`.
+pub type ChainSegment<E> = Vec<(Hash256, SignedBeaconBlock<E>)>;
+
 /// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
 /// signatures are valid, the `chain_segment` is mapped to a `Vec<SignatureVerifiedBlock>` that can
 /// later be transformed into a `FullyVerifiedBlock` without re-checking the signatures. If any
 /// signature in the block is invalid, an `Err` is returned (it is not possible to known _which_
 /// signature was invalid).
 ///
+/// The `chain_segment` is returned alongside any error, so that the caller can fall back to
+/// verifying its blocks individually without having to clone it beforehand.
+///
 /// ## Errors
 ///
 /// The given `chain_segment` must span no more than two epochs, otherwise an error will be
 /// returned.
 pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
-    chain_segment: Vec<(Hash256, SignedBeaconBlock<T::EthSpec>)>,
+    chain_segment: ChainSegment<T::EthSpec>,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<SignatureVerifiedBlock<T>>, (BlockError, ChainSegment<T::EthSpec>)> {
+    let parent = match verify_chain_segment_signatures(&chain_segment, chain) {
+        Ok(Some(parent)) => parent,
+        Ok(None) => return Ok(vec![]),
+        Err(e) => return Err((e, chain_segment)),
+    };
+
+    let mut signature_verified_blocks = chain_segment
+        .into_iter()
+        .map(|(block_root, block)| SignatureVerifiedBlock {
+            block,
+            block_root,
+            parent: None,
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(signature_verified_block) = signature_verified_blocks.first_mut() {
+        signature_verified_block.parent = Some(parent);
+    }
+
+    Ok(signature_verified_blocks)
+}
+
+/// Verifies the signatures of all blocks in `chain_segment` in a single batch, returning the
+/// parent of the first block (or `None` if `chain_segment` is empty).
+fn verify_chain_segment_signatures<T: BeaconChainTypes>(
+    chain_segment: &[(Hash256, SignedBeaconBlock<T::EthSpec>)],
     chain: &BeaconChain<T>,
-) -> Result<Vec<SignatureVerifiedBlock<T>>, BlockError> {
+) -> Result<Option<BeaconSnapshot<T::EthSpec>>, BlockError> {
     let (mut parent, slot) = if let Some(block) = chain_segment.first().map(|(_, block)| block) {
         let parent = load_parent(&block.message, chain)?;
         (parent, block.slot())
     } else {
-        return Ok(vec![]);
+        return Ok(None);
     };
 
     let highest_slot = chain_segment
@@ -206,7 +237,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
     let pubkey_cache = get_validator_pubkey_cache(chain)?;
     let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
 
-    for (block_root, block) in &chain_segment {
+    for (block_root, block) in chain_segment {
         signature_verifier.include_all_signatures(block, Some(*block_root))?;
     }
 
@@ -215,21 +246,9 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
     }
 
     drop(pubkey_cache);
+    drop(state);
 
-    let mut signature_verified_blocks = chain_segment
-        .into_iter()
-        .map(|(block_root, block)| SignatureVerifiedBlock {
-            block,
-            block_root,
-            parent: None,
-        })
-        .collect::<Vec<_>>();
-
-    if let Some(signature_verified_block) = signature_verified_blocks.first_mut() {
-        signature_verified_block.parent = Some(parent);
-    }
-
-    Ok(signature_verified_blocks)
+    Ok(Some(parent))
 }
 
 /// A wrapper around a `SignedBeaconBlock` that indicates it has been approved for re-gossiping on
@@ -291,7 +310,7 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         // Do not gossip or process blocks from future slots.
         let present_slot_with_tolerance = chain
             .slot_clock
-            .now_with_future_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+            .now_with_future_tolerance(chain.config.maximum_gossip_clock_disparity(&chain.spec))
             .ok_or_else(|| BeaconChainError::UnableToReadSlot)?;
         if block.slot() > present_slot_with_tolerance {
             return Err(BlockError::FutureSlot {
@@ -460,6 +479,10 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
             Err(BlockError::InvalidSignature)
         }
     }
+
+    pub fn block_root(&self) -> Hash256 {
+        self.block_root
+    }
 }
 
 impl<T: BeaconChainTypes> IntoFullyVerifiedBlock<T> for SignatureVerifiedBlock<T> {
@@ -710,10 +733,15 @@ pub fn check_block_relevancy<T: BeaconChainTypes>(
 ) -> Result<Hash256, BlockError> {
     let block = &signed_block.message;
 
-    // Do not process blocks from the future.
-    if block.slot > chain.slot()? {
+    // Do not process blocks from the future (with a `MAXIMUM_GOSSIP_CLOCK_DISPARITY` allowance,
+    // as for gossip blocks).
+    let present_slot_with_tolerance = chain
+        .slot_clock
+        .now_with_future_tolerance(chain.config.maximum_gossip_clock_disparity(&chain.spec))
+        .ok_or_else(|| BeaconChainError::UnableToReadSlot)?;
+    if block.slot > present_slot_with_tolerance {
         return Err(BlockError::FutureSlot {
-            present_slot: chain.slot()?,
+            present_slot: present_slot_with_tolerance,
             block_slot: block.slot,
         });
     }
@@ -1,6 +1,11 @@
+use crate::active_validators_cache::ActiveValidatorsCache;
 use crate::beacon_chain::{
     BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
 };
+use crate::canonical_head::{CanonicalHead, ForkChoiceView};
+use crate::chain_config::ChainConfig;
+use crate::checkpoint_state_cache::{CheckpointStateCache, DEFAULT_CHECKPOINT_STATE_CACHE_SIZE};
+use crate::committee_pubkey_cache::CommitteePubkeyCache;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::events::NullEventHandler;
 use crate::head_tracker::HeadTracker;
@@ -16,16 +21,17 @@ use crate::{
     Eth1ChainBackend, EventHandler,
 };
 use eth1::Config as Eth1Config;
-use fork_choice::ForkChoice;
+use fork_choice::{ForkChoice, ForkChoiceStore};
 use operation_pool::{OperationPool, PersistedOperationPool};
-use parking_lot::RwLock;
-use slog::{info, Logger};
+use parking_lot::{Mutex, RwLock};
+use slog::{info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use store::{HotColdDB, ItemStore};
+use store::{Error as StoreError, HotColdDB, ItemStore};
 use types::{
     BeaconBlock, BeaconState, ChainSpec, EthSpec, Graffiti, Hash256, Signature, SignedBeaconBlock,
     Slot,
@@ -84,21 +90,48 @@ where
     type EventHandler = TEventHandler;
 }
 
+/// The first stage of a `BeaconChainBuilder`, before it has been given a store.
+pub struct NoStore;
+
+/// The stage of a `BeaconChainBuilder` which has a store, but which has not yet resolved the anchor
+/// of the chain (i.e., a genesis state or a chain persisted in the store).
+pub struct StoreSet;
+
+/// The stage of a `BeaconChainBuilder` which has resolved the anchor of the chain, but which has
+/// not yet been given a slot clock.
+pub struct AnchorResolved;
+
+/// The final stage of a `BeaconChainBuilder`, which may be built.
+pub struct SlotClockSet;
+
+/// Implemented by the stages of a `BeaconChainBuilder` which have a store.
+pub trait HasStore {}
+
+impl HasStore for StoreSet {}
+impl HasStore for AnchorResolved {}
+impl HasStore for SlotClockSet {}
+
 /// Builds a `BeaconChain` by either creating anew from genesis, or, resuming from an existing chain
 /// persisted to `store`.
 ///
+/// The builder progresses through the stages `NoStore`, `StoreSet`, `AnchorResolved` and
+/// `SlotClockSet`, as tracked by the `S` type parameter. Each of the required components can only be
+/// supplied in its own stage (e.g., `genesis_state` requires a store and `build` requires a slot
+/// clock), so constructing the components in the wrong order is a compile error. The optional
+/// components may be supplied in any stage.
+///
 /// Types may be elided and the compiler will infer them if all necessary builder methods have been
 /// called. If type inference errors are being raised, it is likely that not all required methods
 /// have been called.
 ///
 /// See the tests for an example of a complete working example.
-pub struct BeaconChainBuilder<T: BeaconChainTypes> {
+pub struct BeaconChainBuilder<T: BeaconChainTypes, S> {
     store: Option<Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>>,
     store_migrator: Option<T::StoreMigrator>,
     canonical_head: Option<BeaconSnapshot<T::EthSpec>>,
     /// The finalized checkpoint to anchor the chain. May be genesis or a higher
     /// checkpoint.
-    pub finalized_snapshot: Option<BeaconSnapshot<T::EthSpec>>,
+    finalized_snapshot: Option<BeaconSnapshot<T::EthSpec>>,
     genesis_block_root: Option<Hash256>,
     op_pool: Option<OperationPool<T::EthSpec>>,
     eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
@@ -109,9 +142,11 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     pubkey_cache_path: Option<PathBuf>,
     validator_pubkey_cache: Option<ValidatorPubkeyCache>,
     spec: ChainSpec,
+    chain_config: ChainConfig,
     disabled_forks: Vec<String>,
     log: Option<Logger>,
     graffiti: Graffiti,
+    _stage: PhantomData<S>,
 }
 
 impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
@@ -125,6 +160,7 @@ impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStor
             THotStore,
             TColdStore,
         >,
+        NoStore,
     >
 where
     THotStore: ItemStore<TEthSpec> + 'static,
@@ -156,11 +192,92 @@ where
             disabled_forks: Vec::new(),
             validator_pubkey_cache: None,
             spec: TEthSpec::default_spec(),
+            chain_config: ChainConfig::default(),
             log: None,
             graffiti: Graffiti::default(),
+            _stage: PhantomData,
+        }
+    }
+
+    /// Sets the store (database).
+    pub fn store(
+        mut self,
+        store: Arc<HotColdDB<TEthSpec, THotStore, TColdStore>>,
+    ) -> BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        StoreSet,
+    > {
+        self.store = Some(store);
+        self.into_stage()
+    }
+}
+
+impl<T: BeaconChainTypes, S> BeaconChainBuilder<T, S> {
+    /// Moves all components into a builder of another stage.
+    fn into_stage<S2>(self) -> BeaconChainBuilder<T, S2> {
+        BeaconChainBuilder {
+            store: self.store,
+            store_migrator: self.store_migrator,
+            canonical_head: self.canonical_head,
+            finalized_snapshot: self.finalized_snapshot,
+            genesis_block_root: self.genesis_block_root,
+            op_pool: self.op_pool,
+            eth1_chain: self.eth1_chain,
+            event_handler: self.event_handler,
+            slot_clock: self.slot_clock,
+            head_tracker: self.head_tracker,
+            data_dir: self.data_dir,
+            pubkey_cache_path: self.pubkey_cache_path,
+            validator_pubkey_cache: self.validator_pubkey_cache,
+            spec: self.spec,
+            chain_config: self.chain_config,
+            disabled_forks: self.disabled_forks,
+            log: self.log,
+            graffiti: self.graffiti,
+            _stage: PhantomData,
         }
     }
+}
 
+impl<
+        TStoreMigrator,
+        TSlotClock,
+        TEth1Backend,
+        TEthSpec,
+        TEventHandler,
+        THotStore,
+        TColdStore,
+        S,
+    >
+    BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        S,
+    >
+where
+    THotStore: ItemStore<TEthSpec> + 'static,
+    TColdStore: ItemStore<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TEthSpec, THotStore, TColdStore> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
+    TEthSpec: EthSpec + 'static,
+    TEventHandler: EventHandler<TEthSpec> + 'static,
+{
     /// Override the default spec (as defined by `TEthSpec`).
     ///
     /// This method should generally be called immediately after `Self::new` to ensure components
@@ -170,14 +287,6 @@ where
         self
     }
 
-    /// Sets the store (database).
-    ///
-    /// Should generally be called early in the build chain.
-    pub fn store(mut self, store: Arc<HotColdDB<TEthSpec, THotStore, TColdStore>>) -> Self {
-        self.store = Some(store);
-        self
-    }
-
     /// Sets the store migrator.
     pub fn store_migrator(mut self, store_migrator: TStoreMigrator) -> Self {
         self.store_migrator = Some(store_migrator);
@@ -207,6 +316,71 @@ where
         self
     }
 
+    /// Sets the `BeaconChain` eth1 backend.
+    pub fn eth1_backend(mut self, backend: Option<TEth1Backend>) -> Self {
+        self.eth1_chain = backend.map(Eth1Chain::new);
+        self
+    }
+
+    /// Sets the `BeaconChain` event handler backend.
+    ///
+    /// For example, provide `WebSocketSender` as a `handler`.
+    pub fn event_handler(mut self, handler: TEventHandler) -> Self {
+        self.event_handler = Some(handler);
+        self
+    }
+
+    /// Creates a new, empty operation pool.
+    fn empty_op_pool(mut self) -> Self {
+        self.op_pool = Some(OperationPool::new());
+        self
+    }
+
+    /// Sets the `graffiti` field.
+    pub fn graffiti(mut self, graffiti: Graffiti) -> Self {
+        self.graffiti = graffiti;
+        self
+    }
+
+    /// Sets the configuration which is local to this node.
+    pub fn chain_config(mut self, config: ChainConfig) -> Self {
+        self.chain_config = config;
+        self
+    }
+}
+
+impl<
+        TStoreMigrator,
+        TSlotClock,
+        TEth1Backend,
+        TEthSpec,
+        TEventHandler,
+        THotStore,
+        TColdStore,
+        S,
+    >
+    BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        S,
+    >
+where
+    THotStore: ItemStore<TEthSpec> + 'static,
+    TColdStore: ItemStore<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TEthSpec, THotStore, TColdStore> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
+    TEthSpec: EthSpec + 'static,
+    TEventHandler: EventHandler<TEthSpec> + 'static,
+    S: HasStore,
+{
     /// Attempt to load an existing eth1 cache from the builder's `Store`.
     pub fn get_persisted_eth1_backend(&self) -> Result<Option<SszEth1>, String> {
         let store = self
@@ -231,11 +405,50 @@ where
             .map_err(|e| format!("DB error when reading persisted beacon chain: {:?}", e))?
             .is_some())
     }
+}
 
+impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
+    BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        StoreSet,
+    >
+where
+    THotStore: ItemStore<TEthSpec> + 'static,
+    TColdStore: ItemStore<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TEthSpec, THotStore, TColdStore> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
+    TEthSpec: EthSpec + 'static,
+    TEventHandler: EventHandler<TEthSpec> + 'static,
+{
     /// Attempt to load an existing chain from the builder's `Store`.
     ///
     /// May initialize several components; including the op_pool and finalized checkpoints.
-    pub fn resume_from_db(mut self) -> Result<Self, String> {
+    pub fn resume_from_db(
+        mut self,
+    ) -> Result<
+        BeaconChainBuilder<
+            Witness<
+                TStoreMigrator,
+                TSlotClock,
+                TEth1Backend,
+                TEthSpec,
+                TEventHandler,
+                THotStore,
+                TColdStore,
+            >,
+            AnchorResolved,
+        >,
+        String,
+    > {
         let log = self
             .log
             .as_ref()
@@ -265,22 +478,44 @@ where
                     .to_string()
             })?;
 
-        self.genesis_block_root = Some(chain.genesis_block_root);
-        self.head_tracker = Some(
-            HeadTracker::from_ssz_container(&chain.ssz_head_tracker)
-                .map_err(|e| format!("Failed to decode head tracker for database: {:?}", e))?,
-        );
+        if store
+            .get_item::<SignedBeaconBlock<TEthSpec>>(&chain.genesis_block_root)
+            .map_err(|e| format!("DB error when reading genesis block: {:?}", e))?
+            .is_none()
+        {
+            return Err(format!(
+                "Genesis block {:?} not found in store. The database is inconsistent, try purging \
+                 the beacon chain database.",
+                chain.genesis_block_root
+            ));
+        }
+
+        if !chain.shutdown_clean {
+            warn!(
+                log,
+                "Beacon node was not shut down cleanly";
+                "info" => "checking the database for a consistent head"
+            );
+        }
+
+        let head_tracker = HeadTracker::from_ssz_container(&chain.ssz_head_tracker)
+            .map_err(|e| format!("Failed to decode head tracker for database: {:?}", e))?;
+
+        let canonical_head = match load_snapshot(&store, chain.canonical_head_block_root)? {
+            Some(snapshot) => snapshot,
+            None => {
+                warn!(
+                    log,
+                    "Persisted head missing from database";
+                    "head_block_root" => format!("{}", chain.canonical_head_block_root),
+                    "shutdown_clean" => chain.shutdown_clean,
+                );
+                repair_head(&store, &head_tracker, chain.genesis_block_root, log)?
+            }
+        };
 
-        let head_block_root = chain.canonical_head_block_root;
-        let head_block = store
-            .get_item::<SignedBeaconBlock<TEthSpec>>(&head_block_root)
-            .map_err(|e| format!("DB error when reading head block: {:?}", e))?
-            .ok_or_else(|| "Head block not found in store".to_string())?;
-        let head_state_root = head_block.state_root();
-        let head_state = store
-            .get_state(&head_state_root, Some(head_block.slot()))
-            .map_err(|e| format!("DB error when reading head state: {:?}", e))?
-            .ok_or_else(|| "Head state not found in store".to_string())?;
+        self.genesis_block_root = Some(chain.genesis_block_root);
+        self.head_tracker = Some(head_tracker);
 
         self.op_pool = Some(
             store
@@ -290,44 +525,40 @@ where
                 .unwrap_or_else(OperationPool::new),
         );
 
-        let finalized_block_root = head_state.finalized_checkpoint.root;
-        let finalized_block = store
-            .get_item::<SignedBeaconBlock<TEthSpec>>(&finalized_block_root)
-            .map_err(|e| format!("DB error when reading finalized block: {:?}", e))?
-            .ok_or_else(|| "Finalized block not found in store".to_string())?;
-        let finalized_state_root = finalized_block.state_root();
-        let finalized_state = store
-            .get_state(&finalized_state_root, Some(finalized_block.slot()))
-            .map_err(|e| format!("DB error when reading finalized state: {:?}", e))?
-            .ok_or_else(|| "Finalized state not found in store".to_string())?;
-
-        self.finalized_snapshot = Some(BeaconSnapshot {
-            beacon_block_root: finalized_block_root,
-            beacon_block: finalized_block,
-            beacon_state_root: finalized_state_root,
-            beacon_state: finalized_state,
-        });
+        let finalized_block_root = canonical_head.beacon_state.finalized_checkpoint.root;
+        let finalized_snapshot = load_snapshot(&store, finalized_block_root)?
+            .ok_or_else(|| "Finalized block or state not found in store".to_string())?;
 
-        self.canonical_head = Some(BeaconSnapshot {
-            beacon_block_root: head_block_root,
-            beacon_block: head_block,
-            beacon_state_root: head_state_root,
-            beacon_state: head_state,
-        });
+        self.finalized_snapshot = Some(finalized_snapshot);
+        self.canonical_head = Some(canonical_head);
 
         let pubkey_cache = ValidatorPubkeyCache::load_from_file(pubkey_cache_path)
             .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?;
 
         self.validator_pubkey_cache = Some(pubkey_cache);
 
-        Ok(self)
+        Ok(self.into_stage())
     }
 
     /// Starts a new chain from a genesis state.
     pub fn genesis_state(
         mut self,
         mut beacon_state: BeaconState<TEthSpec>,
-    ) -> Result<Self, String> {
+    ) -> Result<
+        BeaconChainBuilder<
+            Witness<
+                TStoreMigrator,
+                TSlotClock,
+                TEth1Backend,
+                TEthSpec,
+                TEventHandler,
+                THotStore,
+                TColdStore,
+            >,
+            AnchorResolved,
+        >,
+        String,
+    > {
         let store = self
             .store
             .clone()
@@ -368,43 +599,85 @@ where
             beacon_state,
         });
 
-        Ok(self.empty_op_pool())
-    }
-
-    /// Sets the `BeaconChain` eth1 backend.
-    pub fn eth1_backend(mut self, backend: Option<TEth1Backend>) -> Self {
-        self.eth1_chain = backend.map(Eth1Chain::new);
-        self
-    }
-
-    /// Sets the `BeaconChain` event handler backend.
-    ///
-    /// For example, provide `WebSocketSender` as a `handler`.
-    pub fn event_handler(mut self, handler: TEventHandler) -> Self {
-        self.event_handler = Some(handler);
-        self
+        Ok(self.empty_op_pool().into_stage())
     }
+}
 
+impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
+    BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        AnchorResolved,
+    >
+where
+    THotStore: ItemStore<TEthSpec> + 'static,
+    TColdStore: ItemStore<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TEthSpec, THotStore, TColdStore> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
+    TEthSpec: EthSpec + 'static,
+    TEventHandler: EventHandler<TEthSpec> + 'static,
+{
     /// Sets the `BeaconChain` slot clock.
     ///
     /// For example, provide `SystemTimeSlotClock` as a `clock`.
-    pub fn slot_clock(mut self, clock: TSlotClock) -> Self {
+    pub fn slot_clock(
+        mut self,
+        clock: TSlotClock,
+    ) -> BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        SlotClockSet,
+    > {
         self.slot_clock = Some(clock);
-        self
-    }
-
-    /// Creates a new, empty operation pool.
-    fn empty_op_pool(mut self) -> Self {
-        self.op_pool = Some(OperationPool::new());
-        self
+        self.into_stage()
     }
 
-    /// Sets the `graffiti` field.
-    pub fn graffiti(mut self, graffiti: Graffiti) -> Self {
-        self.graffiti = graffiti;
-        self
+    /// Returns the genesis time of the chain.
+    pub fn genesis_time(&self) -> Result<u64, String> {
+        self.finalized_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.beacon_state.genesis_time)
+            .ok_or_else(|| "genesis_time requires an initialized state".to_string())
     }
+}
 
+impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
+    BeaconChainBuilder<
+        Witness<
+            TStoreMigrator,
+            TSlotClock,
+            TEth1Backend,
+            TEthSpec,
+            TEventHandler,
+            THotStore,
+            TColdStore,
+        >,
+        SlotClockSet,
+    >
+where
+    THotStore: ItemStore<TEthSpec> + 'static,
+    TColdStore: ItemStore<TEthSpec> + 'static,
+    TStoreMigrator: Migrate<TEthSpec, THotStore, TColdStore> + 'static,
+    TSlotClock: SlotClock + 'static,
+    TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
+    TEthSpec: EthSpec + 'static,
+    TEventHandler: EventHandler<TEthSpec> + 'static,
+{
     /// Consumes `self`, returning a `BeaconChain` if all required parameters have been supplied.
     ///
     /// An error will be returned at runtime if all required parameters have not been configured.
@@ -441,12 +714,16 @@ where
 
         // If this beacon chain is being loaded from disk, use the stored head. Otherwise, just use
         // the finalized checkpoint (which is probably genesis).
-        let mut canonical_head = if let Some(head) = self.canonical_head {
-            head
-        } else {
-            self.finalized_snapshot
-                .ok_or_else(|| "Cannot build without a state".to_string())?
-        };
+        //
+        // The finalized snapshot is only retained when it differs from the head, it is the anchor
+        // from which fork choice is rebuilt if required.
+        let (mut canonical_head, finalized_snapshot) =
+            match (self.canonical_head, self.finalized_snapshot) {
+                (Some(head), finalized_snapshot) => (head, finalized_snapshot),
+                (None, Some(finalized_snapshot)) => (finalized_snapshot, None),
+                (None, None) => return Err("Cannot build without a state".to_string()),
+            };
+        let head_tracker = self.head_tracker.unwrap_or_default();
 
         canonical_head
             .beacon_state
@@ -469,28 +746,79 @@ where
                     .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
             })?;
 
-        let persisted_fork_choice = store
-            .get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY))
-            .map_err(|e| format!("DB error when reading persisted fork choice: {:?}", e))?;
-
-        let fork_choice = if let Some(persisted) = persisted_fork_choice {
-            let fc_store =
-                BeaconForkChoiceStore::from_persisted(persisted.fork_choice_store, store.clone())
-                    .map_err(|e| format!("Unable to load ForkChoiceStore: {:?}", e))?;
-
-            ForkChoice::from_persisted(persisted.fork_choice, fc_store)
-                .map_err(|e| format!("Unable to parse persisted fork choice from disk: {:?}", e))?
-        } else {
-            let genesis = &canonical_head;
+        let persisted_fork_choice =
+            store.get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY));
+
+        // Borrow the spec separately, since `self` has been partially moved.
+        let spec = &self.spec;
+        let reconstruct = || {
+            reconstruct_fork_choice(
+                &store,
+                finalized_snapshot.as_ref().unwrap_or(&canonical_head),
+                &canonical_head,
+                &head_tracker,
+                spec,
+                &log,
+            )
+        };
 
-            let fc_store = BeaconForkChoiceStore::get_forkchoice_store(store.clone(), genesis);
+        let fork_choice = match persisted_fork_choice {
+            _ if self.chain_config.purge_fork_choice => {
+                warn!(
+                    log,
+                    "Purging persisted fork choice";
+                    "info" => "rebuilding fork choice from the database"
+                );
+                reconstruct()?
+            }
+            Ok(Some(persisted)) => {
+                match fork_choice_from_persisted(persisted, &store, &canonical_head) {
+                    Ok(fork_choice) => fork_choice,
+                    Err(e) => {
+                        warn!(
+                            log,
+                            "Persisted fork choice is invalid";
+                            "error" => e,
+                            "info" => "rebuilding fork choice from the database"
+                        );
+                        reconstruct()?
+                    }
+                }
+            }
+            Ok(None) => {
+                let genesis = &canonical_head;
+
+                let fc_store = BeaconForkChoiceStore::get_forkchoice_store(store.clone(), genesis);
+
+                ForkChoice::from_genesis(fc_store, &genesis.beacon_block.message)
+                    .map_err(|e| format!("Unable to build initialize ForkChoice: {:?}", e))?
+            }
+            Err(StoreError::SszDecodeError(e)) => {
+                warn!(
+                    log,
+                    "Unable to decode persisted fork choice";
+                    "error" => format!("{:?}", e),
+                    "info" => "rebuilding fork choice from the database"
+                );
+                reconstruct()?
+            }
+            Err(e) => {
+                return Err(format!(
+                    "DB error when reading persisted fork choice: {:?}",
+                    e
+                ))
+            }
+        };
 
-            ForkChoice::from_genesis(fc_store, &genesis.beacon_block.message)
-                .map_err(|e| format!("Unable to build initialize ForkChoice: {:?}", e))?
+        let fork_choice_view = ForkChoiceView {
+            head_block_root: canonical_head.beacon_block_root,
+            justified_checkpoint: *fork_choice.fc_store().justified_checkpoint(),
+            finalized_checkpoint: *fork_choice.fc_store().finalized_checkpoint(),
         };
 
         let beacon_chain = BeaconChain {
             spec: self.spec,
+            config: self.chain_config,
             store,
             store_migrator: self
                 .store_migrator
@@ -513,9 +841,14 @@ where
             observed_voluntary_exits: <_>::default(),
             observed_proposer_slashings: <_>::default(),
             observed_attester_slashings: <_>::default(),
+            observed_activity: <_>::default(),
+            proposal_head_votes: <_>::default(),
             eth1_chain: self.eth1_chain,
             genesis_validators_root: canonical_head.beacon_state.genesis_validators_root,
-            canonical_head: TimeoutRwLock::new(canonical_head.clone()),
+            canonical_head: TimeoutRwLock::new(CanonicalHead::new(
+                canonical_head.clone(),
+                fork_choice_view,
+            )),
             genesis_block_root: self
                 .genesis_block_root
                 .ok_or_else(|| "Cannot build without a genesis block root".to_string())?,
@@ -523,13 +856,19 @@ where
             event_handler: self
                 .event_handler
                 .ok_or_else(|| "Cannot build without an event handler".to_string())?,
-            head_tracker: Arc::new(self.head_tracker.unwrap_or_default()),
+            head_tracker: Arc::new(head_tracker),
             snapshot_cache: TimeoutRwLock::new(SnapshotCache::new(
                 DEFAULT_SNAPSHOT_CACHE_SIZE,
                 canonical_head,
             )),
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new()),
+            committee_pubkey_cache: TimeoutRwLock::new(CommitteePubkeyCache::new()),
+            active_validators_cache: Mutex::new(ActiveValidatorsCache::new()),
+            checkpoint_state_cache: TimeoutRwLock::new(CheckpointStateCache::new(
+                DEFAULT_CHECKPOINT_STATE_CACHE_SIZE,
+            )),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
+            proposer_preparation: Mutex::new(<_>::default()),
             disabled_forks: self.disabled_forks,
             log: log.clone(),
             graffiti: self.graffiti,
@@ -547,11 +886,17 @@ where
             "head_slot" => format!("{}", head.beacon_block.slot()),
         );
 
+        // Persist the chain as not shut down cleanly, so that a crash before the next clean
+        // shutdown is detected when resuming.
+        beacon_chain
+            .persist_head_and_fork_choice()
+            .map_err(|e| format!("Failed to persist beacon chain: {:?}", e))?;
+
         Ok(beacon_chain)
     }
 }
 
-impl<TStoreMigrator, TSlotClock, TEthSpec, TEventHandler, THotStore, TColdStore>
+impl<TStoreMigrator, TSlotClock, TEthSpec, TEventHandler, THotStore, TColdStore, S>
     BeaconChainBuilder<
         Witness<
             TStoreMigrator,
@@ -562,6 +907,7 @@ impl<TStoreMigrator, TSlotClock, TEthSpec, TEventHandler, THotStore, TColdStore>
             THotStore,
             TColdStore,
         >,
+        S,
     >
 where
     THotStore: ItemStore<TEthSpec> + 'static,
@@ -606,6 +952,7 @@ impl<TStoreMigrator, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStor
             THotStore,
             TColdStore,
         >,
+        AnchorResolved,
     >
 where
     THotStore: ItemStore<TEthSpec> + 'static,
@@ -618,13 +965,25 @@ where
     /// Sets the `BeaconChain` slot clock to `TestingSlotClock`.
     ///
     /// Requires the state to be initialized.
-    pub fn testing_slot_clock(self, slot_duration: Duration) -> Result<Self, String> {
-        let genesis_time = self
-            .finalized_snapshot
-            .as_ref()
-            .ok_or_else(|| "testing_slot_clock requires an initialized state")?
-            .beacon_state
-            .genesis_time;
+    pub fn testing_slot_clock(
+        self,
+        slot_duration: Duration,
+    ) -> Result<
+        BeaconChainBuilder<
+            Witness<
+                TStoreMigrator,
+                TestingSlotClock,
+                TEth1Backend,
+                TEthSpec,
+                TEventHandler,
+                THotStore,
+                TColdStore,
+            >,
+            SlotClockSet,
+        >,
+        String,
+    > {
+        let genesis_time = self.genesis_time()?;
 
         let slot_clock = TestingSlotClock::new(
             Slot::new(0),
@@ -636,7 +995,7 @@ where
     }
 }
 
-impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>
+impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore, S>
     BeaconChainBuilder<
         Witness<
             TStoreMigrator,
@@ -647,6 +1006,7 @@ impl<TStoreMigrator, TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>
             THotStore,
             TColdStore,
         >,
+        S,
     >
 where
     THotStore: ItemStore<TEthSpec> + 'static,
@@ -663,6 +1023,230 @@ where
     }
 }
 
+/// Loads fork choice from `persisted`, checking that it is consistent with `canonical_head` and
+/// the blocks in `store`.
+fn fork_choice_from_persisted<E, Hot, Cold>(
+    persisted: PersistedForkChoice,
+    store: &Arc<HotColdDB<E, Hot, Cold>>,
+    canonical_head: &BeaconSnapshot<E>,
+) -> Result<ForkChoice<BeaconForkChoiceStore<E, Hot, Cold>, E>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let fc_store =
+        BeaconForkChoiceStore::from_persisted(persisted.fork_choice_store, store.clone())
+            .map_err(|e| format!("Unable to load ForkChoiceStore: {:?}", e))?;
+
+    let fork_choice = ForkChoice::from_persisted(persisted.fork_choice, fc_store)
+        .map_err(|e| format!("Unable to parse persisted fork choice from disk: {:?}", e))?;
+
+    if !fork_choice.contains_block(&canonical_head.beacon_block_root) {
+        return Err(format!(
+            "Fork choice does not contain the head block {:?}",
+            canonical_head.beacon_block_root
+        ));
+    }
+
+    let finalized_root = fork_choice.fc_store().finalized_checkpoint().root;
+    if store
+        .get_item::<SignedBeaconBlock<E>>(&finalized_root)
+        .map_err(|e| format!("DB error when reading finalized block: {:?}", e))?
+        .is_none()
+    {
+        return Err(format!(
+            "Fork choice finalized block {:?} not found in store",
+            finalized_root
+        ));
+    }
+
+    Ok(fork_choice)
+}
+
+/// Rebuilds fork choice from `anchor` (the finalized snapshot) by replaying each block between it
+/// and the heads in `head_tracker` (and `canonical_head`) from `store`.
+///
+/// Votes are not recovered, they will be learned again from blocks and attestations.
+fn reconstruct_fork_choice<E, Hot, Cold>(
+    store: &Arc<HotColdDB<E, Hot, Cold>>,
+    anchor: &BeaconSnapshot<E>,
+    canonical_head: &BeaconSnapshot<E>,
+    head_tracker: &HeadTracker,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<ForkChoice<BeaconForkChoiceStore<E, Hot, Cold>, E>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let fc_store = BeaconForkChoiceStore::get_forkchoice_store(store.clone(), anchor);
+    let anchor_root = fc_store.finalized_checkpoint().root;
+    let anchor_slot = anchor.beacon_block.slot();
+
+    let mut fork_choice = ForkChoice::from_genesis(fc_store, &anchor.beacon_block.message)
+        .map_err(|e| format!("Unable to initialize ForkChoice: {:?}", e))?;
+
+    // Collect every block which descends from the anchor by walking back from each head.
+    let mut blocks: HashMap<Hash256, SignedBeaconBlock<E>> = HashMap::new();
+    let heads = head_tracker
+        .heads()
+        .into_iter()
+        .map(|(block_root, _)| block_root)
+        .chain(std::iter::once(canonical_head.beacon_block_root));
+
+    for head_block_root in heads {
+        let mut block_root = head_block_root;
+        while block_root != anchor_root && !blocks.contains_key(&block_root) {
+            let block = match store
+                .get_item::<SignedBeaconBlock<E>>(&block_root)
+                .map_err(|e| format!("DB error when reading block {:?}: {:?}", block_root, e))?
+            {
+                Some(block) if block.slot() > anchor_slot => block,
+                _ => break,
+            };
+            let parent_root = block.parent_root();
+            blocks.insert(block_root, block);
+            block_root = parent_root;
+        }
+    }
+
+    let mut blocks = blocks.into_iter().collect::<Vec<_>>();
+    blocks.sort_by_key(|(_, block)| block.slot());
+
+    let mut imported = 0;
+    for (block_root, block) in blocks {
+        let state = match store
+            .get_state(&block.state_root(), Some(block.slot()))
+            .map_err(|e| format!("DB error when reading state for {:?}: {:?}", block_root, e))?
+        {
+            Some(state) => state,
+            None => {
+                warn!(
+                    log,
+                    "Missing state whilst rebuilding fork choice";
+                    "block_root" => format!("{}", block_root),
+                    "slot" => format!("{}", block.slot()),
+                );
+                continue;
+            }
+        };
+
+        // Blocks which do not descend from the anchor, or whose ancestors could not be loaded,
+        // are skipped.
+        match fork_choice.on_block(block.slot(), &block.message, block_root, &state, spec) {
+            Ok(()) => imported += 1,
+            Err(e) => warn!(
+                log,
+                "Skipping block whilst rebuilding fork choice";
+                "block_root" => format!("{}", block_root),
+                "error" => format!("{:?}", e),
+            ),
+        }
+    }
+
+    info!(
+        log,
+        "Rebuilt fork choice from database";
+        "anchor_root" => format!("{}", anchor_root),
+        "anchor_slot" => format!("{}", anchor_slot),
+        "blocks" => imported,
+    );
+
+    Ok(fork_choice)
+}
+
+/// Loads the block with `block_root` and its post-state from `store`, returning `None` if either
+/// is missing.
+fn load_snapshot<E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    block_root: Hash256,
+) -> Result<Option<BeaconSnapshot<E>>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let beacon_block = match store
+        .get_item::<SignedBeaconBlock<E>>(&block_root)
+        .map_err(|e| format!("DB error when reading block {:?}: {:?}", block_root, e))?
+    {
+        Some(block) => block,
+        None => return Ok(None),
+    };
+    let beacon_state_root = beacon_block.state_root();
+    let beacon_state = match store
+        .get_state(&beacon_state_root, Some(beacon_block.slot()))
+        .map_err(|e| {
+            format!(
+                "DB error when reading state {:?}: {:?}",
+                beacon_state_root, e
+            )
+        })? {
+        Some(state) => state,
+        None => return Ok(None),
+    };
+
+    Ok(Some(BeaconSnapshot {
+        beacon_block_root: block_root,
+        beacon_block,
+        beacon_state_root,
+        beacon_state,
+    }))
+}
+
+/// Re-derives a head from `store` when the persisted head is missing, e.g. after an unclean
+/// shutdown.
+///
+/// The most recent head in `head_tracker` which is present in the store is used, falling back to
+/// the genesis block. Heads which are missing from the store are removed from `head_tracker`. Fork
+/// choice will replace the repaired head as soon as it finds a better one.
+fn repair_head<E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    head_tracker: &HeadTracker,
+    genesis_block_root: Hash256,
+    log: &Logger,
+) -> Result<BeaconSnapshot<E>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let mut heads = head_tracker.heads();
+    heads.sort_by_key(|(_, slot)| std::cmp::Reverse(*slot));
+
+    for (block_root, slot) in heads {
+        match load_snapshot(store, block_root)? {
+            Some(snapshot) => {
+                warn!(
+                    log,
+                    "Repaired head from database";
+                    "head_block_root" => format!("{}", block_root),
+                    "head_slot" => format!("{}", slot),
+                );
+                return Ok(snapshot);
+            }
+            None => head_tracker.remove_head(block_root),
+        }
+    }
+
+    let snapshot = load_snapshot(store, genesis_block_root)?.ok_or_else(|| {
+        "Unable to repair head: genesis block or state not found in store. Try purging the beacon \
+         chain database."
+            .to_string()
+    })?;
+
+    warn!(
+        log,
+        "Repaired head from database";
+        "head_block_root" => format!("{}", genesis_block_root),
+        "head_slot" => format!("{}", snapshot.beacon_block.slot()),
+    );
+
+    Ok(snapshot)
+}
+
 fn genesis_block<T: EthSpec>(
     genesis_state: &mut BeaconState<T>,
     spec: &ChainSpec,
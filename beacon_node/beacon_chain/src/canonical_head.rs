@@ -0,0 +1,118 @@
+//! Defines the `CanonicalHead`; the head of the canonical chain, stored alongside the view of fork
+//! choice which selected it.
+
+use crate::beacon_chain::HeadInfo;
+use crate::BeaconSnapshot;
+use types::{Checkpoint, EthSpec, Hash256};
+
+/// The view of fork choice at the time the canonical head was selected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForkChoiceView {
+    /// The root of the block returned by `ForkChoice::get_head`.
+    pub head_block_root: Hash256,
+    /// The `justified_checkpoint` of the fork choice store.
+    pub justified_checkpoint: Checkpoint,
+    /// The `finalized_checkpoint` of the fork choice store.
+    pub finalized_checkpoint: Checkpoint,
+}
+
+/// The head of the canonical chain.
+///
+/// All fields are replaced together whilst holding a single write-lock, therefore readers are
+/// always presented with a head block, head state and fork choice view that are consistent with
+/// each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanonicalHead<E: EthSpec> {
+    /// The head block and its post-state.
+    pub snapshot: BeaconSnapshot<E>,
+    /// The view of fork choice which selected `self.snapshot`.
+    pub fork_choice_view: ForkChoiceView,
+}
+
+impl<E: EthSpec> CanonicalHead<E> {
+    pub fn new(snapshot: BeaconSnapshot<E>, fork_choice_view: ForkChoiceView) -> Self {
+        Self {
+            snapshot,
+            fork_choice_view,
+        }
+    }
+
+    /// Returns a summary of the head block and state.
+    ///
+    /// The checkpoints are those of fork choice, rather than the head state. Fork choice may have
+    /// justified or finalized a checkpoint which is not yet reflected in the head state.
+    pub fn head_info(&self) -> HeadInfo {
+        let head = &self.snapshot;
+
+        HeadInfo {
+            slot: head.beacon_block.slot(),
+            block_root: head.beacon_block_root,
+            state_root: head.beacon_state_root,
+            current_justified_checkpoint: self.fork_choice_view.justified_checkpoint,
+            finalized_checkpoint: self.fork_choice_view.finalized_checkpoint,
+            fork: head.beacon_state.fork.clone(),
+            genesis_time: head.beacon_state.genesis_time,
+            genesis_validators_root: head.beacon_state.genesis_validators_root,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::{
+        test_utils::{generate_deterministic_keypair, TestingBeaconStateBuilder},
+        BeaconBlock, Epoch, MainnetEthSpec, Signature, SignedBeaconBlock,
+    };
+
+    #[test]
+    fn head_info_uses_fork_choice_checkpoints() {
+        let spec = MainnetEthSpec::default_spec();
+
+        let state_builder = TestingBeaconStateBuilder::from_deterministic_keypairs(1, &spec);
+        let (beacon_state, _keypairs) = state_builder.build();
+
+        let snapshot = BeaconSnapshot {
+            beacon_state,
+            beacon_state_root: Hash256::from_low_u64_be(1),
+            beacon_block: SignedBeaconBlock {
+                message: BeaconBlock::empty(&spec),
+                signature: Signature::new(&[42], &generate_deterministic_keypair(0).sk),
+            },
+            beacon_block_root: Hash256::from_low_u64_be(2),
+        };
+
+        let fork_choice_view = ForkChoiceView {
+            head_block_root: snapshot.beacon_block_root,
+            justified_checkpoint: Checkpoint {
+                epoch: Epoch::new(3),
+                root: Hash256::from_low_u64_be(3),
+            },
+            finalized_checkpoint: Checkpoint {
+                epoch: Epoch::new(2),
+                root: Hash256::from_low_u64_be(4),
+            },
+        };
+
+        assert_ne!(
+            snapshot.beacon_state.current_justified_checkpoint,
+            fork_choice_view.justified_checkpoint,
+            "precondition: justified checkpoints should differ"
+        );
+        assert_ne!(
+            snapshot.beacon_state.finalized_checkpoint, fork_choice_view.finalized_checkpoint,
+            "precondition: finalized checkpoints should differ"
+        );
+
+        let head_info = CanonicalHead::new(snapshot, fork_choice_view).head_info();
+
+        assert_eq!(
+            head_info.current_justified_checkpoint, fork_choice_view.justified_checkpoint,
+            "should report the fork choice justified checkpoint"
+        );
+        assert_eq!(
+            head_info.finalized_checkpoint, fork_choice_view.finalized_checkpoint,
+            "should report the fork choice finalized checkpoint"
+        );
+    }
+}
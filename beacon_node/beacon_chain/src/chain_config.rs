@@ -0,0 +1,53 @@
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+use types::ChainSpec;
+
+/// Configuration of the `BeaconChain` which is local to this node (i.e., not part of the
+/// `ChainSpec`).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// The tolerated disparity between our clock and the clocks of other nodes when checking
+    /// whether a block or attestation is from a future or past slot.
+    ///
+    /// Applies to blocks and attestations from gossip and to blocks from RPC.
+    ///
+    /// If `None`, the `MAXIMUM_GOSSIP_CLOCK_DISPARITY` of the `ChainSpec` is used.
+    pub maximum_gossip_clock_disparity_ms: Option<u64>,
+    /// Discard the persisted fork choice at startup and rebuild it from the blocks in the store.
+    pub purge_fork_choice: bool,
+}
+
+impl ChainConfig {
+    /// Returns the tolerated clock disparity, falling back to the value in `spec`.
+    pub fn maximum_gossip_clock_disparity(&self, spec: &ChainSpec) -> Duration {
+        self.maximum_gossip_clock_disparity_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| spec.maximum_gossip_clock_disparity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{EthSpec, MainnetEthSpec};
+
+    #[test]
+    fn maximum_gossip_clock_disparity() {
+        let spec = MainnetEthSpec::default_spec();
+
+        assert_eq!(
+            ChainConfig::default().maximum_gossip_clock_disparity(&spec),
+            spec.maximum_gossip_clock_disparity(),
+            "should default to the spec value"
+        );
+
+        let config = ChainConfig {
+            maximum_gossip_clock_disparity_ms: Some(1_500),
+            ..ChainConfig::default()
+        };
+        assert_eq!(
+            config.maximum_gossip_clock_disparity(&spec),
+            Duration::from_millis(1_500)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::metrics;
+use lru::LruCache;
+use types::{BeaconState, Checkpoint, EthSpec};
+
+/// The default number of epoch-boundary states to hold in the cache.
+///
+/// This is the cache's memory budget. Each entry is a full `BeaconState` (without its pubkey, exit
+/// or tree hash caches), which is approx 15 MB with 100k validators. Therefore, this cache should
+/// be approx `4 * 15 = 60 MB`.
+pub const DEFAULT_CHECKPOINT_STATE_CACHE_SIZE: usize = 4;
+
+/// Provides an LRU cache of the states at the start of the epoch of some checkpoint, i.e. the
+/// block of the checkpoint advanced through any skip slots up to the start of its epoch.
+///
+/// These states are produced without computing state roots (see `per_slot_processing`), so they
+/// are only suitable for reading shufflings, balances and the like.
+pub struct CheckpointStateCache<E: EthSpec> {
+    cache: LruCache<Checkpoint, BeaconState<E>>,
+}
+
+impl<E: EthSpec> CheckpointStateCache<E> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: LruCache::new(capacity),
+        }
+    }
+
+    /// Returns a clone of the epoch-boundary state of `checkpoint`, if it is in the cache.
+    pub fn get(&mut self, checkpoint: &Checkpoint) -> Option<BeaconState<E>> {
+        let opt = self
+            .cache
+            .get(checkpoint)
+            .map(|state| state.clone_with_only_committee_caches());
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::CHECKPOINT_STATE_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::CHECKPOINT_STATE_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    /// Adds the epoch-boundary `state` of `checkpoint` to the cache, evicting the least recently
+    /// used state if the cache is full.
+    pub fn insert(&mut self, checkpoint: Checkpoint, state: &BeaconState<E>) {
+        if !self.cache.contains(&checkpoint) {
+            self.cache
+                .put(checkpoint, state.clone_with_only_committee_caches());
+        }
+
+        metrics::set_gauge(
+            &metrics::CHECKPOINT_STATE_CACHE_SIZE,
+            self.cache.len() as i64,
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::{test_utils::TestingBeaconStateBuilder, Epoch, Hash256, MainnetEthSpec, Slot};
+
+    fn get_state(slot: u64) -> BeaconState<MainnetEthSpec> {
+        let spec = MainnetEthSpec::default_spec();
+
+        let state_builder = TestingBeaconStateBuilder::from_deterministic_keypairs(1, &spec);
+        let (mut state, _keypairs) = state_builder.build();
+        state.slot = Slot::new(slot);
+        state
+    }
+
+    fn get_checkpoint(i: u64) -> Checkpoint {
+        Checkpoint {
+            epoch: Epoch::new(i),
+            root: Hash256::from_low_u64_be(i),
+        }
+    }
+
+    #[test]
+    fn insert_get_and_evict() {
+        let mut cache = CheckpointStateCache::new(2);
+
+        for i in 0..3 {
+            cache.insert(get_checkpoint(i), &get_state(i * 32));
+        }
+
+        assert_eq!(cache.len(), 2, "should respect the capacity");
+        assert!(
+            cache.get(&get_checkpoint(0)).is_none(),
+            "should evict the least recently used state"
+        );
+
+        for i in 1..3 {
+            assert_eq!(
+                cache.get(&get_checkpoint(i)).map(|state| state.slot),
+                Some(Slot::new(i * 32)),
+                "should return the state for checkpoint {}",
+                i
+            );
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::metrics;
+use lru::LruCache;
+use types::{AggregatePublicKey, CommitteeIndex, Epoch, Hash256, PublicKey, Slot};
+
+/// The size of the LRU cache that stores the aggregate public keys of committees.
+///
+/// There are at most `64 * 32 = 2,048` committees per epoch on mainnet, each aggregate public key
+/// being a few hundred bytes at most. Therefore, this cache of approximately two epochs of
+/// committees should be less than 1 MB.
+const CACHE_SIZE: usize = 4_096;
+
+/// Identifies a beacon committee by its shuffling, its slot and its index within that slot.
+///
+/// The shuffling is identified in the same way as the `ShufflingCache`: by its epoch and the root
+/// of the block which decided it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommitteeId {
+    pub shuffling_epoch: Epoch,
+    pub shuffling_decision_root: Hash256,
+    pub slot: Slot,
+    pub index: CommitteeIndex,
+}
+
+/// Provides an LRU cache of the aggregate of the (decompressed) public keys of entire committees.
+///
+/// When every member of a committee has attested, the signature of the aggregate can be verified
+/// against the cached key instead of aggregating the committee's public keys again.
+///
+/// Since entries are keyed by the root of the block which decided the shuffling, a change in the
+/// shuffling (e.g., a re-org across the decision block) results in different keys and stale
+/// entries are never returned. They are evicted as the cache fills.
+pub struct CommitteePubkeyCache {
+    cache: LruCache<CommitteeId, AggregatePublicKey>,
+}
+
+impl CommitteePubkeyCache {
+    pub fn new() -> Self {
+        Self {
+            cache: LruCache::new(CACHE_SIZE),
+        }
+    }
+
+    /// Returns a clone of the aggregate public key of the committee, if it is in the cache.
+    pub fn get(&mut self, committee_id: &CommitteeId) -> Option<AggregatePublicKey> {
+        let opt = self.cache.get(committee_id).cloned();
+
+        if opt.is_some() {
+            metrics::inc_counter(&metrics::COMMITTEE_PUBKEY_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::COMMITTEE_PUBKEY_CACHE_MISSES);
+        }
+
+        opt
+    }
+
+    /// Adds the aggregate public key of the committee to the cache, evicting the least recently
+    /// used key if the cache is full.
+    pub fn insert(&mut self, committee_id: CommitteeId, aggregate_pubkey: &AggregatePublicKey) {
+        if !self.cache.contains(&committee_id) {
+            self.cache.put(committee_id, aggregate_pubkey.clone());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+/// Returns the aggregate of `pubkeys`, performing only a single affine conversion.
+pub fn aggregate_pubkeys<'a>(
+    pubkeys: impl IntoIterator<Item = &'a PublicKey>,
+) -> AggregatePublicKey {
+    let mut aggregate = AggregatePublicKey::new();
+    for pubkey in pubkeys {
+        aggregate.add_without_affine(pubkey);
+    }
+    aggregate.affine();
+    aggregate
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::test_utils::generate_deterministic_keypairs;
+
+    fn committee_id(shuffling_decision_root: u64, index: CommitteeIndex) -> CommitteeId {
+        CommitteeId {
+            shuffling_epoch: Epoch::new(1),
+            shuffling_decision_root: Hash256::from_low_u64_be(shuffling_decision_root),
+            slot: Slot::new(8),
+            index,
+        }
+    }
+
+    #[test]
+    fn keyed_by_shuffling_and_committee() {
+        let mut cache = CommitteePubkeyCache::new();
+        let keypairs = generate_deterministic_keypairs(4);
+        let aggregate = aggregate_pubkeys(keypairs.iter().map(|kp| &kp.pk));
+
+        assert!(cache.is_empty());
+        cache.insert(committee_id(1, 0), &aggregate);
+        assert_eq!(cache.len(), 1);
+
+        assert_eq!(
+            cache
+                .get(&committee_id(1, 0))
+                .map(|pk| pk.as_bytes().to_vec()),
+            Some(aggregate.as_bytes().to_vec())
+        );
+        assert!(
+            cache.get(&committee_id(1, 1)).is_none(),
+            "should not return the key of another committee"
+        );
+        assert!(
+            cache.get(&committee_id(2, 0)).is_none(),
+            "should not return the key of another shuffling"
+        );
+    }
+}
@@ -208,11 +208,13 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
 pub struct DummyEth1ChainBackend<T: EthSpec>(PhantomData<T>);
 
 impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
-    /// Produce some deterministic junk based upon the current epoch.
+    /// Produce some deterministic junk based upon the current eth1 voting period.
+    ///
+    /// All honest nodes using this backend vote for the same `Eth1Data` throughout a voting
+    /// period, so the vote is able to reach a majority.
     fn eth1_data(&self, state: &BeaconState<T>, _spec: &ChainSpec) -> Result<Eth1Data, Error> {
-        let current_epoch = state.current_epoch();
         let slots_per_voting_period = T::slots_per_eth1_voting_period() as u64;
-        let current_voting_period: u64 = current_epoch.as_u64() / slots_per_voting_period;
+        let current_voting_period: u64 = state.slot.as_u64() / slots_per_voting_period;
 
         let deposit_root = hash(&int_to_bytes32(current_voting_period));
         let block_hash = hash(&deposit_root);
@@ -788,6 +790,51 @@ mod test {
         }
     }
 
+    mod dummy_backend {
+        use super::*;
+
+        #[test]
+        fn votes_are_constant_within_voting_period() {
+            let spec = &E::default_spec();
+            let backend: DummyEth1ChainBackend<E> = DummyEth1ChainBackend::default();
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), spec);
+            let period = <E as EthSpec>::SlotsPerEth1VotingPeriod::to_u64();
+
+            let first_vote = backend
+                .eth1_data(&state, spec)
+                .expect("should produce eth1 data");
+
+            state.slot = Slot::new(period - 1);
+            assert_eq!(
+                backend
+                    .eth1_data(&state, spec)
+                    .expect("should produce eth1 data"),
+                first_vote,
+                "vote should not change within a voting period"
+            );
+
+            state.slot = Slot::new(period);
+            let second_vote = backend
+                .eth1_data(&state, spec)
+                .expect("should produce eth1 data");
+            assert_ne!(
+                second_vote, first_vote,
+                "vote should change with the voting period"
+            );
+            assert_eq!(
+                second_vote.deposit_count, state.eth1_deposit_index,
+                "vote should not include new deposits"
+            );
+            assert!(
+                backend
+                    .queued_deposits(&state, &second_vote, spec)
+                    .expect("should get deposits")
+                    .is_empty(),
+                "should not produce deposits"
+            );
+        }
+    }
+
     mod eth1_data_sets {
         use super::*;
 
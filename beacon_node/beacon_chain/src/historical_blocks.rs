@@ -0,0 +1,141 @@
+//! Provides verification and import of historical blocks, i.e., blocks which are older than the
+//! oldest block known to the chain (the "anchor").
+//!
+//! Historical blocks are not run through the state transition. Instead:
+//!
+//! - The blocks must form a chain of `parent_root`s which terminates at a block root that is
+//!   already trusted (e.g., the `parent_root` of the oldest block in the database). This
+//!   guarantees that the blocks are canonical.
+//! - The proposer signatures of all blocks are verified in batches, one batch per epoch, using
+//!   the `proposer_index` included in each block. This is safe since the block roots have already
+//!   been authenticated by the chain of roots. Each signature is verified against the fork which
+//!   was in effect at the epoch of its block, rather than the fork of the head state.
+//!
+//! There is presently no caller outside of tests: without checkpoint sync the database always
+//! holds every block back to genesis, so there is nothing to backfill. The backfill sync which
+//! drives this function will be added alongside checkpoint sync.
+use crate::beacon_chain::VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT;
+use crate::errors::BeaconChainError as Error;
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use bls::verify_signature_sets;
+use rayon::prelude::*;
+use state_processing::signature_sets::block_proposal_signature_set_from_parts;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+#[derive(Debug)]
+pub enum HistoricalBlockError {
+    /// The block root of a block in the batch does not match the `parent_root` of its child (or
+    /// the expected anchor root, for the newest block in the batch).
+    MismatchedBlockRoot {
+        block_root: Hash256,
+        expected_block_root: Hash256,
+    },
+    /// The blocks in the batch are not in strictly ascending slot order.
+    NonLinearSlots { block_slot: Slot, parent_slot: Slot },
+    /// At least one of the proposer signatures in the given epoch is invalid.
+    InvalidSignature { epoch: Epoch },
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Verify and store a batch of historical `blocks`, which must be in ascending slot order.
+    ///
+    /// The newest block in the batch must have a block root of `expected_block_root` and each
+    /// block must be the parent of the next. No state transitions are performed; instead the
+    /// proposer signatures are batch-verified for each epoch in the batch.
+    ///
+    /// Returns the `parent_root` of the oldest block in the batch, which is the
+    /// `expected_block_root` for the next (older) batch.
+    pub fn import_historical_block_batch(
+        &self,
+        blocks: &[SignedBeaconBlock<T::EthSpec>],
+        expected_block_root: Hash256,
+    ) -> Result<Hash256, Error> {
+        let _timer = metrics::start_timer(&metrics::HISTORICAL_BLOCK_BATCH_IMPORT_TIMES);
+
+        // Check that the blocks form a chain of roots which terminates at `expected_block_root`.
+        let mut expected_block_root = expected_block_root;
+        let mut block_roots = Vec::with_capacity(blocks.len());
+        for (i, block) in blocks.iter().enumerate().rev() {
+            let block_root = block.canonical_root();
+
+            if block_root != expected_block_root {
+                return Err(HistoricalBlockError::MismatchedBlockRoot {
+                    block_root,
+                    expected_block_root,
+                }
+                .into());
+            }
+
+            if let Some(child) = blocks.get(i + 1) {
+                if block.slot() >= child.slot() {
+                    return Err(HistoricalBlockError::NonLinearSlots {
+                        block_slot: child.slot(),
+                        parent_slot: block.slot(),
+                    }
+                    .into());
+                }
+            }
+
+            expected_block_root = block.parent_root();
+            block_roots.push(block_root);
+        }
+        block_roots.reverse();
+
+        // Build the proposer signature sets, grouped by epoch.
+        let pubkey_cache = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::ValidatorPubkeyCacheLockTimeout)?;
+
+        let mut signature_sets_by_epoch = BTreeMap::new();
+        for (block, block_root) in blocks.iter().zip(block_roots.iter()) {
+            let epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
+            let fork = self.spec.fork_at_epoch::<T::EthSpec>(epoch);
+
+            let signature_set = block_proposal_signature_set_from_parts(
+                block,
+                Some(*block_root),
+                block.message.proposer_index,
+                &fork,
+                self.genesis_validators_root,
+                |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
+                &self.spec,
+            )
+            .map_err(Error::SignatureSetError)?;
+
+            signature_sets_by_epoch
+                .entry(epoch)
+                .or_insert_with(Vec::new)
+                .push(signature_set);
+        }
+
+        drop(pubkey_cache);
+
+        // Verify each epoch in parallel.
+        let invalid_epoch =
+            signature_sets_by_epoch
+                .into_par_iter()
+                .find_map_any(|(epoch, signature_sets)| {
+                    if verify_signature_sets(signature_sets) {
+                        None
+                    } else {
+                        Some(epoch)
+                    }
+                });
+
+        if let Some(epoch) = invalid_epoch {
+            return Err(HistoricalBlockError::InvalidSignature { epoch }.into());
+        }
+
+        // All blocks are valid, store them.
+        for (block, block_root) in blocks.iter().zip(block_roots.iter()) {
+            self.store.put_block(block_root, block.clone())?;
+        }
+
+        metrics::inc_counter_by(&metrics::HISTORICAL_BLOCKS_IMPORTED, blocks.len() as i64);
+
+        Ok(expected_block_root)
+    }
+}
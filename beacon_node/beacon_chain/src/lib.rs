@@ -2,25 +2,35 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod active_validators_cache;
+pub mod attestation_simulator;
 pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_fork_choice_store;
 mod beacon_snapshot;
 mod block_verification;
 pub mod builder;
+mod canonical_head;
+mod chain_config;
+mod checkpoint_state_cache;
+mod committee_pubkey_cache;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
 mod head_tracker;
+mod historical_blocks;
 mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
+pub mod observed_activity;
 mod observed_attestations;
 mod observed_attesters;
 mod observed_block_producers;
 pub mod observed_operations;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+pub mod proposal_head_votes;
+mod proposer_preparation;
 mod shuffling_cache;
 mod snapshot_cache;
 pub mod test_utils;
@@ -29,17 +39,25 @@ mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    ForkChoiceError, StateSkipConfig,
+    ForkChoiceError, StateSkipConfig, BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::errors::{BeaconChainError, BlockProductionError};
+pub use active_validators_cache::ActiveValidators;
 pub use attestation_verification::Error as AttestationError;
-pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceStoreError};
+pub use beacon_fork_choice_store::{
+    BeaconForkChoiceStore, Error as ForkChoiceStoreError, PersistedForkChoiceStore,
+};
 pub use block_verification::{BlockError, GossipVerifiedBlock};
+pub use canonical_head::{CanonicalHead, ForkChoiceView};
+pub use chain_config::ChainConfig;
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
 pub use events::EventHandler;
+pub use historical_blocks::HistoricalBlockError;
 pub use metrics::scrape_for_metrics;
 pub use parking_lot;
+pub use persisted_beacon_chain::PersistedBeaconChain;
+pub use persisted_fork_choice::PersistedForkChoice;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
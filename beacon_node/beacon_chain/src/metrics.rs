@@ -54,6 +54,34 @@ lazy_static! {
         "Time spent hashing and remembering all the attestations in the block"
     );
 
+    /*
+     * Proposer Preparation
+     */
+    pub static ref PROPOSER_PREPARATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_proposer_preparation_seconds",
+        "Time spent running fork choice and advancing the head state ahead of a proposal"
+    );
+    pub static ref PROPOSER_PREPARATION_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_proposer_preparation_hits_total",
+        "Count of block productions which used a prepared state"
+    );
+    pub static ref PROPOSER_PREPARATION_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_proposer_preparation_misses_total",
+        "Count of prepared states which could not be used for block production"
+    );
+
+    /*
+     * Historical Block Import
+     */
+    pub static ref HISTORICAL_BLOCK_BATCH_IMPORT_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_historical_block_batch_import_seconds",
+        "Time spent verifying and storing a batch of historical blocks"
+    );
+    pub static ref HISTORICAL_BLOCKS_IMPORTED: Result<IntCounter> = try_create_int_counter(
+        "beacon_historical_blocks_imported_total",
+        "Count of historical blocks verified and stored"
+    );
+
     /*
      * Block Production
      */
@@ -180,6 +208,38 @@ lazy_static! {
     pub static ref SHUFFLING_CACHE_MISSES: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_misses_total", "Count of times shuffling cache fulfils request");
 
+    /*
+     * Committee pubkey cache
+     */
+    pub static ref COMMITTEE_PUBKEY_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_committee_pubkey_cache_hits_total", "Count of times committee pubkey cache fulfils request");
+    pub static ref COMMITTEE_PUBKEY_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_committee_pubkey_cache_misses_total", "Count of times committee pubkey cache misses request");
+
+    /*
+     * Active validators cache
+     */
+    pub static ref ACTIVE_VALIDATORS_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_active_validators_cache_hits_total", "Count of times active validators cache fulfils request");
+    pub static ref ACTIVE_VALIDATORS_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_active_validators_cache_misses_total", "Count of times active validators cache misses request");
+
+    /*
+     * Checkpoint state cache
+     */
+    pub static ref CHECKPOINT_STATE_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_checkpoint_state_cache_hits_total",
+        "Count of times the checkpoint state cache fulfils a request"
+    );
+    pub static ref CHECKPOINT_STATE_CACHE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_checkpoint_state_cache_misses_total",
+        "Count of times the checkpoint state cache fails to fulfil a request"
+    );
+    pub static ref CHECKPOINT_STATE_CACHE_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "beacon_checkpoint_state_cache_size",
+        "Number of epoch-boundary states in the checkpoint state cache"
+    );
+
     /*
      * Attestation Production
      */
@@ -218,6 +278,10 @@ lazy_static! {
         "beacon_fork_choice_reorg_total",
         "Count of occasions fork choice has switched to a different chain"
     );
+    pub static ref BEACON_BLOCK_HEAD_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram(
+        "beacon_block_head_slot_start_delay_time",
+        "Duration between the start of the block's slot and the time the block became the head"
+    );
     pub static ref FORK_CHOICE_TIMES: Result<Histogram> =
         try_create_histogram("beacon_fork_choice_seconds", "Full runtime of fork choice");
     pub static ref FORK_CHOICE_FIND_HEAD_TIMES: Result<Histogram> =
@@ -300,6 +364,16 @@ lazy_static! {
         try_create_int_gauge("beacon_op_pool_proposer_slashings_total", "Count of proposer slashings in the op pool");
     pub static ref OP_POOL_NUM_VOLUNTARY_EXITS: Result<IntGauge> =
         try_create_int_gauge("beacon_op_pool_voluntary_exits_total", "Count of voluntary exits in the op pool");
+    pub static ref OP_POOL_SIZE_BEFORE_PRUNE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_op_pool_size_before_prune",
+        "Count of operations of each type in the op pool immediately before the last prune",
+        &["type"]
+    );
+    pub static ref OP_POOL_SIZE_AFTER_PRUNE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_op_pool_size_after_prune",
+        "Count of operations of each type in the op pool immediately after the last prune",
+        &["type"]
+    );
 
     /*
      * Participation Metrics
@@ -330,6 +404,69 @@ lazy_static! {
     );
 }
 
+// Third lazy-static block is used to account for macro recursion limit.
+lazy_static! {
+    /*
+     * Attestation Simulator
+     */
+    pub static ref ATTESTATION_SIMULATOR_PRODUCED: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_produced_total",
+        "Count of simulated attestations produced"
+    );
+    pub static ref ATTESTATION_SIMULATOR_HEAD_HIT: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_head_hit_total",
+        "Count of simulated attestations with a correct head vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_HEAD_MISS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_head_miss_total",
+        "Count of simulated attestations with an incorrect head vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_TARGET_HIT: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_target_hit_total",
+        "Count of simulated attestations with a correct target vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_TARGET_MISS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_target_miss_total",
+        "Count of simulated attestations with an incorrect target vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_SOURCE_HIT: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_source_hit_total",
+        "Count of simulated attestations with a correct source vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_SOURCE_MISS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_source_miss_total",
+        "Count of simulated attestations with an incorrect source vote"
+    );
+    pub static ref ATTESTATION_SIMULATOR_INCLUSION_HIT: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_inclusion_hit_total",
+        "Count of simulated attestations which would have been included in a canonical block"
+    );
+    pub static ref ATTESTATION_SIMULATOR_INCLUSION_MISS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_simulator_inclusion_miss_total",
+        "Count of simulated attestations which would not have been included in a canonical block"
+    );
+
+    /*
+     * Proposal Head Votes
+     */
+    pub static ref PROPOSAL_HEAD_VOTE_CHECKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_proposal_head_vote_checks_total",
+        "Count of our proposed blocks which have been checked for head votes"
+    );
+    pub static ref PROPOSAL_HEAD_VOTES: Result<IntCounter> = try_create_int_counter(
+        "beacon_proposal_head_votes_total",
+        "Count of attesters which voted for one of our proposed blocks as head"
+    );
+    pub static ref PROPOSAL_HEAD_VOTE_ATTESTERS: Result<IntCounter> = try_create_int_counter(
+        "beacon_proposal_head_vote_attesters_total",
+        "Count of attesters assigned to the slots of our proposed blocks"
+    );
+    pub static ref PROPOSAL_HEAD_VOTE_PERCENTAGE: Result<IntGauge> = try_create_int_gauge(
+        "beacon_proposal_head_vote_percentage",
+        "Percentage of attesters which voted for our most recently checked proposal as head"
+    );
+}
+
 /// Scrape the `beacon_chain` for metrics that are not constantly updated (e.g., the present slot,
 /// head state info, etc) and update the Prometheus `DEFAULT_REGISTRY`.
 pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
@@ -359,6 +496,24 @@ pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
     );
 }
 
+/// Sets the gauges in `gauge_vec` to the number of each type of operation in the op pool.
+pub fn scrape_op_pool_sizes<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    gauge_vec: &Result<IntGaugeVec>,
+) {
+    let op_pool = &beacon_chain.op_pool;
+    for (op_type, count) in &[
+        ("attestations", op_pool.num_attestations()),
+        ("attester_slashings", op_pool.num_attester_slashings()),
+        ("proposer_slashings", op_pool.num_proposer_slashings()),
+        ("voluntary_exits", op_pool.num_voluntary_exits()),
+    ] {
+        if let Some(gauge) = get_int_gauge(gauge_vec, &[*op_type]) {
+            gauge.set(*count as i64);
+        }
+    }
+}
+
 /// Scrape the given `state` assuming it's the head state, updating the `DEFAULT_REGISTRY`.
 fn scrape_head_state<T: BeaconChainTypes>(state: &BeaconState<T::EthSpec>, state_root: Hash256) {
     set_gauge_by_slot(&HEAD_STATE_SLOT, state.slot);
@@ -1,12 +1,13 @@
 use crate::errors::BeaconChainError;
 use crate::head_tracker::HeadTracker;
 use parking_lot::Mutex;
-use slog::{debug, warn, Logger};
+use slog::{debug, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 use store::hot_cold_store::{process_finalization, HotColdDBError};
 use store::iter::{ParentRootBlockIterator, RootsIterator};
 use store::{Error, ItemStore, StoreOp};
@@ -14,6 +15,12 @@ pub use store::{HotColdDB, MemoryStore};
 use types::*;
 use types::{BeaconState, EthSpec, Hash256, Slot};
 
+/// The minimum time between compactions of the database after pruning.
+///
+/// Compaction is expensive and pruning after each finalization only frees a small amount of space,
+/// so there's little to gain from compacting more often than this.
+const COMPACTION_PERIOD: Duration = Duration::from_secs(2 * 60 * 60);
+
 /// Trait for migration processes that update the database upon finalization.
 pub trait Migrate<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>:
     Send + Sync + 'static
@@ -315,6 +322,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     ) {
         let (tx, rx) = mpsc::channel();
         let thread = thread::spawn(move || {
+            let mut last_compaction: Option<Instant> = None;
+
             while let Ok((
                 state_root,
                 state,
@@ -352,6 +361,29 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                     Ok(()) => {}
                     Err(e) => warn!(log, "Block pruning failed: {:?}", e),
                 }
+
+                if db.get_config().compact_on_prune
+                    && last_compaction
+                        .map_or(true, |instant| instant.elapsed() >= COMPACTION_PERIOD)
+                {
+                    info!(log, "Starting database compaction");
+                    let start = Instant::now();
+
+                    match db.compact() {
+                        Ok(()) => info!(
+                            log,
+                            "Database compaction complete";
+                            "duration_secs" => start.elapsed().as_secs()
+                        ),
+                        Err(e) => warn!(
+                            log,
+                            "Database compaction failed";
+                            "error" => format!("{:?}", e)
+                        ),
+                    }
+
+                    last_compaction = Some(Instant::now());
+                }
             }
         });
 
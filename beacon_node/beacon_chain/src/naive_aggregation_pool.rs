@@ -71,13 +71,7 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
     pub fn insert(&mut self, a: &Attestation<E>) -> Result<InsertOutcome, Error> {
         let _timer = metrics::start_timer(&metrics::ATTESTATION_PROCESSING_AGG_POOL_CORE_INSERT);
 
-        let set_bits = a
-            .aggregation_bits
-            .iter()
-            .enumerate()
-            .filter(|(_i, bit)| *bit)
-            .map(|(i, _bit)| i)
-            .collect::<Vec<_>>();
+        let set_bits = a.aggregation_bits.iter_set_bits().collect::<Vec<_>>();
 
         let committee_index = set_bits
             .first()
@@ -0,0 +1,131 @@
+//! Provides the `ObservedActivity` struct which records the validators that have been seen to
+//! attest, aggregate or propose in recent epochs.
+//!
+//! Validator clients query this record via the liveness endpoints of the HTTP API before they
+//! start signing, so that a validator which is already active on another machine is not started
+//! a second time (i.e., doppelganger protection).
+
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use types::Epoch;
+
+/// The number of epochs for which activity is retained, including the most recent epoch.
+pub const ACTIVITY_EPOCHS: u64 = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// Activity for `epoch` has been pruned, or was never retained.
+    EpochTooLow {
+        epoch: Epoch,
+        lowest_permissible_epoch: Epoch,
+    },
+}
+
+/// Maintains a record of which validators have been seen to be active in each of the most recent
+/// `ACTIVITY_EPOCHS` epochs.
+///
+/// The record prunes itself based upon the epochs of the activity supplied to it. Activity from
+/// epochs prior to the retained epochs is ignored.
+#[derive(Default)]
+pub struct ObservedActivity {
+    lowest_permissible_epoch: RwLock<Epoch>,
+    epochs: RwLock<HashMap<Epoch, HashSet<u64>>>,
+}
+
+impl ObservedActivity {
+    /// Observe that `validator_index` was active in `epoch`.
+    pub fn observe_validator(&self, epoch: Epoch, validator_index: u64) {
+        self.observe_validators(epoch, std::iter::once(validator_index))
+    }
+
+    /// Observe that all of the `validator_indices` were active in `epoch`.
+    pub fn observe_validators(
+        &self,
+        epoch: Epoch,
+        validator_indices: impl IntoIterator<Item = u64>,
+    ) {
+        self.prune(epoch);
+
+        if epoch < *self.lowest_permissible_epoch.read() {
+            return;
+        }
+
+        self.epochs
+            .write()
+            .entry(epoch)
+            .or_default()
+            .extend(validator_indices);
+    }
+
+    /// Returns `Ok(true)` if `validator_index` has been observed to be active in `epoch`.
+    ///
+    /// ## Errors
+    ///
+    /// - `epoch` is earlier than the retained epochs.
+    pub fn is_live(&self, epoch: Epoch, validator_index: u64) -> Result<bool, Error> {
+        let lowest_permissible_epoch = *self.lowest_permissible_epoch.read();
+        if epoch < lowest_permissible_epoch {
+            return Err(Error::EpochTooLow {
+                epoch,
+                lowest_permissible_epoch,
+            });
+        }
+
+        Ok(self
+            .epochs
+            .read()
+            .get(&epoch)
+            .map_or(false, |indices| indices.contains(&validator_index)))
+    }
+
+    /// Removes all activity prior to the `ACTIVITY_EPOCHS` epochs ending at `current_epoch`.
+    ///
+    /// Has no effect if `current_epoch` is earlier than an epoch supplied previously.
+    fn prune(&self, current_epoch: Epoch) {
+        let lowest_permissible_epoch = current_epoch - (ACTIVITY_EPOCHS - 1);
+
+        let mut lowest = self.lowest_permissible_epoch.write();
+        if lowest_permissible_epoch <= *lowest {
+            return;
+        }
+        *lowest = lowest_permissible_epoch;
+
+        self.epochs
+            .write()
+            .retain(|epoch, _| *epoch >= lowest_permissible_epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observe_and_prune() {
+        let activity = ObservedActivity::default();
+
+        activity.observe_validator(Epoch::new(0), 1);
+        activity.observe_validators(Epoch::new(1), vec![2, 3]);
+
+        assert_eq!(activity.is_live(Epoch::new(0), 1), Ok(true));
+        assert_eq!(activity.is_live(Epoch::new(0), 2), Ok(false));
+        assert_eq!(activity.is_live(Epoch::new(1), 3), Ok(true));
+        assert_eq!(activity.is_live(Epoch::new(2), 3), Ok(false));
+
+        // Observing a later epoch prunes the earliest epoch.
+        activity.observe_validator(Epoch::new(ACTIVITY_EPOCHS), 4);
+        assert_eq!(
+            activity.is_live(Epoch::new(0), 1),
+            Err(Error::EpochTooLow {
+                epoch: Epoch::new(0),
+                lowest_permissible_epoch: Epoch::new(1),
+            })
+        );
+        assert_eq!(activity.is_live(Epoch::new(1), 2), Ok(true));
+
+        // Activity from pruned epochs is ignored.
+        activity.observe_validator(Epoch::new(0), 5);
+        assert!(activity.is_live(Epoch::new(0), 5).is_err());
+        assert_eq!(activity.is_live(Epoch::new(ACTIVITY_EPOCHS), 4), Ok(true));
+    }
+}
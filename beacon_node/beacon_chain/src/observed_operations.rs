@@ -70,6 +70,18 @@ impl<E: EthSpec> ObservableOperation<E> for AttesterSlashing<E> {
 }
 
 impl<T: ObservableOperation<E>, E: EthSpec> ObservedOperations<T, E> {
+    /// Returns `true` if all of the validators involved in `op` have been observed in a previous
+    /// operation, in which case `Self::verify_and_observe` would return
+    /// `ObservationOutcome::AlreadyKnown`.
+    ///
+    /// This is cheaper than `Self::verify_and_observe`, since it does not require a state.
+    pub fn is_known(&self, op: &T) -> bool {
+        let observed_validator_indices = self.observed_validator_indices.lock();
+        op.observed_validators()
+            .iter()
+            .all(|index| observed_validator_indices.contains(index))
+    }
+
     pub fn verify_and_observe(
         &self,
         op: T,
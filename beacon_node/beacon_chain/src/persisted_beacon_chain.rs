@@ -4,11 +4,19 @@ use ssz_derive::{Decode, Encode};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use types::Hash256;
 
+/// The components of the `BeaconChain` which are persisted to disk, allowing it to be resumed
+/// after a restart.
 #[derive(Clone, Encode, Decode)]
 pub struct PersistedBeaconChain {
     pub canonical_head_block_root: Hash256,
     pub genesis_block_root: Hash256,
     pub ssz_head_tracker: SszHeadTracker,
+    /// Set to `true` only when the chain is persisted as the `BeaconChain` is dropped.
+    ///
+    /// The chain is persisted with `false` whilst running, so a `false` value read at startup
+    /// indicates that the node did not shut down cleanly and that the persisted head may not be
+    /// present in the database.
+    pub shutdown_clean: bool,
 }
 
 impl StoreItem for PersistedBeaconChain {
@@ -0,0 +1,122 @@
+//! Provides the `ProposalHeadVotes` struct which measures how many of the attesters at the slot of
+//! each of our block proposals voted for the proposed block as head.
+//!
+//! Attesters vote one third of the way through a slot, so a block which has not reached most of
+//! the network by then will receive few head votes. The fraction of head votes is therefore a
+//! direct signal of how quickly our blocks propagate.
+//!
+//! A proposal is checked against the attestations included in the first block imported at a later
+//! slot (usually the next slot), after which it is forgotten.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use types::{BeaconBlock, BeaconState, BitList, CommitteeIndex, EthSpec, Hash256, Slot};
+
+/// The number of head votes for one of our proposals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProposalHeadVoteOutcome {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    /// The number of attesters which voted for the proposed block as head.
+    pub head_votes: usize,
+    /// The number of attesters assigned to the slot of the proposal.
+    pub attesters: usize,
+}
+
+impl ProposalHeadVoteOutcome {
+    /// Returns the percentage of attesters which voted for the proposed block as head.
+    pub fn head_vote_percentage(&self) -> u64 {
+        if self.attesters == 0 {
+            0
+        } else {
+            (self.head_votes as u64 * 100) / self.attesters as u64
+        }
+    }
+}
+
+/// Maintains the set of our proposals which have not yet been checked.
+#[derive(Default)]
+pub struct ProposalHeadVotes {
+    pending: Mutex<HashMap<Slot, Hash256>>,
+}
+
+impl ProposalHeadVotes {
+    /// Register that we proposed the block with `block_root` at `slot`.
+    pub fn register_proposal(&self, slot: Slot, block_root: Hash256) {
+        self.pending.lock().insert(slot, block_root);
+    }
+
+    /// Checks each pending proposal from a slot prior to `block.slot` against the attestations
+    /// in `block`, updating the metrics and returning the outcomes.
+    ///
+    /// The `state` must be the post-state of `block`. Proposals from slots outside of its
+    /// committee caches are forgotten without being checked.
+    pub fn process_block<E: EthSpec>(
+        &self,
+        block: &BeaconBlock<E>,
+        state: &BeaconState<E>,
+    ) -> Vec<ProposalHeadVoteOutcome> {
+        let proposals = {
+            let mut pending = self.pending.lock();
+            if pending.is_empty() {
+                return vec![];
+            }
+
+            let proposals = pending
+                .iter()
+                .filter(|(slot, _)| **slot < block.slot)
+                .map(|(slot, block_root)| (*slot, *block_root))
+                .collect::<Vec<_>>();
+            pending.retain(|slot, _| *slot >= block.slot);
+            proposals
+        };
+
+        let mut outcomes = Vec::with_capacity(proposals.len());
+
+        for (slot, block_root) in proposals {
+            let attesters = match state.get_beacon_committees_at_slot(slot) {
+                Ok(committees) => committees
+                    .iter()
+                    .map(|committee| committee.committee.len())
+                    .sum(),
+                Err(_) => continue,
+            };
+
+            // The same committee may be included in several aggregates, so take the union of their
+            // aggregation bits to avoid counting an attester twice.
+            let mut votes: HashMap<CommitteeIndex, BitList<E::MaxValidatorsPerCommittee>> =
+                HashMap::new();
+            for attestation in block.body.attestations.iter().filter(|attestation| {
+                attestation.data.slot == slot && attestation.data.beacon_block_root == block_root
+            }) {
+                votes
+                    .entry(attestation.data.index)
+                    .and_modify(|bits| *bits = bits.union(&attestation.aggregation_bits))
+                    .or_insert_with(|| attestation.aggregation_bits.clone());
+            }
+
+            let outcome = ProposalHeadVoteOutcome {
+                slot,
+                block_root,
+                head_votes: votes.values().map(|bits| bits.num_set_bits()).sum(),
+                attesters,
+            };
+
+            metrics::inc_counter(&metrics::PROPOSAL_HEAD_VOTE_CHECKS);
+            metrics::inc_counter_by(&metrics::PROPOSAL_HEAD_VOTES, outcome.head_votes as i64);
+            metrics::inc_counter_by(
+                &metrics::PROPOSAL_HEAD_VOTE_ATTESTERS,
+                outcome.attesters as i64,
+            );
+            metrics::set_gauge(
+                &metrics::PROPOSAL_HEAD_VOTE_PERCENTAGE,
+                outcome.head_vote_percentage() as i64,
+            );
+
+            outcomes.push(outcome);
+        }
+
+        outcomes
+    }
+}
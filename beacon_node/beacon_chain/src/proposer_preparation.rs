@@ -0,0 +1,163 @@
+//! Provides the "prepare" path for block proposals.
+//!
+//! Shortly before the start of a slot in which one of the node's validators is due to propose,
+//! fork choice is run and the head state is advanced to the proposal slot. When the block is
+//! requested at the start of the slot, the prepared state can be used directly and block
+//! production only has to pack the block body.
+//!
+//! The validators which are managed by validator clients connected to this node are registered
+//! via `BeaconChain::register_proposer_preparation`.
+use crate::errors::BeaconChainError as Error;
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use slog::debug;
+use state_processing::per_slot_processing;
+use std::collections::HashMap;
+use types::{BeaconState, Epoch, EthSpec, Hash256, RelativeEpoch, Slot};
+
+/// Registrations older than this many epochs are ignored. Validator clients re-register their
+/// validators each epoch.
+pub const PROPOSER_PREPARATION_EPOCHS: u64 = 2;
+
+/// A head state which has been advanced to a future proposal slot.
+struct PreparedState<E: EthSpec> {
+    /// The root of the head block at the time the state was prepared.
+    head_block_root: Hash256,
+    /// The state, advanced to the proposal slot.
+    state: BeaconState<E>,
+}
+
+/// The validators registered for proposer preparation and the most recently prepared state.
+pub struct ProposerPreparation<E: EthSpec> {
+    /// A map of `validator_index -> epoch` of the most recent registration.
+    validators: HashMap<u64, Epoch>,
+    prepared_state: Option<PreparedState<E>>,
+}
+
+impl<E: EthSpec> Default for ProposerPreparation<E> {
+    fn default() -> Self {
+        Self {
+            validators: HashMap::new(),
+            prepared_state: None,
+        }
+    }
+}
+
+impl<E: EthSpec> ProposerPreparation<E> {
+    /// Records that the validators in `validator_indices` are managed by this node at `epoch`,
+    /// pruning any registrations which have expired.
+    fn register(&mut self, validator_indices: &[u64], epoch: Epoch) {
+        for validator_index in validator_indices {
+            self.validators.insert(*validator_index, epoch);
+        }
+
+        self.validators
+            .retain(|_, registered| *registered + PROPOSER_PREPARATION_EPOCHS > epoch);
+    }
+
+    /// Returns `true` if `validator_index` has a registration which has not expired at `epoch`.
+    fn is_registered(&self, validator_index: u64, epoch: Epoch) -> bool {
+        self.validators
+            .get(&validator_index)
+            .map_or(false, |registered| {
+                *registered + PROPOSER_PREPARATION_EPOCHS > epoch
+            })
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Registers `validator_indices` as being managed by a validator client connected to this
+    /// node, so that their block proposals are prepared ahead of time.
+    pub fn register_proposer_preparation(&self, validator_indices: &[u64]) -> Result<(), Error> {
+        let epoch = self.epoch()?;
+        self.proposer_preparation
+            .lock()
+            .register(validator_indices, epoch);
+        Ok(())
+    }
+
+    /// Runs fork choice and advances the head state to the next slot, if a registered validator
+    /// is due to propose in the next slot.
+    ///
+    /// Intended to be called shortly before the start of each slot.
+    pub fn prepare_beacon_proposer(&self) -> Result<(), Error> {
+        let proposal_slot = self.slot()? + 1;
+        let proposal_epoch = proposal_slot.epoch(T::EthSpec::slots_per_epoch());
+
+        if self.proposer_preparation.lock().validators.is_empty() {
+            return Ok(());
+        }
+
+        let _timer = metrics::start_timer(&metrics::PROPOSER_PREPARATION_TIMES);
+
+        self.fork_choice()?;
+
+        let head = self.head()?;
+        if head.beacon_block.slot() >= proposal_slot {
+            return Ok(());
+        }
+
+        let state = advance_state(
+            head.beacon_state,
+            head.beacon_state_root,
+            proposal_slot,
+            &self.spec,
+        )?;
+        let proposer_index = state.get_beacon_proposer_index(proposal_slot, &self.spec)? as u64;
+
+        let mut proposer_preparation = self.proposer_preparation.lock();
+        if proposer_preparation.is_registered(proposer_index, proposal_epoch) {
+            debug!(
+                self.log,
+                "Prepared state for block proposal";
+                "proposer_index" => proposer_index,
+                "slot" => proposal_slot,
+                "head_block_root" => format!("{:?}", head.beacon_block_root),
+            );
+            proposer_preparation.prepared_state = Some(PreparedState {
+                head_block_root: head.beacon_block_root,
+                state,
+            });
+        } else {
+            proposer_preparation.prepared_state = None;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the state prepared by `Self::prepare_beacon_proposer`, if it was prepared for
+    /// `slot` and the head has not changed since.
+    pub(crate) fn take_prepared_proposer_state(
+        &self,
+        slot: Slot,
+    ) -> Result<Option<BeaconState<T::EthSpec>>, Error> {
+        let prepared_state = match self.proposer_preparation.lock().prepared_state.take() {
+            Some(prepared_state) => prepared_state,
+            None => return Ok(None),
+        };
+
+        let head_block_root = self.head_info()?.block_root;
+        if prepared_state.state.slot == slot && prepared_state.head_block_root == head_block_root {
+            metrics::inc_counter(&metrics::PROPOSER_PREPARATION_HITS);
+            Ok(Some(prepared_state.state))
+        } else {
+            metrics::inc_counter(&metrics::PROPOSER_PREPARATION_MISSES);
+            Ok(None)
+        }
+    }
+}
+
+/// Advances `state` (with a root of `state_root`) to `slot` and builds the committee cache for the
+/// current epoch.
+fn advance_state<E: EthSpec>(
+    mut state: BeaconState<E>,
+    state_root: Hash256,
+    slot: Slot,
+    spec: &types::ChainSpec,
+) -> Result<BeaconState<E>, Error> {
+    let mut state_root = Some(state_root);
+    while state.slot < slot {
+        per_slot_processing(&mut state, state_root.take(), spec)?;
+    }
+    state.build_committee_cache(RelativeEpoch::Current, spec)?;
+    Ok(state)
+}
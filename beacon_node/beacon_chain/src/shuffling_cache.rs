@@ -9,7 +9,8 @@ use types::{beacon_state::CommitteeCache, Epoch, Hash256};
 /// ignores a few extra bytes in the caches that should be insignificant compared to the indices).
 const CACHE_SIZE: usize = 16;
 
-/// Provides an LRU cache for `CommitteeCache`.
+/// Provides an LRU cache for `CommitteeCache`, keyed by epoch and the root of the block which
+/// decided the shuffling for that epoch (see `ForkChoice::attester_shuffling_decision_root`).
 ///
 /// It has been named `ShufflingCache` because `CommitteeCacheCache` is a bit weird and looks like
 /// a find/replace error.
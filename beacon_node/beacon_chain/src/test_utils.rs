@@ -7,7 +7,7 @@ use crate::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::CachingEth1Backend,
     events::NullEventHandler,
-    BeaconChain, BeaconChainTypes, StateSkipConfig,
+    BeaconChain, BeaconChainTypes, ChainConfig, StateSkipConfig,
 };
 use genesis::interop_genesis_state;
 use rayon::prelude::*;
@@ -62,6 +62,16 @@ pub enum BlockStrategy {
         /// The slot of the first block produced (must be higher than `previous_slot`.
         first_slot: Slot,
     },
+    /// Ignore the canonical head and produce blocks upon the block with the given root, which
+    /// need not be on the canonical chain.
+    ///
+    /// Useful for simulating forks of forks.
+    ForkAtBlock {
+        /// The root of the parent of the first block produced.
+        parent_root: Hash256,
+        /// The slot of the first block produced (must be higher than the slot of the parent).
+        first_slot: Slot,
+    },
 }
 
 /// Indicates how the `BeaconChainHarness` should produce attestations.
@@ -71,6 +81,11 @@ pub enum AttestationStrategy {
     AllValidators,
     /// Only the given validators should attest. All others should fail to produce attestations.
     SomeValidators(Vec<usize>),
+    /// Each of the given validators attests to the block with the given root, regardless of which
+    /// block the `BeaconChainHarness` has produced. All others should fail to produce attestations.
+    ///
+    /// Useful for splitting attestations between competing forks.
+    PerValidator(HashMap<usize, Hash256>),
 }
 
 /// A testing harness which can instantiate a `BeaconChain` and populate it with blocks and
@@ -182,6 +197,23 @@ impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
         store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
         keypairs: Vec<Keypair>,
         data_dir: TempDir,
+    ) -> Self {
+        Self::resume_from_disk_store_with_chain_config(
+            eth_spec_instance,
+            store,
+            keypairs,
+            data_dir,
+            ChainConfig::default(),
+        )
+    }
+
+    /// As `Self::resume_from_disk_store`, but with the given `chain_config`.
+    pub fn resume_from_disk_store_with_chain_config(
+        eth_spec_instance: E,
+        store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+        keypairs: Vec<Keypair>,
+        data_dir: TempDir,
+        chain_config: ChainConfig,
     ) -> Self {
         let spec = E::default_spec();
 
@@ -196,6 +228,7 @@ impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
                 log.clone(),
             ))
             .data_dir(data_dir.path().to_path_buf())
+            .chain_config(chain_config)
             .resume_from_db()
             .expect("should resume beacon chain from db")
             .dummy_eth1_backend()
@@ -273,24 +306,28 @@ where
     where
         F: FnMut(&SignedBeaconBlock<E>, &BeaconState<E>) -> bool,
     {
-        let mut state = {
-            // Determine the slot for the first block (or skipped block).
-            let state_slot = match block_strategy {
-                BlockStrategy::OnCanonicalHead => {
-                    self.chain.slot().expect("should have a slot") - 1
-                }
-                BlockStrategy::ForkCanonicalChainAt { previous_slot, .. } => previous_slot,
-            };
-
-            self.chain
-                .state_at_slot(state_slot, StateSkipConfig::WithStateRoots)
-                .expect("should find state for slot")
+        // Determine the state upon which the first block (or skipped block) will be built.
+        let mut state = match block_strategy {
+            BlockStrategy::OnCanonicalHead => self
+                .chain
+                .state_at_slot(
+                    self.chain.slot().expect("should have a slot") - 1,
+                    StateSkipConfig::WithStateRoots,
+                )
+                .expect("should find state for slot"),
+            BlockStrategy::ForkCanonicalChainAt { previous_slot, .. } => self
+                .chain
+                .state_at_slot(previous_slot, StateSkipConfig::WithStateRoots)
+                .expect("should find state for slot"),
+            // The parent may not be on the canonical chain, so load its state directly.
+            BlockStrategy::ForkAtBlock { parent_root, .. } => self.get_block_state(parent_root),
         };
 
         // Determine the first slot where a block should be built.
         let mut slot = match block_strategy {
             BlockStrategy::OnCanonicalHead => self.chain.slot().expect("should have a slot"),
-            BlockStrategy::ForkCanonicalChainAt { first_slot, .. } => first_slot,
+            BlockStrategy::ForkCanonicalChainAt { first_slot, .. }
+            | BlockStrategy::ForkAtBlock { first_slot, .. } => first_slot,
         };
 
         let mut head_block_root = None;
@@ -366,6 +403,38 @@ where
         self.chain.head().unwrap().beacon_state
     }
 
+    /// Returns the post-state of the block with the given root, which need not be on the
+    /// canonical chain.
+    pub fn get_block_state(&self, block_root: Hash256) -> BeaconState<E> {
+        let block = self
+            .chain
+            .get_block(&block_root)
+            .expect("should read block")
+            .expect("should find block");
+
+        self.chain
+            .get_state(&block.state_root(), Some(block.slot()))
+            .expect("should read state")
+            .expect("should find state")
+    }
+
+    /// Returns the post-state of the block with the given root, advanced through skip slots to
+    /// `slot` with all caches built.
+    pub fn get_block_state_at_slot(&self, block_root: Hash256, slot: Slot) -> BeaconState<E> {
+        let mut state = self.get_block_state(block_root);
+
+        while state.slot < slot {
+            per_slot_processing(&mut state, None, &self.spec)
+                .expect("should be able to advance state to slot");
+        }
+
+        state
+            .build_all_caches(&self.spec)
+            .expect("should build caches");
+
+        state
+    }
+
     /// Adds a single block (synchronously) onto either the canonical chain (block_strategy ==
     /// OnCanonicalHead) or a fork (block_strategy == ForkCanonicalChainAt).
     pub fn add_block(
@@ -540,7 +609,8 @@ where
         let spec = &self.spec;
         let fork = &state.fork;
 
-        let attesting_validators = self.get_attesting_validators(attestation_strategy);
+        let attesting_validators =
+            self.get_attesting_validators(attestation_strategy, head_block_root);
 
         let committee_count = state
             .get_committee_count_at_slot(state.slot)
@@ -607,20 +677,75 @@ where
             .collect()
     }
 
-    fn get_attesting_validators(&self, attestation_strategy: &AttestationStrategy) -> Vec<usize> {
+    /// Returns the validators which should attest to the block with `head_block_root`.
+    fn get_attesting_validators(
+        &self,
+        attestation_strategy: &AttestationStrategy,
+        head_block_root: Hash256,
+    ) -> Vec<usize> {
         match attestation_strategy {
             AttestationStrategy::AllValidators => (0..self.keypairs.len()).collect(),
             AttestationStrategy::SomeValidators(vec) => vec.clone(),
+            AttestationStrategy::PerValidator(votes) => votes
+                .iter()
+                .filter(|(_, block_root)| **block_root == head_block_root)
+                .map(|(validator_index, _)| *validator_index)
+                .collect(),
         }
     }
 
     /// Generates a `Vec<Attestation>` for some attestation strategy and head_block.
+    ///
+    /// With `AttestationStrategy::PerValidator`, attestations are also generated for each of the
+    /// other blocks in the strategy, using the state of that block advanced to `state.slot`.
     pub fn add_attestations_for_slot(
         &self,
         attestation_strategy: &AttestationStrategy,
         state: &BeaconState<E>,
         head_block_root: Hash256,
         head_block_slot: Slot,
+    ) {
+        if let AttestationStrategy::PerValidator(votes) = attestation_strategy {
+            let mut block_roots = votes.values().copied().collect::<Vec<_>>();
+            block_roots.sort();
+            block_roots.dedup();
+
+            for block_root in block_roots {
+                if block_root == head_block_root {
+                    self.add_attestations_for_block(
+                        attestation_strategy,
+                        state,
+                        block_root,
+                        head_block_slot,
+                    );
+                } else {
+                    let block_state = self.get_block_state_at_slot(block_root, state.slot);
+                    self.add_attestations_for_block(
+                        attestation_strategy,
+                        &block_state,
+                        block_root,
+                        head_block_slot,
+                    );
+                }
+            }
+        } else {
+            self.add_attestations_for_block(
+                attestation_strategy,
+                state,
+                head_block_root,
+                head_block_slot,
+            );
+        }
+    }
+
+    /// Generates and applies the attestations of the validators in `attestation_strategy` which
+    /// attest to `head_block_root`, using `state` to determine the committees.
+    fn add_attestations_for_block(
+        &self,
+        attestation_strategy: &AttestationStrategy,
+        state: &BeaconState<E>,
+        head_block_root: Hash256,
+        head_block_slot: Slot,
     ) {
         // These attestations will not be accepted by the chain so no need to generate them.
         if state.slot + E::slots_per_epoch() < self.chain.slot().expect("should get slot") {
@@ -630,7 +755,8 @@ where
         let spec = &self.spec;
         let fork = &state.fork;
 
-        let attesting_validators = self.get_attesting_validators(attestation_strategy);
+        let attesting_validators =
+            self.get_attesting_validators(attestation_strategy, head_block_root);
 
         let unaggregated_attestations = self.get_unaggregated_attestations(
             attestation_strategy,
@@ -0,0 +1,68 @@
+#![cfg(not(debug_assertions))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use beacon_chain::{
+    attestation_simulator::AttestationSimulator,
+    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy},
+};
+use slog::{o, Discard, Logger};
+use store::config::StoreConfig;
+use types::{EthSpec, Keypair, MinimalEthSpec};
+
+pub const VALIDATOR_COUNT: usize = 16;
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> = types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+/// Produces a simulated attestation at each slot of a chain where every validator attests and
+/// checks that each of them is correct and included.
+#[test]
+fn all_simulated_attestations_hit() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 4;
+
+    let harness = BeaconChainHarness::new(
+        MinimalEthSpec,
+        KEYPAIRS[..].to_vec(),
+        StoreConfig::default(),
+    );
+    let mut simulator =
+        AttestationSimulator::new(harness.chain.clone(), Logger::root(Discard, o!()));
+
+    // Skip past the genesis slot.
+    harness.advance_slot();
+
+    for _ in 0..num_blocks_produced {
+        harness.extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        simulator.produce(harness.chain.slot().expect("should get slot"));
+        harness.advance_slot();
+    }
+
+    let current_slot = harness.chain.slot().expect("should get slot");
+    let outcomes = simulator.check(current_slot);
+
+    assert_eq!(
+        outcomes.len() as u64,
+        num_blocks_produced - MinimalEthSpec::slots_per_epoch(),
+        "should check all attestations outside the inclusion window"
+    );
+
+    for outcome in outcomes {
+        assert!(outcome.head_hit, "head should hit at {}", outcome.slot);
+        assert!(outcome.target_hit, "target should hit at {}", outcome.slot);
+        assert!(outcome.source_hit, "source should hit at {}", outcome.slot);
+        assert!(outcome.included, "should be included at {}", outcome.slot);
+    }
+
+    assert!(
+        simulator.check(current_slot).is_empty(),
+        "should not check an attestation twice"
+    );
+}
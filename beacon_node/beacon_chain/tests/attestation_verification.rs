@@ -12,9 +12,9 @@ use state_processing::per_slot_processing;
 use store::config::StoreConfig;
 use tree_hash::TreeHash;
 use types::{
-    test_utils::generate_deterministic_keypair, AggregateSignature, Attestation, EthSpec, Hash256,
-    Keypair, MainnetEthSpec, SecretKey, SelectionProof, Signature, SignedAggregateAndProof,
-    SignedBeaconBlock, SubnetId, Unsigned,
+    test_utils::generate_deterministic_keypair, AggregateSignature, Attestation, BitList, EthSpec,
+    Hash256, Keypair, MainnetEthSpec, SecretKey, SelectionProof, Signature,
+    SignedAggregateAndProof, SignedBeaconBlock, SubnetId, Unsigned,
 };
 
 pub type E = MainnetEthSpec;
@@ -478,6 +478,48 @@ fn aggregated_gossip_verification() {
         if index == non_aggregator_index as u64
     );
 
+    /*
+     * The following test ensures:
+     *
+     * Spec v0.12.1
+     *
+     * The aggregate attestation's epoch matches its target -- i.e. aggregate.data.target.epoch ==
+     * compute_epoch_at_slot(aggregate.data.slot)
+     */
+
+    assert_invalid!(
+        "aggregate with bad target epoch",
+        {
+            let mut a = valid_aggregate.clone();
+            a.message.aggregate.data.target.epoch += 1;
+            a
+        },
+        AttnError::BadTargetEpoch
+    );
+
+    /*
+     * The following test ensures that the aggregation bitfield has the same length as the
+     * committee, as required by `is_valid_indexed_attestation`.
+     */
+
+    assert_invalid!(
+        "aggregate with bad aggregation bitfield length",
+        {
+            let mut a = valid_aggregate.clone();
+            let aggregation_bits = &mut a.message.aggregate.aggregation_bits;
+            let mut longer_bits =
+                BitList::with_capacity(aggregation_bits.len() + 1).expect("should create bitlist");
+            for i in 0..aggregation_bits.len() {
+                longer_bits
+                    .set(i, aggregation_bits.get(i).expect("should get bit"))
+                    .expect("should set bit");
+            }
+            *aggregation_bits = longer_bits;
+            a
+        },
+        AttnError::Invalid(_)
+    );
+
     // NOTE: from here on, the tests are stateful, and rely on the valid attestation having been
     // seen. A refactor to give each test case its own state might be nice at some point
     assert!(
@@ -833,3 +875,73 @@ fn attestation_that_skips_epochs() {
         .verify_unaggregated_attestation_for_gossip(attestation, subnet_id)
         .expect("should gossip verify attestation that skips slots");
 }
+
+/// Ensures that an attestation to a block which is not on the canonical chain can be verified,
+/// using the shuffling of its own fork.
+#[test]
+fn attestation_to_non_head_fork() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain = &harness.chain;
+
+    harness.extend_chain(
+        MainnetEthSpec::slots_per_epoch() as usize * 2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Build a single-block fork that nobody attests to, so it does not become the head.
+    let head_slot = chain.head_info().expect("should get head info").slot;
+    let fork_root = harness.extend_chain(
+        1,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: head_slot - 1,
+            first_slot: head_slot + 1,
+        },
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+    harness.advance_slot();
+    // Don't attest, so that none of the current committee is already known to have attested.
+    harness.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::SomeValidators(vec![]),
+    );
+
+    assert_ne!(
+        chain.head_info().expect("should get head info").block_root,
+        fork_root,
+        "the fork should not be the head"
+    );
+
+    let current_slot = chain.slot().expect("should get slot");
+    let fork_block = chain
+        .get_block(&fork_root)
+        .expect("should not error getting block")
+        .expect("should find fork block");
+    let mut state = chain
+        .get_state(&fork_block.state_root(), Some(fork_block.slot()))
+        .expect("should not error getting state")
+        .expect("should find state");
+
+    while state.slot < current_slot {
+        per_slot_processing(&mut state, None, &harness.spec).expect("should process slot");
+    }
+
+    let (attestation, subnet_id) = harness
+        .get_unaggregated_attestations(
+            &AttestationStrategy::AllValidators,
+            &state,
+            fork_root,
+            current_slot,
+        )
+        .first()
+        .expect("should have at least one committee")
+        .first()
+        .cloned()
+        .expect("should have at least one attestation in committee");
+
+    harness
+        .chain
+        .verify_unaggregated_attestation_for_gossip(attestation, subnet_id)
+        .expect("should gossip verify attestation to a non-head fork");
+}
@@ -5,7 +5,7 @@ extern crate lazy_static;
 
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType},
-    BeaconSnapshot, BlockError,
+    BeaconChainError, BeaconSnapshot, BlockError, ChainSegmentResult, HistoricalBlockError,
 };
 use store::config::StoreConfig;
 use types::{
@@ -272,6 +272,37 @@ fn chain_segment_non_linear_slots() {
     );
 }
 
+#[test]
+fn chain_segment_failed_block_root() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .chain
+        .slot_clock
+        .set_slot(CHAIN_SEGMENT.last().unwrap().beacon_block.slot().as_u64());
+
+    let invalid_index = 5;
+    let mut blocks = chain_segment_blocks();
+    blocks[invalid_index].signature = junk_signature();
+
+    // The blocks prior to the invalid block should be imported, even though they share a
+    // signature batch with it.
+    match harness.chain.process_chain_segment(blocks.clone()) {
+        ChainSegmentResult::Failed {
+            imported_blocks,
+            error: BlockError::InvalidSignature,
+            failed_block_root,
+        } => {
+            assert_eq!(imported_blocks, invalid_index, "should import prior blocks");
+            assert_eq!(
+                failed_block_root,
+                Some(blocks[invalid_index].canonical_root()),
+                "should identify the invalid block"
+            );
+        }
+        other => panic!("should fail with an invalid signature, got {:?}", other),
+    }
+}
+
 #[test]
 fn invalid_signatures() {
     let mut checked_attestation = false;
@@ -712,3 +743,86 @@ fn block_gossip_verification() {
         "the second proposal by this validator should be rejected"
     );
 }
+
+#[test]
+fn historical_block_batch() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let blocks = chain_segment_blocks();
+    let anchor_root = blocks.last().unwrap().canonical_root();
+    let genesis_root = blocks.first().unwrap().parent_root();
+
+    /*
+     * A batch which does not chain to the anchor is rejected.
+     */
+    assert!(
+        matches!(
+            harness
+                .chain
+                .import_historical_block_batch(&blocks[..blocks.len() - 1], anchor_root),
+            Err(BeaconChainError::HistoricalBlockError(
+                HistoricalBlockError::MismatchedBlockRoot { .. }
+            ))
+        ),
+        "should reject a batch that does not terminate at the anchor"
+    );
+
+    /*
+     * A batch with an invalid proposer signature is rejected, without storing any blocks.
+     */
+    let mut invalid_blocks = blocks.clone();
+    let block_index = invalid_blocks.len() / 2;
+    // The root of a block excludes its signature, so the chain of roots is still intact.
+    invalid_blocks[block_index].signature = junk_signature();
+    assert!(
+        matches!(
+            harness
+                .chain
+                .import_historical_block_batch(&invalid_blocks, anchor_root),
+            Err(BeaconChainError::HistoricalBlockError(
+                HistoricalBlockError::InvalidSignature { epoch }
+            ))
+            if epoch == invalid_blocks[block_index].slot().epoch(E::slots_per_epoch())
+        ),
+        "should reject a batch with an invalid proposer signature"
+    );
+    assert!(
+        harness
+            .chain
+            .store
+            .get_block(&anchor_root)
+            .expect("should read from store")
+            .is_none(),
+        "should not store blocks from an invalid batch"
+    );
+
+    /*
+     * A valid batch is imported in two halves, from newest to oldest.
+     */
+    let (older, newer) = blocks.split_at(blocks.len() / 2);
+    let next_root = harness
+        .chain
+        .import_historical_block_batch(newer, anchor_root)
+        .expect("should import newer half");
+    assert_eq!(next_root, older.last().unwrap().canonical_root());
+    assert_eq!(
+        harness
+            .chain
+            .import_historical_block_batch(older, next_root)
+            .expect("should import older half"),
+        genesis_root,
+        "the final batch should chain to genesis"
+    );
+
+    for block in &blocks {
+        assert_eq!(
+            harness
+                .chain
+                .store
+                .get_block(&block.canonical_root())
+                .expect("should read from store")
+                .as_ref(),
+            Some(block),
+            "should store each historical block"
+        );
+    }
+}
@@ -5,13 +5,15 @@ extern crate lazy_static;
 
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy},
-    BeaconChain, BeaconChainTypes,
+    BeaconChain, BeaconChainTypes, ChainConfig, PersistedBeaconChain, PersistedForkChoice,
+    BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
 };
+use fork_choice::ForkChoiceStore;
 use sloggers::{null::NullLoggerBuilder, Build};
 use std::sync::Arc;
-use store::{HotColdDB, LevelDB, StoreConfig};
+use store::{DBColumn, Error as StoreError, HotColdDB, LevelDB, StoreConfig, StoreItem};
 use tempfile::{tempdir, TempDir};
-use types::{EthSpec, Keypair, MinimalEthSpec};
+use types::{EthSpec, Hash256, Keypair, MinimalEthSpec};
 
 type E = MinimalEthSpec;
 
@@ -137,6 +139,296 @@ fn finalizes_after_resuming_from_db() {
     );
 }
 
+fn get_persisted_beacon_chain(
+    store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+) -> PersistedBeaconChain {
+    store
+        .get_item::<PersistedBeaconChain>(&Hash256::from_slice(&BEACON_CHAIN_DB_KEY))
+        .expect("should read persisted beacon chain")
+        .expect("should have persisted beacon chain")
+}
+
+#[test]
+fn repairs_head_after_unclean_shutdown() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    assert!(
+        !get_persisted_beacon_chain(&store).shutdown_clean,
+        "a running chain should not be marked as shut down cleanly"
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let head_block_root = harness
+        .chain
+        .head_info()
+        .expect("should read head")
+        .block_root;
+
+    // Simulate a crash which left the persisted head pointing to a block that never reached the
+    // database.
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    let mut persisted = get_persisted_beacon_chain(&store);
+    persisted.canonical_head_block_root = Hash256::repeat_byte(42);
+    store
+        .put_item(&Hash256::from_slice(&BEACON_CHAIN_DB_KEY), &persisted)
+        .expect("should write persisted beacon chain");
+
+    let data_dir = harness.data_dir;
+    std::mem::forget(harness.chain);
+
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+    );
+
+    assert_eq!(
+        resumed_harness
+            .chain
+            .head_info()
+            .expect("should read head")
+            .block_root,
+        head_block_root,
+        "the head should be repaired from the head tracker"
+    );
+
+    drop(resumed_harness);
+
+    let persisted = get_persisted_beacon_chain(&store);
+    assert!(
+        persisted.shutdown_clean,
+        "a dropped chain should be marked as shut down cleanly"
+    );
+    assert_eq!(persisted.canonical_head_block_root, head_block_root);
+}
+
+#[test]
+fn rebuilds_inconsistent_fork_choice() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    // The fork choice persisted at genesis, which knows nothing of the blocks produced below.
+    let stale_fork_choice = store
+        .get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY))
+        .expect("should read persisted fork choice")
+        .expect("should have persisted fork choice");
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        (MinimalEthSpec::slots_per_epoch() * 2) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    store
+        .put_item(
+            &Hash256::from_slice(&FORK_CHOICE_DB_KEY),
+            &stale_fork_choice,
+        )
+        .expect("should write persisted fork choice");
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+    );
+
+    assert_fork_choice_rebuilt(&original_chain, &resumed_harness.chain);
+}
+
+#[test]
+fn rebuilds_undecodable_fork_choice() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        (MinimalEthSpec::slots_per_epoch() * 2) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    store
+        .put_item(
+            &Hash256::from_slice(&FORK_CHOICE_DB_KEY),
+            &CorruptForkChoice,
+        )
+        .expect("should write corrupt fork choice");
+    assert!(
+        matches!(
+            store.get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY)),
+            Err(StoreError::SszDecodeError(_))
+        ),
+        "persisted fork choice should fail to decode"
+    );
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+    );
+
+    assert_fork_choice_rebuilt(&original_chain, &resumed_harness.chain);
+}
+
+#[test]
+fn purges_fork_choice() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        (MinimalEthSpec::slots_per_epoch() * 2) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+
+    assert!(
+        harness.chain.fork_choice.read().latest_message(0).is_some(),
+        "the persisted fork choice should contain votes"
+    );
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store_with_chain_config(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+        ChainConfig {
+            purge_fork_choice: true,
+            ..ChainConfig::default()
+        },
+    );
+
+    assert_fork_choice_rebuilt(&original_chain, &resumed_harness.chain);
+    assert!(
+        resumed_harness
+            .chain
+            .fork_choice
+            .read()
+            .latest_message(0)
+            .is_none(),
+        "the valid persisted fork choice should have been discarded"
+    );
+}
+
+/// An item in the fork choice column which is not a valid `PersistedForkChoice`.
+struct CorruptForkChoice;
+
+impl StoreItem for CorruptForkChoice {
+    fn db_column() -> DBColumn {
+        DBColumn::ForkChoice
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        vec![42; 7]
+    }
+
+    fn from_store_bytes(_bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self)
+    }
+}
+
+/// Checks that the fork choice of `resumed`, which was rebuilt from the store, agrees with that
+/// of `original`.
+fn assert_fork_choice_rebuilt<T: BeaconChainTypes>(
+    original: &BeaconChain<T>,
+    resumed: &BeaconChain<T>,
+) {
+    assert_eq!(
+        original.head().unwrap(),
+        resumed.head().unwrap(),
+        "head() should be equal"
+    );
+
+    let fork_choice = resumed.fork_choice.read();
+    for (block_root, _) in original.heads() {
+        assert!(
+            fork_choice.contains_block(&block_root),
+            "rebuilt fork choice should contain each head"
+        );
+    }
+    assert_eq!(
+        fork_choice.fc_store().finalized_checkpoint(),
+        original
+            .fork_choice
+            .read()
+            .fc_store()
+            .finalized_checkpoint(),
+        "rebuilt fork choice should have the same finalized checkpoint"
+    );
+}
+
 /// Checks that two chains are the same, for the purpose of this tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
@@ -0,0 +1,69 @@
+#![cfg(not(debug_assertions))]
+
+#[macro_use]
+extern crate lazy_static;
+
+use beacon_chain::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+use store::config::StoreConfig;
+use types::{Hash256, Keypair};
+
+pub const VALIDATOR_COUNT: usize = 16;
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> = types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+/// Checks a proposal against the following block when every validator attests.
+#[test]
+fn all_attesters_vote_for_proposal() {
+    let harness = BeaconChainHarness::new(
+        types::MinimalEthSpec,
+        KEYPAIRS[..].to_vec(),
+        StoreConfig::default(),
+    );
+
+    // Skip past the genesis slot.
+    harness.advance_slot();
+
+    harness.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    let proposal = harness.chain.head().expect("should get head");
+    let proposal_slot = proposal.beacon_block.slot();
+
+    harness.advance_slot();
+    harness.extend_chain(
+        1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    let head = harness.chain.head().expect("should get head");
+
+    let proposal_head_votes = &harness.chain.proposal_head_votes;
+    proposal_head_votes.register_proposal(proposal_slot, proposal.beacon_block_root);
+    proposal_head_votes.register_proposal(head.beacon_block.slot(), head.beacon_block_root);
+
+    let outcomes =
+        proposal_head_votes.process_block(&head.beacon_block.message, &head.beacon_state);
+
+    assert_eq!(outcomes.len(), 1, "should only check the earlier proposal");
+    let outcome = outcomes[0];
+    assert_eq!(outcome.slot, proposal_slot);
+    assert_eq!(outcome.block_root, proposal.beacon_block_root);
+    assert!(outcome.attesters > 0, "should have attesters");
+    assert_eq!(outcome.head_votes, outcome.attesters);
+    assert_eq!(outcome.head_vote_percentage(), 100);
+
+    // A proposal which was not the block voted for receives no head votes.
+    proposal_head_votes.register_proposal(proposal_slot, Hash256::repeat_byte(42));
+
+    let outcomes =
+        proposal_head_votes.process_block(&head.beacon_block.message, &head.beacon_state);
+
+    assert_eq!(outcomes.len(), 1, "should only check the earlier proposal");
+    assert_eq!(outcomes[0].head_votes, 0);
+    assert_eq!(outcomes[0].head_vote_percentage(), 0);
+}
@@ -261,6 +261,76 @@ fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+// Check that the roots of every finalized slot can be read directly from the freezer, including
+// skipped slots and slots after the latest restore point.
+#[test]
+fn cold_root_lookups() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        E::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Skip a few slots, then build enough blocks on top to finalize beyond the skip.
+    let skip_len = 3;
+    for _ in 0..skip_len {
+        harness.advance_slot();
+    }
+    harness.extend_chain(
+        4 * E::slots_per_epoch() as usize,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: Slot::new(E::slots_per_epoch()),
+            first_slot: Slot::new(E::slots_per_epoch() + skip_len + 1),
+        },
+        AttestationStrategy::AllValidators,
+    );
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > E::slots_per_epoch() + skip_len);
+
+    let block_roots = harness
+        .chain
+        .rev_iter_block_roots()
+        .expect("should get block roots iter")
+        .map(Result::unwrap)
+        .map(|(root, slot)| (slot, root))
+        .collect::<HashMap<_, _>>();
+    let state_roots = harness
+        .chain
+        .rev_iter_state_roots()
+        .expect("should get state roots iter")
+        .map(Result::unwrap)
+        .map(|(root, slot)| (slot, root))
+        .collect::<HashMap<_, _>>();
+
+    for slot in (0..split_slot.as_u64()).map(Slot::new) {
+        assert_eq!(
+            store.get_cold_block_root(slot).unwrap(),
+            Some(block_roots[&slot]),
+            "block root at slot {}",
+            slot
+        );
+        assert_eq!(
+            store.get_cold_state_root(slot).unwrap(),
+            Some(state_roots[&slot]),
+            "state root at slot {}",
+            slot
+        );
+        assert_eq!(
+            harness.chain.root_at_slot(slot).unwrap(),
+            Some(block_roots[&slot])
+        );
+    }
+
+    // Slots at or beyond the split are not served from the freezer.
+    assert_eq!(store.get_cold_block_root(split_slot).unwrap(), None);
+    assert_eq!(store.get_cold_state_root(split_slot).unwrap(), None);
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
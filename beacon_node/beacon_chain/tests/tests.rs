@@ -13,8 +13,12 @@ use operation_pool::PersistedOperationPool;
 use state_processing::{
     per_slot_processing, per_slot_processing::Error as SlotProcessingError, EpochProcessingError,
 };
+use std::collections::HashMap;
+use std::sync::Arc;
 use store::config::StoreConfig;
-use types::{BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Slot};
+use types::{
+    BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Signature, Slot,
+};
 
 // Should ideally be divisible by 3.
 pub const VALIDATOR_COUNT: usize = 24;
@@ -186,6 +190,93 @@ fn chooses_fork() {
     );
 }
 
+#[test]
+fn chooses_fork_of_fork_with_split_attestations() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let no_validators = || AttestationStrategy::SomeValidators(vec![]);
+
+    // Build a chain of unattested blocks, so that the attestations produced below decide the head.
+    harness.extend_chain(2, BlockStrategy::OnCanonicalHead, no_validators());
+    let fork_slot = harness.chain.head_info().expect("should get head").slot;
+
+    let canonical_head = harness.extend_chain(1, BlockStrategy::OnCanonicalHead, no_validators());
+
+    // Fork from the block at `fork_slot`, then fork from that fork.
+    let fork_head = harness.extend_chain(
+        1,
+        BlockStrategy::ForkCanonicalChainAt {
+            previous_slot: fork_slot,
+            first_slot: fork_slot + 2,
+        },
+        no_validators(),
+    );
+    let fork_of_fork_slot = fork_slot + 4;
+    let fork_of_fork_head = harness.extend_chain(
+        1,
+        BlockStrategy::ForkAtBlock {
+            parent_root: fork_head,
+            first_slot: fork_of_fork_slot,
+        },
+        no_validators(),
+    );
+
+    // Split the committee between the canonical chain and the fork of the fork, with the majority
+    // attesting to the fork of the fork.
+    let state = harness.get_block_state_at_slot(fork_of_fork_head, fork_of_fork_slot);
+    let committee = state
+        .get_beacon_committee(fork_of_fork_slot, 0)
+        .expect("should get committee")
+        .committee;
+    assert!(
+        committee.len() >= 3,
+        "test requires a committee of at least three validators"
+    );
+    let votes = committee
+        .iter()
+        .enumerate()
+        .map(|(i, validator_index)| {
+            let block_root = if i == 0 {
+                canonical_head
+            } else {
+                fork_of_fork_head
+            };
+            (*validator_index, block_root)
+        })
+        .collect::<HashMap<_, _>>();
+
+    harness.add_attestations_for_slot(
+        &AttestationStrategy::PerValidator(votes),
+        &state,
+        fork_of_fork_head,
+        fork_of_fork_slot,
+    );
+
+    let fork_of_fork_block = harness
+        .chain
+        .get_block(&fork_of_fork_head)
+        .expect("should read block")
+        .expect("should find block");
+    assert_eq!(
+        fork_of_fork_block.parent_root(),
+        fork_head,
+        "should build upon the non-canonical block"
+    );
+
+    // Attestations are applied to fork choice from the following slot.
+    harness.advance_slot();
+    harness.chain.fork_choice().expect("should run fork choice");
+
+    assert_eq!(
+        harness
+            .chain
+            .head()
+            .expect("should get head")
+            .beacon_block_root,
+        fork_of_fork_head,
+        "the fork of the fork should have the majority of the attestations"
+    );
+}
+
 #[test]
 fn finalizes_with_full_participation() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
@@ -616,3 +707,84 @@ fn produces_and_processes_with_genesis_skip_slots() {
         run_skip_slot_test(i)
     }
 }
+
+#[test]
+fn prepared_proposer_state_produces_identical_block() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let validator_indices = (0..VALIDATOR_COUNT as u64).collect::<Vec<_>>();
+    harness
+        .chain
+        .register_proposer_preparation(&validator_indices)
+        .expect("should register validators");
+    harness
+        .chain
+        .prepare_beacon_proposer()
+        .expect("should prepare proposer");
+
+    harness.advance_slot();
+    let slot = harness.chain.slot().expect("should get slot");
+
+    // The first block is produced upon the prepared state, the second upon a state loaded from
+    // the database. They should be identical.
+    let (prepared_block, prepared_state) = harness
+        .chain
+        .produce_block(Signature::empty_signature(), slot)
+        .expect("should produce block from prepared state");
+    let (block, state) = harness
+        .chain
+        .produce_block(Signature::empty_signature(), slot)
+        .expect("should produce block without prepared state");
+
+    assert_eq!(prepared_block, block, "blocks should be identical");
+    assert_eq!(
+        prepared_state.canonical_root(),
+        state.canonical_root(),
+        "states should be identical"
+    );
+}
+
+#[test]
+fn active_validators_match_registry() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        MinimalEthSpec::slots_per_epoch() as usize * 2,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let state = harness.chain.head().expect("should get head").beacon_state;
+    let spec = &harness.chain.spec;
+
+    let active_validators = harness
+        .chain
+        .active_validators(&state)
+        .expect("should get active validators");
+    let expected_indices = state
+        .get_active_validator_indices(state.current_epoch(), spec)
+        .expect("should get active indices");
+
+    assert_eq!(active_validators.indices, expected_indices);
+    assert_eq!(
+        active_validators.total_active_balance,
+        state
+            .get_total_balance(&expected_indices, spec)
+            .expect("should get total balance")
+    );
+
+    let cached = harness
+        .chain
+        .active_validators(&state)
+        .expect("should get cached active validators");
+    assert!(
+        Arc::ptr_eq(&active_validators, &cached),
+        "second call should hit the cache"
+    );
+}
@@ -1,9 +1,9 @@
 use crate::config::{ClientGenesis, Config as ClientConfig};
-use crate::notifier::spawn_notifier;
+use crate::notifier::{spawn_notifier, FinalityAlertConfig, ShutdownAfterSyncConfig};
 use crate::Client;
 use beacon_chain::events::TeeEventHandler;
 use beacon_chain::{
-    builder::{BeaconChainBuilder, Witness},
+    builder::{AnchorResolved, BeaconChainBuilder, Witness},
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     migrate::{BackgroundMigrator, Migrate},
     slot_clock::{SlotClock, SystemTimeSlotClock},
@@ -15,16 +15,17 @@ use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
+use futures::future;
 use genesis::{interop_genesis_state, Eth1GenesisService};
+use monitoring_api::{BeaconNodeData, MonitoringHttpClient, ProcessData, ProcessType};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
 use parking_lot::Mutex;
 use slog::info;
-use ssz::Decode;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
-use timer::spawn_timer;
+use timer::{spawn_attestation_simulator, spawn_proposer_preparation, spawn_timer};
 use tokio::sync::mpsc::UnboundedSender;
 use types::{
     test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
@@ -54,7 +55,7 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     store_migrator: Option<T::StoreMigrator>,
     runtime_context: Option<RuntimeContext<T::EthSpec>>,
     chain_spec: Option<ChainSpec>,
-    beacon_chain_builder: Option<BeaconChainBuilder<T>>,
+    beacon_chain_builder: Option<BeaconChainBuilder<T, AnchorResolved>>,
     beacon_chain: Option<Arc<BeaconChain<T>>>,
     eth1_service: Option<Eth1Service>,
     event_handler: Option<T::EventHandler>,
@@ -120,6 +121,14 @@ where
         self
     }
 
+    /// Specifies the slot clock used by the beacon chain and all client services.
+    ///
+    /// Tests may provide a `ManualSlotClock` in order to control time deterministically.
+    pub fn slot_clock(mut self, slot_clock: TSlotClock) -> Self {
+        self.slot_clock = Some(slot_clock);
+        self
+    }
+
     /// Initializes the `BeaconChainBuilder`. The `build_beacon_chain` method will need to be
     /// called later in order to actually instantiate the `BeaconChain`.
     pub async fn beacon_chain_builder(
@@ -135,6 +144,7 @@ where
         let data_dir = config.data_dir.clone();
         let disabled_forks = config.disabled_forks.clone();
         let graffiti = config.graffiti.clone();
+        let chain_config = config.chain;
 
         let store =
             store.ok_or_else(|| "beacon_chain_start_method requires a store".to_string())?;
@@ -153,7 +163,8 @@ where
             .data_dir(data_dir)
             .custom_spec(spec.clone())
             .disabled_forks(disabled_forks)
-            .graffiti(graffiti);
+            .graffiti(graffiti)
+            .chain_config(chain_config);
 
         let chain_exists = builder
             .store_contains_beacon_chain()
@@ -192,8 +203,9 @@ where
                     "Starting from known genesis state";
                 );
 
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state =
+                    BeaconState::from_ssz_bytes_with_spec(&genesis_state_bytes, &spec)
+                        .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
 
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
@@ -270,8 +282,82 @@ where
             .ok_or_else(|| "node timer requires a chain spec".to_string())?
             .milliseconds_per_slot;
 
-        spawn_timer(context.executor, beacon_chain, milliseconds_per_slot)
-            .map_err(|e| format!("Unable to start node timer: {}", e))?;
+        spawn_timer(
+            context.executor.clone(),
+            beacon_chain.clone(),
+            milliseconds_per_slot,
+        )
+        .map_err(|e| format!("Unable to start node timer: {}", e))?;
+
+        spawn_attestation_simulator(context.executor, beacon_chain, milliseconds_per_slot)
+            .map_err(|e| format!("Unable to start attestation simulator: {}", e))?;
+
+        Ok(self)
+    }
+
+    /// Immediately starts the dedicated Prometheus metrics http server.
+    pub fn metrics_server(self, client_config: &ClientConfig) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "metrics_server requires a beacon chain")?;
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "metrics_server requires a runtime_context")?
+            .service_context("metrics_http".into());
+
+        rest_api::start_metrics_server(
+            context.executor,
+            &client_config.metrics,
+            beacon_chain,
+            client_config
+                .create_db_path()
+                .map_err(|_| "unable to read data dir")?,
+            client_config
+                .create_freezer_db_path()
+                .map_err(|_| "unable to read freezer DB dir")?,
+        )
+        .map_err(|e| format!("Failed to start metrics server: {}", e))?;
+
+        Ok(self)
+    }
+
+    /// Immediately starts the service that periodically sends a snapshot of the node to a remote
+    /// monitoring endpoint.
+    pub fn monitoring_client(self, config: &monitoring_api::Config) -> Result<Self, String> {
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "monitoring_client requires a runtime_context")?
+            .service_context("monitoring_api".into());
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "monitoring_client requires a beacon chain")?;
+        let network_globals = self
+            .network_globals
+            .clone()
+            .ok_or_else(|| "monitoring_client requires a libp2p network")?;
+
+        let client =
+            MonitoringHttpClient::new(config, ProcessType::BeaconNode, context.log().clone())?;
+
+        client.auto_update(context.executor, move || {
+            let result = beacon_chain
+                .head_info()
+                .map(|head_info| {
+                    ProcessData::BeaconNode(BeaconNodeData {
+                        sync_synced: network_globals.sync_state().is_synced(),
+                        sync_head_slot: head_info.slot.as_u64(),
+                        sync_finalized_epoch: head_info.finalized_checkpoint.epoch.as_u64(),
+                        network_peers_connected: network_globals.connected_peers() as u64,
+                    })
+                })
+                .map_err(|e| format!("Unable to read head: {:?}", e));
+
+            future::ready(result)
+        });
 
         Ok(self)
     }
@@ -320,15 +406,15 @@ where
             eth2_config.clone(),
             events,
         )
-        .map_err(|e| format!("Failed to start HTTP API: {:?}", e))?;
+        .map_err(|e| format!("Failed to start HTTP API: {}", e))?;
 
-        self.http_listen_addr = Some(listening_addr);
+        self.http_listen_addr = listening_addr;
 
         Ok(self)
     }
 
     /// Immediately starts the service that periodically logs information each slot.
-    pub fn notifier(self) -> Result<Self, String> {
+    pub fn notifier(self, client_config: &ClientConfig) -> Result<Self, String> {
         let context = self
             .runtime_context
             .as_ref()
@@ -353,12 +439,59 @@ where
             beacon_chain,
             network_globals,
             milliseconds_per_slot,
+            FinalityAlertConfig {
+                delay_epochs: client_config.finality_alert_epochs,
+                debug_info_dir: if client_config.debug_info_on_finality_delay {
+                    client_config.get_debug_info_dir()
+                } else {
+                    None
+                },
+            },
+            if client_config.shutdown_after_sync {
+                Some(ShutdownAfterSyncConfig {
+                    db_paths: client_config
+                        .get_db_path()
+                        .into_iter()
+                        .chain(client_config.get_freezer_db_path())
+                        .collect(),
+                })
+            } else {
+                None
+            },
         )
         .map_err(|e| format!("Unable to start slot notifier: {}", e))?;
 
         Ok(self)
     }
 
+    /// Immediately starts the service that prepares block proposals ahead of the slot.
+    pub fn proposer_preparation(self, lookahead: Duration) -> Result<Self, String> {
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "proposer_preparation requires a runtime_context")?
+            .service_context("proposer_preparation".into());
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "proposer_preparation requires a beacon chain")?;
+        let milliseconds_per_slot = self
+            .chain_spec
+            .as_ref()
+            .ok_or_else(|| "proposer_preparation requires a chain spec".to_string())?
+            .milliseconds_per_slot;
+
+        spawn_proposer_preparation(
+            context.executor,
+            beacon_chain,
+            milliseconds_per_slot,
+            lookahead,
+        )
+        .map_err(|e| format!("Unable to start proposer preparation: {}", e))?;
+
+        Ok(self)
+    }
+
     /// Consumers the builder, returning a `Client` if all necessary components have been
     /// specified.
     ///
@@ -739,12 +872,7 @@ where
             .as_ref()
             .ok_or_else(|| "system_time_slot_clock requires a beacon_chain_builder")?;
 
-        let genesis_time = beacon_chain_builder
-            .finalized_snapshot
-            .as_ref()
-            .ok_or_else(|| "system_time_slot_clock requires an initialized beacon state")?
-            .beacon_state
-            .genesis_time;
+        let genesis_time = beacon_chain_builder.genesis_time()?;
 
         let spec = self
             .chain_spec
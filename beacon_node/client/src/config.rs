@@ -1,3 +1,4 @@
+use beacon_chain::ChainConfig;
 use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
@@ -12,6 +13,14 @@ const TESTNET_SPEC_CONSTANTS: &str = "minimal";
 /// Default directory name for the freezer database under the top-level data dir.
 const DEFAULT_FREEZER_DB_DIR: &str = "freezer_db";
 
+/// Default number of epochs without finality before the notifier raises an alert.
+///
+/// Under normal operation the finalized epoch is two epochs behind the current epoch.
+pub const DEFAULT_FINALITY_ALERT_EPOCHS: u64 = 4;
+
+/// Default directory name for debug-info dumps under the top-level data dir.
+const DEFAULT_DEBUG_INFO_DIR: &str = "debug_info";
+
 /// Defines how the client should initialize the `BeaconChain` and other components.
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum ClientGenesis {
@@ -65,8 +74,24 @@ pub struct Config {
     pub store: store::StoreConfig,
     pub network: network::NetworkConfig,
     pub rest_api: rest_api::Config,
+    pub metrics: rest_api::MetricsConfig,
+    /// If `Some`, periodically send a snapshot of the node to a remote monitoring service.
+    pub monitoring_api: Option<monitoring_api::Config>,
     pub websocket_server: websocket_server::Config,
     pub eth1: eth1::Config,
+    /// Raise alerts when the finalized epoch is more than this many epochs behind the current
+    /// epoch.
+    pub finality_alert_epochs: u64,
+    /// If true, write a debug-info dump to the data directory when finality is delayed by more
+    /// than `finality_alert_epochs`.
+    pub debug_info_on_finality_delay: bool,
+    /// If `Some`, run fork choice and advance the head state this many milliseconds before the
+    /// start of a slot in which a registered validator is due to propose.
+    pub proposer_preparation_lookahead_ms: Option<u64>,
+    /// Configuration of the `BeaconChain` which is local to this node.
+    pub chain: ChainConfig,
+    /// If true, shut down once the node has synced to the head of the chain.
+    pub shutdown_after_sync: bool,
 }
 
 impl Default for Config {
@@ -81,6 +106,8 @@ impl Default for Config {
             store: <_>::default(),
             network: NetworkConfig::default(),
             rest_api: <_>::default(),
+            metrics: <_>::default(),
+            monitoring_api: None,
             websocket_server: <_>::default(),
             spec_constants: TESTNET_SPEC_CONSTANTS.into(),
             dummy_eth1_backend: false,
@@ -88,6 +115,11 @@ impl Default for Config {
             eth1: <_>::default(),
             disabled_forks: Vec::new(),
             graffiti: Graffiti::default(),
+            finality_alert_epochs: DEFAULT_FINALITY_ALERT_EPOCHS,
+            debug_info_on_finality_delay: false,
+            proposer_preparation_lookahead_ms: None,
+            chain: ChainConfig::default(),
+            shutdown_after_sync: false,
         }
     }
 }
@@ -131,6 +163,14 @@ impl Config {
         ensure_dir_exists(freezer_db_path)
     }
 
+    /// Returns the directory to which debug-info dumps are written.
+    ///
+    /// Will not create any directories.
+    pub fn get_debug_info_dir(&self) -> Option<PathBuf> {
+        self.get_data_dir()
+            .map(|data_dir| data_dir.join(DEFAULT_DEBUG_INFO_DIR))
+    }
+
     /// Returns the core path for the client.
     ///
     /// Will not create any directories.
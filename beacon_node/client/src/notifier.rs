@@ -3,13 +3,19 @@ use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::NetworkGlobals;
 use futures::prelude::*;
 use parking_lot::Mutex;
-use slog::{debug, error, info, warn};
+use serde_derive::Serialize;
+use serde_json::json;
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use time;
 use tokio::time::delay_for;
-use types::{EthSpec, Slot};
+use types::{Epoch, EthSpec, Hash256, Slot};
 
 /// Create a warning log whenever the peer count is at or below this value.
 pub const WARN_PEER_COUNT: usize = 1;
@@ -21,12 +27,44 @@ const MINUTES_PER_HOUR: i64 = 60;
 /// The number of historical observations that should be used to determine the average sync time.
 const SPEEDO_OBSERVATIONS: usize = 4;
 
+/// The number of recent head changes which are included in a debug-info dump.
+const HEAD_HISTORY_LENGTH: usize = 64;
+
+/// Determines how the notifier reacts to the chain failing to finalize.
+#[derive(Debug, Clone)]
+pub struct FinalityAlertConfig {
+    /// Start warning once the finalized epoch is more than this many epochs behind the current
+    /// epoch. The alerts escalate to errors at twice this delay and to critical at four times.
+    pub delay_epochs: u64,
+    /// If `Some`, write a single debug-info dump to this directory each time the finality delay
+    /// crosses `delay_epochs`.
+    pub debug_info_dir: Option<PathBuf>,
+}
+
+/// Instructs the notifier to shut down the node once it has synced to the head of the chain.
+#[derive(Debug, Clone)]
+pub struct ShutdownAfterSyncConfig {
+    /// The database directories, the total size of which is included in the final sync summary.
+    pub db_paths: Vec<PathBuf>,
+}
+
+/// A change of head observed by the notifier, retained for debug-info dumps.
+#[derive(Debug, Clone, Serialize)]
+struct HeadObservation {
+    current_slot: Slot,
+    head_slot: Slot,
+    head_root: Hash256,
+    finalized_epoch: Epoch,
+}
+
 /// Spawns a notifier service which periodically logs information about the node.
 pub fn spawn_notifier<T: BeaconChainTypes>(
     executor: environment::TaskExecutor,
     beacon_chain: Arc<BeaconChain<T>>,
     network: Arc<NetworkGlobals<T::EthSpec>>,
     milliseconds_per_slot: u64,
+    finality_alert_config: FinalityAlertConfig,
+    shutdown_after_sync: Option<ShutdownAfterSyncConfig>,
 ) -> Result<(), String> {
     let slot_duration = Duration::from_millis(milliseconds_per_slot);
     let duration_to_next_slot = beacon_chain
@@ -41,7 +79,11 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
     let interval_duration = slot_duration;
 
     let speedo = Mutex::new(Speedo::default());
+    let mut head_history: VecDeque<HeadObservation> = VecDeque::with_capacity(HEAD_HISTORY_LENGTH);
+    let mut last_finality_alert_epoch: Option<Epoch> = None;
+    let mut debug_info_written = false;
     let log = executor.log().clone();
+    let mut shutdown_sender = executor.shutdown_sender();
     let mut interval = tokio::time::interval_at(start_instant, interval_duration);
 
     let interval_future = async move {
@@ -63,6 +105,16 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
             }
         }
 
+        // Used to summarise the sync if `shutdown_after_sync` is set.
+        let sync_start_time = Instant::now();
+        let sync_start_slot = beacon_chain.best_slot().map_err(|e| {
+            error!(
+                log,
+                "Failed to get beacon chain head slot";
+                "error" => format!("{:?}", e)
+            )
+        })?;
+
         // Perform post-genesis logging.
         while let Some(_) = interval.next().await {
             let connected_peer_count = network.connected_peers();
@@ -89,6 +141,62 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
             let finalized_root = head_info.finalized_checkpoint.root;
             let head_root = head_info.block_root;
 
+            if head_history
+                .back()
+                .map_or(true, |prev| prev.head_root != head_root)
+            {
+                if head_history.len() >= HEAD_HISTORY_LENGTH {
+                    head_history.pop_front();
+                }
+                head_history.push_back(HeadObservation {
+                    current_slot,
+                    head_slot,
+                    head_root,
+                    finalized_epoch,
+                });
+            }
+
+            // Taking advantage of saturating subtraction on `Epoch`.
+            let finality_delay = (current_epoch - finalized_epoch).as_u64();
+            let alert_threshold = finality_alert_config.delay_epochs;
+
+            // A syncing node is expected to be far behind finality, only alert once synced.
+            if sync_state.is_synced() && finality_delay > alert_threshold {
+                // Only alert once per epoch, the notifier runs every slot.
+                if last_finality_alert_epoch != Some(current_epoch) {
+                    last_finality_alert_epoch = Some(current_epoch);
+                    log_finality_delay(&log, finality_delay, alert_threshold, finalized_epoch);
+                }
+
+                if !debug_info_written {
+                    if let Some(dir) = &finality_alert_config.debug_info_dir {
+                        debug_info_written = true;
+                        match write_debug_info(
+                            &beacon_chain,
+                            &network,
+                            &head_history,
+                            current_slot,
+                            dir,
+                        ) {
+                            Ok(path) => warn!(
+                                log,
+                                "Wrote debug info";
+                                "reason" => "finality delayed",
+                                "path" => path.display().to_string(),
+                            ),
+                            Err(e) => error!(
+                                log,
+                                "Failed to write debug info";
+                                "error" => e,
+                            ),
+                        }
+                    }
+                }
+            } else {
+                // Allow another dump if finality recovers and is later delayed again.
+                debug_info_written = false;
+            }
+
             let mut speedo = speedo.lock();
             speedo.observe(head_slot, Instant::now());
 
@@ -123,13 +231,21 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
                     head_distance.as_u64(),
                     slot_distance_pretty(head_distance, slot_duration)
                 );
+                let peer_counts = SyncPeerCounts::from_network(&network);
                 info!(
                     log,
                     "Syncing";
+                    "state" => format!("{}", sync_state),
                     "peers" => peer_count_pretty(connected_peer_count),
+                    "useful_peers" => peer_counts.useful(),
+                    "peer_sync_status" => format!("{}", peer_counts),
                     "distance" => distance,
                     "speed" => sync_speed_pretty(speedo.slots_per_second()),
                     "est_time" => estimated_time_pretty(speedo.estimated_time_till_slot(current_slot)),
+                    "head_slot" => head_slot,
+                    "head_block" => format!("{}", head_root),
+                    "finalized_epoch" => finalized_epoch,
+                    "finalized_root" => format!("{}", finalized_root),
                 );
             } else {
                 if sync_state.is_synced() {
@@ -148,6 +264,45 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
                         "block" => block_info,
                         "slot" => current_slot,
                     );
+
+                    if let Some(config) = &shutdown_after_sync {
+                        if head_distance.as_u64() <= T::EthSpec::slots_per_epoch() {
+                            let db_size = config
+                                .db_paths
+                                .iter()
+                                .map(|path| dir_size(path).unwrap_or(0))
+                                .sum();
+                            info!(
+                                log,
+                                "Sync complete";
+                                "msg" => "shutting down due to --shutdown-after-sync",
+                                "slots_imported" => (head_slot - sync_start_slot).as_u64(),
+                                "duration" => seconds_pretty(sync_start_time.elapsed().as_secs_f64()),
+                                "db_size" => bytes_pretty(db_size),
+                                "head_slot" => head_slot,
+                                "finalized_epoch" => finalized_epoch,
+                            );
+                            if let Err(e) =
+                                shutdown_sender.try_send("Synced with --shutdown-after-sync")
+                            {
+                                crit!(
+                                    log,
+                                    "Failed to request shutdown after sync";
+                                    "error" => format!("{:?}", e)
+                                );
+                            }
+                            break;
+                        }
+                    }
+                } else if head_distance.as_u64() > T::EthSpec::slots_per_epoch() {
+                    warn!(
+                        log,
+                        "Stalled behind the head";
+                        "msg" => "no synced peers, validators will not be served duties",
+                        "peers" => peer_count_pretty(connected_peer_count),
+                        "head_slot" => head_slot,
+                        "current_slot" => current_slot,
+                    );
                 } else {
                     info!(
                         log,
@@ -170,6 +325,169 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Logs a finality delay of `finality_delay` epochs, escalating the level of the log as the delay
+/// grows relative to `threshold`.
+fn log_finality_delay(log: &Logger, finality_delay: u64, threshold: u64, finalized_epoch: Epoch) {
+    let msg = "the chain may be partitioned or lacking participation";
+
+    if finality_delay > threshold.saturating_mul(4) {
+        crit!(
+            log,
+            "Finality is severely delayed";
+            "msg" => msg,
+            "epochs_since_finality" => finality_delay,
+            "finalized_epoch" => finalized_epoch,
+        );
+    } else if finality_delay > threshold.saturating_mul(2) {
+        error!(
+            log,
+            "Finality is delayed";
+            "msg" => msg,
+            "epochs_since_finality" => finality_delay,
+            "finalized_epoch" => finalized_epoch,
+        );
+    } else {
+        warn!(
+            log,
+            "Finality is delayed";
+            "msg" => msg,
+            "epochs_since_finality" => finality_delay,
+            "finalized_epoch" => finalized_epoch,
+        );
+    }
+}
+
+/// Writes a JSON file to `dir` containing information useful for diagnosing a lack of finality:
+/// the fork choice DAG, the known peers, the sync state and the recent changes of head.
+///
+/// Returns the path of the file.
+fn write_debug_info<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    network: &NetworkGlobals<T::EthSpec>,
+    head_history: &VecDeque<HeadObservation>,
+    current_slot: Slot,
+    dir: &Path,
+) -> Result<PathBuf, String> {
+    let head_info = beacon_chain
+        .head_info()
+        .map_err(|e| format!("Unable to read head: {:?}", e))?;
+
+    let fork_choice = serde_json::to_value(
+        beacon_chain
+            .fork_choice
+            .read()
+            .proto_array()
+            .core_proto_array(),
+    )
+    .map_err(|e| format!("Unable to serialize fork choice: {:?}", e))?;
+
+    let peers = network
+        .peers
+        .read()
+        .peers()
+        .map(|(peer_id, peer_info)| {
+            json!({
+                "peer_id": peer_id.to_string(),
+                "peer_info": peer_info,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let debug_info = json!({
+        "current_slot": current_slot,
+        "head_slot": head_info.slot,
+        "head_root": head_info.block_root,
+        "finalized_checkpoint": head_info.finalized_checkpoint,
+        "current_justified_checkpoint": head_info.current_justified_checkpoint,
+        "sync_state": network.sync_state(),
+        "peers": peers,
+        "head_history": head_history,
+        "fork_choice": fork_choice,
+    });
+
+    let bytes = serde_json::to_vec_pretty(&debug_info)
+        .map_err(|e| format!("Unable to serialize debug info: {:?}", e))?;
+
+    fs::create_dir_all(dir).map_err(|e| format!("Unable to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("debug_info_slot_{}.json", current_slot));
+    fs::write(&path, bytes).map_err(|e| format!("Unable to write {}: {}", path.display(), e))?;
+
+    Ok(path)
+}
+
+/// Counts of the connected peers, grouped by their sync status relative to our node.
+#[derive(Debug, Default, PartialEq)]
+struct SyncPeerCounts {
+    advanced: usize,
+    synced: usize,
+    behind: usize,
+    unknown: usize,
+}
+
+impl SyncPeerCounts {
+    fn from_network<E: EthSpec>(network: &NetworkGlobals<E>) -> Self {
+        let mut counts = Self::default();
+
+        for (_, info) in network.peers.read().connected_peers() {
+            if info.sync_status.is_advanced() {
+                counts.advanced += 1;
+            } else if info.sync_status.is_synced() {
+                counts.synced += 1;
+            } else if info.sync_status.is_behind() {
+                counts.behind += 1;
+            } else {
+                counts.unknown += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Returns the number of peers from which we could download blocks.
+    fn useful(&self) -> usize {
+        self.advanced + self.synced
+    }
+}
+
+impl fmt::Display for SyncPeerCounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "advanced: {}, synced: {}, behind: {}, unknown: {}",
+            self.advanced, self.synced, self.behind, self.unknown
+        )
+    }
+}
+
+/// Returns the total size in bytes of the files in `path` and its subdirectories.
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Returns a nicely formatted string describing a number of bytes.
+fn bytes_pretty(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
 /// Returns the peer count, returning something helpful if it's `usize::max_value` (effectively a
 /// `None` value).
 fn peer_count_pretty(peer_count: usize) -> String {
@@ -0,0 +1,167 @@
+#![cfg(test)]
+//! Builds a `Client` from each of the `ClientGenesis` modes which do not require an eth1 node.
+//!
+//! `ClientGenesis::DepositContract` is covered by the `eth1_sim` simulator instead.
+use beacon_chain::store::{HotColdDB, LevelDB, StoreConfig};
+use client::{ClientBuilder, ClientConfig, ClientGenesis};
+use environment::{null_logger, Environment, EnvironmentBuilder};
+use genesis::interop_genesis_state;
+use ssz::Encode;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::{tempdir, TempDir};
+use types::{test_utils::generate_deterministic_keypairs, EthSpec, Hash256, MinimalEthSpec};
+use websocket_server::Config as WebSocketConfig;
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 16;
+
+/// The number of times to attempt to open a database which may still be held by a dropped client.
+const DB_OPEN_ATTEMPTS: usize = 50;
+
+fn new_env() -> Environment<E> {
+    EnvironmentBuilder::minimal()
+        .single_thread_tokio_runtime()
+        .expect("should start tokio runtime")
+        .null_logger()
+        .expect("should start null logger")
+        .build()
+        .expect("should build env")
+}
+
+fn recent_genesis_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("should read system time")
+        .as_secs()
+}
+
+fn client_config(data_dir: &TempDir) -> ClientConfig {
+    let mut config = ClientConfig::default();
+    config.data_dir = data_dir.path().to_path_buf();
+    config
+}
+
+/// Builds a client from `client_genesis`, returning the head block root and the genesis time of
+/// its beacon chain.
+///
+/// The client is dropped (and its database released) before returning.
+fn build_client(
+    env: &mut Environment<E>,
+    data_dir: &TempDir,
+    client_genesis: ClientGenesis,
+) -> Result<(Hash256, u64), String> {
+    let context = env.core_context();
+    let spec = context.eth2_config().spec.clone();
+    let config = client_config(data_dir);
+    let hot_path = config.create_db_path()?;
+    let cold_path = config.create_freezer_db_path()?;
+
+    let result = env.runtime().block_on(async {
+        let client = ClientBuilder::new(E::default())
+            .runtime_context(context)
+            .chain_spec(spec.clone())
+            .disk_store(&hot_path, &cold_path, StoreConfig::default())?
+            .background_migrator()?
+            .beacon_chain_builder(client_genesis, config)
+            .await?
+            .dummy_eth1_backend()?
+            .system_time_slot_clock()?
+            .websocket_event_handler(WebSocketConfig::default())?
+            .build_beacon_chain()?
+            .build();
+
+        let head = client
+            .beacon_chain()
+            .ok_or_else(|| "Client should have a beacon chain".to_string())?
+            .head_info()
+            .map_err(|e| format!("Unable to read head: {:?}", e))?;
+
+        Ok::<_, String>((head.block_root, head.genesis_time))
+    });
+
+    // The background migrator releases its handle on the database from its own thread, so wait
+    // until the database can be opened again.
+    for _ in 0..DB_OPEN_ATTEMPTS {
+        let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &hot_path,
+            &cold_path,
+            StoreConfig::default(),
+            spec.clone(),
+            null_logger()?,
+        );
+        if db.is_ok() {
+            return result;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    Err("Database was not released by the client".to_string())
+}
+
+#[test]
+fn interop_genesis() {
+    let mut env = new_env();
+    let data_dir = tempdir().expect("should create temporary data_dir");
+    let genesis_time = recent_genesis_time();
+
+    let (_, chain_genesis_time) = build_client(
+        &mut env,
+        &data_dir,
+        ClientGenesis::Interop {
+            validator_count: VALIDATOR_COUNT,
+            genesis_time,
+        },
+    )
+    .expect("should build client from interop genesis");
+
+    assert_eq!(chain_genesis_time, genesis_time);
+}
+
+#[test]
+fn ssz_bytes_genesis() {
+    let mut env = new_env();
+    let data_dir = tempdir().expect("should create temporary data_dir");
+    let spec = E::default_spec();
+    let genesis_time = recent_genesis_time();
+
+    let keypairs = generate_deterministic_keypairs(VALIDATOR_COUNT);
+    let genesis_state = interop_genesis_state::<E>(&keypairs, genesis_time, &spec)
+        .expect("should generate interop state");
+
+    let (_, chain_genesis_time) = build_client(
+        &mut env,
+        &data_dir,
+        ClientGenesis::SszBytes {
+            genesis_state_bytes: genesis_state.as_ssz_bytes(),
+        },
+    )
+    .expect("should build client from genesis state bytes");
+
+    assert_eq!(chain_genesis_time, genesis_time);
+}
+
+#[test]
+fn resume_from_store() {
+    let mut env = new_env();
+    let data_dir = tempdir().expect("should create temporary data_dir");
+    let genesis_time = recent_genesis_time();
+
+    let (head_block_root, _) = build_client(
+        &mut env,
+        &data_dir,
+        ClientGenesis::Interop {
+            validator_count: VALIDATOR_COUNT,
+            genesis_time,
+        },
+    )
+    .expect("should build client from interop genesis");
+
+    let (resumed_head_block_root, resumed_genesis_time) =
+        build_client(&mut env, &data_dir, ClientGenesis::FromStore)
+            .expect("should resume client from store");
+
+    assert_eq!(resumed_head_block_root, head_block_root);
+    assert_eq!(resumed_genesis_time, genesis_time);
+}
@@ -1,4 +1,5 @@
 use crate::DepositLog;
+use parking_lot::Mutex;
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
@@ -69,6 +70,7 @@ impl SszDepositCache {
             deposit_contract_deploy_block: self.deposit_contract_deploy_block,
             deposit_tree,
             deposit_roots: self.deposit_roots.clone(),
+            proof_tree: Mutex::new(None),
         })
     }
 }
@@ -86,6 +88,13 @@ pub struct DepositCache {
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
     /// `deposit_index` `i`.
     deposit_roots: Vec<Hash256>,
+    /// An incremental merkle tree containing the first `n` leaves, where `n` is the
+    /// `deposit_count` of the most recent call to `Self::get_deposits`.
+    ///
+    /// Block production repeatedly requests proofs against the same (or a slowly increasing)
+    /// `deposit_count`, which is usually less than the number of known deposits. Maintaining this
+    /// tree avoids rebuilding it from all the leaves for each request.
+    proof_tree: Mutex<Option<DepositDataTree>>,
 }
 
 impl Default for DepositCache {
@@ -98,6 +107,7 @@ impl Default for DepositCache {
             deposit_contract_deploy_block: 1,
             deposit_tree,
             deposit_roots,
+            proof_tree: Mutex::new(None),
         }
     }
 }
@@ -210,39 +220,70 @@ impl DepositCache {
                 known_deposits: self.logs.len(),
             })
         } else {
-            let leaves = self
+            let deposit_count = deposit_count as usize;
+            // The trees maintained by `self` all have a depth of `DEPOSIT_TREE_DEPTH`.
+            let use_cached_trees = tree_depth == DEPOSIT_TREE_DEPTH;
+
+            // If the deposit count is the number of known deposits, the proofs can be generated
+            // directly from `self.deposit_tree`.
+            if use_cached_trees && deposit_count == self.leaves.len() {
+                let deposits = self.deposits_with_proofs(&self.deposit_tree, start, end)?;
+                return Ok((self.deposit_tree.root(), deposits));
+            }
+
+            let mut proof_tree = self.proof_tree.lock();
+
+            // Re-use the proof tree from a previous call if it does not contain too many leaves,
+            // otherwise start with an empty tree.
+            let mut tree = match proof_tree.take() {
+                Some(tree) if use_cached_trees && tree.mix_in_length() <= deposit_count => tree,
+                _ => DepositDataTree::create(&[], 0, tree_depth),
+            };
+
+            // Append any leaves the tree is missing, each append is `O(log n)`.
+            let missing_leaves = self
                 .leaves
-                .get(0..deposit_count as usize)
+                .get(tree.mix_in_length()..deposit_count)
                 .ok_or_else(|| Error::InternalError("Unable to get known leaves".into()))?;
+            for leaf in missing_leaves {
+                tree.push_leaf(*leaf).map_err(Error::DepositTreeError)?;
+            }
+
+            let root = tree.root();
+            let deposits = self.deposits_with_proofs(&tree, start, end);
+
+            if use_cached_trees {
+                *proof_tree = Some(tree);
+            }
 
-            // Note: there is likely a more optimal solution than recreating the `DepositDataTree`
-            // each time this function is called.
-            //
-            // Perhaps a base merkle tree could be maintained that contains all deposits up to the
-            // last finalized eth1 deposit count. Then, that tree could be cloned and extended for
-            // each of these calls.
-
-            let tree = DepositDataTree::create(leaves, deposit_count as usize, tree_depth);
-
-            let deposits = self
-                .logs
-                .get(start as usize..end as usize)
-                .ok_or_else(|| Error::InternalError("Unable to get known log".into()))?
-                .iter()
-                .map(|deposit_log| {
-                    let (_leaf, proof) = tree.generate_proof(deposit_log.index as usize);
-
-                    Deposit {
-                        proof: proof.into(),
-                        data: deposit_log.deposit_data.clone(),
-                    }
-                })
-                .collect();
-
-            Ok((tree.root(), deposits))
+            Ok((root, deposits?))
         }
     }
 
+    /// Returns the `Deposit` objects within the given deposit index range, with proofs generated
+    /// from `tree`.
+    fn deposits_with_proofs(
+        &self,
+        tree: &DepositDataTree,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<Deposit>, Error> {
+        Ok(self
+            .logs
+            .get(start as usize..end as usize)
+            .ok_or_else(|| Error::InternalError("Unable to get known log".into()))?
+            .iter()
+            .map(|deposit_log| {
+                let (_leaf, proof) = tree.generate_proof(deposit_log.index as usize);
+
+                Deposit {
+                    proof: proof.into(),
+                    data: deposit_log.deposit_data.clone(),
+                }
+            })
+            .collect())
+    }
+
     /// Returns the number of deposits with valid signatures that have been observed up to and
     /// including the block at `block_number`.
     ///
@@ -422,6 +463,44 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn get_deposit_reuses_proof_tree() {
+        let n = 64;
+        let mut tree = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            tree.insert_log(log).expect("should add consecutive logs")
+        }
+
+        // Increasing deposit counts extend the proof tree, decreasing ones rebuild it.
+        for &deposit_count in &[8, 8, 16, 40, 12, 63] {
+            let (root, deposits) = tree
+                .get_deposits(0, deposit_count, deposit_count, TREE_DEPTH)
+                .expect("should get deposits");
+            assert_eq!(
+                root, tree.deposit_roots[deposit_count as usize],
+                "should return the deposit root at the deposit count"
+            );
+
+            for (i, deposit) in deposits.iter().enumerate() {
+                assert!(
+                    merkle_proof::verify_merkle_proof(
+                        deposit.data.tree_hash_root(),
+                        &deposit.proof[..],
+                        TREE_DEPTH + 1,
+                        i,
+                        root
+                    ),
+                    "proof should be valid"
+                );
+            }
+        }
+    }
+
     #[test]
     fn get_deposit_invalid() {
         let n = 16;
@@ -4,6 +4,7 @@ use crate::{
     deposit_cache::{DepositCache, SszDepositCache},
 };
 use parking_lot::RwLock;
+use snap::raw::{Decoder, Encoder};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use types::ChainSpec;
@@ -12,6 +13,9 @@ use types::ChainSpec;
 pub struct DepositUpdater {
     pub cache: DepositCache,
     pub last_processed_block: Option<u64>,
+    /// The span of blocks to query for logs, per request, if it has been adapted from
+    /// `Config::blocks_per_log_query` by previous updates.
+    pub blocks_per_log_query: Option<usize>,
 }
 
 impl DepositUpdater {
@@ -20,6 +24,7 @@ impl DepositUpdater {
         DepositUpdater {
             cache,
             last_processed_block: None,
+            blocks_per_log_query: None,
         }
     }
 }
@@ -42,17 +47,31 @@ impl Inner {
         }
     }
 
-    /// Encode the eth1 block and deposit cache as bytes.
+    /// Encode the eth1 block and deposit cache as snappy-compressed SSZ bytes.
     pub fn as_bytes(&self) -> Vec<u8> {
-        let ssz_eth1_cache = SszEth1Cache::from_inner(&self);
-        ssz_eth1_cache.as_ssz_bytes()
+        let ssz_bytes = SszEth1Cache::from_inner(&self).as_ssz_bytes();
+        // Compression of a `Vec` in memory cannot fail, however fall back to the uncompressed
+        // bytes rather than panicking.
+        Encoder::new().compress_vec(&ssz_bytes).unwrap_or(ssz_bytes)
     }
 
     /// Recover `Inner` given byte representation of eth1 deposit and block caches.
+    ///
+    /// Accepts both snappy-compressed and uncompressed SSZ bytes, since caches persisted by prior
+    /// versions are not compressed. The block cache is pruned after it is loaded.
     pub fn from_bytes(bytes: &[u8], config: Config, spec: ChainSpec) -> Result<Self, String> {
-        let ssz_cache = SszEth1Cache::from_ssz_bytes(bytes)
-            .map_err(|e| format!("Ssz decoding error: {:?}", e))?;
-        Ok(ssz_cache.to_inner(config, spec)?)
+        let decompressed = Decoder::new()
+            .decompress_vec(bytes)
+            .ok()
+            .and_then(|ssz_bytes| SszEth1Cache::from_ssz_bytes(&ssz_bytes).ok());
+        let ssz_cache = match decompressed {
+            Some(ssz_cache) => ssz_cache,
+            None => SszEth1Cache::from_ssz_bytes(bytes)
+                .map_err(|e| format!("Ssz decoding error: {:?}", e))?,
+        };
+        let inner = ssz_cache.to_inner(config, spec)?;
+        inner.prune_blocks();
+        Ok(inner)
     }
 
     /// Returns a reference to the specification.
@@ -85,6 +104,7 @@ impl SszEth1Cache {
             deposit_cache: RwLock::new(DepositUpdater {
                 cache: self.deposit_cache.to_deposit_cache()?,
                 last_processed_block: self.last_processed_block,
+                blocks_per_log_query: None,
             }),
             config: RwLock::new(config),
             spec,
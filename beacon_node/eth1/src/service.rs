@@ -6,15 +6,16 @@ use crate::{
     inner::{DepositUpdater, Inner},
     DepositLog,
 };
-use futures::{future::TryFutureExt, stream, stream::TryStreamExt, StreamExt};
+use futures::{future::TryFutureExt, stream, stream::TryStreamExt};
 use parking_lot::{RwLock, RwLockReadGuard};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, trace, Logger};
 use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::time::{interval_at, Duration, Instant};
-use types::ChainSpec;
+use tokio::time::{delay_for, Duration};
+use types::{ChainSpec, EthSpec, Unsigned};
 
 const STANDARD_TIMEOUT_MILLIS: u64 = 15_000;
 
@@ -93,11 +94,19 @@ pub struct Config {
     /// The interval between updates when using the `auto_update` function.
     pub auto_update_interval_millis: u64,
     /// The span of blocks we should query for logs, per request.
+    ///
+    /// This is an upper bound; the span is halved each time the eth1 node fails to respond to a
+    /// log query (e.g., due to a provider's range limits) and doubled again after each
+    /// successful update.
     pub blocks_per_log_query: usize,
     /// The maximum number of log requests per update.
     pub max_log_requests_per_update: Option<usize>,
-    /// The maximum number of log requests per update.
+    /// The maximum number of block requests per update.
     pub max_blocks_per_update: Option<usize>,
+    /// The maximum delay between updates when using the `auto_update` function.
+    ///
+    /// Each consecutive failed update doubles the delay before the next, up to this value.
+    pub max_update_backoff_millis: u64,
 }
 
 impl Default for Config {
@@ -113,10 +122,34 @@ impl Default for Config {
             blocks_per_log_query: 1_000,
             max_log_requests_per_update: None,
             max_blocks_per_update: None,
+            max_update_backoff_millis: 60_000,
         }
     }
 }
 
+impl Config {
+    /// Sets `block_cache_truncation` to the minimum number of blocks required to vote on eth1 data
+    /// (i.e., the blocks within the eth1 voting period and the two follow distances prior to it),
+    /// with a safety margin.
+    ///
+    /// Blocks older than this are not useful and are pruned from the `BlockCache`.
+    pub fn set_block_cache_truncation<E: EthSpec>(&mut self, spec: &ChainSpec) {
+        // Compute the number of eth1 blocks within an eth1 voting period.
+        let seconds_per_voting_period =
+            E::SlotsPerEth1VotingPeriod::to_u64() * spec.milliseconds_per_slot / 1000;
+        let eth1_blocks_per_voting_period = seconds_per_voting_period / spec.seconds_per_eth1_block;
+
+        // The candidate blocks for a vote lie between one and two follow distances prior to the
+        // start of the voting period.
+        let follow_distance_blocks = spec.eth1_follow_distance * 2;
+
+        // Double the total to account for variance in the eth1 block times.
+        let cache_size = (eth1_blocks_per_voting_period + follow_distance_blocks) * 2;
+
+        self.block_cache_truncation = Some(cache_size as usize);
+    }
+}
+
 /// Provides a set of Eth1 caches and async functions to update them.
 ///
 /// Stores the following caches:
@@ -320,6 +353,10 @@ impl Service {
     /// A looping future that updates the cache, then waits `config.auto_update_interval` before
     /// updating it again.
     ///
+    /// If an update fails, the delay before the next update is doubled (up to
+    /// `config.max_update_backoff_millis`) to avoid overwhelming a struggling or rate-limited
+    /// eth1 node.
+    ///
     /// ## Returns
     ///
     /// - Ok(_) if the update was successful (the cache may or may not have been modified).
@@ -328,36 +365,49 @@ impl Service {
     /// Emits logs for debugging and errors.
     pub fn auto_update(self, handle: environment::TaskExecutor) {
         let update_interval = Duration::from_millis(self.config().auto_update_interval_millis);
-
-        let mut interval = interval_at(Instant::now(), update_interval);
+        let max_backoff = std::cmp::max(
+            Duration::from_millis(self.config().max_update_backoff_millis),
+            update_interval,
+        );
 
         let update_future = async move {
-            while interval.next().await.is_some() {
-                self.do_update(update_interval).await.ok();
+            let mut delay = update_interval;
+            loop {
+                let backoff = std::cmp::min(delay * 2, max_backoff);
+                delay = match self.do_update(update_interval, backoff).await {
+                    Ok(()) => update_interval,
+                    Err(()) => backoff,
+                };
+                delay_for(delay).await;
             }
         };
 
         handle.spawn(update_future, "eth1");
     }
 
-    async fn do_update(&self, update_interval: Duration) -> Result<(), ()> {
+    async fn do_update(&self, update_interval: Duration, backoff: Duration) -> Result<(), ()> {
         let update_result = self.update().await;
         match update_result {
-            Err(e) => error!(
-                self.log,
-                "Failed to update eth1 cache";
-                "retry_millis" => update_interval.as_millis(),
-                "error" => e,
-            ),
-            Ok((deposit, block)) => debug!(
-                self.log,
-                "Updated eth1 cache";
-                "retry_millis" => update_interval.as_millis(),
-                "blocks" => format!("{:?}", block),
-                "deposits" => format!("{:?}", deposit),
-            ),
-        };
-        Ok(())
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Failed to update eth1 cache";
+                    "retry_millis" => backoff.as_millis(),
+                    "error" => e,
+                );
+                Err(())
+            }
+            Ok((deposit, block)) => {
+                debug!(
+                    self.log,
+                    "Updated eth1 cache";
+                    "retry_millis" => update_interval.as_millis(),
+                    "blocks" => format!("{:?}", block),
+                    "deposits" => format!("{:?}", deposit),
+                );
+                Ok(())
+            }
+        }
     }
 
     /// Contacts the remote eth1 node and attempts to import deposit logs up to the configured
@@ -377,7 +427,16 @@ impl Service {
         let follow_distance = self.config().follow_distance;
         let deposit_contract_address = self.config().deposit_contract_address.clone();
 
-        let blocks_per_log_query = self.config().blocks_per_log_query;
+        // Use the span from prior updates, which may have been reduced if the eth1 node failed to
+        // respond to larger queries.
+        let max_blocks_per_log_query = std::cmp::max(self.config().blocks_per_log_query, 1);
+        let blocks_per_log_query = self
+            .deposits()
+            .read()
+            .blocks_per_log_query
+            .map_or(max_blocks_per_log_query, |n| {
+                std::cmp::min(std::cmp::max(n, 1), max_blocks_per_log_query)
+            });
         let max_log_requests_per_update = self
             .config()
             .max_log_requests_per_update
@@ -407,7 +466,7 @@ impl Service {
             Vec::new()
         };
 
-        let logs: Vec<(Range<u64>, Vec<Log>)> =
+        let logs_result: Result<Vec<(Range<u64>, Vec<Log>)>, Error> =
             stream::try_unfold(block_number_chunks.into_iter(), |mut chunks| async {
                 match chunks.next() {
                     Some(chunk) => {
@@ -428,13 +487,36 @@ impl Service {
                 }
             })
             .try_collect()
-            .await?;
+            .await;
+
+        let logs = match logs_result {
+            Ok(logs) => {
+                // Grow the span back towards the configured maximum.
+                self.deposits().write().blocks_per_log_query = Some(std::cmp::min(
+                    blocks_per_log_query.saturating_mul(2),
+                    max_blocks_per_log_query,
+                ));
+                logs
+            }
+            Err(e) => {
+                let reduced_blocks_per_log_query = std::cmp::max(blocks_per_log_query / 2, 1);
+                self.deposits().write().blocks_per_log_query = Some(reduced_blocks_per_log_query);
+                debug!(
+                    self.log,
+                    "Reducing eth1 log query span";
+                    "blocks_per_log_query" => reduced_blocks_per_log_query,
+                    "error" => format!("{:?}", e),
+                );
+                return Err(e);
+            }
+        };
 
         let mut logs_imported = 0;
         for (block_range, log_chunk) in logs.iter() {
-            let mut cache = self.deposits().write();
-            log_chunk
-                .into_iter()
+            // Parse the logs (including the verification of each deposit signature) in parallel,
+            // before obtaining the write-lock on the cache.
+            let deposit_logs = log_chunk
+                .par_iter()
                 .map(|raw_log| {
                     DepositLog::from_log(&raw_log, self.inner.spec()).map_err(|error| {
                         Error::FailedToParseDepositLog {
@@ -447,7 +529,10 @@ impl Service {
                 //
                 // This costs an additional `collect`, however it enforces that no logs are
                 // imported if any one of them cannot be parsed.
-                .collect::<Result<Vec<_>, _>>()?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut cache = self.deposits().write();
+            deposit_logs
                 .into_iter()
                 .map(|deposit_log| {
                     cache
@@ -0,0 +1,16 @@
+#![no_main]
+use eth2_libp2p::{Enr, EnrExt, Eth2Enr};
+use libfuzzer_sys::fuzz_target;
+use types::MainnetEthSpec;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(enr_str) = std::str::from_utf8(data) {
+        if let Ok(enr) = enr_str.parse::<Enr>() {
+            // Exercise the eth2-specific fields which are decoded from the ENR.
+            let _ = enr.eth2();
+            let _ = enr.bitfield::<MainnetEthSpec>();
+            let _ = enr.peer_id();
+            let _ = enr.multiaddr();
+        }
+    }
+});
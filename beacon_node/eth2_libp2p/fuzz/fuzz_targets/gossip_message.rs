@@ -0,0 +1,20 @@
+#![no_main]
+use eth2_libp2p::types::{GossipEncoding, GossipKind};
+use eth2_libp2p::{GossipTopic, PubsubMessage, TopicHash};
+use libfuzzer_sys::fuzz_target;
+use types::{MainnetEthSpec, SubnetId};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((selector, data)) = data.split_first() {
+        let kind = match selector % 6 {
+            0 => GossipKind::BeaconBlock,
+            1 => GossipKind::BeaconAggregateAndProof,
+            2 => GossipKind::Attestation(SubnetId::new(0)),
+            3 => GossipKind::VoluntaryExit,
+            4 => GossipKind::ProposerSlashing,
+            _ => GossipKind::AttesterSlashing,
+        };
+        let topic: String = GossipTopic::new(kind, GossipEncoding::SSZSnappy, [0; 4]).into();
+        let _ = PubsubMessage::<MainnetEthSpec>::decode(&[TopicHash::from_raw(topic)], data);
+    }
+});
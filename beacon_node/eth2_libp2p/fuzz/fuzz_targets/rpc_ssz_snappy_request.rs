@@ -0,0 +1,18 @@
+#![no_main]
+use eth2_libp2p::rpc::{fuzzing, Protocol};
+use libfuzzer_sys::fuzz_target;
+use types::MainnetEthSpec;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((selector, data)) = data.split_first() {
+        let protocol = match selector % 6 {
+            0 => Protocol::Status,
+            1 => Protocol::Goodbye,
+            2 => Protocol::BlocksByRange,
+            3 => Protocol::BlocksByRoot,
+            4 => Protocol::Ping,
+            _ => Protocol::MetaData,
+        };
+        fuzzing::decode_ssz_snappy_requests::<MainnetEthSpec>(protocol, data);
+    }
+});
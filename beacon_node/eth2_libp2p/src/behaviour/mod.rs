@@ -2,7 +2,7 @@ use crate::peer_manager::{score::PeerAction, PeerManager, PeerManagerEvent};
 use crate::rpc::*;
 use crate::types::{GossipEncoding, GossipKind, GossipTopic};
 use crate::Eth2Enr;
-use crate::{error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, TopicHash};
+use crate::{error, metrics, Enr, NetworkConfig, NetworkGlobals, PubsubMessage, Subnet, TopicHash};
 use futures::prelude::*;
 use handler::{BehaviourHandler, BehaviourHandlerIn, BehaviourHandlerOut, DelegateIn, DelegateOut};
 use libp2p::{
@@ -34,6 +34,18 @@ mod handler;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
 
+/// The result of validating a gossipsub message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageAcceptance {
+    /// The message is valid and should be propagated to our peers.
+    Accept,
+    /// The message is not propagated, however the peer is not penalized. This is used for
+    /// messages which are late, early or duplicates, rather than invalid.
+    Ignore,
+    /// The message is invalid. It is not propagated and the peer that sent it is penalized.
+    Reject,
+}
+
 /// Builds the network behaviour that manages the core protocols of eth2.
 /// This core behaviour is managed by `Behaviour` which adds peer management to all core
 /// behaviours.
@@ -83,11 +95,15 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         let local_peer_id = local_key.public().into_peer_id();
         let behaviour_log = log.new(o!());
 
-        let identify = Identify::new(
-            "lighthouse/libp2p".into(),
-            version::version(),
-            local_key.public(),
-        );
+        let identify = if net_conf.private {
+            Identify::new("eth2/1.0.0".into(), String::new(), local_key.public())
+        } else {
+            Identify::new(
+                "lighthouse/libp2p".into(),
+                version::version(),
+                local_key.public(),
+            )
+        };
 
         let enr_fork_id = network_globals
             .local_enr()
@@ -223,11 +239,28 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
         }
     }
 
-    /// Forwards a message that is waiting in gossipsub's mcache. Messages are only propagated
-    /// once validated by the beacon chain.
-    pub fn propagate_message(&mut self, propagation_source: &PeerId, message_id: MessageId) {
-        self.gossipsub
-            .propagate_message(&message_id, propagation_source);
+    /// Informs gossipsub of the result of validating a message that is waiting in its mcache.
+    /// Messages are only propagated once accepted by the beacon chain.
+    ///
+    /// Rejected messages penalize the peer that forwarded them to us. Ignored messages are neither
+    /// propagated nor penalized.
+    pub fn report_message_validation_result(
+        &mut self,
+        propagation_source: &PeerId,
+        message_id: MessageId,
+        validation_result: MessageAcceptance,
+    ) {
+        match validation_result {
+            MessageAcceptance::Accept => {
+                self.gossipsub
+                    .propagate_message(&message_id, propagation_source);
+            }
+            MessageAcceptance::Reject => {
+                self.peer_manager
+                    .report_peer(propagation_source, PeerAction::LowToleranceError);
+            }
+            MessageAcceptance::Ignore => {}
+        }
     }
 
     /* Eth2 RPC behaviour functions */
@@ -291,7 +324,14 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
     /// Updates a subnet value to the ENR bitfield.
     ///
     /// The `value` is `true` if a subnet is being added and false otherwise.
-    pub fn update_enr_subnet(&mut self, subnet_id: SubnetId, value: bool) {
+    pub fn update_enr_subnet(&mut self, subnet: Subnet, value: bool) {
+        // the subnets advertised in the ENR are the long-lived subnets required by our validators
+        self.network_globals
+            .peers
+            .write()
+            .set_long_lived_subnet(subnet, value);
+
+        let Subnet::Attestation(subnet_id) = subnet;
         if let Err(e) = self
             .peer_manager
             .discovery_mut()
@@ -305,8 +345,8 @@ impl<TSpec: EthSpec> Behaviour<TSpec> {
 
     /// Attempts to discover new peers for a given subnet. The `min_ttl` gives the time at which we
     /// would like to retain the peers for.
-    pub fn discover_subnet_peers(&mut self, subnet_id: SubnetId, min_ttl: Option<Instant>) {
-        self.peer_manager.discover_subnet_peers(subnet_id, min_ttl)
+    pub fn discover_subnet_peers(&mut self, subnet: Subnet, min_ttl: Option<Instant>) {
+        self.peer_manager.discover_subnet_peers(subnet, min_ttl)
     }
 
     /// Updates the local ENR's "eth2" field with the latest EnrForkId.
@@ -5,11 +5,26 @@ use libp2p::gossipsub::{GossipsubConfig, GossipsubConfigBuilder, GossipsubMessag
 use libp2p::Multiaddr;
 use serde_derive::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use snap::raw::{decompress_len, Decoder};
 use std::path::PathBuf;
 use std::time::Duration;
 
 pub const GOSSIP_MAX_SIZE: usize = 1_048_576;
 
+/// The domain prepended to the decompressed data of a gossip message when computing its id.
+pub const MESSAGE_DOMAIN_VALID_SNAPPY: [u8; 4] = [1, 0, 0, 0];
+/// The domain prepended to the raw data of a gossip message which cannot be snappy-decompressed.
+pub const MESSAGE_DOMAIN_INVALID_SNAPPY: [u8; 4] = [0, 0, 0, 0];
+/// The number of bytes of the SHA256 hash used as a gossip message id.
+const MESSAGE_ID_LEN: usize = 20;
+
+/// The default interval between gossipsub heartbeats.
+pub const DEFAULT_GOSSIP_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// The default time for which gossipsub remembers the id of a seen message, in order to ignore
+/// duplicates. Messages are only valid for a couple of epochs, so remembering them for longer
+/// only costs memory.
+pub const DEFAULT_GOSSIP_DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(385);
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 /// Network configuration for lighthouse.
@@ -37,6 +52,9 @@ pub struct Config {
     pub enr_tcp_port: Option<u16>,
 
     /// Target number of connected peers.
+    pub target_peers: usize,
+
+    /// Maximum number of connected peers. Peers that dial us beyond this limit are disconnected.
     pub max_peers: usize,
 
     /// Gossipsub configuration parameters.
@@ -59,6 +77,35 @@ pub struct Config {
     /// Disables the discovery protocol from starting.
     pub disable_discovery: bool,
 
+    /// Only perform the network duties required to produce and publish blocks, ignoring
+    /// validator requests to subscribe to attestation subnets.
+    pub proposer_only: bool,
+
+    /// Subscribe to all attestation subnets, rather than only those required by the validators
+    /// attached to this node.
+    pub subscribe_all_subnets: bool,
+
+    /// Import every unaggregated attestation received on a subscribed subnet into fork choice
+    /// and the naive aggregation pool, rather than only those on subnets with an aggregator.
+    pub import_all_attestations: bool,
+
+    /// Reduce the information which could link this node to its validators, by omitting the
+    /// client version from identify, delaying published messages by a random amount and never
+    /// advertising attestation subnets in the ENR.
+    pub private: bool,
+
+    /// The maximum number of gossip unaggregated attestations waiting to be processed. Further
+    /// attestations are dropped.
+    pub attestation_queue_len: usize,
+
+    /// The maximum number of gossip aggregated attestations waiting to be processed. Further
+    /// aggregates are dropped.
+    pub aggregate_queue_len: usize,
+
+    /// Process all waiting aggregates before any unaggregated attestation, so that unaggregated
+    /// attestations are dropped first when the node falls behind.
+    pub prioritize_aggregates: bool,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
 }
@@ -79,21 +126,13 @@ impl Default for Config {
             GossipKind::AttesterSlashing,
         ];
 
-        // The function used to generate a gossipsub message id
-        // We use base64(SHA256(data)) for content addressing
-        let gossip_message_id = |message: &GossipsubMessage| {
-            MessageId(base64::encode_config(
-                &Sha256::digest(&message.data),
-                base64::URL_SAFE_NO_PAD,
-            ))
-        };
-
         // gossipsub configuration
         // Note: The topics by default are sent as plain strings. Hashes are an optional
         // parameter.
         let gs_config = GossipsubConfigBuilder::new()
             .max_transmit_size(GOSSIP_MAX_SIZE)
-            .heartbeat_interval(Duration::from_secs(1))
+            .heartbeat_interval(DEFAULT_GOSSIP_HEARTBEAT_INTERVAL)
+            .duplicate_cache_time(DEFAULT_GOSSIP_DUPLICATE_CACHE_TIME)
             .manual_propagation() // require validation before propagation
             .no_source_id()
             .message_id_fn(gossip_message_id)
@@ -122,14 +161,91 @@ impl Default for Config {
             enr_address: None,
             enr_udp_port: None,
             enr_tcp_port: None,
-            max_peers: 50,
+            target_peers: 50,
+            max_peers: 55,
             gs_config,
             discv5_config,
             boot_nodes: vec![],
             libp2p_nodes: vec![],
             client_version: version::version(),
             disable_discovery: false,
+            proposer_only: false,
+            subscribe_all_subnets: false,
+            import_all_attestations: false,
+            private: false,
+            attestation_queue_len: 4_096,
+            aggregate_queue_len: 1_024,
+            prioritize_aggregates: false,
             topics,
         }
     }
 }
+
+/// The function used to generate a gossipsub message id, as defined in the spec.
+///
+/// The id is the first 20 bytes of `SHA256(MESSAGE_DOMAIN_VALID_SNAPPY ++ decompressed_data)`, or
+/// of `SHA256(MESSAGE_DOMAIN_INVALID_SNAPPY ++ data)` if the data is not valid snappy. Hashing the
+/// uncompressed data ensures that differing compressions of the same message share an id. The id
+/// is base64 encoded, as gossipsub message ids are strings.
+pub fn gossip_message_id(message: &GossipsubMessage) -> MessageId {
+    message_id(&message.data)
+}
+
+fn message_id(data: &[u8]) -> MessageId {
+    let decompressed_data = match decompress_len(data) {
+        Ok(n) if n <= GOSSIP_MAX_SIZE => Decoder::new().decompress_vec(data).ok(),
+        _ => None,
+    };
+
+    let mut hasher = Sha256::new();
+    match decompressed_data {
+        Some(decompressed_data) => {
+            hasher.update(&MESSAGE_DOMAIN_VALID_SNAPPY);
+            hasher.update(&decompressed_data);
+        }
+        None => {
+            hasher.update(&MESSAGE_DOMAIN_INVALID_SNAPPY);
+            hasher.update(data);
+        }
+    }
+
+    MessageId(base64::encode_config(
+        &hasher.finalize()[..MESSAGE_ID_LEN],
+        base64::URL_SAFE_NO_PAD,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snap::raw::Encoder;
+
+    fn expected_id(domain: [u8; 4], data: &[u8]) -> MessageId {
+        let preimage = [&domain[..], data].concat();
+        MessageId(base64::encode_config(
+            &Sha256::digest(&preimage)[..MESSAGE_ID_LEN],
+            base64::URL_SAFE_NO_PAD,
+        ))
+    }
+
+    #[test]
+    fn message_id_of_valid_snappy() {
+        let data = vec![42; 1_024];
+        let compressed = Encoder::new().compress_vec(&data).unwrap();
+
+        assert_eq!(
+            message_id(&compressed),
+            expected_id(MESSAGE_DOMAIN_VALID_SNAPPY, &data)
+        );
+    }
+
+    #[test]
+    fn message_id_of_invalid_snappy() {
+        let data = vec![0xff; 16];
+
+        assert_eq!(
+            message_id(&data),
+            expected_id(MESSAGE_DOMAIN_INVALID_SNAPPY, &data)
+        );
+    }
+}
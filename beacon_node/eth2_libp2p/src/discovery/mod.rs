@@ -3,21 +3,20 @@ pub(crate) mod enr;
 pub mod enr_ext;
 
 // Allow external use of the lighthouse ENR builder
-pub use enr::{build_enr, CombinedKey, Eth2Enr};
+pub use enr::{build_enr, save_enr_to_disk, CombinedKey, Eth2Enr};
 pub use enr_ext::{CombinedKeyExt, EnrExt};
 pub use libp2p::core::identity::Keypair;
 
 use crate::metrics;
-use crate::{error, Enr, NetworkConfig, NetworkGlobals};
+use crate::{error, Enr, NetworkConfig, NetworkGlobals, Subnet};
 use discv5::{enr::NodeId, Discv5, Discv5Event};
 use enr::{BITFIELD_ENR_KEY, ETH2_ENR_KEY};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use libp2p::core::PeerId;
 use lru::LruCache;
-use slog::{crit, debug, info, warn};
-use ssz::{Decode, Encode};
-use ssz_types::BitVector;
+use slog::{crit, debug, info, trace, warn};
+use ssz::Encode;
 use std::{
     collections::VecDeque,
     net::SocketAddr,
@@ -60,7 +59,7 @@ pub enum DiscoveryEvent {
 enum QueryType {
     /// We are searching for subnet peers.
     Subnet {
-        subnet_id: SubnetId,
+        subnet: Subnet,
         min_ttl: Option<Instant>,
         retries: usize,
     },
@@ -250,12 +249,12 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
     }
 
     /// Processes a request to search for more peers on a subnet.
-    pub fn discover_subnet_peers(&mut self, subnet_id: SubnetId, min_ttl: Option<Instant>) {
+    pub fn discover_subnet_peers(&mut self, subnet: Subnet, min_ttl: Option<Instant>) {
         // If the discv5 service isn't running, ignore queries
         if !self.started {
             return;
         }
-        self.add_subnet_query(subnet_id, min_ttl, 0);
+        self.add_subnet_query(subnet, min_ttl, 0);
     }
 
     /// Add an ENR to the routing table of the discovery mechanism.
@@ -367,7 +366,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
     /// Adds a subnet query if one doesn't exist. If a subnet query already exists, this
     /// updates the min_ttl field.
-    fn add_subnet_query(&mut self, subnet_id: SubnetId, min_ttl: Option<Instant>, retries: usize) {
+    fn add_subnet_query(&mut self, subnet: Subnet, min_ttl: Option<Instant>, retries: usize) {
         // remove the entry and complete the query if greater than the maximum search count
         if retries > MAX_DISCOVERY_RETRY {
             debug!(
@@ -382,12 +381,12 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         let mut found = false;
         for query in self.queued_queries.iter_mut() {
             if let QueryType::Subnet {
-                subnet_id: ref mut q_subnet_id,
+                subnet: ref mut q_subnet,
                 min_ttl: ref mut q_min_ttl,
                 retries: ref mut q_retries,
             } = query
             {
-                if *q_subnet_id == subnet_id {
+                if *q_subnet == subnet {
                     if *q_min_ttl < min_ttl {
                         *q_min_ttl = min_ttl;
                     }
@@ -402,12 +401,12 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         if !found {
             // Set up the query and add it to the queue
             let query = QueryType::Subnet {
-                subnet_id,
+                subnet,
                 min_ttl,
                 retries,
             };
             // update the metrics and insert into the queue.
-            debug!(self.log, "Queuing subnet query"; "subnet" => *subnet_id, "retries" => retries);
+            debug!(self.log, "Queuing subnet query"; "subnet" => format!("{:?}", subnet), "retries" => retries);
             self.queued_queries.push_back(query);
             metrics::set_gauge(&metrics::DISCOVERY_QUEUE, self.queued_queries.len() as i64);
         }
@@ -436,12 +435,12 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                     self.start_query(QueryType::FindPeers, FIND_NODE_QUERY_CLOSEST_PEERS);
                 }
                 Some(QueryType::Subnet {
-                    subnet_id,
+                    subnet,
                     min_ttl,
                     retries,
                 }) => {
                     // This query is for searching for peers of a particular subnet
-                    self.start_subnet_query(subnet_id, min_ttl, retries);
+                    self.start_subnet_query(subnet, min_ttl, retries);
                 }
                 None => {} // Queue is empty
             }
@@ -456,19 +455,14 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         self.active_queries.len() >= MAX_CONCURRENT_QUERIES
     }
 
-    /// Runs a discovery request for a given subnet_id if one already exists.
-    fn start_subnet_query(
-        &mut self,
-        subnet_id: SubnetId,
-        min_ttl: Option<Instant>,
-        retries: usize,
-    ) {
+    /// Runs a discovery request for a given subnet if one already exists.
+    fn start_subnet_query(&mut self, subnet: Subnet, min_ttl: Option<Instant>, retries: usize) {
         // Determine if we have sufficient peers, which may make this discovery unnecessary.
         let peers_on_subnet = self
             .network_globals
             .peers
             .read()
-            .peers_on_subnet(subnet_id)
+            .peers_on_subnet(subnet)
             .count();
 
         if peers_on_subnet > TARGET_SUBNET_PEERS {
@@ -482,7 +476,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         let target_peers = TARGET_SUBNET_PEERS - peers_on_subnet;
         debug!(self.log, "Discovery query started for subnet";
-            "subnet_id" => *subnet_id,
+            "subnet" => format!("{:?}", subnet),
             "connected_peers_on_subnet" => peers_on_subnet,
             "target_subnet_peers" => TARGET_SUBNET_PEERS,
             "peers_to_find" => target_peers,
@@ -492,7 +486,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         // start the query, and update the queries map if necessary
         let query = QueryType::Subnet {
-            subnet_id,
+            subnet,
             min_ttl,
             retries,
         };
@@ -521,10 +515,10 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         // General predicate
         let predicate: Box<dyn Fn(&Enr) -> bool + Send> = match &query {
             QueryType::FindPeers => Box::new(eth2_fork_predicate),
-            QueryType::Subnet { subnet_id, .. } => {
+            QueryType::Subnet { subnet, .. } => {
                 // build the subnet predicate as a combination of the eth2_fork_predicate and the
                 // subnet predicate
-                let subnet_predicate = subnet_predicate::<TSpec>(*subnet_id, &self.log);
+                let subnet_predicate = subnet_predicate::<TSpec>(*subnet, &self.log);
                 Box::new(move |enr: &Enr| eth2_fork_predicate(enr) && subnet_predicate(enr))
             }
         };
@@ -559,23 +553,23 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                     }
                 }
                 QueryType::Subnet {
-                    subnet_id,
+                    subnet,
                     min_ttl,
                     retries,
                 } => {
                     match query_future.1 {
                         Ok(r) if r.is_empty() => {
-                            debug!(self.log, "Subnet discovery query yielded no results."; "subnet_id" => *subnet_id, "retries" => retries);
+                            debug!(self.log, "Subnet discovery query yielded no results."; "subnet" => format!("{:?}", subnet), "retries" => retries);
                         }
                         Ok(r) => {
-                            debug!(self.log, "Peer subnet discovery request completed"; "peers_found" => r.len(), "subnet_id" => *subnet_id);
+                            debug!(self.log, "Peer subnet discovery request completed"; "peers_found" => r.len(), "subnet" => format!("{:?}", subnet));
                             // A subnet query has completed. Add back to the queue, incrementing retries.
-                            self.add_subnet_query(subnet_id, min_ttl, retries + 1);
+                            self.add_subnet_query(subnet, min_ttl, retries + 1);
                             // Report the results back to the peer manager.
                             return Some((query_future.0.min_ttl(), r));
                         }
                         Err(e) => {
-                            warn!(self.log,"Subnet Discovery query failed"; "subnet_id" => *subnet_id, "error" => e.to_string());
+                            warn!(self.log,"Subnet Discovery query failed"; "subnet" => format!("{:?}", subnet), "error" => e.to_string());
                         }
                     }
                 }
@@ -14,8 +14,8 @@ pub mod rpc;
 mod service;
 pub mod types;
 
-pub use crate::types::{error, Enr, GossipTopic, NetworkGlobals, PubsubMessage};
-pub use behaviour::{BehaviourEvent, PeerRequestId, Request, Response};
+pub use crate::types::{error, Enr, GossipTopic, NetworkGlobals, PubsubMessage, Subnet};
+pub use behaviour::{BehaviourEvent, MessageAcceptance, PeerRequestId, Request, Response};
 pub use config::Config as NetworkConfig;
 pub use discovery::{CombinedKeyExt, EnrExt, Eth2Enr};
 pub use discv5;
@@ -24,6 +24,6 @@ pub use libp2p::{core::ConnectedPoint, PeerId, Swarm};
 pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
-    client::Client, score::PeerAction, PeerDB, PeerInfo, PeerSyncStatus, SyncInfo,
+    client::Client, score::PeerAction, PeerDB, PeerInfo, PeerSyncStatus, SyncInfo, SyncStats,
 };
-pub use service::{Libp2pEvent, Service, NETWORK_KEY_FILENAME};
+pub use service::{interop_keypair, load_private_key, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
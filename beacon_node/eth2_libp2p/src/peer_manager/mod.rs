@@ -4,7 +4,7 @@ pub use self::peerdb::*;
 use crate::discovery::{Discovery, DiscoveryEvent};
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
 use crate::{error, metrics};
-use crate::{Enr, EnrExt, NetworkConfig, NetworkGlobals, PeerId};
+use crate::{Enr, EnrExt, NetworkConfig, NetworkGlobals, PeerId, Subnet};
 use futures::prelude::*;
 use futures::Stream;
 use hashset_delay::HashSetDelay;
@@ -19,7 +19,7 @@ use std::{
     task::{Context, Poll},
     time::{Duration, Instant},
 };
-use types::{EthSpec, SubnetId};
+use types::EthSpec;
 
 pub use libp2p::core::{identity::Keypair, Multiaddr};
 
@@ -28,10 +28,12 @@ mod peer_info;
 mod peer_sync_status;
 mod peerdb;
 pub(crate) mod score;
+mod sync_stats;
 
 pub use peer_info::{PeerConnectionStatus::*, PeerInfo};
 pub use peer_sync_status::{PeerSyncStatus, SyncInfo};
 use score::{PeerAction, ScoreState};
+pub use sync_stats::SyncStats;
 /// The time in seconds between re-status's peers.
 const STATUS_INTERVAL: u64 = 300;
 /// The time in seconds between PING events. We do not send a ping if the other peer as PING'd us within
@@ -42,6 +44,10 @@ const PING_INTERVAL: u64 = 30;
 /// requests. This defines the interval in seconds.  
 const HEARTBEAT_INTERVAL: u64 = 30;
 
+/// The fraction of `target_peers` that should be peers we have dialed. Maintaining peers of our
+/// own choosing makes it harder for an attacker to eclipse this node by connecting to it.
+const MIN_OUTBOUND_PEERS_RATIO: f32 = 0.3;
+
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<TSpec: EthSpec> {
     /// Storage of network globals to access the `PeerDB`.
@@ -54,6 +60,8 @@ pub struct PeerManager<TSpec: EthSpec> {
     status_peers: HashSetDelay<PeerId>,
     /// The target number of peers we would like to connect to.
     target_peers: usize,
+    /// The maximum number of peers we accept connections from.
+    max_peers: usize,
     /// The discovery service.
     discovery: Discovery<TSpec>,
     /// The heartbeat interval to perform routine maintenance.
@@ -99,7 +107,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             events: SmallVec::new(),
             ping_peers: HashSetDelay::new(Duration::from_secs(PING_INTERVAL)),
             status_peers: HashSetDelay::new(Duration::from_secs(STATUS_INTERVAL)),
-            target_peers: config.max_peers, //TODO: Add support for target peers and max peers
+            target_peers: config.target_peers,
+            max_peers: config.max_peers,
             discovery,
             heartbeat,
             log: log.clone(),
@@ -206,17 +215,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     }
 
     /// A request to find peers on a given subnet.
-    pub fn discover_subnet_peers(&mut self, subnet_id: SubnetId, min_ttl: Option<Instant>) {
+    pub fn discover_subnet_peers(&mut self, subnet: Subnet, min_ttl: Option<Instant>) {
         // Extend the time to maintain peers if required.
         if let Some(min_ttl) = min_ttl {
             self.network_globals
                 .peers
                 .write()
-                .extend_peers_on_subnet(subnet_id, min_ttl);
+                .extend_peers_on_subnet(subnet, min_ttl);
         }
 
         // request the subnet query from discovery
-        self.discovery.discover_subnet_peers(subnet_id, min_ttl);
+        self.discovery.discover_subnet_peers(subnet, min_ttl);
     }
 
     /// A STATUS message has been received from a peer. This resets the status timer.
@@ -482,7 +491,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             let peer_id = enr.peer_id();
 
             // if we need more peers, attempt a connection
-            if self.network_globals.connected_or_dialing_peers() < self.target_peers
+            if self.wants_more_peers()
                 && !self
                     .network_globals
                     .peers
@@ -528,8 +537,23 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
             match connection {
                 ConnectingType::Dialing => peerdb.dialing_peer(peer_id),
-                ConnectingType::IngoingConnected => peerdb.connect_outgoing(peer_id),
-                ConnectingType::OutgoingConnected => peerdb.connect_ingoing(peer_id),
+                ConnectingType::IngoingConnected => peerdb.connect_ingoing(peer_id),
+                ConnectingType::OutgoingConnected => peerdb.connect_outgoing(peer_id),
+            }
+        }
+
+        // Reject peers that dial us once we are at our peer limit. Slots are reserved for
+        // outbound peers until we have dialed enough of them.
+        if let ConnectingType::IngoingConnected = connection {
+            let connected_peers = self.network_globals.connected_peers();
+            let outbound_peers = self.network_globals.connected_outbound_peers();
+            let reserved_slots = self.min_outbound_peers().saturating_sub(outbound_peers);
+            if connected_peers + reserved_slots > self.max_peers {
+                debug!(self.log, "Rejecting peer, too many peers"; "peer_id" => peer_id.to_string(), "connected_peers" => connected_peers, "max_peers" => self.max_peers);
+                self.events.push(PeerManagerEvent::DisconnectPeer(
+                    peer_id.clone(),
+                    GoodbyeReason::TooManyPeers,
+                ));
             }
         }
 
@@ -657,6 +681,46 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
+    /// The minimum number of connected peers that should be peers we have dialed.
+    fn min_outbound_peers(&self) -> usize {
+        (self.target_peers as f32 * MIN_OUTBOUND_PEERS_RATIO).ceil() as usize
+    }
+
+    /// Returns true if we should dial more peers. This is the case if we have fewer than
+    /// `target_peers`, or if we have dialed too few of our peers and are below `max_peers`.
+    fn wants_more_peers(&self) -> bool {
+        let peer_count = self.network_globals.connected_or_dialing_peers();
+        peer_count < self.target_peers
+            || (self.network_globals.connected_outbound_peers() < self.min_outbound_peers()
+                && peer_count < self.max_peers)
+    }
+
+    /// Disconnects peers in excess of `target_peers`, whilst retaining the minimum number of
+    /// outbound peers and at least one peer on each subnet our validators require.
+    fn prune_excess_peers(&mut self) {
+        let excess_peers = self
+            .network_globals
+            .connected_peers()
+            .saturating_sub(self.target_peers);
+        if excess_peers == 0 {
+            return;
+        }
+
+        let to_prune = self
+            .network_globals
+            .peers
+            .read()
+            .peers_to_prune(excess_peers, self.min_outbound_peers());
+
+        for peer_id in to_prune {
+            debug!(self.log, "Pruning excess peer"; "peer_id" => peer_id.to_string());
+            self.events.push(PeerManagerEvent::DisconnectPeer(
+                peer_id,
+                GoodbyeReason::TooManyPeers,
+            ));
+        }
+    }
+
     /// The Peer manager's heartbeat maintains the peer count and maintains peer reputations.
     ///
     /// It will request discovery queries if the peer count has not reached the desired number of
@@ -666,15 +730,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     fn heartbeat(&mut self) {
         // TODO: Provide a back-off time for discovery queries. I.e Queue many initially, then only
         // perform discoveries over a larger fixed interval. Perhaps one every 6 heartbeats
-        let peer_count = self.network_globals.connected_or_dialing_peers();
-        if peer_count < self.target_peers {
+        if self.wants_more_peers() {
             // If we need more peers, queue a discovery lookup.
-            debug!(self.log, "Starting a new peer discovery query"; "connected_peers" => peer_count, "target_peers" => self.target_peers);
+            debug!(self.log, "Starting a new peer discovery query";
+                "connected_peers" => self.network_globals.connected_or_dialing_peers(),
+                "outbound_peers" => self.network_globals.connected_outbound_peers(),
+                "target_peers" => self.target_peers);
             self.discovery.discover_peers();
         }
 
-        // TODO: If we have too many peers, remove peers that are not required for subnet
-        // validation.
+        // If we have too many peers, remove peers that are not required for subnet validation.
+        self.prune_excess_peers();
 
         // Updates peer's scores.
         self.update_peer_scores();
@@ -1,8 +1,9 @@
 use super::client::Client;
 use super::score::Score;
+use super::sync_stats::SyncStats;
 use super::PeerSyncStatus;
 use crate::rpc::MetaData;
-use crate::Multiaddr;
+use crate::{Multiaddr, Subnet};
 use serde::{
     ser::{SerializeStructVariant, Serializer},
     Serialize,
@@ -28,6 +29,8 @@ pub struct PeerInfo<T: EthSpec> {
     /// The current syncing state of the peer. The state may be determined after it's initial
     /// connection.
     pub sync_status: PeerSyncStatus,
+    /// The history of the peer's responses to sync batch requests.
+    pub sync_stats: SyncStats,
     /// The ENR subnet bitfield of the peer. This may be determined after it's initial
     /// connection.
     pub meta_data: Option<MetaData<T>>,
@@ -46,6 +49,7 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             connection_status: Default::default(),
             listening_addresses: vec![],
             sync_status: PeerSyncStatus::Unknown,
+            sync_stats: SyncStats::default(),
             meta_data: None,
             min_ttl: None,
         }
@@ -53,8 +57,9 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
 }
 
 impl<T: EthSpec> PeerInfo<T> {
-    /// Returns if the peer is subscribed to a given `SubnetId`
-    pub fn on_subnet(&self, subnet_id: SubnetId) -> bool {
+    /// Returns if the peer is subscribed to a given `Subnet`
+    pub fn on_subnet(&self, subnet: Subnet) -> bool {
+        let Subnet::Attestation(subnet_id) = subnet;
         if let Some(meta_data) = &self.meta_data {
             return meta_data
                 .attnets
@@ -63,6 +68,16 @@ impl<T: EthSpec> PeerInfo<T> {
         }
         false
     }
+
+    /// Returns the long-lived attestation subnets the peer is subscribed to, according to its
+    /// `MetaData`.
+    pub fn long_lived_subnets(&self) -> impl Iterator<Item = Subnet> + '_ {
+        self.meta_data
+            .iter()
+            .flat_map(|meta_data| meta_data.attnets.iter().enumerate())
+            .filter(|(_, subscribed)| *subscribed)
+            .map(|(subnet_id, _)| Subnet::Attestation(SubnetId::new(subnet_id as u64)))
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -154,6 +169,14 @@ impl PeerConnectionStatus {
         }
     }
 
+    /// Checks if the status is connected and at least one of the connections was dialed by us.
+    pub fn is_outbound(&self) -> bool {
+        match self {
+            PeerConnectionStatus::Connected { n_out, .. } => *n_out > 0,
+            _ => false,
+        }
+    }
+
     /// Checks if the status is connected.
     pub fn is_dialing(&self) -> bool {
         match self {
@@ -51,6 +51,16 @@ impl PeerSyncStatus {
         }
     }
 
+    /// Returns the head slot claimed by the peer, if known.
+    pub fn head_slot(&self) -> Option<Slot> {
+        match self {
+            PeerSyncStatus::Synced { info }
+            | PeerSyncStatus::Advanced { info }
+            | PeerSyncStatus::Behind { info } => Some(info.status_head_slot),
+            PeerSyncStatus::Unknown => None,
+        }
+    }
+
     /// Updates the sync state given a fully synced peer.
     /// Returns true if the state has changed.
     pub fn update_synced(&mut self, info: SyncInfo) -> bool {
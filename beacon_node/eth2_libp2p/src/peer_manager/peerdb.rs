@@ -2,11 +2,12 @@ use super::peer_info::{PeerConnectionStatus, PeerInfo};
 use super::peer_sync_status::PeerSyncStatus;
 use super::score::Score;
 use crate::rpc::methods::MetaData;
-use crate::PeerId;
+use crate::{PeerId, Subnet};
 use slog::{crit, debug, trace, warn};
-use std::collections::HashMap;
-use std::time::Instant;
-use types::{EthSpec, SubnetId};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use types::EthSpec;
 
 /// Max number of disconnected nodes to remember.
 const MAX_DC_PEERS: usize = 100;
@@ -21,6 +22,11 @@ pub struct PeerDB<TSpec: EthSpec> {
     disconnected_peers: usize,
     /// The number of banned peers in the database.
     banned_peers: usize,
+    /// The long-lived subnets our validators are subscribed to.
+    long_lived_subnets: HashSet<Subnet>,
+    /// The subnets our validators require peers on for a limited time, mapped to the time until
+    /// which they are required.
+    short_lived_subnets: HashMap<Subnet, Instant>,
     /// PeerDB's logger
     log: slog::Logger,
 }
@@ -31,6 +37,8 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             log: log.clone(),
             disconnected_peers: 0,
             banned_peers: 0,
+            long_lived_subnets: HashSet::new(),
+            short_lived_subnets: HashMap::new(),
             peers: HashMap::new(),
         }
     }
@@ -119,6 +127,14 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map(|(peer_id, _)| peer_id)
     }
 
+    /// Gives the ids of all known connected peers which we have dialed.
+    pub fn connected_outbound_peer_ids(&self) -> impl Iterator<Item = &PeerId> {
+        self.peers
+            .iter()
+            .filter(|(_, info)| info.connection_status.is_outbound())
+            .map(|(peer_id, _)| peer_id)
+    }
+
     /// Connected or dialing peers
     pub fn connected_or_dialing_peers(&self) -> impl Iterator<Item = &PeerId> {
         self.peers
@@ -143,11 +159,11 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
     }
 
     /// Gives an iterator of all peers on a given subnet.
-    pub fn peers_on_subnet(&self, subnet_id: SubnetId) -> impl Iterator<Item = &PeerId> {
+    pub fn peers_on_subnet(&self, subnet: Subnet) -> impl Iterator<Item = &PeerId> {
         self.peers
             .iter()
             .filter(move |(_, info)| {
-                info.connection_status.is_connected() && info.on_subnet(subnet_id)
+                info.connection_status.is_connected() && info.on_subnet(subnet)
             })
             .map(|(peer_id, _)| peer_id)
     }
@@ -195,6 +211,125 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map(|(id, _)| id)
     }
 
+    /// Returns `peer_ids` ordered by their usefulness for downloading blocks, best first.
+    ///
+    /// Peers are ordered by the proportion of their batches which were valid, then by their
+    /// average response time and then by their claimed head slot. Quarantined peers are omitted,
+    /// unless every peer is quarantined in which case all peers are returned so that sync can
+    /// progress.
+    pub fn best_sync_peers<'a>(&self, peer_ids: impl Iterator<Item = &'a PeerId>) -> Vec<PeerId> {
+        let mut peers = peer_ids
+            .map(|peer_id| (peer_id, self.peers.get(peer_id)))
+            .collect::<Vec<_>>();
+
+        let is_quarantined = |info: Option<&PeerInfo<TSpec>>| {
+            info.map_or(false, |info| info.sync_stats.is_quarantined())
+        };
+        if peers.iter().any(|(_, info)| !is_quarantined(*info)) {
+            peers.retain(|(_, info)| !is_quarantined(*info));
+        }
+
+        peers.sort_by(|(_, a), (_, b)| {
+            let success_rate = |info: &Option<&PeerInfo<TSpec>>| {
+                info.map_or(0.0, |info| info.sync_stats.success_rate())
+            };
+            let response_time = |info: &Option<&PeerInfo<TSpec>>| {
+                info.and_then(|info| info.sync_stats.response_time)
+                    .unwrap_or_else(|| Duration::from_secs(u64::max_value()))
+            };
+            let head_slot = |info: &Option<&PeerInfo<TSpec>>| {
+                info.and_then(|info| info.sync_status.head_slot())
+            };
+
+            success_rate(b)
+                .partial_cmp(&success_rate(a))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| response_time(a).cmp(&response_time(b)))
+                .then_with(|| head_slot(b).cmp(&head_slot(a)))
+        });
+
+        peers
+            .into_iter()
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect()
+    }
+
+    /// Returns true if our validators currently require peers on `subnet`.
+    pub fn is_required_subnet(&self, subnet: Subnet) -> bool {
+        self.long_lived_subnets.contains(&subnet)
+            || self
+                .short_lived_subnets
+                .get(&subnet)
+                .map_or(false, |until| *until > Instant::now())
+    }
+
+    /// Selects up to `count` connected peers to disconnect, whilst retaining at least
+    /// `min_outbound_peers` peers that we have dialed.
+    ///
+    /// Peers that dialed us are selected before peers we have dialed. Peers are then selected by
+    /// how well their subnets are covered by the remaining peers, so that peers on over-covered
+    /// subnets are pruned first, and then by lowest score. A peer is never selected if it is the
+    /// last remaining peer on a subnet our validators require, or if it is required until its
+    /// `min_ttl`.
+    pub fn peers_to_prune(&self, count: usize, min_outbound_peers: usize) -> Vec<PeerId> {
+        // Count the peers on each subnet.
+        let mut subnet_peers = HashMap::new();
+        for (_, info) in self.connected_peers() {
+            for subnet in info.long_lived_subnets() {
+                *subnet_peers.entry(subnet).or_insert(0) += 1;
+            }
+        }
+
+        let now = Instant::now();
+        let mut candidates = self
+            .connected_peers()
+            .filter(|(_, info)| info.min_ttl.map_or(true, |min_ttl| min_ttl <= now))
+            .collect::<Vec<_>>();
+        let mut outbound_peers = self.connected_outbound_peer_ids().count();
+
+        let mut to_prune = Vec::new();
+        while to_prune.len() < count {
+            let next = candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, info))| {
+                    (!info.connection_status.is_outbound() || outbound_peers > min_outbound_peers)
+                        && info.long_lived_subnets().all(|subnet| {
+                            !self.is_required_subnet(subnet)
+                                || subnet_peers.get(&subnet).copied().unwrap_or(0) > 1
+                        })
+                })
+                .min_by(|(_, (_, a)), (_, (_, b))| {
+                    a.connection_status
+                        .is_outbound()
+                        .cmp(&b.connection_status.is_outbound())
+                        .then_with(|| {
+                            subnet_coverage(b, &subnet_peers)
+                                .cmp(&subnet_coverage(a, &subnet_peers))
+                        })
+                        .then_with(|| a.score.cmp(&b.score))
+                })
+                .map(|(index, _)| index);
+
+            let (peer_id, info) = match next {
+                Some(index) => candidates.swap_remove(index),
+                None => break,
+            };
+
+            if info.connection_status.is_outbound() {
+                outbound_peers -= 1;
+            }
+            for subnet in info.long_lived_subnets() {
+                if let Some(peers) = subnet_peers.get_mut(&subnet) {
+                    *peers -= 1;
+                }
+            }
+            to_prune.push(peer_id.clone());
+        }
+
+        to_prune
+    }
+
     /// Returns the peer's connection status. Returns unknown if the peer is not in the DB.
     pub fn connection_status(&self, peer_id: &PeerId) -> Option<PeerConnectionStatus> {
         self.peer_info(peer_id)
@@ -234,13 +369,31 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
+    /// Records whether our validators are subscribed to the long-lived `subnet`.
+    pub fn set_long_lived_subnet(&mut self, subnet: Subnet, subscribed: bool) {
+        if subscribed {
+            self.long_lived_subnets.insert(subnet);
+        } else {
+            self.long_lived_subnets.remove(&subnet);
+        }
+    }
+
     /// Extends the ttl of all peers on the given subnet that have a shorter
     /// min_ttl than what's given.
-    pub fn extend_peers_on_subnet(&mut self, subnet_id: SubnetId, min_ttl: Instant) {
+    ///
+    /// The subnet is considered to be required by our validators until `min_ttl`.
+    pub fn extend_peers_on_subnet(&mut self, subnet: Subnet, min_ttl: Instant) {
+        let now = Instant::now();
+        self.short_lived_subnets.retain(|_, until| *until > now);
+        let until = self.short_lived_subnets.entry(subnet).or_insert(min_ttl);
+        if *until < min_ttl {
+            *until = min_ttl;
+        }
+
         let log = &self.log;
         self.peers.iter_mut()
             .filter(move |(_, info)| {
-                info.connection_status.is_connected() && info.on_subnet(subnet_id)
+                info.connection_status.is_connected() && info.on_subnet(subnet)
             })
             .for_each(|(peer_id,info)| {
                 if info.min_ttl.is_none() || Some(min_ttl) > info.min_ttl {
@@ -398,11 +551,21 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
     }
 }
 
+/// Returns the number of peers on the least-covered subnet of the peer, or `usize::max_value()` if
+/// the peer is not on any subnets.
+fn subnet_coverage<T: EthSpec>(info: &PeerInfo<T>, subnet_peers: &HashMap<Subnet, usize>) -> usize {
+    info.long_lived_subnets()
+        .map(|subnet| subnet_peers.get(&subnet).copied().unwrap_or(0))
+        .min()
+        .unwrap_or_else(usize::max_value)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::SyncStats;
     use super::*;
     use slog::{o, Drain};
-    use types::MinimalEthSpec;
+    use types::{MinimalEthSpec, SubnetId};
     type M = MinimalEthSpec;
 
     pub fn build_log(level: slog::Level, enabled: bool) -> slog::Logger {
@@ -457,6 +620,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_connected_outbound_peers() {
+        let mut pdb = get_db();
+
+        let inbound = PeerId::random();
+        let outbound = PeerId::random();
+        let both = PeerId::random();
+        pdb.connect_ingoing(&inbound);
+        pdb.connect_outgoing(&outbound);
+        pdb.connect_ingoing(&both);
+        pdb.connect_outgoing(&both);
+
+        let mut outbound_peers = pdb.connected_outbound_peer_ids().collect::<Vec<_>>();
+        outbound_peers.sort_by_key(|peer_id| peer_id.to_base58());
+        let mut expected = vec![&outbound, &both];
+        expected.sort_by_key(|peer_id| peer_id.to_base58());
+        assert_eq!(outbound_peers, expected);
+
+        pdb.disconnect(&outbound);
+        assert_eq!(pdb.connected_outbound_peer_ids().count(), 1);
+    }
+
+    fn add_subnets(db: &mut PeerDB<M>, peer_id: &PeerId, subnets: &[usize]) {
+        let mut attnets = crate::types::EnrBitfield::<M>::new();
+        for subnet in subnets {
+            attnets.set(*subnet, true).unwrap();
+        }
+        db.add_metadata(
+            peer_id,
+            MetaData {
+                seq_number: 0,
+                attnets,
+            },
+        );
+    }
+
+    #[test]
+    fn test_peers_to_prune_preserves_subnet_coverage() {
+        let mut pdb = get_db();
+
+        let p0 = PeerId::random();
+        let p1 = PeerId::random();
+        let p2 = PeerId::random();
+        let p3 = PeerId::random();
+        for peer_id in &[&p0, &p1, &p2, &p3] {
+            pdb.connect_ingoing(peer_id);
+        }
+        // p0 is the only peer on subnet 1, p1 and p2 share subnet 2 and p3 is the only peer on
+        // subnet 3, which our validators do not require.
+        add_subnets(&mut pdb, &p0, &[1]);
+        add_subnets(&mut pdb, &p1, &[2]);
+        add_subnets(&mut pdb, &p2, &[2]);
+        add_subnets(&mut pdb, &p3, &[3]);
+        add_score(&mut pdb, &p1, -10.0);
+        for subnet in &[1, 2] {
+            pdb.set_long_lived_subnet(Subnet::Attestation(SubnetId::new(*subnet)), true);
+        }
+
+        // the lowest scoring of the two peers on the over-covered subnet is pruned first, then
+        // the peer on the unrequired subnet
+        assert_eq!(pdb.peers_to_prune(2, 0), vec![p1.clone(), p3.clone()]);
+
+        // the last peers on required subnets are never pruned
+        let to_prune = pdb.peers_to_prune(4, 0);
+        assert_eq!(to_prune.len(), 2);
+        assert!(!to_prune.contains(&p0));
+
+        // outbound peers are retained
+        pdb.connect_outgoing(&p3);
+        assert_eq!(pdb.peers_to_prune(4, 1), vec![p1]);
+    }
+
     #[test]
     fn test_disconnected_are_bounded() {
         let mut pdb = get_db();
@@ -513,6 +748,40 @@ mod tests {
         assert_eq!(vec![&p1, &p0, &p2], best_peers);
     }
 
+    #[test]
+    fn test_best_sync_peers() {
+        let mut pdb = get_db();
+
+        let p0 = PeerId::random();
+        let p1 = PeerId::random();
+        let p2 = PeerId::random();
+        let p3 = PeerId::random();
+        for peer_id in &[&p0, &p1, &p2, &p3] {
+            pdb.connect_ingoing(peer_id);
+        }
+
+        fn sync_stats<'a>(pdb: &'a mut PeerDB<M>, peer_id: &PeerId) -> &'a mut SyncStats {
+            &mut pdb.peer_info_mut(peer_id).unwrap().sync_stats
+        }
+        sync_stats(&mut pdb, &p0).record_valid_batch();
+        sync_stats(&mut pdb, &p0).record_response_time(Duration::from_secs(2));
+        sync_stats(&mut pdb, &p1).record_valid_batch();
+        sync_stats(&mut pdb, &p1).record_response_time(Duration::from_secs(1));
+        sync_stats(&mut pdb, &p2).record_invalid_batch();
+        for _ in 0..3 {
+            sync_stats(&mut pdb, &p3).record_invalid_batch();
+        }
+
+        let peers = vec![p0.clone(), p1.clone(), p2.clone(), p3.clone()];
+        assert_eq!(
+            pdb.best_sync_peers(peers.iter()),
+            vec![p1.clone(), p0.clone(), p2.clone()]
+        );
+
+        // the quarantined peer is used if it is the only peer
+        assert_eq!(pdb.best_sync_peers(std::iter::once(&p3)), vec![p3]);
+    }
+
     #[test]
     fn test_the_best_peer() {
         let mut pdb = get_db();
@@ -0,0 +1,109 @@
+//! Tracks how useful a peer has been when downloading blocks during sync.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// The number of consecutive invalid batches after which a peer is quarantined.
+const QUARANTINE_FAILURE_THRESHOLD: u8 = 3;
+/// The duration for which a quarantined peer is not assigned batches, unless no other peers are
+/// available.
+const QUARANTINE_DURATION: Duration = Duration::from_secs(300);
+/// The weight of previous responses in the response time average, out of
+/// `RESPONSE_TIME_WEIGHT + 1`.
+const RESPONSE_TIME_WEIGHT: u32 = 3;
+
+#[derive(Clone, Debug, Default, Serialize)]
+/// The history of a peer's responses to sync batch requests.
+pub struct SyncStats {
+    /// The number of batches from this peer that have been successfully processed.
+    pub valid_batches: u64,
+    /// The number of batches from this peer that contained invalid blocks.
+    pub invalid_batches: u64,
+    /// The number of invalid batches since the last valid batch.
+    consecutive_invalid_batches: u8,
+    /// A moving average of the time taken by this peer to respond to a batch request.
+    pub response_time: Option<Duration>,
+    /// The peer is not assigned batches until this time.
+    #[serde(skip)]
+    quarantined_until: Option<Instant>,
+}
+
+impl SyncStats {
+    /// Records the time taken by the peer to respond to a batch request.
+    pub fn record_response_time(&mut self, response_time: Duration) {
+        self.response_time = Some(match self.response_time {
+            Some(average) => {
+                (average * RESPONSE_TIME_WEIGHT + response_time) / (RESPONSE_TIME_WEIGHT + 1)
+            }
+            None => response_time,
+        });
+    }
+
+    /// Records that a batch from the peer has been successfully processed.
+    pub fn record_valid_batch(&mut self) {
+        self.valid_batches += 1;
+        self.consecutive_invalid_batches = 0;
+        self.quarantined_until = None;
+    }
+
+    /// Records that a batch from the peer contained invalid blocks, quarantining the peer if it has
+    /// sent `QUARANTINE_FAILURE_THRESHOLD` invalid batches in a row.
+    pub fn record_invalid_batch(&mut self) {
+        self.invalid_batches += 1;
+        self.consecutive_invalid_batches = self.consecutive_invalid_batches.saturating_add(1);
+        if self.consecutive_invalid_batches >= QUARANTINE_FAILURE_THRESHOLD {
+            self.quarantined_until = Some(Instant::now() + QUARANTINE_DURATION);
+        }
+    }
+
+    /// Returns true if the peer should not be assigned batches whilst other peers are available.
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_until
+            .map_or(false, |until| until > Instant::now())
+    }
+
+    /// The proportion of the peer's batches that were valid. Peers without any history are
+    /// considered to have a success rate of one half.
+    pub fn success_rate(&self) -> f64 {
+        (self.valid_batches as f64 + 1.0)
+            / ((self.valid_batches + self.invalid_batches) as f64 + 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarantined_after_consecutive_invalid_batches() {
+        let mut stats = SyncStats::default();
+
+        for _ in 0..QUARANTINE_FAILURE_THRESHOLD - 1 {
+            stats.record_invalid_batch();
+        }
+        assert!(!stats.is_quarantined());
+
+        stats.record_valid_batch();
+        for _ in 0..QUARANTINE_FAILURE_THRESHOLD - 1 {
+            stats.record_invalid_batch();
+        }
+        assert!(!stats.is_quarantined());
+
+        stats.record_invalid_batch();
+        assert!(stats.is_quarantined());
+
+        stats.record_valid_batch();
+        assert!(!stats.is_quarantined());
+    }
+
+    #[test]
+    fn response_time_average() {
+        let mut stats = SyncStats::default();
+
+        stats.record_response_time(Duration::from_millis(400));
+        assert_eq!(stats.response_time, Some(Duration::from_millis(400)));
+
+        stats.record_response_time(Duration::from_millis(800));
+        assert_eq!(stats.response_time, Some(Duration::from_millis(500)));
+    }
+}
@@ -111,17 +111,24 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
 
         let length = self.len.expect("length should be Some");
 
-        // Should not attempt to decode rpc chunks with length > max_packet_size
-        if length > self.max_packet_size {
+        // Should not attempt to decode rpc chunks with length > max_packet_size or a length that
+        // is out of bounds for the expected ssz type. The length is attacker-controlled, so this
+        // must be checked before allocating the decode buffer.
+        if length > self.max_packet_size
+            || self.protocol.rpc_request_limits().is_out_of_bounds(length)
+        {
             return Err(RPCError::InvalidData);
         }
-        let mut reader = FrameDecoder::new(Cursor::new(&src));
+        // A valid snappy stream of `length` bytes is never longer than `max_compressed_len`.
+        let max_compressed_len = snap::raw::max_compress_len(length) as u64;
+        let limit_reader = Cursor::new(&src[..]).take(max_compressed_len);
+        let mut reader = FrameDecoder::new(limit_reader);
         let mut decoded_buffer = vec![0; length];
 
         match reader.read_exact(&mut decoded_buffer) {
             Ok(()) => {
                 // `n` is how many bytes the reader read in the compressed stream
-                let n = reader.get_ref().position();
+                let n = reader.get_ref().get_ref().position();
                 self.len = None;
                 let _read_bytes = src.split_to(n as usize);
                 match self.protocol.message_name {
@@ -195,14 +202,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyInboundCodec<TSpec> {
                     },
                 }
             }
-            Err(e) => match e.kind() {
-                // Haven't received enough bytes to decode yet
-                // TODO: check if this is the only Error variant where we return `Ok(None)`
-                ErrorKind::UnexpectedEof => {
-                    return Ok(None);
-                }
-                _ => return Err(e).map_err(RPCError::from),
-            },
+            Err(e) => handle_error(e, reader.get_ref().get_ref().position(), max_compressed_len),
         }
     }
 }
@@ -291,16 +291,26 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
 
         let length = self.len.expect("length should be Some");
 
-        // Should not attempt to decode rpc chunks with length > max_packet_size
-        if length > self.max_packet_size {
+        // Should not attempt to decode rpc chunks with length > max_packet_size or a length that
+        // is out of bounds for the expected ssz type. The length is attacker-controlled, so this
+        // must be checked before allocating the decode buffer.
+        if length > self.max_packet_size
+            || self
+                .protocol
+                .rpc_response_limits::<TSpec>()
+                .is_out_of_bounds(length)
+        {
             return Err(RPCError::InvalidData);
         }
-        let mut reader = FrameDecoder::new(Cursor::new(&src));
+        // A valid snappy stream of `length` bytes is never longer than `max_compressed_len`.
+        let max_compressed_len = snap::raw::max_compress_len(length) as u64;
+        let limit_reader = Cursor::new(&src[..]).take(max_compressed_len);
+        let mut reader = FrameDecoder::new(limit_reader);
         let mut decoded_buffer = vec![0; length];
         match reader.read_exact(&mut decoded_buffer) {
             Ok(()) => {
                 // `n` is how many bytes the reader read in the compressed stream
-                let n = reader.get_ref().position();
+                let n = reader.get_ref().get_ref().position();
                 self.len = None;
                 let _read_byts = src.split_to(n as usize);
                 match self.protocol.message_name {
@@ -367,14 +377,7 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                     },
                 }
             }
-            Err(e) => match e.kind() {
-                // Haven't received enough bytes to decode yet
-                // TODO: check if this is the only Error variant where we return `Ok(None)`
-                ErrorKind::UnexpectedEof => {
-                    return Ok(None);
-                }
-                _ => return Err(e).map_err(RPCError::from),
-            },
+            Err(e) => handle_error(e, reader.get_ref().get_ref().position(), max_compressed_len),
         }
     }
 }
@@ -399,26 +402,161 @@ impl<TSpec: EthSpec> OutboundCodec<RPCRequest<TSpec>> for SSZSnappyOutboundCodec
         if length > self.max_packet_size {
             return Err(RPCError::InvalidData);
         }
-        let mut reader = FrameDecoder::new(Cursor::new(&src));
+        // A valid snappy stream of `length` bytes is never longer than `max_compressed_len`.
+        let max_compressed_len = snap::raw::max_compress_len(length) as u64;
+        let limit_reader = Cursor::new(&src[..]).take(max_compressed_len);
+        let mut reader = FrameDecoder::new(limit_reader);
         let mut decoded_buffer = vec![0; length];
         match reader.read_exact(&mut decoded_buffer) {
             Ok(()) => {
                 // `n` is how many bytes the reader read in the compressed stream
-                let n = reader.get_ref().position();
+                let n = reader.get_ref().get_ref().position();
                 self.len = None;
                 let _read_bytes = src.split_to(n as usize);
                 Ok(Some(
                     String::from_utf8_lossy(&<Vec<u8>>::from_ssz_bytes(&decoded_buffer)?).into(),
                 ))
             }
-            Err(e) => match e.kind() {
+            Err(e) => handle_error(e, reader.get_ref().get_ref().position(), max_compressed_len),
+        }
+    }
+}
+
+/// Handles an error from reading an ssz_snappy chunk.
+///
+/// An `UnexpectedEof` error usually means that more bytes are yet to arrive. However, if the
+/// snappy decoder has already read `max_compressed_len` bytes without filling the decode buffer,
+/// the peer is padding the stream and no amount of further bytes will make it valid.
+fn handle_error<T>(
+    err: std::io::Error,
+    num_bytes: u64,
+    max_compressed_len: u64,
+) -> Result<Option<T>, RPCError> {
+    match err.kind() {
+        ErrorKind::UnexpectedEof => {
+            if num_bytes >= max_compressed_len {
+                Err(RPCError::InvalidData)
+            } else {
                 // Haven't received enough bytes to decode yet
-                // TODO: check if this is the only Error variant where we return `Ok(None)`
-                ErrorKind::UnexpectedEof => {
-                    return Ok(None);
-                }
-                _ => return Err(e).map_err(RPCError::from),
-            },
+                Ok(None)
+            }
+        }
+        _ => Err(err).map_err(RPCError::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::protocol::MAX_RPC_SIZE;
+    use types::{Epoch, Hash256, MainnetEthSpec, Slot};
+
+    type Spec = MainnetEthSpec;
+
+    /// The snappy frame format stream identifier.
+    const STREAM_IDENTIFIER: [u8; 10] =
+        [0xff, 0x06, 0x00, 0x00, 0x73, 0x4e, 0x61, 0x50, 0x70, 0x59];
+
+    fn status_protocol() -> ProtocolId {
+        ProtocolId::new(Protocol::Status, Version::V1, Encoding::SSZSnappy)
+    }
+
+    fn status_message() -> StatusMessage {
+        StatusMessage {
+            fork_digest: [0; 4],
+            finalized_root: Hash256::zero(),
+            finalized_epoch: Epoch::new(1),
+            head_root: Hash256::zero(),
+            head_slot: Slot::new(32),
+        }
+    }
+
+    /// Returns `length` encoded as an unsigned varint.
+    fn length_prefix(length: usize) -> BytesMut {
+        let mut buf = BytesMut::new();
+        Uvi::<usize>::default()
+            .encode(length, &mut buf)
+            .expect("should encode length");
+        buf
+    }
+
+    #[test]
+    fn status_request_round_trip() {
+        let mut buf = BytesMut::new();
+        SSZSnappyOutboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE)
+            .encode(RPCRequest::Status(status_message()), &mut buf)
+            .expect("should encode status");
+
+        let decoded = SSZSnappyInboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE)
+            .decode(&mut buf)
+            .expect("should decode status");
+
+        match decoded {
+            Some(RPCRequest::Status(status)) => assert_eq!(status, status_message()),
+            other => panic!("unexpected decode result: {:?}", other),
+        }
+        assert!(buf.is_empty(), "should consume the entire chunk");
+    }
+
+    #[test]
+    fn rejects_length_above_max_packet_size() {
+        let mut buf = length_prefix(MAX_RPC_SIZE + 1);
+        buf.extend_from_slice(&STREAM_IDENTIFIER);
+
+        let mut codec = SSZSnappyOutboundCodec::<Spec>::new(
+            ProtocolId::new(Protocol::BlocksByRange, Version::V1, Encoding::SSZSnappy),
+            MAX_RPC_SIZE,
+        );
+        assert!(matches!(codec.decode(&mut buf), Err(RPCError::InvalidData)));
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_length() {
+        let status_len = <StatusMessage as Encode>::ssz_fixed_len();
+
+        for length in &[0, status_len - 1, status_len + 1] {
+            let mut buf = length_prefix(*length);
+            buf.extend_from_slice(&STREAM_IDENTIFIER);
+
+            let mut codec = SSZSnappyInboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE);
+            assert!(
+                matches!(codec.decode(&mut buf), Err(RPCError::InvalidData)),
+                "should reject length {}",
+                length
+            );
         }
     }
+
+    #[test]
+    fn rejects_padded_compressed_stream() {
+        let status_len = <StatusMessage as Encode>::ssz_fixed_len();
+        let max_compressed_len = snap::raw::max_compress_len(status_len);
+
+        // A snappy padding chunk (type `0xfe`) longer than any valid compressed status message.
+        let padding_len = max_compressed_len * 2;
+        let mut buf = length_prefix(status_len);
+        buf.extend_from_slice(&STREAM_IDENTIFIER);
+        buf.extend_from_slice(&[
+            0xfe,
+            padding_len as u8,
+            (padding_len >> 8) as u8,
+            (padding_len >> 16) as u8,
+        ]);
+        buf.extend_from_slice(&vec![0; padding_len]);
+
+        let mut codec = SSZSnappyInboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE);
+        assert!(matches!(codec.decode(&mut buf), Err(RPCError::InvalidData)));
+    }
+
+    #[test]
+    fn waits_for_truncated_stream() {
+        let mut buf = BytesMut::new();
+        SSZSnappyOutboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE)
+            .encode(RPCRequest::Status(status_message()), &mut buf)
+            .expect("should encode status");
+        let mut truncated = BytesMut::from(&buf[..buf.len() - 1]);
+
+        let mut codec = SSZSnappyInboundCodec::<Spec>::new(status_protocol(), MAX_RPC_SIZE);
+        assert!(matches!(codec.decode(&mut truncated), Ok(None)));
+    }
 }
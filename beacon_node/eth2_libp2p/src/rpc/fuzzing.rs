@@ -0,0 +1,43 @@
+//! Entry points for fuzzing the RPC codecs, which are otherwise private to this crate.
+//!
+//! Only available with the `fuzzing` feature. See the `fuzz` directory of this crate for the fuzz
+//! targets.
+use crate::rpc::codec::{
+    base::{BaseInboundCodec, BaseOutboundCodec},
+    ssz_snappy::{SSZSnappyInboundCodec, SSZSnappyOutboundCodec},
+};
+use crate::rpc::protocol::{Encoding, Protocol, ProtocolId, Version, MAX_RPC_SIZE};
+use libp2p::bytes::BytesMut;
+use tokio_util::codec::Decoder;
+use types::EthSpec;
+
+/// Decodes `data` as the bytes received on an inbound ssz_snappy substream of `protocol`,
+/// returning the number of requests decoded before the codec errored or required more bytes.
+pub fn decode_ssz_snappy_requests<T: EthSpec>(protocol: Protocol, data: &[u8]) -> usize {
+    let protocol_id = ProtocolId::new(protocol, Version::V1, Encoding::SSZSnappy);
+    let mut codec =
+        BaseInboundCodec::new(SSZSnappyInboundCodec::<T>::new(protocol_id, MAX_RPC_SIZE));
+    let mut src = BytesMut::from(data);
+
+    let mut decoded = 0;
+    while let Ok(Some(_)) = codec.decode(&mut src) {
+        decoded += 1;
+    }
+    decoded
+}
+
+/// Decodes `data` as the bytes received on an outbound ssz_snappy substream of `protocol`,
+/// returning the number of response chunks decoded before the codec errored or required more
+/// bytes.
+pub fn decode_ssz_snappy_responses<T: EthSpec>(protocol: Protocol, data: &[u8]) -> usize {
+    let protocol_id = ProtocolId::new(protocol, Version::V1, Encoding::SSZSnappy);
+    let mut codec =
+        BaseOutboundCodec::new(SSZSnappyOutboundCodec::<T>::new(protocol_id, MAX_RPC_SIZE));
+    let mut src = BytesMut::from(data);
+
+    let mut decoded = 0;
+    while let Ok(Some(_)) = codec.decode(&mut src) {
+        decoded += 1;
+    }
+    decoded
+}
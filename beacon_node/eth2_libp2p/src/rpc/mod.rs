@@ -28,6 +28,8 @@ pub use methods::{
 pub use protocol::{Protocol, RPCError};
 
 pub(crate) mod codec;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
 mod handler;
 pub mod methods;
 mod protocol;
@@ -58,7 +58,7 @@ lazy_static! {
 }
 
 /// The maximum bytes that can be sent across the RPC.
-const MAX_RPC_SIZE: usize = 1_048_576; // 1M
+pub(crate) const MAX_RPC_SIZE: usize = 1_048_576; // 1M
 /// The protocol prefix the RPC protocol id.
 const PROTOCOL_PREFIX: &str = "/eth2/beacon_chain/req";
 /// Time allowed for the first byte of a request to arrive before we time out (Time To First Byte).
@@ -192,6 +192,61 @@ impl ProtocolId {
     }
 }
 
+/// The bounds on the length of the SSZ bytes of an RPC message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RpcLimits {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl RpcLimits {
+    pub fn new(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+
+    /// Limits for a message with a fixed SSZ length of `len`.
+    fn fixed(len: usize) -> Self {
+        Self::new(len, len)
+    }
+
+    /// Returns `true` if an SSZ message of `length` bytes is outside these bounds.
+    pub fn is_out_of_bounds(&self, length: usize) -> bool {
+        length < self.min || length > self.max
+    }
+}
+
+impl ProtocolId {
+    /// Returns the bounds on the length of a request for this protocol.
+    pub fn rpc_request_limits(&self) -> RpcLimits {
+        match self.message_name {
+            Protocol::Status => RpcLimits::fixed(<StatusMessage as Encode>::ssz_fixed_len()),
+            Protocol::Goodbye => RpcLimits::fixed(<GoodbyeReason as Encode>::ssz_fixed_len()),
+            Protocol::BlocksByRange => {
+                RpcLimits::fixed(<BlocksByRangeRequest as Encode>::ssz_fixed_len())
+            }
+            Protocol::BlocksByRoot => {
+                RpcLimits::new(*BLOCKS_BY_ROOT_REQUEST_MIN, *BLOCKS_BY_ROOT_REQUEST_MAX)
+            }
+            Protocol::Ping => RpcLimits::fixed(<Ping as Encode>::ssz_fixed_len()),
+            Protocol::MetaData => RpcLimits::fixed(0),
+        }
+    }
+
+    /// Returns the bounds on the length of a successful response chunk for this protocol.
+    pub fn rpc_response_limits<T: EthSpec>(&self) -> RpcLimits {
+        match self.message_name {
+            Protocol::Status => RpcLimits::fixed(<StatusMessage as Encode>::ssz_fixed_len()),
+            // Goodbye messages do not have a response.
+            Protocol::Goodbye => RpcLimits::fixed(0),
+            Protocol::BlocksByRange | Protocol::BlocksByRoot => {
+                RpcLimits::new(*SIGNED_BEACON_BLOCK_MIN, *SIGNED_BEACON_BLOCK_MAX)
+            }
+            Protocol::Ping => RpcLimits::fixed(<Ping as Encode>::ssz_fixed_len()),
+            Protocol::MetaData => RpcLimits::fixed(<MetaData<T> as Encode>::ssz_fixed_len()),
+        }
+    }
+}
+
 impl ProtocolName for ProtocolId {
     fn protocol_name(&self) -> &[u8] {
         self.protocol_id.as_bytes()
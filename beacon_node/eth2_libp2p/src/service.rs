@@ -18,6 +18,7 @@ use libp2p::{
     swarm::{SwarmBuilder, SwarmEvent},
     PeerId, Swarm, Transport,
 };
+use sha2::{Digest, Sha256};
 use slog::{crit, debug, info, o, trace, warn};
 use std::fs::File;
 use std::io::prelude::*;
@@ -356,7 +357,6 @@ fn keypair_from_hex(hex_bytes: &str) -> error::Result<Keypair> {
         .and_then(keypair_from_bytes)
 }
 
-#[allow(dead_code)]
 fn keypair_from_bytes(mut bytes: Vec<u8>) -> error::Result<Keypair> {
     libp2p::core::identity::secp256k1::SecretKey::from_bytes(&mut bytes)
         .map(|secret| {
@@ -366,11 +366,32 @@ fn keypair_from_bytes(mut bytes: Vec<u8>) -> error::Result<Keypair> {
         .map_err(|e| format!("Unable to parse p2p secret key: {:?}", e).into())
 }
 
+/// Returns a deterministic secp256k1 keypair for the node with the given `index`, so that the
+/// ENRs and peer IDs of the nodes of a multi-client testnet can be computed ahead of time.
+///
+/// The secret key is `sha256(index)`, with `index` encoded as 32 little-endian bytes. In the
+/// unlikely event that this is not a valid secp256k1 secret key it is hashed again.
+///
+/// INSECURE: anyone can compute these keys. For interop testing only.
+pub fn interop_keypair(index: u64) -> Keypair {
+    let mut preimage = [0; 32];
+    preimage[..8].copy_from_slice(&index.to_le_bytes());
+
+    let mut secret = Sha256::digest(&preimage).to_vec();
+    loop {
+        // `keypair_from_bytes` zeroes its input, so provide a copy.
+        match keypair_from_bytes(secret.clone()) {
+            Ok(keypair) => return keypair,
+            Err(_) => secret = Sha256::digest(&secret).to_vec(),
+        }
+    }
+}
+
 /// Loads a private key from disk. If this fails, a new key is
 /// generated and is then saved to disk.
 ///
 /// Currently only secp256k1 keys are allowed, as these are the only keys supported by discv5.
-fn load_private_key(config: &NetworkConfig, log: &slog::Logger) -> Keypair {
+pub fn load_private_key(config: &NetworkConfig, log: &slog::Logger) -> Keypair {
     // check for key from disk
     let network_key_f = config.network_dir.join(NETWORK_KEY_FILENAME);
     if let Ok(mut network_key_file) = File::open(network_key_f.clone()) {
@@ -433,3 +454,15 @@ fn strip_peer_id(addr: &mut Multiaddr) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interop_keypairs_are_deterministic() {
+        let first = interop_keypair(0).public().into_peer_id();
+        assert_eq!(first, interop_keypair(0).public().into_peer_id());
+        assert_ne!(first, interop_keypair(1).public().into_peer_id());
+    }
+}
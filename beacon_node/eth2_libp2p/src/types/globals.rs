@@ -1,13 +1,14 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::PeerDB;
 use crate::rpc::methods::MetaData;
-use crate::types::SyncState;
+use crate::types::{SyncState, SyncStateHysteresis};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, Eth2Enr, GossipTopic, Multiaddr, PeerId};
 use parking_lot::RwLock;
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Instant;
 use types::EthSpec;
 
 pub struct NetworkGlobals<TSpec: EthSpec> {
@@ -29,6 +30,8 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// Prevents the sync state from flapping between `Synced` and `Stalled`.
+    sync_state_hysteresis: RwLock<SyncStateHysteresis>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -51,6 +54,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             peers: RwLock::new(PeerDB::new(log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            sync_state_hysteresis: RwLock::new(SyncStateHysteresis::default()),
         }
     }
 
@@ -85,6 +89,11 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.peers.read().connected_peer_ids().count()
     }
 
+    /// Returns the number of connected libp2p peers which we have dialed.
+    pub fn connected_outbound_peers(&self) -> usize {
+        self.peers.read().connected_outbound_peer_ids().count()
+    }
+
     /// Returns the number of libp2p peers that are either connected or being dialed.
     pub fn connected_or_dialing_peers(&self) -> usize {
         self.peers.read().connected_or_dialing_peers().count()
@@ -113,23 +122,42 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
     ///
     /// If there is a new state, the old state and the new states are returned.
     pub fn update_sync_state(&self) -> Option<(SyncState, SyncState)> {
-        let mut result = None;
         // if we are in a range sync, nothing changes. Range sync will update this.
         if !self.is_syncing() {
-            let new_state = self
-                .peers
-                .read()
-                .synced_peers()
-                .next()
-                .map(|_| SyncState::Synced)
-                .unwrap_or_else(|| SyncState::Stalled);
-
-            let mut peer_state = self.sync_state.write();
-            if new_state != *peer_state {
-                result = Some((peer_state.clone(), new_state.clone()));
-            }
-            *peer_state = new_state;
+            self.set_sync_state(self.peer_sync_state())
+        } else {
+            None
         }
+    }
+
+    /// Returns `Synced` if there is at least one synced peer, otherwise `Stalled`.
+    ///
+    /// This is the state of the node when no range sync is in progress.
+    pub fn peer_sync_state(&self) -> SyncState {
+        self.peers
+            .read()
+            .synced_peers()
+            .next()
+            .map(|_| SyncState::Synced)
+            .unwrap_or_else(|| SyncState::Stalled)
+    }
+
+    /// Sets the syncing state of the node to `new_state`, subject to `SyncStateHysteresis`.
+    ///
+    /// If the state changed, the old state and the new states are returned.
+    pub fn set_sync_state(&self, new_state: SyncState) -> Option<(SyncState, SyncState)> {
+        let mut sync_state = self.sync_state.write();
+        let new_state =
+            self.sync_state_hysteresis
+                .write()
+                .transition(&sync_state, new_state, Instant::now());
+
+        let result = if new_state != *sync_state {
+            Some((sync_state.clone(), new_state.clone()))
+        } else {
+            None
+        };
+        *sync_state = new_state;
         result
     }
 }
@@ -1,6 +1,7 @@
 pub mod error;
 mod globals;
 mod pubsub;
+mod subnet;
 mod sync_state;
 mod topics;
 
@@ -13,5 +14,6 @@ pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
 pub use pubsub::PubsubMessage;
-pub use sync_state::SyncState;
+pub use subnet::Subnet;
+pub use sync_state::{SyncState, SyncStateHysteresis, STALL_TOLERANCE};
 pub use topics::{GossipEncoding, GossipKind, GossipTopic};
@@ -0,0 +1,12 @@
+use serde::Serialize;
+use types::SubnetId;
+
+/// Represents a gossipsub subnet.
+///
+/// Used for subscribing to the appropriate gossipsub subnets and marking
+/// appropriate metadata bitfields. Only attestation subnets exist prior to Altair.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+pub enum Subnet {
+    /// Represents a gossipsub attestation subnet and the metadata `attnets` field.
+    Attestation(SubnetId),
+}
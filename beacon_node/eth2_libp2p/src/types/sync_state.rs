@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use types::{Hash256, Slot};
 
+/// The length of time that a `Synced` node must be without synced peers before it is considered
+/// `Stalled`.
+///
+/// Peers regularly disconnect and re-connect (or are briefly re-classified after a `STATUS`), so
+/// without this delay the node would flap between the two states.
+pub const STALL_TOLERANCE: Duration = Duration::from_secs(60);
+
 /// The current state of the node.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SyncState {
@@ -45,6 +53,15 @@ impl SyncState {
         }
     }
 
+    /// Returns true if the node is syncing a finalized chain, and is therefore at least an epoch
+    /// behind the head.
+    pub fn is_syncing_finalized(&self) -> bool {
+        match self {
+            SyncState::SyncingFinalized { .. } => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if the node is synced.
     pub fn is_synced(&self) -> bool {
         match self {
@@ -64,3 +81,105 @@ impl std::fmt::Display for SyncState {
         }
     }
 }
+
+/// Applies hysteresis to transitions of the node's `SyncState`, to prevent it flapping between
+/// `Synced` and `Stalled` whilst peers come and go.
+#[derive(Debug, Default)]
+pub struct SyncStateHysteresis {
+    /// The time at which a `Synced` node first lost all of its synced peers.
+    stalled_since: Option<Instant>,
+}
+
+impl SyncStateHysteresis {
+    /// Returns the state that the node should transition to from `current` when `proposed` is
+    /// observed at `now`.
+    ///
+    /// A `Synced` node only becomes `Stalled` once `proposed` has been `Stalled` for at least
+    /// `STALL_TOLERANCE`. All other transitions are immediate.
+    pub fn transition(
+        &mut self,
+        current: &SyncState,
+        proposed: SyncState,
+        now: Instant,
+    ) -> SyncState {
+        match (current, &proposed) {
+            (SyncState::Synced, SyncState::Stalled) => {
+                let stalled_since = *self.stalled_since.get_or_insert(now);
+                if now.saturating_duration_since(stalled_since) >= STALL_TOLERANCE {
+                    self.stalled_since = None;
+                    proposed
+                } else {
+                    SyncState::Synced
+                }
+            }
+            _ => {
+                self.stalled_since = None;
+                proposed
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synced_to_stalled_is_delayed() {
+        let mut hysteresis = SyncStateHysteresis::default();
+        let start = Instant::now();
+
+        let state = hysteresis.transition(&SyncState::Synced, SyncState::Stalled, start);
+        assert_eq!(state, SyncState::Synced);
+
+        let state = hysteresis.transition(
+            &state,
+            SyncState::Stalled,
+            start + STALL_TOLERANCE - Duration::from_secs(1),
+        );
+        assert_eq!(state, SyncState::Synced);
+
+        let state = hysteresis.transition(&state, SyncState::Stalled, start + STALL_TOLERANCE);
+        assert_eq!(state, SyncState::Stalled);
+    }
+
+    #[test]
+    fn recovering_peers_resets_the_delay() {
+        let mut hysteresis = SyncStateHysteresis::default();
+        let start = Instant::now();
+
+        hysteresis.transition(&SyncState::Synced, SyncState::Stalled, start);
+        let state = hysteresis.transition(
+            &SyncState::Synced,
+            SyncState::Synced,
+            start + Duration::from_secs(1),
+        );
+        assert_eq!(state, SyncState::Synced);
+
+        let state = hysteresis.transition(&state, SyncState::Stalled, start + STALL_TOLERANCE);
+        assert_eq!(state, SyncState::Synced, "delay should restart");
+    }
+
+    #[test]
+    fn other_transitions_are_immediate() {
+        let mut hysteresis = SyncStateHysteresis::default();
+        let now = Instant::now();
+        let syncing = SyncState::SyncingHead {
+            start_slot: Slot::new(0),
+            head_slot: Slot::new(64),
+        };
+
+        assert_eq!(
+            hysteresis.transition(&SyncState::Synced, syncing.clone(), now),
+            syncing
+        );
+        assert_eq!(
+            hysteresis.transition(&syncing, SyncState::Stalled, now),
+            SyncState::Stalled
+        );
+        assert_eq!(
+            hysteresis.transition(&SyncState::Stalled, SyncState::Synced, now),
+            SyncState::Synced
+        );
+    }
+}
@@ -5,7 +5,7 @@ use eth1::{DepositLog, Eth1Block, Service as Eth1Service};
 use slog::{debug, error, info, trace, Logger};
 use state_processing::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
-    per_block_processing::process_deposit, process_activations,
+    per_block_processing::process_deposit_with_signature_validity, process_activations,
 };
 use std::sync::{
     atomic::{AtomicU64, AtomicUsize, Ordering},
@@ -48,8 +48,6 @@ impl Eth1GenesisService {
             // Truncating the block cache makes searching for genesis more
             // complicated.
             block_cache_truncation: None,
-            // Scan large ranges of blocks when awaiting genesis.
-            blocks_per_log_query: 1_000,
             // Only perform a few log requests each time the eth1 node is polled.
             //
             // For small testnets this makes finding genesis much faster,
@@ -408,30 +406,30 @@ impl Eth1GenesisService {
 
         self.deposit_logs_at_block(eth1_block.number)
             .iter()
-            .map(|deposit_log| Deposit {
-                // Generate a bogus proof.
-                //
-                // The deposits are coming directly from our own deposit tree to there's no need to
-                // make proofs about their inclusion in it.
-                proof: vec![Hash256::zero(); spec.deposit_contract_tree_depth as usize].into(),
-                data: deposit_log.deposit_data.clone(),
-            })
-            .try_for_each(|deposit| {
+            .try_for_each(|deposit_log| {
+                let deposit = Deposit {
+                    // Generate a bogus proof.
+                    //
+                    // The deposits are coming directly from our own deposit tree to there's no
+                    // need to make proofs about their inclusion in it.
+                    proof: vec![Hash256::zero(); spec.deposit_contract_tree_depth as usize].into(),
+                    data: deposit_log.deposit_data.clone(),
+                };
+
                 // Skip proof verification (see comment about bogus proof generation).
                 const PROOF_VERIFICATION: bool = false;
 
-                // Note: presently all the signatures are verified each time this function is
-                // run.
-                //
-                // It would be more efficient to pre-verify signatures, filter out the invalid
-                // ones and disable verification for `process_deposit`.
-                //
-                // Such an optimization would only be useful in a scenario where `MIN_GENESIS_TIME`
-                // is reached _prior_ to `MIN_ACTIVE_VALIDATOR_COUNT`. I suspect this won't be the
-                // case for mainnet, so we defer this optimization.
-
-                process_deposit(&mut state, &deposit, spec, PROOF_VERIFICATION)
-                    .map_err(|e| format!("Error whilst processing deposit: {:?}", e))
+                // The signature of each deposit was verified when its log was added to the
+                // deposit cache, so there is no need to verify it again each time this function
+                // is run.
+                process_deposit_with_signature_validity(
+                    &mut state,
+                    &deposit,
+                    spec,
+                    PROOF_VERIFICATION,
+                    Some(deposit_log.signature_is_valid),
+                )
+                .map_err(|e| format!("Error whilst processing deposit: {:?}", e))
             })?;
 
         process_activations(&mut state, spec)
@@ -77,7 +77,7 @@ fn basic() {
             })
             .collect::<Vec<_>>();
 
-        let deposit_future = deposit_contract.deposit_multiple(deposits);
+        let deposit_future = deposit_contract.deposit_multiple(deposits.clone());
 
         let wait_future =
             service.wait_for_genesis_state::<MinimalEthSpec>(update_interval, spec.clone());
@@ -105,5 +105,19 @@ fn basic() {
             is_valid_genesis_state(&state, &spec),
             "should be valid genesis state"
         );
+
+        let logged_deposits = deposit_contract
+            .deposit_logs()
+            .await
+            .expect("should read deposit logs");
+        assert_eq!(
+            logged_deposits,
+            deposits
+                .into_iter()
+                .enumerate()
+                .map(|(i, delay_then_deposit)| (delay_then_deposit.deposit, i as u64))
+                .collect::<Vec<_>>(),
+            "should log each deposit in order"
+        );
     });
 }
@@ -4,12 +4,12 @@
 
 use crate::metrics;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2_libp2p::{types::GossipKind, NetworkGlobals};
+use eth2_libp2p::{types::GossipKind, NetworkConfig, NetworkGlobals};
 use futures::prelude::*;
 use hashset_delay::HashSetDelay;
 use rand::seq::SliceRandom;
 use rest_types::ValidatorSubscription;
-use slog::{crit, debug, error, o, trace, warn};
+use slog::{crit, debug, error, info, o, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::VecDeque;
 use std::pin::Pin;
@@ -134,6 +134,21 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// The waker for the current thread.
     waker: Option<std::task::Waker>,
 
+    /// If true, validator subscriptions are ignored and no attestation subnets are joined.
+    proposer_only: bool,
+
+    /// If true, the long-lived random subnets are never advertised in the ENR, since they are
+    /// chosen because of the validators attached to this node.
+    private: bool,
+
+    /// If true, the node remains subscribed to all attestation subnets and random subnets are not
+    /// used.
+    subscribe_all_subnets: bool,
+
+    /// If true, all unaggregated attestations on subscribed subnets are processed, regardless of
+    /// whether an attached validator is aggregating.
+    import_all_attestations: bool,
+
     /// The logger for the attestation service.
     log: slog::Logger,
 }
@@ -144,6 +159,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     pub fn new(
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+        config: &NetworkConfig,
         log: &slog::Logger,
     ) -> Self {
         let log = log.new(o!("service" => "attestation_service"));
@@ -164,8 +180,19 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             .checked_mul(DEFAULT_EXPIRATION_TIMEOUT)
             .expect("DEFAULT_EXPIRATION_TIMEOUT must not be ridiculoustly large");
 
+        let mut events = VecDeque::with_capacity(10);
+        if config.subscribe_all_subnets {
+            info!(log, "Subscribing to all attestation subnets");
+            for subnet_id in (0..spec.attestation_subnet_count).map(SubnetId::new) {
+                events.push_back(AttServiceMessage::Subscribe(subnet_id));
+                if !config.private {
+                    events.push_back(AttServiceMessage::EnrAdd(subnet_id));
+                }
+            }
+        }
+
         AttestationService {
-            events: VecDeque::with_capacity(10),
+            events,
             network_globals,
             beacon_chain,
             random_subnets: HashSetDelay::new(Duration::from_millis(random_subnet_duration_millis)),
@@ -175,6 +202,10 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             aggregate_validators_on_subnet: HashSetDelay::new(default_timeout),
             known_validators: HashSetDelay::new(last_seen_val_timeout),
             waker: None,
+            proposer_only: config.proposer_only,
+            private: config.private,
+            subscribe_all_subnets: config.subscribe_all_subnets,
+            import_all_attestations: config.import_all_attestations,
             log,
         }
     }
@@ -195,6 +226,16 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         &mut self,
         subscriptions: Vec<ValidatorSubscription>,
     ) -> Result<(), String> {
+        // A proposer-only node leaves attestation subnets, and the aggregation work they bring, to
+        // other nodes.
+        if self.proposer_only {
+            trace!(self.log,
+                "Ignoring validator subscriptions on a proposer-only node";
+                "count" => subscriptions.len(),
+            );
+            return Ok(());
+        }
+
         for subscription in subscriptions {
             metrics::inc_counter(&metrics::SUBNET_SUBSCRIPTION_REQUESTS);
             //NOTE: We assume all subscriptions have been verified before reaching this service
@@ -279,7 +320,14 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             subnet_id: subnet.clone(),
             slot: attestation.data.slot,
         };
-        self.aggregate_validators_on_subnet.contains(&exact_subnet)
+        if self.aggregate_validators_on_subnet.contains(&exact_subnet) {
+            true
+        } else if self.import_all_attestations {
+            metrics::inc_counter(&metrics::GOSSIP_UNAGGREGATED_ATTESTATIONS_IMPORT_ALL);
+            true
+        } else {
+            false
+        }
     }
 
     /* Internal private functions */
@@ -484,7 +532,8 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// This also updates the ENR to indicate our long-lived subscription to the subnet
     fn add_known_validator(&mut self, validator_index: u64) {
-        if self.known_validators.get(&validator_index).is_none() {
+        // all subnets are already subscribed to, random subnets are not required
+        if self.known_validators.get(&validator_index).is_none() && !self.subscribe_all_subnets {
             // New validator has subscribed
             // Subscribe to random topics and update the ENR if needed.
 
@@ -552,7 +601,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                     .push_back(AttServiceMessage::Subscribe(subnet_id));
             }
             // add the subnet to the ENR bitfield
-            self.events.push_back(AttServiceMessage::EnrAdd(subnet_id));
+            if !self.private {
+                self.events.push_back(AttServiceMessage::EnrAdd(subnet_id));
+            }
         }
     }
 
@@ -620,8 +671,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// Unsubscription events are added, even if we are subscribed to long-lived random subnets. If
     /// a random subnet is present, we do not unsubscribe from it.
     fn handle_unsubscriptions(&mut self, exact_subnet: ExactSubnet) {
-        // Check if the subnet currently exists as a long-lasting random subnet
-        if self.random_subnets.contains(&exact_subnet.subnet_id) {
+        // Check if the subnet currently exists as a long-lasting random subnet, or if we remain
+        // subscribed to all subnets
+        if self.subscribe_all_subnets || self.random_subnets.contains(&exact_subnet.subnet_id) {
             return;
         }
 
@@ -92,16 +92,21 @@ mod tests {
     }
 
     fn get_attestation_service() -> AttestationService<TestBeaconChainType> {
+        get_attestation_service_with_config(NetworkConfig::default())
+    }
+
+    fn get_attestation_service_with_config(
+        config: NetworkConfig,
+    ) -> AttestationService<TestBeaconChainType> {
         let log = get_logger();
 
         let beacon_chain = CHAIN.chain.clone();
 
-        let config = NetworkConfig::default();
         let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
         let enr = build_enr::<MinimalEthSpec>(&enr_key, &config, EnrForkId::default()).unwrap();
 
         let network_globals: NetworkGlobals<MinimalEthSpec> = NetworkGlobals::new(enr, 0, 0, &log);
-        AttestationService::new(beacon_chain, Arc::new(network_globals), &log)
+        AttestationService::new(beacon_chain, Arc::new(network_globals), &config, &log)
     }
 
     fn get_subscription(
@@ -639,4 +644,90 @@ mod tests {
         assert_eq!(enr_add_count, 64);
         assert_eq!(unexpected_msg_count, 0);
     }
+
+    #[tokio::test]
+    async fn proposer_only_ignores_subscriptions() {
+        // subscription config
+        let validator_index = 1;
+        let committee_index = 1;
+        let committee_count = 1;
+
+        // create the attestation service and subscriptions
+        let mut config = NetworkConfig::default();
+        config.proposer_only = true;
+        let mut attestation_service = get_attestation_service_with_config(config);
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        let subscriptions = vec![get_subscription(
+            validator_index,
+            committee_index,
+            current_slot,
+            committee_count,
+        )];
+
+        // submit the subscriptions
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        let events = get_events(attestation_service, 1, 2).await;
+        assert_eq!(events, vec![]);
+    }
+
+    #[tokio::test]
+    async fn subscribe_all_subnets_ignores_random_subnets() {
+        // subscribe 10 slots ahead so we do not produce any exact subnet messages
+        let subscription_slot = 10;
+        let subscription_count = 64;
+        let committee_count = 1;
+
+        // create the attestation service and subscriptions
+        let mut config = NetworkConfig::default();
+        config.subscribe_all_subnets = true;
+        let mut attestation_service = get_attestation_service_with_config(config);
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        let subscriptions = _get_subscriptions(
+            subscription_count,
+            current_slot + subscription_slot,
+            committee_count,
+        );
+
+        // submit the subscriptions
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        let events = get_events(attestation_service, 192, 3).await;
+        let mut discover_peer_count = 0;
+        let mut subscribe_count = 0;
+        let mut enr_add_count = 0;
+        let mut unexpected_msg_count = 0;
+
+        for event in events {
+            match event {
+                AttServiceMessage::DiscoverPeers {
+                    subnet_id: _any_subnet,
+                    min_ttl: _any_instant,
+                } => discover_peer_count = discover_peer_count + 1,
+                AttServiceMessage::Subscribe(_any_subnet) => subscribe_count = subscribe_count + 1,
+                AttServiceMessage::EnrAdd(_any_subnet) => enr_add_count = enr_add_count + 1,
+                _ => unexpected_msg_count = unexpected_msg_count + 1,
+            }
+        }
+
+        // all subnets are subscribed to on startup, and no random subnets are chosen
+        assert_eq!(discover_peer_count, 0);
+        assert_eq!(subscribe_count, 64);
+        assert_eq!(enr_add_count, 64);
+        assert_eq!(unexpected_msg_count, 0);
+    }
 }
@@ -16,11 +16,29 @@ lazy_static! {
         "network_gossip_unaggregated_attestations_ignored_total",
         "Count of gossip unaggregated attestations ignored by attestation service"
     );
+    pub static ref GOSSIP_UNAGGREGATED_ATTESTATIONS_IMPORT_ALL: Result<IntCounter> = try_create_int_counter(
+        "network_gossip_unaggregated_attestations_import_all_total",
+        "Count of gossip unaggregated attestations processed only because all attestations are imported"
+    );
+    pub static ref ATTESTATION_SUBNETS_SUBSCRIBED: Result<IntGauge> = try_create_int_gauge(
+        "network_attestation_subnets_subscribed",
+        "Number of attestation subnets the node is subscribed to"
+    );
     pub static ref GOSSIP_AGGREGATED_ATTESTATIONS_RX: Result<IntCounter> = try_create_int_counter(
         "network_gossip_aggregated_attestations_rx_total",
         "Count of gossip aggregated attestations received"
     );
 
+    pub static ref GOSSIP_ATTESTATIONS_DROPPED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "network_gossip_attestations_dropped_total",
+        "Count of gossip attestations dropped because the router queue was full",
+        &["type"]
+    );
+    pub static ref GOSSIP_BLOCKS_DROPPED: Result<IntCounter> = try_create_int_counter(
+        "network_gossip_blocks_dropped_total",
+        "Count of gossip blocks dropped because the import queue was full"
+    );
+
     /*
      * Gossip Tx
      */
@@ -6,19 +6,21 @@
 #![allow(clippy::unit_arg)]
 
 pub mod processor;
+mod tests;
 
 use crate::error;
+use crate::metrics;
 use crate::service::NetworkMessage;
-use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{
     rpc::{RPCError, RequestId},
-    MessageId, NetworkGlobals, PeerId, PeerRequestId, PubsubMessage, Request, Response,
+    MessageAcceptance, MessageId, NetworkConfig, NetworkGlobals, PeerId, PeerRequestId,
+    PubsubMessage, Request, Response,
 };
-use futures::prelude::*;
 use processor::Processor;
 use slog::{debug, info, o, trace, warn};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::{self, error::TrySendError};
 use types::EthSpec;
 
 /// Handles messages received from the network and client and organises syncing. This
@@ -69,19 +71,61 @@ pub enum RouterMessage<T: EthSpec> {
     StatusPeer(PeerId),
 }
 
+/// Sends messages to the `Router`.
+///
+/// Gossip attestations are sent via bounded queues, so that they are dropped rather than
+/// accumulating without limit when the router falls behind. All other messages are unbounded.
+pub struct RouterSender<T: EthSpec> {
+    messages: mpsc::UnboundedSender<RouterMessage<T>>,
+    attestations: mpsc::Sender<RouterMessage<T>>,
+    aggregates: mpsc::Sender<RouterMessage<T>>,
+}
+
+impl<T: EthSpec> RouterSender<T> {
+    /// Sends `message` to the router.
+    ///
+    /// Gossip attestations which do not fit in their queue are dropped and counted in the
+    /// `network_gossip_attestations_dropped_total` metric, this is not considered an error. An error
+    /// is only returned if the router has shutdown.
+    pub fn send(&mut self, message: RouterMessage<T>) -> Result<(), RouterMessage<T>> {
+        let (queue, kind) = match &message {
+            RouterMessage::PubsubMessage(_, _, PubsubMessage::Attestation(_)) => {
+                (&mut self.attestations, "unaggregated")
+            }
+            RouterMessage::PubsubMessage(_, _, PubsubMessage::AggregateAndProofAttestation(_)) => {
+                (&mut self.aggregates, "aggregate")
+            }
+            _ => return self.messages.send(message).map_err(|e| e.0),
+        };
+
+        match queue.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                metrics::inc_counter_vec(&metrics::GOSSIP_ATTESTATIONS_DROPPED, &[kind]);
+                Ok(())
+            }
+            Err(TrySendError::Closed(message)) => Err(message),
+        }
+    }
+}
+
 impl<T: BeaconChainTypes> Router<T> {
     /// Initializes and runs the Router.
     pub fn spawn(
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+        config: &NetworkConfig,
         executor: environment::TaskExecutor,
         log: slog::Logger,
-    ) -> error::Result<mpsc::UnboundedSender<RouterMessage<T::EthSpec>>> {
+    ) -> error::Result<RouterSender<T::EthSpec>> {
         let message_handler_log = log.new(o!("service"=> "router"));
         trace!(message_handler_log, "Service starting");
 
-        let (handler_send, handler_recv) = mpsc::unbounded_channel();
+        let (handler_send, mut handler_recv) = mpsc::unbounded_channel();
+        let (attestation_send, mut attestation_recv) = mpsc::channel(config.attestation_queue_len);
+        let (aggregate_send, mut aggregate_recv) = mpsc::channel(config.aggregate_queue_len);
+        let prioritize_aggregates = config.prioritize_aggregates;
 
         // Initialise a message instance, which itself spawns the syncing thread.
         let processor = Processor::new(
@@ -104,14 +148,29 @@ impl<T: BeaconChainTypes> Router<T> {
         executor.spawn(
             async move {
                 debug!(log, "Network message router started");
-                handler_recv
-                    .for_each(move |msg| future::ready(handler.handle_message(msg)))
-                    .await;
+                loop {
+                    if prioritize_aggregates {
+                        while let Ok(msg) = aggregate_recv.try_recv() {
+                            handler.handle_message(msg);
+                        }
+                    }
+
+                    tokio::select! {
+                        Some(msg) = handler_recv.recv() => handler.handle_message(msg),
+                        Some(msg) = aggregate_recv.recv() => handler.handle_message(msg),
+                        Some(msg) = attestation_recv.recv() => handler.handle_message(msg),
+                        else => break,
+                    }
+                }
             },
             "router",
         );
 
-        Ok(handler_send)
+        Ok(RouterSender {
+            messages: handler_send,
+            attestations: attestation_send,
+            aggregates: aggregate_send,
+        })
     }
 
     /// Handle all messages incoming from the network service.
@@ -210,51 +269,58 @@ impl<T: BeaconChainTypes> Router<T> {
         match gossip_message {
             // Attestations should never reach the router.
             PubsubMessage::AggregateAndProofAttestation(aggregate_and_proof) => {
-                if let Some(gossip_verified) = self
+                match self
                     .processor
                     .verify_aggregated_attestation_for_gossip(peer_id.clone(), *aggregate_and_proof)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_aggregated_attestation(peer_id, gossip_verified);
+                    Ok(gossip_verified) => {
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
+                        self.processor
+                            .import_aggregated_attestation(peer_id, gossip_verified);
+                    }
+                    Err(acceptance) => self.validate_message(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::Attestation(subnet_attestation) => {
-                if let Some(gossip_verified) =
-                    self.processor.verify_unaggregated_attestation_for_gossip(
-                        peer_id.clone(),
-                        subnet_attestation.1.clone(),
-                        subnet_attestation.0,
-                    )
-                {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_unaggregated_attestation(peer_id, gossip_verified);
+                match self.processor.verify_unaggregated_attestation_for_gossip(
+                    peer_id.clone(),
+                    subnet_attestation.1.clone(),
+                    subnet_attestation.0,
+                ) {
+                    Ok(gossip_verified) => {
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
+                        self.processor
+                            .import_unaggregated_attestation(peer_id, gossip_verified);
+                    }
+                    Err(acceptance) => self.validate_message(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::BeaconBlock(block) => {
                 match self.processor.should_forward_block(&peer_id, block) {
                     Ok(verified_block) => {
                         info!(self.log, "New block received"; "slot" => verified_block.block.slot(), "hash" => verified_block.block_root.to_string());
-                        self.propagate_message(id, peer_id.clone());
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
                         self.processor.on_block_gossip(peer_id, verified_block);
                     }
-                    Err(BlockError::ParentUnknown { .. }) => {} // performing a parent lookup
                     Err(e) => {
-                        // performing a parent lookup
-                        warn!(self.log, "Could not verify block for gossip";
-                            "error" => format!("{:?}", e));
+                        let acceptance = self
+                            .processor
+                            .handle_block_verification_failure(&peer_id, e);
+                        self.validate_message(id, peer_id, acceptance);
                     }
                 }
             }
             PubsubMessage::VoluntaryExit(exit) => {
                 debug!(self.log, "Received a voluntary exit"; "peer_id" => format!("{}", peer_id));
-                if let Some(verified_exit) = self
+                match self
                     .processor
                     .verify_voluntary_exit_for_gossip(&peer_id, *exit)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor.import_verified_voluntary_exit(verified_exit);
+                    Ok(verified_exit) => {
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
+                        self.processor.import_verified_voluntary_exit(verified_exit);
+                    }
+                    Err(acceptance) => self.validate_message(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::ProposerSlashing(proposer_slashing) => {
@@ -263,13 +329,16 @@ impl<T: BeaconChainTypes> Router<T> {
                     "Received a proposer slashing";
                     "peer_id" => format!("{}", peer_id)
                 );
-                if let Some(verified_proposer_slashing) = self
+                match self
                     .processor
                     .verify_proposer_slashing_for_gossip(&peer_id, *proposer_slashing)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_verified_proposer_slashing(verified_proposer_slashing);
+                    Ok(verified_proposer_slashing) => {
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
+                        self.processor
+                            .import_verified_proposer_slashing(verified_proposer_slashing);
+                    }
+                    Err(acceptance) => self.validate_message(id, peer_id, acceptance),
                 }
             }
             PubsubMessage::AttesterSlashing(attester_slashing) => {
@@ -278,29 +347,40 @@ impl<T: BeaconChainTypes> Router<T> {
                     "Received a attester slashing";
                     "peer_id" => format!("{}", peer_id)
                 );
-                if let Some(verified_attester_slashing) = self
+                match self
                     .processor
                     .verify_attester_slashing_for_gossip(&peer_id, *attester_slashing)
                 {
-                    self.propagate_message(id, peer_id.clone());
-                    self.processor
-                        .import_verified_attester_slashing(verified_attester_slashing);
+                    Ok(verified_attester_slashing) => {
+                        self.validate_message(id, peer_id.clone(), MessageAcceptance::Accept);
+                        self.processor
+                            .import_verified_attester_slashing(verified_attester_slashing);
+                    }
+                    Err(acceptance) => self.validate_message(id, peer_id, acceptance),
                 }
             }
         }
     }
 
-    /// Informs the network service that the message should be forwarded to other peers.
-    fn propagate_message(&mut self, message_id: MessageId, propagation_source: PeerId) {
+    /// Informs the network service of the result of validating a gossip message. Accepted
+    /// messages are forwarded to other peers and rejected messages penalize the peer that sent
+    /// them.
+    fn validate_message(
+        &mut self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        validation_result: MessageAcceptance,
+    ) {
         self.network_send
-            .send(NetworkMessage::Propagate {
+            .send(NetworkMessage::Validate {
                 propagation_source,
                 message_id,
+                validation_result,
             })
             .unwrap_or_else(|_| {
                 warn!(
                     self.log,
-                    "Could not send propagation request to the network service"
+                    "Could not send message validation result to the network service"
                 )
             });
     }
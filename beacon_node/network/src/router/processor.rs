@@ -1,3 +1,4 @@
+use crate::metrics;
 use crate::service::NetworkMessage;
 use crate::sync::{PeerSyncInfo, SyncMessage};
 use beacon_chain::{
@@ -10,23 +11,31 @@ use beacon_chain::{
     GossipVerifiedBlock,
 };
 use eth2_libp2p::rpc::*;
-use eth2_libp2p::{NetworkGlobals, PeerAction, PeerId, PeerRequestId, Request, Response};
+use eth2_libp2p::{
+    MessageAcceptance, NetworkGlobals, PeerAction, PeerId, PeerRequestId, Request, Response,
+};
 use itertools::process_results;
 use slog::{debug, error, o, trace, warn};
+use slot_clock::SlotClock;
 use ssz::Encode;
+use state_processing::per_block_processing::errors::{
+    AttesterSlashingInvalid, BlockOperationError, ExitInvalid, IndexedAttestationInvalid,
+    ProposerSlashingInvalid,
+};
 use state_processing::SigVerifiedOp;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::{self, error::TrySendError};
 use types::{
     Attestation, AttesterSlashing, ChainSpec, Epoch, EthSpec, Hash256, ProposerSlashing,
     SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit, Slot, SubnetId,
 };
 
-//TODO: Rate limit requests
+/// The maximum number of gossip blocks which may be waiting for full import. Blocks which arrive
+/// whilst the queue is full are dropped; they have already been forwarded to our peers and sync
+/// will fetch them if they are required.
+const MAX_QUEUED_GOSSIP_BLOCKS: usize = 16;
 
-/// If a block is more than `FUTURE_SLOT_TOLERANCE` slots ahead of our slot clock, we drop it.
-/// Otherwise we queue it.
-pub(crate) const FUTURE_SLOT_TOLERANCE: u64 = 1;
+//TODO: Rate limit requests
 
 /// Processes validated messages from the network. It relays necessary data to the syncing thread
 /// and processes blocks from the pubsub network.
@@ -37,6 +46,8 @@ pub struct Processor<T: BeaconChainTypes> {
     sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
     /// A network context to return and handle RPC requests.
     network: HandlerNetworkContext<T::EthSpec>,
+    /// A bounded queue of gossip blocks awaiting full import.
+    gossip_block_send: mpsc::Sender<(PeerId, GossipVerifiedBlock<T>)>,
     /// The `RPCHandler` logger.
     log: slog::Logger,
 }
@@ -54,17 +65,28 @@ impl<T: BeaconChainTypes> Processor<T> {
 
         // spawn the sync thread
         let sync_send = crate::sync::manager::spawn(
-            executor,
+            executor.clone(),
             beacon_chain.clone(),
             network_globals,
             network_send.clone(),
             sync_logger,
         );
 
+        let (gossip_block_send, gossip_block_recv) = mpsc::channel(MAX_QUEUED_GOSSIP_BLOCKS);
+        spawn_gossip_block_importer(
+            &executor,
+            beacon_chain.clone(),
+            sync_send.clone(),
+            HandlerNetworkContext::new(network_send.clone(), log.clone()),
+            gossip_block_recv,
+            log.clone(),
+        );
+
         Processor {
             chain: beacon_chain,
             sync_send,
             network: HandlerNetworkContext::new(network_send, log.clone()),
+            gossip_block_send,
             log: log.clone(),
         }
     }
@@ -196,7 +218,15 @@ impl<T: BeaconChainTypes> Processor<T> {
             self.network
                 .goodbye_peer(peer_id, GoodbyeReason::IrrelevantNetwork);
         } else if remote.head_slot
-            > self.chain.slot().unwrap_or_else(|_| Slot::from(0u64)) + FUTURE_SLOT_TOLERANCE
+            > self
+                .chain
+                .slot_clock
+                .now_with_future_tolerance(
+                    self.chain
+                        .config
+                        .maximum_gossip_clock_disparity(&self.chain.spec),
+                )
+                .unwrap_or_else(|| Slot::from(0u64))
         {
             // Note: If the slot_clock cannot be read, this will not error. Other system
             // components will deal with an invalid slot clock error.
@@ -516,83 +546,107 @@ impl<T: BeaconChainTypes> Processor<T> {
         result
     }
 
-    /// Process a gossip message declaring a new block.
+    /// Handle an error whilst verifying a gossip `SignedBeaconBlock`.
     ///
-    /// Attempts to apply to block to the beacon chain. May queue the block for later processing.
-    ///
-    /// Returns a `bool` which, if `true`, indicates we should forward the block to our peers.
-    pub fn on_block_gossip(
+    /// Returns `MessageAcceptance::Reject` if the block is invalid and the peer should be
+    /// penalized, otherwise `MessageAcceptance::Ignore`.
+    pub fn handle_block_verification_failure(
         &mut self,
-        peer_id: PeerId,
-        verified_block: GossipVerifiedBlock<T>,
-    ) -> bool {
-        let block = Box::new(verified_block.block.clone());
-        match self.chain.process_block(verified_block) {
-            Ok(_block_root) => {
-                trace!(
+        peer_id: &PeerId,
+        error: BlockError,
+    ) -> MessageAcceptance {
+        match error {
+            // A parent lookup has been started by `Self::should_forward_block`.
+            BlockError::ParentUnknown(_) => MessageAcceptance::Ignore,
+            /*
+             * The block is not invalid, it is either early, late or a duplicate. These can be
+             * triggered by a mismatch between our clock or view of the chain and the peer's.
+             */
+            BlockError::FutureSlot { .. }
+            | BlockError::WouldRevertFinalizedSlot { .. }
+            | BlockError::BlockIsAlreadyKnown
+            | BlockError::RepeatProposal { .. } => {
+                debug!(
                     self.log,
-                    "Gossipsub block processed";
-                    "peer_id" => peer_id.to_string()
+                    "Ignoring gossip block";
+                    "peer_id" => peer_id.to_string(),
+                    "reason" => format!("{:?}", error)
                 );
-
-                // TODO: It would be better if we can run this _after_ we publish the block to
-                // reduce block propagation latency.
-                //
-                // The `MessageHandler` would be the place to put this, however it doesn't seem
-                // to have a reference to the `BeaconChain`. I will leave this for future
-                // works.
-                match self.chain.fork_choice() {
-                    Ok(()) => trace!(
-                        self.log,
-                        "Fork choice success";
-                        "location" => "block gossip"
-                    ),
-                    Err(e) => error!(
-                        self.log,
-                        "Fork choice failed";
-                        "error" => format!("{:?}", e),
-                        "location" => "block gossip"
-                    ),
-                }
+                MessageAcceptance::Ignore
             }
-            Err(BlockError::ParentUnknown { .. }) => {
-                // Inform the sync manager to find parents for this block
-                // This should not occur. It should be checked by `should_forward_block`
-                error!(
+            /*
+             * Lighthouse hit an unexpected error whilst processing the block. It's not clear if
+             * the block is invalid.
+             */
+            BlockError::BeaconChainError(_) => {
+                warn!(
                     self.log,
-                    "Block with unknown parent attempted to be processed";
-                    "peer_id" => peer_id.to_string()
+                    "Could not verify block for gossip";
+                    "peer_id" => peer_id.to_string(),
+                    "error" => format!("{:?}", error)
                 );
-                self.send_to_sync(SyncMessage::UnknownBlock(peer_id, block));
+                MessageAcceptance::Ignore
             }
-            other => {
+            /*
+             * The peer has published an invalid block.
+             */
+            BlockError::StateRootMismatch { .. }
+            | BlockError::GenesisBlock
+            | BlockError::BlockSlotLimitReached
+            | BlockError::IncorrectBlockProposer { .. }
+            | BlockError::ProposalSignatureInvalid
+            | BlockError::UnknownValidator(_)
+            | BlockError::InvalidSignature
+            | BlockError::BlockIsNotLaterThanParent { .. }
+            | BlockError::NonLinearParentRoots
+            | BlockError::NonLinearSlots
+            | BlockError::PerBlockProcessingError(_) => {
                 warn!(
                     self.log,
-                    "Invalid gossip beacon block";
-                    "outcome" => format!("{:?}", other),
-                    "block root" => format!("{}", block.canonical_root()),
-                    "block slot" => block.slot()
+                    "Invalid gossip block";
+                    "peer_id" => peer_id.to_string(),
+                    "error" => format!("{:?}", error)
                 );
-                trace!(
+                MessageAcceptance::Reject
+            }
+        }
+    }
+
+    /// Process a gossip message declaring a new block.
+    ///
+    /// The block has already passed gossip verification and been forwarded to our peers, so the
+    /// full import is queued rather than delaying the router. If the queue is full the block is
+    /// dropped.
+    pub fn on_block_gossip(&mut self, peer_id: PeerId, verified_block: GossipVerifiedBlock<T>) {
+        match self.gossip_block_send.try_send((peer_id, verified_block)) {
+            Ok(()) => {}
+            Err(TrySendError::Full((peer_id, verified_block))) => {
+                metrics::inc_counter(&metrics::GOSSIP_BLOCKS_DROPPED);
+                warn!(
                     self.log,
-                    "Invalid gossip beacon block ssz";
-                    "ssz" => format!("0x{}", hex::encode(block.as_ssz_bytes())),
+                    "Gossip block import queue full";
+                    "block root" => format!("{}", verified_block.block_root),
+                    "peer_id" => peer_id.to_string()
                 );
             }
+            Err(TrySendError::Closed(_)) => {
+                error!(self.log, "Gossip block importer has shutdown");
+            }
         }
-        // TODO: Update with correct block gossip checking
-        true
     }
 
     /// Handle an error whilst verifying an `Attestation` or `SignedAggregateAndProof` from the
     /// network.
+    ///
+    /// Returns `MessageAcceptance::Reject` if the peer has published an invalid consensus message
+    /// and should be penalized, otherwise `MessageAcceptance::Ignore`.
     pub fn handle_attestation_verification_failure(
         &mut self,
         peer_id: PeerId,
         beacon_block_root: Hash256,
         attestation_type: &str,
         error: AttnError,
-    ) {
+    ) -> MessageAcceptance {
         debug!(
             self.log,
             "Invalid attestation from network";
@@ -612,6 +666,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message, _only_ if we trust our own clock.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::InvalidSelectionProof { .. } | AttnError::InvalidSignature => {
                 /*
@@ -619,6 +674,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::EmptyAggregationBitfield => {
                 /*
@@ -631,6 +687,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * https://github.com/ethereum/eth2.0-specs/pull/1732
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::AggregatorPubkeyUnknown(_) => {
                 /*
@@ -646,6 +703,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AggregatorNotInCommittee { .. } => {
                 /*
@@ -661,6 +719,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AttestationAlreadyKnown { .. } => {
                 /*
@@ -669,6 +728,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::AggregatorAlreadyKnown(_) => {
                 /*
@@ -677,6 +737,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::PriorAttestationKnown { .. } => {
                 /*
@@ -684,6 +745,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer is not necessarily faulty.
                  */
+                MessageAcceptance::Ignore
             }
             AttnError::ValidatorIndexTooHigh(_) => {
                 /*
@@ -692,6 +754,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::UnknownHeadBlock { beacon_block_root } => {
                 // Note: its a little bit unclear as to whether or not this block is unknown or
@@ -708,6 +771,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                 );
                 // we don't know the block, get the sync manager to handle the block lookup
                 self.send_to_sync(SyncMessage::UnknownBlockHash(peer_id, beacon_block_root));
+                MessageAcceptance::Ignore
             }
             AttnError::UnknownTargetRoot(_) => {
                 /*
@@ -726,14 +790,15 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::BadTargetEpoch => {
                 /*
-                 * The aggregator index (or similar field) was higher than the maximum
-                 * possible number of validators.
+                 * The attestation's target epoch is not the epoch of its slot.
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::NoCommitteeForSlotAndIndex { .. } => {
                 /*
@@ -741,6 +806,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::NotExactlyOneAggregationBitSet(_) => {
                 /*
@@ -748,6 +814,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::AttestsToFutureBlock { .. } => {
                 /*
@@ -755,18 +822,22 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
 
             AttnError::InvalidSubnetId { received, expected } => {
                 /*
                  * The attestation was received on an incorrect subnet id.
+                 *
+                 * The peer has published an invalid consensus message.
                  */
                 debug!(
                     self.log,
                     "Received attestation on incorrect subnet";
                     "expected" => format!("{:?}", expected),
                     "received" => format!("{:?}", received),
-                )
+                );
+                MessageAcceptance::Reject
             }
             AttnError::Invalid(_) => {
                 /*
@@ -774,6 +845,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
+                MessageAcceptance::Reject
             }
             AttnError::BeaconChainError(e) => {
                 /*
@@ -789,6 +861,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer_id" => peer_id.to_string(),
                     "error" => format!("{:?}", e),
                 );
+                MessageAcceptance::Ignore
             }
         }
     }
@@ -797,7 +870,7 @@ impl<T: BeaconChainTypes> Processor<T> {
         &mut self,
         peer_id: PeerId,
         aggregate_and_proof: SignedAggregateAndProof<T::EthSpec>,
-    ) -> Option<VerifiedAggregatedAttestation<T>> {
+    ) -> Result<VerifiedAggregatedAttestation<T>, MessageAcceptance> {
         // This is provided to the error handling function to assist with debugging.
         let beacon_block_root = aggregate_and_proof.message.aggregate.data.beacon_block_root;
 
@@ -811,7 +884,6 @@ impl<T: BeaconChainTypes> Processor<T> {
                     e,
                 )
             })
-            .ok()
     }
 
     pub fn import_aggregated_attestation(
@@ -844,7 +916,7 @@ impl<T: BeaconChainTypes> Processor<T> {
         peer_id: PeerId,
         unaggregated_attestation: Attestation<T::EthSpec>,
         subnet_id: SubnetId,
-    ) -> Option<VerifiedUnaggregatedAttestation<T>> {
+    ) -> Result<VerifiedUnaggregatedAttestation<T>, MessageAcceptance> {
         // This is provided to the error handling function to assist with debugging.
         let beacon_block_root = unaggregated_attestation.data.beacon_block_root;
 
@@ -858,7 +930,6 @@ impl<T: BeaconChainTypes> Processor<T> {
                     e,
                 )
             })
-            .ok()
     }
 
     pub fn import_unaggregated_attestation(
@@ -928,16 +999,17 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify a voluntary exit before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level. Returns `MessageAcceptance::Reject` if the exit is
+    /// provably invalid and the peer should be penalized, otherwise `MessageAcceptance::Ignore`.
     pub fn verify_voluntary_exit_for_gossip(
         &self,
         peer_id: &PeerId,
         voluntary_exit: SignedVoluntaryExit,
-    ) -> Option<SigVerifiedOp<SignedVoluntaryExit>> {
+    ) -> Result<SigVerifiedOp<SignedVoluntaryExit>, MessageAcceptance> {
         let validator_index = voluntary_exit.message.validator_index;
 
         match self.chain.verify_voluntary_exit_for_gossip(voluntary_exit) {
-            Ok(ObservationOutcome::New(sig_verified_exit)) => Some(sig_verified_exit),
+            Ok(ObservationOutcome::New(sig_verified_exit)) => Ok(sig_verified_exit),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -945,7 +1017,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "validator_index" => validator_index,
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -955,7 +1027,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(operation_verification_acceptance(&e))
             }
         }
     }
@@ -971,19 +1043,20 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify a proposer slashing before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level. Returns `MessageAcceptance::Reject` if the slashing is
+    /// provably invalid and the peer should be penalized, otherwise `MessageAcceptance::Ignore`.
     pub fn verify_proposer_slashing_for_gossip(
         &self,
         peer_id: &PeerId,
         proposer_slashing: ProposerSlashing,
-    ) -> Option<SigVerifiedOp<ProposerSlashing>> {
+    ) -> Result<SigVerifiedOp<ProposerSlashing>, MessageAcceptance> {
         let validator_index = proposer_slashing.signed_header_1.message.proposer_index;
 
         match self
             .chain
             .verify_proposer_slashing_for_gossip(proposer_slashing)
         {
-            Ok(ObservationOutcome::New(verified_slashing)) => Some(verified_slashing),
+            Ok(ObservationOutcome::New(verified_slashing)) => Ok(verified_slashing),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -992,7 +1065,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "validator_index" => validator_index,
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -1002,7 +1075,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(operation_verification_acceptance(&e))
             }
         }
     }
@@ -1018,17 +1091,18 @@ impl<T: BeaconChainTypes> Processor<T> {
 
     /// Verify an attester slashing before gossiping or processing it.
     ///
-    /// Errors are logged at debug level.
+    /// Errors are logged at debug level. Returns `MessageAcceptance::Reject` if the slashing is
+    /// provably invalid and the peer should be penalized, otherwise `MessageAcceptance::Ignore`.
     pub fn verify_attester_slashing_for_gossip(
         &self,
         peer_id: &PeerId,
         attester_slashing: AttesterSlashing<T::EthSpec>,
-    ) -> Option<SigVerifiedOp<AttesterSlashing<T::EthSpec>>> {
+    ) -> Result<SigVerifiedOp<AttesterSlashing<T::EthSpec>>, MessageAcceptance> {
         match self
             .chain
             .verify_attester_slashing_for_gossip(attester_slashing)
         {
-            Ok(ObservationOutcome::New(verified_slashing)) => Some(verified_slashing),
+            Ok(ObservationOutcome::New(verified_slashing)) => Ok(verified_slashing),
             Ok(ObservationOutcome::AlreadyKnown) => {
                 debug!(
                     self.log,
@@ -1036,7 +1110,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "reason" => "Slashings already known for all slashed validators",
                     "peer" => peer_id.to_string()
                 );
-                None
+                Err(MessageAcceptance::Ignore)
             }
             Err(e) => {
                 debug!(
@@ -1045,7 +1119,7 @@ impl<T: BeaconChainTypes> Processor<T> {
                     "peer" => peer_id.to_string(),
                     "error" => format!("{:?}", e)
                 );
-                None
+                Err(operation_verification_acceptance(&e))
             }
         }
     }
@@ -1063,6 +1137,212 @@ impl<T: BeaconChainTypes> Processor<T> {
     }
 }
 
+/// Spawns a task which imports queued gossip blocks one at a time, each on a blocking thread.
+///
+/// If an import shows a block to be invalid, the peer that sent it is penalized.
+fn spawn_gossip_block_importer<T: BeaconChainTypes>(
+    executor: &environment::TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+    sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+    network: HandlerNetworkContext<T::EthSpec>,
+    mut gossip_block_recv: mpsc::Receiver<(PeerId, GossipVerifiedBlock<T>)>,
+    log: slog::Logger,
+) {
+    let handle = executor.runtime_handle();
+    executor.spawn(
+        async move {
+            while let Some((peer_id, verified_block)) = gossip_block_recv.recv().await {
+                let chain = chain.clone();
+                let sync_send = sync_send.clone();
+                let mut network = network.clone();
+                let import_log = log.clone();
+                let import = handle.spawn_blocking(move || {
+                    import_gossip_block(
+                        &chain,
+                        &sync_send,
+                        &mut network,
+                        peer_id,
+                        verified_block,
+                        &import_log,
+                    )
+                });
+                if let Err(e) = import.await {
+                    error!(log, "Gossip block import failed"; "error" => format!("{:?}", e));
+                }
+            }
+        },
+        "gossip_block_import",
+    );
+}
+
+/// Fully import a block received over gossip.
+fn import_gossip_block<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    sync_send: &mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
+    network: &mut HandlerNetworkContext<T::EthSpec>,
+    peer_id: PeerId,
+    verified_block: GossipVerifiedBlock<T>,
+    log: &slog::Logger,
+) {
+    let block = Box::new(verified_block.block.clone());
+    match chain.process_block(verified_block) {
+        Ok(_block_root) => {
+            trace!(
+                log,
+                "Gossipsub block processed";
+                "peer_id" => peer_id.to_string()
+            );
+
+            match chain.fork_choice() {
+                Ok(()) => trace!(
+                    log,
+                    "Fork choice success";
+                    "location" => "block gossip"
+                ),
+                Err(e) => error!(
+                    log,
+                    "Fork choice failed";
+                    "error" => format!("{:?}", e),
+                    "location" => "block gossip"
+                ),
+            }
+        }
+        Err(BlockError::ParentUnknown { .. }) => {
+            // The parent was known when the block passed gossip verification, however it may
+            // have been pruned (e.g., by finalization) whilst the block was queued for import.
+            // Inform the sync manager so that it can look up the parent again.
+            debug!(
+                log,
+                "Parent of gossip block became unknown before import";
+                "peer_id" => peer_id.to_string()
+            );
+            sync_send
+                .send(SyncMessage::UnknownBlock(peer_id, block))
+                .unwrap_or_else(|_| warn!(log, "Could not send message to the sync service"));
+        }
+        /*
+         * The block may have been imported by sync whilst it was queued, or
+         * Lighthouse hit an unexpected error. Neither is the fault of the peer.
+         */
+        Err(e @ BlockError::FutureSlot { .. })
+        | Err(e @ BlockError::WouldRevertFinalizedSlot { .. })
+        | Err(e @ BlockError::BlockIsAlreadyKnown)
+        | Err(e @ BlockError::RepeatProposal { .. })
+        | Err(e @ BlockError::BeaconChainError(_)) => {
+            debug!(
+                log,
+                "Unable to import gossip block";
+                "outcome" => format!("{:?}", e),
+                "block root" => format!("{}", block.canonical_root()),
+                "block slot" => block.slot()
+            );
+        }
+        /*
+         * The peer has published a block which passed gossip verification but is
+         * invalid. We have already forwarded it, so penalize the peer.
+         */
+        Err(e @ BlockError::StateRootMismatch { .. })
+        | Err(e @ BlockError::GenesisBlock)
+        | Err(e @ BlockError::BlockSlotLimitReached)
+        | Err(e @ BlockError::IncorrectBlockProposer { .. })
+        | Err(e @ BlockError::ProposalSignatureInvalid)
+        | Err(e @ BlockError::UnknownValidator(_))
+        | Err(e @ BlockError::InvalidSignature)
+        | Err(e @ BlockError::BlockIsNotLaterThanParent { .. })
+        | Err(e @ BlockError::NonLinearParentRoots)
+        | Err(e @ BlockError::NonLinearSlots)
+        | Err(e @ BlockError::PerBlockProcessingError(_)) => {
+            warn!(
+                log,
+                "Invalid gossip beacon block";
+                "outcome" => format!("{:?}", e),
+                "block root" => format!("{}", block.canonical_root()),
+                "block slot" => block.slot(),
+                "peer_id" => peer_id.to_string()
+            );
+            trace!(
+                log,
+                "Invalid gossip beacon block ssz";
+                "ssz" => format!("0x{}", hex::encode(block.as_ssz_bytes())),
+            );
+            network.report_peer(peer_id, PeerAction::LowToleranceError);
+        }
+    }
+}
+
+/// Determine whether a voluntary exit or slashing which failed gossip verification should be
+/// rejected (penalizing the peer) or ignored.
+///
+/// Only operations which are invalid under any state are rejected. Failures which depend on our
+/// view of the chain (e.g., a validator that has already exited or been slashed) may be caused by
+/// a mismatch between our head and the peer's, so they are ignored.
+pub(crate) fn operation_verification_acceptance(error: &BeaconChainError) -> MessageAcceptance {
+    match error {
+        /*
+         * The peer has published an exit which is invalid under any state.
+         */
+        BeaconChainError::ExitValidationError(BlockOperationError::Invalid(
+            ExitInvalid::BadSignature,
+        ))
+        | BeaconChainError::ExitValidationError(BlockOperationError::Invalid(
+            ExitInvalid::ValidatorUnknown(_),
+        )) => MessageAcceptance::Reject,
+        /*
+         * The peer has published a proposer slashing which is invalid under any state.
+         */
+        BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::ProposerUnknown(_),
+        ))
+        | BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::ProposalSlotMismatch(..),
+        ))
+        | BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::ProposerIndexMismatch(..),
+        ))
+        | BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::ProposalsIdentical,
+        ))
+        | BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::BadProposal1Signature,
+        ))
+        | BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(
+            ProposerSlashingInvalid::BadProposal2Signature,
+        )) => MessageAcceptance::Reject,
+        /*
+         * The peer has published an attester slashing which is invalid under any state.
+         */
+        BeaconChainError::AttesterSlashingValidationError(BlockOperationError::Invalid(
+            AttesterSlashingInvalid::NotSlashable,
+        ))
+        | BeaconChainError::AttesterSlashingValidationError(BlockOperationError::Invalid(
+            AttesterSlashingInvalid::UnknownValidator(_),
+        )) => MessageAcceptance::Reject,
+        BeaconChainError::AttesterSlashingValidationError(BlockOperationError::Invalid(
+            AttesterSlashingInvalid::IndexedAttestation1Invalid(BlockOperationError::Invalid(
+                reason,
+            )),
+        ))
+        | BeaconChainError::AttesterSlashingValidationError(BlockOperationError::Invalid(
+            AttesterSlashingInvalid::IndexedAttestation2Invalid(BlockOperationError::Invalid(
+                reason,
+            )),
+        )) => match reason {
+            IndexedAttestationInvalid::IndicesEmpty
+            | IndexedAttestationInvalid::BadValidatorIndicesOrdering(_)
+            | IndexedAttestationInvalid::UnknownValidator(_)
+            | IndexedAttestationInvalid::BadSignature => MessageAcceptance::Reject,
+            // The signatures may have been invalid or an internal error occurred.
+            IndexedAttestationInvalid::SignatureSetError(_) => MessageAcceptance::Ignore,
+        },
+        /*
+         * The operation may become valid, may already be included in our chain, or Lighthouse
+         * hit an unexpected error whilst verifying it. None of these are provably the fault of
+         * the peer.
+         */
+        _ => MessageAcceptance::Ignore,
+    }
+}
+
 /// Build a `StatusMessage` representing the state of the given `beacon_chain`.
 pub(crate) fn status_message<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
@@ -1084,6 +1364,7 @@ pub(crate) fn status_message<T: BeaconChainTypes>(
 
 /// Wraps a Network Channel to employ various RPC related network functionality for the
 /// processor.
+#[derive(Clone)]
 pub struct HandlerNetworkContext<T: EthSpec> {
     /// The network channel to relay messages to the Network service.
     network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
@@ -1109,7 +1390,7 @@ impl<T: EthSpec> HandlerNetworkContext<T> {
     }
 
     /// Reports a peer's action, adjusting the peer's score.
-    pub fn _report_peer(&mut self, peer_id: PeerId, action: PeerAction) {
+    pub fn report_peer(&mut self, peer_id: PeerId, action: PeerAction) {
         self.inform_network(NetworkMessage::ReportPeer { peer_id, action });
     }
 
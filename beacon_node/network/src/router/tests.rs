@@ -0,0 +1,346 @@
+#[cfg(test)]
+mod tests {
+    use crate::router::processor::{operation_verification_acceptance, Processor};
+    use crate::service::NetworkMessage;
+    use beacon_chain::{
+        attestation_verification::Error as AttnError,
+        test_utils::{BeaconChainHarness, HarnessType},
+        BeaconChain, BeaconChainError,
+    };
+    use eth2_libp2p::discovery::{build_enr, CombinedKey, Keypair};
+    use eth2_libp2p::{
+        CombinedKeyExt, MessageAcceptance, NetworkConfig, NetworkGlobals, PeerAction, PeerId,
+    };
+    use slog::Logger;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use state_processing::per_block_processing::errors::{
+        AttestationInvalid, AttesterSlashingInvalid, BlockOperationError, ExitInvalid,
+        IndexedAttestationInvalid, ProposerSlashingInvalid,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+    use store::config::StoreConfig;
+    use tokio::runtime::Runtime;
+    use tokio::sync::mpsc;
+    use types::{
+        test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
+        BeaconStateError, Domain, EnrForkId, Epoch, EthSpec, Hash256, MinimalEthSpec, Signature,
+        SignedRoot, Slot, SubnetId,
+    };
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 8;
+
+    fn get_logger() -> Logger {
+        NullLoggerBuilder.build().expect("logger should build")
+    }
+
+    /// A `Processor` backed by a test harness, along with the channel it uses to talk to the
+    /// network service.
+    struct TestRig {
+        processor: Processor<HarnessType<E>>,
+        chain: Arc<BeaconChain<HarnessType<E>>>,
+        network_recv: mpsc::UnboundedReceiver<NetworkMessage<E>>,
+        runtime: Runtime,
+        _signal: exit_future::Signal,
+    }
+
+    impl TestRig {
+        fn new() -> Self {
+            let log = get_logger();
+            let chain = Arc::new(
+                BeaconChainHarness::new(
+                    MinimalEthSpec,
+                    generate_deterministic_keypairs(VALIDATOR_COUNT),
+                    StoreConfig::default(),
+                )
+                .chain,
+            );
+
+            let runtime = Runtime::new().unwrap();
+            let (signal, exit) = exit_future::signal();
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = environment::TaskExecutor::new(
+                runtime.handle().clone(),
+                exit,
+                log.clone(),
+                shutdown_tx,
+            );
+
+            let config = NetworkConfig::default();
+            let enr_key = CombinedKey::from_libp2p(&Keypair::generate_secp256k1()).unwrap();
+            let enr = build_enr::<E>(&enr_key, &config, EnrForkId::default()).unwrap();
+            let network_globals = Arc::new(NetworkGlobals::new(enr, 0, 0, &log));
+
+            let (network_send, network_recv) = mpsc::unbounded_channel();
+            let processor = runtime.enter(|| {
+                Processor::new(executor, chain.clone(), network_globals, network_send, &log)
+            });
+
+            Self {
+                processor,
+                chain,
+                network_recv,
+                runtime,
+                _signal: signal,
+            }
+        }
+
+        /// Waits for the processor to report a peer to the network service, ignoring any other
+        /// messages. Returns `None` if no peer is reported before the timeout.
+        fn next_peer_report(&mut self) -> Option<(PeerId, PeerAction)> {
+            let network_recv = &mut self.network_recv;
+            self.runtime.block_on(async {
+                tokio::time::timeout(Duration::from_secs(30), async {
+                    while let Some(message) = network_recv.recv().await {
+                        if let NetworkMessage::ReportPeer { peer_id, action } = message {
+                            return Some((peer_id, action));
+                        }
+                    }
+                    None
+                })
+                .await
+                .ok()
+                .flatten()
+            })
+        }
+    }
+
+    #[test]
+    fn invalid_gossip_block_import_reports_peer() {
+        let mut rig = TestRig::new();
+        let chain = rig.chain.clone();
+        chain.slot_clock.advance_slot();
+
+        let slot = chain.slot().unwrap();
+        let state = chain.head().unwrap().beacon_state;
+        let proposer_index = state
+            .get_beacon_proposer_index(slot, &chain.spec)
+            .expect("should get block proposer");
+        let keypair = generate_deterministic_keypair(proposer_index);
+
+        let randao_reveal = {
+            let epoch = slot.epoch(E::slots_per_epoch());
+            let domain = chain.spec.get_domain(
+                epoch,
+                Domain::Randao,
+                &state.fork,
+                state.genesis_validators_root,
+            );
+            Signature::new(epoch.signing_root(domain).as_bytes(), &keypair.sk)
+        };
+        let (mut block, _) = chain
+            .produce_block(randao_reveal, slot)
+            .expect("should produce block");
+
+        // The state root is only checked during full import, so the block passes gossip
+        // verification and is forwarded before it is found to be invalid.
+        block.state_root = Hash256::repeat_byte(42);
+        let block = block.sign(
+            &keypair.sk,
+            &state.fork,
+            state.genesis_validators_root,
+            &chain.spec,
+        );
+
+        let peer_id = PeerId::random();
+        let verified_block = rig
+            .processor
+            .should_forward_block(&peer_id, Box::new(block))
+            .expect("block should pass gossip verification");
+        rig.processor
+            .on_block_gossip(peer_id.clone(), verified_block);
+
+        let report = rig.next_peer_report();
+        assert!(
+            matches!(
+                report,
+                Some((ref reported, PeerAction::LowToleranceError)) if *reported == peer_id
+            ),
+            "should penalize the peer, got {:?}",
+            report
+        );
+    }
+
+    #[test]
+    fn invalid_attestations_are_rejected() {
+        let mut rig = TestRig::new();
+
+        let rejected = vec![
+            AttnError::InvalidSelectionProof {
+                aggregator_index: 0,
+            },
+            AttnError::InvalidSignature,
+            AttnError::AggregatorPubkeyUnknown(42),
+            AttnError::AggregatorNotInCommittee {
+                aggregator_index: 0,
+            },
+            AttnError::ValidatorIndexTooHigh(42),
+            AttnError::UnknownTargetRoot(Hash256::zero()),
+            AttnError::BadTargetEpoch,
+            AttnError::NoCommitteeForSlotAndIndex {
+                slot: Slot::new(0),
+                index: 42,
+            },
+            AttnError::NotExactlyOneAggregationBitSet(2),
+            AttnError::AttestsToFutureBlock {
+                block: Slot::new(2),
+                attestation: Slot::new(1),
+            },
+            AttnError::InvalidSubnetId {
+                received: SubnetId::new(1),
+                expected: SubnetId::new(2),
+            },
+            AttnError::Invalid(BlockOperationError::Invalid(
+                AttestationInvalid::BadSignature,
+            )),
+        ];
+
+        for error in rejected {
+            let description = format!("{:?}", error);
+            assert_eq!(
+                rig.processor.handle_attestation_verification_failure(
+                    PeerId::random(),
+                    Hash256::zero(),
+                    "unaggregated",
+                    error
+                ),
+                MessageAcceptance::Reject,
+                "{} should be rejected",
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn attestations_which_are_not_provably_invalid_are_ignored() {
+        let mut rig = TestRig::new();
+
+        let ignored = vec![
+            AttnError::FutureSlot {
+                attestation_slot: Slot::new(2),
+                latest_permissible_slot: Slot::new(1),
+            },
+            AttnError::PastSlot {
+                attestation_slot: Slot::new(1),
+                earliest_permissible_slot: Slot::new(2),
+            },
+            AttnError::FutureEpoch {
+                attestation_epoch: Epoch::new(2),
+                current_epoch: Epoch::new(1),
+            },
+            AttnError::PastEpoch {
+                attestation_epoch: Epoch::new(1),
+                current_epoch: Epoch::new(2),
+            },
+            AttnError::EmptyAggregationBitfield,
+            AttnError::AttestationAlreadyKnown(Hash256::zero()),
+            AttnError::AggregatorAlreadyKnown(0),
+            AttnError::PriorAttestationKnown {
+                validator_index: 0,
+                epoch: Epoch::new(0),
+            },
+            AttnError::UnknownHeadBlock {
+                beacon_block_root: Hash256::repeat_byte(42),
+            },
+            AttnError::BeaconChainError(BeaconChainError::CanonicalHeadLockTimeout),
+        ];
+
+        for error in ignored {
+            let description = format!("{:?}", error);
+            assert_eq!(
+                rig.processor.handle_attestation_verification_failure(
+                    PeerId::random(),
+                    Hash256::zero(),
+                    "unaggregated",
+                    error
+                ),
+                MessageAcceptance::Ignore,
+                "{} should be ignored",
+                description
+            );
+        }
+    }
+
+    fn exit_error(reason: ExitInvalid) -> BeaconChainError {
+        BeaconChainError::ExitValidationError(BlockOperationError::Invalid(reason))
+    }
+
+    fn proposer_slashing_error(reason: ProposerSlashingInvalid) -> BeaconChainError {
+        BeaconChainError::ProposerSlashingValidationError(BlockOperationError::Invalid(reason))
+    }
+
+    fn attester_slashing_error(reason: AttesterSlashingInvalid) -> BeaconChainError {
+        BeaconChainError::AttesterSlashingValidationError(BlockOperationError::Invalid(reason))
+    }
+
+    #[test]
+    fn invalid_operations_are_rejected() {
+        let rejected = vec![
+            exit_error(ExitInvalid::BadSignature),
+            exit_error(ExitInvalid::ValidatorUnknown(42)),
+            proposer_slashing_error(ProposerSlashingInvalid::ProposerUnknown(42)),
+            proposer_slashing_error(ProposerSlashingInvalid::ProposalSlotMismatch(
+                Slot::new(1),
+                Slot::new(2),
+            )),
+            proposer_slashing_error(ProposerSlashingInvalid::ProposerIndexMismatch(1, 2)),
+            proposer_slashing_error(ProposerSlashingInvalid::ProposalsIdentical),
+            proposer_slashing_error(ProposerSlashingInvalid::BadProposal1Signature),
+            proposer_slashing_error(ProposerSlashingInvalid::BadProposal2Signature),
+            attester_slashing_error(AttesterSlashingInvalid::NotSlashable),
+            attester_slashing_error(AttesterSlashingInvalid::UnknownValidator(42)),
+            attester_slashing_error(AttesterSlashingInvalid::IndexedAttestation1Invalid(
+                BlockOperationError::Invalid(IndexedAttestationInvalid::BadSignature),
+            )),
+            attester_slashing_error(AttesterSlashingInvalid::IndexedAttestation2Invalid(
+                BlockOperationError::Invalid(IndexedAttestationInvalid::IndicesEmpty),
+            )),
+        ];
+
+        for error in rejected {
+            assert_eq!(
+                operation_verification_acceptance(&error),
+                MessageAcceptance::Reject,
+                "{:?} should be rejected",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn state_dependent_operation_failures_are_ignored() {
+        let ignored = vec![
+            exit_error(ExitInvalid::AlreadyExited(42)),
+            exit_error(ExitInvalid::AlreadyInitiatedExit(42)),
+            exit_error(ExitInvalid::FutureEpoch {
+                state: Epoch::new(1),
+                exit: Epoch::new(2),
+            }),
+            exit_error(ExitInvalid::TooYoungToExit {
+                current_epoch: Epoch::new(1),
+                earliest_exit_epoch: Epoch::new(2),
+            }),
+            proposer_slashing_error(ProposerSlashingInvalid::ProposerNotSlashable(42)),
+            attester_slashing_error(AttesterSlashingInvalid::ValidatorAlreadyWithdrawn(42)),
+            attester_slashing_error(AttesterSlashingInvalid::NoSlashableIndices),
+            attester_slashing_error(AttesterSlashingInvalid::IndexedAttestation1Invalid(
+                BlockOperationError::BeaconStateError(BeaconStateError::UnknownValidator(42)),
+            )),
+            BeaconChainError::ExitValidationError(BlockOperationError::BeaconStateError(
+                BeaconStateError::UnknownValidator(42),
+            )),
+            BeaconChainError::CanonicalHeadLockTimeout,
+        ];
+
+        for error in ignored {
+            assert_eq!(
+                operation_verification_acceptance(&error),
+                MessageAcceptance::Ignore,
+                "{:?} should be ignored",
+                error
+            );
+        }
+    }
+}
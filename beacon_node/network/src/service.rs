@@ -1,5 +1,5 @@
 use crate::persisted_dht::{load_dht, persist_dht};
-use crate::router::{Router, RouterMessage};
+use crate::router::{Router, RouterMessage, RouterSender};
 use crate::{
     attestation_service::{AttServiceMessage, AttestationService},
     NetworkConfig,
@@ -11,19 +11,25 @@ use eth2_libp2p::{
     rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId},
     Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
 };
-use eth2_libp2p::{BehaviourEvent, MessageId, NetworkGlobals, PeerId};
+use eth2_libp2p::{
+    types::GossipKind, BehaviourEvent, MessageAcceptance, MessageId, NetworkGlobals, PeerId, Subnet,
+};
 use futures::prelude::*;
+use rand::Rng;
 use rest_types::ValidatorSubscription;
 use slog::{debug, error, info, o, trace, warn};
 use std::sync::Arc;
 use std::time::Duration;
 use store::HotColdDB;
 use tokio::sync::mpsc;
-use tokio::time::Delay;
+use tokio::time::{Delay, DelayQueue};
 use types::EthSpec;
 
 mod tests;
 
+/// The maximum random delay applied to messages published by a node in private mode.
+const MAX_PRIVATE_PUBLISH_DELAY: Duration = Duration::from_millis(200);
+
 /// Types of messages that the network service can receive.
 #[derive(Debug)]
 pub enum NetworkMessage<T: EthSpec> {
@@ -54,10 +60,12 @@ pub enum NetworkMessage<T: EthSpec> {
     },
     /// Publish a list of messages to the gossipsub protocol.
     Publish { messages: Vec<PubsubMessage<T>> },
-    /// Propagate a received gossipsub message.
-    Propagate {
+    /// Report the result of validating a received gossipsub message. Accepted messages are
+    /// propagated to our peers.
+    Validate {
         propagation_source: PeerId,
         message_id: MessageId,
+        validation_result: MessageAcceptance,
     },
     /// Reports a peer to the peer manager for performing an action.
     ReportPeer { peer_id: PeerId, action: PeerAction },
@@ -80,13 +88,18 @@ pub struct NetworkService<T: BeaconChainTypes> {
     network_recv: mpsc::UnboundedReceiver<NetworkMessage<T::EthSpec>>,
     /// The sending channel for the network service to send messages to be routed throughout
     /// lighthouse.
-    router_send: mpsc::UnboundedSender<RouterMessage<T::EthSpec>>,
+    router_send: RouterSender<T::EthSpec>,
     /// A reference to lighthouse's database to persist the DHT.
     store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
     /// A collection of global variables, accessible outside of the network service.
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     /// A delay that expires when a new fork takes place.
     next_fork_update: Option<Delay>,
+    /// If true, published messages are delayed by a random amount so that their timing does not
+    /// reveal that they originated from this node.
+    private: bool,
+    /// Messages waiting to be published, when running in private mode.
+    delayed_publishes: DelayQueue<Vec<PubsubMessage<T::EthSpec>>>,
     /// The logger for the network service.
     log: slog::Logger,
 }
@@ -133,13 +146,18 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             beacon_chain.clone(),
             network_globals.clone(),
             network_send.clone(),
+            config,
             executor.clone(),
             network_log.clone(),
         )?;
 
         // attestation service
-        let attestation_service =
-            AttestationService::new(beacon_chain.clone(), network_globals.clone(), &network_log);
+        let attestation_service = AttestationService::new(
+            beacon_chain.clone(),
+            network_globals.clone(),
+            config,
+            &network_log,
+        );
 
         // create the network service and spawn the task
         let network_log = network_log.new(o!("service"=> "network"));
@@ -152,6 +170,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             store,
             network_globals: network_globals.clone(),
             next_fork_update,
+            private: config.private,
+            delayed_publishes: DelayQueue::new(),
             log: network_log,
         };
 
@@ -212,34 +232,35 @@ fn spawn_service<T: BeaconChainTypes>(
                         NetworkMessage::SendError{ peer_id, error, id, reason } => {
                             service.libp2p.respond_with_error(peer_id, id, error, reason);
                         }
-                        NetworkMessage::Propagate {
+                        NetworkMessage::Validate {
                             propagation_source,
                             message_id,
+                            validation_result,
                         } => {
-                                trace!(service.log, "Propagating gossipsub message";
+                                trace!(service.log, "Validated gossipsub message";
                                     "propagation_peer" => format!("{:?}", propagation_source),
                                     "message_id" => message_id.to_string(),
+                                    "validation_result" => format!("{:?}", validation_result),
                                 );
                                 service
                                     .libp2p
                                     .swarm
-                                    .propagate_message(&propagation_source, message_id);
+                                    .report_message_validation_result(
+                                        &propagation_source,
+                                        message_id,
+                                        validation_result,
+                                    );
                         }
                         NetworkMessage::Publish { messages } => {
-                                let mut topic_kinds = Vec::new();
-                                for message in &messages {
-                                    if !topic_kinds.contains(&message.kind()) {
-                                        topic_kinds.push(message.kind());
-                                    }
-                                }
-                                debug!(
-                                    service.log,
-                                    "Sending pubsub messages";
-                                    "count" => messages.len(),
-                                    "topics" => format!("{:?}", topic_kinds)
+                            if service.private {
+                                let delay = rand::thread_rng().gen_range(
+                                    Duration::from_millis(0),
+                                    MAX_PRIVATE_PUBLISH_DELAY,
                                 );
-                                expose_publish_metrics(&messages);
-                                service.libp2p.swarm.publish(messages);
+                                service.delayed_publishes.insert(messages, delay);
+                            } else {
+                                publish(&mut service, messages);
+                            }
                         }
                         NetworkMessage::ReportPeer { peer_id, action } => service.libp2p.report_peer(&peer_id, action),
                         NetworkMessage::GoodbyePeer { peer_id, reason } => service.libp2p.goodbye_peer(&peer_id, reason),
@@ -252,24 +273,30 @@ fn spawn_service<T: BeaconChainTypes>(
                         }
                     }
                 }
+                // publish any messages which were delayed in private mode
+                Some(Ok(expired)) = service.delayed_publishes.next() => {
+                    publish(&mut service, expired.into_inner());
+                }
                 // process any attestation service events
                 Some(attestation_service_message) = service.attestation_service.next() => {
                     match attestation_service_message {
                         // TODO: Implement
                         AttServiceMessage::Subscribe(subnet_id) => {
                             service.libp2p.swarm.subscribe_to_subnet(subnet_id);
+                            expose_subnet_subscription_metrics(&service.network_globals);
                         }
                         AttServiceMessage::Unsubscribe(subnet_id) => {
-                            service.libp2p.swarm.subscribe_to_subnet(subnet_id);
+                            service.libp2p.swarm.unsubscribe_from_subnet(subnet_id);
+                            expose_subnet_subscription_metrics(&service.network_globals);
                         }
                         AttServiceMessage::EnrAdd(subnet_id) => {
-                            service.libp2p.swarm.update_enr_subnet(subnet_id, true);
+                            service.libp2p.swarm.update_enr_subnet(Subnet::Attestation(subnet_id), true);
                         }
                         AttServiceMessage::EnrRemove(subnet_id) => {
-                            service.libp2p.swarm.update_enr_subnet(subnet_id, false);
+                            service.libp2p.swarm.update_enr_subnet(Subnet::Attestation(subnet_id), false);
                         }
                         AttServiceMessage::DiscoverPeers{subnet_id, min_ttl} => {
-                            service.libp2p.swarm.discover_subnet_peers(subnet_id, min_ttl);
+                            service.libp2p.swarm.discover_subnet_peers(Subnet::Attestation(subnet_id), min_ttl);
                         }
                     }
                 }
@@ -395,6 +422,27 @@ fn spawn_service<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Publishes `messages` to gossipsub, updating logs and metrics.
+fn publish<T: BeaconChainTypes>(
+    service: &mut NetworkService<T>,
+    messages: Vec<PubsubMessage<T::EthSpec>>,
+) {
+    let mut topic_kinds = Vec::new();
+    for message in &messages {
+        if !topic_kinds.contains(&message.kind()) {
+            topic_kinds.push(message.kind());
+        }
+    }
+    debug!(
+        service.log,
+        "Sending pubsub messages";
+        "count" => messages.len(),
+        "topics" => format!("{:?}", topic_kinds)
+    );
+    expose_publish_metrics(&messages);
+    service.libp2p.swarm.publish(messages);
+}
+
 /// Returns a `Delay` that triggers shortly after the next change in the beacon chain fork version.
 /// If there is no scheduled fork, `None` is returned.
 fn next_fork_delay<T: BeaconChainTypes>(
@@ -424,6 +472,20 @@ fn expose_publish_metrics<T: EthSpec>(messages: &[PubsubMessage<T>]) {
 }
 
 /// Inspects a `message` received from the network and updates Prometheus metrics.
+/// Updates the number of attestation subnets the node is subscribed to.
+fn expose_subnet_subscription_metrics<T: EthSpec>(network_globals: &NetworkGlobals<T>) {
+    let subnets = network_globals
+        .gossipsub_subscriptions
+        .read()
+        .iter()
+        .filter(|topic| match topic.kind() {
+            GossipKind::Attestation(_) => true,
+            _ => false,
+        })
+        .count();
+    metrics::set_gauge(&metrics::ATTESTATION_SUBNETS_SUBSCRIBED, subnets as i64);
+}
+
 fn expose_receive_metrics<T: EthSpec>(message: &PubsubMessage<T>) {
     match message {
         PubsubMessage::BeaconBlock(_) => metrics::inc_counter(&metrics::GOSSIP_BLOCKS_RX),
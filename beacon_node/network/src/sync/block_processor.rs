@@ -1,8 +1,7 @@
-use crate::router::processor::FUTURE_SLOT_TOLERANCE;
 use crate::sync::manager::SyncMessage;
 use crate::sync::range_sync::{BatchId, ChainId};
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError, ChainSegmentResult};
-use eth2_libp2p::PeerId;
+use eth2_libp2p::{PeerAction, PeerId};
 use slog::{debug, error, trace, warn};
 use std::sync::{Arc, Weak};
 use tokio::sync::mpsc;
@@ -18,15 +17,26 @@ pub enum ProcessId {
 }
 
 /// The result of a block processing request.
-// TODO: When correct batch error handling occurs, we will include an error type.
 #[derive(Debug)]
 pub enum BatchProcessResult {
     /// The batch was completed successfully.
     Success,
     /// The batch processing failed.
-    Failed,
+    ///
+    /// If `peer_action` is `Some`, the batch contained a block which is invalid in its own right
+    /// (rather than, e.g., referencing an unknown parent) and the peer that sent it should be
+    /// penalized.
+    Failed { peer_action: Option<PeerAction> },
     /// The batch processing failed but managed to import at least one block.
-    Partial,
+    Partial { peer_action: Option<PeerAction> },
+}
+
+/// Describes why a chain segment could not be processed.
+struct ChainSegmentFailed {
+    /// A description of the failure, for logging.
+    message: String,
+    /// The penalty to apply to the peer that sent the segment, if it is at fault.
+    peer_action: Option<PeerAction>,
 }
 
 /// Spawns a thread handling the block processing of a request: range syncing or parent lookup.
@@ -61,12 +71,16 @@ pub fn spawn_block_processor<T: BeaconChainTypes>(
                     }
                     (imported_blocks, Err(e)) if imported_blocks > 0 => {
                         warn!(log, "Batch processing failed but imported some blocks";
-                            "id" => *batch_id, "error" => e, "imported_blocks"=> imported_blocks);
-                        BatchProcessResult::Partial
+                            "id" => *batch_id, "error" => e.message, "imported_blocks"=> imported_blocks);
+                        BatchProcessResult::Partial {
+                            peer_action: e.peer_action,
+                        }
                     }
                     (_, Err(e)) => {
-                        warn!(log, "Batch processing failed"; "id" => *batch_id, "error" => e);
-                        BatchProcessResult::Failed
+                        warn!(log, "Batch processing failed"; "id" => *batch_id, "error" => e.message);
+                        BatchProcessResult::Failed {
+                            peer_action: e.peer_action,
+                        }
                     }
                 };
 
@@ -94,7 +108,7 @@ pub fn spawn_block_processor<T: BeaconChainTypes>(
                 // reverse
                 match process_blocks(chain, downloaded_blocks.iter().rev(), &log) {
                     (_, Err(e)) => {
-                        warn!(log, "Parent lookup failed"; "last_peer_id" => format!("{}", peer_id), "error" => e);
+                        warn!(log, "Parent lookup failed"; "last_peer_id" => format!("{}", peer_id), "error" => e.message);
                         sync_send
                         .send(SyncMessage::ParentLookupFailed(peer_id))
                         .unwrap_or_else(|_| {
@@ -123,7 +137,7 @@ fn process_blocks<
     chain: Weak<BeaconChain<T>>,
     downloaded_blocks: I,
     log: &slog::Logger,
-) -> (usize, Result<(), String>) {
+) -> (usize, Result<(), ChainSegmentFailed>) {
     if let Some(chain) = chain.upgrade() {
         let blocks = downloaded_blocks.cloned().collect::<Vec<_>>();
         let (imported_blocks, r) = match chain.process_chain_segment(blocks) {
@@ -144,7 +158,15 @@ fn process_blocks<
             ChainSegmentResult::Failed {
                 imported_blocks,
                 error,
+                failed_block_root,
             } => {
+                if let Some(block_root) = failed_block_root {
+                    debug!(
+                        log, "Chain segment contained a failed block";
+                        "block_root" => format!("{}", block_root),
+                        "imported_blocks" => imported_blocks,
+                    );
+                }
                 let r = handle_failed_chain_segment(error, log);
                 if imported_blocks > 0 {
                     run_fork_choice(chain, log);
@@ -178,12 +200,20 @@ fn run_fork_choice<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>, log: &slog::
 }
 
 /// Helper function to handle a `BlockError` from `process_chain_segment`
-fn handle_failed_chain_segment(error: BlockError, log: &slog::Logger) -> Result<(), String> {
+fn handle_failed_chain_segment(
+    error: BlockError,
+    log: &slog::Logger,
+) -> Result<(), ChainSegmentFailed> {
     match error {
         BlockError::ParentUnknown(parent) => {
             // blocks should be sequential and all parents should exist
 
-            Err(format!("Block has an unknown parent: {}", parent))
+            // It's not possible to know whether this peer or the sender of a previous batch is at
+            // fault, so don't penalize the peer here.
+            Err(ChainSegmentFailed {
+                message: format!("Block has an unknown parent: {}", parent),
+                peer_action: None,
+            })
         }
         BlockError::BlockIsAlreadyKnown => {
             // This can happen for many reasons. Head sync's can download multiples and parent
@@ -194,29 +224,21 @@ fn handle_failed_chain_segment(error: BlockError, log: &slog::Logger) -> Result<
             present_slot,
             block_slot,
         } => {
-            if present_slot + FUTURE_SLOT_TOLERANCE >= block_slot {
-                // The block is too far in the future, drop it.
-                warn!(
-                    log, "Block is ahead of our slot clock";
-                    "msg" => "block for future slot rejected, check your time",
-                    "present_slot" => present_slot,
-                    "block_slot" => block_slot,
-                    "FUTURE_SLOT_TOLERANCE" => FUTURE_SLOT_TOLERANCE,
-                );
-            } else {
-                // The block is in the future, but not too far.
-                debug!(
-                    log, "Block is slightly ahead of our slot clock, ignoring.";
-                    "present_slot" => present_slot,
-                    "block_slot" => block_slot,
-                    "FUTURE_SLOT_TOLERANCE" => FUTURE_SLOT_TOLERANCE,
-                );
-            }
+            // The `present_slot` already includes the tolerated clock disparity.
+            warn!(
+                log, "Block is ahead of our slot clock";
+                "msg" => "block for future slot rejected, check your time",
+                "present_slot" => present_slot,
+                "block_slot" => block_slot,
+            );
 
-            Err(format!(
-                "Block with slot {} is higher than the current slot {}",
-                block_slot, present_slot
-            ))
+            Err(ChainSegmentFailed {
+                message: format!(
+                    "Block with slot {} is higher than the current slot {}",
+                    block_slot, present_slot
+                ),
+                peer_action: None,
+            })
         }
         BlockError::WouldRevertFinalizedSlot { .. } => {
             debug!( log, "Finalized or earlier block processed";);
@@ -234,7 +256,10 @@ fn handle_failed_chain_segment(error: BlockError, log: &slog::Logger) -> Result<
                 "outcome" => format!("{:?}", e)
             );
 
-            Err(format!("Internal error whilst processing block: {:?}", e))
+            Err(ChainSegmentFailed {
+                message: format!("Internal error whilst processing block: {:?}", e),
+                peer_action: None,
+            })
         }
         other => {
             warn!(
@@ -243,7 +268,10 @@ fn handle_failed_chain_segment(error: BlockError, log: &slog::Logger) -> Result<
                 "outcome" => format!("{:?}", other),
             );
 
-            Err(format!("Peer sent invalid block. Reason: {:?}", other))
+            Err(ChainSegmentFailed {
+                message: format!("Peer sent invalid block. Reason: {:?}", other),
+                peer_action: Some(PeerAction::LowToleranceError),
+            })
         }
     }
 }
@@ -50,6 +50,7 @@ use ssz_types::VariableList;
 use std::boxed::Box;
 use std::ops::Sub;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -64,6 +65,9 @@ const PARENT_FAIL_TOLERANCE: usize = 5;
 /// is further back than the most recent head slot.
 const PARENT_DEPTH_TOLERANCE: usize = SLOT_IMPORT_TOLERANCE * 2;
 
+/// How often the sync state is re-evaluated in the absence of any other sync events.
+const SYNC_STATE_UPDATE_INTERVAL: Duration = Duration::from_secs(12);
+
 #[derive(Debug)]
 /// A message than can be sent to the sync manager thread.
 pub enum SyncMessage<T: EthSpec> {
@@ -738,9 +742,21 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
     /// The main driving future for the sync manager.
     async fn main(&mut self) {
+        // The transition from `Synced` to `Stalled` is delayed, so it may not be triggered by an
+        // inbound message. Periodically re-evaluate the sync state to ensure it happens.
+        let mut sync_state_interval = tokio::time::interval(SYNC_STATE_UPDATE_INTERVAL);
+
         // process any inbound messages
         loop {
-            if let Some(sync_message) = self.input_channel.recv().await {
+            let sync_message = tokio::select! {
+                sync_message = self.input_channel.recv() => sync_message,
+                _ = sync_state_interval.tick() => {
+                    self.update_sync_state();
+                    continue;
+                }
+            };
+
+            if let Some(sync_message) = sync_message {
                 match sync_message {
                     SyncMessage::AddPeer(peer_id, info) => {
                         self.add_peer(peer_id, info);
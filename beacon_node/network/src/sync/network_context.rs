@@ -8,6 +8,7 @@ use eth2_libp2p::rpc::{BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason,
 use eth2_libp2p::{Client, NetworkGlobals, PeerAction, PeerId, Request};
 use slog::{debug, trace, warn};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use types::EthSpec;
 
@@ -50,6 +51,34 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             .unwrap_or_default()
     }
 
+    /// Returns `peer_ids` ordered by their usefulness for downloading blocks, best first.
+    /// Quarantined peers are omitted unless no other peers are available.
+    pub fn best_sync_peers<'a>(&self, peer_ids: impl Iterator<Item = &'a PeerId>) -> Vec<PeerId> {
+        self.network_globals.peers.read().best_sync_peers(peer_ids)
+    }
+
+    /// Records the time taken by `peer_id` to respond to a batch request.
+    pub fn record_batch_response_time(&self, peer_id: &PeerId, response_time: Duration) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.sync_stats.record_response_time(response_time);
+        }
+    }
+
+    /// Records whether a batch sent by `peer_id` was valid, quarantining peers which repeatedly
+    /// send invalid batches.
+    pub fn record_batch_validity(&self, peer_id: &PeerId, valid: bool) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            if valid {
+                info.sync_stats.record_valid_batch();
+            } else {
+                info.sync_stats.record_invalid_batch();
+                if info.sync_stats.is_quarantined() {
+                    debug!(self.log, "Peer quarantined from sync"; "peer_id" => peer_id.to_string());
+                }
+            }
+        }
+    }
+
     pub fn status_peer<U: BeaconChainTypes>(
         &mut self,
         chain: Arc<BeaconChain<U>>,
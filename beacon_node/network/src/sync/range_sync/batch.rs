@@ -9,6 +9,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Sub;
+use std::time::Instant;
 use types::{EthSpec, SignedBeaconBlock, Slot};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -52,6 +53,8 @@ pub struct Batch<T: EthSpec> {
     pub reprocess_retries: u8,
     /// Marks the batch as undergoing a re-process, with a hash of the original blocks it received.
     pub original_hash: Option<u64>,
+    /// The time at which the batch was last requested.
+    pub request_time: Option<Instant>,
     /// The blocks that have been downloaded.
     pub downloaded_blocks: Vec<SignedBeaconBlock<T>>,
 }
@@ -69,6 +72,7 @@ impl<T: EthSpec> Batch<T> {
             retries: 0,
             reprocess_retries: 0,
             original_hash: None,
+            request_time: None,
             downloaded_blocks: Vec::new(),
         }
     }
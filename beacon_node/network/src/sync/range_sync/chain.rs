@@ -4,10 +4,10 @@ use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::{RequestId, SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{PeerAction, PeerId};
-use rand::prelude::*;
 use slog::{crit, debug, warn};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -183,6 +183,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // blocks for the peer.
         debug!(self.log, "Completed batch received"; "id"=> *batch.id, "blocks" => &batch.downloaded_blocks.len(), "awaiting_batches" => self.completed_batches.len());
 
+        if let Some(request_time) = batch.request_time {
+            network.record_batch_response_time(&batch.current_peer, request_time.elapsed());
+        }
+
         // verify the range of received blocks
         // Note that the order of blocks is verified in block processing
         if let Some(last_slot) = batch.downloaded_blocks.last().map(|b| b.slot()) {
@@ -326,6 +330,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 // If the processed batch was not empty, we can validate previous invalidated
                 // blocks
                 if !batch.downloaded_blocks.is_empty() {
+                    network.record_batch_validity(&batch.current_peer, true);
                     self.mark_processed_batches_as_valid(network, &batch);
                 }
 
@@ -354,10 +359,13 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     ProcessingResult::KeepChain
                 }
             }
-            BatchProcessResult::Partial => {
+            BatchProcessResult::Partial { peer_action } => {
                 warn!(self.log, "Batch processing failed but at least one block was imported";
                     "chain_id" => self.id, "id" => *batch.id, "peer" => format!("{}", batch.current_peer)
                 );
+                if let Some(action) = peer_action {
+                    self.penalize_batch_peer(network, &batch, *action);
+                }
                 // At least one block was successfully verified and imported, so we can be sure all
                 // previous batches are valid and we only need to download the current failed
                 // batch.
@@ -385,9 +393,12 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     ProcessingResult::KeepChain
                 }
             }
-            BatchProcessResult::Failed => {
+            BatchProcessResult::Failed { peer_action } => {
                 debug!(self.log, "Batch processing failed";
                     "chain_id" => self.id,"id" => *batch.id, "peer" => batch.current_peer.to_string(), "client" => network.client_type(&batch.current_peer).to_string());
+                if let Some(action) = peer_action {
+                    self.penalize_batch_peer(network, &batch, *action);
+                }
                 // The batch processing failed
                 // This could be because this batch is invalid, or a previous invalidated batch
                 // is invalid. We need to find out which and downvote the peer that has sent us
@@ -418,6 +429,23 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         Some(res)
     }
 
+    /// Penalizes the peer that sent `batch`, which contained an invalid block.
+    fn penalize_batch_peer(
+        &self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        batch: &Batch<T::EthSpec>,
+        action: PeerAction,
+    ) {
+        debug!(self.log, "Batch contained an invalid block. Scoring peer";
+            "chain_id" => self.id,
+            "batch_id" => *batch.id,
+            "score_adjustment" => action.to_string(),
+            "peer" => format!("{}", batch.current_peer),
+        );
+        network.report_peer(batch.current_peer.clone(), action);
+        network.record_batch_validity(&batch.current_peer, false);
+    }
+
     /// Removes any batches awaiting validation.
     ///
     /// All blocks in `processed_batches` should be prior batches. As the `last_batch` has been
@@ -455,6 +483,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                                 "original_peer" => format!("{}",processed_batch.original_peer),
                                 "new_peer" => format!("{}", processed_batch.current_peer)
                         );
+                        network.record_batch_validity(&processed_batch.original_peer, false);
                         network.report_peer(processed_batch.original_peer, action);
                     } else {
                         // The same peer corrected it's previous mistake. There was an error, so we
@@ -533,14 +562,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // attempt to find another peer to download the batch from (this potentially doubles up
         // requests on a single peer)
-        let current_peer = &batch.current_peer;
-        let new_peer = self
-            .peer_pool
-            .iter()
-            .find(|peer| *peer != current_peer)
-            .unwrap_or_else(|| current_peer);
-
-        batch.current_peer = new_peer.clone();
+        batch.current_peer = self.get_retry_peer(network, &batch.current_peer);
 
         debug!(self.log, "Re-requesting batch";
             "chain_id" => self.id,
@@ -670,14 +692,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             ProcessingResult::RemoveChain
         } else {
             // try to re-process the request using a different peer, if possible
-            let current_peer = &batch.current_peer;
-            let new_peer = self
-                .peer_pool
-                .iter()
-                .find(|peer| *peer != current_peer)
-                .unwrap_or_else(|| current_peer);
-
-            batch.current_peer = new_peer.clone();
+            batch.current_peer = self.get_retry_peer(network, &batch.current_peer);
             debug!(self.log, "Re-Requesting batch";
                 "chain_id" => self.id,
                 "start_slot" => batch.start_slot,
@@ -701,7 +716,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     /// to send a request and there are batches to request, false otherwise.
     fn send_range_request(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) -> bool {
         // find the next pending batch and request it from the peer
-        if let Some(peer_id) = self.get_next_peer() {
+        if let Some(peer_id) = self.get_next_peer(network) {
             if let Some(batch) = self.get_next_batch(peer_id) {
                 debug!(self.log, "Requesting batch";
                     "chain_id" => self.id,
@@ -717,21 +732,29 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         false
     }
 
-    /// Returns a peer if there exists a peer which does not currently have a pending request.
+    /// Returns the most useful peer which does not currently have a pending request, if one
+    /// exists.
     ///
     /// This is used to create the next request.
-    fn get_next_peer(&self) -> Option<PeerId> {
-        // TODO: Optimize this by combining with above two functions.
-        // randomize the peers for load balancing
-        let mut rng = rand::thread_rng();
-        let mut peers = self.peer_pool.iter().collect::<Vec<_>>();
-        peers.shuffle(&mut rng);
-        for peer in peers {
-            if self.pending_batches.peer_is_idle(peer) {
-                return Some(peer.clone());
-            }
-        }
-        None
+    fn get_next_peer(&self, network: &SyncNetworkContext<T::EthSpec>) -> Option<PeerId> {
+        network
+            .best_sync_peers(self.peer_pool.iter())
+            .into_iter()
+            .find(|peer| self.pending_batches.peer_is_idle(peer))
+    }
+
+    /// Returns the most useful peer other than `current_peer` to re-request a batch from, or
+    /// `current_peer` if there are no other peers.
+    fn get_retry_peer(
+        &self,
+        network: &SyncNetworkContext<T::EthSpec>,
+        current_peer: &PeerId,
+    ) -> PeerId {
+        network
+            .best_sync_peers(self.peer_pool.iter())
+            .into_iter()
+            .find(|peer| peer != current_peer)
+            .unwrap_or_else(|| current_peer.clone())
     }
 
     /// Returns the next required batch from the chain if it exists. If there are no more batches
@@ -797,9 +820,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     fn send_batch(
         &mut self,
         network: &mut SyncNetworkContext<T::EthSpec>,
-        batch: Batch<T::EthSpec>,
+        mut batch: Batch<T::EthSpec>,
     ) {
         let request = batch.to_blocks_by_range_request();
+        batch.request_time = Some(Instant::now());
         if let Ok(request_id) = network.blocks_by_range_request(batch.current_peer.clone(), request)
         {
             // add the batch to pending list
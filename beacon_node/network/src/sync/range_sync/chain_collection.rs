@@ -114,30 +114,17 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         // if there is no range sync occurring, the state is either synced or not based on
         // connected peers.
 
-        if self.state == RangeSyncState::Idle {
+        let new_state: SyncState = if self.state == RangeSyncState::Idle {
             // there is no range sync, let the state of peers determine the global node sync state
-            let new_state = self
-                .network_globals
-                .peers
-                .read()
-                .synced_peers()
-                .next()
-                .map(|_| SyncState::Synced)
-                .unwrap_or_else(|| SyncState::Stalled);
-            let mut peer_state = self.network_globals.sync_state.write();
-            if new_state != *peer_state {
-                info!(self.log, "Sync state updated"; "old_state" => format!("{}",peer_state), "new_state" => format!("{}",new_state));
-            }
-            *peer_state = new_state;
+            self.network_globals.peer_sync_state()
         } else {
             // The state is based on a range sync state, update it
-            let mut node_sync_state = self.network_globals.sync_state.write();
-            let new_state: SyncState = self.state.clone().into();
-            if *node_sync_state != new_state {
-                // we are updating the state, inform the user
-                info!(self.log, "Sync state updated"; "old_state" => format!("{}",node_sync_state), "new_state" => format!("{}",new_state));
-            }
-            *node_sync_state = new_state;
+            self.state.clone().into()
+        };
+
+        if let Some((old_state, new_state)) = self.network_globals.set_sync_state(new_state) {
+            // we are updating the state, inform the user
+            info!(self.log, "Sync state updated"; "old_state" => format!("{}", old_state), "new_state" => format!("{}", new_state));
         }
     }
 
@@ -5,7 +5,7 @@ mod persistence;
 
 pub use persistence::PersistedOperationPool;
 
-use attestation::AttMaxCover;
+use attestation::{earliest_attestation_validators, AttMaxCover};
 use attestation_id::AttestationId;
 use max_cover::maximum_cover;
 use parking_lot::RwLock;
@@ -78,7 +78,11 @@ impl<T: EthSpec> OperationPool<T> {
             if existing_attestation.signers_disjoint_from(&attestation) {
                 existing_attestation.aggregate(&attestation);
                 aggregated = true;
-            } else if *existing_attestation == attestation {
+            } else if attestation
+                .aggregation_bits
+                .is_subset(&existing_attestation.aggregation_bits)
+            {
+                // The attestation adds no signers to an existing aggregate.
                 aggregated = true;
             }
         }
@@ -170,6 +174,28 @@ impl<T: EthSpec> OperationPool<T> {
         });
     }
 
+    /// Remove attestations which can no longer add value to a block built upon `head_state`.
+    ///
+    /// These are attestations from prior to the previous epoch of the head, which are too old to
+    /// be included, and attestations whose attesters have all already had an attestation for the
+    /// same slot and committee included on-chain.
+    pub fn prune_attestations_at_head(&self, head_state: &BeaconState<T>) {
+        let current_epoch = head_state.current_epoch();
+        let previous_epoch = head_state.previous_epoch();
+
+        self.attestations.write().retain(|_, attestations| {
+            attestations.retain(|att| {
+                let target_epoch = att.data.target.epoch;
+
+                // Attestations from the future (relative to the head) can't be judged yet.
+                target_epoch > current_epoch
+                    || (target_epoch >= previous_epoch
+                        && earliest_attestation_validators(att, head_state).num_set_bits() > 0)
+            });
+            !attestations.is_empty()
+        });
+    }
+
     /// Insert a proposer slashing into the pool.
     pub fn insert_proposer_slashing(
         &self,
@@ -320,12 +346,40 @@ impl<T: EthSpec> OperationPool<T> {
         );
     }
 
-    /// Prune all types of transactions given the latest finalized state and head fork.
-    pub fn prune_all(&self, finalized_state: &BeaconState<T>, head_fork: Fork) {
+    /// Prune slashings and exits which have already been included in the chain of `head_state`.
+    ///
+    /// I.e., proposer slashings for slashed validators, attester slashings which would not slash
+    /// any validators and exits for validators which have already initiated an exit.
+    pub fn prune_operations_at_head(&self, head_state: &BeaconState<T>, spec: &ChainSpec) {
+        prune_validator_hash_map(
+            &mut self.proposer_slashings.write(),
+            |validator| validator.slashed,
+            head_state,
+        );
+        self.attester_slashings
+            .write()
+            .retain(|(slashing, _)| get_slashable_indices(head_state, slashing).is_ok());
+        prune_validator_hash_map(
+            &mut self.voluntary_exits.write(),
+            |validator| validator.exit_epoch != spec.far_future_epoch,
+            head_state,
+        );
+    }
+
+    /// Prune all types of transactions given the latest finalized state and the head state.
+    pub fn prune_all(
+        &self,
+        finalized_state: &BeaconState<T>,
+        head_state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) {
         self.prune_attestations(finalized_state);
         self.prune_proposer_slashings(finalized_state);
-        self.prune_attester_slashings(finalized_state, head_fork);
+        self.prune_attester_slashings(finalized_state, head_state.fork);
         self.prune_voluntary_exits(finalized_state);
+
+        self.prune_attestations_at_head(head_state);
+        self.prune_operations_at_head(head_state, spec);
     }
 
     /// Total number of voluntary exits in the pool.
@@ -590,6 +644,58 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), 0);
     }
 
+    /// Attestations whose attesters have all been included on-chain should be pruned.
+    #[test]
+    fn attestation_prune_at_head() {
+        let (ref mut state, ref keypairs, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let op_pool = OperationPool::new();
+
+        let slot = state.slot - 1;
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for bc in &committees {
+            let att = signed_attestation(
+                &bc.committee,
+                bc.index,
+                keypairs,
+                ..,
+                slot,
+                state,
+                spec,
+                None,
+            );
+            op_pool
+                .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                .unwrap();
+        }
+
+        // None of the attesters have been included yet, so nothing should be pruned.
+        op_pool.prune_attestations_at_head(state);
+        assert_eq!(op_pool.num_attestations(), committees.len());
+
+        // Include the attestations on-chain.
+        for attestation in op_pool.attestations.read().values().flatten() {
+            state
+                .current_epoch_attestations
+                .push(PendingAttestation {
+                    aggregation_bits: attestation.aggregation_bits.clone(),
+                    data: attestation.data.clone(),
+                    inclusion_delay: 1,
+                    proposer_index: 0,
+                })
+                .unwrap();
+        }
+
+        op_pool.prune_attestations_at_head(state);
+        assert_eq!(op_pool.num_attestations(), 0);
+    }
+
     /// Adding an attestation already in the pool should not increase the size of the pool.
     #[test]
     fn attestation_duplicate() {
@@ -632,6 +738,42 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), committees.len());
     }
 
+    /// An attestation whose signers are all part of an existing aggregate should not be stored.
+    #[test]
+    fn attestation_subset() {
+        let (ref mut state, ref keypairs, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let op_pool = OperationPool::new();
+
+        let slot = state.slot - 1;
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for bc in &committees {
+            for range in vec![0..4, 1..3] {
+                let att = signed_attestation(
+                    &bc.committee,
+                    bc.index,
+                    keypairs,
+                    range,
+                    slot,
+                    state,
+                    spec,
+                    None,
+                );
+                op_pool
+                    .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(op_pool.num_attestations(), committees.len());
+    }
+
     /// Adding lots of attestations that only intersect pairwise should lead to two aggregate
     /// attestations.
     #[test]
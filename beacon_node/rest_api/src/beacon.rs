@@ -28,23 +28,22 @@ pub fn get_head<T: BeaconChainTypes>(
     beacon_chain: Arc<BeaconChain<T>>,
 ) -> ApiResult {
     let chain_head = beacon_chain.head()?;
+    let fork_choice_view = beacon_chain.fork_choice_view()?;
 
     let head = CanonicalHeadResponse {
         slot: chain_head.beacon_state.slot,
         block_root: chain_head.beacon_block_root,
         state_root: chain_head.beacon_state_root,
-        finalized_slot: chain_head
-            .beacon_state
+        finalized_slot: fork_choice_view
             .finalized_checkpoint
             .epoch
             .start_slot(T::EthSpec::slots_per_epoch()),
-        finalized_block_root: chain_head.beacon_state.finalized_checkpoint.root,
-        justified_slot: chain_head
-            .beacon_state
-            .current_justified_checkpoint
+        finalized_block_root: fork_choice_view.finalized_checkpoint.root,
+        justified_slot: fork_choice_view
+            .justified_checkpoint
             .epoch
             .start_slot(T::EthSpec::slots_per_epoch()),
-        justified_block_root: chain_head.beacon_state.current_justified_checkpoint.root,
+        justified_block_root: fork_choice_view.justified_checkpoint.root,
         previous_justified_slot: chain_head
             .beacon_state
             .previous_justified_checkpoint
@@ -249,10 +248,11 @@ pub fn get_active_validators<T: BeaconChainTypes>(
     let mut state = get_state_from_root_opt(&beacon_chain, state_root_opt)?;
     state.update_pubkey_cache()?;
 
-    let validators = state
-        .validators
+    let validators = beacon_chain
+        .active_validators(&state)?
+        .indices
         .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
+        .filter_map(|&index| state.validators.get(index))
         .map(|validator| validator_response_by_pubkey(&state, validator.pubkey.clone()))
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -0,0 +1,97 @@
+use crate::helpers::block_root_at_slot;
+use crate::ApiError;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use rest_types::BlockId;
+use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// Parse a `BlockId` from a path segment.
+///
+/// E.g., `"head"`, `"finalized"`, `"1234"` or `"0x0000...0000"`.
+pub fn parse_block_id(string: &str) -> Result<BlockId, ApiError> {
+    string
+        .parse()
+        .map_err(|e| ApiError::BadRequest(format!("Invalid block id: {}", e)))
+}
+
+/// Returns the root of the block identified by `block_id`.
+///
+/// Returns an error if `block_id` is a slot which does not contain a block in the canonical chain
+/// (i.e., a skip slot).
+pub fn resolve_block_root<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: BlockId,
+) -> Result<Hash256, ApiError> {
+    match block_id {
+        BlockId::Head => Ok(beacon_chain.head_info()?.block_root),
+        BlockId::Genesis => Ok(beacon_chain.genesis_block_root),
+        BlockId::Finalized => Ok(beacon_chain.head_info()?.finalized_checkpoint.root),
+        BlockId::Justified => Ok(beacon_chain.head_info()?.current_justified_checkpoint.root),
+        BlockId::Slot(slot) => {
+            let root = block_root_at_slot(beacon_chain, slot)?.ok_or_else(|| {
+                ApiError::NotFound(format!(
+                    "Unable to find SignedBeaconBlock for slot {}",
+                    slot
+                ))
+            })?;
+
+            // The block roots iterator repeats the previous root for skip slots, so check that the
+            // block is actually at the requested slot.
+            let block = get_block(beacon_chain, root)?;
+            if block.slot() == slot {
+                Ok(root)
+            } else {
+                Err(ApiError::NotFound(format!(
+                    "No SignedBeaconBlock at slot {}",
+                    slot
+                )))
+            }
+        }
+        BlockId::Root(root) => get_block(beacon_chain, root).map(|_| root),
+    }
+}
+
+/// Returns the block identified by `block_id`, along with its root.
+pub fn resolve_block<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block_id: BlockId,
+) -> Result<(Hash256, SignedBeaconBlock<T::EthSpec>), ApiError> {
+    let root = resolve_block_root(beacon_chain, block_id)?;
+    get_block(beacon_chain, root).map(|block| (root, block))
+}
+
+/// Returns `true` if the block with `root` at `slot` is in the canonical chain.
+pub fn is_canonical<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: Hash256,
+    slot: Slot,
+) -> Result<bool, ApiError> {
+    Ok(block_root_at_slot(beacon_chain, slot)? == Some(root))
+}
+
+/// Returns `true` if the block with `root` at `slot` is in the canonical chain and is no later than
+/// the start of the finalized epoch.
+pub fn is_finalized<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: Hash256,
+    slot: Slot,
+) -> Result<bool, ApiError> {
+    let finalized_slot = beacon_chain
+        .head_info()?
+        .finalized_checkpoint
+        .epoch
+        .start_slot(T::EthSpec::slots_per_epoch());
+
+    Ok(slot <= finalized_slot && is_canonical(beacon_chain, root, slot)?)
+}
+
+fn get_block<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: Hash256,
+) -> Result<SignedBeaconBlock<T::EthSpec>, ApiError> {
+    beacon_chain.get_block(&root)?.ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "Unable to find SignedBeaconBlock for root {:?}",
+            root
+        ))
+    })
+}
@@ -1,5 +1,7 @@
+use http_tls::TlsConfig;
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::path::PathBuf;
 
 /// Defines the encoding for the API.
 #[derive(Clone, Serialize, Deserialize, Copy)]
@@ -29,6 +31,12 @@ impl From<&str> for ApiEncodingFormat {
     }
 }
 
+/// The default maximum number of slots which may be replayed to serve a request for a state.
+///
+/// One epoch, so that a single request cannot tie up the node replaying blocks. Operators which
+/// serve historical states may raise it up to their `--slots-per-restore-point`.
+pub const DEFAULT_STATE_REPLAY_BUDGET: u64 = 32;
+
 /// HTTP REST API Configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -38,6 +46,17 @@ pub struct Config {
     pub listen_address: Ipv4Addr,
     /// The port the REST API HTTP server will listen on.
     pub port: u16,
+    /// The value of the `--http-allow-origin` flag: either `*` or a comma-separated list of
+    /// origins that may make cross-origin requests. CORS is disabled if `None`.
+    pub allow_origin: Option<String>,
+    /// If `Some`, the server terminates TLS using the given certificate and key.
+    pub tls_config: Option<TlsConfig>,
+    /// If `Some`, the server listens on a unix domain socket at this path instead of
+    /// `listen_address` and `port`.
+    pub socket_path: Option<PathBuf>,
+    /// The maximum number of slots which may be replayed to serve a request to the debug state
+    /// endpoint.
+    pub state_replay_budget: u64,
 }
 
 impl Default for Config {
@@ -46,6 +65,37 @@ impl Default for Config {
             enabled: false,
             listen_address: Ipv4Addr::new(127, 0, 0, 1),
             port: 5052,
+            allow_origin: None,
+            tls_config: None,
+            socket_path: None,
+            state_replay_budget: DEFAULT_STATE_REPLAY_BUDGET,
+        }
+    }
+}
+
+/// Configuration for the dedicated Prometheus metrics HTTP server.
+///
+/// This server is separate from the REST API so that metrics can be scraped without exposing the
+/// rest of the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Enable the metrics server.
+    pub enabled: bool,
+    /// The IPv4 address the metrics server will listen on.
+    pub listen_address: Ipv4Addr,
+    /// The port the metrics server will listen on.
+    pub port: u16,
+    /// If true, statistics from the memory allocator are included in each scrape.
+    pub allocator_metrics: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: false,
+            listen_address: Ipv4Addr::new(127, 0, 0, 1),
+            port: 5054,
+            allocator_metrics: false,
         }
     }
 }
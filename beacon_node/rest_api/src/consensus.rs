@@ -3,51 +3,11 @@ use crate::response_builder::ResponseBuilder;
 use crate::{ApiError, ApiResult, UrlQuery};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use hyper::{Body, Request};
-use rest_types::{IndividualVotesRequest, IndividualVotesResponse};
-use serde::{Deserialize, Serialize};
-use ssz_derive::{Decode, Encode};
-use state_processing::per_epoch_processing::{TotalBalances, ValidatorStatuses};
+use rest_types::{IndividualVotesRequest, IndividualVotesResponse, VoteCount};
+use state_processing::per_epoch_processing::ValidatorStatuses;
 use std::sync::Arc;
 use types::EthSpec;
 
-/// The results of validators voting during an epoch.
-///
-/// Provides information about the current and previous epochs.
-#[derive(Serialize, Deserialize, Encode, Decode)]
-pub struct VoteCount {
-    /// The total effective balance of all active validators during the _current_ epoch.
-    pub current_epoch_active_gwei: u64,
-    /// The total effective balance of all active validators during the _previous_ epoch.
-    pub previous_epoch_active_gwei: u64,
-    /// The total effective balance of all validators who attested during the _current_ epoch.
-    pub current_epoch_attesting_gwei: u64,
-    /// The total effective balance of all validators who attested during the _current_ epoch and
-    /// agreed with the state about the beacon block at the first slot of the _current_ epoch.
-    pub current_epoch_target_attesting_gwei: u64,
-    /// The total effective balance of all validators who attested during the _previous_ epoch.
-    pub previous_epoch_attesting_gwei: u64,
-    /// The total effective balance of all validators who attested during the _previous_ epoch and
-    /// agreed with the state about the beacon block at the first slot of the _previous_ epoch.
-    pub previous_epoch_target_attesting_gwei: u64,
-    /// The total effective balance of all validators who attested during the _previous_ epoch and
-    /// agreed with the state about the beacon block at the time of attestation.
-    pub previous_epoch_head_attesting_gwei: u64,
-}
-
-impl Into<VoteCount> for TotalBalances {
-    fn into(self) -> VoteCount {
-        VoteCount {
-            current_epoch_active_gwei: self.current_epoch(),
-            previous_epoch_active_gwei: self.previous_epoch(),
-            current_epoch_attesting_gwei: self.current_epoch_attesters(),
-            current_epoch_target_attesting_gwei: self.current_epoch_target_attesters(),
-            previous_epoch_attesting_gwei: self.previous_epoch_attesters(),
-            previous_epoch_target_attesting_gwei: self.previous_epoch_target_attesters(),
-            previous_epoch_head_attesting_gwei: self.previous_epoch_head_attesters(),
-        }
-    }
-}
-
 /// HTTP handler return a `VoteCount` for some given `Epoch`.
 pub fn get_vote_count<T: BeaconChainTypes>(
     req: Request<Body>,
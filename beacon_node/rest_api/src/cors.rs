@@ -0,0 +1,151 @@
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Request, Response, StatusCode};
+
+/// The methods permitted in a cross-origin request.
+const ALLOW_METHODS: &str = "GET, POST, OPTIONS";
+/// The headers permitted in a cross-origin request.
+const ALLOW_HEADERS: &str = "Accept, Authorization, Content-Type";
+
+/// Applies a Cross-Origin Resource Sharing (CORS) policy to HTTP responses.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cors {
+    /// Permit requests from any origin.
+    AnyOrigin,
+    /// Permit requests only from the listed origins (e.g., `http://localhost:8080`).
+    Origins(Vec<String>),
+}
+
+impl Cors {
+    /// Parses a comma-separated list of origins, or `*` for any origin.
+    pub fn from_allow_origin(allow_origin: &str) -> Result<Self, String> {
+        if allow_origin.trim() == "*" {
+            return Ok(Cors::AnyOrigin);
+        }
+
+        let origins = allow_origin
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| {
+                if origin.starts_with("http://") || origin.starts_with("https://") {
+                    Ok(origin.trim_end_matches('/').to_string())
+                } else {
+                    Err(format!(
+                        "Invalid origin {}, must start with http:// or https://",
+                        origin
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if origins.is_empty() {
+            Err("At least one origin must be supplied".to_string())
+        } else {
+            Ok(Cors::Origins(origins))
+        }
+    }
+
+    /// Returns the value for the `Access-Control-Allow-Origin` header for a request with the
+    /// given `Origin` header, or `None` if the origin is not permitted.
+    fn allowed_origin(&self, origin: Option<&HeaderValue>) -> Option<HeaderValue> {
+        match self {
+            Cors::AnyOrigin => Some(HeaderValue::from_static("*")),
+            Cors::Origins(origins) => {
+                let origin = origin?;
+                let origin_str = origin.to_str().ok()?;
+                if origins.iter().any(|allowed| allowed == origin_str) {
+                    Some(origin.clone())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if `req` is a CORS preflight request.
+    pub fn is_preflight<T>(req: &Request<T>) -> bool {
+        req.method() == hyper::Method::OPTIONS
+            && req
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    }
+
+    /// Builds the response to a CORS preflight request with the given `Origin` header.
+    pub fn preflight_response(&self, origin: Option<&HeaderValue>) -> Response<Body> {
+        let mut response = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .expect("Response should always be created.");
+
+        if self.allowed_origin(origin).is_some() {
+            let headers = response.headers_mut();
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static(ALLOW_METHODS),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static(ALLOW_HEADERS),
+            );
+        }
+
+        self.apply(origin, &mut response);
+        response
+    }
+
+    /// Adds the CORS headers to `response`, if the request `Origin` is permitted.
+    pub fn apply(&self, origin: Option<&HeaderValue>, response: &mut Response<Body>) {
+        if let Some(allowed) = self.allowed_origin(origin) {
+            let headers = response.headers_mut();
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allowed);
+            if *self != Cors::AnyOrigin {
+                headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allow_origin_header(cors: &Cors, origin: Option<&'static str>) -> Option<HeaderValue> {
+        let mut response = Response::new(Body::empty());
+        cors.apply(origin.map(HeaderValue::from_static).as_ref(), &mut response);
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Cors::from_allow_origin("*"), Ok(Cors::AnyOrigin));
+        assert_eq!(
+            Cors::from_allow_origin("http://localhost:8080/, https://example.com"),
+            Ok(Cors::Origins(vec![
+                "http://localhost:8080".to_string(),
+                "https://example.com".to_string()
+            ]))
+        );
+        assert!(Cors::from_allow_origin("localhost").is_err());
+        assert!(Cors::from_allow_origin(",").is_err());
+    }
+
+    #[test]
+    fn apply() {
+        let any = Cors::AnyOrigin;
+        assert_eq!(
+            allow_origin_header(&any, None),
+            Some(HeaderValue::from_static("*"))
+        );
+
+        let list = Cors::from_allow_origin("http://localhost:8080").unwrap();
+        assert_eq!(
+            allow_origin_header(&list, Some("http://localhost:8080")),
+            Some(HeaderValue::from_static("http://localhost:8080"))
+        );
+        assert_eq!(allow_origin_header(&list, Some("http://evil.com")), None);
+        assert_eq!(allow_origin_header(&list, None), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Provides a cache of the SSZ bytes of the latest finalized state.
+//!
+//! Nodes syncing from a checkpoint request the finalized state from a trusted provider. Loading
+//! the finalized state from the database (possibly replaying blocks) and encoding it takes several
+//! seconds, so the encoded state is retained until the next finalization.
+use crate::state_id::resolve_state;
+use crate::{metrics, ApiError};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use hyper::body::Bytes;
+use parking_lot::Mutex;
+use rest_types::StateId;
+use ssz::Encode;
+use types::Checkpoint;
+
+/// The SSZ bytes of the finalized state, along with the checkpoint it was finalized at.
+struct CachedState {
+    checkpoint: Checkpoint,
+    bytes: Bytes,
+}
+
+#[derive(Default)]
+pub struct FinalizedStateCache {
+    cached: Mutex<Option<CachedState>>,
+}
+
+impl FinalizedStateCache {
+    /// Returns the SSZ bytes of the state at the current finalized checkpoint of `beacon_chain`,
+    /// loading and encoding it if it is not already cached.
+    ///
+    /// The lock is held whilst a missing state is loaded, so concurrent requests after a new
+    /// finalization wait for a single load rather than each loading the state.
+    pub fn get<T: BeaconChainTypes>(
+        &self,
+        beacon_chain: &BeaconChain<T>,
+    ) -> Result<Bytes, ApiError> {
+        let checkpoint = beacon_chain.head_info()?.finalized_checkpoint;
+        let mut cached = self.cached.lock();
+
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| cached.checkpoint == checkpoint)
+        {
+            metrics::inc_counter(&metrics::FINALIZED_STATE_CACHE_HITS);
+            return Ok(cached.bytes.clone());
+        }
+
+        metrics::inc_counter(&metrics::FINALIZED_STATE_CACHE_MISSES);
+        let (_root, state) = resolve_state(beacon_chain, StateId::Finalized)?;
+        let bytes = Bytes::from(state.as_ssz_bytes());
+        *cached = Some(CachedState {
+            checkpoint,
+            bytes: bytes.clone(),
+        });
+
+        Ok(bytes)
+    }
+}
@@ -4,6 +4,7 @@ use bls::PublicKeyBytes;
 use eth2_libp2p::PubsubMessage;
 use hex;
 use http::header;
+use hyper::body::HttpBody;
 use hyper::{Body, Request};
 use itertools::process_results;
 use network::NetworkMessage;
@@ -13,6 +14,9 @@ use types::{
     BeaconState, CommitteeIndex, Epoch, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, Slot,
 };
 
+/// The maximum size of a request body read by `read_body`.
+pub const MAX_REQUEST_BODY_BYTES: usize = 8 * 1024 * 1024;
+
 /// Parse a slot.
 ///
 /// E.g., `"1234"`
@@ -57,6 +61,25 @@ pub fn check_content_type_for_json(req: &Request<Body>) -> Result<(), ApiError>
     }
 }
 
+/// Reads the body of a request, returning an error if it is longer than `MAX_REQUEST_BODY_BYTES`.
+///
+/// The body is read in chunks so that an oversized body is rejected without being buffered.
+pub async fn read_body(mut body: Body) -> Result<Vec<u8>, ApiError> {
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk
+            .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+        if bytes.len() + chunk.len() > MAX_REQUEST_BODY_BYTES {
+            return Err(ApiError::PayloadTooLarge(format!(
+                "Request body exceeds {} bytes",
+                MAX_REQUEST_BODY_BYTES
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
 /// Parse an SSZ object from some hex-encoded bytes.
 ///
 /// E.g., A signature is `"0x0000000000000000000000000000000000000000000000000000000000000000"`
@@ -119,6 +142,10 @@ pub fn block_root_at_slot<T: BeaconChainTypes>(
     beacon_chain: &BeaconChain<T>,
     target: Slot,
 ) -> Result<Option<Hash256>, ApiError> {
+    if let Some(root) = beacon_chain.store.get_cold_block_root(target)? {
+        return Ok(Some(root));
+    }
+
     Ok(process_results(
         beacon_chain.rev_iter_block_roots()?,
         |iter| {
@@ -192,8 +219,12 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
     } else if head_state.slot > slot {
         // 3. The request slot is prior to the head slot.
         //
-        // Iterate through the state roots on the head state to find the root for that
-        // slot. Once the root is found, load it from the database.
+        // Finalized state roots can be read directly from the freezer. Otherwise, iterate
+        // through the state roots on the head state to find the root for that slot.
+        if let Some(root) = beacon_chain.store.get_cold_state_root(slot)? {
+            return Ok(root);
+        }
+
         process_results(
             head_state
                 .try_iter_ancestor_roots(beacon_chain.store.clone())
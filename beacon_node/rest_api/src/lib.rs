@@ -6,17 +6,23 @@ extern crate network as client_network;
 
 mod advanced;
 mod beacon;
+mod block_id;
 pub mod config;
 mod consensus;
+mod cors;
 mod error;
+mod finalized_state_cache;
 mod helpers;
 mod lighthouse;
 mod metrics;
+mod metrics_server;
 mod network;
 mod node;
 mod response_builder;
 mod router;
 mod spec;
+mod standard;
+mod state_id;
 mod url_query;
 mod validator;
 
@@ -27,10 +33,10 @@ pub use config::ApiEncodingFormat;
 use error::{ApiError, ApiResult};
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
+use finalized_state_cache::FinalizedStateCache;
 use futures::future::TryFutureExt;
-use hyper::server::conn::AddrStream;
-use hyper::service::{make_service_fn, service_fn};
-use hyper::{Body, Request, Server};
+use hyper::service::service_fn;
+use hyper::{Body, Request};
 use parking_lot::Mutex;
 use slog::{info, warn};
 use std::net::SocketAddr;
@@ -41,7 +47,9 @@ use types::SignedBeaconBlockHash;
 use url_query::UrlQuery;
 
 pub use crate::helpers::parse_pubkey_bytes;
-pub use config::Config;
+pub use config::{Config, MetricsConfig};
+pub use cors::Cors;
+pub use metrics_server::start_metrics_server;
 
 pub type NetworkChannel<T> = mpsc::UnboundedSender<NetworkMessage<T>>;
 
@@ -61,13 +69,21 @@ pub fn start_server<T: BeaconChainTypes>(
     freezer_db_path: PathBuf,
     eth2_config: Eth2Config,
     events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
-) -> Result<SocketAddr, hyper::Error> {
+) -> Result<Option<SocketAddr>, String> {
     let log = executor.log();
     let inner_log = log.clone();
     let eth2_config = Arc::new(eth2_config);
+    // The origin is validated when the config is created, so an invalid value disables CORS.
+    let cors = config
+        .allow_origin
+        .as_ref()
+        .and_then(|allow_origin| Cors::from_allow_origin(allow_origin).ok())
+        .map(Arc::new);
+    let state_replay_budget = config.state_replay_budget;
+    let finalized_state_cache = Arc::new(FinalizedStateCache::default());
 
-    // Define the function that will build the request handler.
-    let make_service = make_service_fn(move |_socket: &AddrStream| {
+    // Define the function that will build the request handler for each connection.
+    let new_service = move || {
         let beacon_chain = beacon_chain.clone();
         let log = inner_log.clone();
         let eth2_config = eth2_config.clone();
@@ -76,32 +92,28 @@ pub fn start_server<T: BeaconChainTypes>(
         let db_path = db_path.clone();
         let freezer_db_path = freezer_db_path.clone();
         let events = events.clone();
+        let cors = cors.clone();
+        let finalized_state_cache = finalized_state_cache.clone();
 
-        async move {
-            Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
-                router::route(
-                    req,
-                    beacon_chain.clone(),
-                    network_globals.clone(),
-                    network_channel.clone(),
-                    eth2_config.clone(),
-                    log.clone(),
-                    db_path.clone(),
-                    freezer_db_path.clone(),
-                    events.clone(),
-                )
-            }))
-        }
-    });
-
-    let bind_addr = (config.listen_address, config.port).into();
-    let server = Server::bind(&bind_addr).serve(make_service);
+        service_fn(move |req: Request<Body>| {
+            router::route(
+                req,
+                beacon_chain.clone(),
+                network_globals.clone(),
+                network_channel.clone(),
+                eth2_config.clone(),
+                log.clone(),
+                db_path.clone(),
+                freezer_db_path.clone(),
+                events.clone(),
+                cors.clone(),
+                state_replay_budget,
+                finalized_state_cache.clone(),
+            )
+        })
+    };
 
-    // Determine the address the server is actually listening on.
-    //
-    // This may be different to `bind_addr` if bind port was 0 (this allows the OS to choose a free
-    // port).
-    let actual_listen_addr = server.local_addr();
+    let bind_addr = SocketAddr::from((config.listen_address, config.port));
 
     // Build a channel to kill the HTTP server.
     let exit = executor.exit();
@@ -111,12 +123,22 @@ pub fn start_server<T: BeaconChainTypes>(
         info!(inner_log, "HTTP service shutdown");
     };
 
+    // Determine the address the server is actually listening on, or `None` for a unix socket.
+    //
+    // This may be different to `bind_addr` if bind port was 0 (this allows the OS to choose a free
+    // port).
+    let (actual_listen_addr, server_future) = http_tls::serve(
+        bind_addr,
+        config.socket_path.as_deref(),
+        config.tls_config.as_ref(),
+        new_service,
+        server_exit,
+        &executor,
+    )?;
+
     // Configure the `hyper` server to gracefully shutdown when the shutdown channel is triggered.
     let inner_log = log.clone();
-    let server_future = server
-        .with_graceful_shutdown(async {
-            server_exit.await;
-        })
+    let server_future = server_future
         .map_err(move |e| {
             warn!(
             inner_log,
@@ -125,12 +147,21 @@ pub fn start_server<T: BeaconChainTypes>(
         })
         .unwrap_or_else(|_| ());
 
-    info!(
-        log,
-        "HTTP API started";
-        "address" => format!("{}", actual_listen_addr.ip()),
-        "port" => actual_listen_addr.port(),
-    );
+    if let Some(listen_addr) = actual_listen_addr {
+        info!(
+            log,
+            "HTTP API started";
+            "address" => format!("{}", listen_addr.ip()),
+            "port" => listen_addr.port(),
+            "tls" => config.tls_config.is_some(),
+        );
+    } else {
+        info!(
+            log,
+            "HTTP API started";
+            "socket_path" => format!("{:?}", config.socket_path),
+        );
+    }
 
     executor.spawn_without_exit(server_future, "http");
 
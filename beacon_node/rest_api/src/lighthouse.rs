@@ -1,12 +1,19 @@
 //! This contains a collection of lighthouse specific HTTP endpoints.
 
+use crate::helpers::{check_content_type_for_json, parse_root, state_at_slot};
 use crate::response_builder::ResponseBuilder;
-use crate::ApiResult;
+use crate::state_id::{parse_state_id, resolve_state_with_replay};
+use crate::{ApiError, ApiResult, UrlQuery};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{NetworkGlobals, PeerInfo};
 use hyper::{Body, Request};
+use rest_types::{EpochParticipation, ForkChoiceWeight, LivenessRequestData, LivenessResponseData};
 use serde::Serialize;
+use state_processing::per_epoch_processing::ValidatorStatuses;
+use std::cmp;
 use std::sync::Arc;
-use types::EthSpec;
+use tree_hash::TreeHash;
+use types::{BeaconStateError, Epoch, EthSpec, Hash256, HistoricalBatch, Slot};
 
 /// The syncing state of the beacon node.
 pub fn syncing<T: EthSpec>(
@@ -47,6 +54,298 @@ pub fn connected_peers<T: EthSpec>(
     ResponseBuilder::new(&req)?.body_no_ssz(&peers)
 }
 
+/// Returns the fraction of active stake which voted for the correct target and head during the
+/// given `epoch`.
+///
+/// Attestations for `epoch` may be included in blocks until the end of `epoch + 1`, so the figures
+/// are computed from the last state of `epoch + 1` (or the head state, if that is earlier). The
+/// figures are final once the head has progressed past `epoch + 1`.
+pub fn participation<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let query = UrlQuery::from_request(&req)?;
+    let epoch = query.epoch()?;
+
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let head_slot = beacon_chain.head_info()?.slot;
+
+    if epoch >= head_slot.epoch(slots_per_epoch) {
+        return Err(ApiError::BadRequest(format!(
+            "Participation is only available for epochs prior to the head epoch ({})",
+            head_slot.epoch(slots_per_epoch)
+        )));
+    }
+
+    // This is the last slot of the epoch after the requested epoch.
+    let target_slot = cmp::min((epoch + 2).start_slot(slots_per_epoch) - 1, head_slot);
+
+    let (_root, state) = state_at_slot(&beacon_chain, target_slot)?;
+    let spec = &beacon_chain.spec;
+
+    let mut validator_statuses = ValidatorStatuses::new(&state, spec)?;
+    validator_statuses.process_attestations(&state, spec)?;
+
+    let participation =
+        EpochParticipation::from_previous_epoch(epoch, &validator_statuses.total_balances);
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&participation)
+}
+
+/// Returns the fork choice weight, best descendant and head viability of the block with the given
+/// `block_root`.
+///
+/// The figures are those computed during the most recent run of fork choice.
+pub fn fork_choice_weight<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_root: &str,
+) -> ApiResult {
+    let block_root = parse_root(block_root)?;
+
+    let fork_choice = beacon_chain.fork_choice.read();
+    let proto_array = fork_choice.proto_array();
+
+    let unknown_block = || {
+        ApiError::NotFound(format!(
+            "Block {:?} is not known to fork choice",
+            block_root
+        ))
+    };
+    let block = proto_array
+        .get_block(&block_root)
+        .ok_or_else(unknown_block)?;
+    let weight = proto_array
+        .get_weight(&block_root)
+        .ok_or_else(unknown_block)?;
+    let viable_for_head = proto_array
+        .is_viable_for_head(&block_root)
+        .ok_or_else(unknown_block)?;
+    let best_descendant = proto_array.get_best_descendant(&block_root);
+
+    drop(fork_choice);
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&ForkChoiceWeight {
+        block_root,
+        slot: block.slot,
+        weight,
+        best_descendant,
+        viable_for_head,
+    })
+}
+
+/// Returns the schema version, split point and the size of each column of the database.
+pub fn database_info<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    ResponseBuilder::new(&req)?.body_no_ssz(&beacon_chain.store.database_info()?)
+}
+
+/// Returns the `HistoricalBatch` at the given `index` of `state.historical_roots`.
+pub fn historical_batch<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let index = parse_historical_roots_index(&req)?;
+    let (batch, _historical_root) = historical_batch_at_index(&beacon_chain, index)?;
+
+    ResponseBuilder::new(&req)?.body(&batch)
+}
+
+/// Returns a `HistoricalBlockRootProof` of the block root at the given `slot`.
+///
+/// Only slots covered by an entry in `state.historical_roots` can be proven. The block root at a
+/// skipped slot is that of the latest prior block.
+pub fn historical_block_root_proof<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let slot = UrlQuery::from_request(&req)?.slot()?;
+
+    let slots_per_historical_root = T::EthSpec::slots_per_historical_root() as u64;
+    let index = slot.as_u64() / slots_per_historical_root;
+    let leaf_index = slot.as_u64() % slots_per_historical_root;
+
+    let (batch, historical_root) = historical_batch_at_index(&beacon_chain, index)?;
+
+    let block_root = *batch.block_roots.get(leaf_index as usize).ok_or_else(|| {
+        ApiError::ServerError(format!("Block root at slot {} missing from batch", slot))
+    })?;
+    let proof = batch.block_root_proof(leaf_index as usize).ok_or_else(|| {
+        ApiError::ServerError(format!("Unable to prove block root at slot {}", slot))
+    })?;
+
+    ResponseBuilder::new(&req)?.body(&HistoricalBlockRootProof {
+        slot,
+        block_root,
+        historical_roots_index: index,
+        historical_root,
+        leaf_index,
+        proof,
+    })
+}
+
+/// Returns a `StateMultiproof` of the nodes at the `gindex` query parameters in the tree of the
+/// state identified by `state_id`.
+///
+/// Only nodes down to the roots of the fields of the state may be proven. States which are not
+/// stored are reconstructed as long as no more than `state_replay_budget` slots must be replayed.
+pub fn state_proof<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+    state_replay_budget: u64,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let gindices = UrlQuery::from_request(&req)?
+        .all_of("gindex")?
+        .iter()
+        .map(|gindex| {
+            gindex
+                .parse::<u64>()
+                .map_err(|e| ApiError::BadRequest(format!("Unable to parse gindex: {:?}", e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if gindices.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one gindex is required".to_string(),
+        ));
+    }
+
+    let (state_root, mut state) =
+        resolve_state_with_replay(&beacon_chain, state_id, state_replay_budget)?;
+
+    let (leaves, proof) = match state.compute_merkle_multiproof(&gindices) {
+        Ok(multiproof) => multiproof,
+        Err(BeaconStateError::MerkleTreeError(e)) => {
+            return Err(ApiError::BadRequest(format!("Invalid gindex: {:?}", e)))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    ResponseBuilder::new(&req)?.body(&StateMultiproof {
+        state_root,
+        gindices,
+        leaves,
+        proof,
+    })
+}
+
+/// Parses the `index` query parameter of `req`.
+fn parse_historical_roots_index(req: &Request<Body>) -> Result<u64, ApiError> {
+    UrlQuery::from_request(req)?
+        .only_one("index")?
+        .parse::<u64>()
+        .map_err(|e| ApiError::BadRequest(format!("Unable to parse index: {:?}", e)))
+}
+
+/// Returns the `HistoricalBatch` at `index` of `state.historical_roots` in the canonical chain,
+/// alongside its root.
+///
+/// The batch is read from the state at the first slot after the batch, which still holds all of
+/// the block and state roots of the batch.
+fn historical_batch_at_index<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    index: u64,
+) -> Result<(HistoricalBatch<T::EthSpec>, Hash256), ApiError> {
+    let slots_per_historical_root = T::EthSpec::slots_per_historical_root() as u64;
+    let head_slot = beacon_chain.head_info()?.slot;
+
+    let batch_slot = index
+        .checked_add(1)
+        .and_then(|n| n.checked_mul(slots_per_historical_root))
+        .map(Slot::new)
+        .filter(|slot| *slot <= head_slot)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Historical root {} is not known at the head slot ({})",
+                index, head_slot
+            ))
+        })?;
+
+    let (_state_root, state) = state_at_slot(beacon_chain, batch_slot)?;
+
+    let historical_root = *state.historical_roots.get(index as usize).ok_or_else(|| {
+        ApiError::ServerError(format!(
+            "State at slot {} has no historical root {}",
+            batch_slot, index
+        ))
+    })?;
+
+    let batch = state.historical_batch();
+    if batch.tree_hash_root() != historical_root {
+        return Err(ApiError::ServerError(format!(
+            "Historical batch {} does not match its historical root",
+            index
+        )));
+    }
+
+    Ok((batch, historical_root))
+}
+
+/// Accepts a `LivenessRequestData` and returns whether each of the given validators was seen to
+/// attest, aggregate or propose during the given epoch.
+pub async fn post_liveness<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    check_content_type_for_json(&req)?;
+    let response_builder = ResponseBuilder::new(&req);
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+    let request = serde_json::from_slice::<LivenessRequestData>(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into LivenessRequestData: {:?}",
+            e
+        ))
+    })?;
+
+    let liveness = validator_liveness(&beacon_chain, request.epoch, &request.indices)?;
+
+    response_builder?.body_no_ssz(&liveness)
+}
+
+/// Returns whether each of `indices` was seen to attest, aggregate or propose during `epoch`.
+///
+/// Only activity observed by this node is considered, so a validator is not live if its messages
+/// did not reach this node. Requests for future epochs or epochs that are no longer retained by
+/// the beacon chain are refused.
+pub fn validator_liveness<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+    indices: &[u64],
+) -> Result<Vec<LivenessResponseData>, ApiError> {
+    let current_epoch = beacon_chain.epoch()?;
+    if epoch > current_epoch {
+        return Err(ApiError::BadRequest(format!(
+            "Liveness is not available for epochs after the current epoch ({})",
+            current_epoch
+        )));
+    }
+
+    indices
+        .iter()
+        .map(|&index| {
+            let is_live = beacon_chain
+                .observed_activity
+                .is_live(epoch, index)
+                .map_err(|e| {
+                    ApiError::BadRequest(format!("Liveness unavailable for epoch: {:?}", e))
+                })?;
+
+            Ok(LivenessResponseData {
+                index,
+                epoch,
+                is_live,
+            })
+        })
+        .collect()
+}
+
 /// Information returned by `peers` and `connected_peers`.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -22,6 +22,16 @@ lazy_static! {
         "http_server_success_total",
         "Total count of HTTP 200 responses sent"
     );
+    pub static ref ENDPOINT_RESPONSE_TIME: Result<HistogramVec> = try_create_histogram_vec(
+        "http_server_endpoint_request_duration_seconds",
+        "Time taken to build a response to a HTTP request, per endpoint",
+        &["endpoint"]
+    );
+    pub static ref ENDPOINT_RESPONSE_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "http_server_endpoint_responses_total",
+        "Total count of HTTP responses sent, per endpoint and status code",
+        &["endpoint", "status"]
+    );
     pub static ref VALIDATOR_GET_BLOCK_REQUEST_RESPONSE_TIME: Result<Histogram> =
         try_create_histogram(
             "http_server_validator_block_get_request_duration_seconds",
@@ -37,10 +47,22 @@ lazy_static! {
             "http_server_validator_duties_get_request_duration_seconds",
             "Time taken to respond to GET /validator/duties"
         );
+    pub static ref FINALIZED_STATE_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "http_server_finalized_state_cache_hits_total",
+        "Count of requests for the finalized state served from the cache"
+    );
+    pub static ref FINALIZED_STATE_CACHE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "http_server_finalized_state_cache_misses_total",
+        "Count of requests for the finalized state which required loading the state"
+    );
     pub static ref PROCESS_NUM_THREADS: Result<IntGauge> = try_create_int_gauge(
         "process_num_threads",
         "Number of threads used by the current process"
     );
+    pub static ref PROCESS_NUM_FDS: Result<IntGauge> = try_create_int_gauge(
+        "process_open_fds",
+        "Number of file descriptors held open by the current process"
+    );
     pub static ref PROCESS_RES_MEM: Result<IntGauge> = try_create_int_gauge(
         "process_resident_memory_bytes",
         "Resident memory used by the current process"
@@ -81,6 +103,7 @@ pub fn get_prometheus<T: BeaconChainTypes>(
     beacon_chain: Arc<BeaconChain<T>>,
     db_path: PathBuf,
     freezer_db_path: PathBuf,
+    allocator_metrics: bool,
 ) -> ApiResult {
     let mut buffer = vec![];
     let encoder = TextEncoder::new();
@@ -106,10 +129,15 @@ pub fn get_prometheus<T: BeaconChainTypes>(
     beacon_chain::scrape_for_metrics(&beacon_chain);
     eth2_libp2p::scrape_discovery_metrics();
 
+    if allocator_metrics {
+        malloc_utils::scrape_allocator_metrics();
+    }
+
     // This will silently fail if we are unable to observe the health. This is desired behaviour
     // since we don't support `Health` for all platforms.
     if let Ok(health) = Health::observe() {
         set_gauge(&PROCESS_NUM_THREADS, health.pid_num_threads as i64);
+        set_gauge(&PROCESS_NUM_FDS, health.pid_num_fds as i64);
         set_gauge(&PROCESS_RES_MEM, health.pid_mem_resident_set_size as i64);
         set_gauge(&PROCESS_VIRT_MEM, health.pid_mem_virtual_memory_size as i64);
         set_gauge(&SYSTEM_VIRT_MEM_TOTAL, health.sys_virt_mem_total as i64);
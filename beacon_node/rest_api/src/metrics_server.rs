@@ -0,0 +1,91 @@
+//! A minimal HTTP server which only serves Prometheus metrics.
+//!
+//! Runs independently of the REST API so that metrics may be scraped from an address which does
+//! not expose the rest of the API.
+
+use crate::config::MetricsConfig;
+use crate::error::ApiError;
+use crate::metrics;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use futures::future::TryFutureExt;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server};
+use slog::{info, warn};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Starts the metrics server, returning the address on which it is listening.
+pub fn start_metrics_server<T: BeaconChainTypes>(
+    executor: environment::TaskExecutor,
+    config: &MetricsConfig,
+    beacon_chain: Arc<BeaconChain<T>>,
+    db_path: PathBuf,
+    freezer_db_path: PathBuf,
+) -> Result<SocketAddr, String> {
+    let log = executor.log().clone();
+    let allocator_metrics = config.allocator_metrics;
+
+    let make_service = make_service_fn(move |_socket: &AddrStream| {
+        let beacon_chain = beacon_chain.clone();
+        let db_path = db_path.clone();
+        let freezer_db_path = freezer_db_path.clone();
+
+        async move {
+            Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+                let result = match (req.method(), req.uri().path()) {
+                    (&Method::GET, "/metrics") => metrics::get_prometheus::<T>(
+                        req,
+                        beacon_chain.clone(),
+                        db_path.clone(),
+                        freezer_db_path.clone(),
+                        allocator_metrics,
+                    ),
+                    _ => Err(ApiError::NotFound(
+                        "Only GET /metrics is served on this port".into(),
+                    )),
+                };
+
+                let response: Response<Body> = result.unwrap_or_else(Into::into);
+                async move { Ok::<_, hyper::Error>(response) }
+            }))
+        }
+    });
+
+    let bind_addr = SocketAddr::from((config.listen_address, config.port));
+    let server = Server::try_bind(&bind_addr)
+        .map_err(|e| format!("Unable to bind to {}: {:?}", bind_addr, e))?
+        .serve(make_service);
+    let actual_listen_addr = server.local_addr();
+
+    let exit = executor.exit();
+    let inner_log = log.clone();
+    let server_exit = async move {
+        let _ = exit.await;
+        info!(inner_log, "Metrics server shutdown");
+    };
+
+    let inner_log = log.clone();
+    let server_future = server
+        .with_graceful_shutdown(server_exit)
+        .map_err(move |e| {
+            warn!(
+                inner_log,
+                "Metrics server failed"; "error" => format!("{:?}", e)
+            )
+        })
+        .unwrap_or_else(|_| ());
+
+    info!(
+        log,
+        "Metrics server started";
+        "address" => format!("{}", actual_listen_addr.ip()),
+        "port" => actual_listen_addr.port(),
+        "allocator_metrics" => allocator_metrics,
+    );
+
+    executor.spawn_without_exit(server_future, "metrics_http");
+
+    Ok(actual_listen_addr)
+}
@@ -12,12 +12,21 @@ pub fn get_version(req: Request<Body>) -> ApiResult {
     ResponseBuilder::new(&req)?.body_no_ssz(&version::version())
 }
 
+/// HTTP handler for `/node/syncing`.
+///
+/// A `Stalled` node has no peers to sync from, however it is still reported as syncing if its
+/// head is more than an epoch behind `wall_clock_slot`, so that validators do not use it.
 pub fn syncing<T: EthSpec>(
     req: Request<Body>,
     network: Arc<NetworkGlobals<T>>,
     current_slot: Slot,
+    wall_clock_slot: Slot,
 ) -> ApiResult {
-    let (starting_slot, highest_slot) = match network.sync_state() {
+    let sync_state = network.sync_state();
+    let is_stalled_behind_head =
+        sync_state == SyncState::Stalled && current_slot + T::slots_per_epoch() < wall_clock_slot;
+
+    let (starting_slot, highest_slot) = match sync_state {
         SyncState::SyncingFinalized {
             start_slot,
             head_slot,
@@ -27,6 +36,7 @@ pub fn syncing<T: EthSpec>(
             start_slot,
             head_slot,
         } => (start_slot, head_slot),
+        SyncState::Stalled if is_stalled_behind_head => (current_slot, wall_clock_slot),
         SyncState::Synced | SyncState::Stalled => (Slot::from(0u64), current_slot),
     };
 
@@ -37,7 +47,7 @@ pub fn syncing<T: EthSpec>(
     };
 
     ResponseBuilder::new(&req)?.body(&SyncingResponse {
-        is_syncing: network.is_syncing(),
+        is_syncing: sync_state.is_syncing() || is_stalled_behind_head,
         sync_status,
     })
 }
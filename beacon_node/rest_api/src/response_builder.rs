@@ -1,9 +1,16 @@
 use super::{ApiError, ApiResult};
 use crate::config::ApiEncodingFormat;
+use futures::stream;
+use hyper::body::Bytes;
 use hyper::header;
 use hyper::{Body, Request, Response, StatusCode};
+use rest_types::GenericResponse;
 use serde::Serialize;
 use ssz::Encode;
+use std::convert::Infallible;
+
+/// The size of the chunks in which large SSZ bodies are streamed.
+const SSZ_CHUNK_SIZE: usize = 1024 * 1024;
 
 pub struct ResponseBuilder {
     encoding: ApiEncodingFormat,
@@ -28,6 +35,29 @@ impl ResponseBuilder {
         Ok(Self { encoding })
     }
 
+    /// Returns `true` if the response will be encoded as SSZ.
+    pub fn is_ssz(&self) -> bool {
+        matches!(self.encoding, ApiEncodingFormat::SSZ)
+    }
+
+    /// Returns the pre-encoded SSZ `bytes` using chunked transfer encoding, so that large bodies
+    /// are written to the connection as they are sent rather than as a single buffer.
+    pub fn body_ssz_chunked(self, bytes: Bytes) -> ApiResult {
+        let chunks = (0..bytes.len())
+            .step_by(SSZ_CHUNK_SIZE)
+            .map(move |start| {
+                let end = std::cmp::min(start + SSZ_CHUNK_SIZE, bytes.len());
+                Ok::<_, Infallible>(bytes.slice(start..end))
+            })
+            .collect::<Vec<_>>();
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/ssz")
+            .body(Body::wrap_stream(stream::iter(chunks)))
+            .map_err(|e| ApiError::ServerError(format!("Failed to build response: {:?}", e)))
+    }
+
     pub fn body<T: Serialize + Encode>(self, item: &T) -> ApiResult {
         match self.encoding {
             ApiEncodingFormat::SSZ => Response::builder()
@@ -39,6 +69,15 @@ impl ResponseBuilder {
         }
     }
 
+    /// Returns `data` as SSZ if requested, otherwise wrapped in the `data` field of a
+    /// `GenericResponse`.
+    pub fn body_data<T: Serialize + Encode>(self, data: T) -> ApiResult {
+        match self.encoding {
+            ApiEncodingFormat::SSZ => self.body(&data),
+            _ => self.body_no_ssz(&GenericResponse::from(data)),
+        }
+    }
+
     pub fn body_no_ssz<T: Serialize>(self, item: &T) -> ApiResult {
         let (body, content_type) = match self.encoding {
             ApiEncodingFormat::JSON => (
@@ -1,12 +1,13 @@
 use crate::{
-    advanced, beacon, consensus, error::ApiError, helpers, lighthouse, metrics, network, node,
-    spec, validator, NetworkChannel,
+    advanced, beacon, consensus, cors::Cors, error::ApiError,
+    finalized_state_cache::FinalizedStateCache, helpers, lighthouse, metrics, network, node, spec,
+    standard, validator, NetworkChannel,
 };
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use bus::Bus;
 use eth2_config::Eth2Config;
 use eth2_libp2p::NetworkGlobals;
-use hyper::{Body, Error, Method, Request, Response};
+use hyper::{header, Body, Error, Method, Request, Response};
 use parking_lot::Mutex;
 use slog::debug;
 use std::path::PathBuf;
@@ -14,6 +15,13 @@ use std::sync::Arc;
 use std::time::Instant;
 use types::{SignedBeaconBlockHash, Slot};
 
+/// The prefix of the path which returns the fork choice weight of the block root that follows it.
+const FORK_CHOICE_WEIGHTS_PREFIX: &str = "/lighthouse/fork_choice/weights/";
+/// The prefix and suffix of the path which returns a Merkle proof of the state whose id is between
+/// them.
+const STATE_PROOF_PREFIX: &str = "/lighthouse/beacon/states/";
+const STATE_PROOF_SUFFIX: &str = "/proof";
+
 // Allowing more than 7 arguments.
 #[allow(clippy::too_many_arguments)]
 pub async fn route<T: BeaconChainTypes>(
@@ -26,14 +34,27 @@ pub async fn route<T: BeaconChainTypes>(
     db_path: PathBuf,
     freezer_db_path: PathBuf,
     events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
+    cors: Option<Arc<Cors>>,
+    state_replay_budget: u64,
+    finalized_state_cache: Arc<FinalizedStateCache>,
 ) -> Result<Response<Body>, Error> {
     metrics::inc_counter(&metrics::REQUEST_COUNT);
     let timer = metrics::start_timer(&metrics::REQUEST_RESPONSE_TIME);
     let received_instant = Instant::now();
 
     let path = req.uri().path().to_string();
+    let origin = req.headers().get(header::ORIGIN).cloned();
+
+    if let Some(cors) = &cors {
+        if Cors::is_preflight(&req) {
+            metrics::stop_timer(timer);
+            return Ok(cors.preflight_response(origin.as_ref()));
+        }
+    }
 
     let log = local_log.clone();
+    let method = req.method().clone();
+    let mut matched = true;
     let request_result = match (req.method(), path.as_ref()) {
         // Methods for Client
         (&Method::GET, "/node/health") => node::get_health(req),
@@ -45,7 +66,9 @@ pub async fn route<T: BeaconChainTypes>(
                 .map(|info| info.slot)
                 .unwrap_or_else(|_| Slot::from(0u64));
 
-            node::syncing::<T::EthSpec>(req, network_globals, current_slot)
+            let wall_clock_slot = beacon_chain.slot().unwrap_or(current_slot);
+
+            node::syncing::<T::EthSpec>(req, network_globals, current_slot, wall_clock_slot)
         }
 
         // Methods for Network
@@ -98,6 +121,23 @@ pub async fn route<T: BeaconChainTypes>(
         }
 
         // Methods for Validator
+        //
+        // Duties and objects for signing are refused whilst syncing a finalized chain, since
+        // they would be at least an epoch out of date.
+        (&Method::POST, "/validator/duties")
+        | (&Method::GET, "/validator/duties/all")
+        | (&Method::GET, "/validator/duties/active")
+        | (&Method::GET, "/validator/duties/dependent_roots")
+        | (&Method::GET, "/validator/block")
+        | (&Method::GET, "/validator/attestation")
+        | (&Method::GET, "/validator/aggregate_attestation")
+            if network_globals.sync_state().is_syncing_finalized() =>
+        {
+            Err(ApiError::ServiceUnavailable(format!(
+                "The beacon node is syncing: {}",
+                network_globals.sync_state()
+            )))
+        }
         (&Method::POST, "/validator/duties") => {
             let timer = metrics::start_timer(&metrics::VALIDATOR_GET_DUTIES_REQUEST_RESPONSE_TIME);
             let response = validator::post_validator_duties::<T>(req, beacon_chain);
@@ -107,14 +147,21 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::POST, "/validator/subscribe") => {
             validator::post_validator_subscriptions::<T>(req, network_channel).await
         }
+        (&Method::POST, "/validator/prepare_beacon_proposer") => {
+            validator::post_prepare_beacon_proposer::<T>(req, beacon_chain).await
+        }
         (&Method::GET, "/validator/duties/all") => {
             validator::get_all_validator_duties::<T>(req, beacon_chain)
         }
         (&Method::GET, "/validator/duties/active") => {
             validator::get_active_validator_duties::<T>(req, beacon_chain)
         }
+        (&Method::GET, "/validator/duties/dependent_roots") => {
+            validator::get_duties_dependent_roots::<T>(req, beacon_chain)
+        }
         (&Method::GET, "/validator/block") => {
             let timer = metrics::start_timer(&metrics::VALIDATOR_GET_BLOCK_REQUEST_RESPONSE_TIME);
+            validator::warn_if_not_synced(&network_globals, "block", &log);
             let response = validator::get_new_beacon_block::<T>(req, beacon_chain, log);
             drop(timer);
             response
@@ -125,6 +172,7 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/validator/attestation") => {
             let timer =
                 metrics::start_timer(&metrics::VALIDATOR_GET_ATTESTATION_REQUEST_RESPONSE_TIME);
+            validator::warn_if_not_synced(&network_globals, "attestation", &log);
             let response = validator::get_new_attestation::<T>(req, beacon_chain);
             drop(timer);
             response
@@ -163,7 +211,7 @@ pub async fn route<T: BeaconChainTypes>(
         }
 
         (&Method::GET, "/metrics") => {
-            metrics::get_prometheus::<T>(req, beacon_chain, db_path, freezer_db_path)
+            metrics::get_prometheus::<T>(req, beacon_chain, db_path, freezer_db_path, false)
         }
 
         // Lighthouse specific
@@ -178,16 +226,56 @@ pub async fn route<T: BeaconChainTypes>(
         (&Method::GET, "/lighthouse/connected_peers") => {
             lighthouse::connected_peers::<T::EthSpec>(req, network_globals)
         }
-        _ => Err(ApiError::NotFound(
-            "Request path and/or method not found.".to_owned(),
-        )),
+        (&Method::GET, "/lighthouse/beacon/participation") => {
+            lighthouse::participation::<T>(req, beacon_chain)
+        }
+        (&Method::POST, "/lighthouse/liveness") => {
+            lighthouse::post_liveness::<T>(req, beacon_chain).await
+        }
+        (&Method::GET, "/lighthouse/database/info") => {
+            lighthouse::database_info::<T>(req, beacon_chain)
+        }
+        (&Method::GET, "/lighthouse/beacon/historical_batch") => {
+            lighthouse::historical_batch::<T>(req, beacon_chain)
+        }
+        (&Method::GET, "/lighthouse/beacon/historical_block_root_proof") => {
+            lighthouse::historical_block_root_proof::<T>(req, beacon_chain)
+        }
+        (&Method::GET, p) if is_state_proof_path(p) => {
+            let state_id = p
+                .trim_start_matches(STATE_PROOF_PREFIX)
+                .trim_end_matches(STATE_PROOF_SUFFIX);
+            lighthouse::state_proof::<T>(req, beacon_chain, state_id, state_replay_budget)
+        }
+        (&Method::GET, p) if p.starts_with(FORK_CHOICE_WEIGHTS_PREFIX) => {
+            let block_root = p.trim_start_matches(FORK_CHOICE_WEIGHTS_PREFIX);
+            lighthouse::fork_choice_weight::<T>(req, beacon_chain, block_root)
+        }
+
+        // Methods for the standard Eth2 API
+        (_, p) if standard::is_standard_path(p) => {
+            standard::route::<T>(
+                req,
+                beacon_chain,
+                state_replay_budget,
+                finalized_state_cache,
+            )
+            .await
+        }
+
+        _ => {
+            matched = false;
+            Err(ApiError::NotFound(
+                "Request path and/or method not found.".to_owned(),
+            ))
+        }
     };
 
     // Map the Rust-friendly `Result` in to a http-friendly response. In effect, this ensures that
     // any `Err` returned from our response handlers becomes a valid http response to the client
     // (e.g., a response with a 404 or 500 status).
     let duration = Instant::now().duration_since(received_instant);
-    match request_result {
+    let mut response = match request_result {
         Ok(response) => {
             debug!(
                 local_log,
@@ -198,7 +286,7 @@ pub async fn route<T: BeaconChainTypes>(
             metrics::inc_counter(&metrics::SUCCESS_COUNT);
             metrics::stop_timer(timer);
 
-            Ok(response)
+            response
         }
         Err(e) => {
             let error_response = e.into();
@@ -211,7 +299,47 @@ pub async fn route<T: BeaconChainTypes>(
             );
             metrics::stop_timer(timer);
 
-            Ok(error_response)
+            error_response
         }
+    };
+
+    // Label the endpoint metrics by the route rather than the raw path, so that unknown paths and
+    // path parameters cannot create an unbounded number of labels.
+    let endpoint = if standard::is_standard_path(&path) {
+        standard::endpoint_template(&path)
+            .map(|template| format!("{} {}", method, template))
+            .unwrap_or_else(|| "unknown".to_string())
+    } else if path.starts_with(FORK_CHOICE_WEIGHTS_PREFIX) && matched {
+        format!("{} {}{{block_root}}", method, FORK_CHOICE_WEIGHTS_PREFIX)
+    } else if is_state_proof_path(&path) && matched {
+        format!(
+            "{} {}{{state_id}}{}",
+            method, STATE_PROOF_PREFIX, STATE_PROOF_SUFFIX
+        )
+    } else if matched {
+        format!("{} {}", method, path)
+    } else {
+        "unknown".to_string()
+    };
+    if let Some(histogram) = metrics::get_histogram(&metrics::ENDPOINT_RESPONSE_TIME, &[&endpoint])
+    {
+        histogram.observe(duration.as_secs_f64());
+    }
+    metrics::inc_counter_vec(
+        &metrics::ENDPOINT_RESPONSE_COUNT,
+        &[&endpoint, response.status().as_str()],
+    );
+
+    if let Some(cors) = &cors {
+        cors.apply(origin.as_ref(), &mut response);
     }
+
+    Ok(response)
+}
+
+/// Returns `true` if `path` requests a Merkle proof of a state.
+fn is_state_proof_path(path: &str) -> bool {
+    path.len() > STATE_PROOF_PREFIX.len() + STATE_PROOF_SUFFIX.len()
+        && path.starts_with(STATE_PROOF_PREFIX)
+        && path.ends_with(STATE_PROOF_SUFFIX)
 }
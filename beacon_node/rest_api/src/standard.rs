@@ -0,0 +1,551 @@
+//! Handlers for the standard Eth2 beacon node API (`/eth/v1/...` and `/eth/v2/...`).
+//!
+//! Unlike the rest of this crate, these endpoints take parameters in the request path, so they
+//! are dispatched by `route` rather than directly by the router.
+
+use crate::block_id::{is_canonical, is_finalized, parse_block_id, resolve_block};
+use crate::finalized_state_cache::FinalizedStateCache;
+use crate::helpers::{
+    block_root_at_slot, check_content_type_for_json, parse_committee_index, parse_epoch,
+    parse_root, parse_slot, read_body,
+};
+use crate::lighthouse::validator_liveness;
+use crate::response_builder::ResponseBuilder;
+use crate::state_id::{parse_state_id, resolve_state, resolve_state_with_replay};
+use crate::{ApiError, ApiResult, UrlQuery};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use hyper::{Body, Method, Request};
+use rest_types::{
+    BlockHeaderData, CommitteeData, ExecutionOptimisticFinalizedResponse, FinalityCheckpointsData,
+    GenericResponse, LivenessData, PaginatedResponse, RootData, StateId, ValidatorBalanceData,
+    ValidatorData, ValidatorId, ValidatorStatus, ValidatorsRequestBody,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use types::{
+    BeaconState, EthSpec, Hash256, RelativeEpoch, SignedBeaconBlock, SignedBeaconBlockHeader,
+};
+
+/// Returns `true` if `path` belongs to the standard Eth2 API and should be dispatched by `route`.
+pub fn is_standard_path(path: &str) -> bool {
+    path.starts_with("/eth/v1/") || path.starts_with("/eth/v2/")
+}
+
+/// Dispatches a request for any `/eth/v1/...` or `/eth/v2/...` path.
+///
+/// Requests for states via the debug API are rejected if they would require replaying more than
+/// `state_replay_budget` slots.
+pub async fn route<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_replay_budget: u64,
+    finalized_state_cache: Arc<FinalizedStateCache>,
+) -> ApiResult {
+    let path = req.uri().path().to_string();
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    match (req.method(), segments.as_slice()) {
+        (&Method::GET, ["eth", "v1", "beacon", "states", state_id, "validators"]) => {
+            get_state_validators(req, beacon_chain, state_id)
+        }
+        (&Method::POST, ["eth", "v1", "beacon", "states", state_id, "validators"]) => {
+            post_state_validators(req, beacon_chain, state_id).await
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "states", state_id, "validator_balances"]) => {
+            get_state_validator_balances(req, beacon_chain, state_id)
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "states", state_id, "committees"]) => {
+            get_state_committees(req, beacon_chain, state_id)
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "states", state_id, "finality_checkpoints"]) => {
+            get_state_finality_checkpoints(req, beacon_chain, state_id)
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "headers"]) => get_headers(req, beacon_chain),
+        (&Method::GET, ["eth", "v1", "beacon", "headers", block_id]) => {
+            get_header(req, beacon_chain, block_id)
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "blocks", block_id]) => {
+            get_block(req, beacon_chain, block_id)
+        }
+        (&Method::GET, ["eth", "v1", "beacon", "blocks", block_id, "root"]) => {
+            get_block_root(req, beacon_chain, block_id)
+        }
+        (&Method::POST, ["eth", "v1", "validator", "liveness", epoch]) => {
+            post_validator_liveness(req, beacon_chain, epoch).await
+        }
+        (&Method::GET, ["eth", "v2", "debug", "beacon", "states", state_id]) => get_debug_state(
+            req,
+            beacon_chain,
+            state_id,
+            state_replay_budget,
+            &finalized_state_cache,
+        ),
+        _ => Err(ApiError::NotFound(
+            "Request path and/or method not found.".to_owned(),
+        )),
+    }
+}
+
+/// Returns the path of the endpoint matching `path` with its parameters replaced by placeholders
+/// (e.g., `/eth/v1/beacon/states/{state_id}/validators`), or `None` if no endpoint matches.
+///
+/// Used to label metrics without creating a new label for each distinct parameter.
+pub fn endpoint_template(path: &str) -> Option<&'static str> {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    match segments.as_slice() {
+        ["eth", "v1", "beacon", "states", _, "validators"] => {
+            Some("/eth/v1/beacon/states/{state_id}/validators")
+        }
+        ["eth", "v1", "beacon", "states", _, "validator_balances"] => {
+            Some("/eth/v1/beacon/states/{state_id}/validator_balances")
+        }
+        ["eth", "v1", "beacon", "states", _, "committees"] => {
+            Some("/eth/v1/beacon/states/{state_id}/committees")
+        }
+        ["eth", "v1", "beacon", "states", _, "finality_checkpoints"] => {
+            Some("/eth/v1/beacon/states/{state_id}/finality_checkpoints")
+        }
+        ["eth", "v1", "beacon", "headers"] => Some("/eth/v1/beacon/headers"),
+        ["eth", "v1", "beacon", "headers", _] => Some("/eth/v1/beacon/headers/{block_id}"),
+        ["eth", "v1", "beacon", "blocks", _] => Some("/eth/v1/beacon/blocks/{block_id}"),
+        ["eth", "v1", "beacon", "blocks", _, "root"] => {
+            Some("/eth/v1/beacon/blocks/{block_id}/root")
+        }
+        ["eth", "v1", "validator", "liveness", _] => Some("/eth/v1/validator/liveness/{epoch}"),
+        ["eth", "v2", "debug", "beacon", "states", _] => {
+            Some("/eth/v2/debug/beacon/states/{state_id}")
+        }
+        _ => None,
+    }
+}
+
+/// Parses all values of the `key` query parameter with `parse`.
+///
+/// Each value may be a comma-separated list, so `?id=1,2&id=3` yields three items.
+fn parse_query_list<T, F>(req: &Request<Body>, key: &str, parse: F) -> Result<Vec<T>, ApiError>
+where
+    F: Fn(&str) -> Result<T, String>,
+{
+    UrlQuery::from_request(req)?
+        .all_of(key)?
+        .iter()
+        .flat_map(|value| value.split(','))
+        .filter(|value| !value.is_empty())
+        .map(|value| parse(value).map_err(ApiError::BadRequest))
+        .collect()
+}
+
+/// Returns the indices of the validators in `state` identified by `ids`, or all validators if
+/// `ids` is empty.
+///
+/// Unknown validators are silently ignored. The provided `state` must have a fully up-to-date
+/// pubkey cache.
+fn validator_indices<E: EthSpec>(
+    state: &BeaconState<E>,
+    ids: &[ValidatorId],
+) -> Result<Vec<usize>, ApiError> {
+    if ids.is_empty() {
+        return Ok((0..state.validators.len()).collect());
+    }
+
+    let mut indices = Vec::with_capacity(ids.len());
+    for id in ids {
+        let index_opt = match id {
+            ValidatorId::PublicKey(pubkey) => state.get_validator_index(pubkey)?,
+            ValidatorId::Index(index) => Some(*index as usize),
+        };
+        if let Some(index) = index_opt.filter(|i| *i < state.validators.len()) {
+            indices.push(index);
+        }
+    }
+    Ok(indices)
+}
+
+/// Returns `true` if every status in `statuses` is only held by active validators.
+fn only_active_statuses(statuses: &[ValidatorStatus]) -> bool {
+    !statuses.is_empty()
+        && statuses
+            .iter()
+            .all(|status| status.superstatus() == ValidatorStatus::Active)
+}
+
+/// Returns a `ValidatorData` for each validator in `state` that is identified by `ids` and has one
+/// of `statuses`. Empty `ids` or `statuses` match all validators.
+///
+/// If all validators are requested but only with active statuses, the cached active validators
+/// are used to avoid checking the status of every validator in the registry.
+pub fn filter_validators<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state: &mut BeaconState<T::EthSpec>,
+    ids: &[ValidatorId],
+    statuses: &[ValidatorStatus],
+) -> Result<Vec<ValidatorData>, ApiError> {
+    state.update_pubkey_cache()?;
+    let epoch = state.current_epoch();
+    let far_future_epoch = chain.spec.far_future_epoch;
+
+    let indices = if ids.is_empty() && only_active_statuses(statuses) {
+        chain.active_validators(state)?.indices.clone()
+    } else {
+        validator_indices(state, ids)?
+    };
+
+    Ok(indices
+        .into_iter()
+        .filter_map(|index| {
+            let validator = state.validators.get(index)?;
+            let balance = *state.balances.get(index)?;
+            let status =
+                ValidatorStatus::from_validator(validator, balance, epoch, far_future_epoch);
+
+            if statuses.is_empty() || statuses.iter().any(|filter| status.matches(*filter)) {
+                Some(ValidatorData {
+                    index: index as u64,
+                    balance,
+                    status,
+                    validator: validator.clone(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+/// HTTP handler to return the validators in a state, optionally filtered by the `id` and `status`
+/// query parameters.
+pub fn get_state_validators<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let ids = parse_query_list(&req, "id", ValidatorId::from_str)?;
+    let statuses = parse_query_list(&req, "status", ValidatorStatus::from_str)?;
+
+    let (_root, mut state) = resolve_state(&beacon_chain, state_id)?;
+    let validators = filter_validators(&beacon_chain, &mut state, &ids, &statuses)?;
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&GenericResponse::from(validators))
+}
+
+/// HTTP handler which accepts a `ValidatorsRequestBody` and returns a page of the matching
+/// validators in a state.
+///
+/// This is the same as `get_state_validators`, except that it allows for a basically unbounded
+/// list of `ids`, whereas the GET request is limited by the max length of a URL.
+pub async fn post_state_validators<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+) -> ApiResult {
+    check_content_type_for_json(&req)?;
+    let state_id = parse_state_id(state_id)?;
+    let response_builder = ResponseBuilder::new(&req);
+
+    let chunks = read_body(req.into_body()).await?;
+    let request = serde_json::from_slice::<ValidatorsRequestBody>(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into ValidatorsRequestBody: {:?}",
+            e
+        ))
+    })?;
+
+    let (_root, mut state) = resolve_state(&beacon_chain, state_id)?;
+    let validators = filter_validators(&beacon_chain, &mut state, &request.ids, &request.statuses)?;
+
+    let page = PaginatedResponse::paginate(validators, request.offset, request.limit)
+        .map_err(ApiError::BadRequest)?;
+
+    response_builder?.body_no_ssz(&page)
+}
+
+/// HTTP handler which accepts a list of validator indices and returns whether each of them was
+/// seen to attest, aggregate or propose during `epoch`.
+pub async fn post_validator_liveness<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    epoch: &str,
+) -> ApiResult {
+    check_content_type_for_json(&req)?;
+    let epoch = parse_epoch(epoch)?;
+    let response_builder = ResponseBuilder::new(&req);
+
+    let chunks = read_body(req.into_body()).await?;
+    let indices = serde_json::from_slice::<Vec<u64>>(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into a list of validator indices: {:?}",
+            e
+        ))
+    })?;
+
+    let liveness = validator_liveness(&beacon_chain, epoch, &indices)?
+        .into_iter()
+        .map(|data| LivenessData {
+            index: data.index,
+            is_live: data.is_live,
+        })
+        .collect::<Vec<_>>();
+
+    response_builder?.body_no_ssz(&GenericResponse::from(liveness))
+}
+
+/// HTTP handler to return the balances of the validators in a state, optionally filtered by the
+/// `id` query parameter.
+pub fn get_state_validator_balances<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let ids = parse_query_list(&req, "id", ValidatorId::from_str)?;
+
+    let (_root, mut state) = resolve_state(&beacon_chain, state_id)?;
+    state.update_pubkey_cache()?;
+
+    let balances = validator_indices(&state, &ids)?
+        .into_iter()
+        .filter_map(|index| {
+            state
+                .balances
+                .get(index)
+                .map(|balance| ValidatorBalanceData {
+                    index: index as u64,
+                    balance: *balance,
+                })
+        })
+        .collect::<Vec<_>>();
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&GenericResponse::from(balances))
+}
+
+/// HTTP handler to return the committees of a state, optionally filtered by the `epoch`, `index`
+/// and `slot` query parameters.
+///
+/// The `epoch` defaults to the current epoch of the state and may be at most one epoch either
+/// side of it.
+pub fn get_state_committees<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let epoch_opt = UrlQuery::from_request(&req)?
+        .first_of_opt(&["epoch"])
+        .map(|(_key, value)| parse_epoch(&value))
+        .transpose()?;
+    let index_opt = UrlQuery::from_request(&req)?
+        .first_of_opt(&["index"])
+        .map(|(_key, value)| parse_committee_index(&value))
+        .transpose()?;
+    let slot_opt = UrlQuery::from_request(&req)?
+        .first_of_opt(&["slot"])
+        .map(|(_key, value)| parse_slot(&value))
+        .transpose()?;
+
+    let (_root, mut state) = resolve_state(&beacon_chain, state_id)?;
+    let epoch = epoch_opt.unwrap_or_else(|| state.current_epoch());
+
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(|_| {
+        ApiError::BadRequest(format!(
+            "Epoch {} is not within one epoch of the state's epoch {}",
+            epoch,
+            state.current_epoch()
+        ))
+    })?;
+
+    state
+        .build_committee_cache(relative_epoch, &beacon_chain.spec)
+        .map_err(|e| ApiError::ServerError(format!("Unable to build committee cache: {:?}", e)))?;
+
+    let committees = state
+        .get_beacon_committees_at_epoch(relative_epoch)
+        .map_err(|e| ApiError::ServerError(format!("Unable to get all committees: {:?}", e)))?
+        .into_iter()
+        .filter(|c| index_opt.map_or(true, |index| c.index == index))
+        .filter(|c| slot_opt.map_or(true, |slot| c.slot == slot))
+        .map(|c| CommitteeData {
+            index: c.index,
+            slot: c.slot,
+            validators: c.committee.iter().map(|i| *i as u64).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&GenericResponse::from(committees))
+}
+
+/// HTTP handler to return the finality checkpoints of a state.
+pub fn get_state_finality_checkpoints<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let (_root, state) = resolve_state(&beacon_chain, state_id)?;
+
+    let checkpoints = FinalityCheckpointsData {
+        previous_justified: state.previous_justified_checkpoint,
+        current_justified: state.current_justified_checkpoint,
+        finalized: state.finalized_checkpoint,
+    };
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&GenericResponse::from(checkpoints))
+}
+
+/// Returns the `BlockHeaderData` for `block`, which has the given `root`.
+fn block_header_data<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    root: Hash256,
+    block: SignedBeaconBlock<T::EthSpec>,
+) -> Result<BlockHeaderData, ApiError> {
+    Ok(BlockHeaderData {
+        root,
+        canonical: is_canonical(beacon_chain, root, block.slot())?,
+        header: SignedBeaconBlockHeader {
+            message: block.message.block_header(),
+            signature: block.signature,
+        },
+    })
+}
+
+/// HTTP handler to return block headers, optionally filtered by the `slot` and `parent_root`
+/// query parameters.
+///
+/// If neither parameter is supplied, the header of the head block is returned. Otherwise, the
+/// canonical block at `slot` and all non-finalized blocks known to fork choice which match the
+/// filters are returned.
+pub fn get_headers<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let slot_opt = UrlQuery::from_request(&req)?
+        .first_of_opt(&["slot"])
+        .map(|(_key, value)| parse_slot(&value))
+        .transpose()?;
+    let parent_root_opt = UrlQuery::from_request(&req)?
+        .first_of_opt(&["parent_root"])
+        .map(|(_key, value)| parse_root(&value))
+        .transpose()?;
+
+    let mut roots = if slot_opt.is_none() && parent_root_opt.is_none() {
+        vec![beacon_chain.head_info()?.block_root]
+    } else {
+        let fork_choice = beacon_chain.fork_choice.read();
+        let nodes = &fork_choice.proto_array().core_proto_array().nodes;
+
+        nodes
+            .iter()
+            .filter(|node| slot_opt.map_or(true, |slot| node.slot == slot))
+            .filter(|node| {
+                parent_root_opt.map_or(true, |parent_root| {
+                    node.parent
+                        .and_then(|i| nodes.get(i))
+                        .map_or(false, |parent| parent.root == parent_root)
+                })
+            })
+            .map(|node| node.root)
+            .collect::<Vec<_>>()
+    };
+
+    // Finalized blocks may have been pruned from fork choice, so also check the canonical chain.
+    if let Some(slot) = slot_opt {
+        if let Some(root) = block_root_at_slot(&beacon_chain, slot)? {
+            roots.push(root);
+        }
+    }
+
+    let mut headers = vec![];
+    for root in roots {
+        if headers.iter().any(|h: &BlockHeaderData| h.root == root) {
+            continue;
+        }
+
+        let block = match beacon_chain.get_block(&root)? {
+            Some(block) => block,
+            None => continue,
+        };
+
+        let matches_slot = slot_opt.map_or(true, |slot| block.slot() == slot);
+        let matches_parent = parent_root_opt.map_or(true, |root| block.parent_root() == root);
+        if matches_slot && matches_parent {
+            headers.push(block_header_data(&beacon_chain, root, block)?);
+        }
+    }
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&GenericResponse::from(headers))
+}
+
+/// HTTP handler to return the header of the block identified by `block_id`.
+pub fn get_header<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: &str,
+) -> ApiResult {
+    let block_id = parse_block_id(block_id)?;
+    let (root, block) = resolve_block(&beacon_chain, block_id)?;
+    let finalized = is_finalized(&beacon_chain, root, block.slot())?;
+    let header = block_header_data(&beacon_chain, root, block)?;
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&ExecutionOptimisticFinalizedResponse::new(
+        header, finalized,
+    ))
+}
+
+/// HTTP handler to return the block identified by `block_id`.
+pub fn get_block<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: &str,
+) -> ApiResult {
+    let block_id = parse_block_id(block_id)?;
+    let (root, block) = resolve_block(&beacon_chain, block_id)?;
+    let finalized = is_finalized(&beacon_chain, root, block.slot())?;
+
+    ResponseBuilder::new(&req)?
+        .body_no_ssz(&ExecutionOptimisticFinalizedResponse::new(block, finalized))
+}
+
+/// HTTP handler to return the root of the block identified by `block_id`.
+pub fn get_block_root<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    block_id: &str,
+) -> ApiResult {
+    let block_id = parse_block_id(block_id)?;
+    let (root, block) = resolve_block(&beacon_chain, block_id)?;
+    let finalized = is_finalized(&beacon_chain, root, block.slot())?;
+
+    ResponseBuilder::new(&req)?.body_no_ssz(&ExecutionOptimisticFinalizedResponse::new(
+        RootData { root },
+        finalized,
+    ))
+}
+
+/// HTTP handler to return the full state identified by `state_id`.
+///
+/// States at any slot up to the current slot may be requested. States which are not stored are
+/// reconstructed by replaying blocks (or skipped slots) on top of the nearest stored state, as
+/// long as no more than `state_replay_budget` slots must be replayed.
+///
+/// The finalized state is served from `finalized_state_cache` when requested as SSZ, which is how
+/// checkpoint sync clients request it.
+pub fn get_debug_state<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+    state_id: &str,
+    state_replay_budget: u64,
+    finalized_state_cache: &FinalizedStateCache,
+) -> ApiResult {
+    let state_id = parse_state_id(state_id)?;
+    let response_builder = ResponseBuilder::new(&req)?;
+
+    if state_id == StateId::Finalized && response_builder.is_ssz() {
+        let bytes = finalized_state_cache.get(&beacon_chain)?;
+        return response_builder.body_ssz_chunked(bytes);
+    }
+
+    let (_root, state) = resolve_state_with_replay(&beacon_chain, state_id, state_replay_budget)?;
+
+    response_builder.body_data(state)
+}
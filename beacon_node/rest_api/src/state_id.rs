@@ -0,0 +1,114 @@
+use crate::helpers::state_at_slot;
+use crate::ApiError;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use rest_types::StateId;
+use state_processing::per_slot_processing;
+use types::{BeaconState, EthSpec, Hash256, RelativeEpoch, Slot};
+
+/// Parse a `StateId` from a path segment.
+///
+/// E.g., `"head"`, `"finalized"`, `"1234"` or `"0x0000...0000"`.
+pub fn parse_state_id(string: &str) -> Result<StateId, ApiError> {
+    string
+        .parse()
+        .map_err(|e| ApiError::BadRequest(format!("Invalid state id: {}", e)))
+}
+
+/// Returns the `BeaconState` (and its root) in the canonical chain of `beacon_chain` identified by
+/// `state_id`.
+///
+/// The head state is read from the in-memory head snapshot, all other states are found via the
+/// state roots iterator and then loaded from the store.
+pub fn resolve_state<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state_id: StateId,
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
+    match state_id {
+        StateId::Head => {
+            let head = beacon_chain.head()?;
+            Ok((head.beacon_state_root, head.beacon_state))
+        }
+        StateId::Genesis => state_at_slot(beacon_chain, Slot::new(0)),
+        StateId::Finalized | StateId::Justified => {
+            let head_info = beacon_chain.head_info()?;
+            let checkpoint = if state_id == StateId::Finalized {
+                head_info.finalized_checkpoint
+            } else {
+                head_info.current_justified_checkpoint
+            };
+            let slot = checkpoint.epoch.start_slot(T::EthSpec::slots_per_epoch());
+            state_at_slot(beacon_chain, slot)
+        }
+        StateId::Slot(slot) => state_at_slot(beacon_chain, slot),
+        StateId::Root(root) => {
+            let state = beacon_chain
+                .get_state(&root, None)?
+                .ok_or_else(|| ApiError::NotFound(format!("No state for root: {:?}", root)))?;
+            Ok((root, state))
+        }
+    }
+}
+
+/// Returns the `BeaconState` (and its root) in the canonical chain of `beacon_chain` identified by
+/// `state_id`, reconstructing it if it is not stored.
+///
+/// States prior to the head are loaded from the store, which replays blocks on top of the nearest
+/// epoch boundary state (or restore point, for finalized states). States later than the head are
+/// produced by replaying skipped slots on top of the head state.
+///
+/// Returns an error if more than `replay_budget` slots would need to be replayed.
+pub fn resolve_state_with_replay<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    state_id: StateId,
+    replay_budget: u64,
+) -> Result<(Hash256, BeaconState<T::EthSpec>), ApiError> {
+    let slot = match state_id {
+        StateId::Slot(slot) => slot,
+        _ => return resolve_state(beacon_chain, state_id),
+    };
+
+    let current_slot = beacon_chain
+        .slot()
+        .map_err(|_| ApiError::ServerError("Unable to read slot clock".to_string()))?;
+    if slot > current_slot {
+        return Err(ApiError::BadRequest(format!(
+            "Requested slot {} is past the current slot {}",
+            slot, current_slot
+        )));
+    }
+
+    let head = beacon_chain.head()?;
+    let head_slot = head.beacon_state.slot;
+
+    let replay_slots = if slot > head_slot {
+        slot.as_u64() - head_slot.as_u64()
+    } else if slot >= beacon_chain.store.get_split_slot() {
+        slot.as_u64() % T::EthSpec::slots_per_epoch()
+    } else {
+        slot.as_u64() % beacon_chain.store.get_config().slots_per_restore_point
+    };
+
+    if replay_slots > replay_budget {
+        return Err(ApiError::BadRequest(format!(
+            "Loading the state at slot {} requires replaying {} slots, which exceeds the \
+             budget of {}",
+            slot, replay_slots, replay_budget
+        )));
+    }
+
+    if slot > head_slot {
+        let spec = &beacon_chain.spec;
+        let mut state = head.beacon_state;
+        let mut state_root = Some(head.beacon_state_root);
+
+        while state.slot < slot {
+            // Ensure the next epoch state caches are built in case of an epoch transition.
+            state.build_committee_cache(RelativeEpoch::Next, spec)?;
+            per_slot_processing(&mut state, state_root.take(), spec)?;
+        }
+
+        Ok((state.canonical_root(), state))
+    } else {
+        state_at_slot(beacon_chain, slot)
+    }
+}
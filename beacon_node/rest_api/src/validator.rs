@@ -1,4 +1,6 @@
-use crate::helpers::{check_content_type_for_json, publish_beacon_block_to_network};
+use crate::helpers::{
+    block_root_at_slot, check_content_type_for_json, publish_beacon_block_to_network,
+};
 use crate::response_builder::ResponseBuilder;
 use crate::{ApiError, ApiResult, NetworkChannel, UrlQuery};
 use beacon_chain::{
@@ -6,19 +8,47 @@ use beacon_chain::{
     BlockError, ForkChoiceError, StateSkipConfig,
 };
 use bls::PublicKeyBytes;
-use eth2_libp2p::PubsubMessage;
+use eth2_libp2p::{types::SyncState, NetworkGlobals, PubsubMessage};
 use hyper::{Body, Request};
 use network::NetworkMessage;
 use rayon::prelude::*;
-use rest_types::{ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorSubscription};
+use rest_types::{
+    BroadcastValidation, DutiesDependentRoots, ProposerPreparationData, ValidatorDutiesRequest,
+    ValidatorDutyBytes, ValidatorSubscription,
+};
 use slog::{error, info, trace, warn, Logger};
 use std::sync::Arc;
 use types::beacon_state::EthSpec;
 use types::{
-    Attestation, AttestationData, BeaconState, Epoch, RelativeEpoch, SelectionProof,
+    Attestation, AttestationData, BeaconState, Epoch, Hash256, RelativeEpoch, SelectionProof,
     SignedAggregateAndProof, SignedBeaconBlock, SubnetId,
 };
 
+/// Logs a warning if the node is producing a `object` for a validator whilst it may not be on the
+/// head of the chain.
+pub fn warn_if_not_synced<E: EthSpec>(
+    network_globals: &NetworkGlobals<E>,
+    object: &str,
+    log: &Logger,
+) {
+    match network_globals.sync_state() {
+        SyncState::Synced => {}
+        SyncState::Stalled => warn!(
+            log,
+            "Producing for validator whilst stalled";
+            "msg" => "there are no synced peers, the head may be out of date",
+            "object" => object,
+        ),
+        state => warn!(
+            log,
+            "Producing for validator whilst syncing";
+            "msg" => "the head may be out of date",
+            "object" => object,
+            "sync_state" => format!("{}", state),
+        ),
+    }
+}
+
 /// HTTP Handler to retrieve the duties for a set of validators during a particular epoch. This
 /// method allows for collecting bulk sets of validator duties without risking exceeding the max
 /// URL length with query pairs.
@@ -47,7 +77,7 @@ pub async fn post_validator_duties<T: BeaconChainTypes>(
                 bulk_request.pubkeys.into_iter().map(Into::into).collect(),
             )
         })
-        .and_then(|duties| response_builder?.body_no_ssz(&duties))
+        .and_then(|duties| response_builder?.body(&duties))
 }
 
 /// HTTP Handler to retrieve subscriptions for a set of validators. This allows the node to
@@ -85,6 +115,45 @@ pub async fn post_validator_subscriptions<T: BeaconChainTypes>(
         .and_then(|_| response_builder?.body_no_ssz(&()))
 }
 
+/// HTTP Handler to register validators for proposer preparation. This allows the node to run fork
+/// choice and advance the head state ahead of the validators' block proposals.
+pub async fn post_prepare_beacon_proposer<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    try_future!(check_content_type_for_json(&req));
+    let response_builder = ResponseBuilder::new(&req);
+
+    let body = req.into_body();
+    let chunks = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
+
+    serde_json::from_slice(&chunks)
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "Unable to parse JSON into ProposerPreparationData: {:?}",
+                e
+            ))
+        })
+        .and_then(move |preparation_data: Vec<ProposerPreparationData>| {
+            let validator_indices = preparation_data
+                .iter()
+                .map(|data| data.validator_index)
+                .collect::<Vec<_>>();
+
+            beacon_chain
+                .register_proposer_preparation(&validator_indices)
+                .map_err(|e| {
+                    ApiError::ServerError(format!(
+                        "Unable to register proposer preparation: {:?}",
+                        e
+                    ))
+                })
+        })
+        .and_then(|_| response_builder?.body_no_ssz(&()))
+}
+
 /// HTTP Handler to retrieve all validator duties for the given epoch.
 pub fn get_all_validator_duties<T: BeaconChainTypes>(
     req: Request<Body>,
@@ -104,7 +173,7 @@ pub fn get_all_validator_duties<T: BeaconChainTypes>(
 
     let duties = return_validator_duties(beacon_chain, epoch, validator_pubkeys)?;
 
-    ResponseBuilder::new(&req)?.body_no_ssz(&duties)
+    ResponseBuilder::new(&req)?.body(&duties)
 }
 
 /// HTTP Handler to retrieve all active validator duties for the given epoch.
@@ -118,16 +187,64 @@ pub fn get_active_validator_duties<T: BeaconChainTypes>(
 
     let state = get_state_for_epoch(&beacon_chain, epoch, StateSkipConfig::WithoutStateRoots)?;
 
-    let validator_pubkeys = state
-        .validators
+    let validator_pubkeys = beacon_chain
+        .active_validators(&state)?
+        .indices
         .iter()
-        .filter(|validator| validator.is_active_at(state.current_epoch()))
+        .filter_map(|&index| state.validators.get(index))
         .map(|validator| validator.pubkey.clone())
         .collect();
 
     let duties = return_validator_duties(beacon_chain, epoch, validator_pubkeys)?;
 
-    ResponseBuilder::new(&req)?.body_no_ssz(&duties)
+    ResponseBuilder::new(&req)?.body(&duties)
+}
+
+/// HTTP Handler to retrieve the roots of the blocks which determined the attester and proposer
+/// duties of the given epoch.
+pub fn get_duties_dependent_roots<T: BeaconChainTypes>(
+    req: Request<Body>,
+    beacon_chain: Arc<BeaconChain<T>>,
+) -> ApiResult {
+    let query = UrlQuery::from_request(&req)?;
+
+    let epoch = query.epoch()?;
+
+    let dependent_roots = DutiesDependentRoots {
+        epoch,
+        attester_dependent_root: decision_block_root(&beacon_chain, epoch.saturating_sub(1u64))?,
+        proposer_dependent_root: decision_block_root(&beacon_chain, epoch)?,
+    };
+
+    ResponseBuilder::new(&req)?.body(&dependent_roots)
+}
+
+/// Returns the root of the block at the last slot of the epoch prior to `epoch`, as seen from the
+/// canonical head.
+///
+/// If that slot is later than the head, the head block root is returned since the head will be
+/// the block at that slot unless a new block is imported.
+fn decision_block_root<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    epoch: Epoch,
+) -> Result<Hash256, ApiError> {
+    if epoch == T::EthSpec::genesis_epoch() {
+        return Ok(beacon_chain.genesis_block_root);
+    }
+
+    let decision_slot = epoch.start_slot(T::EthSpec::slots_per_epoch()) - 1;
+    let head_info = beacon_chain.head_info()?;
+
+    if decision_slot >= head_info.slot {
+        Ok(head_info.block_root)
+    } else {
+        block_root_at_slot(beacon_chain, decision_slot)?.ok_or_else(|| {
+            ApiError::ServerError(format!(
+                "Unable to find block root at slot {}",
+                decision_slot
+            ))
+        })
+    }
 }
 
 /// Helper function to return the state that can be used to determine the duties for some `epoch`.
@@ -307,6 +424,9 @@ pub fn get_new_beacon_block<T: BeaconChainTypes>(
 }
 
 /// HTTP Handler to publish a SignedBeaconBlock, which has been signed by a validator.
+///
+/// The `broadcast_validation` query parameter determines how thoroughly the block is checked
+/// before it is published. See `BroadcastValidation`.
 pub async fn publish_beacon_block<T: BeaconChainTypes>(
     req: Request<Body>,
     beacon_chain: Arc<BeaconChain<T>>,
@@ -316,87 +436,168 @@ pub async fn publish_beacon_block<T: BeaconChainTypes>(
     try_future!(check_content_type_for_json(&req));
     let response_builder = ResponseBuilder::new(&req);
 
+    let broadcast_validation = UrlQuery::from_request(&req)?
+        .first_of_opt(&["broadcast_validation"])
+        .map(|(_key, value)| value.parse::<BroadcastValidation>())
+        .transpose()
+        .map_err(ApiError::BadRequest)?
+        .unwrap_or_default();
+
     let body = req.into_body();
     let chunks = hyper::body::to_bytes(body)
         .await
         .map_err(|e| ApiError::ServerError(format!("Unable to get request body: {:?}", e)))?;
 
-    serde_json::from_slice(&chunks).map_err(|e| {
-                    ApiError::BadRequest(format!("Unable to parse JSON into SignedBeaconBlock: {:?}", e))
-                })
-            .and_then(move |block: SignedBeaconBlock<T::EthSpec>| {
-                let slot = block.slot();
-                match beacon_chain.process_block(block.clone()) {
-                    Ok(block_root) => {
-                        // Block was processed, publish via gossipsub
-                        info!(
-                            log,
-                            "Block from local validator";
-                            "block_root" => format!("{}", block_root),
-                            "block_slot" => slot,
-                        );
-
-                        publish_beacon_block_to_network::<T>(network_chan, block)?;
-
-                        // Run the fork choice algorithm and enshrine a new canonical head, if
-                        // found.
-                        //
-                        // The new head may or may not be the block we just received.
-                        if let Err(e) = beacon_chain.fork_choice() {
-                            error!(
-                                log,
-                                "Failed to find beacon chain head";
-                                "error" => format!("{:?}", e)
-                            );
-                        } else {
-                            // In the best case, validators should produce blocks that become the
-                            // head.
-                            //
-                            // Potential reasons this may not be the case:
-                            //
-                            // - A quick re-org between block produce and publish.
-                            // - Excessive time between block produce and publish.
-                            // - A validator is using another beacon node to produce blocks and
-                            // submitting them here.
-                            if beacon_chain.head()?.beacon_block_root != block_root {
-                                warn!(
-                                    log,
-                                    "Block from validator is not head";
-                                    "desc" => "potential re-org",
-                                );
-
-                            }
-                        }
-
-                        Ok(())
-                    }
-                    Err(BlockError::BeaconChainError(e)) => {
-                        error!(
-                            log,
-                            "Error whilst processing block";
-                            "error" => format!("{:?}", e)
-                        );
-
-                        Err(ApiError::ServerError(format!(
-                            "Error while processing block: {:?}",
-                            e
-                        )))
-                    }
-                    Err(other) => {
-                        warn!(
-                            log,
-                            "Invalid block from local validator";
-                            "outcome" => format!("{:?}", other)
-                        );
-
-                        Err(ApiError::ProcessingError(format!(
-                            "The SignedBeaconBlock could not be processed and has not been published: {:?}",
-                            other
-                        )))
-                    }
+    let block: SignedBeaconBlock<T::EthSpec> = serde_json::from_slice(&chunks).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Unable to parse JSON into SignedBeaconBlock: {:?}",
+            e
+        ))
+    })?;
+    let slot = block.slot();
+
+    let import_result = match broadcast_validation {
+        BroadcastValidation::Gossip => {
+            let gossip_verified = beacon_chain
+                .verify_block_for_gossip(block.clone())
+                .map_err(|e| {
+                    warn!(
+                        log,
+                        "Invalid block from local validator";
+                        "outcome" => format!("{:?}", e)
+                    );
+
+                    ApiError::ProcessingError(format!(
+                        "The SignedBeaconBlock failed gossip verification and has not been published: {:?}",
+                        e
+                    ))
+                })?;
+
+            publish_beacon_block_to_network::<T>(network_chan, block)?;
+
+            beacon_chain.process_block(gossip_verified)
+        }
+        BroadcastValidation::Consensus | BroadcastValidation::ConsensusAndEquivocation => {
+            if broadcast_validation == BroadcastValidation::ConsensusAndEquivocation {
+                check_for_equivocation(&beacon_chain, &block)?;
+            }
+
+            let result = beacon_chain.process_block(block.clone());
+            if result.is_ok() {
+                publish_beacon_block_to_network::<T>(network_chan, block)?;
+            }
+            result
+        }
+    };
+
+    match import_result {
+        Ok(block_root) => {
+            info!(
+                log,
+                "Block from local validator";
+                "block_root" => format!("{}", block_root),
+                "block_slot" => slot,
+                "broadcast_validation" => broadcast_validation.to_string(),
+            );
+
+            beacon_chain
+                .proposal_head_votes
+                .register_proposal(slot, block_root);
+
+            // Run the fork choice algorithm and enshrine a new canonical head, if
+            // found.
+            //
+            // The new head may or may not be the block we just received.
+            if let Err(e) = beacon_chain.fork_choice() {
+                error!(
+                    log,
+                    "Failed to find beacon chain head";
+                    "error" => format!("{:?}", e)
+                );
+            } else {
+                // In the best case, validators should produce blocks that become the
+                // head.
+                //
+                // Potential reasons this may not be the case:
+                //
+                // - A quick re-org between block produce and publish.
+                // - Excessive time between block produce and publish.
+                // - A validator is using another beacon node to produce blocks and
+                // submitting them here.
+                if beacon_chain.head()?.beacon_block_root != block_root {
+                    warn!(
+                        log,
+                        "Block from validator is not head";
+                        "desc" => "potential re-org",
+                    );
                 }
-        })
-        .and_then(|_| response_builder?.body_no_ssz(&()))
+            }
+        }
+        Err(BlockError::BeaconChainError(e)) => {
+            error!(
+                log,
+                "Error whilst processing block";
+                "error" => format!("{:?}", e)
+            );
+
+            return Err(ApiError::ServerError(format!(
+                "Error while processing block: {:?}",
+                e
+            )));
+        }
+        Err(other) => {
+            warn!(
+                log,
+                "Invalid block from local validator";
+                "outcome" => format!("{:?}", other)
+            );
+
+            let published = if broadcast_validation == BroadcastValidation::Gossip {
+                "has been published"
+            } else {
+                "has not been published"
+            };
+
+            return Err(ApiError::ProcessingError(format!(
+                "The SignedBeaconBlock could not be processed and {}: {:?}",
+                published, other
+            )));
+        }
+    }
+
+    response_builder?.body_no_ssz(&())
+}
+
+/// Returns an error if fork choice knows of a different block from the proposer of `block` at the
+/// same slot.
+fn check_for_equivocation<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+) -> Result<(), ApiError> {
+    let block_root = block.canonical_root();
+    let sibling_roots = beacon_chain
+        .fork_choice
+        .read()
+        .proto_array()
+        .core_proto_array()
+        .nodes
+        .iter()
+        .filter(|node| node.slot == block.slot() && node.root != block_root)
+        .map(|node| node.root)
+        .collect::<Vec<_>>();
+
+    for root in sibling_roots {
+        if let Some(other) = beacon_chain.get_block(&root)? {
+            if other.message.proposer_index == block.message.proposer_index {
+                return Err(ApiError::ProcessingError(format!(
+                    "The SignedBeaconBlock is an equivocation of {:?} and has not been published",
+                    root
+                )));
+            }
+        }
+    }
+
+    Ok(())
 }
 
 /// HTTP Handler to produce a new Attestation from the current state, ready to be signed by a validator.
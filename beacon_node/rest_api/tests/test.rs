@@ -9,7 +9,8 @@ use node_test_rig::{
     testing_client_config, ClientConfig, ClientGenesis, LocalBeaconNode,
 };
 use remote_beacon_node::{
-    Committee, HeadBeaconBlock, PersistedOperationPool, PublishStatus, ValidatorResponse,
+    BlockId, BroadcastValidation, Committee, Encoding, HeadBeaconBlock, PersistedOperationPool,
+    PublishStatus, StateId, ValidatorId, ValidatorResponse, ValidatorStatus, ValidatorsRequestBody,
 };
 use rest_types::ValidatorDutyBytes;
 use std::convert::TryInto;
@@ -20,9 +21,9 @@ use types::{
         build_double_vote_attester_slashing, build_proposer_slashing,
         generate_deterministic_keypair, AttesterSlashingTestTask, ProposerSlashingTestTask,
     },
-    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, MinimalEthSpec, PublicKey,
-    RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
-    SubnetId, Validator,
+    BeaconBlock, BeaconState, ChainSpec, Domain, Epoch, EthSpec, Hash256, MinimalEthSpec,
+    PublicKey, RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot,
+    Slot, SubnetId, Validator,
 };
 use version;
 
@@ -318,12 +319,72 @@ fn validator_duties() {
         .expect("should fetch duties from http api");
 
     // 2. Check with a long skip forward.
-    check_duties(duties, epoch, validators, beacon_chain, spec);
+    check_duties(
+        duties.clone(),
+        epoch,
+        validators.clone(),
+        beacon_chain,
+        spec,
+    );
+
+    // 3. Check that SSZ duties match the JSON duties.
+    let ssz_duties = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .clone()
+                .with_encoding(Encoding::Ssz)
+                .validator()
+                .get_duties(epoch, &validators),
+        )
+        .expect("should fetch ssz duties from http api");
+    assert_eq!(ssz_duties, duties, "ssz and json duties should match");
 
     // TODO: test an epoch in the past. Blocked because the `LocalBeaconNode` cannot produce a
     // chain, yet.
 }
 
+#[test]
+fn duties_dependent_roots() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+    let head_block_root = beacon_chain
+        .head_info()
+        .expect("should get head info")
+        .block_root;
+
+    for epoch in vec![Epoch::new(0), Epoch::new(1), Epoch::new(4)] {
+        let dependent_roots = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .validator()
+                    .get_duties_dependent_roots(epoch),
+            )
+            .expect("should fetch dependent roots from http api");
+
+        assert_eq!(dependent_roots.epoch, epoch, "epoch should match request");
+        // No blocks have been produced, so every deciding block is the genesis (head) block.
+        assert_eq!(
+            dependent_roots.attester_dependent_root, head_block_root,
+            "attester dependent root should be the head"
+        );
+        assert_eq!(
+            dependent_roots.proposer_dependent_root, head_block_root,
+            "proposer dependent root should be the head"
+        );
+    }
+}
+
 fn check_duties<T: BeaconChainTypes>(
     duties: Vec<ValidatorDutyBytes>,
     epoch: Epoch,
@@ -525,6 +586,91 @@ fn validator_block_post() {
     );
 }
 
+#[test]
+fn validator_block_post_equivocation() {
+    let mut env = build_env();
+
+    let spec = &E::default_spec();
+
+    let two_slots_secs = (spec.milliseconds_per_slot / 1_000) * 2;
+
+    let mut config = testing_client_config();
+    config.genesis = ClientGenesis::Interop {
+        validator_count: 8,
+        genesis_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - two_slots_secs,
+    };
+
+    let node = build_node(&mut env, config);
+    let remote_node = node.remote_node().expect("should produce remote node");
+
+    let beacon_chain = node
+        .client
+        .beacon_chain()
+        .expect("client should have beacon chain");
+
+    let slot = Slot::new(1);
+    let randao_reveal = get_randao_reveal(beacon_chain.clone(), slot, spec);
+
+    let block = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .validator()
+                .produce_block(slot, randao_reveal),
+        )
+        .expect("should fetch block from http api");
+
+    let mut equivocating_block = block.clone();
+    equivocating_block.body.graffiti[0] ^= 1;
+
+    let signed_block = sign_block(beacon_chain.clone(), block, spec);
+    let block_root = signed_block.canonical_root();
+
+    let publish_status = env
+        .runtime()
+        .block_on(remote_node.http.validator().publish_block_with_validation(
+            signed_block,
+            BroadcastValidation::ConsensusAndEquivocation,
+        ))
+        .expect("should publish block");
+
+    assert_eq!(
+        publish_status,
+        PublishStatus::Valid,
+        "the first block should be valid"
+    );
+
+    let signed_equivocation = sign_block(beacon_chain.clone(), equivocating_block, spec);
+
+    let publish_status = env
+        .runtime()
+        .block_on(remote_node.http.validator().publish_block_with_validation(
+            signed_equivocation,
+            BroadcastValidation::ConsensusAndEquivocation,
+        ))
+        .expect("should publish block");
+
+    assert!(
+        !publish_status.is_valid(),
+        "the equivocating block should not be valid"
+    );
+
+    let head = env
+        .runtime()
+        .block_on(remote_node.http.beacon().get_head())
+        .expect("should get head");
+
+    assert_eq!(
+        head.block_root, block_root,
+        "the first block should remain the head block"
+    );
+}
+
 #[test]
 fn validator_block_get() {
     let mut env = build_env();
@@ -653,6 +799,162 @@ fn beacon_block() {
     );
 }
 
+#[test]
+fn ssz_encoding() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let json = remote_node.http.beacon();
+    let ssz = remote_node
+        .http
+        .clone()
+        .with_encoding(Encoding::Ssz)
+        .beacon();
+
+    assert_eq!(
+        env.runtime()
+            .block_on(ssz.get_state_by_slot(Slot::new(0)))
+            .expect("should fetch ssz state"),
+        env.runtime()
+            .block_on(json.get_state_by_slot(Slot::new(0)))
+            .expect("should fetch json state"),
+        "ssz and json states should match"
+    );
+    assert_eq!(
+        env.runtime()
+            .block_on(ssz.get_block_by_slot(Slot::new(0)))
+            .expect("should fetch ssz block"),
+        env.runtime()
+            .block_on(json.get_block_by_slot(Slot::new(0)))
+            .expect("should fetch json block"),
+        "ssz and json blocks should match"
+    );
+    assert_eq!(
+        env.runtime()
+            .block_on(ssz.get_head())
+            .expect("should fetch ssz head"),
+        env.runtime()
+            .block_on(json.get_head())
+            .expect("should fetch json head"),
+        "ssz and json heads should match"
+    );
+    assert_eq!(
+        env.runtime()
+            .block_on(ssz.get_genesis_time())
+            .expect("should fetch ssz genesis time"),
+        env.runtime()
+            .block_on(json.get_genesis_time())
+            .expect("should fetch json genesis time"),
+        "ssz and json genesis times should match"
+    );
+
+    // Endpoints which only support JSON are unaffected by the encoding.
+    let spec = remote_node.http.clone().with_encoding(Encoding::Ssz).spec();
+    env.runtime()
+        .block_on(spec.get_eth2_config())
+        .expect("should fetch eth2 config");
+}
+
+#[test]
+fn beacon_headers_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let genesis_block = chain
+        .block_at_slot(Slot::new(0))
+        .expect("should find block")
+        .expect("block should not be none");
+    let genesis_root = genesis_block.canonical_root();
+
+    for block_id in &[
+        BlockId::Head,
+        BlockId::Genesis,
+        BlockId::Finalized,
+        BlockId::Slot(Slot::new(0)),
+        BlockId::Root(genesis_root),
+    ] {
+        let header = env
+            .runtime()
+            .block_on(remote_node.http.standard().get_header(*block_id))
+            .expect("should fetch header from http api");
+
+        assert_eq!(header.root, genesis_root, "{} should be genesis", block_id);
+        assert!(header.canonical, "{} should be canonical", block_id);
+        assert_eq!(header.header.message, genesis_block.message.block_header());
+
+        let root = env
+            .runtime()
+            .block_on(remote_node.http.standard().get_block_root(*block_id))
+            .expect("should fetch root from http api");
+
+        assert_eq!(root, genesis_root, "{} root should be genesis", block_id);
+
+        let response = env
+            .runtime()
+            .block_on(remote_node.http.standard().get_block(*block_id))
+            .expect("should fetch block from http api");
+
+        assert_eq!(
+            response.data, genesis_block,
+            "{} should be genesis",
+            block_id
+        );
+        assert!(response.finalized, "{} should be finalized", block_id);
+        assert!(!response.execution_optimistic);
+    }
+
+    let headers = env
+        .runtime()
+        .block_on(remote_node.http.standard().get_headers(None, None))
+        .expect("should fetch headers from http api");
+
+    assert_eq!(headers.len(), 1, "should only return the head");
+    assert_eq!(headers[0].root, genesis_root);
+
+    let headers = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .standard()
+                .get_headers(Some(Slot::new(0)), None),
+        )
+        .expect("should fetch headers from http api");
+
+    assert_eq!(headers.len(), 1, "should only return genesis");
+    assert_eq!(headers[0].root, genesis_root);
+
+    let headers = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .standard()
+                .get_headers(None, Some(genesis_root)),
+        )
+        .expect("should fetch headers from http api");
+
+    assert!(headers.is_empty(), "genesis should have no children");
+
+    assert!(
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .standard()
+                    .get_header(BlockId::Root(Hash256::repeat_byte(42))),
+            )
+            .is_err(),
+        "should not find unknown block"
+    );
+}
+
 #[test]
 fn genesis_time() {
     let mut env = build_env();
@@ -946,6 +1248,269 @@ fn get_committees() {
     assert_eq!(result, expected, "result should be as expected");
 }
 
+#[test]
+fn get_state_validators_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let all = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .standard()
+                .get_state_validators(StateId::Head, &[], &[]),
+        )
+        .expect("should fetch from http api");
+
+    assert_eq!(all.len(), state.validators.len(), "should return all");
+    for (i, data) in all.iter().enumerate() {
+        assert_eq!(data.index, i as u64);
+        assert_eq!(data.balance, state.balances[i]);
+        assert_eq!(data.validator, state.validators[i]);
+        assert_eq!(data.status, ValidatorStatus::ActiveOngoing);
+    }
+
+    let ids = vec![
+        ValidatorId::Index(1),
+        ValidatorId::PublicKey(state.validators[3].pubkey.clone()),
+        ValidatorId::Index(u64::max_value()),
+    ];
+    let some = env
+        .runtime()
+        .block_on(remote_node.http.standard().get_state_validators(
+            StateId::Genesis,
+            &ids,
+            &[ValidatorStatus::Active],
+        ))
+        .expect("should fetch from http api");
+
+    assert_eq!(
+        some.iter().map(|data| data.index).collect::<Vec<_>>(),
+        vec![1, 3],
+        "should ignore unknown validators"
+    );
+
+    let exited = env
+        .runtime()
+        .block_on(remote_node.http.standard().get_state_validators(
+            StateId::Head,
+            &ids,
+            &[ValidatorStatus::Exited],
+        ))
+        .expect("should fetch from http api");
+
+    assert!(exited.is_empty(), "no validators should be exited");
+
+    let balances = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .standard()
+                .get_state_validator_balances(StateId::Slot(Slot::new(0)), &ids[0..1]),
+        )
+        .expect("should fetch from http api");
+
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0].index, 1);
+    assert_eq!(balances[0].balance, state.balances[1]);
+}
+
+#[test]
+fn get_debug_state_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let head_state = chain.head().expect("should get head").beacon_state;
+
+    for state_id in &[
+        StateId::Head,
+        StateId::Genesis,
+        StateId::Slot(head_state.slot),
+    ] {
+        let state = env
+            .runtime()
+            .block_on(remote_node.http.standard().get_debug_state(*state_id))
+            .expect("should fetch state from http api");
+
+        assert_eq!(
+            state.canonical_root(),
+            head_state.canonical_root(),
+            "should return the head state"
+        );
+    }
+
+    let future_slot = chain.slot().expect("should get slot") + 1_000;
+    assert!(
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .standard()
+                    .get_debug_state(StateId::Slot(future_slot))
+            )
+            .is_err(),
+        "should not return a state from the future"
+    );
+}
+
+#[test]
+fn post_state_validators_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    let ids = state
+        .validators
+        .iter()
+        .map(|v| ValidatorId::PublicKey(v.pubkey.clone()))
+        .collect::<Vec<_>>();
+    let limit = 3;
+
+    let mut request = ValidatorsRequestBody {
+        ids,
+        statuses: vec![ValidatorStatus::ActiveOngoing],
+        offset: 0,
+        limit: Some(limit),
+    };
+    let mut indices = vec![];
+
+    loop {
+        let page = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .standard()
+                    .post_state_validators(StateId::Head, &request),
+            )
+            .expect("should fetch from http api");
+
+        assert_eq!(page.total, state.validators.len() as u64);
+        assert!(page.data.len() as u64 <= limit);
+        indices.extend(page.data.iter().map(|data| data.index));
+
+        match page.next_offset {
+            Some(offset) => request.offset = offset,
+            None => break,
+        }
+    }
+
+    assert_eq!(
+        indices,
+        (0..state.validators.len() as u64).collect::<Vec<_>>(),
+        "should return every validator exactly once"
+    );
+}
+
+#[test]
+fn get_state_committees_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let head = chain.head().expect("should get head");
+
+    let result = env
+        .runtime()
+        .block_on(
+            remote_node
+                .http
+                .standard()
+                .get_state_committees(StateId::Root(head.beacon_state_root), None),
+        )
+        .expect("should fetch from http api");
+
+    let expected = head
+        .beacon_state
+        .get_beacon_committees_at_epoch(RelativeEpoch::Current)
+        .expect("should get committees")
+        .iter()
+        .map(|c| (c.slot, c.index, c.committee.to_vec()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        result
+            .into_iter()
+            .map(|c| (
+                c.slot,
+                c.index,
+                c.validators.into_iter().map(|i| i as usize).collect()
+            ))
+            .collect::<Vec<_>>(),
+        expected,
+        "result should be as expected"
+    );
+
+    let far_epoch = head.beacon_state.current_epoch() + 2;
+    assert!(
+        env.runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .standard()
+                    .get_state_committees(StateId::Head, Some(far_epoch)),
+            )
+            .is_err(),
+        "should not return committees for distant epochs"
+    );
+}
+
+#[test]
+fn get_state_finality_checkpoints_standard() {
+    let mut env = build_env();
+
+    let node = build_node(&mut env, testing_client_config());
+    let remote_node = node.remote_node().expect("should produce remote node");
+    let chain = node
+        .client
+        .beacon_chain()
+        .expect("node should have beacon chain");
+    let state = &chain.head().expect("should get head").beacon_state;
+
+    for state_id in &[StateId::Head, StateId::Finalized, StateId::Justified] {
+        let result = env
+            .runtime()
+            .block_on(
+                remote_node
+                    .http
+                    .standard()
+                    .get_state_finality_checkpoints(*state_id),
+            )
+            .expect("should fetch from http api");
+
+        assert_eq!(result.finalized, state.finalized_checkpoint);
+        assert_eq!(result.current_justified, state.current_justified_checkpoint);
+        assert_eq!(
+            result.previous_justified,
+            state.previous_justified_checkpoint
+        );
+    }
+}
+
 #[test]
 fn get_fork_choice() {
     let mut env = build_env();
@@ -66,11 +66,19 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("The UDP port that discovery will listen on. Defaults to `port`")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("target-peers")
+                .long("target-peers")
+                .help("The target number of peers. Discovery searches for new peers whilst below \
+                       this number and excess peers are pruned.")
+                .default_value("50")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("max-peers")
                 .long("max-peers")
-                .help("The maximum number of peers.")
-                .default_value("50")
+                .help("The maximum number of peers. Peers that connect to this node beyond this \
+                       limit are disconnected. Defaults to 10% more than --target-peers.")
                 .takes_value(true),
         )
         .arg(
@@ -137,6 +145,86 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Disables the discv5 discovery protocol. The node will not search for new peers or participate in the discovery protocol.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("private")
+                .long("private")
+                .help("Reduces the information which could link this node to the validators it \
+                       hosts. Omits the client version from the libp2p identify protocol, delays \
+                       published gossip messages by a small random amount and never advertises \
+                       attestation subnets in the ENR.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("gossip-heartbeat-interval")
+                .long("gossip-heartbeat-interval")
+                .value_name("MILLISECONDS")
+                .help("The interval between gossipsub heartbeats, which maintain the mesh and \
+                       emit gossip about recently seen messages.")
+                .default_value("1000")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("gossip-duplicate-cache-time")
+                .long("gossip-duplicate-cache-time")
+                .value_name("SECONDS")
+                .help("The time for which the ids of seen gossip messages are remembered in \
+                       order to ignore duplicates. Lowering this reduces memory usage on nodes \
+                       receiving many messages, at the cost of processing more duplicates.")
+                .default_value("385")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("attestation-queue-len")
+                .long("attestation-queue-len")
+                .value_name("COUNT")
+                .help("The maximum number of gossip unaggregated attestations waiting to be \
+                       processed. Further attestations are dropped until the queue drains.")
+                .default_value("4096")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("aggregate-queue-len")
+                .long("aggregate-queue-len")
+                .value_name("COUNT")
+                .help("The maximum number of gossip aggregated attestations waiting to be \
+                       processed. Further aggregates are dropped until the queue drains.")
+                .default_value("1024")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("prioritize-aggregates")
+                .long("prioritize-aggregates")
+                .help("Process all waiting gossip aggregates before any unaggregated attestation, \
+                       so that unaggregated attestations are dropped first when the node falls \
+                       behind.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("proposer-only")
+                .long("proposer-only")
+                .help("Only perform the network duties required for block production. The node will \
+                       not subscribe to attestation subnets on behalf of validators, leaving \
+                       attestation and aggregation duties to other beacon nodes. Intended for \
+                       sentry architectures.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("subscribe-all-subnets")
+                .long("subscribe-all-subnets")
+                .help("Subscribe to all attestation subnets, regardless of the validators attached \
+                       to this node. This greatly increases bandwidth, memory and CPU usage and is \
+                       intended for research and monitoring nodes.")
+                .conflicts_with("proposer-only")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("import-all-attestations")
+                .long("import-all-attestations")
+                .help("Import all unaggregated attestations received on subscribed subnets into \
+                       fork choice, rather than only those on subnets where an attached validator \
+                       is aggregating. Usually combined with --subscribe-all-subnets.")
+                .takes_value(false),
+        )
 
         /* REST API related arguments */
         .arg(
@@ -161,6 +249,104 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("5052")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-allow-origin")
+                .long("http-allow-origin")
+                .value_name("ORIGIN")
+                .help("Set the value of the Access-Control-Allow-Origin response HTTP header. \
+                    Use * to allow any origin (not recommended in production), or a \
+                    comma-separated list of origins (e.g., http://localhost:8080). CORS is \
+                    disabled by default.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-cert")
+                .long("http-tls-cert")
+                .value_name("PATH")
+                .help("Serve the RESTful HTTP API over TLS using the PEM-encoded certificate chain \
+                    at this path. The certificate and key are reloaded when the process receives \
+                    SIGHUP.")
+                .requires("http-tls-key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-key")
+                .long("http-tls-key")
+                .value_name("PATH")
+                .help("The PEM-encoded private key for the certificate given by --http-tls-cert.")
+                .requires("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-socket-path")
+                .long("http-socket-path")
+                .value_name("PATH")
+                .help("Serve the RESTful HTTP API on a unix domain socket at this path instead of \
+                    a TCP port, ignoring --http-address and --http-port. The socket is only \
+                    accessible by the user running the beacon node. Not available on Windows.")
+                .conflicts_with("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-state-replay-budget")
+                .long("http-state-replay-budget")
+                .value_name("SLOTS")
+                .help("The maximum number of slots which may be replayed to serve a request for \
+                    a state via the debug API. Requests which exceed the budget are rejected. \
+                    Defaults to 32 (one epoch); raise it (up to --slots-per-restore-point) to \
+                    serve any historical state.")
+                .takes_value(true),
+        )
+        /* Prometheus metrics HTTP server related arguments */
+        .arg(
+            Arg::with_name("metrics")
+                .long("metrics")
+                .help("Enable a dedicated HTTP server which serves Prometheus metrics at /metrics. \
+                       Disabled by default.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("metrics-address")
+                .long("metrics-address")
+                .value_name("ADDRESS")
+                .help("Set the listen address for the Prometheus metrics HTTP server.")
+                .default_value("127.0.0.1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-port")
+                .long("metrics-port")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the Prometheus metrics HTTP server.")
+                .default_value("5054")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("metrics-allocator-stats")
+                .long("metrics-allocator-stats")
+                .help("Include statistics from the memory allocator in the metrics served by the \
+                       Prometheus metrics HTTP server. Gathering these statistics briefly locks \
+                       the allocator during each scrape.")
+                .takes_value(false),
+        )
+        /* Remote monitoring related arguments */
+        .arg(
+            Arg::with_name("monitoring-endpoint")
+                .long("monitoring-endpoint")
+                .value_name("URL")
+                .help("Periodically POST a JSON snapshot of the node (sync status, peers, head \
+                       slot) to this URL. Intended for users who do not run Prometheus.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-endpoint-period")
+                .long("monitoring-endpoint-period")
+                .value_name("SECONDS")
+                .help("The number of seconds between each snapshot sent to --monitoring-endpoint.")
+                .requires("monitoring-endpoint")
+                .default_value("60")
+                .takes_value(true),
+        )
         /* Websocket related arguments */
         .arg(
             Arg::with_name("ws")
@@ -209,6 +395,33 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies the server for a web3 connection to the Eth1 chain. Also enables the --eth1 flag. Defaults to http://127.0.0.1:8545.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("eth1-blocks-per-log-query")
+                .long("eth1-blocks-per-log-query")
+                .value_name("BLOCKS")
+                .help("Specifies the maximum number of blocks that a single deposit log query \
+                       can span. The span is automatically reduced if the eth1 node fails to \
+                       respond, so this only needs to be lowered for providers which reject \
+                       large queries outright. [default: 1000]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-max-blocks-per-update")
+                .long("eth1-max-blocks-per-update")
+                .value_name("BLOCKS")
+                .help("Specifies the maximum number of eth1 block headers to download each time \
+                       the eth1 caches are updated. Unlimited by default.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-max-update-backoff")
+                .long("eth1-max-update-backoff")
+                .value_name("MILLISECONDS")
+                .help("Specifies the maximum delay between attempts to update the eth1 caches \
+                       after consecutive failures. The delay doubles after each failure. \
+                       [default: 60000]")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slots-per-restore-point")
                 .long("slots-per-restore-point")
@@ -224,6 +437,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies how many blocks the database should cache in memory [default: 5]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("auto-compact-db")
+                .long("auto-compact-db")
+                .value_name("BOOLEAN")
+                .help("Enable or disable automatic compaction of the database after it is pruned \
+                       upon finalization.")
+                .takes_value(true)
+                .possible_values(&["true", "false"])
+                .default_value("true")
+        )
 
         /*
          * Purge.
@@ -245,4 +468,57 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value(DEFAULT_GRAFFITI)
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("finality-alert-epochs")
+                .long("finality-alert-epochs")
+                .value_name("EPOCHS")
+                .help("Log warnings when the finalized epoch falls more than this many epochs \
+                       behind the current epoch. The logs escalate to errors at twice this delay \
+                       and to critical at four times.")
+                .takes_value(true)
+                .default_value("4")
+        )
+        .arg(
+            Arg::with_name("debug-info-on-finality-delay")
+                .long("debug-info-on-finality-delay")
+                .help("When the finality delay exceeds --finality-alert-epochs, write a single \
+                       JSON file containing the fork choice DAG, peers, sync state and recent \
+                       heads to the debug_info directory in the datadir.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("shutdown-after-sync")
+                .long("shutdown-after-sync")
+                .help("Shut down cleanly once the node has synced to the head of the chain, \
+                       logging a summary of the sync. Useful for producing database snapshots.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("proposer-preparation-lookahead")
+                .long("proposer-preparation-lookahead")
+                .value_name("MILLISECONDS")
+                .help("Run fork choice and advance the head state this many milliseconds before \
+                       the start of a slot in which a validator of a connected validator client \
+                       is due to propose, so that block production only has to pack the block \
+                       body. Disabled if not supplied.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("maximum-gossip-clock-disparity")
+                .long("maximum-gossip-clock-disparity")
+                .value_name("MILLISECONDS")
+                .help("The tolerated disparity between the clock of this node and those of its \
+                       peers when checking whether blocks and attestations are from a future or \
+                       past slot. Applies to gossip and RPC. Defaults to the \
+                       MAXIMUM_GOSSIP_CLOCK_DISPARITY of the chain spec.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("purge-fork-choice")
+                .long("purge-fork-choice")
+                .help("Discard the fork choice persisted in the database and rebuild it by \
+                       replaying the blocks in the database from the finalized checkpoint. \
+                       Fork choice is rebuilt automatically if the persisted copy is invalid.")
+                .takes_value(false)
+        )
 }
@@ -4,12 +4,14 @@ use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{config::DEFAULT_DATADIR, ClientConfig, ClientGenesis};
 use eth2_libp2p::{Enr, Multiaddr};
 use eth2_testnet_config::Eth2TestnetConfig;
+use http_tls::TlsConfig;
 use slog::{crit, info, Logger};
 use ssz::Encode;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
 use std::path::PathBuf;
+use std::time::Duration;
 use types::{ChainSpec, EthSpec, GRAFFITI_BYTES_LEN};
 
 pub const BEACON_NODE_DIR: &str = "beacon";
@@ -89,10 +91,23 @@ pub fn get_config<E: EthSpec>(
         client_config.network.listen_address = listen_address;
     }
 
+    if let Some(target_peers_str) = cli_args.value_of("target-peers") {
+        client_config.network.target_peers = target_peers_str
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid number of target peers: {}", target_peers_str))?;
+    }
+
     if let Some(max_peers_str) = cli_args.value_of("max-peers") {
         client_config.network.max_peers = max_peers_str
             .parse::<usize>()
             .map_err(|_| format!("Invalid number of max peers: {}", max_peers_str))?;
+    } else {
+        let target_peers = client_config.network.target_peers;
+        client_config.network.max_peers = target_peers + target_peers / 10;
+    }
+
+    if client_config.network.max_peers < client_config.network.target_peers {
+        return Err("--max-peers must not be less than --target-peers".into());
     }
 
     if let Some(port_str) = cli_args.value_of("port") {
@@ -200,6 +215,66 @@ pub fn get_config<E: EthSpec>(
         slog::warn!(log, "Discovery is disabled. New peers will not be found");
     }
 
+    if let Some(interval) = cli_args.value_of("gossip-heartbeat-interval") {
+        let interval = interval
+            .parse::<u64>()
+            .map_err(|_| "gossip-heartbeat-interval is not a valid u64.")?;
+        if interval == 0 {
+            return Err("gossip-heartbeat-interval must be greater than zero.".into());
+        }
+        client_config.network.gs_config.heartbeat_interval = Duration::from_millis(interval);
+    }
+
+    if let Some(time) = cli_args.value_of("gossip-duplicate-cache-time") {
+        client_config.network.gs_config.duplicate_cache_time = Duration::from_secs(
+            time.parse::<u64>()
+                .map_err(|_| "gossip-duplicate-cache-time is not a valid u64.")?,
+        );
+    }
+
+    if let Some(len) = cli_args.value_of("attestation-queue-len") {
+        client_config.network.attestation_queue_len = len
+            .parse::<usize>()
+            .map_err(|_| "attestation-queue-len is not a valid usize.")?;
+        if client_config.network.attestation_queue_len == 0 {
+            return Err("attestation-queue-len must be greater than zero.".into());
+        }
+    }
+
+    if let Some(len) = cli_args.value_of("aggregate-queue-len") {
+        client_config.network.aggregate_queue_len = len
+            .parse::<usize>()
+            .map_err(|_| "aggregate-queue-len is not a valid usize.")?;
+        if client_config.network.aggregate_queue_len == 0 {
+            return Err("aggregate-queue-len must be greater than zero.".into());
+        }
+    }
+
+    if cli_args.is_present("prioritize-aggregates") {
+        client_config.network.prioritize_aggregates = true;
+    }
+
+    if cli_args.is_present("private") {
+        client_config.network.private = true;
+    }
+
+    if cli_args.is_present("subscribe-all-subnets") {
+        client_config.network.subscribe_all_subnets = true;
+    }
+
+    if cli_args.is_present("import-all-attestations") {
+        client_config.network.import_all_attestations = true;
+    }
+
+    if cli_args.is_present("proposer-only") {
+        client_config.network.proposer_only = true;
+        slog::info!(
+            log,
+            "Running in proposer-only mode";
+            "info" => "attestation subnet subscriptions will be ignored"
+        );
+    }
+
     /*
      * Http server
      */
@@ -220,6 +295,73 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "http-port is not a valid u16.")?;
     }
 
+    if let Some(allow_origin) = cli_args.value_of("http-allow-origin") {
+        // Pre-validate the config value to give feedback to the user on node startup, instead of
+        // as late as when the first API response is produced.
+        rest_api::Cors::from_allow_origin(allow_origin)
+            .map_err(|e| format!("Invalid --http-allow-origin: {}", e))?;
+        client_config.rest_api.allow_origin = Some(allow_origin.to_string());
+    }
+
+    if let (Some(cert), Some(key)) = (
+        cli_args.value_of("http-tls-cert"),
+        cli_args.value_of("http-tls-key"),
+    ) {
+        client_config.rest_api.tls_config = Some(TlsConfig {
+            cert: PathBuf::from(cert),
+            key: PathBuf::from(key),
+        });
+    }
+
+    if let Some(socket_path) = cli_args.value_of("http-socket-path") {
+        if cfg!(not(unix)) {
+            return Err("--http-socket-path is only supported on unix platforms".into());
+        }
+        client_config.rest_api.socket_path = Some(PathBuf::from(socket_path));
+    }
+
+    if let Some(budget) = clap_utils::parse_optional(cli_args, "http-state-replay-budget")? {
+        client_config.rest_api.state_replay_budget = budget;
+    }
+
+    /*
+     * Prometheus metrics HTTP server
+     */
+
+    if cli_args.is_present("metrics") {
+        client_config.metrics.enabled = true;
+    }
+
+    if let Some(address) = cli_args.value_of("metrics-address") {
+        client_config.metrics.listen_address = address
+            .parse::<Ipv4Addr>()
+            .map_err(|_| "metrics-address is not a valid IPv4 address.")?;
+    }
+
+    if let Some(port) = cli_args.value_of("metrics-port") {
+        client_config.metrics.port = port
+            .parse::<u16>()
+            .map_err(|_| "metrics-port is not a valid u16.")?;
+    }
+
+    if cli_args.is_present("metrics-allocator-stats") {
+        client_config.metrics.allocator_metrics = true;
+    }
+
+    /*
+     * Remote monitoring
+     */
+
+    if let Some(endpoint) = cli_args.value_of("monitoring-endpoint") {
+        let mut monitoring_config = monitoring_api::Config::new(endpoint.to_string());
+        if let Some(period) = cli_args.value_of("monitoring-endpoint-period") {
+            monitoring_config.update_period_secs = period
+                .parse::<u64>()
+                .map_err(|_| "monitoring-endpoint-period is not a valid u64.")?;
+        }
+        client_config.monitoring_api = Some(monitoring_config);
+    }
+
     /*
      * Websocket server
      */
@@ -264,6 +406,18 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.endpoint = val.to_string();
     }
 
+    if let Some(blocks) = clap_utils::parse_optional(cli_args, "eth1-blocks-per-log-query")? {
+        client_config.eth1.blocks_per_log_query = blocks;
+    }
+
+    if let Some(blocks) = clap_utils::parse_optional(cli_args, "eth1-max-blocks-per-update")? {
+        client_config.eth1.max_blocks_per_update = Some(blocks);
+    }
+
+    if let Some(millis) = clap_utils::parse_optional(cli_args, "eth1-max-update-backoff")? {
+        client_config.eth1.max_update_backoff_millis = millis;
+    }
+
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
         client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
     }
@@ -285,6 +439,12 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    if let Some(auto_compact_db) = cli_args.value_of("auto-compact-db") {
+        client_config.store.compact_on_prune = auto_compact_db
+            .parse()
+            .map_err(|_| "auto-compact-db is not a valid boolean".to_string())?;
+    }
+
     if spec_constants != client_config.spec_constants {
         crit!(log, "Specification constants do not match.";
               "client_config" => client_config.spec_constants,
@@ -311,6 +471,7 @@ pub fn get_config<E: EthSpec>(
         client_config.network.discovery_port =
             unused_port("udp").map_err(|e| format!("Failed to get port for discovery: {}", e))?;
         client_config.rest_api.port = 0;
+        client_config.metrics.port = 0;
         client_config.websocket_server.port = 0;
     }
 
@@ -327,6 +488,7 @@ pub fn get_config<E: EthSpec>(
     client_config.eth1.lowest_cached_block_number =
         client_config.eth1.deposit_contract_deploy_block;
     client_config.eth1.follow_distance = spec.eth1_follow_distance;
+    client_config.eth1.set_block_cache_truncation::<E>(spec);
 
     if let Some(mut boot_nodes) = eth2_testnet_config.boot_enr {
         client_config.network.boot_nodes.append(&mut boot_nodes)
@@ -359,6 +521,30 @@ pub fn get_config<E: EthSpec>(
         }
     }
 
+    if let Some(epochs) = cli_args.value_of("finality-alert-epochs") {
+        client_config.finality_alert_epochs = epochs
+            .parse()
+            .map_err(|_| "finality-alert-epochs is not a valid integer".to_string())?;
+    }
+
+    if cli_args.is_present("debug-info-on-finality-delay") {
+        client_config.debug_info_on_finality_delay = true;
+    }
+
+    if cli_args.is_present("shutdown-after-sync") {
+        client_config.shutdown_after_sync = true;
+    }
+
+    client_config.proposer_preparation_lookahead_ms =
+        clap_utils::parse_optional(cli_args, "proposer-preparation-lookahead")?;
+
+    client_config.chain.maximum_gossip_clock_disparity_ms =
+        clap_utils::parse_optional(cli_args, "maximum-gossip-clock-disparity")?;
+
+    if cli_args.is_present("purge-fork-choice") {
+        client_config.chain.purge_fork_choice = true;
+    }
+
     Ok(client_config)
 }
 
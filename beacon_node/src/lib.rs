@@ -21,6 +21,7 @@ use config::get_config;
 use environment::RuntimeContext;
 use slog::{info, warn};
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 use types::EthSpec;
 
 /// A type-alias to the tighten the definition of a production-intended `Client`.
@@ -127,7 +128,13 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let builder = builder
             .build_beacon_chain()?
             .network(&mut client_config.network)?
-            .notifier()?;
+            .notifier(&client_config)?;
+
+        let builder = if let Some(lookahead_ms) = client_config.proposer_preparation_lookahead_ms {
+            builder.proposer_preparation(Duration::from_millis(lookahead_ms))?
+        } else {
+            builder
+        };
 
         let builder = if client_config.rest_api.enabled {
             builder.http_server(&client_config, &http_eth2_config, events)?
@@ -135,6 +142,18 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             builder
         };
 
+        let builder = if client_config.metrics.enabled {
+            builder.metrics_server(&client_config)?
+        } else {
+            builder
+        };
+
+        let builder = if let Some(monitoring_config) = &client_config.monitoring_api {
+            builder.monitoring_client(monitoring_config)?
+        } else {
+            builder
+        };
+
         Ok(Self(builder.build()))
     }
 
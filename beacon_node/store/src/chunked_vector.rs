@@ -520,6 +520,23 @@ pub fn load_vector_from_db<F: FixedLengthField<E>, E: EthSpec, S: KeyValueStore<
     Ok(result.into())
 }
 
+/// Load the single value at `vindex` of a fixed length field, reading only the chunk containing it.
+///
+/// Returns `None` if the value has not been stored, i.e. its chunk is missing or the entry still
+/// holds the default value used to fill unset entries.
+pub fn load_value_from_db<F: FixedLengthField<E>, E: EthSpec, S: KeyValueStore<E>>(
+    store: &S,
+    vindex: usize,
+) -> Result<Option<F::Value>, Error> {
+    let chunk_size = F::chunk_size();
+    let chunk_index = vindex / chunk_size;
+    let key = &chunk_key(chunk_index as u64)[..];
+
+    Ok(Chunk::<F::Value>::load(store, F::column(), key)?
+        .and_then(|chunk| chunk.values.get(vindex % chunk_size).cloned())
+        .filter(|value| *value != F::Value::default()))
+}
+
 /// The historical roots are stored in vector chunks, despite not actually being a vector.
 pub fn load_variable_list_from_db<F: VariableLengthField<E>, E: EthSpec, S: KeyValueStore<E>>(
     store: &S,
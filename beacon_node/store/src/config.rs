@@ -11,6 +11,8 @@ pub struct StoreConfig {
     pub slots_per_restore_point: u64,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
+    /// Whether to compact the database after pruning it upon finalization.
+    pub compact_on_prune: bool,
 }
 
 impl Default for StoreConfig {
@@ -19,6 +21,7 @@ impl Default for StoreConfig {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            compact_on_prune: true,
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::chunked_vector::{
-    store_updated_vector, BlockRoots, HistoricalRoots, RandaoMixes, StateRoots,
+    load_value_from_db, store_updated_vector, BlockRoots, HistoricalRoots, RandaoMixes, StateRoots,
 };
 use crate::config::StoreConfig;
 use crate::forwards_iter::HybridForwardsBlockRootsIterator;
@@ -9,11 +9,12 @@ use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metrics;
 use crate::{
-    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
-    StoreOp,
+    get_key_for_col, ColumnSize, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState,
+    StoreItem, StoreOp, CURRENT_SCHEMA_VERSION,
 };
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
+use serde_derive::{Deserialize, Serialize};
 use slog::{debug, error, trace, warn, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
@@ -21,6 +22,7 @@ use state_processing::{
     per_block_processing, per_slot_processing, BlockProcessingError, BlockSignatureStrategy,
     SlotProcessingError,
 };
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::Path;
@@ -658,11 +660,63 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(state)
     }
 
+    /// Returns the root of the canonical block at `slot` from the freezer database, reading a
+    /// single chunk.
+    ///
+    /// As with `BeaconState::block_roots`, a skipped slot has the root of the most recent prior
+    /// block.
+    ///
+    /// Returns `None` if `slot` is not prior to the split slot, or if its root was never stored
+    /// (e.g., the database was migrated by a version which only stored roots at restore points).
+    pub fn get_cold_block_root(&self, slot: Slot) -> Result<Option<Hash256>, Error> {
+        if slot >= self.get_split_slot() {
+            return Ok(None);
+        }
+        load_value_from_db::<BlockRoots, _, _>(&self.cold_db, slot.as_usize())
+    }
+
+    /// Returns the root of the canonical state at `slot` from the freezer database, reading a
+    /// single chunk.
+    ///
+    /// Returns `None` under the same conditions as `Self::get_cold_block_root`.
+    pub fn get_cold_state_root(&self, slot: Slot) -> Result<Option<Hash256>, Error> {
+        if slot >= self.get_split_slot() {
+            return Ok(None);
+        }
+        load_value_from_db::<StateRoots, _, _>(&self.cold_db, slot.as_usize())
+    }
+
+    /// Return the configuration of this database.
+    pub fn get_config(&self) -> &StoreConfig {
+        &self.config
+    }
+
+    /// Compact the hot database, reclaiming the space used by pruned blocks and states.
+    pub fn compact(&self) -> Result<(), Error> {
+        self.hot_db.compact()
+    }
+
     /// Fetch a copy of the current split slot from memory.
     pub fn get_split_slot(&self) -> Slot {
         self.split.read().slot
     }
 
+    /// Return a summary of the database, including the size of each column.
+    ///
+    /// Reads every key in the database, so should be used sparingly.
+    pub fn database_info(&self) -> Result<DatabaseInfo, Error> {
+        let split = *self.split.read();
+
+        Ok(DatabaseInfo {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            split_slot: split.slot,
+            split_state_root: split.state_root,
+            slots_per_restore_point: self.config.slots_per_restore_point,
+            hot_db: column_sizes::<E, _>(&self.hot_db)?,
+            cold_db: column_sizes::<E, _>(&self.cold_db)?,
+        })
+    }
+
     /// Fetch the slot of the most recently stored restore point.
     pub fn get_latest_restore_point_slot(&self) -> Slot {
         (self.get_split_slot() - 1) / self.config.slots_per_restore_point
@@ -812,6 +866,26 @@ pub fn process_finalization<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         hot_db_ops.push(StoreOp::DeleteState(state_root.into(), slot));
     }
 
+    // 2. Store the block and state roots of the new frozen head. Restore points only store the
+    // roots prior to their own slot, so without this the roots between the latest restore point
+    // and the split slot could not be read from the freezer.
+    let mut cold_db_ops: Vec<KeyValueStoreOp> = Vec::new();
+    store_updated_vector(
+        BlockRoots,
+        &store.cold_db,
+        frozen_head,
+        &store.spec,
+        &mut cold_db_ops,
+    )?;
+    store_updated_vector(
+        StateRoots,
+        &store.cold_db,
+        frozen_head,
+        &store.spec,
+        &mut cold_db_ops,
+    )?;
+    store.cold_db.do_atomically(cold_db_ops)?;
+
     // Warning: Critical section.  We have to take care not to put any of the two databases in an
     //          inconsistent state if the OS process dies at any point during the freezeing
     //          procedure.
@@ -874,6 +948,31 @@ pub fn process_finalization<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     Ok(())
 }
 
+/// Returns the size of every column of `db`, keyed by the name of the column.
+fn column_sizes<E: EthSpec, S: ItemStore<E>>(
+    db: &S,
+) -> Result<BTreeMap<String, ColumnSize>, Error> {
+    DBColumn::ALL
+        .iter()
+        .map(|column| Ok((format!("{:?}", column), db.column_size((*column).into())?)))
+        .collect()
+}
+
+/// A summary of the database, as returned by `HotColdDB::database_info`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    /// The `CURRENT_SCHEMA_VERSION` of this node.
+    pub schema_version: u64,
+    /// The slot at which the hot and freezer databases are split.
+    pub split_slot: Slot,
+    pub split_state_root: Hash256,
+    pub slots_per_restore_point: u64,
+    /// The size of each column of the hot database.
+    pub hot_db: BTreeMap<String, ColumnSize>,
+    /// The size of each column of the freezer database.
+    pub cold_db: BTreeMap<String, ColumnSize>,
+}
+
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 pub struct Split {
@@ -2,9 +2,11 @@ use super::*;
 use crate::metrics;
 use db_key::Key;
 use leveldb::database::batch::{Batch, Writebatch};
+use leveldb::database::compaction::Compaction;
 use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
+use leveldb::iterator::{Iterable, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
 use std::marker::PhantomData;
 use std::path::Path;
@@ -55,6 +57,12 @@ impl<E: EthSpec> LevelDB<E> {
 
         metrics::inc_counter(&metrics::DISK_DB_WRITE_COUNT);
         metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as i64);
+        metrics::inc_counter_vec(&metrics::DISK_DB_COLUMN_WRITE_COUNT, &[col]);
+        metrics::inc_counter_vec_by(
+            &metrics::DISK_DB_COLUMN_WRITE_BYTES,
+            &[col],
+            val.len() as i64,
+        );
         let timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
         self.db
@@ -85,6 +93,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         let column_key = get_key_for_col(col, key);
 
         metrics::inc_counter(&metrics::DISK_DB_READ_COUNT);
+        metrics::inc_counter_vec(&metrics::DISK_DB_COLUMN_READ_COUNT, &[col]);
         let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
 
         self.db
@@ -93,6 +102,11 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
             .map(|opt| {
                 opt.map(|bytes| {
                     metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as i64);
+                    metrics::inc_counter_vec_by(
+                        &metrics::DISK_DB_COLUMN_READ_BYTES,
+                        &[col],
+                        bytes.len() as i64,
+                    );
                     metrics::stop_timer(timer);
                     bytes
                 })
@@ -116,6 +130,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         let column_key = get_key_for_col(col, key);
 
         metrics::inc_counter(&metrics::DISK_DB_DELETE_COUNT);
+        metrics::inc_counter_vec(&metrics::DISK_DB_COLUMN_DELETE_COUNT, &[col]);
 
         self.db
             .delete(self.write_options(), BytesKey::from_vec(column_key))
@@ -127,10 +142,21 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         for op in ops_batch {
             match op {
                 KeyValueStoreOp::PutKeyValue(key, value) => {
+                    let col = column_of_key(&key);
+                    metrics::inc_counter_vec(&metrics::DISK_DB_COLUMN_WRITE_COUNT, &[col]);
+                    metrics::inc_counter_vec_by(
+                        &metrics::DISK_DB_COLUMN_WRITE_BYTES,
+                        &[col],
+                        value.len() as i64,
+                    );
                     leveldb_batch.put(BytesKey::from_vec(key), &value);
                 }
 
                 KeyValueStoreOp::DeleteKey(key) => {
+                    metrics::inc_counter_vec(
+                        &metrics::DISK_DB_COLUMN_DELETE_COUNT,
+                        &[column_of_key(&key)],
+                    );
                     leveldb_batch.delete(BytesKey::from_vec(key));
                 }
             }
@@ -138,10 +164,56 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         self.db.write(self.write_options(), &leveldb_batch)?;
         Ok(())
     }
+
+    /// Compact the columns which are subject to pruning.
+    ///
+    /// LevelDB only reclaims the space used by deleted keys once their files are compacted, which
+    /// can otherwise take a very long time for keys that are never read again.
+    fn compact(&self) -> Result<(), Error> {
+        metrics::inc_counter(&metrics::DISK_DB_COMPACT_COUNT);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_COMPACT_TIMES);
+
+        for column in &[
+            DBColumn::BeaconState,
+            DBColumn::BeaconStateSummary,
+            DBColumn::BeaconBlock,
+        ] {
+            let column: &str = (*column).into();
+            // All keys in these columns are 32-byte hashes, so the range below covers them all.
+            let start_key = BytesKey::from_vec(get_key_for_col(column, &[]));
+            let end_key = BytesKey::from_vec(get_key_for_col(column, &[0xff; 32]));
+            self.db.compact(&start_key, &end_key);
+        }
+        Ok(())
+    }
+
+    fn column_size(&self, col: &str) -> Result<ColumnSize, Error> {
+        let start_key = BytesKey::from_vec(get_key_for_col(col, &[]));
+
+        Ok(self
+            .db
+            .iter(self.read_options())
+            .from(&start_key)
+            .take_while(|(key, _)| key.matches_column(col))
+            .fold(ColumnSize::default(), |size, (key, value)| ColumnSize {
+                keys: size.keys + 1,
+                bytes: size.bytes + (key.key.len() + value.len()) as u64,
+            }))
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
 
+/// The length of the column prefix of every key created by `get_key_for_col`.
+const COLUMN_PREFIX_LEN: usize = 3;
+
+/// Returns the name of the column of a key created by `get_key_for_col`, for use in metrics.
+fn column_of_key(key: &[u8]) -> &str {
+    key.get(..COLUMN_PREFIX_LEN)
+        .and_then(|prefix| std::str::from_utf8(prefix).ok())
+        .unwrap_or("unknown")
+}
+
 /// Used for keying leveldb.
 pub struct BytesKey {
     key: Vec<u8>,
@@ -161,6 +233,11 @@ impl BytesKey {
     fn from_vec(key: Vec<u8>) -> Self {
         Self { key }
     }
+
+    /// Return `true` if this key was created by `get_key_for_col` for `column`.
+    fn matches_column(&self, column: &str) -> bool {
+        self.key.starts_with(column.as_bytes())
+    }
 }
 
 impl From<LevelDBError> for Error {
@@ -24,10 +24,11 @@ mod partial_beacon_state;
 
 pub mod iter;
 
+use serde_derive::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
+pub use self::hot_cold_store::{DatabaseInfo, HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
@@ -36,6 +37,12 @@ pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
 pub use metrics::scrape_for_metrics;
 pub use types::*;
 
+/// The version of the on-disk database schema.
+///
+/// Must be incremented whenever the representation of any item in the database changes, so that
+/// databases (and snapshots of them) from other versions can be detected.
+pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
 pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Retrieve some bytes in `column` with `key`.
     fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error>;
@@ -59,6 +66,22 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Execute either all of the operations in `batch` or none at all, returning an error.
     fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error>;
+
+    /// Compact the database, reclaiming the space used by deleted keys.
+    fn compact(&self) -> Result<(), Error>;
+
+    /// Return the number of keys in `column` and the total size of those keys and their values.
+    ///
+    /// This reads the entire column and may be slow for large columns.
+    fn column_size(&self, column: &str) -> Result<ColumnSize, Error>;
+}
+
+/// The size of a column, as returned by `KeyValueStore::column_size`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSize {
+    pub keys: u64,
+    /// The total size of the keys (including the column prefix) and values, in bytes.
+    pub bytes: u64,
 }
 
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
@@ -152,6 +175,26 @@ pub enum DBColumn {
     DhtEnrs,
 }
 
+impl DBColumn {
+    /// Every column, for iterating over the whole database.
+    pub const ALL: [DBColumn; 14] = [
+        DBColumn::BeaconMeta,
+        DBColumn::BeaconBlock,
+        DBColumn::BeaconState,
+        DBColumn::BeaconChain,
+        DBColumn::OpPool,
+        DBColumn::Eth1Cache,
+        DBColumn::ForkChoice,
+        DBColumn::BeaconRestorePoint,
+        DBColumn::BeaconStateSummary,
+        DBColumn::BeaconBlockRoots,
+        DBColumn::BeaconStateRoots,
+        DBColumn::BeaconHistoricalRoots,
+        DBColumn::BeaconRandaoMixes,
+        DBColumn::DhtEnrs,
+    ];
+}
+
 impl Into<&'static str> for DBColumn {
     /// Returns a `&str` that can be used for keying a key-value data base.
     fn into(self) -> &'static str {
@@ -233,11 +276,27 @@ mod tests {
         let retrieved = store.get(&key).unwrap().unwrap();
         assert_eq!(item, retrieved);
 
+        let column: &str = DBColumn::BeaconBlock.into();
+        let key_len = get_key_for_col(column, key.as_bytes()).len();
+        assert_eq!(
+            store.column_size(column).unwrap(),
+            ColumnSize {
+                keys: 1,
+                bytes: (key_len + item.as_ssz_bytes().len()) as u64,
+            }
+        );
+        assert_eq!(
+            store.column_size(DBColumn::BeaconState.into()).unwrap(),
+            ColumnSize::default()
+        );
+
         store.delete::<StorableThing>(&key).unwrap();
 
         assert_eq!(store.exists::<StorableThing>(&key).unwrap(), false);
 
         assert_eq!(store.get::<StorableThing>(&key).unwrap(), None);
+
+        assert_eq!(store.column_size(column).unwrap(), ColumnSize::default());
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use super::{ColumnSize, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -87,6 +87,24 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
         }
         Ok(())
     }
+
+    fn compact(&self) -> Result<(), Error> {
+        // no-op
+        Ok(())
+    }
+
+    fn column_size(&self, col: &str) -> Result<ColumnSize, Error> {
+        let prefix = Self::get_key_for_col(col, &[]);
+        Ok(self
+            .db
+            .read()
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .fold(ColumnSize::default(), |size, (key, value)| ColumnSize {
+                keys: size.keys + 1,
+                bytes: size.bytes + (key.len() + value.len()) as u64,
+            }))
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
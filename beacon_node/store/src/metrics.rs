@@ -1,5 +1,7 @@
 pub use lighthouse_metrics::{set_gauge, try_create_int_gauge, *};
 
+use std::ffi::OsStr;
+
 use std::fs;
 use std::path::Path;
 
@@ -43,6 +45,57 @@ lazy_static! {
         "store_disk_db_delete_count_total",
         "Total number of deletions from the hot on-disk DB"
     );
+    pub static ref DISK_DB_COLUMN_READ_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_column_read_count_total",
+        "Total number of reads from the on-disk DBs, by column",
+        &["col"]
+    );
+    pub static ref DISK_DB_COLUMN_READ_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_column_read_bytes_total",
+        "Number of bytes read from the on-disk DBs, by column",
+        &["col"]
+    );
+    pub static ref DISK_DB_COLUMN_WRITE_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_column_write_count_total",
+        "Total number of writes to the on-disk DBs, by column",
+        &["col"]
+    );
+    pub static ref DISK_DB_COLUMN_WRITE_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_column_write_bytes_total",
+        "Number of bytes written to the on-disk DBs, by column",
+        &["col"]
+    );
+    pub static ref DISK_DB_COLUMN_DELETE_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_column_delete_count_total",
+        "Total number of deletions from the on-disk DBs, by column",
+        &["col"]
+    );
+    /*
+     * LevelDB files
+     */
+    pub static ref DISK_DB_TABLE_FILES: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "store_disk_db_table_files",
+        "Number of LevelDB table files in each on-disk DB",
+        &["db"]
+    );
+    pub static ref DISK_DB_TABLE_SIZE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "store_disk_db_table_size",
+        "Size of the LevelDB table files in each on-disk DB (bytes)",
+        &["db"]
+    );
+    pub static ref DISK_DB_LOG_SIZE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "store_disk_db_log_size",
+        "Size of the LevelDB write-ahead log in each on-disk DB (bytes)",
+        &["db"]
+    );
+    pub static ref DISK_DB_COMPACT_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_disk_db_compact_count_total",
+        "Total number of compactions of the on-disk DB"
+    );
+    pub static ref DISK_DB_COMPACT_TIMES: Result<Histogram> = try_create_histogram(
+        "store_disk_db_compact_seconds",
+        "Time taken to compact the on-disk DB"
+    );
     /*
      * Beacon State
      */
@@ -133,6 +186,45 @@ pub fn scrape_for_metrics(db_path: &Path, freezer_db_path: &Path) {
     set_gauge(&DISK_DB_SIZE, db_size as i64);
     let freezer_db_size = size_of_dir(freezer_db_path);
     set_gauge(&FREEZER_DB_SIZE, freezer_db_size as i64);
+
+    scrape_leveldb_files("hot", db_path);
+    scrape_leveldb_files("freezer", freezer_db_path);
+}
+
+/// Updates the metrics describing the files of the LevelDB database at `path`.
+///
+/// LevelDB keeps key-values in immutable table files, which are rewritten during compaction, and
+/// appends recent writes to a log file until they are flushed to a table.
+fn scrape_leveldb_files(db: &str, path: &Path) {
+    let mut table_files = 0;
+    let mut table_size = 0;
+    let mut log_size = 0;
+
+    if let Ok(iter) = fs::read_dir(path) {
+        for entry in iter.filter_map(std::result::Result::ok) {
+            let path = entry.path();
+            let size = size_of_dir_entry(entry);
+
+            match path.extension().and_then(OsStr::to_str) {
+                Some("ldb") | Some("sst") => {
+                    table_files += 1;
+                    table_size += size;
+                }
+                Some("log") => log_size += size,
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(gauge) = get_int_gauge(&DISK_DB_TABLE_FILES, &[db]) {
+        gauge.set(table_files);
+    }
+    if let Some(gauge) = get_int_gauge(&DISK_DB_TABLE_SIZE, &[db]) {
+        gauge.set(table_size as i64);
+    }
+    if let Some(gauge) = get_int_gauge(&DISK_DB_LOG_SIZE, &[db]) {
+        gauge.set(log_size as i64);
+    }
 }
 
 fn size_of_dir(path: &Path) -> u64 {
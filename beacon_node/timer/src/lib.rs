@@ -2,9 +2,11 @@
 //!
 //! This service allows task execution on the beacon node for various functionality.
 
+use beacon_chain::attestation_simulator::AttestationSimulator;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use futures::stream::StreamExt;
-use slog::info;
+use parking_lot::Mutex;
+use slog::{info, warn};
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::Duration;
@@ -36,3 +38,116 @@ pub fn spawn_timer<T: BeaconChainTypes>(
 
     Ok(())
 }
+
+/// Spawns a service which produces a simulated attestation one third of the way through each slot
+/// and later checks whether it was correct and would have been included, see
+/// `beacon_chain::attestation_simulator`.
+pub fn spawn_attestation_simulator<T: BeaconChainTypes>(
+    executor: environment::TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    milliseconds_per_slot: u64,
+) -> Result<(), &'static str> {
+    let log = executor.log().clone();
+    let slot_duration = Duration::from_millis(milliseconds_per_slot);
+    let start_instant = Instant::now()
+        + beacon_chain
+            .slot_clock
+            .duration_to_next_slot()
+            .ok_or_else(|| "attestation_simulator unable to determine time to next slot")?
+        + beacon_chain.slot_clock.unagg_attestation_production_delay();
+
+    let simulator = Arc::new(Mutex::new(AttestationSimulator::new(
+        beacon_chain.clone(),
+        log.clone(),
+    )));
+
+    // Warning: `interval_at` panics if `milliseconds_per_slot` = 0.
+    let mut interval = interval_at(start_instant, slot_duration);
+    let inner_executor = executor.clone();
+    let simulator_future = async move {
+        while interval.next().await.is_some() {
+            let current_slot = match beacon_chain.slot() {
+                Ok(slot) => slot,
+                Err(_) => continue,
+            };
+            let simulator = simulator.clone();
+
+            // Attestation production reads from the database, so avoid blocking the executor.
+            inner_executor.spawn_blocking(
+                move || {
+                    let mut simulator = simulator.lock();
+                    simulator.produce(current_slot);
+                    simulator.check(current_slot);
+                },
+                "attestation_simulator_tick",
+            );
+        }
+    };
+
+    executor.spawn(simulator_future, "attestation_simulator");
+    info!(log, "Attestation simulator service started");
+
+    Ok(())
+}
+
+/// Spawns a service which runs fork choice and advances the head state `lookahead` before the
+/// start of each slot, if a validator registered for proposer preparation is due to propose in
+/// that slot.
+pub fn spawn_proposer_preparation<T: BeaconChainTypes>(
+    executor: environment::TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    milliseconds_per_slot: u64,
+    lookahead: Duration,
+) -> Result<(), &'static str> {
+    let log = executor.log().clone();
+    let slot_duration = Duration::from_millis(milliseconds_per_slot);
+
+    if lookahead >= slot_duration {
+        return Err("proposer preparation lookahead must be less than the slot duration");
+    }
+
+    let duration_to_next_slot = beacon_chain
+        .slot_clock
+        .duration_to_next_slot()
+        .ok_or_else(|| "proposer_preparation unable to determine time to next slot")?;
+    let start_instant = if duration_to_next_slot > lookahead {
+        Instant::now() + (duration_to_next_slot - lookahead)
+    } else {
+        Instant::now() + duration_to_next_slot + (slot_duration - lookahead)
+    };
+
+    // Warning: `interval_at` panics if `milliseconds_per_slot` = 0.
+    let mut interval = interval_at(start_instant, slot_duration);
+    let inner_executor = executor.clone();
+    let inner_log = log.clone();
+    let preparation_future = async move {
+        while interval.next().await.is_some() {
+            let beacon_chain = beacon_chain.clone();
+            let log = inner_log.clone();
+
+            // Fork choice and state advance read from the database, so avoid blocking the
+            // executor.
+            inner_executor.spawn_blocking(
+                move || {
+                    if let Err(e) = beacon_chain.prepare_beacon_proposer() {
+                        warn!(
+                            log,
+                            "Failed to prepare beacon proposer";
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+                },
+                "proposer_preparation_tick",
+            );
+        }
+    };
+
+    executor.spawn(preparation_future, "proposer_preparation");
+    info!(
+        log,
+        "Proposer preparation service started";
+        "lookahead_ms" => lookahead.as_millis() as u64
+    );
+
+    Ok(())
+}
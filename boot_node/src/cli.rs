@@ -50,6 +50,15 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("The UDP port of the boot node's ENR. This is the port that external peers will dial to reach this boot node. Set this only if the external port differs from the listening port.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("network-dir")
+                .long("network-dir")
+                .value_name("DIR")
+                .help("The directory in which the boot node's network key and ENR are stored. A new key is generated \
+                if none exists, allowing the boot node to keep the same identity across restarts. Defaults to \
+                ~/.lighthouse/boot_node.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enable-enr-auto-update")
                 .short("x")
@@ -1,7 +1,11 @@
 use clap::ArgMatches;
 use discv5::{enr::CombinedKey, Enr};
-use std::convert::TryFrom;
+use eth2_libp2p::discovery::ENR_FILENAME;
+use eth2_libp2p::{load_private_key, CombinedKeyExt, NetworkConfig};
+use slog::{debug, Logger};
+use std::fs;
 use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 
 /// A set of configuration parameters for the bootnode, established from CLI arguments.
 pub struct BootNodeConfig {
@@ -11,12 +15,12 @@ pub struct BootNodeConfig {
     pub local_enr: Enr,
     pub local_key: CombinedKey,
     pub auto_update: bool,
+    /// The directory in which the network key and ENR are persisted.
+    pub network_dir: PathBuf,
 }
 
-impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
-    type Error = String;
-
-    fn try_from(matches: &ArgMatches<'_>) -> Result<Self, Self::Error> {
+impl BootNodeConfig {
+    pub fn new(matches: &ArgMatches<'_>, log: &Logger) -> Result<Self, String> {
         let listen_address = matches
             .value_of("listen-address")
             .expect("required parameter")
@@ -41,7 +45,7 @@ impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
         };
 
         let enr_port = {
-            if let Some(port) = matches.value_of("boot-node-enr-port") {
+            if let Some(port) = matches.value_of("enr-port") {
                 port.parse::<u16>()
                     .map_err(|_| format!("Invalid ENR port"))?
             } else {
@@ -56,29 +60,76 @@ impl TryFrom<&ArgMatches<'_>> for BootNodeConfig {
             resolve_address(address_string.into(), enr_port)?
         };
 
-        let auto_update = matches.is_present("enable-enr_auto_update");
+        let auto_update = matches.is_present("enable-enr-auto-update");
 
         // the address to listen on
-        let listen_socket = SocketAddr::new(listen_address.into(), enr_port);
+        let listen_socket = SocketAddr::new(listen_address, listen_port);
+
+        let network_dir = matches
+            .value_of("network-dir")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let mut network_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+                network_dir.push(".lighthouse");
+                network_dir.push("boot_node");
+                network_dir
+            });
+
+        // Load the network key from disk, generating and persisting a new one if required
+        let network_config = NetworkConfig {
+            network_dir: network_dir.clone(),
+            ..NetworkConfig::default()
+        };
+        let local_key = CombinedKey::from_libp2p(&load_private_key(&network_config, log))?;
 
-        // Generate a new key and build a new ENR
-        let local_key = CombinedKey::generate_secp256k1();
-        let local_enr = discv5::enr::EnrBuilder::new("v4")
+        // Build the ENR, reusing the one on disk if it is still valid
+        let mut local_enr = discv5::enr::EnrBuilder::new("v4")
             .ip(enr_address)
             .udp(enr_port)
             .build(&local_key)
             .map_err(|e| format!("Failed to build ENR: {:?}", e))?;
 
+        if let Some(disk_enr) = load_enr_from_disk(&network_dir, log) {
+            if disk_enr.node_id() == local_enr.node_id() {
+                if disk_enr.ip() == local_enr.ip() && disk_enr.udp() == local_enr.udp() {
+                    debug!(log, "ENR loaded from disk"; "file" => format!("{:?}", network_dir));
+                    local_enr = disk_enr;
+                } else {
+                    // same node id, different configuration - update the sequence number
+                    let new_seq_no = disk_enr.seq().checked_add(1).ok_or_else(|| {
+                        "ENR sequence number on file is too large. Remove it to generate a new NodeId"
+                    })?;
+                    local_enr
+                        .set_seq(new_seq_no, &local_key)
+                        .map_err(|e| format!("Could not update ENR sequence number: {:?}", e))?;
+                    debug!(log, "ENR sequence number increased"; "seq" => new_seq_no);
+                }
+            }
+        }
+
         Ok(BootNodeConfig {
             listen_socket,
             boot_nodes,
             local_enr,
             local_key,
             auto_update,
+            network_dir,
         })
     }
 }
 
+/// Reads the ENR stored in `network_dir`, if it exists and is valid.
+fn load_enr_from_disk(network_dir: &Path, log: &Logger) -> Option<Enr> {
+    let enr_string = fs::read_to_string(network_dir.join(ENR_FILENAME)).ok()?;
+    match enr_string.trim().parse::<Enr>() {
+        Ok(enr) => Some(enr),
+        Err(e) => {
+            debug!(log, "ENR from file could not be decoded"; "error" => format!("{:?}", e));
+            None
+        }
+    }
+}
+
 /// Resolves an IP/DNS string to an IpAddr.
 fn resolve_address(address_string: String, port: u16) -> Result<IpAddr, String> {
     match address_string.parse::<IpAddr>() {
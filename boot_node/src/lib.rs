@@ -3,7 +3,6 @@ use clap::ArgMatches;
 use slog;
 use slog::{o, Drain, Level, Logger};
 
-use std::convert::TryFrom;
 mod cli;
 mod config;
 mod server;
@@ -57,7 +56,7 @@ fn main(matches: &ArgMatches<'_>, log: slog::Logger) -> Result<(), String> {
         .map_err(|e| format!("Failed to build runtime: {}", e))?;
 
     // parse the CLI args into a useable config
-    let config = BootNodeConfig::try_from(matches)?;
+    let config = BootNodeConfig::new(matches, &log)?;
 
     // Run the boot node
     runtime.block_on(server::run(config, log));
@@ -2,7 +2,7 @@
 
 use super::BootNodeConfig;
 use discv5::{Discv5, Discv5ConfigBuilder, Discv5Event};
-use eth2_libp2p::EnrExt;
+use eth2_libp2p::{discovery::save_enr_to_disk, EnrExt};
 use futures::prelude::*;
 use slog::info;
 
@@ -34,6 +34,9 @@ pub async fn run(config: BootNodeConfig, log: slog::Logger) {
     // construct the discv5 server
     let mut discv5 = Discv5::new(config.local_enr, config.local_key, discv5_config).unwrap();
 
+    // persist the ENR so that its sequence number is retained across restarts
+    save_enr_to_disk(&config.network_dir, &discv5.local_enr(), &log);
+
     // If there are any bootnodes add them to the routing table
     for enr in config.boot_nodes {
         info!(log, "Adding bootnode"; "address" => format!("{:?}", enr.udp_socket()), "peer_id" => enr.peer_id().to_string(), "node_id" => enr.node_id().to_string());
@@ -81,6 +84,8 @@ pub async fn run(config: BootNodeConfig, log: slog::Logger) {
                     Discv5Event::NodeInserted { .. } => {} // Ignore
                     Discv5Event::SocketUpdated(socket_addr) => {
                         info!(log, "External socket address updated"; "socket_addr" => format!("{:?}", socket_addr));
+                        // the ENR has been updated, persist the new record
+                        save_enr_to_disk(&config.network_dir, &discv5.local_enr(), &log);
                     }
                 }
             }
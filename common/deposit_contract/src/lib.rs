@@ -1,4 +1,4 @@
-use ethabi::{Contract, Token};
+use ethabi::{Contract, ParamType, Token};
 use ssz::{Decode, DecodeError as SszDecodeError, Encode};
 use tree_hash::TreeHash;
 use types::{DepositData, Hash256, PublicKeyBytes, SignatureBytes};
@@ -13,6 +13,7 @@ pub enum DecodeError {
     UnableToGetBytes,
     MissingToken,
     InadequateBytes,
+    InvalidLittleEndianU64,
 }
 
 impl From<ethabi::Error> for DecodeError {
@@ -26,6 +27,8 @@ pub const DEPOSIT_GAS: usize = 400_000;
 pub const ABI: &[u8] = include_bytes!("../contracts/v0.12.1_validator_registration.json");
 pub const BYTECODE: &[u8] = include_bytes!("../contracts/v0.12.1_validator_registration.bytecode");
 pub const DEPOSIT_DATA_LEN: usize = 420; // lol
+/// The name of the event emitted by the deposit contract for each deposit.
+pub const DEPOSIT_EVENT_NAME: &str = "DepositEvent";
 
 pub mod testnet {
     pub const ABI: &[u8] =
@@ -83,6 +86,61 @@ pub fn decode_eth1_tx_data(
     Ok((deposit_data, root))
 }
 
+/// Returns the topic of the `DepositEvent` log emitted by the deposit contract, i.e. the hash of the
+/// event signature. Useful for filtering the logs of an eth1 node.
+pub fn deposit_event_topic() -> Result<Hash256, Error> {
+    let abi = Contract::load(ABI)?;
+    let event = abi.event(DEPOSIT_EVENT_NAME)?;
+    Ok(Hash256::from_slice(event.signature().as_bytes()))
+}
+
+/// Decodes the data of a `DepositEvent` log emitted by the deposit contract, returning the
+/// deposit and its index in the deposit contract.
+///
+/// Each field of the event is ABI-encoded `bytes`, with the amount and index encoded as
+/// little-endian `u64`s.
+pub fn decode_deposit_log_data(bytes: &[u8]) -> Result<(DepositData, u64), DecodeError> {
+    let mut tokens = ethabi::decode(&vec![ParamType::Bytes; 5], bytes)?;
+
+    macro_rules! next_bytes {
+        () => {
+            tokens
+                .pop()
+                .ok_or_else(|| DecodeError::MissingToken)?
+                .to_bytes()
+                .ok_or_else(|| DecodeError::UnableToGetBytes)?
+        };
+    };
+
+    fn le_u64(bytes: &[u8]) -> Result<u64, DecodeError> {
+        if bytes.len() == 8 {
+            let mut array = [0; 8];
+            array.copy_from_slice(bytes);
+            Ok(u64::from_le_bytes(array))
+        } else {
+            Err(DecodeError::InvalidLittleEndianU64)
+        }
+    }
+
+    let index = le_u64(&next_bytes!())?;
+    let signature =
+        SignatureBytes::from_ssz_bytes(&next_bytes!()).map_err(DecodeError::SszDecodeError)?;
+    let amount = le_u64(&next_bytes!())?;
+    let withdrawal_credentials =
+        Hash256::from_ssz_bytes(&next_bytes!()).map_err(DecodeError::SszDecodeError)?;
+    let pubkey =
+        PublicKeyBytes::from_ssz_bytes(&next_bytes!()).map_err(DecodeError::SszDecodeError)?;
+
+    let deposit_data = DepositData {
+        pubkey,
+        withdrawal_credentials,
+        amount,
+        signature,
+    };
+
+    Ok((deposit_data, index))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +186,39 @@ mod tests {
             "decode root should match original root"
         );
     }
+
+    #[test]
+    fn deposit_log_round_trip() {
+        let spec = &E::default_spec();
+
+        let keypair = generate_deterministic_keypair(42);
+        let mut original = get_deposit(keypair, spec);
+        original.amount = 32_000_000_000;
+        let index: u64 = 7;
+
+        // Encoded in the same way as the `DepositEvent` of the deposit contract.
+        let data = ethabi::encode(&[
+            Token::Bytes(original.pubkey.as_ssz_bytes()),
+            Token::Bytes(original.withdrawal_credentials.as_ssz_bytes()),
+            Token::Bytes(original.amount.to_le_bytes().to_vec()),
+            Token::Bytes(original.signature.as_ssz_bytes()),
+            Token::Bytes(index.to_le_bytes().to_vec()),
+        ]);
+
+        let (decoded, decoded_index) =
+            decode_deposit_log_data(&data).expect("should decode log data");
+
+        assert_eq!(decoded, original, "decoded should match original");
+        assert_eq!(decoded_index, index, "decoded index should match original");
+
+        assert!(
+            decode_deposit_log_data(&data[..data.len() - 32]).is_err(),
+            "should not decode truncated log data"
+        );
+    }
+
+    #[test]
+    fn deposit_event_topic_is_known() {
+        deposit_event_topic().expect("the ABI should contain the deposit event");
+    }
 }
@@ -0,0 +1,508 @@
+//! Provides TLS termination for the `hyper` HTTP servers used by the beacon node and validator
+//! client.
+//!
+//! The certificate and key are read from PEM files and may be reloaded at runtime (e.g., after a
+//! certificate renewal) without restarting the server, see `spawn_reload_on_sighup`.
+//!
+//! Also provides `bind_unix_socket`, which allows the same servers to listen on a unix domain
+//! socket instead of a TCP port, and `serve`, which starts a server on whichever of these is
+//! configured.
+
+use environment::TaskExecutor;
+use futures::future::{BoxFuture, Future, FutureExt};
+use hyper::server::accept::{self, Accept};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, Service};
+use hyper::{Body, Request, Response, Server};
+use parking_lot::RwLock;
+use rustls::internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use rustls::{Certificate, NoClientAuth, PrivateKey, ServerConfig};
+use serde_derive::{Deserialize, Serialize};
+use slog::{debug, info, warn};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Semaphore};
+
+pub use tokio_rustls::server::TlsStream;
+
+#[cfg(unix)]
+pub use tokio::net::UnixStream;
+
+/// The maximum number of connections which have completed a TLS handshake but have not yet been
+/// picked up by the HTTP server.
+const ACCEPTED_CHANNEL_SIZE: usize = 64;
+
+/// The maximum number of TLS handshakes which may be in progress at once. New connections are
+/// not accepted while this many handshakes are in progress.
+const MAX_CONCURRENT_HANDSHAKES: usize = 128;
+
+/// The time a client is given to complete the TLS handshake before its connection is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time to wait before accepting new connections after an error (e.g., the process has
+/// exhausted its file descriptors).
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+/// The PEM-encoded certificate chain and private key used to terminate TLS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to the certificate chain, leaf certificate first.
+    pub cert: PathBuf,
+    /// Path to the private key, in PKCS #8 or PKCS #1 (RSA) format.
+    pub key: PathBuf,
+}
+
+/// Performs TLS handshakes using the certificate and key in a `TlsConfig`.
+///
+/// Cloned instances share the same certificate, so a `reload` is seen by all of them.
+#[derive(Clone)]
+pub struct TlsAcceptor {
+    config: TlsConfig,
+    inner: Arc<RwLock<tokio_rustls::TlsAcceptor>>,
+}
+
+impl TlsAcceptor {
+    /// Loads the certificate and key described by `config`.
+    pub fn new(config: TlsConfig) -> Result<Self, String> {
+        let acceptor = load_acceptor(&config)?;
+
+        Ok(Self {
+            config,
+            inner: Arc::new(RwLock::new(acceptor)),
+        })
+    }
+
+    /// Returns the config used to load the certificate and key.
+    pub fn config(&self) -> &TlsConfig {
+        &self.config
+    }
+
+    /// Re-reads the certificate and key from disk. Connections established after this function
+    /// returns will use the new certificate.
+    ///
+    /// The existing certificate remains in use if the new one cannot be loaded.
+    pub fn reload(&self) -> Result<(), String> {
+        let acceptor = load_acceptor(&self.config)?;
+        *self.inner.write() = acceptor;
+        Ok(())
+    }
+
+    /// Returns an `Accept` which yields the TLS streams for the connections on `listener`.
+    ///
+    /// Handshakes are performed in their own tasks so that a slow client cannot prevent others
+    /// from connecting. At most `MAX_CONCURRENT_HANDSHAKES` are performed at once and each must
+    /// complete within `HANDSHAKE_TIMEOUT`, so clients which never complete the handshake cannot
+    /// exhaust the file descriptors or memory of the process. Connections which fail the
+    /// handshake are dropped.
+    pub fn incoming(
+        &self,
+        mut listener: TcpListener,
+        executor: TaskExecutor,
+    ) -> impl Accept<Conn = TlsStream<TcpStream>, Error = io::Error> {
+        let (tx, rx) = mpsc::channel::<io::Result<TlsStream<TcpStream>>>(ACCEPTED_CHANNEL_SIZE);
+        let inner = self.inner.clone();
+        let log = executor.log().clone();
+        let handshake_executor = executor.clone();
+        let handshakes = Arc::new(Semaphore::new(MAX_CONCURRENT_HANDSHAKES));
+
+        let accept_loop = async move {
+            loop {
+                // The permit is returned by `HandshakePermit` when the handshake task ends.
+                handshakes.acquire().await.forget();
+                let permit = HandshakePermit(handshakes.clone());
+
+                let (stream, peer_addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!(
+                            log,
+                            "Unable to accept TCP connection";
+                            "error" => format!("{:?}", e)
+                        );
+                        tokio::time::delay_for(ACCEPT_ERROR_DELAY).await;
+                        continue;
+                    }
+                };
+
+                // Clone the acceptor so the lock is not held during the handshake.
+                let acceptor = inner.read().clone();
+                let mut tx = tx.clone();
+                let log = log.clone();
+
+                handshake_executor.spawn(
+                    async move {
+                        let handshake =
+                            tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await;
+                        drop(permit);
+
+                        match handshake {
+                            Ok(Ok(tls_stream)) => {
+                                let _ = tx.send(Ok(tls_stream)).await;
+                            }
+                            Ok(Err(e)) => debug!(
+                                log,
+                                "TLS handshake failed";
+                                "peer_addr" => format!("{}", peer_addr),
+                                "error" => format!("{:?}", e)
+                            ),
+                            Err(_) => debug!(
+                                log,
+                                "TLS handshake timed out";
+                                "peer_addr" => format!("{}", peer_addr),
+                            ),
+                        }
+                    },
+                    "tls_handshake",
+                );
+            }
+        };
+
+        // The accept loop is cancelled on shutdown, which drops `tx` and ends the stream.
+        executor.spawn(accept_loop, "tls_accept");
+
+        accept::from_stream(rx)
+    }
+}
+
+/// Starts a HTTP server which handles each connection with a service built by `new_service`.
+///
+/// - If `socket_path` is `Some`, the server listens on a unix domain socket at that path (see
+///   `bind_unix_socket`), which is removed when the server stops.
+/// - Otherwise, if `tls_config` is `Some`, the server listens on `listen_addr` and terminates TLS,
+///   reloading the certificate on `SIGHUP`.
+/// - Otherwise, the server listens on `listen_addr` without TLS.
+///
+/// Returns the address the server is listening on (or `None` for a unix socket), which may differ
+/// from `listen_addr` if its port is 0, along with a future which runs the server until `shutdown`
+/// completes.
+pub fn serve<F, S>(
+    listen_addr: SocketAddr,
+    socket_path: Option<&Path>,
+    tls_config: Option<&TlsConfig>,
+    new_service: F,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+    executor: &TaskExecutor,
+) -> Result<
+    (
+        Option<SocketAddr>,
+        BoxFuture<'static, Result<(), hyper::Error>>,
+    ),
+    String,
+>
+where
+    F: Fn() -> S + Send + 'static,
+    S: Service<Request<Body>, Response = Response<Body>, Error = hyper::Error> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    match (socket_path, tls_config) {
+        #[cfg(unix)]
+        (Some(socket_path), _) => {
+            let incoming = bind_unix_socket(socket_path)?;
+
+            let make_service = make_service_fn(move |_socket: &UnixStream| {
+                let service = new_service();
+                async move { Ok::<_, hyper::Error>(service) }
+            });
+            let socket_path = socket_path.to_path_buf();
+            let server = Server::builder(incoming)
+                .serve(make_service)
+                .with_graceful_shutdown(shutdown)
+                .inspect(move |_| {
+                    let _ = std::fs::remove_file(&socket_path);
+                });
+
+            Ok((None, server.boxed()))
+        }
+        (_, Some(tls_config)) => {
+            let acceptor = TlsAcceptor::new(tls_config.clone())?;
+            spawn_reload_on_sighup(acceptor.clone(), executor)?;
+
+            let listener = std::net::TcpListener::bind(listen_addr)
+                .and_then(|listener| {
+                    listener.set_nonblocking(true)?;
+                    TcpListener::from_std(listener)
+                })
+                .map_err(|e| format!("Unable to bind to {}: {:?}", listen_addr, e))?;
+            let actual_listen_addr = listener
+                .local_addr()
+                .map_err(|e| format!("Unable to read listen address: {:?}", e))?;
+
+            let make_service = make_service_fn(move |_socket: &TlsStream<TcpStream>| {
+                let service = new_service();
+                async move { Ok::<_, hyper::Error>(service) }
+            });
+            let server = Server::builder(acceptor.incoming(listener, executor.clone()))
+                .serve(make_service)
+                .with_graceful_shutdown(shutdown);
+
+            Ok((Some(actual_listen_addr), server.boxed()))
+        }
+        (_, None) => {
+            let make_service = make_service_fn(move |_socket: &AddrStream| {
+                let service = new_service();
+                async move { Ok::<_, hyper::Error>(service) }
+            });
+            let server = Server::try_bind(&listen_addr)
+                .map_err(|e| format!("Unable to bind to {}: {:?}", listen_addr, e))?
+                .serve(make_service);
+            let actual_listen_addr = server.local_addr();
+
+            Ok((
+                Some(actual_listen_addr),
+                server.with_graceful_shutdown(shutdown).boxed(),
+            ))
+        }
+    }
+}
+
+/// Returns a handshake permit to its `Semaphore` when dropped, including when the handshake task
+/// is cancelled on shutdown.
+struct HandshakePermit(Arc<Semaphore>);
+
+impl Drop for HandshakePermit {
+    fn drop(&mut self) {
+        self.0.add_permits(1);
+    }
+}
+
+/// Spawns a task which reloads the certificate and key of `acceptor` whenever the process receives
+/// `SIGHUP`.
+///
+/// Does nothing on non-unix platforms.
+pub fn spawn_reload_on_sighup(
+    acceptor: TlsAcceptor,
+    executor: &TaskExecutor,
+) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup())
+            .map_err(|e| format!("Unable to listen for SIGHUP: {:?}", e))?;
+        let log = executor.log().clone();
+
+        executor.spawn(
+            async move {
+                while hangup.recv().await.is_some() {
+                    match acceptor.reload() {
+                        Ok(()) => info!(
+                            log,
+                            "Reloaded TLS certificate";
+                            "cert" => format!("{:?}", acceptor.config().cert),
+                        ),
+                        Err(e) => warn!(
+                            log,
+                            "Unable to reload TLS certificate";
+                            "error" => e,
+                            "msg" => "the previous certificate remains in use",
+                        ),
+                    }
+                }
+            },
+            "tls_reload",
+        );
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (acceptor, executor);
+    }
+
+    Ok(())
+}
+
+/// Binds a unix domain socket at `path` and returns an `Accept` which yields its connections.
+///
+/// A stale socket left at `path` by a previous run is replaced, however any other kind of file is
+/// left untouched and an error is returned. The socket is only accessible by the current user
+/// (i.e., `600 (-rw-------)` permissions).
+///
+/// The socket is bound inside a new directory which is only accessible by the current user, and
+/// moved to `path` once its permissions have been set, so other users can never connect to it.
+#[cfg(unix)]
+pub fn bind_unix_socket(
+    path: &Path,
+) -> Result<impl Accept<Conn = UnixStream, Error = io::Error>, String> {
+    use futures::stream;
+    use std::fs;
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use tokio::net::UnixListener;
+
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            fs::remove_file(path)
+                .map_err(|e| format!("Unable to remove stale socket {:?}: {:?}", path, e))?;
+        } else {
+            return Err(format!("{:?} already exists and is not a socket", path));
+        }
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid socket path {:?}", path))?;
+    let parent = match path.parent() {
+        Some(parent) if parent != Path::new("") => parent,
+        _ => Path::new("."),
+    };
+    let private_dir = parent.join(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    if private_dir.exists() {
+        fs::remove_dir_all(&private_dir)
+            .map_err(|e| format!("Unable to remove stale {:?}: {:?}", private_dir, e))?;
+    }
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", private_dir, e))?;
+
+    let private_path = private_dir.join(file_name);
+    let listener = UnixListener::bind(&private_path)
+        .map_err(|e| format!("Unable to bind {:?}: {:?}", path, e))
+        .and_then(|listener| {
+            fs::set_permissions(&private_path, fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Unable to set permissions on {:?}: {:?}", path, e))?;
+            fs::rename(&private_path, path)
+                .map_err(|e| format!("Unable to move socket to {:?}: {:?}", path, e))?;
+            Ok(listener)
+        });
+    let _ = fs::remove_dir_all(&private_dir);
+    let listener = listener?;
+
+    let incoming = stream::unfold(listener, |mut listener| async move {
+        let conn = listener.accept().await.map(|(stream, _addr)| stream);
+        Some((conn, listener))
+    });
+
+    Ok(accept::from_stream(incoming))
+}
+
+fn load_acceptor(config: &TlsConfig) -> Result<tokio_rustls::TlsAcceptor, String> {
+    let certs = load_certs(&config.cert)?;
+    let key = load_private_key(&config.key)?;
+
+    let mut server_config = ServerConfig::new(NoClientAuth::new());
+    server_config
+        .set_single_cert(certs, key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {:?}", e))?;
+
+    Ok(Arc::new(server_config).into())
+}
+
+fn load_certs(path: &Path) -> Result<Vec<Certificate>, String> {
+    let certs = certs(&mut open(path)?)
+        .map_err(|_| format!("Unable to parse certificates in {:?}", path))?;
+
+    if certs.is_empty() {
+        Err(format!("No certificates found in {:?}", path))
+    } else {
+        Ok(certs)
+    }
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey, String> {
+    let mut keys = pkcs8_private_keys(&mut open(path)?)
+        .map_err(|_| format!("Unable to parse PKCS #8 private key in {:?}", path))?;
+
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut open(path)?)
+            .map_err(|_| format!("Unable to parse RSA private key in {:?}", path))?;
+    }
+
+    keys.into_iter()
+        .next()
+        .ok_or_else(|| format!("No private key found in {:?}", path))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, String> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> TlsConfig {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs");
+
+        TlsConfig {
+            cert: dir.join("cert.pem"),
+            key: dir.join("key.pem"),
+        }
+    }
+
+    #[test]
+    fn load_and_reload() {
+        let acceptor = TlsAcceptor::new(test_config()).expect("should load cert and key");
+        acceptor.reload().expect("should reload cert and key");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir::TempDir::new("http_tls").unwrap();
+        let path = dir.path().join("http.sock");
+
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            bind_unix_socket(&path).expect("should bind socket");
+            // Binding again should replace the stale socket.
+            bind_unix_socket(&path).expect("should replace socket");
+        });
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The private directory used to bind the socket should have been removed.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let file_path = dir.path().join("not_a_socket");
+        std::fs::write(&file_path, b"").unwrap();
+        runtime.block_on(async {
+            assert!(bind_unix_socket(&file_path).is_err());
+        });
+    }
+
+    #[test]
+    fn handshake_permit_is_returned() {
+        let handshakes = Arc::new(Semaphore::new(1));
+
+        let mut runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async { handshakes.acquire().await.forget() });
+        assert_eq!(handshakes.available_permits(), 0);
+
+        drop(HandshakePermit(handshakes.clone()));
+        assert_eq!(handshakes.available_permits(), 1);
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let config = test_config();
+
+        assert!(TlsAcceptor::new(TlsConfig {
+            cert: config.cert.clone(),
+            key: config.cert.clone(),
+        })
+        .is_err());
+        assert!(TlsAcceptor::new(TlsConfig {
+            cert: config.key.clone(),
+            key: config.key.clone(),
+        })
+        .is_err());
+        assert!(TlsAcceptor::new(TlsConfig {
+            cert: config.cert.with_file_name("missing.pem"),
+            key: config.key,
+        })
+        .is_err());
+    }
+}
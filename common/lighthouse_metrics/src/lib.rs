@@ -57,8 +57,8 @@
 use prometheus::{HistogramOpts, HistogramTimer, Opts};
 
 pub use prometheus::{
-    Encoder, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntGauge, IntGaugeVec, Result,
-    TextEncoder,
+    Encoder, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Result, TextEncoder,
 };
 
 /// Collect all the metrics for reporting.
@@ -115,6 +115,19 @@ pub fn try_create_histogram_vec(
     Ok(histogram_vec)
 }
 
+/// Attempts to crate a `IntCounterVec`, returning `Err` if the registry does not accept the counter
+/// (potentially due to naming conflict).
+pub fn try_create_int_counter_vec(
+    name: &str,
+    help: &str,
+    label_names: &[&str],
+) -> Result<IntCounterVec> {
+    let opts = Opts::new(name, help);
+    let counter_vec = IntCounterVec::new(opts, label_names)?;
+    prometheus::register(Box::new(counter_vec.clone()))?;
+    Ok(counter_vec)
+}
+
 /// Attempts to crate a `IntGaugeVec`, returning `Err` if the registry does not accept the gauge
 /// (potentially due to naming conflict).
 pub fn try_create_int_gauge_vec(
@@ -149,6 +162,17 @@ pub fn get_int_gauge(int_gauge_vec: &Result<IntGaugeVec>, name: &[&str]) -> Opti
     }
 }
 
+pub fn get_int_counter(
+    int_counter_vec: &Result<IntCounterVec>,
+    name: &[&str],
+) -> Option<IntCounter> {
+    if let Ok(int_counter_vec) = int_counter_vec {
+        Some(int_counter_vec.get_metric_with_label_values(name).ok()?)
+    } else {
+        None
+    }
+}
+
 pub fn get_histogram(histogram_vec: &Result<HistogramVec>, name: &[&str]) -> Option<Histogram> {
     if let Ok(histogram_vec) = histogram_vec {
         Some(histogram_vec.get_metric_with_label_values(name).ok()?)
@@ -185,6 +209,18 @@ pub fn inc_counter_by(counter: &Result<IntCounter>, value: i64) {
     }
 }
 
+pub fn inc_counter_vec(int_counter_vec: &Result<IntCounterVec>, name: &[&str]) {
+    if let Some(counter) = get_int_counter(int_counter_vec, name) {
+        counter.inc()
+    }
+}
+
+pub fn inc_counter_vec_by(int_counter_vec: &Result<IntCounterVec>, name: &[&str], value: i64) {
+    if let Some(counter) = get_int_counter(int_counter_vec, name) {
+        counter.inc_by(value);
+    }
+}
+
 pub fn set_gauge(gauge: &Result<IntGauge>, value: i64) {
     if let Ok(gauge) = gauge {
         gauge.set(value);
@@ -0,0 +1,60 @@
+//! Reads allocator statistics from the GNU C library via `mallinfo(3)`.
+//!
+//! The fields of `mallinfo` are C `int`s and will wrap for values larger than 2 GiB. This is
+//! acceptable for tracking trends, but the absolute values should be treated with care.
+
+use lazy_static::lazy_static;
+use lighthouse_metrics::*;
+
+lazy_static! {
+    pub static ref MALLINFO_ARENA: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_arena",
+        "The total amount of memory allocated by means other than mmap(2)"
+    );
+    pub static ref MALLINFO_ORDBLKS: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_ordblks",
+        "The number of ordinary (i.e., non-fastbin) free blocks"
+    );
+    pub static ref MALLINFO_SMBLKS: Result<IntGauge> =
+        try_create_int_gauge("mallinfo_smblks", "The number of fastbin free blocks");
+    pub static ref MALLINFO_HBLKS: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_hblks",
+        "The number of blocks currently allocated using mmap(2)"
+    );
+    pub static ref MALLINFO_HBLKHD: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_hblkhd",
+        "The number of bytes in blocks currently allocated using mmap(2)"
+    );
+    pub static ref MALLINFO_FSMBLKS: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_fsmblks",
+        "The total number of bytes in fastbin free blocks"
+    );
+    pub static ref MALLINFO_UORDBLKS: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_uordblks",
+        "The total number of bytes used by in-use allocations"
+    );
+    pub static ref MALLINFO_FORDBLKS: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_fordblks",
+        "The total number of bytes in free blocks"
+    );
+    pub static ref MALLINFO_KEEPCOST: Result<IntGauge> = try_create_int_gauge(
+        "mallinfo_keepcost",
+        "The total amount of releasable free space at the top of the heap"
+    );
+}
+
+/// Reads `mallinfo` and updates the corresponding metrics.
+pub fn scrape_mallinfo_metrics() {
+    // Safe because `mallinfo` takes no arguments and returns a plain struct of integers.
+    let mallinfo = unsafe { libc::mallinfo() };
+
+    set_gauge(&MALLINFO_ARENA, mallinfo.arena as i64);
+    set_gauge(&MALLINFO_ORDBLKS, mallinfo.ordblks as i64);
+    set_gauge(&MALLINFO_SMBLKS, mallinfo.smblks as i64);
+    set_gauge(&MALLINFO_HBLKS, mallinfo.hblks as i64);
+    set_gauge(&MALLINFO_HBLKHD, mallinfo.hblkhd as i64);
+    set_gauge(&MALLINFO_FSMBLKS, mallinfo.fsmblks as i64);
+    set_gauge(&MALLINFO_UORDBLKS, mallinfo.uordblks as i64);
+    set_gauge(&MALLINFO_FORDBLKS, mallinfo.fordblks as i64);
+    set_gauge(&MALLINFO_KEEPCOST, mallinfo.keepcost as i64);
+}
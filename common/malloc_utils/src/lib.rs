@@ -0,0 +1,17 @@
+//! Provides statistics about the allocator used by the Lighthouse binaries, so that memory
+//! regressions can be tracked between releases.
+//!
+//! Lighthouse uses the system allocator. Statistics are currently only available for the GNU C
+//! library (`glibc`) on Linux, on all other platforms `scrape_allocator_metrics` is a no-op.
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod glibc;
+
+/// Updates the allocator metrics in the `lighthouse_metrics` default registry.
+///
+/// Reading the statistics requires walking each of the allocator's arenas, so this should only
+/// be called at the time of a scrape.
+pub fn scrape_allocator_metrics() {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    glibc::scrape_mallinfo_metrics();
+}
@@ -0,0 +1,168 @@
+//! Periodically sends a compact JSON snapshot of a Lighthouse process to a remote monitoring
+//! service, for users who do not run their own Prometheus instance.
+//!
+//! The process-specific information is gathered by a closure provided to
+//! `MonitoringHttpClient::auto_update`, which allows the same client to be used by both the beacon
+//! node and the validator client.
+
+mod types;
+
+use environment::TaskExecutor;
+use futures::{Future, StreamExt};
+use reqwest::Client;
+use rest_types::Health;
+use serde_derive::{Deserialize, Serialize};
+use slog::{debug, error, info, Logger};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::interval;
+use url::Url;
+
+pub use types::*;
+
+/// The default interval between snapshots.
+pub const DEFAULT_UPDATE_PERIOD_SECS: u64 = 60;
+
+/// The time allowed for the monitoring endpoint to respond to each snapshot.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration for the monitoring client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The URL to which each snapshot is POSTed.
+    pub endpoint: String,
+    /// The number of seconds between snapshots.
+    pub update_period_secs: u64,
+}
+
+impl Config {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            update_period_secs: DEFAULT_UPDATE_PERIOD_SECS,
+        }
+    }
+}
+
+/// Sends `MonitoringSnapshot`s to a remote endpoint.
+#[derive(Clone)]
+pub struct MonitoringHttpClient {
+    client: Client,
+    endpoint: Url,
+    update_period: Duration,
+    process: ProcessType,
+    log: Logger,
+}
+
+impl MonitoringHttpClient {
+    pub fn new(config: &Config, process: ProcessType, log: Logger) -> Result<Self, String> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|e| format!("Invalid monitoring endpoint {}: {:?}", config.endpoint, e))?;
+
+        if config.update_period_secs == 0 {
+            return Err("The monitoring update period must be greater than zero".into());
+        }
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Unable to build monitoring HTTP client: {:?}", e))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            update_period: Duration::from_secs(config.update_period_secs),
+            process,
+            log,
+        })
+    }
+
+    /// Spawns a task which calls `gather` and sends the resulting snapshot to the endpoint once
+    /// each update period.
+    ///
+    /// Failures are logged and do not stop subsequent updates.
+    pub fn auto_update<F, Fut>(self, executor: TaskExecutor, gather: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<ProcessData, String>> + Send,
+    {
+        info!(
+            self.log,
+            "Starting monitoring API";
+            "endpoint" => self.endpoint.as_str(),
+            "update_period_secs" => self.update_period.as_secs(),
+        );
+
+        let mut interval = interval(self.update_period);
+
+        let update_future = async move {
+            while interval.next().await.is_some() {
+                match gather().await {
+                    Ok(data) => {
+                        if let Err(e) = self.send(self.snapshot(data)).await {
+                            error!(
+                                self.log,
+                                "Failed to send monitoring snapshot";
+                                "error" => e,
+                            );
+                        }
+                    }
+                    Err(e) => error!(
+                        self.log,
+                        "Failed to gather monitoring snapshot";
+                        "error" => e,
+                    ),
+                }
+            }
+        };
+
+        executor.spawn(update_future, "monitoring_api");
+    }
+
+    /// Wraps the process-specific `data` with the information common to all processes.
+    fn snapshot(&self, data: ProcessData) -> MonitoringSnapshot {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        // Process statistics are not available on all platforms, in which case they are omitted.
+        let process_metrics = Health::observe().ok().map(|health| ProcessMetrics {
+            memory_resident_bytes: health.pid_mem_resident_set_size,
+            threads: health.pid_num_threads as u64,
+            open_fds: health.pid_num_fds,
+        });
+
+        MonitoringSnapshot {
+            version: SNAPSHOT_VERSION,
+            timestamp,
+            process: self.process,
+            client_version: version::version(),
+            process_metrics,
+            data,
+        }
+    }
+
+    /// POSTs `snapshot` to the endpoint as JSON.
+    async fn send(&self, snapshot: MonitoringSnapshot) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .json(&snapshot)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {:?}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Endpoint responded with {}", status));
+        }
+
+        debug!(
+            self.log,
+            "Sent monitoring snapshot";
+            "process" => format!("{:?}", snapshot.process),
+        );
+
+        Ok(())
+    }
+}
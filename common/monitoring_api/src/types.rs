@@ -0,0 +1,61 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// The version of the snapshot format, incremented whenever a field is changed or removed.
+pub const SNAPSHOT_VERSION: u64 = 1;
+
+/// The kind of process which produced a `MonitoringSnapshot`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessType {
+    BeaconNode,
+    Validator,
+}
+
+/// A compact summary of the state of a Lighthouse process, sent to the monitoring endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitoringSnapshot {
+    pub version: u64,
+    /// Milliseconds since the UNIX epoch at which the snapshot was taken.
+    pub timestamp: u64,
+    pub process: ProcessType,
+    /// The version string of the Lighthouse binary.
+    pub client_version: String,
+    /// Process statistics, if they are available on this platform.
+    pub process_metrics: Option<ProcessMetrics>,
+    #[serde(flatten)]
+    pub data: ProcessData,
+}
+
+/// Statistics about the operating system process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    pub memory_resident_bytes: u64,
+    pub threads: u64,
+    pub open_fds: u64,
+}
+
+/// The process-specific part of a `MonitoringSnapshot`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProcessData {
+    BeaconNode(BeaconNodeData),
+    Validator(ValidatorData),
+}
+
+/// Information about a beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeData {
+    pub sync_synced: bool,
+    pub sync_head_slot: u64,
+    pub sync_finalized_epoch: u64,
+    pub network_peers_connected: u64,
+}
+
+/// Information about the validators managed by a validator client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorData {
+    pub validator_total: u64,
+    pub validator_active: u64,
+    /// The sum of the balances of all validators known to the beacon node, in Gwei.
+    pub validator_balance_total_gwei: u64,
+}
@@ -1,28 +1,45 @@
 //! Provides a `RemoteBeaconNode` which interacts with a HTTP API on another Lighthouse (or
 //! compatible) instance.
 //!
-//! Presently, this is only used for testing but it _could_ become a user-facing library.
+//! Each endpoint served by the beacon node `rest_api` has a typed method here, sharing its
+//! request and response types with the server via `rest_types`. Responses are requested as JSON
+//! by default, endpoints which support SSZ may be requested as SSZ using
+//! `HttpClient::with_encoding`. If the node cannot encode a particular response as SSZ, the
+//! request is retried as JSON.
+//!
+//! The exceptions are `/metrics`, which serves Prometheus text, and `/lighthouse/syncing`,
+//! `/lighthouse/peers` and `/lighthouse/connected_peers`, whose types are defined by the
+//! networking stack (`eth2_libp2p`) and cannot be deserialized.
 
 use eth2_config::Eth2Config;
-use reqwest::{Client, ClientBuilder, Response, StatusCode};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use ssz::Encode;
+use futures::stream::{self, Stream};
+use reqwest::header::{self, HeaderValue};
+use reqwest::{Certificate, Client, ClientBuilder, RequestBuilder, Response, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use ssz::{Decode, Encode};
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::time::Duration;
 use types::{
-    Attestation, AttestationData, AttesterSlashing, BeaconBlock, BeaconState, CommitteeIndex,
-    Epoch, EthSpec, Fork, Hash256, ProposerSlashing, PublicKey, PublicKeyBytes, Signature,
-    SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
+    Attestation, AttestationData, AttesterSlashing, BeaconBlock, BeaconState, ChainSpec,
+    CommitteeIndex, Epoch, EthSpec, Fork, Hash256, HistoricalBatch, ProposerSlashing, PublicKey,
+    PublicKeyBytes, Signature, SignedAggregateAndProof, SignedBeaconBlock, Slot, SubnetId,
 };
 use url::Url;
 
 pub use operation_pool::PersistedOperationPool;
 pub use proto_array::core::ProtoArray;
 pub use rest_types::{
-    CanonicalHeadResponse, Committee, HeadBeaconBlock, Health, IndividualVotesRequest,
-    IndividualVotesResponse, SyncingResponse, ValidatorDutiesRequest, ValidatorDutyBytes,
-    ValidatorRequest, ValidatorResponse, ValidatorSubscription,
+    BlockHeaderData, BlockId, BlockResponse, BroadcastValidation, CanonicalHeadResponse, Committee,
+    CommitteeData, DutiesDependentRoots, EpochParticipation, ExecutionOptimisticFinalizedResponse,
+    FinalityCheckpointsData, ForkChoiceWeight, GenericResponse, HeadBeaconBlock, Health,
+    HistoricalBlockRootProof, IndividualVotesRequest, IndividualVotesResponse, LivenessData,
+    LivenessRequestData, LivenessResponseData, PaginatedResponse, ProposerPreparationData,
+    RootData, StateId, StateMultiproof, StateResponse, SyncingResponse, ValidatorBalanceData,
+    ValidatorData, ValidatorDutiesRequest, ValidatorDutyBytes, ValidatorId, ValidatorRequest,
+    ValidatorResponse, ValidatorStatus, ValidatorSubscription, ValidatorsRequestBody, VoteCount,
 };
+pub use store::DatabaseInfo;
 
 // Setting a long timeout for debug ensures that crypto-heavy operations can still succeed.
 #[cfg(debug_assertions)]
@@ -49,6 +66,27 @@ impl<E: EthSpec> RemoteBeaconNode<E> {
                 .map_err(|e| format!("Unable to create http client: {:?}", e))?,
         })
     }
+
+    /// Uses the given timeout and additionally trusts the PEM-encoded root certificate `ca_cert`,
+    /// allowing connections to a node which serves a self-signed certificate.
+    pub fn new_with_ca_cert(
+        http_endpoint: String,
+        timeout: Duration,
+        ca_cert: &[u8],
+    ) -> Result<Self, String> {
+        let ca_cert = Certificate::from_pem(ca_cert)
+            .map_err(|e| format!("Unable to parse CA certificate: {:?}", e))?;
+        let client = ClientBuilder::new()
+            .timeout(timeout)
+            .add_root_certificate(ca_cert)
+            .build()
+            .map_err(|e| format!("Unable to build http client: {:?}", e))?;
+
+        Ok(Self {
+            http: HttpClient::from_client(http_endpoint, client)
+                .map_err(|e| format!("Unable to create http client: {:?}", e))?,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -59,10 +97,14 @@ pub enum Error {
     ReqwestError(reqwest::Error),
     /// There was an error when encoding/decoding an object using serde.
     SerdeJsonError(serde_json::Error),
+    /// Unable to decode an SSZ-encoded response.
+    SszDecodeError(ssz::DecodeError),
     /// The server responded to the request, however it did not return a 200-type success code.
     DidNotSucceed { status: StatusCode, body: String },
     /// The request input was invalid.
     InvalidInput,
+    /// A server-sent event could not be parsed.
+    InvalidEvent(String),
 }
 
 #[derive(Clone)]
@@ -70,23 +112,59 @@ pub struct HttpClient<E> {
     client: Client,
     url: Url,
     timeout: Duration,
+    encoding: Encoding,
     _phantom: PhantomData<E>,
 }
 
+/// The encoding requested (via the `Accept` header) for responses from endpoints which support
+/// more than one encoding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Json,
+    Ssz,
+}
+
+impl Encoding {
+    fn content_type(self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Ssz => "application/ssz",
+        }
+    }
+}
+
 impl<E: EthSpec> HttpClient<E> {
     /// Creates a new instance (without connecting to the node).
     pub fn new(server_url: String, timeout: Duration) -> Result<Self, Error> {
+        let client = ClientBuilder::new()
+            .timeout(timeout)
+            .build()
+            .expect("should build from static configuration");
+        Self::from_client(server_url, client)
+    }
+
+    /// Creates a new instance which sends requests with the given `client`.
+    pub fn from_client(server_url: String, client: Client) -> Result<Self, Error> {
         Ok(Self {
-            client: ClientBuilder::new()
-                .timeout(timeout)
-                .build()
-                .expect("should build from static configuration"),
+            client,
             url: Url::parse(&server_url)?,
             timeout: Duration::from_secs(15),
+            encoding: Encoding::Json,
             _phantom: PhantomData,
         })
     }
 
+    /// Returns a client which requests responses with the given `encoding`, where the endpoint
+    /// supports it. Endpoints which only support JSON always return JSON.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn network(&self) -> Network<E> {
+        Network(self.clone())
+    }
+
     pub fn beacon(&self) -> Beacon<E> {
         Beacon(self.clone())
     }
@@ -111,6 +189,14 @@ impl<E: EthSpec> HttpClient<E> {
         Consensus(self.clone())
     }
 
+    pub fn standard(&self) -> Standard<E> {
+        Standard(self.clone())
+    }
+
+    pub fn lighthouse(&self) -> Lighthouse<E> {
+        Lighthouse(self.clone())
+    }
+
     fn url(&self, path: &str) -> Result<Url, Error> {
         self.url.join(path).map_err(|e| e.into())
     }
@@ -143,6 +229,108 @@ impl<E: EthSpec> HttpClient<E> {
         let success = error_for_status(response).await.map_err(Error::from)?;
         success.json::<T>().await.map_err(Error::from)
     }
+
+    /// Like `json_get`, but requests the response in `self.encoding` and decodes it according to
+    /// the `Content-Type` of the response.
+    pub async fn negotiated_get<T: DeserializeOwned + Decode>(
+        &self,
+        mut url: Url,
+        query_pairs: Vec<(String, String)>,
+    ) -> Result<T, Error> {
+        query_pairs.into_iter().for_each(|(key, param)| {
+            url.query_pairs_mut().append_pair(&key, &param);
+        });
+
+        let response = self
+            .negotiated_send(|| self.client.get(&url.to_string()))
+            .await?;
+        decode_negotiated::<T, T, _>(response, |item| item).await
+    }
+
+    /// Like `negotiated_get`, but for endpoints which wrap JSON responses in the `data` field of a
+    /// `GenericResponse` (SSZ responses are never wrapped).
+    pub async fn negotiated_get_data<T: DeserializeOwned + Decode + Serialize>(
+        &self,
+        url: Url,
+    ) -> Result<T, Error> {
+        let response = self
+            .negotiated_send(|| self.client.get(&url.to_string()))
+            .await?;
+        decode_negotiated::<T, GenericResponse<T>, _>(response, |response| response.data).await
+    }
+
+    /// Like `json_post`, but requests the response in `self.encoding` and decodes it according to
+    /// the `Content-Type` of the response.
+    pub async fn negotiated_post<B: Serialize, T: DeserializeOwned + Decode>(
+        &self,
+        url: Url,
+        body: B,
+    ) -> Result<T, Error> {
+        let response = self
+            .negotiated_send(|| self.client.post(&url.to_string()).json(&body))
+            .await?;
+        decode_negotiated::<T, T, _>(response, |item| item).await
+    }
+
+    /// Sends the request built by `request`, asking for a response in `self.encoding`.
+    ///
+    /// If SSZ was requested and the node responds that it cannot encode the response as SSZ, the
+    /// request is sent again asking for JSON.
+    async fn negotiated_send<F>(&self, request: F) -> Result<Response, Error>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let response = request()
+            .header(
+                header::ACCEPT,
+                HeaderValue::from_static(self.encoding.content_type()),
+            )
+            .send()
+            .await
+            .map_err(Error::from)?;
+
+        if self.encoding == Encoding::Ssz && response.status() == StatusCode::UNSUPPORTED_MEDIA_TYPE
+        {
+            let response = request()
+                .header(
+                    header::ACCEPT,
+                    HeaderValue::from_static(Encoding::Json.content_type()),
+                )
+                .send()
+                .await
+                .map_err(Error::from)?;
+            return error_for_status(response).await;
+        }
+
+        error_for_status(response).await
+    }
+}
+
+/// Decodes `response` as SSZ if its `Content-Type` is SSZ, otherwise decodes it as JSON of type `J`
+/// and converts it with `from_json`.
+async fn decode_negotiated<T, J, F>(response: Response, from_json: F) -> Result<T, Error>
+where
+    T: Decode,
+    J: DeserializeOwned,
+    F: FnOnce(J) -> T,
+{
+    let is_ssz = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map_or(false, |content_type| {
+            content_type.as_bytes() == Encoding::Ssz.content_type().as_bytes()
+        });
+
+    if is_ssz {
+        let bytes = response.bytes().await.map_err(Error::from)?;
+        T::from_ssz_bytes(&bytes).map_err(Error::SszDecodeError)
+    } else {
+        response
+            .json::<J>()
+            .await
+            .map(from_json)
+            .map_err(Error::from)
+    }
 }
 
 /// Returns an `Error` (with a description) if the `response` was not a 200-type success response.
@@ -163,6 +351,23 @@ async fn error_for_status(response: Response) -> Result<Response, Error> {
     }
 }
 
+/// Parses the block root from the `data` field of a server-sent head event.
+fn parse_sse_head_event(event: &str) -> Option<Result<Hash256, Error>> {
+    let data = event
+        .lines()
+        .find(|line| line.starts_with("data:"))?
+        .trim_start_matches("data:")
+        .trim();
+
+    Some(
+        hex::decode(data.trim_start_matches("0x"))
+            .ok()
+            .filter(|bytes| bytes.len() == 32)
+            .map(|bytes| Hash256::from_slice(&bytes))
+            .ok_or_else(|| Error::InvalidEvent(data.to_string())),
+    )
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum PublishStatus {
     /// The object was valid and has been published to the network.
@@ -285,15 +490,59 @@ impl<E: EthSpec> Validator<E> {
         };
 
         let url = self.url("duties")?;
-        let response = client.json_post::<_>(url, bulk_request).await?;
-        let success = error_for_status(response).await.map_err(Error::from)?;
-        success.json().await.map_err(Error::from)
+        client.negotiated_post(url, bulk_request).await
+    }
+
+    /// Returns the duties of every validator in the given epoch.
+    pub async fn get_all_duties(&self, epoch: Epoch) -> Result<Vec<ValidatorDutyBytes>, Error> {
+        let client = self.0.clone();
+
+        let url = self.url("duties/all")?;
+        client
+            .negotiated_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
+            .await
+    }
+
+    /// Returns the duties of every active validator in the given epoch.
+    pub async fn get_active_duties(&self, epoch: Epoch) -> Result<Vec<ValidatorDutyBytes>, Error> {
+        let client = self.0.clone();
+
+        let url = self.url("duties/active")?;
+        client
+            .negotiated_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
+            .await
+    }
+
+    /// Returns the roots of the blocks which determined the duties of the given epoch.
+    pub async fn get_duties_dependent_roots(
+        &self,
+        epoch: Epoch,
+    ) -> Result<DutiesDependentRoots, Error> {
+        let client = self.0.clone();
+
+        let url = self.url("duties/dependent_roots")?;
+        client
+            .negotiated_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
+            .await
     }
 
     /// Posts a block to the beacon node, expecting it to verify it and publish it to the network.
     pub async fn publish_block(&self, block: SignedBeaconBlock<E>) -> Result<PublishStatus, Error> {
+        self.publish_block_with_validation(block, BroadcastValidation::default())
+            .await
+    }
+
+    /// Posts a block to the beacon node, expecting it to verify it to the level given by
+    /// `broadcast_validation` before publishing it to the network.
+    pub async fn publish_block_with_validation(
+        &self,
+        block: SignedBeaconBlock<E>,
+        broadcast_validation: BroadcastValidation,
+    ) -> Result<PublishStatus, Error> {
         let client = self.0.clone();
-        let url = self.url("block")?;
+        let mut url = self.url("block")?;
+        url.query_pairs_mut()
+            .append_pair("broadcast_validation", &broadcast_validation.to_string());
         let response = client.json_post::<_>(url, block).await?;
 
         match response.status() {
@@ -317,7 +566,7 @@ impl<E: EthSpec> Validator<E> {
         let client = self.0.clone();
         let url = self.url("block")?;
         client
-            .json_get::<BeaconBlock<E>>(
+            .negotiated_get::<BeaconBlock<E>>(
                 url,
                 vec![
                     ("slot".into(), format!("{}", slot.as_u64())),
@@ -347,6 +596,27 @@ impl<E: EthSpec> Validator<E> {
                 .map(|_| PublishStatus::Unknown),
         }
     }
+
+    /// Registers a list of validators for proposer preparation.
+    pub async fn prepare_beacon_proposer(
+        &self,
+        preparation_data: Vec<ProposerPreparationData>,
+    ) -> Result<PublishStatus, Error> {
+        let client = self.0.clone();
+        let url = self.url("prepare_beacon_proposer")?;
+        let response = client.json_post::<_>(url, preparation_data).await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(PublishStatus::Valid),
+            StatusCode::ACCEPTED => Ok(PublishStatus::Invalid(
+                response.text().await.map_err(Error::from)?,
+            )),
+            _ => response
+                .error_for_status()
+                .map_err(Error::from)
+                .map(|_| PublishStatus::Unknown),
+        }
+    }
 }
 
 /// Provides the functions on the `/beacon` endpoint of the node.
@@ -365,35 +635,86 @@ impl<E: EthSpec> Beacon<E> {
     pub async fn get_genesis_time(&self) -> Result<u64, Error> {
         let client = self.0.clone();
         let url = self.url("genesis_time")?;
-        client.json_get(url, vec![]).await
+        client.negotiated_get(url, vec![]).await
     }
 
     /// Returns the genesis validators root.
     pub async fn get_genesis_validators_root(&self) -> Result<Hash256, Error> {
         let client = self.0.clone();
         let url = self.url("genesis_validators_root")?;
-        client.json_get(url, vec![]).await
+        client.negotiated_get(url, vec![]).await
     }
 
     /// Returns the fork at the head of the beacon chain.
     pub async fn get_fork(&self) -> Result<Fork, Error> {
         let client = self.0.clone();
         let url = self.url("fork")?;
-        client.json_get(url, vec![]).await
+        client.negotiated_get(url, vec![]).await
+    }
+
+    /// Subscribes to the server-sent event stream of the node, returning a stream of the roots of
+    /// new head blocks.
+    ///
+    /// The stream ends when the connection is closed or after `timeout`, whichever is sooner. An
+    /// error is yielded before the stream ends if the connection failed.
+    pub async fn stream_heads(
+        &self,
+        timeout: Duration,
+    ) -> Result<impl Stream<Item = Result<Hash256, Error>>, Error> {
+        let client = self.0.clone();
+        let url = self.url("fork/stream")?;
+        let response = client
+            .client
+            .get(&url.to_string())
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(Error::from)?;
+        let response = error_for_status(response).await?;
+
+        let initial = (Some(response), String::new(), VecDeque::new());
+        Ok(stream::unfold(
+            initial,
+            |(mut response, mut buffer, mut pending)| async move {
+                loop {
+                    if let Some(item) = pending.pop_front() {
+                        return Some((item, (response, buffer, pending)));
+                    }
+
+                    match response.as_mut()?.chunk().await {
+                        Ok(Some(chunk)) => {
+                            buffer.push_str(&String::from_utf8_lossy(&chunk));
+                            // Events are terminated by a blank line.
+                            while let Some(end) = buffer.find("\n\n") {
+                                let event = buffer.drain(..end + 2).collect::<String>();
+                                pending.extend(parse_sse_head_event(&event));
+                            }
+                        }
+                        Ok(None) => return None,
+                        Err(e) => {
+                            response = None;
+                            pending.push_back(Err(Error::from(e)));
+                        }
+                    }
+                }
+            },
+        ))
     }
 
     /// Returns info about the head of the canonical beacon chain.
     pub async fn get_head(&self) -> Result<CanonicalHeadResponse, Error> {
         let client = self.0.clone();
         let url = self.url("head")?;
-        client.json_get::<CanonicalHeadResponse>(url, vec![]).await
+        client
+            .negotiated_get::<CanonicalHeadResponse>(url, vec![])
+            .await
     }
 
     /// Returns the set of known beacon chain head blocks. One of these will be the canonical head.
     pub async fn get_heads(&self) -> Result<Vec<HeadBeaconBlock>, Error> {
         let client = self.0.clone();
         let url = self.url("heads")?;
-        client.json_get(url, vec![]).await
+        client.negotiated_get(url, vec![]).await
     }
 
     /// Returns the block and block root at the given slot.
@@ -423,7 +744,7 @@ impl<E: EthSpec> Beacon<E> {
         let client = self.0.clone();
         let url = self.url("block")?;
         client
-            .json_get::<BlockResponse<E>>(url, vec![(query_key, query_param)])
+            .negotiated_get::<BlockResponse<E>>(url, vec![(query_key, query_param)])
             .await
             .map(|response| (response.beacon_block, response.root))
     }
@@ -443,12 +764,19 @@ impl<E: EthSpec> Beacon<E> {
             .await
     }
 
+    /// Returns the genesis state.
+    pub async fn get_genesis_state(&self) -> Result<BeaconState<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url("state/genesis")?;
+        client.negotiated_get(url, vec![]).await
+    }
+
     /// Returns the root of the state at the given slot.
     pub async fn get_state_root(&self, slot: Slot) -> Result<Hash256, Error> {
         let client = self.0.clone();
         let url = self.url("state_root")?;
         client
-            .json_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
+            .negotiated_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
             .await
     }
 
@@ -457,7 +785,7 @@ impl<E: EthSpec> Beacon<E> {
         let client = self.0.clone();
         let url = self.url("block_root")?;
         client
-            .json_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
+            .negotiated_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
             .await
     }
 
@@ -470,7 +798,7 @@ impl<E: EthSpec> Beacon<E> {
         let client = self.0.clone();
         let url = self.url("state")?;
         client
-            .json_get::<StateResponse<E>>(url, vec![(query_key, query_param)])
+            .negotiated_get::<StateResponse<E>>(url, vec![(query_key, query_param)])
             .await
             .map(|response| (response.beacon_state, response.root))
     }
@@ -546,7 +874,7 @@ impl<E: EthSpec> Beacon<E> {
 
         let url = self.url("committees")?;
         client
-            .json_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
+            .negotiated_get(url, vec![("epoch".into(), format!("{}", epoch.as_u64()))])
             .await
     }
 
@@ -592,6 +920,74 @@ impl<E: EthSpec> Spec<E> {
         let url = self.url("eth2_config")?;
         client.json_get(url, vec![]).await
     }
+
+    /// Returns the `ChainSpec` of the node.
+    pub async fn get_spec(&self) -> Result<ChainSpec, Error> {
+        let client = self.0.clone();
+        let url = self.0.url("spec")?;
+        client.json_get(url, vec![]).await
+    }
+
+    pub async fn get_slots_per_epoch(&self) -> Result<u64, Error> {
+        let client = self.0.clone();
+        let url = self.url("slots_per_epoch")?;
+        client.negotiated_get(url, vec![]).await
+    }
+}
+
+/// Provides the functions on the `/network` endpoint of the node.
+#[derive(Clone)]
+pub struct Network<E>(HttpClient<E>);
+
+impl<E: EthSpec> Network<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("network/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Returns the base64-encoded ENR of the node.
+    pub async fn get_enr(&self) -> Result<String, Error> {
+        let client = self.0.clone();
+        let url = self.url("enr")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Returns the base58-encoded libp2p `PeerId` of the node.
+    pub async fn get_peer_id(&self) -> Result<String, Error> {
+        let client = self.0.clone();
+        let url = self.url("peer_id")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Returns the number of connected peers.
+    pub async fn get_peer_count(&self) -> Result<usize, Error> {
+        let client = self.0.clone();
+        let url = self.url("peer_count")?;
+        client.negotiated_get(url, vec![]).await
+    }
+
+    /// Returns the `PeerId` of each connected peer.
+    pub async fn get_peers(&self) -> Result<Vec<String>, Error> {
+        let client = self.0.clone();
+        let url = self.url("peers")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Returns the TCP port of the libp2p service.
+    pub async fn get_listen_port(&self) -> Result<u16, Error> {
+        let client = self.0.clone();
+        let url = self.url("listen_port")?;
+        client.negotiated_get(url, vec![]).await
+    }
+
+    /// Returns the libp2p multiaddrs the node is listening on.
+    pub async fn get_listen_addresses(&self) -> Result<Vec<String>, Error> {
+        let client = self.0.clone();
+        let url = self.url("listen_addresses")?;
+        client.json_get(url, vec![]).await
+    }
 }
 
 /// Provides the functions on the `/node` endpoint of the node.
@@ -680,32 +1076,311 @@ impl<E: EthSpec> Consensus<E> {
     }
 
     /// Gets a `VoteCount` for the given `epoch`.
-    pub async fn get_vote_count(&self, epoch: Epoch) -> Result<IndividualVotesResponse, Error> {
+    pub async fn get_vote_count(&self, epoch: Epoch) -> Result<VoteCount, Error> {
         let client = self.0.clone();
         let query_params = vec![("epoch".into(), format!("{}", epoch.as_u64()))];
-        let url = self.url("vote_count")?;
-        client.json_get(url, query_params).await
+        let url = self.url("global_votes")?;
+        client.negotiated_get(url, query_params).await
     }
 }
 
-#[derive(Deserialize)]
-#[serde(bound = "T: EthSpec")]
-pub struct BlockResponse<T: EthSpec> {
-    pub beacon_block: SignedBeaconBlock<T>,
-    pub root: Hash256,
+/// Provides the functions on the `/lighthouse` endpoints of the node.
+#[derive(Clone)]
+pub struct Lighthouse<E>(HttpClient<E>);
+
+impl<E: EthSpec> Lighthouse<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("lighthouse/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Gets the fraction of active stake which voted for the correct target and head during
+    /// `epoch`.
+    pub async fn get_participation(&self, epoch: Epoch) -> Result<EpochParticipation, Error> {
+        let client = self.0.clone();
+        let query_params = vec![("epoch".into(), format!("{}", epoch.as_u64()))];
+        let url = self.url("beacon/participation")?;
+        client.json_get(url, query_params).await
+    }
+
+    /// Returns whether each of `indices` was seen to attest, aggregate or propose during `epoch`.
+    pub async fn post_liveness(
+        &self,
+        epoch: Epoch,
+        indices: Vec<u64>,
+    ) -> Result<Vec<LivenessResponseData>, Error> {
+        let client = self.0.clone();
+        let url = self.url("liveness")?;
+        let response = client
+            .json_post::<_>(url, LivenessRequestData { epoch, indices })
+            .await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Gets the schema version, split point and the size of each column of the database.
+    pub async fn get_database_info(&self) -> Result<DatabaseInfo, Error> {
+        let client = self.0.clone();
+        let url = self.url("database/info")?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Gets the `HistoricalBatch` at `index` of `state.historical_roots`.
+    pub async fn get_historical_batch(&self, index: u64) -> Result<HistoricalBatch<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url("beacon/historical_batch")?;
+        client
+            .negotiated_get(url, vec![("index".into(), format!("{}", index))])
+            .await
+    }
+
+    /// Gets a Merkle proof of the block root at `slot` against `state.historical_roots`.
+    pub async fn get_historical_block_root_proof(
+        &self,
+        slot: Slot,
+    ) -> Result<HistoricalBlockRootProof, Error> {
+        let client = self.0.clone();
+        let url = self.url("beacon/historical_block_root_proof")?;
+        client
+            .negotiated_get(url, vec![("slot".into(), format!("{}", slot.as_u64()))])
+            .await
+    }
+
+    /// Gets a Merkle multiproof of the nodes at `gindices` in the tree of the state identified by
+    /// `state_id`.
+    pub async fn get_state_proof(
+        &self,
+        state_id: StateId,
+        gindices: &[u64],
+    ) -> Result<StateMultiproof, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/states/{}/proof", state_id))?;
+        let query_params = gindices
+            .iter()
+            .map(|gindex| ("gindex".into(), format!("{}", gindex)))
+            .collect();
+        client.negotiated_get(url, query_params).await
+    }
+
+    /// Gets the fork choice weight, best descendant and head viability of the block with
+    /// `block_root`.
+    pub async fn get_fork_choice_weight(
+        &self,
+        block_root: Hash256,
+    ) -> Result<ForkChoiceWeight, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("fork_choice/weights/{:?}", block_root))?;
+        client.json_get(url, vec![]).await
+    }
 }
 
-#[derive(Deserialize)]
-#[serde(bound = "T: EthSpec")]
-pub struct StateResponse<T: EthSpec> {
-    pub beacon_state: BeaconState<T>,
-    pub root: Hash256,
+/// Provides the functions on the standard `/eth/v1` and `/eth/v2` endpoints of the node.
+#[derive(Clone)]
+pub struct Standard<E>(HttpClient<E>);
+
+impl<E: EthSpec> Standard<E> {
+    fn url(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("eth/v1/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    fn url_v2(&self, path: &str) -> Result<Url, Error> {
+        self.0
+            .url("eth/v2/")
+            .and_then(move |url| url.join(path).map_err(Error::from))
+            .map_err(Into::into)
+    }
+
+    /// Returns the validators in the state identified by `state_id`.
+    ///
+    /// If `ids` or `statuses` are non-empty, only the matching validators are returned.
+    pub async fn get_state_validators(
+        &self,
+        state_id: StateId,
+        ids: &[ValidatorId],
+        statuses: &[ValidatorStatus],
+    ) -> Result<Vec<ValidatorData>, Error> {
+        let client = self.0.clone();
+        let mut query_params = vec![];
+        if !ids.is_empty() {
+            query_params.push(("id".into(), join_display(ids)));
+        }
+        if !statuses.is_empty() {
+            query_params.push(("status".into(), join_display(statuses)));
+        }
+        let url = self.url(&format!("beacon/states/{}/validators", state_id))?;
+        client
+            .json_get::<GenericResponse<_>>(url, query_params)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns a page of the validators in the state identified by `state_id` which match
+    /// `request`.
+    ///
+    /// Unlike `get_state_validators`, there is no limit on the number of `request.ids`.
+    pub async fn post_state_validators(
+        &self,
+        state_id: StateId,
+        request: &ValidatorsRequestBody,
+    ) -> Result<PaginatedResponse<ValidatorData>, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/states/{}/validators", state_id))?;
+        let response = client.json_post::<_>(url, request).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success.json().await.map_err(Error::from)
+    }
+
+    /// Returns whether each of the validators at `indices` was seen to attest, aggregate or
+    /// propose during `epoch`.
+    pub async fn post_validator_liveness(
+        &self,
+        epoch: Epoch,
+        indices: &[u64],
+    ) -> Result<Vec<LivenessData>, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("validator/liveness/{}", epoch))?;
+        let response = client.json_post::<_>(url, indices).await?;
+        let success = error_for_status(response).await.map_err(Error::from)?;
+        success
+            .json::<GenericResponse<_>>()
+            .await
+            .map(|response| response.data)
+            .map_err(Error::from)
+    }
+
+    /// Returns the balances of the validators in the state identified by `state_id`.
+    ///
+    /// If `ids` is non-empty, only the matching validators are returned.
+    pub async fn get_state_validator_balances(
+        &self,
+        state_id: StateId,
+        ids: &[ValidatorId],
+    ) -> Result<Vec<ValidatorBalanceData>, Error> {
+        let client = self.0.clone();
+        let mut query_params = vec![];
+        if !ids.is_empty() {
+            query_params.push(("id".into(), join_display(ids)));
+        }
+        let url = self.url(&format!("beacon/states/{}/validator_balances", state_id))?;
+        client
+            .json_get::<GenericResponse<_>>(url, query_params)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns the committees of the state identified by `state_id`.
+    ///
+    /// If `epoch` is `None`, the committees for the current epoch of the state are returned.
+    pub async fn get_state_committees(
+        &self,
+        state_id: StateId,
+        epoch: Option<Epoch>,
+    ) -> Result<Vec<CommitteeData>, Error> {
+        let client = self.0.clone();
+        let query_params = if let Some(epoch) = epoch {
+            vec![("epoch".into(), format!("{}", epoch.as_u64()))]
+        } else {
+            vec![]
+        };
+        let url = self.url(&format!("beacon/states/{}/committees", state_id))?;
+        client
+            .json_get::<GenericResponse<_>>(url, query_params)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns the finality checkpoints of the state identified by `state_id`.
+    pub async fn get_state_finality_checkpoints(
+        &self,
+        state_id: StateId,
+    ) -> Result<FinalityCheckpointsData, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/states/{}/finality_checkpoints", state_id))?;
+        client
+            .json_get::<GenericResponse<_>>(url, vec![])
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns block headers.
+    ///
+    /// If both `slot` and `parent_root` are `None`, only the head block header is returned.
+    pub async fn get_headers(
+        &self,
+        slot: Option<Slot>,
+        parent_root: Option<Hash256>,
+    ) -> Result<Vec<BlockHeaderData>, Error> {
+        let client = self.0.clone();
+        let mut query_params = vec![];
+        if let Some(slot) = slot {
+            query_params.push(("slot".into(), format!("{}", slot.as_u64())));
+        }
+        if let Some(parent_root) = parent_root {
+            query_params.push(("parent_root".into(), root_as_string(parent_root)));
+        }
+        let url = self.url("beacon/headers")?;
+        client
+            .json_get::<GenericResponse<_>>(url, query_params)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns the header of the block identified by `block_id`.
+    pub async fn get_header(&self, block_id: BlockId) -> Result<BlockHeaderData, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/headers/{}", block_id))?;
+        client
+            .json_get::<GenericResponse<_>>(url, vec![])
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Returns the block identified by `block_id`, along with whether it is finalized.
+    pub async fn get_block(
+        &self,
+        block_id: BlockId,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<SignedBeaconBlock<E>>, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/blocks/{}", block_id))?;
+        client.json_get(url, vec![]).await
+    }
+
+    /// Returns the root of the block identified by `block_id`.
+    pub async fn get_block_root(&self, block_id: BlockId) -> Result<Hash256, Error> {
+        let client = self.0.clone();
+        let url = self.url(&format!("beacon/blocks/{}/root", block_id))?;
+        client
+            .json_get::<GenericResponse<RootData>>(url, vec![])
+            .await
+            .map(|response| response.data.root)
+    }
+
+    /// Returns the full state identified by `state_id`, which the node may need to reconstruct by
+    /// replaying blocks.
+    pub async fn get_debug_state(&self, state_id: StateId) -> Result<BeaconState<E>, Error> {
+        let client = self.0.clone();
+        let url = self.url_v2(&format!("debug/beacon/states/{}", state_id))?;
+        client.negotiated_get_data(url).await
+    }
 }
 
 fn root_as_string(root: Hash256) -> String {
     format!("0x{:?}", root)
 }
 
+fn join_display<T: std::fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 fn as_ssz_hex_string<T: Encode>(item: &T) -> String {
     format!("0x{}", hex::encode(item.as_ssz_bytes()))
 }
@@ -63,3 +63,32 @@ pub struct StateResponse<T: EthSpec> {
     pub root: Hash256,
     pub beacon_state: BeaconState<T>,
 }
+
+/// A Merkle proof that `block_root` was the root of the latest block at `slot`, against the entry
+/// of `state.historical_roots` which covers `slot`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct HistoricalBlockRootProof {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    /// The index of the entry in `state.historical_roots` which covers `slot`.
+    pub historical_roots_index: u64,
+    /// The root of the `HistoricalBatch` which covers `slot`.
+    pub historical_root: Hash256,
+    /// The index of `block_root` amongst the leaves of the `HistoricalBatch` tree.
+    pub leaf_index: u64,
+    /// The Merkle branch in bottom-up order. Its length is the depth of the tree.
+    pub proof: Vec<Hash256>,
+}
+
+/// The nodes at some generalized indices of the Merkle tree of a `BeaconState`, and a Merkle
+/// multiproof of their inclusion in the `state_root`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct StateMultiproof {
+    pub state_root: Hash256,
+    /// The generalized indices of the proven nodes.
+    pub gindices: Vec<u64>,
+    /// The proven nodes, in the same order as `gindices`.
+    pub leaves: Vec<Hash256>,
+    /// The nodes at the helper indices of `gindices`, in descending order of generalized index.
+    pub proof: Vec<Hash256>,
+}
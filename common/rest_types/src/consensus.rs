@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use state_processing::per_epoch_processing::ValidatorStatus;
-use types::{Epoch, PublicKeyBytes};
+use state_processing::per_epoch_processing::{TotalBalances, ValidatorStatus};
+use types::{Epoch, Hash256, PublicKeyBytes, Slot};
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
 pub struct IndividualVotesRequest {
@@ -64,3 +64,98 @@ pub struct IndividualVotesResponse {
     /// Voting statistics for the validator, if they voted in the given epoch.
     pub vote: Option<IndividualVote>,
 }
+
+/// The results of validators voting during an epoch.
+///
+/// Provides information about the current and previous epochs.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct VoteCount {
+    /// The total effective balance of all active validators during the _current_ epoch.
+    pub current_epoch_active_gwei: u64,
+    /// The total effective balance of all active validators during the _previous_ epoch.
+    pub previous_epoch_active_gwei: u64,
+    /// The total effective balance of all validators who attested during the _current_ epoch.
+    pub current_epoch_attesting_gwei: u64,
+    /// The total effective balance of all validators who attested during the _current_ epoch and
+    /// agreed with the state about the beacon block at the first slot of the _current_ epoch.
+    pub current_epoch_target_attesting_gwei: u64,
+    /// The total effective balance of all validators who attested during the _previous_ epoch.
+    pub previous_epoch_attesting_gwei: u64,
+    /// The total effective balance of all validators who attested during the _previous_ epoch and
+    /// agreed with the state about the beacon block at the first slot of the _previous_ epoch.
+    pub previous_epoch_target_attesting_gwei: u64,
+    /// The total effective balance of all validators who attested during the _previous_ epoch and
+    /// agreed with the state about the beacon block at the time of attestation.
+    pub previous_epoch_head_attesting_gwei: u64,
+}
+
+impl Into<VoteCount> for TotalBalances {
+    fn into(self) -> VoteCount {
+        VoteCount {
+            current_epoch_active_gwei: self.current_epoch(),
+            previous_epoch_active_gwei: self.previous_epoch(),
+            current_epoch_attesting_gwei: self.current_epoch_attesters(),
+            current_epoch_target_attesting_gwei: self.current_epoch_target_attesters(),
+            previous_epoch_attesting_gwei: self.previous_epoch_attesters(),
+            previous_epoch_target_attesting_gwei: self.previous_epoch_target_attesters(),
+            previous_epoch_head_attesting_gwei: self.previous_epoch_head_attesters(),
+        }
+    }
+}
+
+/// The fraction of active stake which voted correctly during some `epoch`.
+///
+/// Each fraction is in the range `0.0..=1.0`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct EpochParticipation {
+    /// The epoch to which these figures apply.
+    pub epoch: Epoch,
+    /// The total effective balance of all active validators during `epoch`.
+    pub active_gwei: u64,
+    /// The fraction of `active_gwei` which had an attestation for `epoch` included in a block.
+    pub attesting_fraction: f64,
+    /// The fraction of `active_gwei` which attested to the correct target checkpoint.
+    pub target_attesting_fraction: f64,
+    /// The fraction of `active_gwei` which attested to the correct head block.
+    pub head_attesting_fraction: f64,
+}
+
+impl EpochParticipation {
+    /// Builds the participation for `epoch` from the _previous_ epoch figures of `balances`.
+    ///
+    /// The `balances` should have been computed from a state whose previous epoch is `epoch`.
+    pub fn from_previous_epoch(epoch: Epoch, balances: &TotalBalances) -> Self {
+        let active_gwei = balances.previous_epoch();
+        let fraction = |gwei: u64| {
+            if active_gwei > 0 {
+                gwei as f64 / active_gwei as f64
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            epoch,
+            active_gwei,
+            attesting_fraction: fraction(balances.previous_epoch_attesters()),
+            target_attesting_fraction: fraction(balances.previous_epoch_target_attesters()),
+            head_attesting_fraction: fraction(balances.previous_epoch_head_attesters()),
+        }
+    }
+}
+
+/// The standing of a block in fork choice.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct ForkChoiceWeight {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    /// The total effective balance of the validators whose latest vote is for this block or one
+    /// of its descendants.
+    pub weight: u64,
+    /// The descendant of this block which would be chosen as the head if this block were the
+    /// justified block, or `None` if it has no such descendant.
+    pub best_descendant: Option<Hash256>,
+    /// `true` if the justified and finalized checkpoints of this block agree with those of fork
+    /// choice, allowing it to become the head.
+    pub viable_for_head: bool,
+}
@@ -0,0 +1,98 @@
+//! A collection of types for the keymanager API served by the validator client
+//! (`/eth/v1/keystores` and `/lighthouse/keystores`).
+//!
+//! Keystores and slashing protection are passed as JSON-encoded strings, as in the standard
+//! keymanager API. The slashing protection string is a JSON list of
+//! `slashing_protection::ValidatorRecords`.
+
+use bls::PublicKeyBytes;
+use serde::{Deserialize, Serialize};
+
+/// The body of `POST /eth/v1/keystores`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportKeystoresRequest {
+    /// JSON-encoded EIP-2335 keystores.
+    pub keystores: Vec<String>,
+    /// The password for each of `keystores`, in the same order.
+    pub passwords: Vec<String>,
+    /// The slashing protection history to import alongside the keystores, if any.
+    #[serde(default)]
+    pub slashing_protection: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKeystoreStatus {
+    /// The keystore was imported and the validator is now active.
+    Imported,
+    /// The validator was already active, the keystore was ignored.
+    Duplicate,
+    /// The keystore could not be imported, see `message`.
+    Error,
+}
+
+/// The result of importing a single keystore, returned in the same order as the request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportKeystoreStatusData {
+    pub status: ImportKeystoreStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The body of `DELETE /eth/v1/keystores` and `DELETE /lighthouse/keystores`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeleteKeystoresRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteKeystoreStatus {
+    /// The validator was active and has been removed.
+    Deleted,
+    /// The validator was not active, but has slashing protection history.
+    NotActive,
+    /// The validator is unknown to the validator client.
+    NotFound,
+    /// The validator could not be removed, see `message`.
+    Error,
+}
+
+/// The result of deleting a single keystore, returned in the same order as the request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeleteKeystoreStatusData {
+    pub status: DeleteKeystoreStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Returned by `DELETE /eth/v1/keystores`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeleteKeystoresResponse {
+    pub data: Vec<DeleteKeystoreStatusData>,
+    /// The slashing protection history of each of the requested validators known to the
+    /// validator client.
+    pub slashing_protection: String,
+}
+
+/// The result of exporting a single keystore, returned in the same order as the request.
+///
+/// The keystore and its password are only present if the validator was `Deleted`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportKeystoreData {
+    #[serde(flatten)]
+    pub status: DeleteKeystoreStatusData,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validating_keystore: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validating_keystore_password: Option<String>,
+}
+
+/// Returned by `DELETE /lighthouse/keystores`, which deletes validators in the same way as
+/// `DELETE /eth/v1/keystores` but also returns their keystores and passwords, so that they can be
+/// imported into another validator client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportKeystoresResponse {
+    pub data: Vec<ExportKeystoreData>,
+    pub slashing_protection: String,
+}
@@ -4,18 +4,37 @@
 
 mod beacon;
 mod consensus;
+mod keymanager;
 mod node;
+mod standard;
 mod validator;
 
 pub use beacon::{
-    BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, StateResponse,
-    ValidatorRequest, ValidatorResponse,
+    BlockResponse, CanonicalHeadResponse, Committee, HeadBeaconBlock, HistoricalBlockRootProof,
+    StateMultiproof, StateResponse, ValidatorRequest, ValidatorResponse,
 };
 
 pub use validator::{
-    ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
+    BroadcastValidation, DutiesDependentRoots, LivenessRequestData, LivenessResponseData,
+    ProposerPreparationData, ValidatorDutiesRequest, ValidatorDuty, ValidatorDutyBytes,
+    ValidatorSubscription,
 };
 
-pub use consensus::{IndividualVote, IndividualVotesRequest, IndividualVotesResponse};
+pub use consensus::{
+    EpochParticipation, ForkChoiceWeight, IndividualVote, IndividualVotesRequest,
+    IndividualVotesResponse, VoteCount,
+};
+
+pub use keymanager::{
+    DeleteKeystoreStatus, DeleteKeystoreStatusData, DeleteKeystoresRequest,
+    DeleteKeystoresResponse, ExportKeystoreData, ExportKeystoresResponse, ImportKeystoreStatus,
+    ImportKeystoreStatusData, ImportKeystoresRequest,
+};
 
 pub use node::{Health, SyncingResponse, SyncingStatus};
+
+pub use standard::{
+    BlockHeaderData, BlockId, CommitteeData, ExecutionOptimisticFinalizedResponse,
+    FinalityCheckpointsData, GenericResponse, LivenessData, PaginatedResponse, RootData, StateId,
+    ValidatorBalanceData, ValidatorData, ValidatorId, ValidatorStatus, ValidatorsRequestBody,
+};
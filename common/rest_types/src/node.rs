@@ -41,6 +41,8 @@ pub struct Health {
     pub pid: u32,
     /// The number of threads used by this pid.
     pub pid_num_threads: i32,
+    /// The number of file descriptors held open by this pid.
+    pub pid_num_fds: u64,
     /// The total resident memory used by this pid.
     pub pid_mem_resident_set_size: u64,
     /// The total virtual memory used by this pid.
@@ -80,6 +82,10 @@ impl Health {
 
         let stat = pid::stat_self().map_err(|e| format!("Unable to get stat: {:?}", e))?;
 
+        let num_fds = std::fs::read_dir("/proc/self/fd")
+            .map_err(|e| format!("Unable to read open file descriptors: {:?}", e))?
+            .count();
+
         let vm = psutil::memory::virtual_memory()
             .map_err(|e| format!("Unable to get virtual memory: {:?}", e))?;
         let loadavg =
@@ -88,6 +94,7 @@ impl Health {
         Ok(Self {
             pid: process.pid().into(),
             pid_num_threads: stat.num_threads,
+            pid_num_fds: num_fds as u64,
             pid_mem_resident_set_size: process_mem.rss().into(),
             pid_mem_virtual_memory_size: process_mem.vms().into(),
             sys_virt_mem_total: vm.total().into(),
@@ -0,0 +1,495 @@
+//! A collection of types for the standard Eth2 beacon node API (`/eth/v1/...`).
+
+use bls::PublicKeyBytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use types::{Checkpoint, CommitteeIndex, Epoch, Hash256, SignedBeaconBlockHeader, Slot, Validator};
+
+/// The standard API wraps all responses in a `data` field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenericResponse<T: Serialize> {
+    pub data: T,
+}
+
+impl<T: Serialize> From<T> for GenericResponse<T> {
+    fn from(data: T) -> Self {
+        Self { data }
+    }
+}
+
+/// Like `GenericResponse`, but also indicates whether the returned object is finalized.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionOptimisticFinalizedResponse<T: Serialize> {
+    /// Always `false`, since blocks are never imported optimistically.
+    pub execution_optimistic: bool,
+    /// `true` if the object is in the canonical chain at or prior to the finalized checkpoint.
+    pub finalized: bool,
+    pub data: T,
+}
+
+impl<T: Serialize> ExecutionOptimisticFinalizedResponse<T> {
+    pub fn new(data: T, finalized: bool) -> Self {
+        Self {
+            execution_optimistic: false,
+            finalized,
+            data,
+        }
+    }
+}
+
+/// Identifies a `BeaconState` in a request path.
+///
+/// E.g., `head`, `genesis`, `finalized`, `justified`, `1234` or `0x...`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StateId {
+    Head,
+    Genesis,
+    Finalized,
+    Justified,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+impl FromStr for StateId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(StateId::Head),
+            "genesis" => Ok(StateId::Genesis),
+            "finalized" => Ok(StateId::Finalized),
+            "justified" => Ok(StateId::Justified),
+            other if other.starts_with("0x") => Hash256::from_str(&other[2..])
+                .map(StateId::Root)
+                .map_err(|e| format!("{} cannot be parsed as a root: {:?}", other, e)),
+            other => other
+                .parse::<u64>()
+                .map(Slot::new)
+                .map(StateId::Slot)
+                .map_err(|_| format!("{} cannot be parsed as a state id", other)),
+        }
+    }
+}
+
+impl fmt::Display for StateId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateId::Head => write!(f, "head"),
+            StateId::Genesis => write!(f, "genesis"),
+            StateId::Finalized => write!(f, "finalized"),
+            StateId::Justified => write!(f, "justified"),
+            StateId::Slot(slot) => write!(f, "{}", slot),
+            StateId::Root(root) => write!(f, "{:?}", root),
+        }
+    }
+}
+
+/// Identifies a `SignedBeaconBlock` in a request path.
+///
+/// E.g., `head`, `genesis`, `finalized`, `justified`, `1234` or `0x...`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockId {
+    Head,
+    Genesis,
+    Finalized,
+    Justified,
+    Slot(Slot),
+    Root(Hash256),
+}
+
+impl FromStr for BlockId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(BlockId::Head),
+            "genesis" => Ok(BlockId::Genesis),
+            "finalized" => Ok(BlockId::Finalized),
+            "justified" => Ok(BlockId::Justified),
+            other if other.starts_with("0x") => Hash256::from_str(&other[2..])
+                .map(BlockId::Root)
+                .map_err(|e| format!("{} cannot be parsed as a root: {:?}", other, e)),
+            other => other
+                .parse::<u64>()
+                .map(Slot::new)
+                .map(BlockId::Slot)
+                .map_err(|_| format!("{} cannot be parsed as a block id", other)),
+        }
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockId::Head => write!(f, "head"),
+            BlockId::Genesis => write!(f, "genesis"),
+            BlockId::Finalized => write!(f, "finalized"),
+            BlockId::Justified => write!(f, "justified"),
+            BlockId::Slot(slot) => write!(f, "{}", slot),
+            BlockId::Root(root) => write!(f, "{:?}", root),
+        }
+    }
+}
+
+/// Identifies a validator by either its public key or its index in the registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidatorId {
+    PublicKey(PublicKeyBytes),
+    Index(u64),
+}
+
+impl FromStr for ValidatorId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            let bytes = hex::decode(&s[2..])
+                .map_err(|e| format!("{} cannot be parsed as a public key: {:?}", s, e))?;
+            PublicKeyBytes::from_bytes(&bytes)
+                .map(ValidatorId::PublicKey)
+                .map_err(|e| format!("{} cannot be parsed as a public key: {:?}", s, e))
+        } else {
+            s.parse::<u64>()
+                .map(ValidatorId::Index)
+                .map_err(|_| format!("{} cannot be parsed as a validator index", s))
+        }
+    }
+}
+
+impl fmt::Display for ValidatorId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidatorId::PublicKey(pubkey) => write!(f, "0x{}", hex::encode(pubkey.as_bytes())),
+            ValidatorId::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+impl Serialize for ValidatorId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidatorId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The lifecycle status of a validator, as defined by the standard API.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    PendingInitialized,
+    PendingQueued,
+    ActiveOngoing,
+    ActiveExiting,
+    ActiveSlashed,
+    ExitedUnslashed,
+    ExitedSlashed,
+    WithdrawalPossible,
+    WithdrawalDone,
+    // High-level statuses, only used as filters.
+    Active,
+    Pending,
+    Exited,
+    Withdrawal,
+}
+
+impl ValidatorStatus {
+    /// Determine the status of `validator` at `epoch`.
+    pub fn from_validator(
+        validator: &Validator,
+        balance: u64,
+        epoch: Epoch,
+        far_future_epoch: Epoch,
+    ) -> Self {
+        if validator.is_withdrawable_at(epoch) {
+            if balance == 0 {
+                ValidatorStatus::WithdrawalDone
+            } else {
+                ValidatorStatus::WithdrawalPossible
+            }
+        } else if validator.is_exited_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ExitedSlashed
+            } else {
+                ValidatorStatus::ExitedUnslashed
+            }
+        } else if validator.is_active_at(epoch) {
+            if validator.slashed {
+                ValidatorStatus::ActiveSlashed
+            } else if validator.exit_epoch < far_future_epoch {
+                ValidatorStatus::ActiveExiting
+            } else {
+                ValidatorStatus::ActiveOngoing
+            }
+        } else if validator.activation_eligibility_epoch == far_future_epoch {
+            ValidatorStatus::PendingInitialized
+        } else {
+            ValidatorStatus::PendingQueued
+        }
+    }
+
+    /// Returns the high-level status that contains `self`.
+    pub fn superstatus(self) -> Self {
+        match self {
+            ValidatorStatus::PendingInitialized | ValidatorStatus::PendingQueued => {
+                ValidatorStatus::Pending
+            }
+            ValidatorStatus::ActiveOngoing
+            | ValidatorStatus::ActiveExiting
+            | ValidatorStatus::ActiveSlashed => ValidatorStatus::Active,
+            ValidatorStatus::ExitedUnslashed | ValidatorStatus::ExitedSlashed => {
+                ValidatorStatus::Exited
+            }
+            ValidatorStatus::WithdrawalPossible | ValidatorStatus::WithdrawalDone => {
+                ValidatorStatus::Withdrawal
+            }
+            high_level => high_level,
+        }
+    }
+
+    /// Returns `true` if `self` is `filter`, or if `filter` is the high-level status of `self`.
+    pub fn matches(self, filter: Self) -> bool {
+        self == filter || self.superstatus() == filter
+    }
+}
+
+impl FromStr for ValidatorStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending_initialized" => Ok(ValidatorStatus::PendingInitialized),
+            "pending_queued" => Ok(ValidatorStatus::PendingQueued),
+            "active_ongoing" => Ok(ValidatorStatus::ActiveOngoing),
+            "active_exiting" => Ok(ValidatorStatus::ActiveExiting),
+            "active_slashed" => Ok(ValidatorStatus::ActiveSlashed),
+            "exited_unslashed" => Ok(ValidatorStatus::ExitedUnslashed),
+            "exited_slashed" => Ok(ValidatorStatus::ExitedSlashed),
+            "withdrawal_possible" => Ok(ValidatorStatus::WithdrawalPossible),
+            "withdrawal_done" => Ok(ValidatorStatus::WithdrawalDone),
+            "active" => Ok(ValidatorStatus::Active),
+            "pending" => Ok(ValidatorStatus::Pending),
+            "exited" => Ok(ValidatorStatus::Exited),
+            "withdrawal" => Ok(ValidatorStatus::Withdrawal),
+            other => Err(format!("{} is not a valid validator status", other)),
+        }
+    }
+}
+
+impl fmt::Display for ValidatorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            ValidatorStatus::PendingInitialized => "pending_initialized",
+            ValidatorStatus::PendingQueued => "pending_queued",
+            ValidatorStatus::ActiveOngoing => "active_ongoing",
+            ValidatorStatus::ActiveExiting => "active_exiting",
+            ValidatorStatus::ActiveSlashed => "active_slashed",
+            ValidatorStatus::ExitedUnslashed => "exited_unslashed",
+            ValidatorStatus::ExitedSlashed => "exited_slashed",
+            ValidatorStatus::WithdrawalPossible => "withdrawal_possible",
+            ValidatorStatus::WithdrawalDone => "withdrawal_done",
+            ValidatorStatus::Active => "active",
+            ValidatorStatus::Pending => "pending",
+            ValidatorStatus::Exited => "exited",
+            ValidatorStatus::Withdrawal => "withdrawal",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorData {
+    pub index: u64,
+    pub balance: u64,
+    pub status: ValidatorStatus,
+    pub validator: Validator,
+}
+
+/// The body of a `POST /eth/v1/beacon/states/{state_id}/validators` request.
+///
+/// Empty `ids` or `statuses` match all validators. At most `limit` matching validators are
+/// returned, starting from the `offset`-th match.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorsRequestBody {
+    #[serde(default)]
+    pub ids: Vec<ValidatorId>,
+    #[serde(default)]
+    pub statuses: Vec<ValidatorStatus>,
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// A single page of a larger list of results.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaginatedResponse<T> {
+    pub data: Vec<T>,
+    /// The total number of results across all pages.
+    pub total: u64,
+    /// The `offset` of the next page, if any.
+    pub next_offset: Option<u64>,
+}
+
+impl<T> PaginatedResponse<T> {
+    /// Returns the page of `items` starting at `offset` with at most `limit` items.
+    ///
+    /// Returns an error if `limit == Some(0)`, since no page could ever advance `offset`.
+    pub fn paginate(items: Vec<T>, offset: u64, limit: Option<u64>) -> Result<Self, String> {
+        if limit == Some(0) {
+            return Err("limit must be greater than zero".to_string());
+        }
+
+        let total = items.len() as u64;
+        let data = items
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit.unwrap_or(total) as usize)
+            .collect::<Vec<_>>();
+        let end = offset.saturating_add(data.len() as u64);
+        let next_offset = if end < total { Some(end) } else { None };
+
+        Ok(Self {
+            data,
+            total,
+            next_offset,
+        })
+    }
+}
+
+/// An item in the list returned by `POST /eth/v1/validator/liveness/{epoch}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LivenessData {
+    pub index: u64,
+    pub is_live: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorBalanceData {
+    pub index: u64,
+    pub balance: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommitteeData {
+    pub index: CommitteeIndex,
+    pub slot: Slot,
+    pub validators: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FinalityCheckpointsData {
+    pub previous_justified: Checkpoint,
+    pub current_justified: Checkpoint,
+    pub finalized: Checkpoint,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RootData {
+    pub root: Hash256,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockHeaderData {
+    pub root: Hash256,
+    /// `true` if the block is in the canonical chain, according to fork choice.
+    pub canonical: bool,
+    pub header: SignedBeaconBlockHeader,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_id_round_trip() {
+        for s in &["head", "genesis", "finalized", "justified", "42"] {
+            assert_eq!(s.parse::<StateId>().unwrap().to_string(), *s);
+        }
+        let root = Hash256::from_low_u64_be(42);
+        assert_eq!(
+            format!("{:?}", root).parse::<StateId>(),
+            Ok(StateId::Root(root))
+        );
+        assert!("cats".parse::<StateId>().is_err());
+        assert!("0x00".parse::<StateId>().is_err());
+    }
+
+    #[test]
+    fn block_id_round_trip() {
+        for s in &["head", "genesis", "finalized", "justified", "42"] {
+            assert_eq!(s.parse::<BlockId>().unwrap().to_string(), *s);
+        }
+        let root = Hash256::from_low_u64_be(42);
+        assert_eq!(
+            format!("{:?}", root).parse::<BlockId>(),
+            Ok(BlockId::Root(root))
+        );
+        assert!("-1".parse::<BlockId>().is_err());
+    }
+
+    #[test]
+    fn paginate() {
+        let page = PaginatedResponse::paginate((0..10).collect(), 2, Some(5)).unwrap();
+        assert_eq!(page.data, vec![2, 3, 4, 5, 6]);
+        assert_eq!(page.total, 10);
+        assert_eq!(page.next_offset, Some(7));
+
+        let page = PaginatedResponse::paginate((0..10).collect(), 7, Some(5)).unwrap();
+        assert_eq!(page.data, vec![7, 8, 9]);
+        assert_eq!(page.next_offset, None);
+
+        let page = PaginatedResponse::paginate((0..10).collect::<Vec<u64>>(), 12, None).unwrap();
+        assert!(page.data.is_empty());
+        assert_eq!(page.next_offset, None);
+
+        assert!(PaginatedResponse::paginate((0..10).collect::<Vec<u64>>(), 0, Some(0)).is_err());
+        let page = PaginatedResponse::paginate((0..10).collect(), 9, Some(1)).unwrap();
+        assert_eq!(page.data, vec![9]);
+        assert_eq!(page.next_offset, None);
+    }
+
+    #[test]
+    fn validator_status() {
+        let far_future_epoch = Epoch::new(u64::max_value());
+        let mut validator = Validator {
+            pubkey: PublicKeyBytes::empty(),
+            withdrawal_credentials: Hash256::zero(),
+            effective_balance: 32,
+            slashed: false,
+            activation_eligibility_epoch: far_future_epoch,
+            activation_epoch: far_future_epoch,
+            exit_epoch: far_future_epoch,
+            withdrawable_epoch: far_future_epoch,
+        };
+        let status = |v: &Validator, epoch: u64| {
+            ValidatorStatus::from_validator(v, 32, Epoch::new(epoch), far_future_epoch)
+        };
+
+        assert_eq!(status(&validator, 0), ValidatorStatus::PendingInitialized);
+        validator.activation_eligibility_epoch = Epoch::new(1);
+        assert_eq!(status(&validator, 1), ValidatorStatus::PendingQueued);
+        validator.activation_epoch = Epoch::new(2);
+        assert_eq!(status(&validator, 2), ValidatorStatus::ActiveOngoing);
+        validator.exit_epoch = Epoch::new(4);
+        validator.withdrawable_epoch = Epoch::new(6);
+        assert_eq!(status(&validator, 3), ValidatorStatus::ActiveExiting);
+        assert_eq!(status(&validator, 4), ValidatorStatus::ExitedUnslashed);
+        assert_eq!(status(&validator, 6), ValidatorStatus::WithdrawalPossible);
+        assert_eq!(
+            ValidatorStatus::from_validator(&validator, 0, Epoch::new(6), far_future_epoch),
+            ValidatorStatus::WithdrawalDone
+        );
+
+        assert!(ValidatorStatus::ActiveExiting.matches(ValidatorStatus::Active));
+        assert!(ValidatorStatus::ActiveExiting.matches(ValidatorStatus::ActiveExiting));
+        assert!(!ValidatorStatus::ActiveExiting.matches(ValidatorStatus::Exited));
+    }
+}
@@ -1,9 +1,12 @@
 use bls::{PublicKey, PublicKeyBytes, Signature};
 use eth2_hashing::hash;
 use serde::{Deserialize, Serialize};
+use ssz::{Decode as SszDecode, Encode as SszEncode};
 use ssz_derive::{Decode, Encode};
 use std::convert::TryInto;
-use types::{CommitteeIndex, Epoch, Slot};
+use std::fmt;
+use std::str::FromStr;
+use types::{CommitteeIndex, Epoch, Hash256, Slot};
 
 /// A Validator duty with the validator public key represented a `PublicKeyBytes`.
 pub type ValidatorDutyBytes = ValidatorDutyBase<PublicKeyBytes>;
@@ -11,8 +14,11 @@ pub type ValidatorDutyBytes = ValidatorDutyBase<PublicKeyBytes>;
 pub type ValidatorDuty = ValidatorDutyBase<PublicKey>;
 
 // NOTE: if you add or remove fields, please adjust `eq_ignoring_proposal_slots`
-#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
-pub struct ValidatorDutyBase<T> {
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct ValidatorDutyBase<T>
+where
+    T: SszEncode + SszDecode,
+{
     /// The validator's BLS public key, uniquely identifying them.
     pub validator_pubkey: T,
     /// The validator's index in `state.validators`
@@ -35,7 +41,7 @@ pub struct ValidatorDutyBase<T> {
     pub aggregator_modulo: Option<u64>,
 }
 
-impl<T> ValidatorDutyBase<T> {
+impl<T: SszEncode + SszDecode> ValidatorDutyBase<T> {
     /// Given a `slot_signature` determines if the validator of this duty is an aggregator.
     // Note that we assume the signature is for the associated pubkey to avoid the signature
     // verification
@@ -92,6 +98,92 @@ pub struct ValidatorSubscription {
     pub is_aggregator: bool,
 }
 
+/// Informs the beacon node that a validator is managed by a connected validator client, so that
+/// the node may prepare for the validator's block proposals ahead of time.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
+pub struct ProposerPreparationData {
+    /// The validators index.
+    pub validator_index: u64,
+}
+
+/// The roots of the blocks which determined the duties of some epoch.
+///
+/// If either root changes, the corresponding duties of the epoch must be downloaded again.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy, Encode, Decode)]
+pub struct DutiesDependentRoots {
+    /// The epoch of the duties.
+    pub epoch: Epoch,
+    /// The root of the block at the last slot of `epoch - 2`, which determined the attester
+    /// shuffling of `epoch`.
+    pub attester_dependent_root: Hash256,
+    /// The root of the block at the last slot of `epoch - 1`, which determined the proposer
+    /// shuffling of `epoch`.
+    pub proposer_dependent_root: Hash256,
+}
+
+/// Requests the liveness of some validators via `POST /lighthouse/liveness`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct LivenessRequestData {
+    pub epoch: Epoch,
+    pub indices: Vec<u64>,
+}
+
+/// Indicates whether a validator was seen to attest, aggregate or propose in some epoch.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct LivenessResponseData {
+    pub index: u64,
+    pub epoch: Epoch,
+    pub is_live: bool,
+}
+
+/// The level of validation a beacon node must apply to a block submitted for publishing before it
+/// is broadcast on the network.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastValidation {
+    /// Broadcast once the block passes the gossip checks, then import it.
+    Gossip,
+    /// Broadcast only once the block has been fully imported.
+    Consensus,
+    /// As per `Consensus`, but also refuse to broadcast a block if another block from the same
+    /// proposer at the same slot is already known.
+    ConsensusAndEquivocation,
+}
+
+impl Default for BroadcastValidation {
+    fn default() -> Self {
+        BroadcastValidation::Consensus
+    }
+}
+
+impl FromStr for BroadcastValidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gossip" => Ok(BroadcastValidation::Gossip),
+            "consensus" => Ok(BroadcastValidation::Consensus),
+            "consensus_and_equivocation" => Ok(BroadcastValidation::ConsensusAndEquivocation),
+            other => Err(format!(
+                "{} is not a valid broadcast validation level",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BroadcastValidation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BroadcastValidation::Gossip => write!(f, "gossip"),
+            BroadcastValidation::Consensus => write!(f, "consensus"),
+            BroadcastValidation::ConsensusAndEquivocation => {
+                write!(f, "consensus_and_equivocation")
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
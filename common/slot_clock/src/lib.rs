@@ -46,6 +46,9 @@ pub trait SlotClock: Send + Sync + Sized {
     /// Returns the duration until the next slot.
     fn duration_to_next_slot(&self) -> Option<Duration>;
 
+    /// Returns the duration between the UNIX epoch and the start of `slot`.
+    fn start_of(&self, slot: Slot) -> Option<Duration>;
+
     /// Returns the duration until the first slot of the next epoch.
     fn duration_to_next_epoch(&self, slots_per_epoch: u64) -> Option<Duration>;
 
@@ -62,4 +65,38 @@ pub trait SlotClock: Send + Sync + Sized {
         self.slot_of(self.now_duration()?.checked_sub(tolerance)?)
             .or_else(|| Some(self.genesis_slot()))
     }
+
+    /// Returns the duration from the start of `slot` until now, or zero if `slot` has not yet
+    /// started.
+    fn duration_since_start_of(&self, slot: Slot) -> Option<Duration> {
+        Some(
+            self.now_duration()?
+                .checked_sub(self.start_of(slot)?)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns the duration since the start of the current slot, truncated to whole milliseconds.
+    fn millis_from_current_slot_start(&self) -> Option<Duration> {
+        let since_start = self.duration_since_start_of(self.now()?)?;
+        Some(Duration::from_millis(since_start.as_millis() as u64))
+    }
+
+    /// Returns the duration since the start of the current slot, truncated to whole seconds.
+    fn seconds_from_current_slot_start(&self) -> Option<Duration> {
+        let since_start = self.duration_since_start_of(self.now()?)?;
+        Some(Duration::from_secs(since_start.as_secs()))
+    }
+
+    /// Returns the delay between the start of a slot and the time at which unaggregated
+    /// attestations are produced (i.e., one third of the way through the slot).
+    fn unagg_attestation_production_delay(&self) -> Duration {
+        self.slot_duration() / 3
+    }
+
+    /// Returns the delay between the start of a slot and the time at which aggregated attestations
+    /// are produced (i.e., two thirds of the way through the slot).
+    fn agg_attestation_production_delay(&self) -> Duration {
+        self.slot_duration() * 2 / 3
+    }
 }
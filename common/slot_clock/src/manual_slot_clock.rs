@@ -1,32 +1,39 @@
 use super::SlotClock;
 use parking_lot::RwLock;
 use std::convert::TryInto;
+use std::sync::Arc;
 use std::time::Duration;
 use types::Slot;
 
 /// Determines the present slot based upon a manually-incremented UNIX timestamp.
+///
+/// Clones share the same timestamp, so a test may hold a clone and drive the time observed by
+/// every service that was given the clock.
+#[derive(Clone)]
 pub struct ManualSlotClock {
     genesis_slot: Slot,
     /// Duration from UNIX epoch to genesis.
     genesis_duration: Duration,
     /// Duration from UNIX epoch to right now.
-    current_time: RwLock<Duration>,
+    current_time: Arc<RwLock<Duration>>,
     /// The length of each slot.
     slot_duration: Duration,
 }
 
-impl Clone for ManualSlotClock {
-    fn clone(&self) -> Self {
-        ManualSlotClock {
-            genesis_slot: self.genesis_slot.clone(),
-            genesis_duration: self.genesis_duration.clone(),
-            current_time: RwLock::new(self.current_time.read().clone()),
-            slot_duration: self.slot_duration.clone(),
-        }
+impl ManualSlotClock {
+    /// Sets the present time to `duration` after the UNIX epoch.
+    ///
+    /// The time may be set backwards.
+    pub fn set_current_time(&self, duration: Duration) {
+        *self.current_time.write() = duration;
+    }
+
+    /// Advances the present time by `duration`, without regard for slot boundaries.
+    pub fn advance_time(&self, duration: Duration) {
+        let mut current_time = self.current_time.write();
+        *current_time += duration;
     }
-}
 
-impl ManualSlotClock {
     pub fn set_slot(&self, slot: u64) {
         let slots_since_genesis = slot
             .checked_sub(self.genesis_slot.as_u64())
@@ -41,6 +48,12 @@ impl ManualSlotClock {
         self.set_slot(self.now().unwrap().as_u64() + 1)
     }
 
+    /// Advances the clock to the start of the slot `n` slots after the present slot, skipping
+    /// over any slots in between.
+    pub fn skip_slots(&self, n: u64) {
+        self.set_slot(self.now().unwrap().as_u64() + n)
+    }
+
     pub fn genesis_duration(&self) -> &Duration {
         &self.genesis_duration
     }
@@ -98,7 +111,7 @@ impl SlotClock for ManualSlotClock {
 
         Self {
             genesis_slot,
-            current_time: RwLock::new(genesis_duration.clone()),
+            current_time: Arc::new(RwLock::new(genesis_duration)),
             genesis_duration,
             slot_duration,
         }
@@ -147,6 +160,10 @@ impl SlotClock for ManualSlotClock {
         self.duration_to_slot(slot, *self.current_time.read())
     }
 
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        self.start_of(slot)
+    }
+
     fn genesis_slot(&self) -> Slot {
         self.genesis_slot
     }
@@ -168,6 +185,24 @@ mod tests {
         assert_eq!(clock.now(), Some(Slot::new(123)));
     }
 
+    #[test]
+    fn clones_share_time() {
+        let clock =
+            ManualSlotClock::new(Slot::new(0), Duration::from_secs(0), Duration::from_secs(1));
+        let clone = clock.clone();
+
+        clock.skip_slots(5);
+        assert_eq!(clone.now(), Some(Slot::new(5)));
+
+        clone.advance_time(Duration::from_millis(1_500));
+        assert_eq!(clock.now(), Some(Slot::new(6)));
+        assert_eq!(clock.now_duration(), Some(Duration::from_millis(6_500)));
+
+        // Travel back in time.
+        clone.set_current_time(Duration::from_millis(2_999));
+        assert_eq!(clock.now(), Some(Slot::new(2)));
+    }
+
     #[test]
     fn test_is_prior_to_genesis() {
         let genesis_secs = 1;
@@ -354,4 +389,46 @@ mod tests {
             "significant past tolerance should return previous slot"
         );
     }
+
+    #[test]
+    fn test_sub_slot_durations() {
+        let clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(10),
+            Duration::from_secs(6),
+        );
+
+        // Set clock to part-way through the 1st slot.
+        clock.set_current_time(Duration::from_micros(18_345_678));
+
+        assert_eq!(
+            clock.duration_since_start_of(Slot::new(1)),
+            Some(Duration::from_micros(2_345_678))
+        );
+        assert_eq!(
+            clock.duration_since_start_of(Slot::new(2)),
+            Some(Duration::from_secs(0)),
+            "should be zero for a slot which has not started"
+        );
+        assert_eq!(
+            clock.millis_from_current_slot_start(),
+            Some(Duration::from_millis(2_345))
+        );
+        assert_eq!(
+            clock.seconds_from_current_slot_start(),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            clock.unagg_attestation_production_delay(),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            clock.agg_attestation_production_delay(),
+            Duration::from_secs(4)
+        );
+
+        // Set the clock prior to genesis.
+        clock.set_current_time(Duration::from_secs(9));
+        assert_eq!(clock.millis_from_current_slot_start(), None);
+    }
 }
@@ -3,14 +3,10 @@ use proto_array::{Block as ProtoBlock, ProtoArrayForkChoice};
 use ssz_derive::{Decode, Encode};
 use std::marker::PhantomData;
 use types::{
-    BeaconBlock, BeaconState, BeaconStateError, Epoch, EthSpec, Hash256, IndexedAttestation, Slot,
+    BeaconBlock, BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, Hash256,
+    IndexedAttestation, Slot,
 };
 
-/// Defined here:
-///
-/// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#configuration
-pub const SAFE_SLOTS_TO_UPDATE_JUSTIFIED: u64 = 8;
-
 #[derive(Debug)]
 pub enum Error<T> {
     InvalidAttestation(InvalidAttestation),
@@ -180,6 +176,24 @@ impl<E: EthSpec> From<&IndexedAttestation<E>> for QueuedAttestation {
     }
 }
 
+impl QueuedAttestation {
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn attesting_indices(&self) -> &[u64] {
+        &self.attesting_indices
+    }
+
+    pub fn block_root(&self) -> Hash256 {
+        self.block_root
+    }
+
+    pub fn target_epoch(&self) -> Epoch {
+        self.target_epoch
+    }
+}
+
 /// Returns all values in `self.queued_attestations` that have a slot that is earlier than the
 /// current slot. Also removes those values from `self.queued_attestations`.
 fn dequeue_attestations(
@@ -196,6 +210,15 @@ fn dequeue_attestations(
     std::mem::replace(queued_attestations, remaining)
 }
 
+/// The result of a previous call to `ForkChoice::get_head`.
+///
+/// It remains valid until the slot changes or the block DAG, votes or checkpoints are modified.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CachedHead {
+    slot: Slot,
+    root: Hash256,
+}
+
 /// Provides an implementation of "Ethereum 2.0 Phase 0 -- Beacon Chain Fork Choice":
 ///
 /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#ethereum-20-phase-0----beacon-chain-fork-choice
@@ -206,6 +229,7 @@ fn dequeue_attestations(
 ///
 /// - Management of the justified state and caching of balances.
 /// - Queuing of attestations from the current slot.
+/// - Caching of the head between calls to `get_head` in the same slot.
 pub struct ForkChoice<T, E> {
     /// Storage for `ForkChoice`, modelled off the spec `Store` object.
     fc_store: T,
@@ -213,6 +237,8 @@ pub struct ForkChoice<T, E> {
     proto_array: ProtoArrayForkChoice,
     /// Attestations that arrived at the current slot and must be queued for later processing.
     queued_attestations: Vec<QueuedAttestation>,
+    /// The most recent result of `Self::get_head`, cleared whenever it may have been invalidated.
+    cached_head: Option<CachedHead>,
     _phantom: PhantomData<E>,
 }
 
@@ -253,6 +279,7 @@ where
             fc_store,
             proto_array,
             queued_attestations: vec![],
+            cached_head: None,
             _phantom: PhantomData,
         })
     }
@@ -270,6 +297,7 @@ where
             fc_store,
             proto_array,
             queued_attestations,
+            cached_head: None,
             _phantom: PhantomData,
         }
     }
@@ -326,22 +354,35 @@ where
     /// Is equivalent to:
     ///
     /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#get_head
+    ///
+    /// ## Notes
+    ///
+    /// The result is cached and returned from subsequent calls in the same slot, as long as no
+    /// blocks or attestations have been applied in the meantime.
     pub fn get_head(&mut self, current_slot: Slot) -> Result<Hash256, Error<T::Error>> {
-        self.update_time(current_slot)?;
+        let current_slot = self.update_time(current_slot)?;
+
+        if let Some(cached_head) = self.cached_head {
+            if cached_head.slot == current_slot {
+                return Ok(cached_head.root);
+            }
+        }
 
         let store = &mut self.fc_store;
 
-        let result = self
-            .proto_array
-            .find_head(
-                store.justified_checkpoint().epoch,
-                store.justified_checkpoint().root,
-                store.finalized_checkpoint().epoch,
-                store.justified_balances(),
-            )
-            .map_err(Into::into);
+        let root = self.proto_array.find_head(
+            store.justified_checkpoint().epoch,
+            store.justified_checkpoint().root,
+            store.finalized_checkpoint().epoch,
+            store.justified_balances(),
+        )?;
+
+        self.cached_head = Some(CachedHead {
+            slot: current_slot,
+            root,
+        });
 
-        result
+        Ok(root)
     }
 
     /// Returns `true` if the given `store` should be updated to set
@@ -356,13 +397,14 @@ where
         &mut self,
         current_slot: Slot,
         state: &BeaconState<E>,
+        spec: &ChainSpec,
     ) -> Result<bool, Error<T::Error>> {
         self.update_time(current_slot)?;
 
         let new_justified_checkpoint = &state.current_justified_checkpoint;
 
         if compute_slots_since_epoch_start::<E>(self.fc_store.get_current_slot())
-            < SAFE_SLOTS_TO_UPDATE_JUSTIFIED
+            < spec.safe_slots_to_update_justified
         {
             return Ok(true);
         }
@@ -419,6 +461,7 @@ where
         block: &BeaconBlock<E>,
         block_root: Hash256,
         state: &BeaconState<E>,
+        spec: &ChainSpec,
     ) -> Result<(), Error<T::Error>> {
         let current_slot = self.update_time(current_slot)?;
 
@@ -469,6 +512,9 @@ where
             }));
         }
 
+        // The block is valid, so any of the following changes may alter the head.
+        self.cached_head = None;
+
         // Update justified checkpoint.
         if state.current_justified_checkpoint.epoch > self.fc_store.justified_checkpoint().epoch {
             if state.current_justified_checkpoint.epoch
@@ -477,7 +523,7 @@ where
                 self.fc_store
                     .set_best_justified_checkpoint(state.current_justified_checkpoint);
             }
-            if self.should_update_justified_checkpoint(current_slot, state)? {
+            if self.should_update_justified_checkpoint(current_slot, state, spec)? {
                 self.fc_store
                     .set_justified_checkpoint(state.current_justified_checkpoint)
                     .map_err(Error::UnableToSetJustifiedCheckpoint)?;
@@ -514,8 +560,14 @@ where
             .slot
             .epoch(E::slots_per_epoch())
             .start_slot(E::slots_per_epoch());
+        // Resolve the target root from the fork choice DAG, which is always aware of the parent of
+        // `block`. Only fall back to `state.block_roots` if the target precedes the blocks in
+        // `self.proto_array` (e.g., they have been pruned), so that `state` need not have intact
+        // `block_roots` in the common case.
         let target_root = if block.slot == target_slot {
             block_root
+        } else if let Some(root) = self.get_ancestor(block.parent_root, target_slot)? {
+            root
         } else {
             *state
                 .get_block_root(target_slot)
@@ -689,6 +741,7 @@ where
                     attestation.data.target.epoch,
                 )?;
             }
+            self.cached_head = None;
         } else {
             // The spec declares:
             //
@@ -706,6 +759,11 @@ where
     /// Call `on_tick` for all slots between `fc_store.get_current_slot()` and the provided
     /// `current_slot`. Returns the value of `self.fc_store.get_current_slot`.
     pub fn update_time(&mut self, current_slot: Slot) -> Result<Slot, Error<T::Error>> {
+        if self.fc_store.get_current_slot() < current_slot {
+            // Ticking may update the justified checkpoint.
+            self.cached_head = None;
+        }
+
         while self.fc_store.get_current_slot() < current_slot {
             let previous_slot = self.fc_store.get_current_slot();
             // Note: we are relying upon `on_tick` to update `fc_store.time` to ensure we don't
@@ -733,6 +791,7 @@ where
                     attestation.target_epoch,
                 )?;
             }
+            self.cached_head = None;
         }
 
         Ok(())
@@ -748,6 +807,28 @@ where
         self.proto_array.get_block(block_root)
     }
 
+    /// Returns the root of the block which decided the attester shuffling for `epoch` on the chain
+    /// of `block_root`.
+    ///
+    /// The shuffling for `epoch` is determined by the state at the last slot of `epoch - 2`, so all
+    /// blocks which share the ancestor at that slot share the shuffling, regardless of which fork
+    /// they are on.
+    ///
+    /// Returns `None` if `block_root` is unknown, or if the decision block is prior to the
+    /// finalized block and has been pruned from fork choice.
+    pub fn attester_shuffling_decision_root(
+        &self,
+        block_root: Hash256,
+        epoch: Epoch,
+    ) -> Option<Hash256> {
+        let decision_slot = epoch
+            .saturating_sub(1_u64)
+            .start_slot(E::slots_per_epoch())
+            .saturating_sub(1_u64);
+
+        self.get_ancestor(block_root, decision_slot).ok().flatten()
+    }
+
     /// Returns the latest message for a given validator, if any.
     ///
     /// Returns `(block_root, block_slot)`.
@@ -775,10 +856,19 @@ where
         &self.queued_attestations
     }
 
+    /// Returns the slot and root of the head cached by the last call to `Self::get_head`, if it
+    /// has not since been invalidated.
+    pub fn cached_head(&self) -> Option<(Slot, Hash256)> {
+        self.cached_head
+            .map(|cached_head| (cached_head.slot, cached_head.root))
+    }
+
     /// Prunes the underlying fork choice DAG.
     pub fn prune(&mut self) -> Result<(), Error<T::Error>> {
         let finalized_root = self.fc_store.finalized_checkpoint().root;
 
+        self.cached_head = None;
+
         self.proto_array
             .maybe_prune(finalized_root)
             .map_err(Into::into)
@@ -797,6 +887,7 @@ where
             fc_store,
             proto_array,
             queued_attestations: persisted.queued_attestations,
+            cached_head: None,
             _phantom: PhantomData,
         })
     }
@@ -820,6 +911,19 @@ pub struct PersistedForkChoice {
     queued_attestations: Vec<QueuedAttestation>,
 }
 
+impl PersistedForkChoice {
+    /// Returns the SSZ-encoded `ProtoArrayForkChoice`, decodable with
+    /// `ProtoArrayForkChoice::from_bytes`.
+    pub fn proto_array_bytes(&self) -> &[u8] {
+        &self.proto_array_bytes
+    }
+
+    /// Returns the attestations that were queued for a future slot at the time of persisting.
+    pub fn queued_attestations(&self) -> &[QueuedAttestation] {
+        &self.queued_attestations
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,6 +44,13 @@ pub trait ForkChoiceStore<T: EthSpec>: Sized {
     /// Returns balances from the `state` identified by `justified_checkpoint.root`.
     fn justified_balances(&self) -> &[u64];
 
+    /// Returns the effective balances from the `state` identified by `checkpoint.root`.
+    ///
+    /// Unlike `Self::justified_balances`, the `checkpoint` need not be the current
+    /// `justified_checkpoint`. Implementations should avoid requiring the caller to supply the
+    /// `BeaconState` (e.g., by caching balances at epoch boundary blocks).
+    fn checkpoint_balances(&self, checkpoint: &Checkpoint) -> Result<Vec<u64>, Self::Error>;
+
     /// Returns the `best_justified_checkpoint`.
     fn best_justified_checkpoint(&self) -> &Checkpoint;
 
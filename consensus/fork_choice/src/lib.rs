@@ -3,6 +3,5 @@ mod fork_choice_store;
 
 pub use crate::fork_choice::{
     Error, ForkChoice, InvalidAttestation, InvalidBlock, PersistedForkChoice, QueuedAttestation,
-    SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
 };
 pub use fork_choice_store::ForkChoiceStore;
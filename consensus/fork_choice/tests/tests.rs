@@ -4,17 +4,14 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, HarnessType},
     BeaconChain, BeaconChainError, BeaconForkChoiceStore, ForkChoiceError,
 };
-use fork_choice::{
-    ForkChoiceStore, InvalidAttestation, InvalidBlock, QueuedAttestation,
-    SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
-};
+use fork_choice::{ForkChoiceStore, InvalidAttestation, InvalidBlock, QueuedAttestation};
 use std::sync::Mutex;
 use store::{MemoryStore, StoreConfig};
 use types::{
     test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
-    Epoch, EthSpec, IndexedAttestation, MainnetEthSpec, Slot, SubnetId,
+    Epoch, EthSpec, FixedVector, IndexedAttestation, MainnetEthSpec, Slot, SubnetId,
 };
-use types::{BeaconBlock, BeaconState, Hash256, SignedBeaconBlock};
+use types::{BeaconBlock, BeaconState, Checkpoint, Hash256, SignedBeaconBlock};
 
 pub type E = MainnetEthSpec;
 
@@ -100,6 +97,33 @@ impl ForkChoiceTest {
         self
     }
 
+    /// Run fork choice at the current slot, populating its head cache.
+    pub fn compute_head(self) -> Self {
+        let current_slot = self.harness.chain.slot().unwrap();
+        self.harness
+            .chain
+            .fork_choice
+            .write()
+            .get_head(current_slot)
+            .unwrap();
+        self
+    }
+
+    /// Inspect the head cached in fork choice.
+    pub fn inspect_cached_head<F>(self, mut func: F) -> Self
+    where
+        F: FnMut(Option<(Slot, Hash256)>),
+    {
+        func(self.harness.chain.fork_choice.read().cached_head());
+        self
+    }
+
+    /// Prune the fork choice DAG.
+    pub fn prune_fork_choice(self) -> Self {
+        self.harness.chain.fork_choice.write().prune().unwrap();
+        self
+    }
+
     /// Skip a slot, without producing a block.
     pub fn skip_slot(self) -> Self {
         self.harness.advance_slot();
@@ -192,7 +216,13 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                &state,
+                &self.harness.spec,
+            )
             .unwrap();
         self
     }
@@ -217,23 +247,28 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                &state,
+                &self.harness.spec,
+            )
             .err()
             .expect("on_block did not return an error");
         comparison_func(err);
         self
     }
 
-    /// Compares the justified balances in the `ForkChoiceStore` verses a direct lookup from the
-    /// database.
-    fn check_justified_balances(&self) {
+    /// Returns the effective balances of the state of the block at `block_root`, read directly
+    /// from the database.
+    fn get_balances_from_db(&self, block_root: Hash256) -> Vec<u64> {
         let harness = &self.harness;
-        let fc = self.harness.chain.fork_choice.read();
 
         let state_root = harness
             .chain
             .store
-            .get_item::<SignedBeaconBlock<E>>(&fc.fc_store().justified_checkpoint().root)
+            .get_item::<SignedBeaconBlock<E>>(&block_root)
             .unwrap()
             .unwrap()
             .message
@@ -244,7 +279,7 @@ impl ForkChoiceTest {
             .get_state(&state_root, None)
             .unwrap()
             .unwrap();
-        let balances = state
+        state
             .validators
             .into_iter()
             .map(|v| {
@@ -254,7 +289,14 @@ impl ForkChoiceTest {
                     0
                 }
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
+
+    /// Compares the justified balances in the `ForkChoiceStore` verses a direct lookup from the
+    /// database.
+    fn check_justified_balances(&self) {
+        let fc = self.harness.chain.fork_choice.read();
+        let balances = self.get_balances_from_db(fc.fc_store().justified_checkpoint().root);
 
         assert_eq!(
             &balances[..],
@@ -263,6 +305,23 @@ impl ForkChoiceTest {
         )
     }
 
+    /// Compares the balances returned by `ForkChoiceStore::checkpoint_balances` for the checkpoint
+    /// returned by `func` verses a direct lookup from the database.
+    fn check_checkpoint_balances<F>(&self, func: F)
+    where
+        F: Fn(&BeaconForkChoiceStore<E, MemoryStore<E>, MemoryStore<E>>) -> Checkpoint,
+    {
+        let fc = self.harness.chain.fork_choice.read();
+        let checkpoint = func(fc.fc_store());
+        let balances = self.get_balances_from_db(checkpoint.root);
+
+        assert_eq!(
+            balances,
+            fc.fc_store().checkpoint_balances(&checkpoint).unwrap(),
+            "balances should match"
+        )
+    }
+
     /// Returns an attestation that is valid for some slot in the given `chain`.
     ///
     /// Also returns some info about who created it.
@@ -338,7 +397,7 @@ impl ForkChoiceTest {
 }
 
 fn is_safe_to_update(slot: Slot) -> bool {
-    slot % E::slots_per_epoch() < SAFE_SLOTS_TO_UPDATE_JUSTIFIED
+    slot % E::slots_per_epoch() < E::default_spec().safe_slots_to_update_justified
 }
 
 /// - The new justified checkpoint descends from the current.
@@ -467,6 +526,17 @@ fn justified_balances() {
         .check_justified_balances()
 }
 
+/// Check that the balances of a checkpoint other than the justified checkpoint are obtained
+/// correctly.
+#[test]
+fn checkpoint_balances() {
+    ForkChoiceTest::new()
+        .apply_blocks_while(|_, state| state.current_justified_checkpoint.epoch == 0)
+        .apply_blocks(1)
+        .assert_justified_epoch(2)
+        .check_checkpoint_balances(|fc_store| *fc_store.finalized_checkpoint())
+}
+
 macro_rules! assert_invalid_block {
     ($err: tt, $($error: pat) |+ $( if $guard: expr )?) => {
         assert!(
@@ -576,6 +646,43 @@ fn invalid_block_finalized_descendant() {
         );
 }
 
+/// Ensure the target root of a block is obtained from the fork choice DAG, rather than relying
+/// upon the `block_roots` of the supplied state.
+#[test]
+fn block_target_root_without_state_block_roots() {
+    let block_root = Mutex::new(Hash256::zero());
+    let expected_target_root = Mutex::new(Hash256::zero());
+
+    let test = ForkChoiceTest::new()
+        .apply_blocks(2)
+        .apply_block_directly_to_fork_choice(|block, state| {
+            let target_slot = block
+                .slot
+                .epoch(E::slots_per_epoch())
+                .start_slot(E::slots_per_epoch());
+            assert!(block.slot > target_slot, "block must not be at epoch start");
+
+            *expected_target_root.lock().unwrap() = *state.get_block_root(target_slot).unwrap();
+            *block_root.lock().unwrap() = block.canonical_root();
+
+            state.block_roots = FixedVector::from_elem(Hash256::zero());
+        });
+
+    let proto_block = test
+        .harness
+        .chain
+        .fork_choice
+        .read()
+        .get_block(&block_root.lock().unwrap())
+        .expect("block should be in fork choice");
+
+    assert_eq!(
+        proto_block.target_root,
+        *expected_target_root.lock().unwrap(),
+        "target root should match"
+    );
+}
+
 macro_rules! assert_invalid_attestation {
     ($err: tt, $($error: pat) |+ $( if $guard: expr )?) => {
         assert!(
@@ -837,3 +944,139 @@ fn valid_attestation_skip_across_epoch() {
             |result| result.unwrap(),
         );
 }
+
+/// Ensure the head is cached between calls to `get_head` in the same slot.
+#[test]
+fn cached_head_within_slot() {
+    let cached_head = Mutex::new(None);
+
+    let test = ForkChoiceTest::new()
+        .apply_blocks(2)
+        .compute_head()
+        .inspect_cached_head(|cached| {
+            assert!(cached.is_some(), "head should be cached");
+            *cached_head.lock().unwrap() = cached;
+        })
+        .compute_head()
+        .inspect_cached_head(|cached| {
+            assert_eq!(
+                cached,
+                *cached_head.lock().unwrap(),
+                "cache should be unchanged"
+            )
+        });
+
+    let (slot, root) = cached_head.lock().unwrap().unwrap();
+    assert_eq!(
+        slot,
+        test.harness.chain.slot().unwrap(),
+        "slot should match"
+    );
+    assert_eq!(
+        root,
+        test.harness.chain.head_info().unwrap().block_root,
+        "root should match"
+    );
+}
+
+/// Ensure a valid block invalidates the cached head.
+#[test]
+fn cached_head_invalidated_by_block() {
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .compute_head()
+        .inspect_cached_head(|cached| assert!(cached.is_some()))
+        .apply_block_directly_to_fork_choice(|_, _| {})
+        .inspect_cached_head(|cached| assert_eq!(cached, None));
+}
+
+/// Ensure an attestation which is applied to the DAG invalidates the cached head.
+#[test]
+fn cached_head_invalidated_by_attestation() {
+    ForkChoiceTest::new()
+        .apply_blocks_without_new_attestations(1)
+        .apply_attestation_to_chain(
+            MutationDelay::Blocks(1),
+            |_, chain| {
+                let mut fork_choice = chain.fork_choice.write();
+                fork_choice.get_head(chain.slot().unwrap()).unwrap();
+                assert!(fork_choice.cached_head().is_some());
+            },
+            |result| assert_eq!(result.unwrap(), ()),
+        )
+        .inspect_cached_head(|cached| assert_eq!(cached, None));
+}
+
+/// Ensure an attestation from the current slot does not invalidate the cached head until it is
+/// dequeued in a later slot.
+#[test]
+fn cached_head_invalidated_by_dequeued_attestation() {
+    ForkChoiceTest::new()
+        .apply_blocks_without_new_attestations(1)
+        .compute_head()
+        .apply_attestation_to_chain(
+            MutationDelay::NoDelay,
+            |_, _| {},
+            |result| assert_eq!(result.unwrap(), ()),
+        )
+        .inspect_cached_head(|cached| assert!(cached.is_some()))
+        .skip_slot()
+        .inspect_queued_attestations(|queue| assert_eq!(queue.len(), 0))
+        .inspect_cached_head(|cached| assert_eq!(cached, None));
+}
+
+/// Ensure pruning invalidates the cached head.
+#[test]
+fn cached_head_invalidated_by_prune() {
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .compute_head()
+        .inspect_cached_head(|cached| assert!(cached.is_some()))
+        .prune_fork_choice()
+        .inspect_cached_head(|cached| assert_eq!(cached, None));
+}
+
+/// Ensure an invalid block does not modify the cached head.
+#[test]
+fn cached_head_unchanged_by_invalid_block() {
+    let junk = Hash256::from_low_u64_be(42);
+    let cached_head = Mutex::new(None);
+
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .compute_head()
+        .inspect_cached_head(|cached| {
+            assert!(cached.is_some());
+            *cached_head.lock().unwrap() = cached;
+        })
+        .apply_invalid_block_directly_to_fork_choice(
+            |block, _| {
+                block.parent_root = junk;
+            },
+            |err| assert_invalid_block!(err, InvalidBlock::UnknownParent(_)),
+        )
+        .inspect_cached_head(|cached| assert_eq!(cached, *cached_head.lock().unwrap()));
+}
+
+/// Ensure an invalid attestation does not modify the cached head.
+#[test]
+fn cached_head_unchanged_by_invalid_attestation() {
+    let junk = Hash256::from_low_u64_be(42);
+    let cached_head = Mutex::new(None);
+
+    ForkChoiceTest::new()
+        .apply_blocks_without_new_attestations(1)
+        .compute_head()
+        .inspect_cached_head(|cached| {
+            assert!(cached.is_some());
+            *cached_head.lock().unwrap() = cached;
+        })
+        .apply_attestation_to_chain(
+            MutationDelay::NoDelay,
+            |attestation, _| {
+                attestation.data.target.root = junk;
+            },
+            |result| assert_invalid_attestation!(result, InvalidAttestation::UnknownTargetRoot(_)),
+        )
+        .inspect_cached_head(|cached| assert_eq!(cached, *cached_head.lock().unwrap()));
+}
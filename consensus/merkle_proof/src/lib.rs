@@ -2,6 +2,7 @@ use eth2_hashing::{hash, hash32_concat, ZERO_HASHES};
 use ethereum_types::H256;
 use lazy_static::lazy_static;
 use safe_arith::ArithError;
+use std::collections::{BTreeSet, HashMap};
 
 const MAX_TREE_DEPTH: usize = 32;
 const EMPTY_SLICE: &[H256] = &[];
@@ -41,6 +42,8 @@ pub enum MerkleTreeError {
     DepthTooSmall,
     // Overflow occurred
     ArithError,
+    // Generalized index is zero or deeper than the tree
+    InvalidGeneralizedIndex(u64),
 }
 
 impl MerkleTree {
@@ -195,6 +198,134 @@ impl MerkleTree {
 
         (current_node.hash(), proof)
     }
+
+    /// Return the hash of the node at the generalized index `gindex`, or `None` if `gindex` is
+    /// not within a tree of the given `depth`.
+    ///
+    /// The root has a generalized index of `1` and the children of the node at `i` have the
+    /// generalized indices `2 * i` and `2 * i + 1`.
+    pub fn node_hash(&self, gindex: u64, depth: usize) -> Option<H256> {
+        if gindex == 0 {
+            return None;
+        }
+
+        let node_depth = (63 - gindex.leading_zeros()) as usize;
+        if node_depth > depth {
+            return None;
+        }
+
+        let mut current_node = self;
+        for i in (0..node_depth).rev() {
+            let (left, right) = current_node.left_and_right_branches()?;
+            current_node = if (gindex >> i) & 0x01 == 1 {
+                right
+            } else {
+                left
+            };
+        }
+
+        Some(current_node.hash())
+    }
+
+    /// Return the nodes at each of `gindices` and a Merkle multiproof of their inclusion.
+    ///
+    /// The multiproof contains the nodes at the generalized indices returned by
+    /// `get_helper_indices`, in the same order.
+    pub fn generate_multiproof(
+        &self,
+        gindices: &[u64],
+        depth: usize,
+    ) -> Result<(Vec<H256>, Vec<H256>), MerkleTreeError> {
+        let node_hash = |gindex: u64| {
+            self.node_hash(gindex, depth)
+                .ok_or_else(|| MerkleTreeError::InvalidGeneralizedIndex(gindex))
+        };
+
+        let leaves = gindices
+            .iter()
+            .map(|gindex| node_hash(*gindex))
+            .collect::<Result<_, _>>()?;
+        let proof = get_helper_indices(gindices)
+            .into_iter()
+            .map(node_hash)
+            .collect::<Result<_, _>>()?;
+
+        Ok((leaves, proof))
+    }
+}
+
+/// Return the generalized indices of the nodes required to prove the nodes at `gindices`, in
+/// descending order.
+///
+/// This is the equivalent to the `get_helper_indices` function of the SSZ Merkle proofs spec.
+pub fn get_helper_indices(gindices: &[u64]) -> Vec<u64> {
+    let mut branch_indices = BTreeSet::new();
+    let mut path_indices = BTreeSet::new();
+
+    for &gindex in gindices {
+        let mut i = gindex;
+        while i > 1 {
+            branch_indices.insert(i ^ 1);
+            path_indices.insert(i);
+            i /= 2;
+        }
+    }
+
+    branch_indices
+        .difference(&path_indices)
+        .rev()
+        .copied()
+        .collect()
+}
+
+/// Verify a multiproof that each of `leaves` exists at the corresponding generalized index of
+/// `gindices` in a Merkle tree rooted at `root`.
+///
+/// The `proof` must contain the nodes at the generalized indices returned by
+/// `get_helper_indices`, in the same order.
+pub fn verify_merkle_multiproof(
+    leaves: &[H256],
+    proof: &[H256],
+    gindices: &[u64],
+    root: H256,
+) -> bool {
+    let helper_indices = get_helper_indices(gindices);
+    if leaves.len() != gindices.len()
+        || proof.len() != helper_indices.len()
+        || gindices.contains(&0)
+    {
+        return false;
+    }
+
+    let mut objects = gindices
+        .iter()
+        .chain(helper_indices.iter())
+        .copied()
+        .zip(leaves.iter().chain(proof.iter()).copied())
+        .collect::<HashMap<_, _>>();
+
+    let mut keys = objects.keys().copied().collect::<Vec<_>>();
+    keys.sort_by(|a, b| b.cmp(a));
+
+    let mut pos = 0;
+    while pos < keys.len() {
+        let k = keys[pos];
+        if objects.contains_key(&k)
+            && objects.contains_key(&(k ^ 1))
+            && !objects.contains_key(&(k / 2))
+        {
+            let left = objects[&(k & !1)];
+            let right = objects[&(k | 1)];
+            objects.insert(
+                k / 2,
+                H256::from_slice(&hash32_concat(left.as_bytes(), right.as_bytes())),
+            );
+            keys.push(k / 2);
+        }
+        pos += 1;
+    }
+
+    objects.get(&1) == Some(&root)
 }
 
 /// Verify a proof that `leaf` exists at `index` in a Merkle tree rooted at `root`.
@@ -395,6 +526,70 @@ mod tests {
         ));
     }
 
+    /// Check that multiproofs generated for arbitrary sets of nodes can be verified.
+    #[quickcheck]
+    fn quickcheck_multiproof_create_and_verify(
+        int_leaves: Vec<u64>,
+        gindices: Vec<u64>,
+    ) -> TestResult {
+        let depth = 4;
+        if int_leaves.len() > 2usize.pow(depth as u32) {
+            return TestResult::discard();
+        }
+
+        let leaves: Vec<_> = int_leaves.into_iter().map(H256::from_low_u64_be).collect();
+        let merkle_tree = MerkleTree::create(&leaves, depth);
+        let gindices: Vec<_> = gindices
+            .into_iter()
+            .map(|gindex| gindex % 2u64.pow(depth as u32 + 1))
+            .filter(|gindex| *gindex > 0)
+            .collect();
+        if gindices.is_empty() {
+            return TestResult::discard();
+        }
+
+        let (nodes, proof) = merkle_tree
+            .generate_multiproof(&gindices, depth)
+            .expect("gindices are within the tree");
+
+        TestResult::from_bool(verify_merkle_multiproof(
+            &nodes,
+            &proof,
+            &gindices,
+            merkle_tree.hash(),
+        ))
+    }
+
+    #[test]
+    fn multiproof_small_example() {
+        let leaf_b00 = H256::from([0xAA; 32]);
+        let leaf_b01 = H256::from([0xBB; 32]);
+        let leaf_b10 = H256::from([0xCC; 32]);
+        let leaf_b11 = H256::from([0xDD; 32]);
+        let depth = 2;
+        let tree = MerkleTree::create(&[leaf_b00, leaf_b01, leaf_b10, leaf_b11], depth);
+        let root = tree.hash();
+
+        assert_eq!(get_helper_indices(&[4, 7]), vec![6, 5]);
+        let (nodes, proof) = tree.generate_multiproof(&[4, 7], depth).unwrap();
+        assert_eq!(nodes, vec![leaf_b00, leaf_b11]);
+        assert!(verify_merkle_multiproof(&nodes, &proof, &[4, 7], root));
+        assert!(!verify_merkle_multiproof(&nodes, &proof, &[4, 6], root));
+
+        let (nodes, proof) = tree.generate_multiproof(&[1], depth).unwrap();
+        assert_eq!(nodes, vec![root]);
+        assert!(proof.is_empty());
+
+        assert_eq!(
+            tree.generate_multiproof(&[8], depth),
+            Err(MerkleTreeError::InvalidGeneralizedIndex(8))
+        );
+        assert_eq!(
+            tree.generate_multiproof(&[0], depth),
+            Err(MerkleTreeError::InvalidGeneralizedIndex(0))
+        );
+    }
+
     #[test]
     fn verify_zero_depth() {
         let leaf = H256::from([0xD6; 32]);
@@ -1,5 +1,9 @@
+#[macro_use]
+extern crate lazy_static;
+
 mod error;
 pub mod fork_choice_test_definition;
+mod metrics;
 mod proto_array;
 mod proto_array_fork_choice;
 mod ssz_container;
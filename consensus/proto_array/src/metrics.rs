@@ -0,0 +1,23 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    /*
+     * Find head
+     */
+    pub static ref FIND_HEAD_COMPUTE_DELTAS_TIMES: Result<Histogram> = try_create_histogram(
+        "proto_array_find_head_compute_deltas_seconds",
+        "Time taken to apply new votes and balance changes as per-node weight deltas"
+    );
+    pub static ref FIND_HEAD_APPLY_SCORE_CHANGES_TIMES: Result<Histogram> = try_create_histogram(
+        "proto_array_find_head_apply_score_changes_seconds",
+        "Time taken to propagate weight deltas through the tree and update best children"
+    );
+    pub static ref FIND_HEAD_SELECT_HEAD_TIMES: Result<Histogram> = try_create_histogram(
+        "proto_array_find_head_select_head_seconds",
+        "Time taken to select the best descendant of the justified block"
+    );
+    pub static ref PROTO_ARRAY_NODES: Result<IntGauge> = try_create_int_gauge(
+        "proto_array_nodes",
+        "Number of nodes in the proto array at the last call to find_head"
+    );
+}
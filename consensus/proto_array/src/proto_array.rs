@@ -27,6 +27,19 @@ pub struct ProtoNode {
     best_descendant: Option<usize>,
 }
 
+impl ProtoNode {
+    /// Returns the total balance of all validators voting for this node or its descendants.
+    pub fn weight(&self) -> u64 {
+        self.weight
+    }
+
+    /// Returns the index of the descendant of this node which would become the head if this
+    /// node were the justified root, if any.
+    pub fn best_descendant(&self) -> Option<usize> {
+        self.best_descendant
+    }
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
 pub struct ProtoArray {
     /// Do not attempt to prune the tree unless it has at least this many nodes. Small prunes
@@ -403,7 +416,7 @@ impl ProtoArray {
     ///
     /// Any node that has a different finalized or justified epoch should not be viable for the
     /// head.
-    fn node_is_viable_for_head(&self, node: &ProtoNode) -> bool {
+    pub fn node_is_viable_for_head(&self, node: &ProtoNode) -> bool {
         (node.justified_epoch == self.justified_epoch || self.justified_epoch == Epoch::new(0))
             && (node.finalized_epoch == self.finalized_epoch
                 || self.finalized_epoch == Epoch::new(0))
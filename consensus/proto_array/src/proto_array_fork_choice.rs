@@ -1,4 +1,5 @@
 use crate::error::Error;
+use crate::metrics;
 use crate::proto_array::ProtoArray;
 use crate::ssz_container::SszContainer;
 use ssz::{Decode, Encode};
@@ -138,6 +139,12 @@ impl ProtoArrayForkChoice {
 
         let new_balances = justified_state_balances;
 
+        metrics::set_gauge(
+            &metrics::PROTO_ARRAY_NODES,
+            self.proto_array.nodes.len() as i64,
+        );
+
+        let timer = metrics::start_timer(&metrics::FIND_HEAD_COMPUTE_DELTAS_TIMES);
         let deltas = compute_deltas(
             &self.proto_array.indices,
             &mut self.votes,
@@ -145,13 +152,17 @@ impl ProtoArrayForkChoice {
             &new_balances,
         )
         .map_err(|e| format!("find_head compute_deltas failed: {:?}", e))?;
+        metrics::stop_timer(timer);
 
+        let timer = metrics::start_timer(&metrics::FIND_HEAD_APPLY_SCORE_CHANGES_TIMES);
         self.proto_array
             .apply_score_changes(deltas, justified_epoch, finalized_epoch)
             .map_err(|e| format!("find_head apply_score_changes failed: {:?}", e))?;
+        metrics::stop_timer(timer);
 
         *old_balances = new_balances.to_vec();
 
+        let _timer = metrics::start_timer(&metrics::FIND_HEAD_SELECT_HEAD_TIMES);
         self.proto_array
             .find_head(&justified_root)
             .map_err(|e| format!("find_head failed: {:?}", e))
@@ -194,6 +205,45 @@ impl ProtoArrayForkChoice {
         })
     }
 
+    /// Returns the weight of the block with the given `block_root`, if it is known.
+    ///
+    /// The weight is only updated during `Self::find_head`.
+    pub fn get_weight(&self, block_root: &Hash256) -> Option<u64> {
+        let block_index = self.proto_array.indices.get(block_root)?;
+        self.proto_array
+            .nodes
+            .get(*block_index)
+            .map(|node| node.weight())
+    }
+
+    /// Returns the root of the best descendant of the block with the given `block_root`, if the
+    /// block is known and has a descendant which leads to a viable head.
+    ///
+    /// Like the weight, the best descendant is only updated during `Self::find_head`.
+    pub fn get_best_descendant(&self, block_root: &Hash256) -> Option<Hash256> {
+        let block_index = self.proto_array.indices.get(block_root)?;
+        let best_descendant_index = self
+            .proto_array
+            .nodes
+            .get(*block_index)?
+            .best_descendant()?;
+        self.proto_array
+            .nodes
+            .get(best_descendant_index)
+            .map(|node| node.root)
+    }
+
+    /// Returns `true` if the block with the given `block_root` agrees with the justified and
+    /// finalized checkpoints of fork choice, and could therefore become the head. Returns `None`
+    /// if the block is unknown.
+    pub fn is_viable_for_head(&self, block_root: &Hash256) -> Option<bool> {
+        let block_index = self.proto_array.indices.get(block_root)?;
+        self.proto_array
+            .nodes
+            .get(*block_index)
+            .map(|node| self.proto_array.node_is_viable_for_head(node))
+    }
+
     pub fn latest_message(&self, validator_index: usize) -> Option<(Hash256, Epoch)> {
         if validator_index < self.votes.0.len() {
             let vote = &self.votes.0[validator_index];
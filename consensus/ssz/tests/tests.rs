@@ -349,6 +349,217 @@ mod round_trip {
         round_trip(vec);
     }
 
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "union")]
+    enum ThreeVariantUnion {
+        None,
+        U16(u16),
+        List(Vec<u8>),
+    }
+
+    #[test]
+    fn three_variant_union_encoding() {
+        assert_eq!(ThreeVariantUnion::None.as_ssz_bytes(), vec![00, 00, 00, 00]);
+        assert_eq!(
+            ThreeVariantUnion::U16(42).as_ssz_bytes(),
+            vec![01, 00, 00, 00, 42, 00]
+        );
+        assert_eq!(
+            ThreeVariantUnion::List(vec![1, 2]).as_ssz_bytes(),
+            vec![02, 00, 00, 00, 01, 02]
+        );
+    }
+
+    #[test]
+    fn three_variant_union_invalid() {
+        assert!(ThreeVariantUnion::from_ssz_bytes(&[03, 00, 00, 00]).is_err());
+        assert!(ThreeVariantUnion::from_ssz_bytes(&[00, 00, 00, 00, 01]).is_err());
+        assert!(ThreeVariantUnion::from_ssz_bytes(&[01, 00, 00, 00, 01]).is_err());
+        assert!(ThreeVariantUnion::from_ssz_bytes(&[01, 00, 00]).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct UnionInContainer {
+        a: u8,
+        b: ThreeVariantUnion,
+        c: ThreeVariantUnion,
+    }
+
+    #[test]
+    fn union_round_trip() {
+        round_trip(vec![
+            ThreeVariantUnion::None,
+            ThreeVariantUnion::U16(0),
+            ThreeVariantUnion::U16(u16::max_value()),
+            ThreeVariantUnion::List(vec![]),
+            ThreeVariantUnion::List(vec![1, 2, 3]),
+        ]);
+
+        round_trip(vec![
+            UnionInContainer {
+                a: 42,
+                b: ThreeVariantUnion::None,
+                c: ThreeVariantUnion::List(vec![1]),
+            },
+            UnionInContainer {
+                a: 0,
+                b: ThreeVariantUnion::U16(7),
+                c: ThreeVariantUnion::None,
+            },
+        ]);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(struct_behaviour = "stable_container", max_fields = "4")]
+    struct StableContainer {
+        a: Option<u16>,
+        b: Option<Vec<u8>>,
+        c: Option<u8>,
+    }
+
+    /// An earlier version of `StableContainer`, prior to the addition of `c`.
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(struct_behaviour = "stable_container", max_fields = "4")]
+    struct StableContainerV1 {
+        a: Option<u16>,
+        b: Option<Vec<u8>>,
+    }
+
+    #[test]
+    fn stable_container_encoding() {
+        let s = StableContainer {
+            a: Some(42),
+            b: Some(vec![7]),
+            c: None,
+        };
+
+        // Offsets are relative to the start of the fields, after the `Bitvector`.
+        let bytes = vec![
+            //  1   2   3   4   5   6   7   8
+            //  | u16   | offset        | list
+            03, 42, 00, 06, 00, 00, 00, 07,
+        ];
+
+        assert_eq!(s.as_ssz_bytes(), bytes);
+
+        let s = StableContainer {
+            a: None,
+            b: None,
+            c: Some(5),
+        };
+
+        assert_eq!(s.as_ssz_bytes(), vec![04, 05]);
+    }
+
+    /// The active fields of `StableContainer { a: Some(_), b: Some(_), c: None }`.
+    #[derive(Debug, PartialEq, Encode)]
+    struct StableContainerActiveFields {
+        a: u16,
+        b: Vec<u8>,
+    }
+
+    #[test]
+    fn stable_container_fields_are_a_container() {
+        let s = StableContainer {
+            a: Some(42),
+            b: Some(vec![1, 2, 3]),
+            c: None,
+        };
+        let fields = StableContainerActiveFields {
+            a: 42,
+            b: vec![1, 2, 3],
+        };
+
+        assert_eq!(s.as_ssz_bytes()[1..], fields.as_ssz_bytes()[..]);
+    }
+
+    /// The `Shape` example from EIP-7495.
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(struct_behaviour = "stable_container", max_fields = "4")]
+    struct Shape {
+        side: Option<u16>,
+        color: Option<u8>,
+        radius: Option<u16>,
+    }
+
+    #[test]
+    fn stable_container_eip_7495_vectors() {
+        let square = Shape {
+            side: Some(0x42),
+            color: Some(1),
+            radius: None,
+        };
+        assert_eq!(square.as_ssz_bytes(), vec![0x03, 0x42, 0x00, 0x01]);
+        assert_eq!(Shape::from_ssz_bytes(&[0x03, 0x42, 0x00, 0x01]), Ok(square));
+
+        let circle = Shape {
+            side: None,
+            color: Some(1),
+            radius: Some(0x42),
+        };
+        assert_eq!(circle.as_ssz_bytes(), vec![0x06, 0x01, 0x42, 0x00]);
+        assert_eq!(Shape::from_ssz_bytes(&[0x06, 0x01, 0x42, 0x00]), Ok(circle));
+    }
+
+    #[test]
+    fn stable_container_decodes_earlier_version() {
+        let v1 = StableContainerV1 {
+            a: Some(42),
+            b: Some(vec![1, 2]),
+        };
+
+        assert_eq!(
+            StableContainer::from_ssz_bytes(&v1.as_ssz_bytes()),
+            Ok(StableContainer {
+                a: Some(42),
+                b: Some(vec![1, 2]),
+                c: None,
+            })
+        );
+
+        // Fields which are unknown to the earlier version cannot be ignored.
+        let s = StableContainer {
+            a: None,
+            b: None,
+            c: Some(5),
+        };
+        assert!(StableContainerV1::from_ssz_bytes(&s.as_ssz_bytes()).is_err());
+    }
+
+    #[test]
+    fn stable_container_invalid() {
+        assert!(StableContainer::from_ssz_bytes(&[]).is_err());
+        assert!(StableContainer::from_ssz_bytes(&[08]).is_err());
+        assert!(StableContainer::from_ssz_bytes(&[01, 42]).is_err());
+        assert!(StableContainer::from_ssz_bytes(&[00, 42]).is_err());
+    }
+
+    #[test]
+    fn stable_container_round_trip() {
+        round_trip(vec![
+            StableContainer {
+                a: None,
+                b: None,
+                c: None,
+            },
+            StableContainer {
+                a: Some(1),
+                b: None,
+                c: Some(2),
+            },
+            StableContainer {
+                a: None,
+                b: Some(vec![]),
+                c: None,
+            },
+            StableContainer {
+                a: Some(u16::max_value()),
+                b: Some(vec![1, 2, 3]),
+                c: Some(u8::max_value()),
+            },
+        ]);
+    }
+
     #[test]
     fn tuple_u8_u16() {
         let vec: Vec<(u8, u16)> = vec![
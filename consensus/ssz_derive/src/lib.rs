@@ -1,7 +1,7 @@
 #![recursion_limit = "256"]
 //! Provides procedural derive macros for the `Encode` and `Decode` traits of the `eth2_ssz` crate.
 //!
-//! Supports field attributes, see each derive macro for more information.
+//! Supports field and container attributes, see each derive macro for more information.
 
 extern crate proc_macro;
 
@@ -59,25 +59,180 @@ fn should_skip_serializing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `ssz::Encode` for some `struct`.
+/// Returns the value of the container attribute `#[ssz(<key> = "<value>")]`, if it is present.
+///
+/// # Panics
+/// A value which is not a string or integer literal will raise a panic at compile time.
+fn get_container_attr(item: &DeriveInput, key: &str) -> Option<String> {
+    item.attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("ssz"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+                if name_value.path.is_ident(key) =>
+            {
+                match name_value.lit {
+                    syn::Lit::Str(s) => Some(s.value()),
+                    syn::Lit::Int(i) => Some(i.base10_digits().to_string()),
+                    _ => panic!("ssz_derive expects #[ssz({} = \"..\")].", key),
+                }
+            }
+            _ => None,
+        })
+}
+
+/// Returns the number of fields that a stable container may ever have, as declared by the
+/// container attribute `#[ssz(max_fields = "N")]`.
+fn get_stable_container_max_fields(item: &DeriveInput, struct_data: &syn::DataStruct) -> usize {
+    let max_fields = get_container_attr(item, "max_fields")
+        .expect("ssz_derive stable containers require #[ssz(max_fields = \"N\")].")
+        .parse::<usize>()
+        .expect("ssz_derive expects max_fields to be an integer.");
+
+    if struct_data.fields.len() > max_fields {
+        panic!("ssz_derive stable container has more than max_fields fields.");
+    }
+
+    max_fields
+}
+
+/// Returns the ident and the `T` of each `Option<T>` field in a stable container.
+///
+/// # Panics
+/// Any unnamed field, or any field which is not an `Option<T>`, will raise a panic at compile
+/// time.
+fn get_stable_container_fields(struct_data: &syn::DataStruct) -> Vec<(&syn::Ident, &syn::Type)> {
+    struct_data
+        .fields
+        .iter()
+        .map(|f| {
+            let ident = f
+                .ident
+                .as_ref()
+                .expect("ssz_derive only supports named struct fields.");
+            let inner = option_inner_type(&f.ty)
+                .expect("ssz_derive stable container fields must be of type Option<T>.");
+            (ident, inner)
+        })
+        .collect()
+}
+
+/// Returns `T` if `ty` is an `Option<T>`.
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A variant of an enum which is encoded as an SSZ union.
+struct UnionVariant<'a> {
+    ident: &'a syn::Ident,
+    /// The type of the value held by the variant, or `None` for the unit variant.
+    ty: Option<&'a syn::Type>,
+}
+
+/// Returns the variants of an enum which is encoded as an SSZ union, in the order of their union
+/// index.
+///
+/// # Panics
+/// Any variant with named fields, with more than one unnamed field or with a discriminant will
+/// raise a panic at compile time. Only the first variant may be a unit variant (i.e., the SSZ
+/// `None` type).
+fn get_union_variants(enum_data: &syn::DataEnum) -> Vec<UnionVariant> {
+    if enum_data.variants.is_empty() {
+        panic!("ssz_derive unions must have at least one variant.");
+    }
+
+    enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            if variant.discriminant.is_some() {
+                panic!("ssz_derive union variants may not have discriminants.");
+            }
+
+            let ty = match &variant.fields {
+                syn::Fields::Unit if i == 0 => None,
+                syn::Fields::Unit => panic!("ssz_derive only supports a unit variant first."),
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    Some(&fields.unnamed[0].ty)
+                }
+                _ => panic!("ssz_derive union variants must have a single unnamed field."),
+            };
+
+            UnionVariant {
+                ident: &variant.ident,
+                ty,
+            }
+        })
+        .collect()
+}
+
+/// Returns the number of bytes in the `Bitvector[max_fields]` which prefixes a stable container.
+fn bitvector_len(max_fields: usize) -> usize {
+    (max_fields + 7) / 8
+}
+
+/// Implements `ssz::Encode` for some `struct` or `enum`.
 ///
 /// Fields are encoded in the order they are defined.
 ///
 /// ## Field attributes
 ///
 /// - `#[ssz(skip_serializing)]`: the field will not be serialized.
+///
+/// ## Container attributes
+///
+/// - `#[ssz(enum_behaviour = "union")]`: the `enum` is encoded as an SSZ union; a union index
+/// (the position of the variant) followed by the value held by the variant. Each variant must
+/// hold a single value, except for the first variant which may be a unit variant.
+/// - `#[ssz(struct_behaviour = "stable_container", max_fields = "N")]`: the `struct` is encoded as
+/// a stable container (EIP-7495); a `Bitvector[N]` of the fields which are present followed by a
+/// container of those fields, whose offsets are relative to the end of the `Bitvector`. Every field must be an `Option<T>`, so that fields added in newer spec versions may be absent.
 #[proc_macro_derive(Encode, attributes(ssz))]
 pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(s) => match get_container_attr(&item, "struct_behaviour").as_deref() {
+            None | Some("container") => ssz_encode_derive_struct(&item, s),
+            Some("stable_container") => ssz_encode_derive_stable_container(&item, s),
+            Some(other) => panic!("ssz_derive does not support struct_behaviour = {:?}", other),
+        },
+        syn::Data::Enum(e) => match get_container_attr(&item, "enum_behaviour").as_deref() {
+            Some("union") => ssz_encode_derive_enum_union(&item, e),
+            _ => panic!("ssz_derive only supports enums with #[ssz(enum_behaviour = \"union\")]."),
+        },
+        _ => panic!("ssz_derive only supports structs and enums."),
+    }
+}
+
+/// Implements `ssz::Encode` for a `struct` which is encoded as an SSZ container.
+fn ssz_encode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
-    };
-
     let field_idents = get_serializable_named_field_idents(&struct_data);
     let field_idents_a = get_serializable_named_field_idents(&struct_data);
     let field_types_a = get_serializable_field_types(&struct_data);
@@ -144,6 +299,134 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Implements `ssz::Encode` for a `struct` which is encoded as a stable container.
+fn ssz_encode_derive_stable_container(
+    item: &DeriveInput,
+    struct_data: &syn::DataStruct,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let bitvector_len = bitvector_len(get_stable_container_max_fields(item, struct_data));
+    let fields = get_stable_container_fields(struct_data);
+    let field_idents_a = fields.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let field_idents_b = field_idents_a.clone();
+    let field_idents_c = field_idents_a.clone();
+    let field_types_a = fields.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+    let field_types_b = field_types_a.clone();
+    let field_types_c = field_types_a.clone();
+    let field_indices = (0..fields.len()).collect::<Vec<_>>();
+
+    let output = quote! {
+        #[allow(clippy::integer_arithmetic)]
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                let mut len = #bitvector_len;
+                #(
+                    if let Some(ref field) = self.#field_idents_a {
+                        if <#field_types_a as ssz::Encode>::is_ssz_fixed_len() {
+                            len += <#field_types_b as ssz::Encode>::ssz_fixed_len();
+                        } else {
+                            len += ssz::BYTES_PER_LENGTH_OFFSET;
+                            len += field.ssz_bytes_len();
+                        }
+                    }
+                )*
+
+                len
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                let mut active_fields = [0_u8; #bitvector_len];
+                // The active fields are encoded as a container which follows the `Bitvector`, so
+                // offsets are relative to the end of the `Bitvector`.
+                let mut offset = 0;
+                #(
+                    if self.#field_idents_b.is_some() {
+                        active_fields[#field_indices / 8] |= 1 << (#field_indices % 8);
+                        offset += <#field_types_c as ssz::Encode>::ssz_fixed_len();
+                    }
+                )*
+
+                buf.extend_from_slice(&active_fields);
+
+                let mut encoder = ssz::SszEncoder::container(buf, offset);
+
+                #(
+                    if let Some(ref field) = self.#field_idents_c {
+                        encoder.append(field);
+                    }
+                )*
+
+                encoder.finalize();
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `ssz::Encode` for an `enum` which is encoded as an SSZ union.
+fn ssz_encode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut len_arms = vec![];
+    let mut append_arms = vec![];
+
+    for (union_index, variant) in get_union_variants(enum_data).into_iter().enumerate() {
+        let ident = variant.ident;
+
+        if variant.ty.is_some() {
+            len_arms.push(quote! {
+                #name::#ident(ref inner) => ssz::BYTES_PER_LENGTH_OFFSET + inner.ssz_bytes_len()
+            });
+            append_arms.push(quote! {
+                #name::#ident(ref inner) => {
+                    buf.extend_from_slice(&ssz::encode_union_index(#union_index));
+                    inner.ssz_append(buf);
+                }
+            });
+        } else {
+            len_arms.push(quote! {
+                #name::#ident => ssz::BYTES_PER_LENGTH_OFFSET
+            });
+            append_arms.push(quote! {
+                #name::#ident => buf.extend_from_slice(&ssz::encode_union_index(#union_index))
+            });
+        }
+    }
+
+    let output = quote! {
+        #[allow(clippy::integer_arithmetic)]
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        #len_arms,
+                    )*
+                }
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match self {
+                    #(
+                        #append_arms,
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
 /// Returns true if some field has an attribute declaring it should not be deserialized.
 ///
 /// The field attribute is: `#[ssz(skip_deserializing)]`
@@ -154,7 +437,7 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `ssz::Decode` for some `struct`.
+/// Implements `ssz::Decode` for some `struct` or `enum`.
 ///
 /// Fields are decoded in the order they are defined.
 ///
@@ -163,18 +446,34 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
 /// - `#[ssz(skip_deserializing)]`: during de-serialization the field will be instantiated from a
 /// `Default` implementation. The decoder will assume that the field was not serialized at all
 /// (e.g., if it has been serialized, an error will be raised instead of `Default` overriding it).
-#[proc_macro_derive(Decode)]
+///
+/// ## Container attributes
+///
+/// The same as for `Encode`. When decoding a stable container, absent fields are `None` and an
+/// error is raised if the `Bitvector` declares a field which is not known to the `struct`.
+#[proc_macro_derive(Decode, attributes(ssz))]
 pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(s) => match get_container_attr(&item, "struct_behaviour").as_deref() {
+            None | Some("container") => ssz_decode_derive_struct(&item, s),
+            Some("stable_container") => ssz_decode_derive_stable_container(&item, s),
+            Some(other) => panic!("ssz_derive does not support struct_behaviour = {:?}", other),
+        },
+        syn::Data::Enum(e) => match get_container_attr(&item, "enum_behaviour").as_deref() {
+            Some("union") => ssz_decode_derive_enum_union(&item, e),
+            _ => panic!("ssz_derive only supports enums with #[ssz(enum_behaviour = \"union\")]."),
+        },
+        _ => panic!("ssz_derive only supports structs and enums."),
+    }
+}
+
+/// Implements `ssz::Decode` for a `struct` which is encoded as an SSZ container.
+fn ssz_decode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
-    };
-
     let mut register_types = vec![];
     let mut field_names = vec![];
     let mut fixed_decodes = vec![];
@@ -308,3 +607,133 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     };
     output.into()
 }
+
+/// Implements `ssz::Decode` for a `struct` which is encoded as a stable container.
+fn ssz_decode_derive_stable_container(
+    item: &DeriveInput,
+    struct_data: &syn::DataStruct,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let bitvector_len = bitvector_len(get_stable_container_max_fields(item, struct_data));
+    let fields = get_stable_container_fields(struct_data);
+    let num_fields = fields.len();
+    let field_idents_a = fields.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let field_idents_b = field_idents_a.clone();
+    let field_idents_c = field_idents_a.clone();
+    let field_types = fields.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+    let field_indices_a = (0..num_fields).collect::<Vec<_>>();
+    let field_indices_b = field_indices_a.clone();
+
+    let output = quote! {
+        #[allow(clippy::integer_arithmetic)]
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                let active_fields = bytes.get(0..#bitvector_len).ok_or_else(|| {
+                    ssz::DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: #bitvector_len,
+                    }
+                })?;
+                let is_active = |i: usize| active_fields[i / 8] & (1 << (i % 8)) != 0;
+
+                if let Some(i) = (#num_fields..#bitvector_len * 8).find(|i| is_active(*i)) {
+                    return Err(ssz::DecodeError::BytesInvalid(format!(
+                        "{} has no field at index {}",
+                        stringify!(#name),
+                        i
+                    )));
+                }
+
+                // The active fields are decoded as a container which follows the `Bitvector`.
+                let mut builder = ssz::SszDecoderBuilder::new(&bytes[#bitvector_len..]);
+
+                #(
+                    if is_active(#field_indices_a) {
+                        builder.register_type::<#field_types>()?;
+                    }
+                )*
+
+                let mut decoder = builder.build()?;
+
+                #(
+                    let #field_idents_a = if is_active(#field_indices_b) {
+                        Some(decoder.decode_next()?)
+                    } else {
+                        None
+                    };
+                )*
+
+                Ok(Self {
+                    #(
+                        #field_idents_b: #field_idents_c,
+                    )*
+                })
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `ssz::Decode` for an `enum` which is encoded as an SSZ union.
+fn ssz_decode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut decode_arms = vec![];
+
+    for (union_index, variant) in get_union_variants(enum_data).into_iter().enumerate() {
+        let ident = variant.ident;
+
+        if let Some(ty) = variant.ty {
+            decode_arms.push(quote! {
+                #union_index => Ok(#name::#ident(<#ty as ssz::Decode>::from_ssz_bytes(value_bytes)?))
+            });
+        } else {
+            decode_arms.push(quote! {
+                #union_index if value_bytes.is_empty() => Ok(#name::#ident),
+                #union_index => Err(ssz::DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: ssz::BYTES_PER_LENGTH_OFFSET,
+                })
+            });
+        }
+    }
+
+    let output = quote! {
+        #[allow(clippy::integer_arithmetic)]
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                if bytes.len() < ssz::BYTES_PER_LENGTH_OFFSET {
+                    return Err(ssz::DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: ssz::BYTES_PER_LENGTH_OFFSET,
+                    });
+                }
+
+                let (index_bytes, value_bytes) = bytes.split_at(ssz::BYTES_PER_LENGTH_OFFSET);
+
+                match ssz::read_union_index(index_bytes)? {
+                    #(
+                        #decode_arms,
+                    )*
+                    index => Err(ssz::DecodeError::BytesInvalid(format!(
+                        "{} is not a valid union index for {}",
+                        index,
+                        stringify!(#name)
+                    ))),
+                }
+            }
+        }
+    };
+    output.into()
+}
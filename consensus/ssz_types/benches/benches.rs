@@ -0,0 +1,114 @@
+use criterion::Criterion;
+use criterion::{black_box, criterion_group, criterion_main, Benchmark};
+use ssz_types::{typenum::U2048, BitList};
+
+/// Equal to `MAX_VALIDATORS_PER_COMMITTEE`.
+type Bits = BitList<U2048>;
+
+const LEN: usize = 2048;
+
+/// Returns a bitfield of `LEN` bits with every `step`'th bit set, starting at `offset`.
+fn bitfield(offset: usize, step: usize) -> Bits {
+    let mut bitfield = Bits::with_capacity(LEN).unwrap();
+    for i in (offset..LEN).step_by(step) {
+        bitfield.set(i, true).unwrap();
+    }
+    bitfield
+}
+
+/// The per-bit equivalent of `Bits::intersects`.
+fn intersects_per_bit(a: &Bits, b: &Bits) -> bool {
+    a.iter().zip(b.iter()).any(|(a, b)| a && b)
+}
+
+/// The per-bit equivalent of `Bits::is_subset`.
+fn is_subset_per_bit(a: &Bits, b: &Bits) -> bool {
+    a.iter().zip(b.iter()).all(|(a, b)| !a || b)
+}
+
+/// The per-bit equivalent of `Bits::iter_set_bits`.
+fn set_bits_per_bit(a: &Bits) -> Vec<usize> {
+    a.iter()
+        .enumerate()
+        .filter(|(_, bit)| *bit)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn bitfields(c: &mut Criterion) {
+    let sparse = bitfield(1, 64);
+    let dense = bitfield(0, 2);
+    let full = bitfield(0, 1);
+
+    let (a, b) = (sparse.clone(), dense.clone());
+    c.bench(
+        "intersects",
+        Benchmark::new("per_bit", move |bench| {
+            bench.iter(|| black_box(intersects_per_bit(&a, &b)))
+        }),
+    );
+
+    let (a, b) = (sparse.clone(), dense.clone());
+    c.bench(
+        "intersects",
+        Benchmark::new("word", move |bench| {
+            bench.iter(|| black_box(a.intersects(&b)))
+        }),
+    );
+
+    let (a, b) = (dense.clone(), full.clone());
+    c.bench(
+        "is_subset",
+        Benchmark::new("per_bit", move |bench| {
+            bench.iter(|| black_box(is_subset_per_bit(&a, &b)))
+        }),
+    );
+
+    let (a, b) = (dense.clone(), full);
+    c.bench(
+        "is_subset",
+        Benchmark::new("word", move |bench| {
+            bench.iter(|| black_box(a.is_subset(&b)))
+        }),
+    );
+
+    let (a, b) = (sparse.clone(), dense.clone());
+    c.bench(
+        "union",
+        Benchmark::new("word", move |bench| bench.iter(|| black_box(a.union(&b)))),
+    );
+
+    let (a, b) = (sparse.clone(), dense.clone());
+    c.bench(
+        "intersection",
+        Benchmark::new("word", move |bench| {
+            bench.iter(|| black_box(a.intersection(&b)))
+        }),
+    );
+
+    let a = sparse.clone();
+    c.bench(
+        "set_bits",
+        Benchmark::new("per_bit", move |bench| {
+            bench.iter(|| black_box(set_bits_per_bit(&a)))
+        }),
+    );
+
+    let a = sparse;
+    c.bench(
+        "set_bits",
+        Benchmark::new("word", move |bench| {
+            bench.iter(|| black_box(a.iter_set_bits().collect::<Vec<_>>()))
+        }),
+    );
+
+    c.bench(
+        "num_set_bits",
+        Benchmark::new("word", move |bench| {
+            bench.iter(|| black_box(dense.num_set_bits()))
+        }),
+    );
+}
+
+criterion_group!(benches, bitfields);
+criterion_main!(benches);
@@ -8,6 +8,11 @@ use ssz::{Decode, Encode};
 use tree_hash::Hash256;
 use typenum::Unsigned;
 
+/// The number of bytes in each word of the word-at-a-time bitfield operations.
+const BYTES_PER_WORD: usize = 8;
+/// The number of bits in each word of the word-at-a-time bitfield operations.
+const BITS_PER_WORD: usize = BYTES_PER_WORD * 8;
+
 /// A marker trait applied to `Variable` and `Fixed` that defines the behaviour of a `Bitfield`.
 pub trait BitfieldBehaviour: Clone {}
 
@@ -204,12 +209,12 @@ impl<N: Unsigned + Clone> Bitfield<Variable<N>> {
     pub fn intersection(&self, other: &Self) -> Self {
         let min_len = std::cmp::min(self.len(), other.len());
         let mut result = Self::with_capacity(min_len).expect("min len always less than N");
-        // Bitwise-and the bytes together, starting from the left of each vector. This takes care
+        // Bitwise-and the words together, starting from the left of each vector. This takes care
         // of masking out any entries beyond `min_len` as well, assuming the bitfield doesn't
         // contain any set bits beyond its length.
-        for i in 0..result.bytes.len() {
-            result.bytes[i] = self.bytes[i] & other.bytes[i];
-        }
+        let num_bytes = result.bytes.len();
+        result.bytes.copy_from_slice(&self.bytes[..num_bytes]);
+        map_words_inplace(&mut result.bytes, &other.bytes, |a, b| a & b);
         result
     }
 
@@ -217,12 +222,13 @@ impl<N: Unsigned + Clone> Bitfield<Variable<N>> {
     ///
     /// Return a new BitList with length equal to the longer of the two inputs.
     pub fn union(&self, other: &Self) -> Self {
-        let max_len = std::cmp::max(self.len(), other.len());
-        let mut result = Self::with_capacity(max_len).expect("max len always less than N");
-        for i in 0..result.bytes.len() {
-            result.bytes[i] =
-                self.bytes.get(i).copied().unwrap_or(0) | other.bytes.get(i).copied().unwrap_or(0);
-        }
+        let (longer, shorter) = if self.len() >= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut result = longer.clone();
+        map_words_inplace(&mut result.bytes, &shorter.bytes, |a, b| a | b);
         result
     }
 }
@@ -267,6 +273,20 @@ impl<N: Unsigned + Clone> Bitfield<Fixed<N>> {
     pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
         Self::from_raw_bytes(bytes, Self::capacity())
     }
+
+    /// Compute the intersection of two BitVectors.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        map_words_inplace(&mut result.bytes, &other.bytes, |a, b| a & b);
+        result
+    }
+
+    /// Compute the union of two BitVectors.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        map_words_inplace(&mut result.bytes, &other.bytes, |a, b| a | b);
+        result
+    }
 }
 
 impl<N: Unsigned + Clone> Default for Bitfield<Fixed<N>> {
@@ -397,19 +417,48 @@ impl<T: BitfieldBehaviour> Bitfield<T> {
         }
     }
 
+    /// Returns an iterator across the indices of the bits that are set to `true`, in increasing
+    /// order.
+    ///
+    /// Unlike `self.iter()`, unset bits are skipped a word at a time.
+    pub fn iter_set_bits(&self) -> impl Iterator<Item = usize> + '_ {
+        words(&self.bytes)
+            .enumerate()
+            .flat_map(|(i, word)| SetBitIter {
+                word,
+                offset: i * BITS_PER_WORD,
+            })
+    }
+
     /// Returns true if no bits are set.
     pub fn is_zero(&self) -> bool {
-        self.bytes.iter().all(|byte| *byte == 0)
+        words(&self.bytes).all(|word| word == 0)
     }
 
     /// Returns the number of bits that are set to `true`.
     pub fn num_set_bits(&self) -> usize {
-        self.bytes
-            .iter()
-            .map(|byte| byte.count_ones() as usize)
+        words(&self.bytes)
+            .map(|word| word.count_ones() as usize)
             .sum()
     }
 
+    /// Returns true if some bit is set in both `self` and `other`, which may be of different
+    /// lengths.
+    ///
+    /// Equivalent to `!self.intersection(other).is_zero()`, without allocating.
+    pub fn intersects(&self, other: &Self) -> bool {
+        words(&self.bytes)
+            .zip(words(&other.bytes))
+            .any(|(a, b)| a & b != 0)
+    }
+
+    /// Returns true if every bit that is set in `self` is also set in `other`, which may be of a
+    /// different length.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let mut other_words = words(&other.bytes);
+        words(&self.bytes).all(|a| a & !other_words.next().unwrap_or(0) == 0)
+    }
+
     /// Compute the difference of this Bitfield and another of potentially different length.
     pub fn difference(&self, other: &Self) -> Self {
         let mut result = self.clone();
@@ -419,11 +468,7 @@ impl<T: BitfieldBehaviour> Bitfield<T> {
 
     /// Compute the difference of this Bitfield and another of potentially different length.
     pub fn difference_inplace(&mut self, other: &Self) {
-        let min_byte_len = std::cmp::min(self.bytes.len(), other.bytes.len());
-
-        for i in 0..min_byte_len {
-            self.bytes[i] &= !other.bytes[i];
-        }
+        map_words_inplace(&mut self.bytes, &other.bytes, |a, b| a & !b);
     }
 
     /// Shift the bits to higher indices, filling the lower indices with zeroes.
@@ -456,6 +501,50 @@ fn bytes_for_bit_len(bit_len: usize) -> usize {
     std::cmp::max(1, (bit_len + 7) / 8)
 }
 
+/// Returns an iterator across `bytes` as little-endian words, such that the `j`'th bit of the
+/// `i`'th word is the `i * BITS_PER_WORD + j`'th bit of the bitfield. The final word is padded
+/// with zeros.
+fn words(bytes: &[u8]) -> impl Iterator<Item = u64> + '_ {
+    bytes.chunks(BYTES_PER_WORD).map(|chunk| {
+        let mut word = [0; BYTES_PER_WORD];
+        word[..chunk.len()].copy_from_slice(chunk);
+        u64::from_le_bytes(word)
+    })
+}
+
+/// Replaces each word of `bytes` with `f(word, other_word)`, where `other_word` is the
+/// corresponding word of `other`.
+///
+/// Words of `bytes` beyond the end of `other` are left unchanged.
+fn map_words_inplace(bytes: &mut [u8], other: &[u8], f: impl Fn(u64, u64) -> u64) {
+    for (chunk, other_word) in bytes.chunks_mut(BYTES_PER_WORD).zip(words(other)) {
+        let word = words(chunk).next().unwrap_or(0);
+        let len = chunk.len();
+        chunk.copy_from_slice(&f(word, other_word).to_le_bytes()[..len]);
+    }
+}
+
+/// An iterator over the indices of the set bits in a single word of a `Bitfield`.
+struct SetBitIter {
+    word: u64,
+    offset: usize,
+}
+
+impl Iterator for SetBitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.word == 0 {
+            None
+        } else {
+            let i = self.word.trailing_zeros() as usize;
+            // Clear the lowest set bit.
+            self.word &= self.word - 1;
+            Some(self.offset + i)
+        }
+    }
+}
+
 /// An iterator over the bits in a `Bitfield`.
 pub struct BitIter<'a, T> {
     bitfield: &'a Bitfield<T>,
@@ -759,6 +848,24 @@ mod bitvector {
 
         assert!(BitVector4::from_ssz_bytes(&bad).is_err());
     }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = BitVector16::from_bytes(vec![0b1100, 0b0001]).unwrap();
+        let b = BitVector16::from_bytes(vec![0b1011, 0b1001]).unwrap();
+
+        assert_eq!(
+            a.intersection(&b),
+            BitVector16::from_bytes(vec![0b1000, 0b0001]).unwrap()
+        );
+        assert_eq!(
+            a.union(&b),
+            BitVector16::from_bytes(vec![0b1111, 0b1001]).unwrap()
+        );
+        assert!(a.intersects(&b));
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(!a.union(&b).is_subset(&a));
+    }
 }
 
 #[cfg(test)]
@@ -1204,6 +1311,57 @@ mod bitlist {
         );
     }
 
+    #[test]
+    fn iter_set_bits() {
+        let mut bitfield = BitList1024::with_capacity(1000).unwrap();
+        let set_bits = vec![0, 2, 8, 63, 64, 65, 511, 999];
+        for &i in &set_bits {
+            bitfield.set(i, true).unwrap();
+        }
+
+        assert_eq!(bitfield.iter_set_bits().collect::<Vec<_>>(), set_bits);
+        assert_eq!(bitfield.num_set_bits(), set_bits.len());
+        assert_eq!(
+            BitList1024::with_capacity(9)
+                .unwrap()
+                .iter_set_bits()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn intersects() {
+        let a = BitList1024::from_raw_bytes(vec![0b1100, 0b0001], 16).unwrap();
+        let b = BitList1024::from_raw_bytes(vec![0b0011, 0b1000], 16).unwrap();
+        let c = BitList1024::from_raw_bytes(vec![0b0000, 0b0001, 0b0000, 0, 0, 0, 0, 0, 1], 72)
+            .unwrap();
+
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+        assert!(a.intersects(&c));
+        assert!(c.intersects(&a));
+        assert!(!b.intersects(&c));
+        assert!(c.intersects(&c));
+        assert!(!BitList1024::with_capacity(72).unwrap().intersects(&c));
+    }
+
+    #[test]
+    fn is_subset() {
+        let a = BitList1024::from_raw_bytes(vec![0b1100, 0b0001], 16).unwrap();
+        let b = BitList1024::from_raw_bytes(vec![0b1000, 0b0001], 16).unwrap();
+        let c = BitList1024::from_raw_bytes(vec![0b1000, 0b0001, 0, 0, 0, 0, 0, 0, 1], 72).unwrap();
+        let empty = BitList1024::with_capacity(0).unwrap();
+
+        assert!(b.is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(a.is_subset(&a));
+        assert!(b.is_subset(&c));
+        assert!(!c.is_subset(&b));
+        assert!(empty.is_subset(&a));
+        assert!(!a.is_subset(&empty));
+    }
+
     #[test]
     fn ssz_bytes_len() {
         for i in 1..64 {
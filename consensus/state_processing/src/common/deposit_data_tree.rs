@@ -21,6 +21,11 @@ impl DepositDataTree {
         }
     }
 
+    /// Returns the number of leaves that have been added to the tree.
+    pub fn mix_in_length(&self) -> usize {
+        self.mix_in_length
+    }
+
     /// Returns 32 bytes representing the "mix in length" for the merkle root of this tree.
     fn length_bytes(&self) -> Vec<u8> {
         int_to_bytes32(self.mix_in_length as u64)
@@ -11,13 +11,10 @@ pub fn get_attesting_indices<T: EthSpec>(
         return Err(BeaconStateError::InvalidBitfield);
     }
 
-    let mut indices = Vec::with_capacity(bitlist.num_set_bits());
-
-    for (i, validator_index) in committee.iter().enumerate() {
-        if let Ok(true) = bitlist.get(i) {
-            indices.push(*validator_index)
-        }
-    }
+    let mut indices = bitlist
+        .iter_set_bits()
+        .filter_map(|i| committee.get(i).copied())
+        .collect::<Vec<_>>();
 
     indices.sort_unstable();
 
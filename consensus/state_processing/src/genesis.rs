@@ -1,5 +1,8 @@
-use super::per_block_processing::{errors::BlockProcessingError, process_deposit};
+use super::per_block_processing::{
+    errors::BlockProcessingError, process_deposit_with_signature_validity, verify_deposit_signature,
+};
 use crate::common::DepositDataTree;
+use rayon::prelude::*;
 use safe_arith::{ArithError, SafeArith};
 use tree_hash::TreeHash;
 use types::DEPOSIT_TREE_DEPTH;
@@ -29,12 +32,24 @@ pub fn initialize_beacon_state_from_eth1<T: EthSpec>(
 
     let mut deposit_tree = DepositDataTree::create(&[], 0, DEPOSIT_TREE_DEPTH);
 
-    for deposit in deposits.iter() {
+    // The deposit signatures do not depend upon the state, so verify them all in parallel.
+    let signatures_valid = deposits
+        .par_iter()
+        .map(|deposit| verify_deposit_signature(&deposit.data, spec).is_ok())
+        .collect::<Vec<_>>();
+
+    for (deposit, signature_is_valid) in deposits.iter().zip(signatures_valid) {
         deposit_tree
             .push_leaf(deposit.data.tree_hash_root())
             .map_err(BlockProcessingError::MerkleTreeError)?;
         state.eth1_data.deposit_root = deposit_tree.root();
-        process_deposit(&mut state, &deposit, spec, true)?;
+        process_deposit_with_signature_validity(
+            &mut state,
+            &deposit,
+            spec,
+            true,
+            Some(signature_is_valid),
+        )?;
     }
 
     process_activations(&mut state, spec)?;
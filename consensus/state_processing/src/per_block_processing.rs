@@ -409,23 +409,36 @@ pub fn process_deposits<T: EthSpec>(
         }
     );
 
-    // Verify merkle proofs in parallel.
-    deposits
+    // Verify merkle proofs and signatures in parallel.
+    //
+    // The signature of a deposit for an existing validator does not need to be verified, however
+    // verifying every signature here is cheaper than verifying the signatures of new validators
+    // in series below.
+    let signatures_valid = deposits
         .par_iter()
         .enumerate()
-        .try_for_each(|(i, deposit)| {
+        .map(|(i, deposit)| {
             verify_deposit_merkle_proof(
                 state,
                 deposit,
                 state.eth1_deposit_index.safe_add(i as u64)?,
                 spec,
             )
-            .map_err(|e| e.into_with_index(i))
-        })?;
+            .map_err(|e| e.into_with_index(i))?;
+
+            Ok(verify_deposit_signature(&deposit.data, spec).is_ok())
+        })
+        .collect::<Result<Vec<_>, BlockProcessingError>>()?;
 
     // Update the state in series.
-    for deposit in deposits {
-        process_deposit(state, deposit, spec, false)?;
+    for (deposit, signature_is_valid) in deposits.iter().zip(signatures_valid) {
+        process_deposit_with_signature_validity(
+            state,
+            deposit,
+            spec,
+            false,
+            Some(signature_is_valid),
+        )?;
     }
 
     Ok(())
@@ -439,6 +452,22 @@ pub fn process_deposit<T: EthSpec>(
     deposit: &Deposit,
     spec: &ChainSpec,
     verify_merkle_proof: bool,
+) -> Result<(), BlockProcessingError> {
+    process_deposit_with_signature_validity(state, deposit, spec, verify_merkle_proof, None)
+}
+
+/// Process a single deposit, optionally verifying its merkle proof.
+///
+/// If `signature_is_valid` is `Some`, the deposit signature has already been verified elsewhere
+/// (e.g., in parallel with other deposits) and is not verified again.
+///
+/// Spec v0.12.1
+pub fn process_deposit_with_signature_validity<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    deposit: &Deposit,
+    spec: &ChainSpec,
+    verify_merkle_proof: bool,
+    signature_is_valid: Option<bool>,
 ) -> Result<(), BlockProcessingError> {
     let deposit_index = state.eth1_deposit_index as usize;
     if verify_merkle_proof {
@@ -469,7 +498,9 @@ pub fn process_deposit<T: EthSpec>(
     } else {
         // The signature should be checked for new validators. Return early for a bad
         // signature.
-        if verify_deposit_signature(&deposit.data, spec).is_err() {
+        let signature_is_valid = signature_is_valid
+            .unwrap_or_else(|| verify_deposit_signature(&deposit.data, spec).is_ok());
+        if !signature_is_valid {
             return Ok(());
         }
 
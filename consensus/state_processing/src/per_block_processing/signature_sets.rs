@@ -8,10 +8,10 @@ use std::borrow::Cow;
 use std::convert::TryInto;
 use tree_hash::TreeHash;
 use types::{
-    AggregateSignature, AttesterSlashing, BeaconBlock, BeaconState, BeaconStateError, ChainSpec,
-    DepositData, Domain, EthSpec, Fork, Hash256, IndexedAttestation, ProposerSlashing, PublicKey,
-    Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedBeaconBlockHeader, SignedRoot,
-    SignedVoluntaryExit, SigningData,
+    AggregatePublicKey, AggregateSignature, AttesterSlashing, BeaconBlock, BeaconState,
+    BeaconStateError, ChainSpec, DepositData, Domain, EthSpec, Fork, Hash256, IndexedAttestation,
+    ProposerSlashing, PublicKey, Signature, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedBeaconBlockHeader, SignedRoot, SignedVoluntaryExit, SigningData,
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -84,11 +84,42 @@ where
         });
     }
 
+    block_proposal_signature_set_from_parts(
+        signed_block,
+        block_root,
+        proposer_index as u64,
+        &state.fork,
+        state.genesis_validators_root,
+        get_pubkey,
+        spec,
+    )
+}
+
+/// A signature set that is valid if the block was signed by `proposer_index`.
+///
+/// Unlike `block_proposal_signature_set`, this function does not check that `proposer_index` is
+/// the expected proposer for the block's slot. It is the caller's responsibility to ensure the
+/// `proposer_index` is correct (e.g., because the block is known to be canonical).
+pub fn block_proposal_signature_set_from_parts<'a, T, F>(
+    signed_block: &'a SignedBeaconBlock<T>,
+    block_root: Option<Hash256>,
+    proposer_index: u64,
+    fork: &Fork,
+    genesis_validators_root: Hash256,
+    get_pubkey: F,
+    spec: &'a ChainSpec,
+) -> Result<SignatureSet>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let block = &signed_block.message;
+
     let domain = spec.get_domain(
         block.slot.epoch(T::slots_per_epoch()),
         Domain::BeaconProposer,
-        &state.fork,
-        state.genesis_validators_root,
+        fork,
+        genesis_validators_root,
     );
 
     let message = if let Some(root) = block_root {
@@ -103,7 +134,8 @@ where
 
     Ok(SignatureSet::single(
         &signed_block.signature,
-        get_pubkey(proposer_index).ok_or_else(|| Error::ValidatorUnknown(proposer_index as u64))?,
+        get_pubkey(proposer_index as usize)
+            .ok_or_else(|| Error::ValidatorUnknown(proposer_index))?,
         message.as_bytes().to_vec(),
     ))
 }
@@ -265,6 +297,29 @@ where
     Ok(SignatureSet::new(signature, pubkeys, message))
 }
 
+/// Returns the signature set for the given `indexed_attestation`, where `aggregate_pubkey` is the
+/// aggregate of the public keys of all of its attesting indices.
+pub fn indexed_attestation_signature_set_from_aggregate_pubkey<T: EthSpec>(
+    aggregate_pubkey: &AggregatePublicKey,
+    signature: &AggregateSignature,
+    indexed_attestation: &IndexedAttestation<T>,
+    fork: &Fork,
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> SignatureSet {
+    let domain = spec.get_domain(
+        indexed_attestation.data.target.epoch,
+        Domain::BeaconAttester,
+        &fork,
+        genesis_validators_root,
+    );
+
+    let message = indexed_attestation.data.signing_root(domain);
+    let message = message.as_bytes().to_vec();
+
+    SignatureSet::from_aggregate_public_key(signature, aggregate_pubkey, message)
+}
+
 /// Returns the signature set for the given `attester_slashing` and corresponding `pubkeys`.
 pub fn attester_slashing_signature_sets<'a, T, F>(
     state: &'a BeaconState<T>,
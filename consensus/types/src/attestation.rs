@@ -31,9 +31,7 @@ pub struct Attestation<T: EthSpec> {
 impl<T: EthSpec> Attestation<T> {
     /// Are the aggregation bitfields of these attestations disjoint?
     pub fn signers_disjoint_from(&self, other: &Self) -> bool {
-        self.aggregation_bits
-            .intersection(&other.aggregation_bits)
-            .is_zero()
+        !self.aggregation_bits.intersects(&other.aggregation_bits)
     }
 
     /// Aggregate another Attestation into this one.
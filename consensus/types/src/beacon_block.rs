@@ -1,8 +1,10 @@
+use crate::fork_name::read_slot_at_offset;
 use crate::test_utils::TestRandom;
 use crate::*;
 use bls::Signature;
 
 use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
@@ -208,9 +210,64 @@ impl<T: EthSpec> BeaconBlock<T> {
     }
 }
 
+impl<T: EthSpec> ForkVersionDecode for BeaconBlock<T> {
+    fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError> {
+        match fork_name {
+            ForkName::Base => Self::from_ssz_bytes(bytes),
+        }
+    }
+}
+
+fork_versioned_container! {
+    /// A `BeaconBlock` as defined by any fork.
+    pub enum VersionedBeaconBlock<T> {
+        Base(BeaconBlock<T>),
+    }
+    common_fields {
+        slot: Slot,
+        proposer_index: u64,
+        parent_root: Hash256,
+        state_root: Hash256,
+    }
+}
+
+impl<T: EthSpec> VersionedBeaconBlock<T> {
+    /// SSZ decode a block, using the fork in effect at the slot of the block to determine its
+    /// definition.
+    pub fn from_ssz_bytes_with_spec(bytes: &[u8], spec: &ChainSpec) -> Result<Self, DecodeError> {
+        // The `slot` is the first field of the block.
+        let slot = read_slot_at_offset(bytes, 0)?;
+
+        Self::from_ssz_bytes_by_fork(bytes, spec.fork_name_at_slot::<T>(slot))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
+    use ssz::Encode;
 
     ssz_and_tree_hash_tests!(BeaconBlock<MainnetEthSpec>);
+
+    #[test]
+    fn versioned_decode_with_spec() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let spec = MainnetEthSpec::default_spec();
+        let block = BeaconBlock::<MainnetEthSpec>::random_for_test(&mut rng);
+        let bytes = block.as_ssz_bytes();
+
+        let versioned = VersionedBeaconBlock::from_ssz_bytes_with_spec(&bytes, &spec)
+            .expect("should decode block");
+        assert_eq!(versioned, VersionedBeaconBlock::Base(block.clone()));
+        assert_eq!(versioned.fork_name(), ForkName::Base);
+        assert_eq!(*versioned.slot(), block.slot);
+        assert_eq!(*versioned.parent_root(), block.parent_root);
+        assert_eq!(versioned.as_ssz_bytes(), bytes);
+
+        assert!(
+            VersionedBeaconBlock::<MainnetEthSpec>::from_ssz_bytes_with_spec(&bytes[..7], &spec)
+                .is_err()
+        );
+    }
 }
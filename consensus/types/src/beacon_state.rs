@@ -1,5 +1,6 @@
 use self::committee_cache::get_active_validator_indices;
 use self::exit_cache::ExitCache;
+use crate::fork_name::read_slot_at_offset;
 use crate::test_utils::TestRandom;
 use crate::*;
 
@@ -7,10 +8,11 @@ use cached_tree_hash::{CacheArena, CachedTreeHash};
 use compare_fields_derive::CompareFields;
 use eth2_hashing::hash;
 use int_to_bytes::{int_to_bytes4, int_to_bytes8};
+use merkle_proof::MerkleTree;
 use pubkey_cache::PubkeyCache;
 use safe_arith::{ArithError, SafeArith};
 use serde_derive::{Deserialize, Serialize};
-use ssz::ssz_encode;
+use ssz::{ssz_encode, Decode, DecodeError};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum::Unsigned, BitVector, FixedVector};
 use std::convert::TryInto;
@@ -85,6 +87,7 @@ pub enum Error {
     ArithError(ArithError),
     MissingBeaconBlock(SignedBeaconBlockHash),
     MissingBeaconState(BeaconStateHash),
+    MerkleTreeError(merkle_proof::MerkleTreeError),
 }
 
 /// Control whether an epoch-indexed field can be indexed at the next epoch or not.
@@ -221,6 +224,14 @@ where
 }
 
 impl<T: EthSpec> BeaconState<T> {
+    /// SSZ decode a state, using the fork in effect at the slot of the state to determine its
+    /// definition.
+    pub fn from_ssz_bytes_with_spec(bytes: &[u8], spec: &ChainSpec) -> Result<Self, DecodeError> {
+        let slot = read_state_slot(bytes)?;
+
+        Self::from_ssz_bytes_by_fork(bytes, spec.fork_name_at_slot::<T>(slot))
+    }
+
     /// Create a new BeaconState suitable for genesis.
     ///
     /// Not a complete genesis state, see `initialize_beacon_state_from_eth1`.
@@ -1062,6 +1073,35 @@ impl<T: EthSpec> BeaconState<T> {
         }
     }
 
+    /// Returns the nodes at each of `gindices` in the Merkle tree of the state, and a Merkle
+    /// multiproof of their inclusion in the tree hash root of the state.
+    ///
+    /// Only the nodes above (and including) the roots of the fields of the state may be proven,
+    /// e.g., the root of `self.finalized_checkpoint` but not its `epoch`. The proof contains the
+    /// nodes at the generalized indices returned by `merkle_proof::get_helper_indices`.
+    ///
+    /// Initialize the tree hash cache if it isn't already initialized.
+    pub fn compute_merkle_multiproof(
+        &mut self,
+        gindices: &[u64],
+    ) -> Result<(Vec<Hash256>, Vec<Hash256>), Error> {
+        self.initialize_tree_hash_cache();
+
+        let cache = self.tree_hash_cache.take();
+
+        if let Some(mut cache) = cache {
+            let field_roots = cache.recalculate_field_roots(&self)?;
+            self.tree_hash_cache = Some(cache);
+
+            let depth = BeaconTreeHashCache::tree_depth();
+            MerkleTree::create(&field_roots, depth)
+                .generate_multiproof(gindices, depth)
+                .map_err(Into::into)
+        } else {
+            Err(Error::TreeHashCacheNotInitialized)
+        }
+    }
+
     /// Completely drops the tree hash cache, replacing it with a new, empty cache.
     pub fn drop_tree_hash_cache(&mut self) {
         self.tree_hash_cache = None;
@@ -1123,6 +1163,52 @@ impl<T: EthSpec> BeaconState<T> {
     }
 }
 
+impl<T: EthSpec> ForkVersionDecode for BeaconState<T> {
+    fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError> {
+        match fork_name {
+            ForkName::Base => Self::from_ssz_bytes(bytes),
+        }
+    }
+}
+
+fork_versioned_container! {
+    /// A `BeaconState` as defined by any fork.
+    pub enum VersionedBeaconState<T> {
+        Base(BeaconState<T>),
+    }
+    common_fields {
+        genesis_time: u64,
+        genesis_validators_root: Hash256,
+        slot: Slot,
+        fork: Fork,
+        latest_block_header: BeaconBlockHeader,
+        eth1_data: Eth1Data,
+        eth1_deposit_index: u64,
+        validators: VariableList<Validator, T::ValidatorRegistryLimit>,
+        balances: VariableList<u64, T::ValidatorRegistryLimit>,
+        previous_justified_checkpoint: Checkpoint,
+        current_justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+    }
+}
+
+impl<T: EthSpec> VersionedBeaconState<T> {
+    /// SSZ decode a state, using the fork in effect at the slot of the state to determine its
+    /// definition.
+    pub fn from_ssz_bytes_with_spec(bytes: &[u8], spec: &ChainSpec) -> Result<Self, DecodeError> {
+        let slot = read_state_slot(bytes)?;
+
+        Self::from_ssz_bytes_by_fork(bytes, spec.fork_name_at_slot::<T>(slot))
+    }
+}
+
+/// Reads the `slot` of the SSZ encoded state in `bytes`.
+fn read_state_slot(bytes: &[u8]) -> Result<Slot, DecodeError> {
+    // The `slot` follows the fixed-length `genesis_time` and `genesis_validators_root`.
+    let slot_offset = <u64 as Decode>::ssz_fixed_len() + <Hash256 as Decode>::ssz_fixed_len();
+    read_slot_at_offset(bytes, slot_offset)
+}
+
 /// This implementation primarily exists to satisfy some testing requirements (ef_tests). It is
 /// recommended to use the methods directly on the beacon state instead.
 impl<T: EthSpec> CachedTreeHash<BeaconTreeHashCache> for BeaconState<T> {
@@ -1165,6 +1251,12 @@ impl From<tree_hash::Error> for Error {
     }
 }
 
+impl From<merkle_proof::MerkleTreeError> for Error {
+    fn from(e: merkle_proof::MerkleTreeError) -> Error {
+        Error::MerkleTreeError(e)
+    }
+}
+
 impl From<ArithError> for Error {
     fn from(e: ArithError) -> Error {
         Error::ArithError(e)
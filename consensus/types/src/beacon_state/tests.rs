@@ -213,6 +213,64 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn versioned_decode_with_spec() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use ssz::Encode;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+    let spec = MainnetEthSpec::default_spec();
+    let state: FoundationBeaconState = BeaconState::random_for_test(&mut rng);
+    let bytes = state.as_ssz_bytes();
+
+    let versioned =
+        VersionedBeaconState::from_ssz_bytes_with_spec(&bytes, &spec).expect("should decode state");
+    assert_eq!(versioned.fork_name(), ForkName::Base);
+    assert_eq!(*versioned.slot(), state.slot);
+    assert_eq!(versioned.validators(), &state.validators);
+    assert_eq!(
+        versioned.finalized_checkpoint(),
+        &state.finalized_checkpoint
+    );
+    assert_eq!(versioned.as_ssz_bytes(), bytes);
+    assert_eq!(
+        BeaconState::from_ssz_bytes_with_spec(&bytes, &spec),
+        Ok(state)
+    );
+}
+
+#[test]
+fn merkle_multiproof() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use merkle_proof::{verify_merkle_multiproof, MerkleTreeError};
+    use tree_hash::TreeHash;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: FoundationBeaconState = BeaconState::random_for_test(&mut rng);
+    let root = state.tree_hash_root();
+
+    // The `slot` and `finalized_checkpoint` fields in a tree of depth 5.
+    let gindices = [32 + 2, 32 + 20];
+    let (leaves, proof) = state.compute_merkle_multiproof(&gindices).unwrap();
+
+    assert_eq!(
+        leaves,
+        vec![
+            state.slot.tree_hash_root(),
+            state.finalized_checkpoint.tree_hash_root()
+        ]
+    );
+    assert!(verify_merkle_multiproof(&leaves, &proof, &gindices, root));
+
+    assert_eq!(
+        state.compute_merkle_multiproof(&[64]),
+        Err(Error::MerkleTreeError(
+            MerkleTreeError::InvalidGeneralizedIndex(64)
+        ))
+    );
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
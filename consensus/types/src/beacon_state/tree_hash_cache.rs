@@ -83,78 +83,79 @@ impl BeaconTreeHashCache {
     ) -> Result<Hash256, Error> {
         let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
 
-        hasher.write(state.genesis_time.tree_hash_root().as_bytes())?;
-        hasher.write(state.genesis_validators_root.tree_hash_root().as_bytes())?;
-        hasher.write(state.slot.tree_hash_root().as_bytes())?;
-        hasher.write(state.fork.tree_hash_root().as_bytes())?;
-        hasher.write(state.latest_block_header.tree_hash_root().as_bytes())?;
-        hasher.write(
+        for root in self.recalculate_field_roots(state)? {
+            hasher.write(root.as_bytes())?;
+        }
+
+        hasher.finish().map_err(Into::into)
+    }
+
+    /// Updates the cache and returns the tree hash root of each of the fields of the given
+    /// `state`, in order. These are the leaves of the Merkle tree of the `state`.
+    ///
+    /// The provided `state` should be a descendant of the last `state` given to this function, or
+    /// the `Self::new` function.
+    pub fn recalculate_field_roots<T: EthSpec>(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<Vec<Hash256>, Error> {
+        let mut roots = Vec::with_capacity(NUM_BEACON_STATE_HASHING_FIELDS);
+
+        roots.push(state.genesis_time.tree_hash_root());
+        roots.push(state.genesis_validators_root.tree_hash_root());
+        roots.push(state.slot.tree_hash_root());
+        roots.push(state.fork.tree_hash_root());
+        roots.push(state.latest_block_header.tree_hash_root());
+        roots.push(
             state
                 .block_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?,
+        );
+        roots.push(
             state
                 .state_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?,
+        );
+        roots.push(
             state
                 .historical_roots
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_data.tree_hash_root().as_bytes())?;
-        hasher.write(state.eth1_data_votes.tree_hash_root().as_bytes())?;
-        hasher.write(state.eth1_deposit_index.tree_hash_root().as_bytes())?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?,
+        );
+        roots.push(state.eth1_data.tree_hash_root());
+        roots.push(state.eth1_data_votes.tree_hash_root());
+        roots.push(state.eth1_deposit_index.tree_hash_root());
+        roots.push(
             self.validators
-                .recalculate_tree_hash_root(&state.validators[..])?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&state.validators[..])?,
+        );
+        roots.push(
             state
                 .balances
-                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?,
+        );
+        roots.push(
             state
                 .randao_mixes
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?
-                .as_bytes(),
-        )?;
-        hasher.write(
+                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?,
+        );
+        roots.push(
             state
                 .slashings
-                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .previous_epoch_attestations
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.current_epoch_attestations.tree_hash_root().as_bytes())?;
-        hasher.write(state.justification_bits.tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .previous_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .current_justified_checkpoint
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.finalized_checkpoint.tree_hash_root().as_bytes())?;
+                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?,
+        );
+        roots.push(state.previous_epoch_attestations.tree_hash_root());
+        roots.push(state.current_epoch_attestations.tree_hash_root());
+        roots.push(state.justification_bits.tree_hash_root());
+        roots.push(state.previous_justified_checkpoint.tree_hash_root());
+        roots.push(state.current_justified_checkpoint.tree_hash_root());
+        roots.push(state.finalized_checkpoint.tree_hash_root());
+
+        Ok(roots)
+    }
 
-        hasher.finish().map_err(Into::into)
+    /// The depth of the Merkle tree of a `BeaconState`, which has a leaf for each field.
+    pub fn tree_depth() -> usize {
+        int_log(NUM_BEACON_STATE_HASHING_FIELDS)
     }
 
     /// Updates the cache and provides the root of the given `validators`.
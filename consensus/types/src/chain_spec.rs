@@ -3,6 +3,7 @@ use int_to_bytes::int_to_bytes4;
 use serde_derive::{Deserialize, Serialize};
 use std::fs::File;
 use std::path::Path;
+use std::time::Duration;
 use tree_hash::TreeHash;
 use utils::{
     fork_from_hex_str, fork_to_hex_str, u32_from_hex_str, u32_to_hex_str, u8_from_hex_str,
@@ -129,6 +130,11 @@ pub struct ChainSpec {
 }
 
 impl ChainSpec {
+    /// Returns the accepted clock drift for nodes gossiping blocks and attestations.
+    pub fn maximum_gossip_clock_disparity(&self) -> Duration {
+        Duration::from_millis(self.maximum_gossip_clock_disparity_millis)
+    }
+
     /// Returns an `EnrForkId` for the given `slot`.
     ///
     /// Presently, we don't have any forks so we just ignore the slot. In the future this function
@@ -144,6 +150,18 @@ impl ChainSpec {
         }
     }
 
+    /// Returns the `Fork` which was in effect at the given `epoch`.
+    ///
+    /// Presently, we don't have any forks so we just ignore the epoch and return the genesis
+    /// fork. Unlike `state.fork`, this remains correct for epochs prior to the fork before last.
+    pub fn fork_at_epoch<T: EthSpec>(&self, _epoch: Epoch) -> Fork {
+        Fork {
+            previous_version: self.genesis_fork_version,
+            current_version: self.genesis_fork_version,
+            epoch: T::genesis_epoch(),
+        }
+    }
+
     /// Returns the epoch of the next scheduled change in the `fork.current_version`.
     ///
     /// There are no future forks scheduled so this function always returns `None`. This may not
@@ -435,6 +453,16 @@ mod tests {
             &spec,
         );
     }
+
+    #[test]
+    fn test_fork_at_epoch() {
+        let spec = ChainSpec::mainnet();
+
+        for epoch in vec![Epoch::new(0), Epoch::new(1), spec.far_future_epoch - 1] {
+            let fork = spec.fork_at_epoch::<MainnetEthSpec>(epoch);
+            assert_eq!(fork.get_fork_version(epoch), spec.genesis_fork_version);
+        }
+    }
 }
 
 /// Union of a ChainSpec struct and an EthSpec struct that holds constants used for the configs
@@ -551,6 +579,12 @@ pub struct YamlConfig {
     random_subnets_per_validator: u64,
     epochs_per_random_subnet_subscription: u64,
     seconds_per_eth1_block: u64,
+
+    // Networking
+    attestation_subnet_count: u64,
+    attestation_propagation_slot_range: u64,
+    /// In milliseconds.
+    maximum_gossip_clock_disparity: u64,
 }
 
 impl Default for YamlConfig {
@@ -631,6 +665,11 @@ impl YamlConfig {
             random_subnets_per_validator: spec.random_subnets_per_validator,
             epochs_per_random_subnet_subscription: spec.epochs_per_random_subnet_subscription,
             seconds_per_eth1_block: spec.seconds_per_eth1_block,
+
+            // Networking
+            attestation_subnet_count: spec.attestation_subnet_count,
+            attestation_propagation_slot_range: spec.attestation_propagation_slot_range,
+            maximum_gossip_clock_disparity: spec.maximum_gossip_clock_disparity_millis,
         }
     }
 
@@ -641,27 +680,176 @@ impl YamlConfig {
             .map_err(|e| format!("Error parsing spec at {}: {:?}", filename.display(), e))
     }
 
-    pub fn apply_to_chain_spec<T: EthSpec>(&self, chain_spec: &ChainSpec) -> Option<ChainSpec> {
-        // Checking for EthSpec constants
-        if self.justification_bits_length != T::JustificationBitsLength::to_u32()
-            || self.max_validators_per_committee != T::MaxValidatorsPerCommittee::to_u32()
-            || self.genesis_epoch != T::genesis_epoch()
-            || self.slots_per_epoch != T::slots_per_epoch()
-            || self.epochs_per_eth1_voting_period != T::EpochsPerEth1VotingPeriod::to_u64()
-            || self.slots_per_historical_root != T::slots_per_historical_root()
-            || self.epochs_per_historical_vector != T::epochs_per_historical_vector()
-            || self.epochs_per_slashings_vector != T::EpochsPerSlashingsVector::to_usize()
-            || self.historical_roots_limit != T::HistoricalRootsLimit::to_u64()
-            || self.validator_registry_limit != T::ValidatorRegistryLimit::to_u64()
-            || self.max_proposer_slashings != T::MaxProposerSlashings::to_u32()
-            || self.max_attester_slashings != T::MaxAttesterSlashings::to_u32()
-            || self.max_attestations != T::MaxAttestations::to_u32()
-            || self.max_deposits != T::MaxDeposits::to_u32()
-            || self.max_voluntary_exits != T::MaxVoluntaryExits::to_u32()
-        {
-            return None;
+    /// Returns an error describing the first value of the config which is incompatible with the
+    /// `T` preset, or which could not be used to run a chain.
+    ///
+    /// The preset values (e.g., `SLOTS_PER_EPOCH`) determine the SSZ types of the chain and
+    /// cannot be changed at runtime. All other values are copied into the `ChainSpec` by
+    /// `Self::apply_to_chain_spec`.
+    pub fn validate<T: EthSpec>(&self) -> Result<(), String> {
+        let preset_values = [
+            (
+                "JUSTIFICATION_BITS_LENGTH",
+                u64::from(self.justification_bits_length),
+                T::JustificationBitsLength::to_u64(),
+            ),
+            (
+                "MAX_VALIDATORS_PER_COMMITTEE",
+                u64::from(self.max_validators_per_committee),
+                T::MaxValidatorsPerCommittee::to_u64(),
+            ),
+            (
+                "GENESIS_EPOCH",
+                self.genesis_epoch.as_u64(),
+                T::genesis_epoch().as_u64(),
+            ),
+            (
+                "SLOTS_PER_EPOCH",
+                self.slots_per_epoch,
+                T::slots_per_epoch(),
+            ),
+            (
+                "EPOCHS_PER_ETH1_VOTING_PERIOD",
+                self.epochs_per_eth1_voting_period,
+                T::EpochsPerEth1VotingPeriod::to_u64(),
+            ),
+            (
+                "SLOTS_PER_HISTORICAL_ROOT",
+                self.slots_per_historical_root as u64,
+                T::SlotsPerHistoricalRoot::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_HISTORICAL_VECTOR",
+                self.epochs_per_historical_vector as u64,
+                T::EpochsPerHistoricalVector::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_SLASHINGS_VECTOR",
+                self.epochs_per_slashings_vector as u64,
+                T::EpochsPerSlashingsVector::to_u64(),
+            ),
+            (
+                "HISTORICAL_ROOTS_LIMIT",
+                self.historical_roots_limit,
+                T::HistoricalRootsLimit::to_u64(),
+            ),
+            (
+                "VALIDATOR_REGISTRY_LIMIT",
+                self.validator_registry_limit,
+                T::ValidatorRegistryLimit::to_u64(),
+            ),
+            (
+                "MAX_PROPOSER_SLASHINGS",
+                u64::from(self.max_proposer_slashings),
+                T::MaxProposerSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTER_SLASHINGS",
+                u64::from(self.max_attester_slashings),
+                T::MaxAttesterSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTATIONS",
+                u64::from(self.max_attestations),
+                T::MaxAttestations::to_u64(),
+            ),
+            (
+                "MAX_DEPOSITS",
+                u64::from(self.max_deposits),
+                T::MaxDeposits::to_u64(),
+            ),
+            (
+                "MAX_VOLUNTARY_EXITS",
+                u64::from(self.max_voluntary_exits),
+                T::MaxVoluntaryExits::to_u64(),
+            ),
+            (
+                "ATTESTATION_SUBNET_COUNT",
+                self.attestation_subnet_count,
+                T::SubnetBitfieldLength::to_u64(),
+            ),
+        ];
+
+        for (name, value, preset_value) in preset_values.iter() {
+            if value != preset_value {
+                return Err(format!(
+                    "{} is {} but must be {} for the {} preset",
+                    name,
+                    value,
+                    preset_value,
+                    T::spec_name()
+                ));
+            }
         }
 
+        // Values which are used as divisors or which would prevent the chain from progressing.
+        let non_zero_values = [
+            ("TARGET_COMMITTEE_SIZE", self.target_committee_size as u64),
+            (
+                "MAX_COMMITTEES_PER_SLOT",
+                self.max_committees_per_slot as u64,
+            ),
+            ("CHURN_LIMIT_QUOTIENT", self.churn_limit_quotient),
+            ("SHUFFLE_ROUND_COUNT", u64::from(self.shuffle_round_count)),
+            ("HYSTERESIS_QUOTIENT", self.hysteresis_quotient),
+            (
+                "EFFECTIVE_BALANCE_INCREMENT",
+                self.effective_balance_increment,
+            ),
+            ("SECONDS_PER_SLOT", self.seconds_per_slot),
+            ("BASE_REWARDS_PER_EPOCH", self.base_rewards_per_epoch),
+            (
+                "WHISTLEBLOWER_REWARD_QUOTIENT",
+                self.whistleblower_reward_quotient,
+            ),
+            ("PROPOSER_REWARD_QUOTIENT", self.proposer_reward_quotient),
+            (
+                "INACTIVITY_PENALTY_QUOTIENT",
+                self.inactivity_penalty_quotient,
+            ),
+            (
+                "MIN_SLASHING_PENALTY_QUOTIENT",
+                self.min_slashing_penalty_quotient,
+            ),
+            (
+                "TARGET_AGGREGATORS_PER_COMMITTEE",
+                self.target_aggregators_per_committee,
+            ),
+            (
+                "EPOCHS_PER_RANDOM_SUBNET_SUBSCRIPTION",
+                self.epochs_per_random_subnet_subscription,
+            ),
+            ("SECONDS_PER_ETH1_BLOCK", self.seconds_per_eth1_block),
+        ];
+
+        for (name, value) in non_zero_values.iter() {
+            if *value == 0 {
+                return Err(format!("{} must not be zero", name));
+            }
+        }
+
+        if self.min_seed_lookahead > self.max_seed_lookahead {
+            return Err(format!(
+                "MIN_SEED_LOOKAHEAD ({}) exceeds MAX_SEED_LOOKAHEAD ({})",
+                self.min_seed_lookahead, self.max_seed_lookahead
+            ));
+        }
+
+        if self.ejection_balance > self.max_effective_balance {
+            return Err(format!(
+                "EJECTION_BALANCE ({}) exceeds MAX_EFFECTIVE_BALANCE ({})",
+                self.ejection_balance, self.max_effective_balance
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of `chain_spec` with all of the values of this config applied, or `None` if
+    /// the config fails `Self::validate`.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, chain_spec: &ChainSpec) -> Option<ChainSpec> {
+        self.validate::<T>().ok()?;
+
         // Create a ChainSpec from the yaml config
         Some(ChainSpec {
             far_future_epoch: Epoch::from(self.far_future_epoch),
@@ -702,9 +890,20 @@ impl YamlConfig {
             domain_randao: self.domain_randao,
             domain_deposit: self.domain_deposit,
             domain_voluntary_exit: self.domain_voluntary_exit,
+            domain_selection_proof: self.domain_selection_proof,
+            domain_aggregate_and_proof: self.domain_aggregate_and_proof,
+            max_committees_per_slot: self.max_committees_per_slot,
+            safe_slots_to_update_justified: self.safe_slots_to_update_justified,
             boot_nodes: chain_spec.boot_nodes.clone(),
             genesis_fork_version: self.genesis_fork_version,
             eth1_follow_distance: self.eth1_follow_distance,
+            seconds_per_eth1_block: self.seconds_per_eth1_block,
+            target_aggregators_per_committee: self.target_aggregators_per_committee,
+            random_subnets_per_validator: self.random_subnets_per_validator,
+            epochs_per_random_subnet_subscription: self.epochs_per_random_subnet_subscription,
+            attestation_subnet_count: self.attestation_subnet_count,
+            attestation_propagation_slot_range: self.attestation_propagation_slot_range,
+            maximum_gossip_clock_disparity_millis: self.maximum_gossip_clock_disparity,
             ..*chain_spec
         })
     }
@@ -779,4 +978,46 @@ mod yaml_tests {
             .expect("should have applied spec");
         assert_eq!(new_spec, ChainSpec::minimal());
     }
+
+    #[test]
+    fn apply_runtime_values() {
+        let spec = ChainSpec::minimal();
+        let mut yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+
+        yamlconfig.safe_slots_to_update_justified = 4;
+        yamlconfig.target_aggregators_per_committee = 8;
+        yamlconfig.maximum_gossip_clock_disparity = 1_000;
+
+        let new_spec = yamlconfig
+            .apply_to_chain_spec::<MinimalEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(new_spec.safe_slots_to_update_justified, 4);
+        assert_eq!(new_spec.target_aggregators_per_committee, 8);
+        assert_eq!(
+            new_spec.maximum_gossip_clock_disparity(),
+            Duration::from_millis(1_000)
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let spec = ChainSpec::minimal();
+        let yamlconfig = YamlConfig::from_spec::<MinimalEthSpec>(&spec);
+        assert_eq!(yamlconfig.validate::<MinimalEthSpec>(), Ok(()));
+        assert!(yamlconfig
+            .validate::<MainnetEthSpec>()
+            .unwrap_err()
+            .starts_with("SLOTS_PER_EPOCH"));
+
+        let mut zero_slot_time = yamlconfig.clone();
+        zero_slot_time.seconds_per_slot = 0;
+        assert_eq!(
+            zero_slot_time.validate::<MinimalEthSpec>(),
+            Err("SECONDS_PER_SLOT must not be zero".to_string())
+        );
+
+        let mut inverted_lookahead = yamlconfig;
+        inverted_lookahead.min_seed_lookahead = inverted_lookahead.max_seed_lookahead + 1;
+        assert!(inverted_lookahead.validate::<MinimalEthSpec>().is_err());
+    }
 }
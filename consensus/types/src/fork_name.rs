@@ -0,0 +1,114 @@
+use crate::{ChainSpec, Epoch, EthSpec, Slot};
+use serde_derive::{Deserialize, Serialize};
+use ssz::DecodeError;
+use std::fmt;
+use std::str::FromStr;
+
+/// The forks of the beacon chain, in the order in which they are scheduled.
+///
+/// Containers which are redefined by a fork are decoded according to the fork in effect at their
+/// slot (see `ForkVersionDecode`), so that introducing a fork only requires a new variant here and
+/// in `ChainSpec::fork_name_at_epoch`, rather than changes at each call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub enum ForkName {
+    /// The fork in effect from genesis, also known as phase 0.
+    Base,
+}
+
+impl ForkName {
+    /// Returns all forks, in the order in which they are scheduled.
+    pub fn list_all() -> Vec<ForkName> {
+        vec![ForkName::Base]
+    }
+}
+
+impl ChainSpec {
+    /// Returns the name of the fork in effect at `epoch`.
+    pub fn fork_name_at_epoch(&self, _epoch: Epoch) -> ForkName {
+        ForkName::Base
+    }
+
+    /// Returns the name of the fork in effect at `slot`.
+    pub fn fork_name_at_slot<E: EthSpec>(&self, slot: Slot) -> ForkName {
+        self.fork_name_at_epoch(slot.epoch(E::slots_per_epoch()))
+    }
+
+    /// Returns the fork version which is introduced by `fork_name`.
+    pub fn fork_version_for_name(&self, fork_name: ForkName) -> [u8; 4] {
+        match fork_name {
+            ForkName::Base => self.genesis_fork_version,
+        }
+    }
+}
+
+impl fmt::Display for ForkName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForkName::Base => write!(f, "phase0"),
+        }
+    }
+}
+
+impl FromStr for ForkName {
+    type Err = String;
+
+    fn from_str(fork_name: &str) -> Result<Self, String> {
+        match fork_name.to_lowercase().as_str() {
+            "phase0" | "base" => Ok(ForkName::Base),
+            _ => Err(format!("Unknown fork name: {}", fork_name)),
+        }
+    }
+}
+
+impl From<ForkName> for String {
+    fn from(fork_name: ForkName) -> String {
+        fork_name.to_string()
+    }
+}
+
+impl std::convert::TryFrom<String> for ForkName {
+    type Error = String;
+
+    fn try_from(fork_name: String) -> Result<Self, String> {
+        fork_name.parse()
+    }
+}
+
+/// SSZ decoding of containers which may be redefined by a fork.
+pub trait ForkVersionDecode: Sized {
+    /// SSZ decode `bytes` as the definition of `Self` which is in effect during `fork_name`.
+    fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError>;
+}
+
+/// Reads the `Slot` which is SSZ encoded at `offset` in `bytes`.
+///
+/// Useful for determining the fork of a container before decoding it.
+pub(crate) fn read_slot_at_offset(bytes: &[u8], offset: usize) -> Result<Slot, DecodeError> {
+    let end = offset
+        .checked_add(<Slot as ssz::Decode>::ssz_fixed_len())
+        .ok_or_else(|| DecodeError::OutOfBoundsByte { i: offset })?;
+    let slot_bytes = bytes
+        .get(offset..end)
+        .ok_or_else(|| DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: end,
+        })?;
+
+    <Slot as ssz::Decode>::from_ssz_bytes(slot_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_name_strings() {
+        for fork_name in ForkName::list_all() {
+            assert_eq!(fork_name.to_string().parse::<ForkName>(), Ok(fork_name));
+        }
+        assert_eq!("base".parse::<ForkName>(), Ok(ForkName::Base));
+        assert!("unknown".parse::<ForkName>().is_err());
+    }
+}
@@ -0,0 +1,105 @@
+/// Defines an `enum` with a variant for each definition of a container which is redefined by a
+/// fork.
+///
+/// Each variant must be named after the `ForkName` which introduces its definition. The generated
+/// `enum` provides:
+///
+/// - `fork_name`, returning the fork of the held definition.
+/// - A reference accessor for each of the `common_fields`, which every definition must share.
+/// - `From` for each definition.
+/// - `ssz::Encode` and `ForkVersionDecode`, so it can be decoded once the fork is known.
+///
+/// Callers which only read the common fields are therefore unaffected by a new fork; adding a
+/// fork only requires a new `ForkName` variant and a new variant here.
+///
+/// The containers are assumed to be variable-length, as are all of those redefined by forks.
+macro_rules! fork_versioned_container {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident<$e:ident> $variants:tt
+        common_fields {
+            $($field:ident: $field_ty:ty),* $(,)?
+        }
+    ) => {
+        fork_versioned_container!(@enum [$(#[$attr])*] $name, $e, $variants);
+
+        impl<$e: EthSpec> $name<$e> {
+            $(
+                fork_versioned_container!(@field $name, $variants, $field, $field_ty);
+            )*
+        }
+    };
+    (
+        @enum [$(#[$attr:meta])*] $name:ident, $e:ident,
+        { $($variant:ident($inner:ty)),+ $(,)? }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name<$e: EthSpec> {
+            $($variant($inner)),+
+        }
+
+        impl<$e: EthSpec> $name<$e> {
+            /// Returns the fork which introduced the definition of the container held by `self`.
+            pub fn fork_name(&self) -> ForkName {
+                match self {
+                    $($name::$variant(_) => ForkName::$variant),+
+                }
+            }
+        }
+
+        $(
+            impl<$e: EthSpec> From<$inner> for $name<$e> {
+                fn from(inner: $inner) -> Self {
+                    $name::$variant(inner)
+                }
+            }
+        )+
+
+        impl<$e: EthSpec> ssz::Encode for $name<$e> {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    $($name::$variant(inner) => inner.ssz_bytes_len()),+
+                }
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match self {
+                    $($name::$variant(inner) => inner.ssz_append(buf)),+
+                }
+            }
+        }
+
+        impl<$e: EthSpec> ForkVersionDecode for $name<$e> {
+            fn from_ssz_bytes_by_fork(
+                bytes: &[u8],
+                fork_name: ForkName,
+            ) -> Result<Self, ssz::DecodeError> {
+                match fork_name {
+                    $(
+                        ForkName::$variant => {
+                            <$inner as ssz::Decode>::from_ssz_bytes(bytes).map($name::$variant)
+                        }
+                    )+
+                }
+            }
+        }
+    };
+    (
+        @field $name:ident,
+        { $($variant:ident($inner:ty)),+ $(,)? },
+        $field:ident,
+        $field_ty:ty
+    ) => {
+        /// Returns this field of the held container.
+        pub fn $field(&self) -> &$field_ty {
+            match self {
+                $($name::$variant(inner) => &inner.$field),+
+            }
+        }
+    };
+}
@@ -1,10 +1,12 @@
 use crate::test_utils::TestRandom;
 use crate::*;
 
+use merkle_proof::MerkleTree;
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::FixedVector;
+use ssz_types::{typenum::Unsigned, FixedVector};
 use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
 /// Historical block and state roots.
@@ -17,11 +19,96 @@ pub struct HistoricalBatch<T: EthSpec> {
     pub state_roots: FixedVector<Hash256, T::SlotsPerHistoricalRoot>,
 }
 
+impl<T: EthSpec> HistoricalBatch<T> {
+    /// The depth of the Merkle proofs returned by `Self::block_root_proof` and
+    /// `Self::state_root_proof`.
+    pub fn proof_depth() -> usize {
+        Self::roots_depth() + 1
+    }
+
+    /// Returns a Merkle proof of the inclusion of `self.block_roots[index]` in
+    /// `self.tree_hash_root()`, or `None` if `index` is out of bounds.
+    ///
+    /// The proof is in bottom-up order, of length `Self::proof_depth()` and for the leaf at
+    /// `index`.
+    pub fn block_root_proof(&self, index: usize) -> Option<Vec<Hash256>> {
+        Self::roots_proof(&self.block_roots, index, self.state_roots.tree_hash_root())
+    }
+
+    /// Returns a Merkle proof of the inclusion of `self.state_roots[index]` in
+    /// `self.tree_hash_root()`, or `None` if `index` is out of bounds.
+    ///
+    /// The proof is in bottom-up order, of length `Self::proof_depth()` and for the leaf at
+    /// `index + T::SlotsPerHistoricalRoot`.
+    pub fn state_root_proof(&self, index: usize) -> Option<Vec<Hash256>> {
+        Self::roots_proof(&self.state_roots, index, self.block_roots.tree_hash_root())
+    }
+
+    /// The depth of the Merkle tree of either of the `block_roots` or `state_roots` fields.
+    fn roots_depth() -> usize {
+        T::SlotsPerHistoricalRoot::to_usize().trailing_zeros() as usize
+    }
+
+    /// Returns a proof of the inclusion of `roots[index]` in the tree of the batch, where
+    /// `sibling_root` is the root of the other field.
+    fn roots_proof(
+        roots: &FixedVector<Hash256, T::SlotsPerHistoricalRoot>,
+        index: usize,
+        sibling_root: Hash256,
+    ) -> Option<Vec<Hash256>> {
+        if index >= roots.len() {
+            return None;
+        }
+
+        let depth = Self::roots_depth();
+        let (_, mut proof) = MerkleTree::create(&roots[..], depth).generate_proof(index, depth);
+        proof.push(sibling_root);
+
+        Some(proof)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
+    use merkle_proof::verify_merkle_proof;
 
     pub type FoundationHistoricalBatch = HistoricalBatch<MainnetEthSpec>;
 
     ssz_and_tree_hash_tests!(FoundationHistoricalBatch);
+
+    #[test]
+    fn root_proofs() {
+        type E = MinimalEthSpec;
+
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let batch = HistoricalBatch::<E>::random_for_test(&mut rng);
+        let root = batch.tree_hash_root();
+        let depth = HistoricalBatch::<E>::proof_depth();
+        let len = <E as EthSpec>::SlotsPerHistoricalRoot::to_usize();
+
+        for index in &[0, 1, len / 2, len - 1] {
+            let proof = batch.block_root_proof(*index).unwrap();
+            assert!(verify_merkle_proof(
+                batch.block_roots[*index],
+                &proof,
+                depth,
+                *index,
+                root
+            ));
+
+            let proof = batch.state_root_proof(*index).unwrap();
+            assert!(verify_merkle_proof(
+                batch.state_roots[*index],
+                &proof,
+                depth,
+                *index + len,
+                root
+            ));
+        }
+
+        assert_eq!(batch.block_root_proof(len), None);
+        assert_eq!(batch.state_root_proof(len), None);
+    }
 }
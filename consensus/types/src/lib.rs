@@ -7,6 +7,8 @@
 
 #[macro_use]
 pub mod test_utils;
+#[macro_use]
+mod fork_versioned;
 
 pub mod aggregate_and_proof;
 pub mod attestation;
@@ -28,6 +30,7 @@ pub mod eth1_data;
 pub mod eth_spec;
 pub mod fork;
 pub mod fork_data;
+pub mod fork_name;
 pub mod free_attestation;
 pub mod historical_batch;
 pub mod indexed_attestation;
@@ -59,7 +62,7 @@ pub use crate::attestation::{Attestation, Error as AttestationError};
 pub use crate::attestation_data::AttestationData;
 pub use crate::attestation_duty::AttestationDuty;
 pub use crate::attester_slashing::AttesterSlashing;
-pub use crate::beacon_block::BeaconBlock;
+pub use crate::beacon_block::{BeaconBlock, VersionedBeaconBlock};
 pub use crate::beacon_block_body::BeaconBlockBody;
 pub use crate::beacon_block_header::BeaconBlockHeader;
 pub use crate::beacon_committee::{BeaconCommittee, OwnedBeaconCommittee};
@@ -73,6 +76,7 @@ pub use crate::enr_fork_id::EnrForkId;
 pub use crate::eth1_data::Eth1Data;
 pub use crate::fork::Fork;
 pub use crate::fork_data::ForkData;
+pub use crate::fork_name::{ForkName, ForkVersionDecode};
 pub use crate::free_attestation::FreeAttestation;
 pub use crate::historical_batch::HistoricalBatch;
 pub use crate::indexed_attestation::IndexedAttestation;
@@ -1,9 +1,11 @@
+use crate::fork_name::read_slot_at_offset;
 use crate::{
-    test_utils::TestRandom, BeaconBlock, ChainSpec, Domain, EthSpec, Fork, Hash256, PublicKey,
-    SignedRoot, SigningData, Slot,
+    test_utils::TestRandom, BeaconBlock, ChainSpec, Domain, EthSpec, Fork, ForkName,
+    ForkVersionDecode, Hash256, PublicKey, SignedRoot, SigningData, Slot,
 };
 use bls::Signature;
 use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError, BYTES_PER_LENGTH_OFFSET};
 use ssz_derive::{Decode, Encode};
 use std::fmt;
 use test_random_derive::TestRandom;
@@ -49,6 +51,23 @@ pub struct SignedBeaconBlock<E: EthSpec> {
 }
 
 impl<E: EthSpec> SignedBeaconBlock<E> {
+    /// SSZ decode a block, using the fork in effect at the slot of the block to determine its
+    /// definition.
+    pub fn from_ssz_bytes_with_spec(bytes: &[u8], spec: &ChainSpec) -> Result<Self, DecodeError> {
+        // The `message` is the first field of the block. Since it is variable-length, the block
+        // begins with its offset.
+        let message_offset = bytes
+            .get(0..BYTES_PER_LENGTH_OFFSET)
+            .ok_or_else(|| DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BYTES_PER_LENGTH_OFFSET,
+            })
+            .and_then(u32::from_ssz_bytes)? as usize;
+        let slot = read_slot_at_offset(bytes, message_offset)?;
+
+        Self::from_ssz_bytes_by_fork(bytes, spec.fork_name_at_slot::<E>(slot))
+    }
+
     /// Verify `self.signature`.
     ///
     /// If the root of `block.message` is already known it can be passed in via `object_root_opt`.
@@ -104,10 +123,37 @@ impl<E: EthSpec> SignedBeaconBlock<E> {
     }
 }
 
+impl<E: EthSpec> ForkVersionDecode for SignedBeaconBlock<E> {
+    fn from_ssz_bytes_by_fork(bytes: &[u8], fork_name: ForkName) -> Result<Self, DecodeError> {
+        match fork_name {
+            ForkName::Base => Self::from_ssz_bytes(bytes),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
     use crate::MainnetEthSpec;
+    use ssz::Encode;
 
     ssz_tests!(SignedBeaconBlock<MainnetEthSpec>);
+
+    #[test]
+    fn decode_with_spec() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let spec = MainnetEthSpec::default_spec();
+        let block = SignedBeaconBlock::<MainnetEthSpec>::random_for_test(&mut rng);
+        let bytes = block.as_ssz_bytes();
+
+        assert_eq!(
+            SignedBeaconBlock::from_ssz_bytes_with_spec(&bytes, &spec),
+            Ok(block)
+        );
+        assert!(
+            SignedBeaconBlock::<MainnetEthSpec>::from_ssz_bytes_with_spec(&bytes[..3], &spec)
+                .is_err()
+        );
+    }
 }
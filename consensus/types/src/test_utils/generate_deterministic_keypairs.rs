@@ -1,5 +1,5 @@
 use crate::*;
-use eth2_interop_keypairs::{keypair, keypairs_from_yaml_file};
+use eth2_interop_keypairs::{be_private_key, keypairs_from_yaml_file};
 use log::debug;
 use rayon::prelude::*;
 use std::path::PathBuf;
@@ -27,20 +27,29 @@ pub fn generate_deterministic_keypairs(validator_count: usize) -> Vec<Keypair> {
 ///
 /// This is used for testing only, and not to be used in production!
 pub fn generate_deterministic_keypair(validator_index: usize) -> Keypair {
-    let raw = keypair(validator_index);
+    let sk = SecretKey::from_bytes(&be_private_key(validator_index)).unwrap_or_else(|_| {
+        panic!(
+            "Should build valid private key for validator index {}",
+            validator_index
+        )
+    });
     Keypair {
-        pk: PublicKey::from_raw(raw.pk),
-        sk: SecretKey::from_raw(raw.sk),
+        pk: PublicKey::from_secret_key(&sk),
+        sk,
     }
 }
 
 /// Loads a list of keypairs from file.
 pub fn load_keypairs_from_yaml(path: PathBuf) -> Result<Vec<Keypair>, String> {
-    Ok(keypairs_from_yaml_file(path)?
+    keypairs_from_yaml_file(path)?
         .into_iter()
-        .map(|raw| Keypair {
-            pk: PublicKey::from_raw(raw.pk),
-            sk: SecretKey::from_raw(raw.sk),
+        .map(|raw| {
+            let sk = SecretKey::from_bytes(&raw.sk.as_bytes())
+                .map_err(|e| format!("Invalid secret key in YAML file: {:?}", e))?;
+            Ok(Keypair {
+                pk: PublicKey::from_secret_key(&sk),
+                sk,
+            })
         })
-        .collect())
+        .collect()
 }
@@ -0,0 +1,96 @@
+use bls::{verify_signature_sets, AggregateSignature, Keypair, PublicKey, Signature, SignatureSet};
+use criterion::Criterion;
+use criterion::{black_box, criterion_group, criterion_main, Benchmark};
+use std::borrow::Cow;
+
+const MESSAGE: &[u8] = &[42; 32];
+const COMMITTEE_SIZE: usize = 128;
+const SET_COUNT: usize = 64;
+
+fn keypairs(n: usize) -> Vec<Keypair> {
+    (0..n).map(|_| Keypair::random()).collect()
+}
+
+fn aggregate(keypairs: &[Keypair], message: &[u8]) -> AggregateSignature {
+    let mut aggregate = AggregateSignature::new();
+    for keypair in keypairs {
+        aggregate.add(&Signature::new(message, &keypair.sk));
+    }
+    aggregate
+}
+
+fn signatures(c: &mut Criterion) {
+    c.bench(
+        &format!("bls ({})", bls::BACKEND_NAME),
+        Benchmark::new("sign", move |b| {
+            let keypair = Keypair::random();
+            b.iter(|| black_box(Signature::new(MESSAGE, &keypair.sk)))
+        })
+        .sample_size(10),
+    );
+
+    c.bench(
+        &format!("bls ({})", bls::BACKEND_NAME),
+        Benchmark::new("verify", move |b| {
+            let keypair = Keypair::random();
+            let signature = Signature::new(MESSAGE, &keypair.sk);
+            b.iter(|| black_box(signature.verify(MESSAGE, &keypair.pk)))
+        })
+        .sample_size(10),
+    );
+
+    c.bench(
+        &format!("bls ({})", bls::BACKEND_NAME),
+        Benchmark::new(
+            format!("aggregate verify {} keys", COMMITTEE_SIZE),
+            move |b| {
+                let keypairs = keypairs(COMMITTEE_SIZE);
+                let signature = aggregate(&keypairs, MESSAGE);
+                let public_keys = keypairs.iter().map(|kp| &kp.pk).collect::<Vec<_>>();
+                b.iter(|| black_box(signature.verify_unaggregated(MESSAGE, &public_keys)))
+            },
+        )
+        .sample_size(10),
+    );
+
+    c.bench(
+        &format!("bls ({})", bls::BACKEND_NAME),
+        Benchmark::new(
+            format!(
+                "verify {} signature sets of {} keys",
+                SET_COUNT, COMMITTEE_SIZE
+            ),
+            move |b| {
+                let keypairs = keypairs(COMMITTEE_SIZE);
+                let public_keys = keypairs
+                    .iter()
+                    .map(|kp| kp.pk.clone())
+                    .collect::<Vec<PublicKey>>();
+                let sets = (0..SET_COUNT)
+                    .map(|i| {
+                        let message = vec![i as u8; 32];
+                        (aggregate(&keypairs, &message), message)
+                    })
+                    .collect::<Vec<_>>();
+
+                b.iter(|| {
+                    let sets = sets
+                        .iter()
+                        .map(|(signature, message)| {
+                            SignatureSet::new(
+                                signature,
+                                public_keys.iter().map(Cow::Borrowed).collect(),
+                                message.clone(),
+                            )
+                        })
+                        .collect();
+                    black_box(verify_signature_sets(sets))
+                })
+            },
+        )
+        .sample_size(10),
+    );
+}
+
+criterion_group!(benches, signatures);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+//! Sets the `blst_adx` cfg if `blst` is built to use the ADX and BMI2 instructions of `x86_64`
+//! CPUs, so that `check_cpu_features` can refuse to run on CPUs which lack them.
+//!
+//! `blst` uses these instructions if the `force-adx` feature is enabled or, unless the `portable`
+//! feature is enabled, if the CPU of the build machine supports them.
+
+use std::env;
+
+fn main() {
+    let feature = |name: &str| env::var(format!("CARGO_FEATURE_{}", name)).is_ok();
+    let target_x86_64 = env::var("CARGO_CFG_TARGET_ARCH").map_or(false, |arch| arch == "x86_64");
+
+    let blst = feature("SUPRANATIONAL") && !feature("MILAGRO");
+    let adx = feature("SUPRANATIONAL_FORCE_ADX")
+        || (!feature("SUPRANATIONAL_PORTABLE") && build_cpu_has_adx());
+
+    if target_x86_64 && blst && adx {
+        println!("cargo:rustc-cfg=blst_adx");
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn build_cpu_has_adx() -> bool {
+    is_x86_feature_detected!("adx") && is_x86_feature_detected!("bmi2")
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn build_cpu_has_adx() -> bool {
+    false
+}
@@ -1,5 +1,5 @@
 use super::{PublicKey, BLS_PUBLIC_KEY_BYTE_SIZE};
-use milagro_bls::AggregatePublicKey as RawAggregatePublicKey;
+use crate::impls::{AggregatePublicKey as RawAggregatePublicKey, AggregatePublicKeyExt};
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
@@ -26,11 +26,11 @@ impl AggregatePublicKey {
     }
 
     pub fn add_without_affine(&mut self, public_key: &PublicKey) {
-        self.0.point.add(&public_key.as_raw().point)
+        self.0.add_without_affine(public_key.as_raw())
     }
 
     pub fn affine(&mut self) {
-        self.0.point.affine()
+        self.0.affine()
     }
 
     pub fn add(&mut self, public_key: &PublicKey) {
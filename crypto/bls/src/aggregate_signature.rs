@@ -1,5 +1,5 @@
 use super::*;
-use milagro_bls::AggregateSignature as RawAggregateSignature;
+use crate::impls::AggregateSignature as RawAggregateSignature;
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
@@ -0,0 +1,402 @@
+//! Provides the interface of `milagro_bls`, backed by the `blst` library.
+//!
+//! `blst` cannot represent the point at infinity as an aggregate, so aggregates are `None` until
+//! the first key or signature is added.
+
+use blst::min_pk as blst_core;
+use blst::{blst_scalar, blst_scalar_from_uint64, BLST_ERROR};
+use rand::RngCore;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// The domain separation tag of the Ethereum 2.0 proof-of-possession ciphersuite.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// The number of random bits used to weight each signature in a batch verification.
+const RAND_BITS: usize = 64;
+
+const SECRET_KEY_BYTES: usize = 32;
+const PUBLIC_KEY_BYTES: usize = 48;
+const SIGNATURE_BYTES: usize = 96;
+
+/// The compressed encoding of the point at infinity in G1.
+const INFINITY_PUBLIC_KEY: [u8; PUBLIC_KEY_BYTES] = {
+    let mut bytes = [0; PUBLIC_KEY_BYTES];
+    bytes[0] = 0xc0;
+    bytes
+};
+
+/// The compressed encoding of the point at infinity in G2.
+const INFINITY_SIGNATURE: [u8; SIGNATURE_BYTES] = {
+    let mut bytes = [0; SIGNATURE_BYTES];
+    bytes[0] = 0xc0;
+    bytes
+};
+
+/// An error raised by `blst`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Error(pub BLST_ERROR);
+
+impl From<BLST_ERROR> for Error {
+    fn from(e: BLST_ERROR) -> Self {
+        Self(e)
+    }
+}
+
+#[derive(Clone)]
+pub struct SecretKey(blst_core::SecretKey);
+
+impl SecretKey {
+    /// Generates a secret key from 32 bytes of key material read from `rng`.
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        let mut ikm = [0; SECRET_KEY_BYTES];
+        rng.fill_bytes(&mut ikm);
+        let sk =
+            blst_core::SecretKey::key_gen(&ikm, &[]).expect("key material is of sufficient length");
+        ikm.zeroize();
+        Self(sk)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        blst_core::SecretKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(Into::into)
+    }
+
+    pub fn as_bytes(&self) -> [u8; SECRET_KEY_BYTES] {
+        self.0.to_bytes()
+    }
+}
+
+#[derive(Clone)]
+pub struct PublicKey(blst_core::PublicKey);
+
+impl PublicKey {
+    pub fn from_secret_key(secret_key: &SecretKey) -> Self {
+        Self(secret_key.0.sk_to_pk())
+    }
+
+    /// Decodes a compressed public key, rejecting points which are at infinity or outside of the
+    /// G1 subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != PUBLIC_KEY_BYTES {
+            return Err(Error(BLST_ERROR::BLST_BAD_ENCODING));
+        }
+        let pk = blst_core::PublicKey::uncompress(bytes)?;
+        pk.validate()?;
+        Ok(Self(pk))
+    }
+
+    pub fn as_bytes(&self) -> [u8; PUBLIC_KEY_BYTES] {
+        self.0.compress()
+    }
+
+    /// Decodes an uncompressed public key, rejecting points which are at infinity or outside of
+    /// the G1 subgroup.
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != PUBLIC_KEY_BYTES * 2 {
+            return Err(Error(BLST_ERROR::BLST_BAD_ENCODING));
+        }
+        let pk = blst_core::PublicKey::deserialize(bytes)?;
+        pk.validate()?;
+        Ok(Self(pk))
+    }
+
+    pub fn as_uncompressed_bytes(&mut self) -> [u8; PUBLIC_KEY_BYTES * 2] {
+        self.0.serialize()
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl Eq for PublicKey {}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.as_bytes()[..]))
+    }
+}
+
+/// An aggregate of public keys, which is `None` whilst it is the point at infinity.
+#[derive(Clone, Default)]
+pub struct AggregatePublicKey(Option<blst_core::AggregatePublicKey>);
+
+impl AggregatePublicKey {
+    pub fn new() -> Self {
+        Self(None)
+    }
+
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        Self(Some(blst_core::AggregatePublicKey::from_public_key(
+            &public_key.0,
+        )))
+    }
+
+    pub fn aggregate(public_keys: &[&PublicKey]) -> Self {
+        let mut aggregate = Self::new();
+        for public_key in public_keys {
+            aggregate.add(public_key);
+        }
+        aggregate
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes[..] == INFINITY_PUBLIC_KEY[..] {
+            Ok(Self::new())
+        } else {
+            PublicKey::from_bytes(bytes).map(|pk| Self::from_public_key(&pk))
+        }
+    }
+
+    pub fn add(&mut self, public_key: &PublicKey) {
+        match &mut self.0 {
+            Some(aggregate) => aggregate
+                .add_public_key(&public_key.0, false)
+                .expect("public key validation is disabled"),
+            None => *self = Self::from_public_key(public_key),
+        }
+    }
+
+    pub fn as_bytes(&self) -> [u8; PUBLIC_KEY_BYTES] {
+        self.to_public_key()
+            .map_or(INFINITY_PUBLIC_KEY, |pk| pk.compress())
+    }
+
+    /// Returns the aggregate as a single public key, or `None` if it is the point at infinity.
+    fn to_public_key(&self) -> Option<blst_core::PublicKey> {
+        self.0.as_ref().map(|aggregate| aggregate.to_public_key())
+    }
+}
+
+impl fmt::Debug for AggregatePublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.as_bytes()[..]))
+    }
+}
+
+/// `blst` converts points to affine coordinates itself, so `add_without_affine` is the same as
+/// `add` and `affine` has no effect.
+pub trait AggregatePublicKeyExt {
+    fn add_without_affine(&mut self, public_key: &PublicKey);
+    fn affine(&mut self);
+}
+
+impl AggregatePublicKeyExt for AggregatePublicKey {
+    fn add_without_affine(&mut self, public_key: &PublicKey) {
+        self.add(public_key)
+    }
+
+    fn affine(&mut self) {}
+}
+
+#[derive(Clone)]
+pub struct Signature(blst_core::Signature);
+
+impl Signature {
+    pub fn new(msg: &[u8], secret_key: &SecretKey) -> Self {
+        Self(secret_key.0.sign(msg, DST, &[]))
+    }
+
+    pub fn verify(&self, msg: &[u8], public_key: &PublicKey) -> bool {
+        self.0.verify(true, msg, DST, &[], &public_key.0, false) == BLST_ERROR::BLST_SUCCESS
+    }
+
+    /// Decodes a compressed signature. The signature is checked to be in the G2 subgroup during
+    /// verification.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != SIGNATURE_BYTES {
+            return Err(Error(BLST_ERROR::BLST_BAD_ENCODING));
+        }
+        // The default (all-zero) affine point is the point at infinity.
+        if bytes[..] == INFINITY_SIGNATURE[..] {
+            return Ok(Self(blst_core::Signature::default()));
+        }
+        blst_core::Signature::uncompress(bytes)
+            .map(Self)
+            .map_err(Into::into)
+    }
+
+    pub fn as_bytes(&self) -> [u8; SIGNATURE_BYTES] {
+        self.0.compress()
+    }
+}
+
+impl PartialEq for Signature {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl Eq for Signature {}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.as_bytes()[..]))
+    }
+}
+
+/// An aggregate of signatures, which is `None` whilst it is the point at infinity.
+#[derive(Clone, Default)]
+pub struct AggregateSignature(Option<blst_core::AggregateSignature>);
+
+impl AggregateSignature {
+    pub fn new() -> Self {
+        Self(None)
+    }
+
+    pub fn from_signature(signature: &Signature) -> Self {
+        Self(Some(blst_core::AggregateSignature::from_signature(
+            &signature.0,
+        )))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes[..] == INFINITY_SIGNATURE[..] {
+            Ok(Self::new())
+        } else {
+            Signature::from_bytes(bytes).map(|sig| Self::from_signature(&sig))
+        }
+    }
+
+    pub fn add(&mut self, signature: &Signature) {
+        match &mut self.0 {
+            Some(aggregate) => aggregate
+                .add_signature(&signature.0, false)
+                .expect("signature group check is disabled"),
+            None => *self = Self::from_signature(signature),
+        }
+    }
+
+    pub fn add_aggregate(&mut self, other: &Self) {
+        match (&mut self.0, &other.0) {
+            (Some(aggregate), Some(other)) => aggregate.add_aggregate(other),
+            (None, Some(_)) => *self = other.clone(),
+            (_, None) => {}
+        }
+    }
+
+    pub fn as_bytes(&self) -> [u8; SIGNATURE_BYTES] {
+        self.to_signature()
+            .map_or(INFINITY_SIGNATURE, |sig| sig.compress())
+    }
+
+    /// Verifies the aggregate against `msg`, signed by all of the `public_keys`.
+    pub fn fast_aggregate_verify(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
+        let public_keys = public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+        match self.to_signature() {
+            Some(sig) if !public_keys.is_empty() => {
+                sig.fast_aggregate_verify(true, msg, DST, &public_keys) == BLST_ERROR::BLST_SUCCESS
+            }
+            _ => false,
+        }
+    }
+
+    /// Verifies the aggregate against `msg`, signed by the keys which make up `public_key`.
+    pub fn fast_aggregate_verify_pre_aggregated(
+        &self,
+        msg: &[u8],
+        public_key: &AggregatePublicKey,
+    ) -> bool {
+        match (self.to_signature(), public_key.to_public_key()) {
+            (Some(sig), Some(pk)) => {
+                sig.verify(true, msg, DST, &[], &pk, false) == BLST_ERROR::BLST_SUCCESS
+            }
+            _ => false,
+        }
+    }
+
+    /// Verifies the aggregate against each of `msgs`, signed by the corresponding `public_keys`.
+    pub fn aggregate_verify(&self, msgs: &[&[u8]], public_keys: &[&PublicKey]) -> bool {
+        if msgs.is_empty() || msgs.len() != public_keys.len() {
+            return false;
+        }
+        let public_keys = public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+        match self.to_signature() {
+            Some(sig) => {
+                sig.aggregate_verify(true, msgs, DST, &public_keys, false)
+                    == BLST_ERROR::BLST_SUCCESS
+            }
+            None => false,
+        }
+    }
+
+    /// Verifies many aggregate signatures at once, each weighted by a random scalar drawn from
+    /// `rng`.
+    ///
+    /// Returns `false` if `sets` is empty or any set is at infinity.
+    pub fn verify_multiple_aggregate_signatures<
+        'a,
+        R: RngCore,
+        I: Iterator<Item = (&'a AggregateSignature, &'a AggregatePublicKey, &'a [u8])>,
+    >(
+        rng: &mut R,
+        sets: I,
+    ) -> bool {
+        let mut msgs = vec![];
+        let mut sigs = vec![];
+        let mut pks = vec![];
+        let mut rands = vec![];
+
+        for (signature, public_key, msg) in sets {
+            match (signature.to_signature(), public_key.to_public_key()) {
+                (Some(sig), Some(pk)) => {
+                    sigs.push(sig);
+                    pks.push(pk);
+                }
+                _ => return false,
+            }
+            msgs.push(msg);
+            rands.push(random_scalar(rng));
+        }
+
+        if msgs.is_empty() {
+            return false;
+        }
+
+        let sig_refs = sigs.iter().collect::<Vec<_>>();
+        let pk_refs = pks.iter().collect::<Vec<_>>();
+
+        blst_core::Signature::verify_multiple_aggregate_signatures(
+            &msgs, DST, &pk_refs, false, &sig_refs, true, &rands, RAND_BITS,
+        ) == BLST_ERROR::BLST_SUCCESS
+    }
+
+    /// Returns the aggregate as a single signature, or `None` if it is the point at infinity.
+    fn to_signature(&self) -> Option<blst_core::Signature> {
+        self.0.as_ref().map(|aggregate| aggregate.to_signature())
+    }
+}
+
+impl PartialEq for AggregateSignature {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes()[..] == other.as_bytes()[..]
+    }
+}
+
+impl Eq for AggregateSignature {}
+
+impl fmt::Debug for AggregateSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.as_bytes()[..]))
+    }
+}
+
+/// Returns a non-zero scalar of `RAND_BITS` random bits.
+fn random_scalar<R: RngCore>(rng: &mut R) -> blst_scalar {
+    let mut value = 0;
+    while value == 0 {
+        value = rng.next_u64();
+    }
+    let limbs = [value, 0, 0, 0];
+
+    let mut scalar = blst_scalar::default();
+    // Safe since both pointers are valid for the duration of the call, and `limbs` has the four
+    // limbs that are read.
+    unsafe {
+        blst_scalar_from_uint64(&mut scalar, limbs.as_ptr());
+    }
+    scalar
+}
@@ -0,0 +1,20 @@
+//! Provides the `milagro_bls` types, which are used as the BLS backend when the `milagro` feature
+//! is enabled.
+
+pub use milagro_bls::{AggregatePublicKey, AggregateSignature, PublicKey, SecretKey, Signature};
+
+/// Operations upon the projective point of an `AggregatePublicKey`.
+pub trait AggregatePublicKeyExt {
+    fn add_without_affine(&mut self, public_key: &PublicKey);
+    fn affine(&mut self);
+}
+
+impl AggregatePublicKeyExt for AggregatePublicKey {
+    fn add_without_affine(&mut self, public_key: &PublicKey) {
+        self.point.add(&public_key.point)
+    }
+
+    fn affine(&mut self) {
+        self.point.affine()
+    }
+}
@@ -0,0 +1,213 @@
+//! The BLS libraries which may provide the raw types wrapped by this crate.
+//!
+//! Each backend exposes the interface of `milagro_bls`, so that the wrappers are agnostic to the
+//! backend in use. `blst` (the `supranational` feature) is used by default, unless the `milagro`
+//! feature is enabled.
+
+#[cfg(feature = "supranational")]
+pub mod blst;
+#[cfg(feature = "milagro")]
+pub mod milagro;
+
+#[cfg(feature = "milagro")]
+pub use self::milagro::*;
+
+#[cfg(all(feature = "supranational", not(feature = "milagro")))]
+pub use self::blst::*;
+
+#[cfg(not(any(feature = "supranational", feature = "milagro")))]
+compile_error!("bls requires either the \"supranational\" or the \"milagro\" feature.");
+
+/// The name of the BLS library in use.
+#[cfg(feature = "milagro")]
+pub const BACKEND_NAME: &str = "milagro";
+#[cfg(all(
+    feature = "supranational",
+    not(feature = "milagro"),
+    feature = "supranational-portable"
+))]
+pub const BACKEND_NAME: &str = "blst-portable";
+#[cfg(all(
+    feature = "supranational",
+    not(feature = "milagro"),
+    not(feature = "supranational-portable"),
+    feature = "supranational-force-adx"
+))]
+pub const BACKEND_NAME: &str = "blst-modern";
+#[cfg(all(
+    feature = "supranational",
+    not(feature = "milagro"),
+    not(feature = "supranational-portable"),
+    not(feature = "supranational-force-adx")
+))]
+pub const BACKEND_NAME: &str = "blst";
+
+/// Tests that both backends produce identical keys and signatures, and accept each other's
+/// signatures.
+///
+/// Run with `cargo test -p bls --features milagro`.
+#[cfg(all(test, feature = "supranational", feature = "milagro"))]
+mod tests {
+    use super::{blst, milagro};
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    const NUM_KEYS: usize = 8;
+
+    struct Keys {
+        blst: Vec<(blst::SecretKey, blst::PublicKey)>,
+        milagro: Vec<(milagro::SecretKey, milagro::PublicKey)>,
+    }
+
+    fn keys() -> Keys {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+
+        let mut keys = Keys {
+            blst: vec![],
+            milagro: vec![],
+        };
+
+        while keys.blst.len() < NUM_KEYS {
+            let mut bytes = [0; 32];
+            rng.fill(&mut bytes[1..]);
+
+            let (blst_sk, milagro_sk) = match (
+                blst::SecretKey::from_bytes(&bytes),
+                milagro::SecretKey::from_bytes(&bytes),
+            ) {
+                (Ok(blst_sk), Ok(milagro_sk)) => (blst_sk, milagro_sk),
+                _ => continue,
+            };
+
+            keys.blst
+                .push((blst_sk.clone(), blst::PublicKey::from_secret_key(&blst_sk)));
+            keys.milagro.push((
+                milagro_sk.clone(),
+                milagro::PublicKey::from_secret_key(&milagro_sk),
+            ));
+        }
+
+        keys
+    }
+
+    #[test]
+    fn public_keys_match() {
+        let keys = keys();
+
+        for ((_, blst_pk), (_, milagro_pk)) in keys.blst.iter().zip(keys.milagro.iter()) {
+            assert_eq!(blst_pk.as_bytes()[..], milagro_pk.as_bytes()[..]);
+            assert_eq!(
+                blst_pk.clone().as_uncompressed_bytes()[..],
+                milagro_pk.clone().as_uncompressed_bytes()[..]
+            );
+            assert_eq!(
+                blst::PublicKey::from_bytes(&milagro_pk.as_bytes()).unwrap(),
+                *blst_pk
+            );
+        }
+    }
+
+    #[test]
+    fn signatures_match_and_verify() {
+        let keys = keys();
+        let msg = [42; 32];
+
+        for ((blst_sk, blst_pk), (milagro_sk, milagro_pk)) in
+            keys.blst.iter().zip(keys.milagro.iter())
+        {
+            let blst_sig = blst::Signature::new(&msg, blst_sk);
+            let milagro_sig = milagro::Signature::new(&msg, milagro_sk);
+
+            assert_eq!(blst_sig.as_bytes()[..], milagro_sig.as_bytes()[..]);
+
+            let from_milagro = blst::Signature::from_bytes(&milagro_sig.as_bytes()).unwrap();
+            assert!(from_milagro.verify(&msg, blst_pk));
+            assert!(!from_milagro.verify(&[0; 32], blst_pk));
+
+            let from_blst = milagro::Signature::from_bytes(&blst_sig.as_bytes()).unwrap();
+            assert!(from_blst.verify(&msg, milagro_pk));
+        }
+    }
+
+    #[test]
+    fn aggregates_match_and_verify() {
+        let keys = keys();
+        let msg = [42; 32];
+
+        let mut blst_agg = blst::AggregateSignature::new();
+        let mut milagro_agg = milagro::AggregateSignature::new();
+        for ((blst_sk, _), (milagro_sk, _)) in keys.blst.iter().zip(keys.milagro.iter()) {
+            blst_agg.add(&blst::Signature::new(&msg, blst_sk));
+            milagro_agg.add(&milagro::Signature::new(&msg, milagro_sk));
+        }
+
+        assert_eq!(blst_agg.as_bytes()[..], milagro_agg.as_bytes()[..]);
+
+        let blst_pks = keys.blst.iter().map(|(_, pk)| pk).collect::<Vec<_>>();
+        let milagro_pks = keys.milagro.iter().map(|(_, pk)| pk).collect::<Vec<_>>();
+
+        let blst_agg_pk = blst::AggregatePublicKey::aggregate(&blst_pks);
+        let milagro_agg_pk = milagro::AggregatePublicKey::aggregate(&milagro_pks);
+        assert_eq!(blst_agg_pk.as_bytes()[..], milagro_agg_pk.as_bytes()[..]);
+
+        let from_milagro = blst::AggregateSignature::from_bytes(&milagro_agg.as_bytes()).unwrap();
+        assert!(from_milagro.fast_aggregate_verify(&msg, &blst_pks));
+        assert!(from_milagro.fast_aggregate_verify_pre_aggregated(&msg, &blst_agg_pk));
+        assert!(!from_milagro.fast_aggregate_verify(&msg, &blst_pks[1..]));
+
+        let from_blst = milagro::AggregateSignature::from_bytes(&blst_agg.as_bytes()).unwrap();
+        assert!(from_blst.fast_aggregate_verify(&msg, &milagro_pks));
+    }
+
+    #[test]
+    fn aggregate_verify_distinct_messages() {
+        let keys = keys();
+        let msgs = (0..NUM_KEYS as u8).map(|i| vec![i; 32]).collect::<Vec<_>>();
+        let msg_refs = msgs.iter().map(|msg| msg.as_slice()).collect::<Vec<_>>();
+
+        let mut milagro_agg = milagro::AggregateSignature::new();
+        for ((milagro_sk, _), msg) in keys.milagro.iter().zip(msgs.iter()) {
+            milagro_agg.add(&milagro::Signature::new(msg, milagro_sk));
+        }
+
+        let blst_pks = keys.blst.iter().map(|(_, pk)| pk).collect::<Vec<_>>();
+        let blst_agg = blst::AggregateSignature::from_bytes(&milagro_agg.as_bytes()).unwrap();
+
+        assert!(blst_agg.aggregate_verify(&msg_refs, &blst_pks));
+        assert!(!blst_agg.aggregate_verify(&msg_refs[1..], &blst_pks[1..]));
+    }
+
+    #[test]
+    fn verify_multiple_aggregate_signatures() {
+        let keys = keys();
+        let rng = &mut rand::thread_rng();
+
+        let sets = keys
+            .blst
+            .iter()
+            .enumerate()
+            .map(|(i, (sk, pk))| {
+                let msg = vec![i as u8; 32];
+                let sig = blst::AggregateSignature::from_signature(&blst::Signature::new(&msg, sk));
+                (sig, blst::AggregatePublicKey::from_public_key(pk), msg)
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            blst::AggregateSignature::verify_multiple_aggregate_signatures(
+                rng,
+                sets.iter().map(|(sig, pk, msg)| (sig, pk, msg.as_slice()))
+            )
+        );
+
+        // Swapping two messages must invalidate the batch.
+        assert!(
+            !blst::AggregateSignature::verify_multiple_aggregate_signatures(
+                rng,
+                sets.iter()
+                    .zip(sets.iter().skip(1).chain(sets.iter().take(1)))
+                    .map(|((sig, pk, _), (_, _, msg))| (sig, pk, msg.as_slice()))
+            )
+        );
+    }
+}
@@ -1,8 +1,8 @@
-extern crate milagro_bls;
 extern crate ssz;
 
 #[macro_use]
 mod macros;
+mod impls;
 mod keypair;
 mod public_key_bytes;
 mod secret_hash;
@@ -10,6 +10,7 @@ mod secret_key;
 mod signature_bytes;
 mod signature_set;
 
+pub use crate::impls::BACKEND_NAME;
 pub use crate::keypair::Keypair;
 pub use crate::public_key_bytes::PublicKeyBytes;
 pub use crate::secret_key::SecretKey;
@@ -66,6 +67,40 @@ pub const BLS_PUBLIC_KEY_BYTE_SIZE: usize = 48;
 use eth2_hashing::hash;
 use ssz::ssz_encode;
 
+/// Returns an error if the CPU lacks instructions which the BLS backend was compiled to use.
+///
+/// `blst` uses the ADX and BMI2 instructions of `x86_64` CPUs when built with the
+/// `supranational-force-adx` feature, or when built without the `supranational-portable` feature
+/// on a CPU which supports them (see `build.rs`). Running such a build on an older CPU would
+/// otherwise terminate the process with an illegal instruction, at the first use of BLS.
+pub fn check_cpu_features() -> Result<(), String> {
+    #[cfg(blst_adx)]
+    {
+        if !cpu_has_adx() {
+            return Err(format!(
+                "This build of the {} BLS library requires a CPU with the ADX and BMI2 \
+                 instructions, which this CPU lacks. Use a build with the portable feature \
+                 instead.",
+                BACKEND_NAME
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the CPU supports the instructions used by the optimized `blst` assembly.
+#[cfg(target_arch = "x86_64")]
+pub fn cpu_has_adx() -> bool {
+    is_x86_feature_detected!("adx") && is_x86_feature_detected!("bmi2")
+}
+
+/// Returns `true` if the CPU supports the instructions used by the optimized `blst` assembly.
+#[cfg(not(target_arch = "x86_64"))]
+pub fn cpu_has_adx() -> bool {
+    false
+}
+
 /// Returns the withdrawal credentials for a given public key.
 pub fn get_withdrawal_credentials(pubkey: &PublicKey, prefix_byte: u8) -> Vec<u8> {
     let hashed = hash(&ssz_encode(pubkey));
@@ -1,7 +1,7 @@
 extern crate rand;
 
+use crate::impls::SecretKey as RawSecretKey;
 use crate::SecretHash;
-use milagro_bls::SecretKey as RawSecretKey;
 use ssz::DecodeError;
 
 /// A single BLS signature.
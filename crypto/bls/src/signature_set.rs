@@ -1,8 +1,8 @@
-use crate::{AggregateSignature, PublicKey, Signature};
+use crate::{AggregatePublicKey, AggregateSignature, PublicKey, Signature};
 use std::borrow::Cow;
 
 #[cfg(not(feature = "fake_crypto"))]
-use milagro_bls::{
+use crate::impls::{
     AggregatePublicKey as RawAggregatePublicKey, AggregateSignature as RawAggregateSignature,
     PublicKey as RawPublicKey,
 };
@@ -46,6 +46,20 @@ where {
         }
     }
 
+    /// Instantiates a set for keys which have already been aggregated (e.g., an entire committee),
+    /// avoiding the cost of aggregating them again.
+    pub fn from_aggregate_public_key(
+        signature: &AggregateSignature,
+        signing_keys: &AggregatePublicKey,
+        message: Message,
+    ) -> Self {
+        Self {
+            signature: signature.as_raw().clone(),
+            signing_keys: signing_keys.as_raw().clone(),
+            message,
+        }
+    }
+
     pub fn is_valid(&self) -> bool {
         self.signature
             .fast_aggregate_verify_pre_aggregated(&self.message, &self.signing_keys)
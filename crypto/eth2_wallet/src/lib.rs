@@ -6,6 +6,6 @@ pub mod json_wallet;
 pub use bip39;
 pub use validator_path::{KeyType, ValidatorPath, COIN_TYPE, PURPOSE};
 pub use wallet::{
-    recover_validator_secret, DerivedKey, Error, KeystoreError, PlainText, Uuid,
-    ValidatorKeystores, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_from_seed, DerivedKey, Error, KeystoreError,
+    PlainText, Uuid, ValidatorKeystores, Wallet, WalletBuilder,
 };
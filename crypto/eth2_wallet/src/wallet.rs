@@ -277,9 +277,23 @@ pub fn recover_validator_secret(
     index: u32,
     key_type: KeyType,
 ) -> Result<(PlainText, ValidatorPath), Error> {
-    let path = ValidatorPath::new(index, key_type);
     let secret = wallet.decrypt_seed(wallet_password)?;
-    let master = DerivedKey::from_seed(secret.as_bytes()).map_err(|()| Error::EmptyPassword)?;
+
+    recover_validator_secret_from_seed(secret.as_bytes(), index, key_type)
+}
+
+/// Returns `(secret, path)` for the `key_type` for the validator at `index`, deriving directly
+/// from the given `seed` rather than an encrypted wallet.
+///
+/// This is useful for tools which derive many validators from a mnemonic without persisting a
+/// wallet (e.g., bulk validator creation).
+pub fn recover_validator_secret_from_seed(
+    seed: &[u8],
+    index: u32,
+    key_type: KeyType,
+) -> Result<(PlainText, ValidatorPath), Error> {
+    let path = ValidatorPath::new(index, key_type);
+    let master = DerivedKey::from_seed(seed).map_err(|()| Error::EmptySeed)?;
 
     let destination = path.iter_nodes().fold(master, |dk, i| dk.child(*i));
 
@@ -0,0 +1,116 @@
+use beacon_chain::{
+    PersistedBeaconChain, PersistedForkChoice, BEACON_CHAIN_DB_KEY, FORK_CHOICE_DB_KEY,
+};
+use beacon_node::{get_data_dir, ClientConfig};
+use clap::{Arg, ArgMatches};
+use serde_derive::{Deserialize, Serialize};
+use slog::Logger;
+use std::path::PathBuf;
+use store::{HotColdDB, LevelDB, StoreConfig, CURRENT_SCHEMA_VERSION};
+use types::{ChainSpec, Checkpoint, EthSpec, Hash256, Slot};
+
+pub const FREEZER_DIR_FLAG: &str = "freezer-dir";
+
+/// The name of the manifest file, which is always the first entry in a snapshot archive.
+pub const MANIFEST_FILENAME: &str = "manifest.json";
+/// The directory in a snapshot archive which contains the hot database.
+pub const HOT_DB_ARCHIVE_DIR: &str = "chain_db";
+/// The directory in a snapshot archive which contains the freezer database.
+pub const FREEZER_DB_ARCHIVE_DIR: &str = "freezer_db";
+
+pub fn freezer_dir_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(FREEZER_DIR_FLAG)
+        .long(FREEZER_DIR_FLAG)
+        .value_name("DIR")
+        .help("The --freezer-dir of the beacon node, if it is not the default.")
+        .takes_value(true)
+}
+
+/// Describes the database contained in a snapshot archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The `store::CURRENT_SCHEMA_VERSION` of the node which produced the snapshot.
+    pub schema_version: u64,
+    pub head_block_root: Hash256,
+    pub head_slot: Slot,
+    pub finalized_checkpoint: Checkpoint,
+    /// The slot at which the hot and freezer databases are split.
+    pub split_slot: Slot,
+}
+
+impl Manifest {
+    /// Reads the head and finalized checkpoint persisted by a beacon node when it shut down.
+    pub fn from_store<E: EthSpec>(
+        store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    ) -> Result<Self, String> {
+        let persisted_beacon_chain = store
+            .get_item::<PersistedBeaconChain>(&Hash256::from_slice(&BEACON_CHAIN_DB_KEY))
+            .map_err(|e| format!("Unable to read persisted beacon chain: {:?}", e))?
+            .ok_or_else(|| "No persisted beacon chain found in the database".to_string())?;
+        let persisted_fork_choice = store
+            .get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY))
+            .map_err(|e| format!("Unable to read persisted fork choice: {:?}", e))?
+            .ok_or_else(|| "No persisted fork choice found in the database".to_string())?;
+
+        let head_block_root = persisted_beacon_chain.canonical_head_block_root;
+        let head_block = store
+            .get_block(&head_block_root)
+            .map_err(|e| format!("Unable to read head block: {:?}", e))?
+            .ok_or_else(|| {
+                format!(
+                    "Head block {:?} is missing from the database",
+                    head_block_root
+                )
+            })?;
+
+        Ok(Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            head_block_root,
+            head_slot: head_block.slot(),
+            finalized_checkpoint: *persisted_fork_choice
+                .fork_choice_store
+                .finalized_checkpoint(),
+            split_slot: store.get_split_slot(),
+        })
+    }
+}
+
+/// The on-disk locations of the hot and freezer databases of a beacon node.
+pub struct DatabaseDirs {
+    pub hot: PathBuf,
+    pub freezer: PathBuf,
+}
+
+impl DatabaseDirs {
+    /// Determines the database directories from the global `--datadir` flag and `--freezer-dir`,
+    /// using the same defaults as the beacon node.
+    pub fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let mut client_config = ClientConfig::default();
+        client_config.data_dir = get_data_dir(matches);
+        client_config.freezer_db_path = clap_utils::parse_optional(matches, FREEZER_DIR_FLAG)?;
+
+        Ok(Self {
+            hot: client_config
+                .get_db_path()
+                .ok_or_else(|| "Unable to locate the hot database".to_string())?,
+            freezer: client_config
+                .get_freezer_db_path()
+                .ok_or_else(|| "Unable to locate the freezer database".to_string())?,
+        })
+    }
+
+    /// Opens the databases. This fails if a beacon node is using them, since leveldb only permits
+    /// a single process to open a database.
+    pub fn open<E: EthSpec>(
+        &self,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, String> {
+        HotColdDB::open(&self.hot, &self.freezer, StoreConfig::default(), spec, log).map_err(|e| {
+            format!(
+                "Unable to open the database, ensure the beacon node is stopped: {:?}",
+                e
+            )
+        })
+    }
+}
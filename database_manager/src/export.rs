@@ -0,0 +1,95 @@
+use crate::common::{
+    freezer_dir_arg, DatabaseDirs, Manifest, FREEZER_DB_ARCHIVE_DIR, HOT_DB_ARCHIVE_DIR,
+    MANIFEST_FILENAME,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use flate2::{write::GzEncoder, Compression};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use types::EthSpec;
+
+pub const CMD: &str = "export";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Writes a snapshot of the hot and freezer databases of a beacon node to a single \
+            compressed archive, along with a manifest describing the head and finalized \
+            checkpoint of the snapshot. The beacon node must be stopped.",
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("FILE")
+                .help("The path of the archive to create. Must not already exist.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(freezer_dir_arg())
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let dirs = DatabaseDirs::from_cli(matches)?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_PATH_FLAG)?;
+
+    if output_path.exists() {
+        return Err(format!("{:?} already exists", output_path));
+    }
+    for dir in &[&dirs.hot, &dirs.freezer] {
+        if !dir.exists() {
+            return Err(format!("No database found at {:?}", dir));
+        }
+    }
+
+    // The database is closed again before it is archived, so that leveldb is not modifying any
+    // files (e.g., by compacting) whilst they are being read.
+    let log = env.core_context().log().clone();
+    let manifest = Manifest::from_store(&dirs.open::<T>(env.eth2_config.spec.clone(), log)?)?;
+
+    if let Err(e) = write_archive(&output_path, &manifest, &dirs) {
+        let _ = fs::remove_file(&output_path);
+        return Err(e);
+    }
+
+    println!("Exported database to {:?}", output_path);
+    println!("Head block root: {:?}", manifest.head_block_root);
+    println!("Head slot: {}", manifest.head_slot);
+    println!(
+        "Finalized checkpoint: {:?} (epoch {})",
+        manifest.finalized_checkpoint.root, manifest.finalized_checkpoint.epoch
+    );
+
+    Ok(())
+}
+
+/// Writes a gzipped tar archive containing the `manifest` followed by the hot and freezer
+/// databases.
+fn write_archive(path: &Path, manifest: &Manifest, dirs: &DatabaseDirs) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Unable to create {:?}: {}", path, e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest_bytes = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Unable to serialize manifest: {:?}", e))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_FILENAME, manifest_bytes.as_slice())
+        .map_err(|e| format!("Unable to write manifest: {}", e))?;
+
+    builder
+        .append_dir_all(HOT_DB_ARCHIVE_DIR, &dirs.hot)
+        .map_err(|e| format!("Unable to archive {:?}: {}", dirs.hot, e))?;
+    builder
+        .append_dir_all(FREEZER_DB_ARCHIVE_DIR, &dirs.freezer)
+        .map_err(|e| format!("Unable to archive {:?}: {}", dirs.freezer, e))?;
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Unable to finish writing {:?}: {}", path, e))
+}
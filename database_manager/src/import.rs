@@ -0,0 +1,402 @@
+use crate::common::{
+    freezer_dir_arg, DatabaseDirs, Manifest, FREEZER_DB_ARCHIVE_DIR, HOT_DB_ARCHIVE_DIR,
+    MANIFEST_FILENAME,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use flate2::read::GzDecoder;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use store::CURRENT_SCHEMA_VERSION;
+use types::EthSpec;
+
+pub const CMD: &str = "import";
+pub const INPUT_PATH_FLAG: &str = "input-path";
+pub const FORCE_FLAG: &str = "force";
+
+/// Appended to the name of each database directory to form the directory it is unpacked into.
+const STAGING_SUFFIX: &str = ".import";
+/// Appended to the name of each database directory to form the directory it is moved aside to.
+const BACKUP_SUFFIX: &str = ".import-old";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Restores the hot and freezer databases of a beacon node from an archive produced \
+            by the 'export' command. The beacon node must be stopped.",
+        )
+        .arg(
+            Arg::with_name(INPUT_PATH_FLAG)
+                .long(INPUT_PATH_FLAG)
+                .value_name("FILE")
+                .help("The archive produced by the 'export' command.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(FORCE_FLAG)
+                .long(FORCE_FLAG)
+                .help(
+                    "Replace any existing database. It is only deleted once the snapshot has \
+                    been unpacked and verified.",
+                )
+                .takes_value(false),
+        )
+        .arg(freezer_dir_arg())
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let dirs = DatabaseDirs::from_cli(matches)?;
+    let input_path: PathBuf = clap_utils::parse_required(matches, INPUT_PATH_FLAG)?;
+    let force = matches.is_present(FORCE_FLAG);
+
+    if !force {
+        for dir in &[&dirs.hot, &dirs.freezer] {
+            if dir.exists() {
+                return Err(format!(
+                    "A database already exists at {:?}, use --{} to replace it",
+                    dir, FORCE_FLAG
+                ));
+            }
+        }
+    }
+
+    // The snapshot is unpacked and checked alongside the existing databases, which are only
+    // replaced once the snapshot is known to be valid.
+    let staging = DatabaseDirs {
+        hot: sibling_dir(&dirs.hot, STAGING_SUFFIX)?,
+        freezer: sibling_dir(&dirs.freezer, STAGING_SUFFIX)?,
+    };
+    remove_dirs(&staging)?;
+
+    let manifest = match unpack_and_verify::<T>(&input_path, &staging, &mut env) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = remove_dirs(&staging);
+            return Err(e);
+        }
+    };
+
+    swap_dirs(&staging, &dirs)?;
+
+    println!("Imported database from {:?}", input_path);
+    println!("Head block root: {:?}", manifest.head_block_root);
+    println!("Head slot: {}", manifest.head_slot);
+    println!(
+        "Finalized checkpoint: {:?} (epoch {})",
+        manifest.finalized_checkpoint.root, manifest.finalized_checkpoint.epoch
+    );
+
+    Ok(())
+}
+
+/// Unpacks the archive at `path` into `dirs` and checks that the resulting database agrees with
+/// the manifest it was shipped with.
+fn unpack_and_verify<T: EthSpec>(
+    path: &Path,
+    dirs: &DatabaseDirs,
+    env: &mut Environment<T>,
+) -> Result<Manifest, String> {
+    let manifest = unpack_archive(path, dirs)?;
+
+    let log = env.core_context().log().clone();
+    let imported = Manifest::from_store(&dirs.open::<T>(env.eth2_config.spec.clone(), log)?)?;
+    if imported != manifest {
+        return Err(format!(
+            "The imported database does not match the manifest. Manifest: {:?}, database: {:?}",
+            manifest, imported
+        ));
+    }
+
+    Ok(manifest)
+}
+
+/// Moves the verified databases in `staging` into place at `dirs`.
+///
+/// Any existing databases are first moved aside and are only deleted once both of the new
+/// databases are in place. If a rename fails, the existing databases are restored.
+fn swap_dirs(staging: &DatabaseDirs, dirs: &DatabaseDirs) -> Result<(), String> {
+    let backup = DatabaseDirs {
+        hot: sibling_dir(&dirs.hot, BACKUP_SUFFIX)?,
+        freezer: sibling_dir(&dirs.freezer, BACKUP_SUFFIX)?,
+    };
+    for dir in &[&backup.hot, &backup.freezer] {
+        if dir.exists() {
+            return Err(format!(
+                "{:?} exists, it may hold a database from an interrupted import. Move or \
+                delete it before importing.",
+                dir
+            ));
+        }
+    }
+
+    let pairs = [
+        (&staging.hot, &dirs.hot, &backup.hot),
+        (&staging.freezer, &dirs.freezer, &backup.freezer),
+    ];
+    let mut moved_aside = vec![];
+    let mut placed = vec![];
+
+    let result = pairs.iter().try_for_each(|&(new, current, old)| {
+        if current.exists() {
+            fs::rename(current, old)
+                .map_err(|e| format!("Unable to move {:?} to {:?}: {}", current, old, e))?;
+            moved_aside.push((current, old));
+        }
+        fs::rename(new, current)
+            .map_err(|e| format!("Unable to move {:?} to {:?}: {}", new, current, e))?;
+        placed.push(current);
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        for current in placed {
+            let _ = fs::remove_dir_all(current);
+        }
+        for (current, old) in moved_aside {
+            fs::rename(old, current).map_err(|restore_error| {
+                format!(
+                    "{}. Unable to restore the previous database from {:?} to {:?}: {}",
+                    e, old, current, restore_error
+                )
+            })?;
+        }
+        let _ = remove_dirs(staging);
+        return Err(e);
+    }
+
+    remove_dirs(&backup)
+}
+
+/// Returns a path in the same directory as `dir`, with `suffix` appended to its name.
+fn sibling_dir(dir: &Path, suffix: &str) -> Result<PathBuf, String> {
+    let name = dir
+        .file_name()
+        .ok_or_else(|| format!("Invalid database directory: {:?}", dir))?;
+    let mut sibling_name = name.to_os_string();
+    sibling_name.push(suffix);
+    Ok(dir.with_file_name(sibling_name))
+}
+
+/// Removes the directories in `dirs`, if they exist.
+fn remove_dirs(dirs: &DatabaseDirs) -> Result<(), String> {
+    for dir in &[&dirs.hot, &dirs.freezer] {
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|e| format!("Unable to remove {:?}: {}", dir, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the manifest from the archive at `path` and unpacks the hot and freezer databases into
+/// `dirs`, returning the manifest.
+fn unpack_archive(path: &Path, dirs: &DatabaseDirs) -> Result<Manifest, String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {}", path, e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = archive
+        .entries()
+        .map_err(|e| format!("Unable to read archive: {}", e))?;
+
+    let mut manifest_entry = entries
+        .next()
+        .ok_or_else(|| "Archive is empty".to_string())?
+        .map_err(|e| format!("Unable to read archive: {}", e))?;
+    if manifest_entry.path().ok().as_deref() != Some(Path::new(MANIFEST_FILENAME)) {
+        return Err(format!("Archive does not begin with {}", MANIFEST_FILENAME));
+    }
+    let mut manifest_bytes = vec![];
+    manifest_entry
+        .read_to_end(&mut manifest_bytes)
+        .map_err(|e| format!("Unable to read manifest: {}", e))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| format!("Unable to parse manifest: {:?}", e))?;
+
+    if manifest.schema_version != CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Snapshot has schema version {}, this node requires version {}",
+            manifest.schema_version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Unable to read archive: {}", e))?;
+        let entry_path = entry
+            .path()
+            .map_err(|e| format!("Invalid path in archive: {}", e))?
+            .into_owned();
+        let dest = destination(&entry_path, dirs)?;
+        let entry_type = entry.header().entry_type();
+
+        // Links could point outside of the database directories, so only plain files and
+        // directories are accepted.
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err(format!(
+                "Unexpected entry type in archive: {:?} ({:?})",
+                entry_path, entry_type
+            ));
+        }
+
+        if entry_type.is_dir() {
+            fs::create_dir_all(&dest).map_err(|e| format!("Unable to create {:?}: {}", dest, e))?;
+        } else {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Unable to create {:?}: {}", parent, e))?;
+            }
+            entry
+                .unpack(&dest)
+                .map_err(|e| format!("Unable to unpack {:?}: {}", dest, e))?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Maps a path in the archive to its location on disk, rejecting any path which is outside of
+/// the database directories.
+fn destination(entry_path: &Path, dirs: &DatabaseDirs) -> Result<PathBuf, String> {
+    let mut components = entry_path.components();
+    let base = match components.next() {
+        Some(Component::Normal(dir)) if dir == OsStr::new(HOT_DB_ARCHIVE_DIR) => &dirs.hot,
+        Some(Component::Normal(dir)) if dir == OsStr::new(FREEZER_DB_ARCHIVE_DIR) => &dirs.freezer,
+        _ => return Err(format!("Unexpected path in archive: {:?}", entry_path)),
+    };
+
+    let rest = components.as_path();
+    if rest
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Unexpected path in archive: {:?}", entry_path));
+    }
+
+    Ok(base.join(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destination_rejects_paths_outside_databases() {
+        let dirs = DatabaseDirs {
+            hot: PathBuf::from("/data/chain_db"),
+            freezer: PathBuf::from("/data/freezer_db"),
+        };
+
+        assert_eq!(
+            destination(Path::new("chain_db/000005.ldb"), &dirs),
+            Ok(PathBuf::from("/data/chain_db/000005.ldb"))
+        );
+        assert_eq!(
+            destination(Path::new("freezer_db/CURRENT"), &dirs),
+            Ok(PathBuf::from("/data/freezer_db/CURRENT"))
+        );
+        assert!(destination(Path::new("chain_db/../../etc/passwd"), &dirs).is_err());
+        assert!(destination(Path::new("network/key"), &dirs).is_err());
+        assert!(destination(Path::new("/chain_db/CURRENT"), &dirs).is_err());
+    }
+
+    fn test_dirs(base: &Path) -> DatabaseDirs {
+        DatabaseDirs {
+            hot: base.join("chain_db"),
+            freezer: base.join("freezer_db"),
+        }
+    }
+
+    #[test]
+    fn unpack_rejects_links() {
+        use flate2::{write::GzEncoder, Compression};
+        use types::{Checkpoint, Hash256, Slot};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let archive_path = tmp.path().join("snapshot.tar.gz");
+
+        let manifest = Manifest {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            head_block_root: Hash256::zero(),
+            head_slot: Slot::new(0),
+            finalized_checkpoint: Checkpoint::default(),
+            split_slot: Slot::new(0),
+        };
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+
+        let file = File::create(&archive_path).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_FILENAME, &manifest_bytes[..])
+            .unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(0o777);
+        header.set_link_name("/etc").unwrap();
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "chain_db/x", std::io::empty())
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dirs = test_dirs(&tmp.path().join("data"));
+        assert!(unpack_archive(&archive_path, &dirs).is_err());
+        assert!(!dirs.hot.join("x").exists());
+    }
+
+    #[test]
+    fn swap_replaces_existing_databases() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = test_dirs(tmp.path());
+        let staging = DatabaseDirs {
+            hot: sibling_dir(&dirs.hot, STAGING_SUFFIX).unwrap(),
+            freezer: sibling_dir(&dirs.freezer, STAGING_SUFFIX).unwrap(),
+        };
+
+        for (dir, contents) in &[
+            (&dirs.hot, "old"),
+            (&dirs.freezer, "old"),
+            (&staging.hot, "new"),
+            (&staging.freezer, "new"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("CURRENT"), contents).unwrap();
+        }
+
+        swap_dirs(&staging, &dirs).unwrap();
+
+        for dir in &[&dirs.hot, &dirs.freezer] {
+            assert_eq!(fs::read_to_string(dir.join("CURRENT")).unwrap(), "new");
+            assert!(!sibling_dir(dir, BACKUP_SUFFIX).unwrap().exists());
+            assert!(!sibling_dir(dir, STAGING_SUFFIX).unwrap().exists());
+        }
+    }
+
+    #[test]
+    fn swap_restores_existing_databases_on_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = test_dirs(tmp.path());
+        let staging = DatabaseDirs {
+            hot: sibling_dir(&dirs.hot, STAGING_SUFFIX).unwrap(),
+            freezer: sibling_dir(&dirs.freezer, STAGING_SUFFIX).unwrap(),
+        };
+
+        for dir in &[&dirs.hot, &dirs.freezer, &staging.hot] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("CURRENT"), "old").unwrap();
+        }
+        fs::write(staging.hot.join("CURRENT"), "new").unwrap();
+        // The staged freezer database is missing, so moving it into place fails.
+
+        assert!(swap_dirs(&staging, &dirs).is_err());
+
+        for dir in &[&dirs.hot, &dirs.freezer] {
+            assert_eq!(fs::read_to_string(dir.join("CURRENT")).unwrap(), "old");
+            assert!(!sibling_dir(dir, BACKUP_SUFFIX).unwrap().exists());
+        }
+    }
+}
@@ -0,0 +1,38 @@
+mod common;
+pub mod export;
+pub mod import;
+
+use clap::App;
+use clap::ArgMatches;
+use environment::Environment;
+use types::EthSpec;
+
+pub const CMD: &str = "database_manager";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["db", "database-manager", CMD])
+        .about(
+            "Utilities for managing the beacon node database. Exports the hot and freezer \
+            databases to a single compressed snapshot and bootstraps new nodes from such \
+            snapshots.",
+        )
+        .subcommand(export::cli_app())
+        .subcommand(import::cli_app())
+}
+
+/// Run the database manager, returning an error if the operation did not succeed.
+pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
+    match matches.subcommand() {
+        (export::CMD, Some(matches)) => export::cli_run(matches, env)?,
+        (import::CMD, Some(matches)) => import::cli_run(matches, env)?,
+        (unknown, _) => {
+            return Err(format!(
+                "{} is not a valid {} command. See --help.",
+                unknown, CMD
+            ));
+        }
+    }
+
+    Ok(())
+}
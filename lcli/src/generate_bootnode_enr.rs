@@ -1,7 +1,9 @@
 use clap::ArgMatches;
 use eth2_libp2p::{
     discovery::{build_enr, CombinedKey, CombinedKeyExt, Keypair, ENR_FILENAME},
-    NetworkConfig, NETWORK_KEY_FILENAME,
+    interop_keypair,
+    multiaddr::Protocol,
+    EnrExt, NetworkConfig, NETWORK_KEY_FILENAME,
 };
 use std::fs;
 use std::fs::File;
@@ -10,11 +12,16 @@ use std::net::IpAddr;
 use std::path::PathBuf;
 use types::{EnrForkId, EthSpec};
 
+/// The file in which the multiaddr of the node is written, if requested.
+pub const MULTIADDR_FILENAME: &str = "multiaddr";
+
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let ip: IpAddr = clap_utils::parse_required(matches, "ip")?;
     let udp_port: u16 = clap_utils::parse_required(matches, "udp-port")?;
     let tcp_port: u16 = clap_utils::parse_required(matches, "tcp-port")?;
     let output_dir: PathBuf = clap_utils::parse_required(matches, "output-dir")?;
+    let interop_index: Option<u64> = clap_utils::parse_optional(matches, "interop-index")?;
+    let write_multiaddr = matches.is_present("multiaddr");
 
     if output_dir.exists() {
         return Err(format!(
@@ -28,7 +35,11 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     config.enr_udp_port = Some(udp_port);
     config.enr_tcp_port = Some(tcp_port);
 
-    let local_keypair = Keypair::generate_secp256k1();
+    let local_keypair = match interop_index {
+        Some(index) => interop_keypair(index),
+        None => Keypair::generate_secp256k1(),
+    };
+    // The ENR is signed with the network key.
     let enr_key = CombinedKey::from_libp2p(&local_keypair)?;
     let enr = build_enr::<T>(&enr_key, &config, EnrForkId::default())
         .map_err(|e| format!("Unable to create ENR: {:?}", e))?;
@@ -52,5 +63,33 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         .write_all(&secret_bytes)
         .map_err(|e| format!("Unable to write key to {}: {:?}", NETWORK_KEY_FILENAME, e))?;
 
+    if write_multiaddr {
+        let multiaddr = enr
+            .multiaddr_p2p()
+            .into_iter()
+            .find(|multiaddr| {
+                multiaddr
+                    .iter()
+                    .any(|protocol| matches!(protocol, Protocol::Tcp(_)))
+            })
+            .ok_or_else(|| "ENR does not contain a TCP multiaddr".to_string())?;
+
+        let mut multiaddr_file = File::create(output_dir.join(MULTIADDR_FILENAME))
+            .map_err(|e| format!("Unable to create {}: {:?}", MULTIADDR_FILENAME, e))?;
+        multiaddr_file
+            .write_all(multiaddr.to_string().as_bytes())
+            .map_err(|e| {
+                format!(
+                    "Unable to write multiaddr to {}: {:?}",
+                    MULTIADDR_FILENAME, e
+                )
+            })?;
+
+        println!("Multiaddr: {}", multiaddr);
+    }
+
+    println!("ENR: {}", enr.to_base64());
+    println!("Peer ID: {}", enr.peer_id());
+
     Ok(())
 }
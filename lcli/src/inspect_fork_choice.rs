@@ -0,0 +1,182 @@
+use beacon_chain::{PersistedForkChoice, FORK_CHOICE_DB_KEY};
+use clap::ArgMatches;
+use proto_array::ProtoArrayForkChoice;
+use ssz::Decode;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use store::{KeyValueStore, LevelDB};
+use types::{EthSpec, Hash256, Slot};
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let db_path: Option<PathBuf> = clap_utils::parse_optional(matches, "db")?;
+    let file_path: Option<PathBuf> = clap_utils::parse_optional(matches, "file")?;
+    let slot: Option<Slot> = clap_utils::parse_optional::<u64>(matches, "slot")?.map(Slot::new);
+
+    let persisted = match (db_path, file_path) {
+        (Some(path), None) => {
+            info!("Loading fork choice from database at {:?}", path);
+            let db = LevelDB::<T>::open(&path)
+                .map_err(|e| format!("Unable to open database: {:?}", e))?;
+            db.get_item::<PersistedForkChoice>(&Hash256::from_slice(&FORK_CHOICE_DB_KEY))
+                .map_err(|e| format!("Unable to read fork choice from database: {:?}", e))?
+                .ok_or_else(|| "No fork choice found in database".to_string())?
+        }
+        (None, Some(path)) => {
+            info!("Loading fork choice from file at {:?}", path);
+            let mut bytes = vec![];
+            File::open(&path)
+                .and_then(|mut file| file.read_to_end(&mut bytes))
+                .map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
+            PersistedForkChoice::from_ssz_bytes(&bytes)
+                .map_err(|e| format!("Unable to decode fork choice: {:?}", e))?
+        }
+        _ => return Err("Exactly one of --db or --file must be supplied".to_string()),
+    };
+
+    let fc_store = &persisted.fork_choice_store;
+    let queued_attestations = persisted.fork_choice.queued_attestations();
+    let mut proto_array =
+        ProtoArrayForkChoice::from_bytes(persisted.fork_choice.proto_array_bytes())?;
+
+    let justified = *fc_store.justified_checkpoint();
+    let finalized = *fc_store.finalized_checkpoint();
+    let best_justified = *fc_store.best_justified_checkpoint();
+
+    println!("Store time: {}", fc_store.time());
+    println!(
+        "Justified: epoch {}, root {:?}",
+        justified.epoch, justified.root
+    );
+    println!(
+        "Finalized: epoch {}, root {:?}",
+        finalized.epoch, finalized.root
+    );
+    println!(
+        "Best justified: epoch {}, root {:?}",
+        best_justified.epoch, best_justified.root
+    );
+    println!(
+        "Justified balances: {} validators, {} gwei",
+        fc_store.justified_balances().len(),
+        fc_store.justified_balances().iter().sum::<u64>()
+    );
+
+    println!();
+    println!("Queued attestations: {}", queued_attestations.len());
+    for attestation in queued_attestations {
+        println!(
+            "  slot {}, block {:?}, target epoch {}, {} attesters",
+            attestation.slot(),
+            attestation.block_root(),
+            attestation.target_epoch(),
+            attestation.attesting_indices().len()
+        );
+    }
+
+    // Apply any queued attestations that would have been dequeued by `slot`, mirroring the
+    // behaviour of `ForkChoice::update_time`. Justified checkpoint updates that happen at epoch
+    // boundaries require beacon states and are not replayed.
+    if let Some(slot) = slot {
+        let mut applied = 0;
+        for attestation in queued_attestations.iter().filter(|a| a.slot() < slot) {
+            for validator_index in attestation.attesting_indices() {
+                proto_array.process_attestation(
+                    *validator_index as usize,
+                    attestation.block_root(),
+                    attestation.target_epoch(),
+                )?;
+            }
+            applied += 1;
+        }
+        println!();
+        println!(
+            "Applied {} queued attestations before slot {}",
+            applied, slot
+        );
+    }
+
+    let head = proto_array.find_head(
+        justified.epoch,
+        justified.root,
+        finalized.epoch,
+        fc_store.justified_balances(),
+    )?;
+
+    println!();
+    print_tree(&proto_array, head, &justified.root, &finalized.root);
+
+    println!();
+    match slot {
+        Some(slot) => println!("Head at slot {}: {:?}", slot, head),
+        None => println!("Head: {:?}", head),
+    }
+
+    Ok(())
+}
+
+/// Prints the proto array as a tree. Indentation only increases where the chain forks, so long
+/// linear chains remain readable.
+fn print_tree(
+    proto_array: &ProtoArrayForkChoice,
+    head: Hash256,
+    justified_root: &Hash256,
+    finalized_root: &Hash256,
+) {
+    let nodes = &proto_array.core_proto_array().nodes;
+
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = vec![];
+    for (index, node) in nodes.iter().enumerate() {
+        match node.parent {
+            Some(parent) => children.entry(parent).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+
+    println!("Proto array: {} nodes", nodes.len());
+
+    let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|i| (i, 0)).collect();
+    while let Some((index, depth)) = stack.pop() {
+        let node = &nodes[index];
+
+        let mut flags = vec![];
+        if node.root == head {
+            flags.push("head");
+        }
+        if node.root == *justified_root {
+            flags.push("justified");
+        }
+        if node.root == *finalized_root {
+            flags.push("finalized");
+        }
+
+        println!(
+            "{:indent$}slot {}, root {:?}, weight {}, justified {}, finalized {}{}",
+            "",
+            node.slot,
+            node.root,
+            node.weight(),
+            node.justified_epoch,
+            node.finalized_epoch,
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", flags.join(", "))
+            },
+            indent = depth * 2
+        );
+
+        if let Some(node_children) = children.get(&index) {
+            let child_depth = if node_children.len() > 1 {
+                depth + 1
+            } else {
+                depth
+            };
+            for child in node_children.iter().rev() {
+                stack.push((*child, child_depth));
+            }
+        }
+    }
+}
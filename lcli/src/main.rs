@@ -7,9 +7,11 @@ mod deploy_deposit_contract;
 mod eth1_genesis;
 mod generate_bootnode_enr;
 mod insecure_validators;
+mod inspect_fork_choice;
 mod interop_genesis;
 mod new_testnet;
 mod parse_hex;
+mod parse_ssz;
 mod refund_deposit_contract;
 mod skip_slots;
 mod transition_blocks;
@@ -18,6 +20,7 @@ use clap::{App, Arg, ArgMatches, SubCommand};
 use environment::EnvironmentBuilder;
 use log::Level;
 use parse_hex::run_parse_hex;
+use parse_ssz::{run_hash_tree_root, run_pretty_ssz, SSZ_TYPES};
 use std::fs::File;
 use std::path::PathBuf;
 use std::process;
@@ -94,7 +97,7 @@ fn main() {
                         .value_name("SLOT_COUNT")
                         .takes_value(true)
                         .required(true)
-                        .help("Number of slots to skip before outputting a state.."),
+                        .help("Number of slots to skip before outputting a state and its root."),
                 )
                 .arg(
                     Arg::with_name("output")
@@ -150,6 +153,44 @@ fn main() {
                         .help("SSZ encoded as 0x-prefixed hex"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("pretty-ssz")
+                .about("Parses a file of SSZ bytes and prints it as YAML")
+                .arg(
+                    Arg::with_name("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(SSZ_TYPES)
+                        .help("The schema of the supplied SSZ."),
+                )
+                .arg(
+                    Arg::with_name("path")
+                        .value_name("SSZ_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a file of SSZ bytes."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("hash-tree-root")
+                .about("Parses a file of SSZ bytes and prints its hash tree root")
+                .arg(
+                    Arg::with_name("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(SSZ_TYPES)
+                        .help("The schema of the supplied SSZ."),
+                )
+                .arg(
+                    Arg::with_name("path")
+                        .value_name("SSZ_FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a file of SSZ bytes."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("deploy-deposit-contract")
                 .about(
@@ -440,6 +481,20 @@ fn main() {
                         .required(true)
                         .help("The directory in which to create the network dir"),
                 )
+                .arg(
+                    Arg::with_name("interop-index")
+                        .long("interop-index")
+                        .value_name("INDEX")
+                        .takes_value(true)
+                        .help("Derive the network key deterministically from INDEX, rather than \
+                            generating a random key. INSECURE: for interop testing only."),
+                )
+                .arg(
+                    Arg::with_name("multiaddr")
+                        .long("multiaddr")
+                        .help("Also write the libp2p multiaddr of the node (including its peer ID) \
+                            to the network dir."),
+                )
         )
         .subcommand(
             SubCommand::with_name("insecure-validators")
@@ -468,6 +523,39 @@ fn main() {
                         .help("The directory for storing secrets."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("inspect-fork-choice")
+                .about(
+                    "Loads a persisted fork choice and prints the proto array tree, checkpoints \
+                    and queued attestations. Optionally re-runs get_head at a given slot.",
+                )
+                .arg(
+                    Arg::with_name("db")
+                        .long("db")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .conflicts_with("file")
+                        .required_unless("file")
+                        .help("Path to a beacon node hot database (e.g. ~/.lighthouse/beacon/chain_db). \
+                            The beacon node must not be running."),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to a file containing SSZ-encoded PersistedForkChoice bytes."),
+                )
+                .arg(
+                    Arg::with_name("slot")
+                        .long("slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .help("Applies queued attestations from before this slot, then runs \
+                            get_head. Justified checkpoint updates at epoch boundaries are \
+                            not replayed."),
+                )
+        )
         .get_matches();
 
     macro_rules! run_with_spec {
@@ -552,6 +640,11 @@ fn run<T: EthSpec>(
         ("pretty-hex", Some(matches)) => {
             run_parse_hex::<T>(matches).map_err(|e| format!("Failed to pretty print hex: {}", e))
         }
+        ("pretty-ssz", Some(matches)) => {
+            run_pretty_ssz::<T>(matches).map_err(|e| format!("Failed to pretty print ssz: {}", e))
+        }
+        ("hash-tree-root", Some(matches)) => run_hash_tree_root::<T>(matches)
+            .map_err(|e| format!("Failed to compute hash tree root: {}", e)),
         ("deploy-deposit-contract", Some(matches)) => {
             deploy_deposit_contract::run::<T>(env, matches)
                 .map_err(|e| format!("Failed to run deploy-deposit-contract command: {}", e))
@@ -574,6 +667,8 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run generate-bootnode-enr command: {}", e)),
         ("insecure-validators", Some(matches)) => insecure_validators::run(matches)
             .map_err(|e| format!("Failed to run insecure-validators command: {}", e)),
+        ("inspect-fork-choice", Some(matches)) => inspect_fork_choice::run::<T>(matches)
+            .map_err(|e| format!("Failed to run inspect-fork-choice command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
@@ -4,14 +4,101 @@ use serde::Serialize;
 use ssz::Decode;
 use std::fs::File;
 use std::io::Read;
-use types::{EthSpec, SignedBeaconBlock};
+use tree_hash::TreeHash;
+use types::{
+    AggregateAndProof, Attestation, AttestationData, AttesterSlashing, BeaconBlock,
+    BeaconBlockBody, BeaconBlockHeader, BeaconState, Checkpoint, Deposit, DepositData,
+    DepositMessage, Eth1Data, EthSpec, Fork, ForkData, HistoricalBatch, IndexedAttestation,
+    PendingAttestation, ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedBeaconBlockHeader, SignedVoluntaryExit, Validator, VoluntaryExit,
+};
 
-pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+/// The names of the SSZ types supported by `pretty-ssz` and `hash-tree-root`.
+pub const SSZ_TYPES: &[&str] = &[
+    "SignedBeaconBlock",
+    "BeaconBlock",
+    "BeaconBlockBody",
+    "BeaconBlockHeader",
+    "SignedBeaconBlockHeader",
+    "BeaconState",
+    "Attestation",
+    "AttestationData",
+    "IndexedAttestation",
+    "PendingAttestation",
+    "AggregateAndProof",
+    "SignedAggregateAndProof",
+    "AttesterSlashing",
+    "ProposerSlashing",
+    "VoluntaryExit",
+    "SignedVoluntaryExit",
+    "Deposit",
+    "DepositData",
+    "DepositMessage",
+    "Checkpoint",
+    "Eth1Data",
+    "Fork",
+    "ForkData",
+    "HistoricalBatch",
+    "Validator",
+];
+
+/// Calls `$f::<T>($bytes)`, where `T` is the SSZ type named `$type_str`.
+///
+/// Must be kept in sync with `SSZ_TYPES`.
+macro_rules! map_ssz_type {
+    ($type_str: expr, $E: ty, $f: ident, $bytes: expr) => {
+        match $type_str {
+            "SignedBeaconBlock" => $f::<SignedBeaconBlock<$E>>($bytes),
+            "BeaconBlock" => $f::<BeaconBlock<$E>>($bytes),
+            "BeaconBlockBody" => $f::<BeaconBlockBody<$E>>($bytes),
+            "BeaconBlockHeader" => $f::<BeaconBlockHeader>($bytes),
+            "SignedBeaconBlockHeader" => $f::<SignedBeaconBlockHeader>($bytes),
+            "BeaconState" => $f::<BeaconState<$E>>($bytes),
+            "Attestation" => $f::<Attestation<$E>>($bytes),
+            "AttestationData" => $f::<AttestationData>($bytes),
+            "IndexedAttestation" => $f::<IndexedAttestation<$E>>($bytes),
+            "PendingAttestation" => $f::<PendingAttestation<$E>>($bytes),
+            "AggregateAndProof" => $f::<AggregateAndProof<$E>>($bytes),
+            "SignedAggregateAndProof" => $f::<SignedAggregateAndProof<$E>>($bytes),
+            "AttesterSlashing" => $f::<AttesterSlashing<$E>>($bytes),
+            "ProposerSlashing" => $f::<ProposerSlashing>($bytes),
+            "VoluntaryExit" => $f::<VoluntaryExit>($bytes),
+            "SignedVoluntaryExit" => $f::<SignedVoluntaryExit>($bytes),
+            "Deposit" => $f::<Deposit>($bytes),
+            "DepositData" => $f::<DepositData>($bytes),
+            "DepositMessage" => $f::<DepositMessage>($bytes),
+            "Checkpoint" => $f::<Checkpoint>($bytes),
+            "Eth1Data" => $f::<Eth1Data>($bytes),
+            "Fork" => $f::<Fork>($bytes),
+            "ForkData" => $f::<ForkData>($bytes),
+            "HistoricalBatch" => $f::<HistoricalBatch<$E>>($bytes),
+            "Validator" => $f::<Validator>($bytes),
+            other => Err(format!("Unknown type: {}", other)),
+        }
+    };
+}
+
+/// Decodes an SSZ file and prints it as YAML.
+pub fn run_pretty_ssz<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let (type_str, bytes) = read_type_and_bytes::<T>(matches)?;
+    map_ssz_type!(type_str, T, decode_and_print, &bytes)
+}
+
+/// Decodes an SSZ file and prints its hash tree root.
+pub fn run_hash_tree_root<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let (type_str, bytes) = read_type_and_bytes::<T>(matches)?;
+    map_ssz_type!(type_str, T, decode_and_print_root, &bytes)
+}
+
+fn read_type_and_bytes<'a, T: EthSpec>(
+    matches: &'a ArgMatches,
+) -> Result<(&'a str, Vec<u8>), String> {
     let type_str = matches
         .value_of("type")
         .ok_or_else(|| "No type supplied".to_string())?;
     let path = parse_path(matches, "path")?;
 
+    info!("Using {} spec", T::spec_name());
     info!("Type: {:?}", type_str);
 
     let mut bytes = vec![];
@@ -19,12 +106,7 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     file.read_to_end(&mut bytes)
         .map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
 
-    match type_str {
-        "SignedBeaconBlock" => decode_and_print::<SignedBeaconBlock<T>>(&bytes)?,
-        other => return Err(format!("Unknown type: {}", other)),
-    };
-
-    Ok(())
+    Ok((type_str, bytes))
 }
 
 fn decode_and_print<T: Decode + Serialize>(bytes: &[u8]) -> Result<(), String> {
@@ -38,3 +120,11 @@ fn decode_and_print<T: Decode + Serialize>(bytes: &[u8]) -> Result<(), String> {
 
     Ok(())
 }
+
+fn decode_and_print_root<T: Decode + TreeHash>(bytes: &[u8]) -> Result<(), String> {
+    let item = T::from_ssz_bytes(&bytes).map_err(|e| format!("Ssz decode failed: {:?}", e))?;
+
+    println!("{:?}", item.tree_hash_root());
+
+    Ok(())
+}
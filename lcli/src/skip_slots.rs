@@ -5,6 +5,7 @@ use state_processing::per_slot_processing;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
+use tree_hash::TreeHash;
 use types::{BeaconState, EthSpec};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
@@ -44,6 +45,11 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
             .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
     }
 
+    // Print the root to stdout so that it may be compared with the roots of other clients.
+    let state_root = state.tree_hash_root();
+    info!("Post-state slot: {}", state.slot);
+    println!("{:?}", state_root);
+
     let mut output_file =
         File::create(output_path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
 
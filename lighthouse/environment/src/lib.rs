@@ -9,7 +9,11 @@
 
 use eth2_config::Eth2Config;
 use eth2_testnet_config::Eth2TestnetConfig;
-use futures::channel::oneshot;
+use futures::channel::{
+    mpsc::{channel, Receiver, Sender},
+    oneshot,
+};
+use futures::{future, StreamExt};
 
 pub use executor::TaskExecutor;
 use slog::{info, o, Drain, Level, Logger};
@@ -156,11 +160,20 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         mut self,
         eth2_testnet_config: Eth2TestnetConfig<E>,
     ) -> Result<Self, String> {
-        // Create a new chain spec from the default configuration.
-        self.eth2_config.spec = eth2_testnet_config
+        let yaml_config = eth2_testnet_config
             .yaml_config
             .as_ref()
-            .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?
+            .ok_or_else(|| "The testnet directory must contain a spec config".to_string())?;
+
+        yaml_config.validate::<E>().map_err(|e| {
+            format!(
+                "The loaded config is not compatible with the {} spec: {}",
+                &self.eth2_config.spec_constants, e
+            )
+        })?;
+
+        // Create a new chain spec from the default configuration.
+        self.eth2_config.spec = yaml_config
             .apply_to_chain_spec::<E>(&self.eth2_config.spec)
             .ok_or_else(|| {
                 format!(
@@ -189,12 +202,15 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
     /// Consumes the builder, returning an `Environment`.
     pub fn build(self) -> Result<Environment<E>, String> {
         let (signal, exit) = exit_future::signal();
+        let (signal_tx, signal_rx) = channel(1);
         Ok(Environment {
             runtime: self
                 .runtime
                 .ok_or_else(|| "Cannot build environment without runtime".to_string())?,
+            signal_rx: Some(signal_rx),
             signal: Some(signal),
             exit,
+            signal_tx,
             log: self
                 .log
                 .ok_or_else(|| "Cannot build environment without log".to_string())?,
@@ -225,6 +241,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             executor: TaskExecutor {
                 handle: self.executor.handle.clone(),
                 exit: self.executor.exit.clone(),
+                signal_tx: self.executor.signal_tx.clone(),
                 log: self.executor.log.new(o!("service" => service_name)),
             },
             eth_spec_instance: self.eth_spec_instance.clone(),
@@ -247,6 +264,10 @@ impl<E: EthSpec> RuntimeContext<E> {
 /// validator client, or to run tests that involve logging and async task execution.
 pub struct Environment<E: EthSpec> {
     runtime: Runtime,
+    /// Receiver side of an internal shutdown signal.
+    signal_rx: Option<Receiver<&'static str>>,
+    /// Sender to request shutting down.
+    signal_tx: Sender<&'static str>,
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
     log: Logger,
@@ -269,6 +290,7 @@ impl<E: EthSpec> Environment<E> {
         RuntimeContext {
             executor: TaskExecutor {
                 exit: self.exit.clone(),
+                signal_tx: self.signal_tx.clone(),
                 handle: self.runtime().handle().clone(),
                 log: self.log.clone(),
             },
@@ -282,6 +304,7 @@ impl<E: EthSpec> Environment<E> {
         RuntimeContext {
             executor: TaskExecutor {
                 exit: self.exit.clone(),
+                signal_tx: self.signal_tx.clone(),
                 handle: self.runtime().handle().clone(),
                 log: self.log.new(o!("service" => service_name)),
             },
@@ -290,8 +313,10 @@ impl<E: EthSpec> Environment<E> {
         }
     }
 
-    /// Block the current thread until Ctrl+C is received.
-    pub fn block_until_ctrl_c(&mut self) -> Result<(), String> {
+    /// Block the current thread until Ctrl+C is received or a task requests a shutdown.
+    ///
+    /// Returns the reason given by the task, or `"Received Ctrl+C"`.
+    pub fn block_until_shutdown_requested(&mut self) -> Result<&'static str, String> {
         let (ctrlc_send, ctrlc_oneshot) = oneshot::channel();
         let ctrlc_send_c = RefCell::new(Some(ctrlc_send));
         ctrlc::set_handler(move || {
@@ -301,10 +326,21 @@ impl<E: EthSpec> Environment<E> {
         })
         .map_err(|e| format!("Could not set ctrlc handler: {:?}", e))?;
 
-        // Block this thread until Crtl+C is pressed.
-        self.runtime()
-            .block_on(ctrlc_oneshot)
-            .map_err(|e| format!("Ctrlc oneshot failed: {:?}", e))
+        let mut signal_rx = self
+            .signal_rx
+            .take()
+            .ok_or_else(|| "Inner shutdown already received".to_string())?;
+
+        // Block this thread until Crtl+C is pressed or a task sends a shutdown reason.
+        match self
+            .runtime()
+            .block_on(future::select(ctrlc_oneshot, signal_rx.next()))
+        {
+            future::Either::Left((Ok(()), _)) => Ok("Received Ctrl+C"),
+            future::Either::Left((Err(e), _)) => Err(format!("Ctrlc oneshot failed: {:?}", e)),
+            future::Either::Right((Some(reason), _)) => Ok(reason),
+            future::Either::Right((None, _)) => Err("Shutdown channel closed".to_string()),
+        }
     }
 
     /// Shutdown the `tokio` runtime when all tasks are idle.
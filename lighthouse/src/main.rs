@@ -24,6 +24,13 @@ pub const CLIENT_CONFIG_FILENAME: &str = "beacon-node.toml";
 pub const ETH2_CONFIG_FILENAME: &str = "eth2-spec.toml";
 
 fn main() {
+    // Binaries built with the ADX instructions crash with an illegal instruction on older CPUs,
+    // so refuse to run on them before any cryptography is performed.
+    if let Err(e) = bls::check_cpu_features() {
+        eprintln!("{}", e);
+        exit(1);
+    }
+
     // Parse the CLI parameters.
     let matches = App::new("Lighthouse")
         .version(VERSION)
@@ -104,6 +111,8 @@ fn main() {
         .subcommand(boot_node::cli_app())
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
+        .subcommand(validator_manager::cli_app())
+        .subcommand(database_manager::cli_app())
         .get_matches();
 
     // boot node subcommand circumvents the environment
@@ -203,11 +212,35 @@ fn run<E: EthSpec>(
         return Ok(());
     };
 
+    if let Some(sub_matches) = matches.subcommand_matches(validator_manager::CMD) {
+        // Pass the entire `environment` to the validator manager so it can run blocking
+        // operations.
+        validator_manager::run(sub_matches, environment)?;
+
+        // Exit as soon as validator manager returns control.
+        return Ok(());
+    };
+
+    if let Some(sub_matches) = matches.subcommand_matches(database_manager::CMD) {
+        // Pass the entire `environment` to the database manager so it can open the database
+        // with the correct spec.
+        database_manager::run(sub_matches, environment)?;
+
+        // Exit as soon as database manager returns control.
+        return Ok(());
+    };
+
     warn!(
         log,
         "Ethereum 2.0 is pre-release. This software is experimental."
     );
 
+    info!(
+        log,
+        "Using BLS backend";
+        "backend" => bls::BACKEND_NAME
+    );
+
     if !matches.is_present("testnet-dir") {
         info!(
             log,
@@ -264,9 +297,9 @@ fn run<E: EthSpec>(
         return Err("No subcommand supplied.".into());
     }
 
-    // Block this thread until Crtl+C is pressed.
-    environment.block_until_ctrl_c()?;
-    info!(log, "Shutting down..");
+    // Block this thread until Crtl+C is pressed or a service requests a shutdown.
+    let shutdown_reason = environment.block_until_shutdown_requested()?;
+    info!(log, "Shutting down.."; "reason" => shutdown_reason);
 
     environment.fire_signal();
     drop(beacon_node);
@@ -6,6 +6,7 @@ use crate::cases::ssz_static::{check_serialization, check_tree_hash};
 use crate::decode::yaml_decode_file;
 use serde::{de::Error as SerdeError, Deserializer};
 use serde_derive::Deserialize;
+use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -216,6 +217,7 @@ fn ssz_generic_test<T: SszStaticType>(path: &Path) -> Result<(), Error> {
     // TODO: signing root (annoying because of traits)
     if let Some(value) = value {
         check_serialization(&value, &serialized)?;
+        check_union_and_stable_container(&value, &serialized)?;
 
         if let Some(ref meta) = meta {
             check_tree_hash(&meta.root, value.tree_hash_root().as_bytes())?;
@@ -231,6 +233,56 @@ fn ssz_generic_test<T: SszStaticType>(path: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Checks that `value` round-trips through a union and a stable container, and that each of them
+/// encodes `value` as the `serialized` bytes from the spec tests, following the union selector or
+/// the `Bitvector` of active fields.
+fn check_union_and_stable_container<T: SszStaticType>(
+    value: &T,
+    serialized: &[u8],
+) -> Result<(), Error> {
+    let union = TestUnion::Value(value.clone());
+    let mut expected = ssz::encode_union_index(1).to_vec();
+    expected.extend_from_slice(serialized);
+    compare_result::<Vec<u8>, Error>(&Ok(union.as_ssz_bytes()), &Some(expected.clone()))?;
+    compare_result(&TestUnion::from_ssz_bytes(&expected), &Some(union))?;
+
+    let stable_container = TestStableContainer {
+        value: Some(value.clone()),
+    };
+    // The active fields are encoded as a container, so a variable-length value is preceded by its
+    // offset within that container.
+    let mut expected = vec![0b0000_0001];
+    if !<T as Encode>::is_ssz_fixed_len() {
+        expected.extend_from_slice(&(ssz::BYTES_PER_LENGTH_OFFSET as u32).to_le_bytes());
+    }
+    expected.extend_from_slice(serialized);
+    compare_result::<Vec<u8>, Error>(
+        &Ok(stable_container.as_ssz_bytes()),
+        &Some(expected.clone()),
+    )?;
+    compare_result(
+        &TestStableContainer::from_ssz_bytes(&expected),
+        &Some(stable_container),
+    )?;
+
+    Ok(())
+}
+
+/// A union which holds either nothing or some value of type `T`.
+#[derive(Debug, PartialEq, Decode, Encode)]
+#[ssz(enum_behaviour = "union")]
+enum TestUnion<T: ssz::Encode + ssz::Decode> {
+    None,
+    Value(T),
+}
+
+/// A stable container with a single field of type `T`.
+#[derive(Debug, PartialEq, Decode, Encode)]
+#[ssz(struct_behaviour = "stable_container", max_fields = "8")]
+struct TestStableContainer<T: ssz::Encode + ssz::Decode> {
+    value: Option<T>,
+}
+
 // Containers for SSZ generic tests
 #[derive(Debug, Clone, Default, PartialEq, Decode, Encode, TreeHash, Deserialize)]
 struct SingleFieldTestStruct {
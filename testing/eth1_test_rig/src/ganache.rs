@@ -13,10 +13,83 @@ use web3::{
 
 /// How long we will wait for ganache to indicate that it is ready.
 const GANACHE_STARTUP_TIMEOUT_MILLIS: u64 = 10_000;
+/// The environment variable which selects the local eth1 node used for testing.
+pub const ETH1_TEST_NODE_ENV_VAR: &str = "ETH1_TEST_NODE";
+/// The mnemonic from which the accounts of the local eth1 node are derived.
+const MNEMONIC: &str = "vast thought differ pull jewel broom cook wrist tribe word before omit";
+
+/// The local eth1 node implementations which may be used for testing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eth1NodeKind {
+    /// [`ganache-cli`](https://github.com/trufflesuite/ganache-cli).
+    Ganache,
+    /// [`anvil`](https://github.com/foundry-rs/foundry), from the Foundry toolkit.
+    Anvil,
+}
+
+impl Eth1NodeKind {
+    /// Reads the kind of node from the `ETH1_TEST_NODE` environment variable (either `ganache` or
+    /// `anvil`), defaulting to `ganache` if it is not set.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var(ETH1_TEST_NODE_ENV_VAR) {
+            Ok(value) => match value.as_str() {
+                "ganache" => Ok(Eth1NodeKind::Ganache),
+                "anvil" => Ok(Eth1NodeKind::Anvil),
+                other => Err(format!(
+                    "Unknown {} \"{}\", expected \"ganache\" or \"anvil\"",
+                    ETH1_TEST_NODE_ENV_VAR, other
+                )),
+            },
+            Err(_) => Ok(Eth1NodeKind::Ganache),
+        }
+    }
 
-/// Provides a dedicated `ganachi-cli` instance with a connected `Web3` instance.
+    /// The name of the node's binary, which must be available on `PATH`.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            Eth1NodeKind::Ganache => "ganache-cli",
+            Eth1NodeKind::Anvil => "anvil",
+        }
+    }
+
+    /// Returns the command which starts the node listening on `port`.
+    fn command(&self, port: u16) -> Command {
+        let mut command = Command::new(self.binary());
+        command.stdout(Stdio::piped());
+
+        match self {
+            Eth1NodeKind::Ganache => command
+                .arg("--defaultBalanceEther")
+                .arg("1000000000")
+                .arg("--gasLimit")
+                .arg("1000000000")
+                .arg("--accounts")
+                .arg("10")
+                .arg("--port")
+                .arg(format!("{}", port))
+                .arg("--mnemonic")
+                .arg(format!("\"{}\"", MNEMONIC)),
+            Eth1NodeKind::Anvil => command
+                .arg("--balance")
+                .arg("1000000000")
+                .arg("--gas-limit")
+                .arg("1000000000")
+                .arg("--accounts")
+                .arg("10")
+                .arg("--port")
+                .arg(format!("{}", port))
+                .arg("--mnemonic")
+                .arg(MNEMONIC),
+        };
+
+        command
+    }
+}
+
+/// Provides a dedicated local eth1 node instance (`ganache-cli` by default, see `Eth1NodeKind`)
+/// with a connected `Web3` instance.
 ///
-/// Requires that `ganachi-cli` is installed and available on `PATH`.
+/// Requires that the node's binary is installed and available on `PATH`.
 pub struct GanacheInstance {
     pub port: u16,
     child: Child,
@@ -25,31 +98,25 @@ pub struct GanacheInstance {
 }
 
 impl GanacheInstance {
-    /// Start a new `ganache-cli` process, waiting until it indicates that it is ready to accept
-    /// RPC connections.
+    /// Start a new process of the node selected by the `ETH1_TEST_NODE` environment variable,
+    /// waiting until it indicates that it is ready to accept RPC connections.
     pub fn new() -> Result<Self, String> {
+        Self::new_with_kind(Eth1NodeKind::from_env()?)
+    }
+
+    /// Start a new process of the given `kind` of node, waiting until it indicates that it is
+    /// ready to accept RPC connections.
+    pub fn new_with_kind(kind: Eth1NodeKind) -> Result<Self, String> {
         let port = unused_port()?;
+        let binary = kind.binary();
 
-        let mut child = Command::new("ganache-cli")
-            .stdout(Stdio::piped())
-            .arg("--defaultBalanceEther")
-            .arg("1000000000")
-            .arg("--gasLimit")
-            .arg("1000000000")
-            .arg("--accounts")
-            .arg("10")
-            .arg("--port")
-            .arg(format!("{}", port))
-            .arg("--mnemonic")
-            .arg("\"vast thought differ pull jewel broom cook wrist tribe word before omit\"")
-            .spawn()
-            .map_err(|e| {
-                format!(
-                    "Failed to start ganache-cli. \
-                     Is it ganache-cli installed and available on $PATH? Error: {:?}",
-                    e
-                )
-            })?;
+        let mut child = kind.command(port).spawn().map_err(|e| {
+            format!(
+                "Failed to start {}. \
+                 Is it {} installed and available on $PATH? Error: {:?}",
+                binary, binary, e
+            )
+        })?;
 
         let stdout = child
             .stdout
@@ -59,9 +126,10 @@ impl GanacheInstance {
         let mut reader = BufReader::new(stdout);
         loop {
             if start + Duration::from_millis(GANACHE_STARTUP_TIMEOUT_MILLIS) <= Instant::now() {
-                break Err(
-                    "Timed out waiting for ganache to start. Is ganache-cli installed?".to_string(),
-                );
+                break Err(format!(
+                    "Timed out waiting for {} to start. Is {} installed?",
+                    binary, binary
+                ));
             }
 
             let mut line = String::new();
@@ -1,24 +1,28 @@
 //! Provides utilities for deploying and manipulating the eth2 deposit contract on the eth1 chain.
 //!
 //! Presently used with [`ganache-cli`](https://github.com/trufflesuite/ganache-cli) to simulate
-//! the deposit contract for testing beacon node eth1 integration.
+//! the deposit contract for testing beacon node eth1 integration. Alternatively,
+//! [`anvil`](https://github.com/foundry-rs/foundry) may be used by setting the `ETH1_TEST_NODE`
+//! environment variable to `anvil`.
 //!
 //! Not tested to work with actual clients (e.g., geth). It should work fine, however there may be
 //! some initial issues.
 mod ganache;
 
 use deposit_contract::{
-    encode_eth1_tx_data, testnet, ABI, BYTECODE, CONTRACT_DEPLOY_GAS, DEPOSIT_GAS,
+    decode_deposit_log_data, deposit_event_topic, encode_eth1_tx_data, testnet, ABI, BYTECODE,
+    CONTRACT_DEPLOY_GAS, DEPOSIT_GAS,
 };
 use futures::compat::Future01CompatExt;
 use ganache::GanacheInstance;
+pub use ganache::{Eth1NodeKind, ETH1_TEST_NODE_ENV_VAR};
 use std::time::Duration;
 use tokio::time::delay_for;
 use types::DepositData;
 use types::{test_utils::generate_deterministic_keypair, EthSpec, Hash256, Keypair, Signature};
 use web3::contract::{Contract, Options};
 use web3::transports::Http;
-use web3::types::{Address, TransactionRequest, U256};
+use web3::types::{Address, BlockNumber, FilterBuilder, TransactionRequest, H256, U256};
 use web3::Web3;
 
 pub const DEPLOYER_ACCOUNTS_INDEX: usize = 0;
@@ -217,6 +221,37 @@ impl DepositContract {
         Ok(())
     }
 
+    /// Returns all of the deposits in the `DepositEvent` logs of the deposit contract, alongside
+    /// their indices, in the order in which they were made.
+    pub async fn deposit_logs(&self) -> Result<Vec<(DepositData, u64)>, String> {
+        let topic = deposit_event_topic()
+            .map_err(|e| format!("Failed to get deposit event topic: {:?}", e))?;
+        let filter = FilterBuilder::default()
+            .address(vec![self.contract.address()])
+            .topics(
+                Some(vec![H256::from_slice(topic.as_bytes())]),
+                None,
+                None,
+                None,
+            )
+            .from_block(BlockNumber::Earliest)
+            .to_block(BlockNumber::Latest)
+            .build();
+
+        self.web3
+            .eth()
+            .logs(filter)
+            .compat()
+            .await
+            .map_err(|e| format!("Failed to get deposit logs: {:?}", e))?
+            .into_iter()
+            .map(|log| {
+                decode_deposit_log_data(&log.data.0)
+                    .map_err(|e| format!("Failed to decode deposit log: {:?}", e))
+            })
+            .collect()
+    }
+
     /// Peforms many deposits, each preceded by a delay.
     pub async fn deposit_multiple(&self, deposits: Vec<DelayThenDeposit>) -> Result<(), String> {
         for deposit in deposits.into_iter() {
@@ -1,6 +1,9 @@
 use crate::local_network::LocalNetwork;
+use eth1_test_rig::DepositContract;
 use std::time::Duration;
-use types::{Epoch, EthSpec, Slot, Unsigned};
+use types::{
+    test_utils::generate_deterministic_keypair, Epoch, EthSpec, PublicKeyBytes, Slot, Unsigned,
+};
 
 /// Checks that all of the validators have on-boarded by the start of the second eth1 voting
 /// period.
@@ -42,6 +45,38 @@ pub async fn verify_first_finalization<E: EthSpec>(
     Ok(())
 }
 
+/// Checks that the deposit contract logged a deposit for each of the `expected_deposit_count`
+/// deterministic validators, in order.
+pub async fn verify_deposit_logs(
+    deposit_contract: DepositContract,
+    expected_deposit_count: usize,
+) -> Result<(), String> {
+    let deposits = deposit_contract.deposit_logs().await?;
+
+    if deposits.len() != expected_deposit_count {
+        return Err(format!(
+            "Deposit contract logged {} deposits, expected {}",
+            deposits.len(),
+            expected_deposit_count
+        ));
+    }
+
+    for (i, (deposit_data, index)) in deposits.into_iter().enumerate() {
+        if index != i as u64 {
+            return Err(format!("Deposit {} was logged with index {}", i, index));
+        }
+
+        if deposit_data.pubkey != PublicKeyBytes::from(generate_deterministic_keypair(i).pk) {
+            return Err(format!(
+                "Deposit {} was logged with an unexpected pubkey",
+                i
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Delays for `epochs`, plus half a slot extra.
 pub async fn epoch_delay(epochs: Epoch, slot_duration: Duration, slots_per_epoch: u64) {
     let duration = slot_duration * (epochs.as_u64() * slots_per_epoch) as u32 + slot_duration / 2;
@@ -89,10 +89,11 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         });
 
         // Submit deposits to the deposit contract.
+        let depositor = deposit_contract.clone();
         tokio::spawn(async move {
             for i in 0..total_validator_count {
                 println!("Submitting deposit for validator {}...", i);
-                let _ = deposit_contract
+                let _ = depositor
                     .deposit_deterministic_async::<E>(i, deposit_amount)
                     .await;
             }
@@ -147,7 +148,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
          * tests start at the right time. Whilst this is works well for now, it's subject to
          * breakage by changes to the VC.
          */
-        let (finalization, block_prod, validator_count, onboarding) = futures::join!(
+        let (finalization, block_prod, validator_count, onboarding, deposits) = futures::join!(
             // Check that the chain finalizes at the first given opportunity.
             checks::verify_first_finalization(network.clone(), slot_duration),
             // Check that a block is produced at every slot.
@@ -168,13 +169,20 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
                 network.clone(),
                 slot_duration,
                 total_validator_count,
-            )
+            ),
+            // Check that the deposit contract logged each of the deposits once they have all been
+            // onboarded.
+            async {
+                checks::epoch_delay(Epoch::new(2), slot_duration, E::slots_per_epoch()).await;
+                checks::verify_deposit_logs(deposit_contract.clone(), total_validator_count).await
+            }
         );
 
         block_prod?;
         finalization?;
         validator_count?;
         onboarding?;
+        deposits?;
 
         // The `final_future` either completes immediately or never completes, depending on the value
         // of `end_after_checks`.
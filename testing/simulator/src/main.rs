@@ -2,6 +2,8 @@
 //! `v` validators. A deposit contract is deployed at the start of the simulation using a local
 //! `ganache-cli` instance (you must have `ganache-cli` installed and avaliable on your path). All
 //! beacon nodes independently listen for genesis from the deposit contract, then start operating.
+//! To use `anvil` instead of `ganache-cli`, set the `ETH1_TEST_NODE` environment variable to
+//! `anvil`.
 //!
 //! As the simulation runs, there are checks made to ensure that all components are running
 //! correctly. If any of these checks fail, the simulation will exit immediately.
@@ -5,14 +5,19 @@ mod signed_attestation;
 mod signed_block;
 mod slashing_database;
 mod test_utils;
+mod validator_records;
 
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::SlashingDatabase;
+pub use crate::validator_records::ValidatorRecords;
 use rusqlite::Error as SQLError;
 use std::io::{Error as IOError, ErrorKind};
 use std::string::ToString;
-use types::{Hash256, PublicKey};
+use types::{AttestationData, BeaconBlockHeader, Hash256, PublicKey};
+
+/// The filename within the validator client data directory of the slashing protection database.
+pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
 
 /// The attestation or block is not safe to sign.
 ///
@@ -27,6 +32,20 @@ pub enum NotSafe {
     SQLPoolError(String),
 }
 
+impl NotSafe {
+    /// Returns `true` if the message was refused because signing it would be slashable, rather
+    /// than because of an error or an unregistered validator.
+    pub fn is_slashable(&self) -> bool {
+        match self {
+            NotSafe::InvalidBlock(_) | NotSafe::InvalidAttestation(_) => true,
+            NotSafe::UnregisteredValidator(_)
+            | NotSafe::IOError(_)
+            | NotSafe::SQLError(_)
+            | NotSafe::SQLPoolError(_) => false,
+        }
+    }
+}
+
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
 #[derive(PartialEq, Debug)]
 pub enum Safe {
@@ -36,6 +55,25 @@ pub enum Safe {
     Valid,
 }
 
+/// A message which must be checked by `SlashingDatabase::check_and_insert` before it is signed.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SigningRequest<'a> {
+    BlockProposal(&'a BeaconBlockHeader),
+    Attestation(&'a AttestationData),
+}
+
+impl<'a> From<&'a BeaconBlockHeader> for SigningRequest<'a> {
+    fn from(block_header: &'a BeaconBlockHeader) -> Self {
+        SigningRequest::BlockProposal(block_header)
+    }
+}
+
+impl<'a> From<&'a AttestationData> for SigningRequest<'a> {
+    fn from(attestation: &'a AttestationData) -> Self {
+        SigningRequest::Attestation(attestation)
+    }
+}
+
 /// Safely parse a `Hash256` from the given `column` of an SQLite `row`.
 fn hash256_from_row(column: usize, row: &rusqlite::Row) -> rusqlite::Result<Hash256> {
     use rusqlite::{types::Type, Error};
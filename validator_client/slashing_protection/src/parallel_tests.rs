@@ -22,13 +22,72 @@ fn block_same_slot() {
     let num_blocks = 10;
     let results = (0..num_blocks)
         .into_par_iter()
-        .map(|_| slashing_db.check_and_insert_block_proposal(&pk, &block(1), DEFAULT_DOMAIN))
+        .map(|_| slashing_db.check_and_insert(&pk, &block(1), DEFAULT_DOMAIN))
         .collect::<Vec<_>>();
 
     let num_successes = results.iter().filter(|res| res.is_ok()).count();
     assert_eq!(num_successes, 1);
 }
 
+#[test]
+fn block_same_slot_racing_proposals_are_slashable() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pk = pubkey(0);
+
+    slashing_db.register_validator(&pk).unwrap();
+
+    // Distinct blocks racing for the same slot: exactly one may be signed, and the others must be
+    // refused as slashable rather than failing for some other reason.
+    let num_blocks = 10;
+    let results = (0..num_blocks)
+        .into_par_iter()
+        .map(|_| slashing_db.check_and_insert(&pk, &block(1), DEFAULT_DOMAIN))
+        .collect::<Vec<_>>();
+
+    let num_valid = results
+        .iter()
+        .filter(|res| **res == Ok(Safe::Valid))
+        .count();
+    assert_eq!(num_valid, 1);
+    assert!(results
+        .iter()
+        .filter_map(|res| res.as_ref().err())
+        .all(NotSafe::is_slashable));
+}
+
+#[test]
+fn block_same_slot_racing_duplicates() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pk = pubkey(0);
+
+    slashing_db.register_validator(&pk).unwrap();
+
+    // The same block submitted concurrently is only recorded once, and is never slashable.
+    let block = block(1);
+    let num_blocks = 10;
+    let results = (0..num_blocks)
+        .into_par_iter()
+        .map(|_| slashing_db.check_and_insert(&pk, &block, DEFAULT_DOMAIN))
+        .collect::<Vec<_>>();
+
+    let num_valid = results
+        .iter()
+        .filter(|res| **res == Ok(Safe::Valid))
+        .count();
+    let num_same_data = results
+        .iter()
+        .filter(|res| **res == Ok(Safe::SameData))
+        .count();
+    assert_eq!(num_valid, 1);
+    assert_eq!(num_same_data, num_blocks - 1);
+}
+
 #[test]
 fn attestation_same_target() {
     let dir = tempdir().unwrap();
@@ -44,7 +103,7 @@ fn attestation_same_target() {
     let results = (0..num_attestations)
         .into_par_iter()
         .map(|i| {
-            slashing_db.check_and_insert_attestation(
+            slashing_db.check_and_insert(
                 &pk,
                 &attestation_data_builder(i, num_attestations),
                 DEFAULT_DOMAIN,
@@ -73,7 +132,7 @@ fn attestation_surround_fest() {
         .into_par_iter()
         .map(|i| {
             let att = attestation_data_builder(i, 2 * num_attestations - i);
-            slashing_db.check_and_insert_attestation(&pk, &att, DEFAULT_DOMAIN)
+            slashing_db.check_and_insert(&pk, &att, DEFAULT_DOMAIN)
         })
         .collect::<Vec<_>>();
 
@@ -1,8 +1,9 @@
 use crate::hash256_from_row;
+use serde_derive::{Deserialize, Serialize};
 use types::{AttestationData, Epoch, Hash256, SignedRoot};
 
 /// An attestation that has previously been signed.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SignedAttestation {
     pub source_epoch: Epoch,
     pub target_epoch: Epoch,
@@ -1,8 +1,9 @@
 use crate::hash256_from_row;
+use serde_derive::{Deserialize, Serialize};
 use types::{BeaconBlockHeader, Hash256, SignedRoot, Slot};
 
 /// A block that has previously been signed.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SignedBlock {
     pub slot: Slot,
     pub signing_root: Hash256,
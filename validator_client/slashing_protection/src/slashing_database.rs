@@ -1,6 +1,6 @@
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
-use crate::{NotSafe, Safe, SignedAttestation, SignedBlock};
+use crate::{NotSafe, Safe, SignedAttestation, SignedBlock, SigningRequest, ValidatorRecords};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs::{File, OpenOptions};
@@ -354,54 +354,141 @@ impl SlashingDatabase {
         Ok(())
     }
 
-    /// Check a block proposal for slash safety, and if it is safe, record it in the database.
+    /// Check a block proposal or attestation for slash safety, and if it is safe, record it in the
+    /// database.
     ///
     /// The checking and inserting happen atomically and exclusively. We enforce exclusivity
     /// to prevent concurrent checks and inserts from resulting in slashable data being inserted.
     ///
-    /// This is the safe, externally-callable interface for checking block proposals.
-    pub fn check_and_insert_block_proposal(
+    /// This is the only externally-callable interface for checking messages, and a message must
+    /// not be signed unless it returns `Ok`.
+    pub fn check_and_insert<'a>(
         &self,
         validator_pubkey: &PublicKey,
-        block_header: &BeaconBlockHeader,
+        request: impl Into<SigningRequest<'a>>,
         domain: Hash256,
     ) -> Result<Safe, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
 
-        let safe = self.check_block_proposal(&txn, validator_pubkey, block_header, domain)?;
-
-        if safe != Safe::SameData {
-            self.insert_block_proposal(&txn, validator_pubkey, block_header, domain)?;
-        }
+        let safe = match request.into() {
+            SigningRequest::BlockProposal(block_header) => {
+                let safe =
+                    self.check_block_proposal(&txn, validator_pubkey, block_header, domain)?;
+                if safe != Safe::SameData {
+                    self.insert_block_proposal(&txn, validator_pubkey, block_header, domain)?;
+                }
+                safe
+            }
+            SigningRequest::Attestation(attestation) => {
+                let safe = self.check_attestation(&txn, validator_pubkey, attestation, domain)?;
+                if safe != Safe::SameData {
+                    self.insert_attestation(&txn, validator_pubkey, attestation, domain)?;
+                }
+                safe
+            }
+        };
 
         txn.commit()?;
         Ok(safe)
     }
 
-    /// Check an attestation for slash safety, and if it is safe, record it in the database.
+    /// Export all of the signed blocks and attestations recorded for `validator_pubkey`.
     ///
-    /// The checking and inserting happen atomically and exclusively. We enforce exclusivity
-    /// to prevent concurrent checks and inserts from resulting in slashable data being inserted.
-    ///
-    /// This is the safe, externally-callable interface for checking attestations.
-    pub fn check_and_insert_attestation(
+    /// Used when moving a validator to another validator client, so that its slashing protection
+    /// history travels with it.
+    pub fn export_validator_records(
         &self,
         validator_pubkey: &PublicKey,
-        attestation: &AttestationData,
-        domain: Hash256,
-    ) -> Result<Safe, NotSafe> {
+    ) -> Result<ValidatorRecords, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+
+        let validator_id = Self::get_validator_id(&txn, validator_pubkey)?;
+
+        let signed_blocks = txn
+            .prepare(
+                "SELECT slot, signing_root
+                 FROM signed_blocks
+                 WHERE validator_id = ?1
+                 ORDER BY slot ASC",
+            )?
+            .query_map(params![validator_id], SignedBlock::from_row)?
+            .collect::<Result<_, _>>()?;
+
+        let signed_attestations = txn
+            .prepare(
+                "SELECT source_epoch, target_epoch, signing_root
+                 FROM signed_attestations
+                 WHERE validator_id = ?1
+                 ORDER BY target_epoch ASC",
+            )?
+            .query_map(params![validator_id], SignedAttestation::from_row)?
+            .collect::<Result<_, _>>()?;
+
+        txn.commit()?;
+
+        Ok(ValidatorRecords {
+            pubkey: validator_pubkey.clone(),
+            signed_blocks,
+            signed_attestations,
+        })
+    }
+
+    /// Import the signed blocks and attestations in `records`, registering the validator if it is
+    /// not already known to this database.
+    ///
+    /// Records that conflict with existing records (same slot or same target epoch) are ignored.
+    /// This is safe because the existing record already prevents signing at that slot or target
+    /// epoch, and surround checks consider every record regardless of its origin.
+    pub fn import_validator_records(&self, records: &ValidatorRecords) -> Result<(), NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
 
-        let safe = self.check_attestation(&txn, validator_pubkey, attestation, domain)?;
+        let validator_id = match Self::get_validator_id(&txn, &records.pubkey) {
+            Ok(id) => id,
+            Err(NotSafe::UnregisteredValidator(_)) => {
+                txn.execute(
+                    "INSERT INTO validators (public_key) VALUES (?1)",
+                    params![records.pubkey.as_hex_string()],
+                )?;
+                txn.last_insert_rowid()
+            }
+            Err(e) => return Err(e),
+        };
 
-        if safe != Safe::SameData {
-            self.insert_attestation(&txn, validator_pubkey, attestation, domain)?;
+        {
+            let mut stmt = txn.prepare(
+                "INSERT OR IGNORE INTO signed_blocks (validator_id, slot, signing_root)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            for block in &records.signed_blocks {
+                stmt.execute(params![
+                    validator_id,
+                    block.slot,
+                    block.signing_root.as_bytes()
+                ])?;
+            }
+        }
+
+        {
+            let mut stmt = txn.prepare(
+                "INSERT OR IGNORE INTO signed_attestations
+                 (validator_id, source_epoch, target_epoch, signing_root)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for attestation in &records.signed_attestations {
+                stmt.execute(params![
+                    validator_id,
+                    attestation.source_epoch,
+                    attestation.target_epoch,
+                    attestation.signing_root.as_bytes()
+                ])?;
+            }
         }
 
         txn.commit()?;
-        Ok(safe)
+        Ok(())
     }
 }
 
@@ -410,6 +497,7 @@ mod tests {
     use super::*;
     use crate::test_utils::pubkey;
     use tempfile::tempdir;
+    use types::Slot;
 
     #[test]
     fn open_non_existent_error() {
@@ -468,4 +556,39 @@ mod tests {
         let db2 = SlashingDatabase::open(&file).unwrap();
         check(&db2);
     }
+
+    #[test]
+    fn export_import_records() {
+        let dir = tempdir().unwrap();
+        let src = SlashingDatabase::create(&dir.path().join("src.sqlite")).unwrap();
+        let pk = pubkey(0);
+        src.register_validator(&pk).unwrap();
+
+        let header = BeaconBlockHeader {
+            slot: Slot::new(10),
+            proposer_index: 0,
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::zero(),
+        };
+        src.check_and_insert(&pk, &header, Hash256::zero()).unwrap();
+
+        let records = src.export_validator_records(&pk).unwrap();
+        assert_eq!(records.signed_blocks.len(), 1);
+        assert!(records.signed_attestations.is_empty());
+        drop(src);
+
+        let dst = SlashingDatabase::create(&dir.path().join("dst.sqlite")).unwrap();
+        dst.import_validator_records(&records).unwrap();
+        // Importing twice must be idempotent.
+        dst.import_validator_records(&records).unwrap();
+        assert_eq!(dst.export_validator_records(&pk).unwrap(), records);
+
+        // The imported history must prevent a conflicting proposal.
+        let mut conflicting = header;
+        conflicting.state_root = Hash256::repeat_byte(1);
+        assert!(dst
+            .check_and_insert(&pk, &conflicting, Hash256::zero())
+            .is_err());
+    }
 }
@@ -60,7 +60,7 @@ impl<T> Test<T> {
 pub struct StreamTest<T> {
     /// Validators to register.
     pub registered_validators: Vec<PublicKey>,
-    /// Vector of cases and the value expected when calling `check_and_insert_X`.
+    /// Vector of cases and the value expected when calling `check_and_insert`.
     pub cases: Vec<Test<T>>,
 }
 
@@ -85,7 +85,7 @@ impl StreamTest<AttestationData> {
 
         for (i, test) in self.cases.iter().enumerate() {
             assert_eq!(
-                slashing_db.check_and_insert_attestation(&test.pubkey, &test.data, test.domain),
+                slashing_db.check_and_insert(&test.pubkey, &test.data, test.domain),
                 test.expected,
                 "attestation {} not processed as expected",
                 i
@@ -106,7 +106,7 @@ impl StreamTest<BeaconBlockHeader> {
 
         for (i, test) in self.cases.iter().enumerate() {
             assert_eq!(
-                slashing_db.check_and_insert_block_proposal(&test.pubkey, &test.data, test.domain),
+                slashing_db.check_and_insert(&test.pubkey, &test.data, test.domain),
                 test.expected,
                 "attestation {} not processed as expected",
                 i
@@ -0,0 +1,22 @@
+use crate::{SignedAttestation, SignedBlock};
+use serde_derive::{Deserialize, Serialize};
+use types::PublicKey;
+
+/// The complete slashing protection history of a single validator.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorRecords {
+    pub pubkey: PublicKey,
+    pub signed_blocks: Vec<SignedBlock>,
+    pub signed_attestations: Vec<SignedAttestation>,
+}
+
+impl ValidatorRecords {
+    /// Records for a validator that has never signed anything.
+    pub fn empty(pubkey: PublicKey) -> Self {
+        Self {
+            pubkey,
+            signed_blocks: vec![],
+            signed_attestations: vec![],
+        }
+    }
+}
@@ -10,9 +10,20 @@ use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
-use tokio::time::{delay_until, interval_at, Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::{delay_for, delay_until, interval_at, timeout_at, Duration, Instant};
 use types::{Attestation, ChainSpec, CommitteeIndex, EthSpec, Slot, SubnetId};
 
+/// The head event stream is re-opened after this period, since requests to the beacon node cannot
+/// be made without a timeout.
+const HEAD_STREAM_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The delay before re-opening the head event stream after it fails.
+const HEAD_STREAM_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The slot of the most recent head block observed via the head event stream of the beacon node.
+type HeadSlotReceiver = watch::Receiver<Option<Slot>>;
+
 /// Builds an `AttestationService`.
 pub struct AttestationServiceBuilder<T, E: EthSpec> {
     duties_service: Option<DutiesService<T, E>>,
@@ -90,7 +101,9 @@ pub struct Inner<T, E: EthSpec> {
     context: RuntimeContext<E>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot,
+/// or as soon as the block for the slot becomes the head of the beacon node if that is sooner.
+/// Aggregates are produced 2/3rds of the way through the slot.
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -132,12 +145,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             "next_update_millis" => duration_to_next_slot.as_millis()
         );
 
+        let head_slot_rx = self.spawn_head_monitor();
+
         let mut interval = {
             // Note: `interval_at` panics if `slot_duration` is 0
-            interval_at(
-                Instant::now() + duration_to_next_slot + slot_duration / 3,
-                slot_duration,
-            )
+            interval_at(Instant::now() + duration_to_next_slot, slot_duration)
         };
 
         let executor = self.context.executor.clone();
@@ -146,17 +158,22 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             while interval.next().await.is_some() {
                 let log = self.context.log();
 
-                if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
-                    crit!(
+                match self.attestation_deadlines() {
+                    Ok((slot, attestation_instant, aggregate_production_instant)) => {
+                        self.inner.context.executor.runtime_handle().spawn(
+                            self.clone().attest_at_slot(
+                                slot,
+                                attestation_instant,
+                                aggregate_production_instant,
+                                head_slot_rx.clone(),
+                            ),
+                        );
+                    }
+                    Err(e) => crit!(
                         log,
-                        "Failed to spawn attestation tasks";
+                        "Failed to schedule attestation tasks";
                         "error" => e
-                    )
-                } else {
-                    trace!(
-                        log,
-                        "Spawned attestation tasks";
-                    )
+                    ),
                 }
             }
         };
@@ -165,25 +182,140 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         Ok(())
     }
 
-    /// For each each required attestation, spawn a new task that downloads, signs and uploads the
-    /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
+    /// Returns the current slot along with the instants at which its attestations (1/3rd of the
+    /// way through the slot) and aggregates (2/3rds of the way through the slot) are due.
+    fn attestation_deadlines(&self) -> Result<(Slot, Instant, Instant), String> {
         let slot = self
             .slot_clock
             .now()
             .ok_or_else(|| "Failed to read slot clock".to_string())?;
-        let duration_to_next_slot = self
+        let duration_since_slot_start = self
             .slot_clock
-            .duration_to_next_slot()
-            .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
+            .duration_since_start_of(slot)
+            .ok_or_else(|| "Unable to determine duration since slot start".to_string())?;
+
+        let attestation_instant = Instant::now()
+            + self
+                .slot_clock
+                .unagg_attestation_production_delay()
+                .checked_sub(duration_since_slot_start)
+                .unwrap_or_else(|| Duration::from_secs(0));
 
         // If a validator needs to publish an aggregate attestation, they must do so at 2/3
         // through the slot. This delay triggers at this time
         let aggregate_production_instant = Instant::now()
-            + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
+            + self
+                .slot_clock
+                .agg_attestation_production_delay()
+                .checked_sub(duration_since_slot_start)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
+        Ok((slot, attestation_instant, aggregate_production_instant))
+    }
+
+    /// Waits until attestations for `slot` are due, then spawns the attestation tasks.
+    async fn attest_at_slot(
+        self,
+        slot: Slot,
+        attestation_instant: Instant,
+        aggregate_production_instant: Instant,
+        head_slot_rx: HeadSlotReceiver,
+    ) {
+        let log = self.context.log();
+
+        if wait_for_attestation_time(slot, attestation_instant, head_slot_rx).await {
+            debug!(
+                log,
+                "Head block observed, attesting early";
+                "slot" => slot.as_u64(),
+            );
+        }
+
+        if let Err(e) = self.spawn_attestation_tasks(slot, aggregate_production_instant) {
+            crit!(
+                log,
+                "Failed to spawn attestation tasks";
+                "error" => e
+            )
+        } else {
+            trace!(
+                log,
+                "Spawned attestation tasks";
+            )
+        }
+    }
+
+    /// Spawns a task which follows the head of the beacon node via its event stream, returning a
+    /// receiver of the slot of each new head block.
+    fn spawn_head_monitor(&self) -> HeadSlotReceiver {
+        let (head_slot_tx, head_slot_rx) = watch::channel(None);
+        let service = self.clone();
+
+        let head_monitor_fut = async move {
+            loop {
+                if let Err(e) = service.follow_head_events(&head_slot_tx).await {
+                    debug!(
+                        service.context.log(),
+                        "Head event stream failed";
+                        "info" => "attestations will be produced 1/3rd of the way through the slot",
+                        "error" => e,
+                    );
+                    delay_for(HEAD_STREAM_RETRY_DELAY).await;
+                }
+            }
+        };
+
+        self.context
+            .executor
+            .spawn(head_monitor_fut, "attestation_head_monitor");
+
+        head_slot_rx
+    }
+
+    /// Reads head events from the beacon node until the stream ends, broadcasting the slot of each
+    /// new head block on `head_slot_tx`.
+    async fn follow_head_events(
+        &self,
+        head_slot_tx: &watch::Sender<Option<Slot>>,
+    ) -> Result<(), String> {
+        let mut head_roots = Box::pin(
+            self.beacon_node
+                .http
+                .beacon()
+                .stream_heads(HEAD_STREAM_TIMEOUT)
+                .await
+                .map_err(|e| format!("Unable to open head event stream: {:?}", e))?,
+        );
+
+        while let Some(head_root) = head_roots.next().await {
+            let head_root = head_root.map_err(|e| format!("Unable to read head event: {:?}", e))?;
+
+            // The event only contains the block root, so the slot must be requested separately.
+            let head = self
+                .beacon_node
+                .http
+                .beacon()
+                .get_head()
+                .await
+                .map_err(|e| format!("Unable to get head: {:?}", e))?;
+
+            if head.block_root == head_root {
+                head_slot_tx
+                    .broadcast(Some(head.slot))
+                    .map_err(|_| "Head slot receivers dropped".to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// For each each required attestation, spawn a new task that downloads, signs and uploads the
+    /// attestation to the beacon node.
+    fn spawn_attestation_tasks(
+        &self,
+        slot: Slot,
+        aggregate_production_instant: Instant,
+    ) -> Result<(), String> {
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
             .duties_service
             .attesters(slot)
@@ -553,6 +685,32 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     }
 }
 
+/// Waits until `attestation_instant`, or until the head block of `slot` is received on
+/// `head_slot_rx` if that is sooner.
+///
+/// Returns `true` if the head block was observed before `attestation_instant`.
+async fn wait_for_attestation_time(
+    slot: Slot,
+    attestation_instant: Instant,
+    mut head_slot_rx: HeadSlotReceiver,
+) -> bool {
+    let head_observed = async move {
+        loop {
+            let head_slot = *head_slot_rx.borrow();
+            if head_slot.map_or(false, |head_slot| head_slot >= slot) {
+                return;
+            }
+
+            if head_slot_rx.recv().await.is_none() {
+                // The head monitor has stopped, wait for the attestation instant.
+                futures::future::pending::<()>().await;
+            }
+        }
+    };
+
+    timeout_at(attestation_instant, head_observed).await.is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -576,4 +734,33 @@ mod tests {
             "state should have been updated"
         );
     }
+
+    #[tokio::test]
+    async fn attestation_time_brought_forward_by_head_block() {
+        let slot = Slot::new(4);
+        let (head_slot_tx, head_slot_rx) = watch::channel(None);
+
+        // A head block from a prior slot does not bring the attestation forward.
+        head_slot_tx
+            .broadcast(Some(slot - 1))
+            .expect("should broadcast head slot");
+        assert!(
+            !wait_for_attestation_time(
+                slot,
+                Instant::now() + Duration::from_millis(10),
+                head_slot_rx.clone()
+            )
+            .await,
+            "should wait until the attestation instant"
+        );
+
+        head_slot_tx
+            .broadcast(Some(slot))
+            .expect("should broadcast head slot");
+        assert!(
+            wait_for_attestation_time(slot, Instant::now() + Duration::from_secs(60), head_slot_rx)
+                .await,
+            "should attest when the head block is observed"
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::validator_store::ValidatorStore;
+use crate::{metrics, validator_store::ValidatorStore};
 use environment::RuntimeContext;
 use futures::channel::mpsc::Receiver;
 use futures::{StreamExt, TryFutureExt};
@@ -7,13 +7,14 @@ use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::ops::Deref;
 use std::sync::Arc;
-use types::{EthSpec, PublicKey, Slot};
+use types::{EthSpec, PublicKey, SignedBeaconBlock, Slot};
 
 /// Builds a `BlockService`.
 pub struct BlockServiceBuilder<T, E: EthSpec> {
     validator_store: Option<ValidatorStore<T, E>>,
     slot_clock: Option<Arc<T>>,
     beacon_node: Option<RemoteBeaconNode<E>>,
+    fallback_beacon_nodes: Vec<RemoteBeaconNode<E>>,
     context: Option<RuntimeContext<E>>,
 }
 
@@ -23,6 +24,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             validator_store: None,
             slot_clock: None,
             beacon_node: None,
+            fallback_beacon_nodes: vec![],
             context: None,
         }
     }
@@ -42,6 +44,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    /// Beacon nodes which are used, in order, if `beacon_node` fails to produce or publish a block.
+    pub fn fallback_beacon_nodes(mut self, beacon_nodes: Vec<RemoteBeaconNode<E>>) -> Self {
+        self.fallback_beacon_nodes = beacon_nodes;
+        self
+    }
+
     pub fn runtime_context(mut self, context: RuntimeContext<E>) -> Self {
         self.context = Some(context);
         self
@@ -59,6 +67,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 beacon_node: self
                     .beacon_node
                     .ok_or_else(|| "Cannot build BlockService without beacon_node")?,
+                fallback_beacon_nodes: self.fallback_beacon_nodes,
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build BlockService without runtime_context")?,
@@ -72,6 +81,7 @@ pub struct Inner<T, E: EthSpec> {
     validator_store: ValidatorStore<T, E>,
     slot_clock: Arc<T>,
     beacon_node: RemoteBeaconNode<E>,
+    fallback_beacon_nodes: Vec<RemoteBeaconNode<E>>,
     context: RuntimeContext<E>,
 }
 
@@ -197,6 +207,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
     }
 
     /// Produce a block at the given slot for validator_pubkey
+    ///
+    /// The block is requested from the primary beacon node, falling back to each of the fallback
+    /// beacon nodes in turn if production fails. The signed block is first published to the node
+    /// which produced it, falling back to the other nodes if publishing fails. No further attempts
+    /// are made once `slot` has passed.
+    ///
+    /// Only a single block is ever signed, so the fallbacks cannot cause a slashable proposal.
     async fn publish_block(self, slot: Slot, validator_pubkey: PublicKey) -> Result<(), String> {
         let log = self.context.log();
 
@@ -210,26 +227,128 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .randao_reveal(&validator_pubkey, slot.epoch(E::slots_per_epoch()))
             .ok_or_else(|| "Unable to produce randao reveal".to_string())?;
 
-        let block = self
-            .beacon_node
-            .http
-            .validator()
-            .produce_block(slot, randao_reveal)
-            .await
-            .map_err(|e| format!("Error from beacon node when producing block: {:?}", e))?;
+        let beacon_nodes = self.beacon_nodes();
+        let mut used_fallback = false;
+
+        // Step 1.
+        //
+        // Produce the block, falling back to the next node on failure.
+        let mut produced = None;
+        for (index, beacon_node) in beacon_nodes.iter().enumerate() {
+            self.check_proposal_deadline(slot)?;
+
+            if index > 0 {
+                used_fallback = true;
+                metrics::inc_counter(&metrics::BLOCK_PROPOSAL_FALLBACK_ATTEMPTS);
+            }
+
+            match beacon_node
+                .http
+                .validator()
+                .produce_block(slot, randao_reveal.clone())
+                .await
+            {
+                Ok(block) => {
+                    produced = Some((index, block));
+                    break;
+                }
+                Err(e) => warn!(
+                    log,
+                    "Beacon node failed to produce block";
+                    "error" => format!("{:?}", e),
+                    "beacon_node" => beacon_node_name(index),
+                    "slot" => slot.as_u64(),
+                ),
+            }
+        }
+
+        let (producer_index, block) = produced.ok_or_else(|| {
+            metrics::inc_counter(&metrics::BLOCK_PROPOSALS_FAILED);
+            "No beacon node was able to produce a block".to_string()
+        })?;
 
         let signed_block = self
             .validator_store
             .sign_block(&validator_pubkey, block, current_slot)
             .ok_or_else(|| "Unable to sign block".to_string())?;
 
-        let publish_status = self
-            .beacon_node
-            .http
-            .validator()
-            .publish_block(signed_block.clone())
-            .await
-            .map_err(|e| format!("Error from beacon node when publishing block: {:?}", e))?;
+        // Step 2.
+        //
+        // Publish the block, starting with the node which produced it.
+        let publish_order = std::iter::once(producer_index)
+            .chain((0..beacon_nodes.len()).filter(|index| *index != producer_index));
+
+        for (attempt, index) in publish_order.enumerate() {
+            self.check_proposal_deadline(slot)?;
+
+            if attempt > 0 {
+                used_fallback = true;
+                metrics::inc_counter(&metrics::BLOCK_PROPOSAL_FALLBACK_ATTEMPTS);
+            }
+
+            match beacon_nodes[index]
+                .http
+                .validator()
+                .publish_block(signed_block.clone())
+                .await
+            {
+                Ok(publish_status) => {
+                    self.log_publish_status(publish_status, &signed_block, index);
+
+                    if used_fallback {
+                        metrics::inc_counter(&metrics::BLOCK_PROPOSALS_RECOVERED);
+                        info!(
+                            log,
+                            "Recovered block proposal using fallback beacon node";
+                            "beacon_node" => beacon_node_name(index),
+                            "slot" => slot.as_u64(),
+                        );
+                    }
+
+                    return Ok(());
+                }
+                Err(e) => warn!(
+                    log,
+                    "Beacon node failed to publish block";
+                    "error" => format!("{:?}", e),
+                    "beacon_node" => beacon_node_name(index),
+                    "slot" => slot.as_u64(),
+                ),
+            }
+        }
+
+        metrics::inc_counter(&metrics::BLOCK_PROPOSALS_FAILED);
+        Err("No beacon node was able to publish the block".to_string())
+    }
+
+    /// Returns the primary beacon node followed by the fallback beacon nodes.
+    fn beacon_nodes(&self) -> Vec<&RemoteBeaconNode<E>> {
+        std::iter::once(&self.beacon_node)
+            .chain(self.fallback_beacon_nodes.iter())
+            .collect()
+    }
+
+    /// Returns an error if `slot` has passed, in which case the proposal can no longer be made.
+    fn check_proposal_deadline(&self, slot: Slot) -> Result<(), String> {
+        match self.slot_clock.now() {
+            Some(current_slot) if current_slot > slot => {
+                metrics::inc_counter(&metrics::BLOCK_PROPOSALS_FAILED);
+                Err(format!(
+                    "Proposal slot {} passed before the block was published",
+                    slot
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn log_publish_status(
+        &self,
+        publish_status: PublishStatus,
+        signed_block: &SignedBeaconBlock<E>,
+        beacon_node_index: usize,
+    ) {
+        let log = self.context.log();
 
         match publish_status {
             PublishStatus::Valid => info!(
@@ -237,6 +356,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 "Successfully published block";
                 "deposits" => signed_block.message.body.deposits.len(),
                 "attestations" => signed_block.message.body.attestations.len(),
+                "beacon_node" => beacon_node_name(beacon_node_index),
                 "slot" => signed_block.slot().as_u64(),
             ),
             PublishStatus::Invalid(msg) => crit!(
@@ -247,7 +367,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             ),
             PublishStatus::Unknown => crit!(log, "Unknown condition when publishing block"),
         }
+    }
+}
 
-        Ok(())
+/// Returns a name for the beacon node at `index` in `BlockService::beacon_nodes`, for logging.
+fn beacon_node_name(index: usize) -> String {
+    if index == 0 {
+        "primary".to_string()
+    } else {
+        format!("fallback_{}", index)
     }
 }
@@ -16,6 +16,37 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value(&DEFAULT_HTTP_SERVER)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("fallback-servers")
+                .long("fallback-servers")
+                .value_name("NETWORK_ADDRESSES")
+                .help(
+                    "A comma-separated list of beacon node addresses which are used, in order, to \
+                    produce and publish blocks if the beacon node given by --server fails to do \
+                    so within the proposal slot.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("beacon-node-tls-ca-cert")
+                .long("beacon-node-tls-ca-cert")
+                .value_name("PATH")
+                .help(
+                    "A PEM-encoded root certificate to trust when connecting to the beacon node \
+                    over https. Useful when the beacon node uses a self-signed certificate.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable-beacon-node-ssz")
+                .long("disable-beacon-node-ssz")
+                .help(
+                    "Request all responses from the beacon node as JSON. By default, blocks, \
+                    states and duties are requested as SSZ, falling back to JSON if the beacon \
+                    node does not support SSZ for a particular request.",
+                )
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("secrets-dir")
                 .long("secrets-dir")
@@ -52,4 +83,78 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                       node is not synced.",
                 ),
         )
+        .arg(
+            Arg::with_name("http")
+                .long("http")
+                .help(
+                    "Enable the HTTP API server. Every request must include the API token, which \
+                    is stored in the api-token.txt file in the validator data directory.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("http-address")
+                .long("http-address")
+                .value_name("ADDRESS")
+                .help("Set the listen address for the HTTP API server.")
+                .default_value("127.0.0.1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-port")
+                .long("http-port")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the HTTP API server.")
+                .default_value("5062")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-cert")
+                .long("http-tls-cert")
+                .value_name("PATH")
+                .help(
+                    "Serve the HTTP API over TLS using the PEM-encoded certificate chain at this \
+                    path. The certificate and key are reloaded when the process receives SIGHUP.",
+                )
+                .requires("http-tls-key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-key")
+                .long("http-tls-key")
+                .value_name("PATH")
+                .help("The PEM-encoded private key for the certificate given by --http-tls-cert.")
+                .requires("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-socket-path")
+                .long("http-socket-path")
+                .value_name("PATH")
+                .help(
+                    "Serve the HTTP API on a unix domain socket at this path instead of a TCP \
+                    port, ignoring --http-address and --http-port. The socket is only accessible \
+                    by the user running the validator client. Not available on Windows.",
+                )
+                .conflicts_with("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-endpoint")
+                .long("monitoring-endpoint")
+                .value_name("URL")
+                .help("Periodically POST a JSON snapshot of the validators managed by this client \
+                    (counts and total balance) to this URL. Intended for users who do not run \
+                    Prometheus.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-endpoint-period")
+                .long("monitoring-endpoint-period")
+                .value_name("SECONDS")
+                .help("The number of seconds between each snapshot sent to --monitoring-endpoint.")
+                .requires("monitoring-endpoint")
+                .default_value("60")
+                .takes_value(true),
+        )
 }
@@ -1,13 +1,15 @@
+use crate::http_api;
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_path_with_default_in_home_dir};
+use http_tls::TlsConfig;
 use serde_derive::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 pub const DEFAULT_HTTP_SERVER: &str = "http://localhost:5052/";
 pub const DEFAULT_DATA_DIR: &str = ".lighthouse/validators";
 pub const DEFAULT_SECRETS_DIR: &str = ".lighthouse/secrets";
-/// Path to the slashing protection database within the datadir.
-pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+
+pub use slashing_protection::SLASHING_PROTECTION_FILENAME;
 
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Serialize, Deserialize)]
@@ -20,6 +22,14 @@ pub struct Config {
     ///
     /// Should be similar to `http://localhost:8080`
     pub http_server: String,
+    /// The http endpoints of beacon nodes which produce and publish blocks if `http_server` fails
+    /// to do so, in order of preference.
+    pub fallback_http_servers: Vec<String>,
+    /// An optional PEM-encoded root certificate to trust when connecting to the beacon node over
+    /// https (e.g., when the beacon node uses a self-signed certificate).
+    pub beacon_node_tls_ca_cert: Option<PathBuf>,
+    /// If true, request responses from the beacon node as SSZ where the endpoint supports it.
+    pub beacon_node_ssz: bool,
     /// If true, the validator client will still poll for duties and produce blocks even if the
     /// beacon node is not synced at startup.
     pub allow_unsynced_beacon_node: bool,
@@ -27,6 +37,10 @@ pub struct Config {
     pub strict: bool,
     /// If true, register new validator keys with the slashing protection database.
     pub auto_register: bool,
+    /// Configuration for the HTTP API.
+    pub http_api: http_api::Config,
+    /// If `Some`, periodically send a summary of the validators to a remote monitoring service.
+    pub monitoring_api: Option<monitoring_api::Config>,
 }
 
 impl Default for Config {
@@ -42,9 +56,14 @@ impl Default for Config {
             data_dir,
             secrets_dir,
             http_server: DEFAULT_HTTP_SERVER.to_string(),
+            fallback_http_servers: vec![],
+            beacon_node_tls_ca_cert: None,
+            beacon_node_ssz: true,
             allow_unsynced_beacon_node: false,
             auto_register: false,
             strict: false,
+            http_api: <_>::default(),
+            monitoring_api: None,
         }
     }
 }
@@ -72,6 +91,18 @@ impl Config {
             config.http_server = server;
         }
 
+        if let Some(servers) = cli_args.value_of("fallback-servers") {
+            config.fallback_http_servers = servers
+                .split(',')
+                .map(str::trim)
+                .filter(|server| !server.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        config.beacon_node_tls_ca_cert = parse_optional(cli_args, "beacon-node-tls-ca-cert")?;
+        config.beacon_node_ssz = !cli_args.is_present("disable-beacon-node-ssz");
+
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
         config.auto_register = cli_args.is_present("auto-register");
         config.strict = cli_args.is_present("strict");
@@ -81,6 +112,40 @@ impl Config {
             config.auto_register = true
         }
 
+        if cli_args.is_present("http") {
+            config.http_api.enabled = true;
+        }
+
+        if let Some(address) = parse_optional(cli_args, "http-address")? {
+            config.http_api.listen_addr = address;
+        }
+
+        if let Some(port) = parse_optional(cli_args, "http-port")? {
+            config.http_api.listen_port = port;
+        }
+
+        if let (Some(cert), Some(key)) = (
+            parse_optional(cli_args, "http-tls-cert")?,
+            parse_optional(cli_args, "http-tls-key")?,
+        ) {
+            config.http_api.tls_config = Some(TlsConfig { cert, key });
+        }
+
+        if let Some(socket_path) = parse_optional(cli_args, "http-socket-path")? {
+            if cfg!(not(unix)) {
+                return Err("--http-socket-path is only supported on unix platforms".into());
+            }
+            config.http_api.socket_path = Some(socket_path);
+        }
+
+        if let Some(endpoint) = parse_optional::<String>(cli_args, "monitoring-endpoint")? {
+            let mut monitoring_config = monitoring_api::Config::new(endpoint);
+            if let Some(period) = parse_optional(cli_args, "monitoring-endpoint-period")? {
+                monitoring_config.update_period_secs = period;
+            }
+            config.monitoring_api = Some(monitoring_config);
+        }
+
         if let Some(secrets_dir) = parse_optional(cli_args, "secrets-dir")? {
             config.secrets_dir = secrets_dir;
         }
@@ -0,0 +1,181 @@
+//! Stores the attestation duties of the managed validators.
+//!
+//! The attester shuffling of epoch `N` is decided by the block at the last slot of epoch `N - 2`
+//! (the "dependent root"). The duties of each epoch are stored alongside the dependent root they
+//! were computed from, so they only need to be downloaded again if that root changes or a new
+//! validator is added.
+
+use super::DutyAndProof;
+use crate::validator_store::ValidatorStore;
+use parking_lot::RwLock;
+use rest_types::ValidatorSubscription;
+use slot_clock::SlotClock;
+use std::collections::HashMap;
+use types::{Epoch, EthSpec, Hash256, PublicKey, Slot};
+
+/// The attestation duties of the managed validators in some epoch.
+struct EpochDuties {
+    /// The root of the block which decided the attester shuffling of the epoch.
+    dependent_root: Hash256,
+    /// The duties of each validator, including those which are not active in the epoch.
+    duties: HashMap<PublicKey, DutyAndProof>,
+}
+
+/// The outcome of replacing the attestation duties of an epoch.
+#[derive(Default)]
+pub struct UpdateOutcome {
+    /// Subscriptions which must be sent to the beacon node for new or changed duties.
+    pub subscriptions: Vec<ValidatorSubscription>,
+    /// The number of validators whose duties differ from those previously known for the epoch.
+    pub changed: usize,
+    /// The number of duties which were not from the requested epoch and were ignored.
+    pub invalid: usize,
+    /// Errors encountered whilst computing selection proofs. The affected duties are not stored.
+    pub errors: Vec<String>,
+}
+
+/// Stores the attestation duties of the managed validators, keyed by epoch.
+#[derive(Default)]
+pub struct AttesterDuties {
+    epochs: RwLock<HashMap<Epoch, EpochDuties>>,
+}
+
+impl AttesterDuties {
+    /// Returns `true` if the duties of `epoch` are unknown, were decided by a block other than
+    /// `dependent_root` or are missing any of `pubkeys`.
+    pub fn requires_update(
+        &self,
+        epoch: Epoch,
+        dependent_root: Hash256,
+        pubkeys: &[PublicKey],
+    ) -> bool {
+        self.epochs.read().get(&epoch).map_or(true, |known| {
+            known.dependent_root != dependent_root
+                || pubkeys
+                    .iter()
+                    .any(|pubkey| !known.duties.contains_key(pubkey))
+        })
+    }
+
+    /// Returns the dependent root of the duties known for `epoch`, if any.
+    pub fn dependent_root(&self, epoch: Epoch) -> Option<Hash256> {
+        self.epochs
+            .read()
+            .get(&epoch)
+            .map(|known| known.dependent_root)
+    }
+
+    /// Replaces the duties of `epoch` with `duties`, which were decided by `dependent_root`.
+    ///
+    /// Selection proofs are only computed for duties which differ from those previously known,
+    /// and subscriptions are only returned for duties which are new or have changed.
+    pub fn update<T: SlotClock + 'static, E: EthSpec>(
+        &self,
+        epoch: Epoch,
+        dependent_root: Hash256,
+        duties: Vec<DutyAndProof>,
+        validator_store: &ValidatorStore<T, E>,
+    ) -> UpdateOutcome {
+        // Selection proofs are signed outside of the lock, so read a copy of the previous duties.
+        let previous = self
+            .epochs
+            .read()
+            .get(&epoch)
+            .map(|known| known.duties.clone())
+            .unwrap_or_default();
+
+        let mut outcome = UpdateOutcome::default();
+        let mut epoch_duties = HashMap::with_capacity(duties.len());
+
+        for mut duty in duties {
+            if duty
+                .duty
+                .attestation_slot
+                .map_or(false, |slot| slot.epoch(E::slots_per_epoch()) != epoch)
+            {
+                outcome.invalid += 1;
+                continue;
+            }
+
+            let known = previous.get(duty.validator_pubkey());
+
+            match known {
+                Some(known) if known.selection_proof_eq(&duty) => {
+                    duty.selection_proof = known.selection_proof.clone();
+                }
+                _ => {
+                    if let Err(e) = duty.compute_selection_proof(validator_store) {
+                        outcome.errors.push(e);
+                        continue;
+                    }
+                }
+            }
+
+            if known.map_or(false, |known| {
+                !known.duty.eq_ignoring_proposal_slots(&duty.duty)
+            }) {
+                outcome.changed += 1;
+            }
+
+            if known.map_or(true, |known| !known.subscription_eq(&duty)) {
+                if let Some(subscription) = duty.subscription() {
+                    outcome.subscriptions.push(subscription);
+                }
+            }
+
+            epoch_duties.insert(duty.validator_pubkey().clone(), duty);
+        }
+
+        self.epochs.write().insert(
+            epoch,
+            EpochDuties {
+                dependent_root,
+                duties: epoch_duties,
+            },
+        );
+
+        outcome
+    }
+
+    /// Returns the duties of all validators which must attest in `slot`.
+    pub fn attesters(&self, slot: Slot, slots_per_epoch: u64) -> Vec<DutyAndProof> {
+        self.epochs
+            .read()
+            .get(&slot.epoch(slots_per_epoch))
+            .map(|known| {
+                known
+                    .duties
+                    .values()
+                    .filter(|duty| duty.duty.attestation_slot == Some(slot))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the earliest known attestation slot of `pubkey` which is not prior to `from`.
+    pub fn next_attestation_slot(&self, pubkey: &PublicKey, from: Slot) -> Option<Slot> {
+        self.epochs
+            .read()
+            .values()
+            .filter_map(|known| known.duties.get(pubkey)?.duty.attestation_slot)
+            .filter(|slot| *slot >= from)
+            .min()
+    }
+
+    /// Returns the total number of validators that should attest in the given epoch.
+    pub fn attester_count(&self, epoch: Epoch) -> usize {
+        self.epochs.read().get(&epoch).map_or(0, |known| {
+            known
+                .duties
+                .values()
+                .filter(|duty| duty.duty.attestation_slot.is_some())
+                .count()
+        })
+    }
+
+    /// Removes the duties of all epochs prior to `prior_to`.
+    pub fn prune(&self, prior_to: Epoch) {
+        self.epochs.write().retain(|epoch, _| *epoch >= prior_to);
+    }
+}
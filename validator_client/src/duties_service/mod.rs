@@ -1,15 +1,28 @@
+//! Maintains the duties of the managed validators.
+//!
+//! Attestation and block proposal duties are stored separately by the `attester` and `proposer`
+//! modules, keyed by epoch. Each epoch is stored alongside the roots of the blocks which decided
+//! its duties (the "dependent roots"). The beacon node is asked for the dependent roots of the
+//! current and next epochs each slot, and duties are only downloaded again when a root changes or
+//! a validator is added.
+
+mod attester;
+mod proposer;
+
 use crate::{
     block_service::BlockServiceNotification, is_synced::is_synced, validator_store::ValidatorStore,
 };
+use attester::AttesterDuties;
 use environment::RuntimeContext;
 use futures::channel::mpsc::Sender;
 use futures::{SinkExt, StreamExt};
-use parking_lot::RwLock;
+use proposer::ProposerDuties;
 use remote_beacon_node::{PublishStatus, RemoteBeaconNode};
-use rest_types::{ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription};
+use rest_types::{
+    ProposerPreparationData, ValidatorDuty, ValidatorDutyBytes, ValidatorSubscription,
+};
 use slog::{debug, error, trace, warn};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
 use std::convert::TryInto;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -22,8 +35,6 @@ const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
 /// Remove any duties where the `duties_epoch < current_epoch - PRUNE_DEPTH`.
 const PRUNE_DEPTH: u64 = 4;
 
-type BaseHashMap = HashMap<PublicKey, HashMap<Epoch, DutyAndProof>>;
-
 #[derive(Debug, Clone)]
 pub struct DutyAndProof {
     /// The validator duty.
@@ -103,6 +114,18 @@ impl DutyAndProof {
         ))
     }
 
+    /// Returns the subscription required for the beacon node to collect the attestations of this
+    /// duty, if the validator is scheduled to attest.
+    pub fn subscription(&self) -> Option<ValidatorSubscription> {
+        Some(ValidatorSubscription {
+            validator_index: self.duty.validator_index?,
+            attestation_committee_index: self.duty.attestation_committee_index?,
+            slot: self.duty.attestation_slot?,
+            committee_count_at_slot: self.duty.committee_count_at_slot?,
+            is_aggregator: self.selection_proof.is_some(),
+        })
+    }
+
     pub fn validator_pubkey(&self) -> &PublicKey {
         &self.duty.validator_pubkey
     }
@@ -131,200 +154,6 @@ impl TryInto<DutyAndProof> for ValidatorDutyBytes {
     }
 }
 
-/// The outcome of inserting some `ValidatorDuty` into the `DutiesStore`.
-#[derive(PartialEq, Debug, Clone)]
-enum InsertOutcome {
-    /// These are the first duties received for this validator.
-    NewValidator,
-    /// The duties for this given epoch were previously unknown and have been stored.
-    NewEpoch,
-    /// The duties were identical to some already in the store.
-    Identical,
-    /// The duties informed us of new proposal slots but were otherwise identical.
-    NewProposalSlots,
-    /// There were duties for this validator and epoch in the store that were different to the ones
-    /// provided. The existing duties were replaced.
-    Replaced { should_resubscribe: bool },
-    /// The given duties were invalid.
-    Invalid,
-}
-
-impl InsertOutcome {
-    /// Returns `true` if the outcome indicates that the validator _might_ require a subscription.
-    pub fn is_subscription_candidate(self) -> bool {
-        match self {
-            InsertOutcome::Replaced { should_resubscribe } => should_resubscribe,
-            InsertOutcome::NewValidator | InsertOutcome::NewEpoch => true,
-            InsertOutcome::Identical | InsertOutcome::Invalid | InsertOutcome::NewProposalSlots => {
-                false
-            }
-        }
-    }
-}
-
-#[derive(Default)]
-pub struct DutiesStore {
-    store: RwLock<BaseHashMap>,
-}
-
-impl DutiesStore {
-    /// Returns the total number of validators that should propose in the given epoch.
-    fn proposer_count(&self, epoch: Epoch) -> usize {
-        self.store
-            .read()
-            .iter()
-            .filter(|(_validator_pubkey, validator_map)| {
-                validator_map
-                    .get(&epoch)
-                    .map(|duties| {
-                        duties
-                            .duty
-                            .block_proposal_slots
-                            .as_ref()
-                            .map_or(false, |proposal_slots| !proposal_slots.is_empty())
-                    })
-                    .unwrap_or(false)
-            })
-            .count()
-    }
-
-    /// Returns the total number of validators that should attest in the given epoch.
-    fn attester_count(&self, epoch: Epoch) -> usize {
-        self.store
-            .read()
-            .iter()
-            .filter(|(_validator_pubkey, validator_map)| {
-                validator_map
-                    .get(&epoch)
-                    .map(|duties| duties.duty.attestation_slot.is_some())
-                    .unwrap_or_else(|| false)
-            })
-            .count()
-    }
-
-    fn block_proposers(&self, slot: Slot, slots_per_epoch: u64) -> Vec<PublicKey> {
-        self.store
-            .read()
-            .iter()
-            // As long as a `HashMap` iterator does not return duplicate keys, neither will this
-            // function.
-            .filter_map(|(_validator_pubkey, validator_map)| {
-                let epoch = slot.epoch(slots_per_epoch);
-
-                validator_map.get(&epoch).and_then(|duties| {
-                    if duties.duty.block_proposal_slots.as_ref()?.contains(&slot) {
-                        Some(duties.duty.validator_pubkey.clone())
-                    } else {
-                        None
-                    }
-                })
-            })
-            .collect()
-    }
-
-    fn attesters(&self, slot: Slot, slots_per_epoch: u64) -> Vec<DutyAndProof> {
-        self.store
-            .read()
-            .iter()
-            // As long as a `HashMap` iterator does not return duplicate keys, neither will this
-            // function.
-            .filter_map(|(_validator_pubkey, validator_map)| {
-                let epoch = slot.epoch(slots_per_epoch);
-
-                validator_map.get(&epoch).and_then(|duties| {
-                    if duties.duty.attestation_slot == Some(slot) {
-                        Some(duties)
-                    } else {
-                        None
-                    }
-                })
-            })
-            .cloned()
-            .collect()
-    }
-
-    fn is_aggregator(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<bool> {
-        Some(
-            self.store
-                .read()
-                .get(validator_pubkey)?
-                .get(&epoch)?
-                .selection_proof
-                .is_some(),
-        )
-    }
-
-    fn insert<T: SlotClock + 'static, E: EthSpec>(
-        &self,
-        epoch: Epoch,
-        mut duties: DutyAndProof,
-        slots_per_epoch: u64,
-        validator_store: &ValidatorStore<T, E>,
-    ) -> Result<InsertOutcome, String> {
-        let mut store = self.store.write();
-
-        if !duties_match_epoch(&duties.duty, epoch, slots_per_epoch) {
-            return Ok(InsertOutcome::Invalid);
-        }
-
-        // TODO: refactor with Entry.
-
-        if let Some(validator_map) = store.get_mut(&duties.duty.validator_pubkey) {
-            if let Some(known_duties) = validator_map.get_mut(&epoch) {
-                if known_duties.duty.eq_ignoring_proposal_slots(&duties.duty) {
-                    if known_duties.duty.block_proposal_slots == duties.duty.block_proposal_slots {
-                        Ok(InsertOutcome::Identical)
-                    } else if duties.duty.block_proposal_slots.is_some() {
-                        known_duties.duty.block_proposal_slots = duties.duty.block_proposal_slots;
-                        Ok(InsertOutcome::NewProposalSlots)
-                    } else {
-                        Ok(InsertOutcome::Invalid)
-                    }
-                } else {
-                    // Compute the selection proof.
-                    duties.compute_selection_proof(validator_store)?;
-
-                    // Determine if a re-subscription is required.
-                    let should_resubscribe = !duties.subscription_eq(known_duties);
-
-                    // Replace the existing duties.
-                    *known_duties = duties;
-
-                    Ok(InsertOutcome::Replaced { should_resubscribe })
-                }
-            } else {
-                // Compute the selection proof.
-                duties.compute_selection_proof(validator_store)?;
-
-                validator_map.insert(epoch, duties);
-
-                Ok(InsertOutcome::NewEpoch)
-            }
-        } else {
-            // Compute the selection proof.
-            duties.compute_selection_proof(validator_store)?;
-
-            let validator_pubkey = duties.duty.validator_pubkey.clone();
-
-            let mut validator_map = HashMap::new();
-            validator_map.insert(epoch, duties);
-
-            store.insert(validator_pubkey, validator_map);
-
-            Ok(InsertOutcome::NewValidator)
-        }
-    }
-
-    fn prune(&self, prior_to: Epoch) {
-        self.store
-            .write()
-            .retain(|_validator_pubkey, validator_map| {
-                validator_map.retain(|epoch, _duties| *epoch >= prior_to);
-                !validator_map.is_empty()
-            });
-    }
-}
-
 pub struct DutiesServiceBuilder<T, E: EthSpec> {
     validator_store: Option<ValidatorStore<T, E>>,
     slot_clock: Option<T>,
@@ -373,7 +202,8 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
     pub fn build(self) -> Result<DutiesService<T, E>, String> {
         Ok(DutiesService {
             inner: Arc::new(Inner {
-                store: Arc::new(DutiesStore::default()),
+                attesters: AttesterDuties::default(),
+                proposers: ProposerDuties::default(),
                 validator_store: self
                     .validator_store
                     .ok_or_else(|| "Cannot build DutiesService without validator_store")?,
@@ -394,7 +224,8 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesServiceBuilder<T, E> {
 
 /// Helper to minimise `Arc` usage.
 pub struct Inner<T, E: EthSpec> {
-    store: Arc<DutiesStore>,
+    attesters: AttesterDuties,
+    proposers: ProposerDuties,
     validator_store: ValidatorStore<T, E>,
     pub(crate) slot_clock: T,
     pub(crate) beacon_node: RemoteBeaconNode<E>,
@@ -406,9 +237,9 @@ pub struct Inner<T, E: EthSpec> {
 
 /// Maintains a store of the duties for all voting validators in the `validator_store`.
 ///
-/// Polls the beacon node at the start of each slot, collecting duties for the current and next
-/// epoch. The duties service notifies the block production service to run each time it completes,
-/// so it *must* be run every slot.
+/// Polls the beacon node at the start of each slot, downloading the duties of the current and next
+/// epoch if they are unknown or their dependent roots have changed. The duties service notifies
+/// the block production service to run each time it completes, so it *must* be run every slot.
 pub struct DutiesService<T, E: EthSpec> {
     inner: Arc<Inner<T, E>>,
 }
@@ -437,12 +268,12 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
 
     /// Returns the total number of validators that should propose in the given epoch.
     pub fn proposer_count(&self, epoch: Epoch) -> usize {
-        self.store.proposer_count(epoch)
+        self.proposers.proposer_count(epoch)
     }
 
     /// Returns the total number of validators that should attest in the given epoch.
     pub fn attester_count(&self, epoch: Epoch) -> usize {
-        self.store.attester_count(epoch)
+        self.attesters.attester_count(epoch)
     }
 
     /// Returns the pubkeys of the validators which are assigned to propose in the given slot.
@@ -450,12 +281,22 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
     /// It is possible that multiple validators have an identical proposal slot, however that is
     /// likely the result of heavy forking (lol) or inconsistent beacon node connections.
     pub fn block_proposers(&self, slot: Slot) -> Vec<PublicKey> {
-        self.store.block_proposers(slot, E::slots_per_epoch())
+        self.proposers.block_proposers(slot, E::slots_per_epoch())
     }
 
     /// Returns all `ValidatorDuty` for the given `slot`.
     pub fn attesters(&self, slot: Slot) -> Vec<DutyAndProof> {
-        self.store.attesters(slot, E::slots_per_epoch())
+        self.attesters.attesters(slot, E::slots_per_epoch())
+    }
+
+    /// Returns the next slot at or after `slot` in which `pubkey` is known to attest.
+    pub fn next_attestation_slot(&self, pubkey: &PublicKey, slot: Slot) -> Option<Slot> {
+        self.attesters.next_attestation_slot(pubkey, slot)
+    }
+
+    /// Returns the next slot at or after `slot` in which `pubkey` is known to propose.
+    pub fn next_proposal_slot(&self, pubkey: &PublicKey, slot: Slot) -> Option<Slot> {
+        self.proposers.next_proposal_slot(pubkey, slot)
     }
 
     /// Start the service that periodically polls the beacon node for validator duties.
@@ -500,7 +341,7 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         Ok(())
     }
 
-    /// Attempt to download the duties of all managed validators for this epoch and the next.
+    /// Attempt to update the duties of all managed validators for this epoch and the next.
     async fn do_update(self, block_service_tx: &mut Sender<BlockServiceNotification>) {
         let log = self.context.log();
 
@@ -529,16 +370,17 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                 "current_epoch" => current_epoch.as_u64(),
             );
 
-            self.store.prune(prune_below);
+            self.attesters.prune(prune_below);
+            self.proposers.prune(prune_below);
         }
 
         // Update duties for the current epoch, but keep running if there's an error:
         // block production or the next epoch update could still succeed.
-        if let Err(e) = self.clone().update_epoch(current_epoch).await {
+        if let Err(e) = self.clone().update_epoch(current_epoch, true).await {
             error!(
                 log,
                 "Failed to get current epoch duties";
-                "http_error" => format!("{:?}", e)
+                "error" => e
             );
         }
 
@@ -557,44 +399,66 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             );
         };
 
-        // Update duties for the next epoch.
-        if let Err(e) = self.clone().update_epoch(current_epoch + 1).await {
+        // Update attestation duties for the next epoch. Proposal duties cannot be known until the
+        // epoch begins.
+        if let Err(e) = self.clone().update_epoch(current_epoch + 1, false).await {
             error!(
                 log,
                 "Failed to get next epoch duties";
-                "http_error" => format!("{:?}", e)
+                "error" => e
             );
         }
     }
 
-    /// Attempt to download the duties of all managed validators for the given `epoch`.
-    async fn update_epoch(self, epoch: Epoch) -> Result<(), String> {
-        let pubkeys = self.validator_store.voting_pubkeys();
-        let all_duties = self
+    /// Download the duties of all managed validators for the given `epoch`, if the duties are
+    /// unknown or the blocks which decided them have changed.
+    ///
+    /// Proposal duties are only updated if `update_proposers` is `true`.
+    async fn update_epoch(self, epoch: Epoch, update_proposers: bool) -> Result<(), String> {
+        let log = self.context.log().clone();
+
+        let dependent_roots = self
             .beacon_node
             .http
             .validator()
-            .get_duties(epoch, pubkeys.as_slice())
+            .get_duties_dependent_roots(epoch)
             .await
-            .map_err(move |e| format!("Failed to get duties for epoch {}: {:?}", epoch, e))?;
+            .map_err(|e| format!("Failed to get dependent roots for epoch {}: {:?}", epoch, e))?;
 
-        let log = self.context.log().clone();
+        let pubkeys = self.validator_store.voting_pubkeys();
 
-        let mut new_validator = 0;
-        let mut new_epoch = 0;
-        let mut new_proposal_slots = 0;
-        let mut identical = 0;
-        let mut replaced = 0;
-        let mut invalid = 0;
+        let update_attesters = self.attesters.requires_update(
+            epoch,
+            dependent_roots.attester_dependent_root,
+            &pubkeys,
+        );
+        let update_proposers = update_proposers
+            && self.proposers.requires_update(
+                epoch,
+                dependent_roots.proposer_dependent_root,
+                &pubkeys,
+            );
 
-        // For each of the duties, attempt to insert them into our local store and build a
-        // list of new or changed selections proofs for any aggregating validators.
-        let validator_subscriptions = all_duties
+        if !update_attesters && !update_proposers {
+            trace!(
+                log,
+                "Duties are up to date";
+                "epoch" => epoch.as_u64(),
+            );
+            return Ok(());
+        }
+
+        let all_duties = self
+            .beacon_node
+            .http
+            .validator()
+            .get_duties(epoch, pubkeys.as_slice())
+            .await
+            .map_err(move |e| format!("Failed to get duties for epoch {}: {:?}", epoch, e))?
             .into_iter()
             .filter_map(|remote_duties| {
                 // Convert the remote duties into our local representation.
-                let duties: DutyAndProof = remote_duties
-                    .clone()
+                remote_duties
                     .try_into()
                     .map_err(|e| {
                         error!(
@@ -603,89 +467,108 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
                             "error" => e
                         )
                     })
-                    .ok()?;
-
-                let validator_pubkey = duties.duty.validator_pubkey.clone();
-
-                // Attempt to update our local store.
-                let outcome = self
-                    .store
-                    .insert(epoch, duties, E::slots_per_epoch(), &self.validator_store)
-                    .map_err(|e| {
-                        error!(
-                            log,
-                            "Unable to store duties";
-                            "error" => e
-                        )
-                    })
-                    .ok()?;
-
-                match &outcome {
-                    InsertOutcome::NewValidator => {
-                        debug!(
-                            log,
-                            "First duty assignment for validator";
-                            "proposal_slots" => format!("{:?}", &remote_duties.block_proposal_slots),
-                            "attestation_slot" => format!("{:?}", &remote_duties.attestation_slot),
-                            "validator" => format!("{:?}", &remote_duties.validator_pubkey)
-                        );
-                        new_validator += 1;
-                    }
-                    InsertOutcome::NewProposalSlots => new_proposal_slots += 1,
-                    InsertOutcome::NewEpoch => new_epoch += 1,
-                    InsertOutcome::Identical => identical += 1,
-                    InsertOutcome::Replaced { .. } => replaced += 1,
-                    InsertOutcome::Invalid => invalid += 1,
-                };
-
-                // The selection proof is computed on `store.insert`, so it's necessary to check
-                // with the store that the validator is an aggregator.
-                let is_aggregator = self.store.is_aggregator(&validator_pubkey, epoch)?;
-
-                if outcome.is_subscription_candidate() {
-                    Some(ValidatorSubscription {
-                        validator_index: remote_duties.validator_index?,
-                        attestation_committee_index: remote_duties.attestation_committee_index?,
-                        slot: remote_duties.attestation_slot?,
-                        committee_count_at_slot: remote_duties.committee_count_at_slot?,
-                        is_aggregator,
-                    })
-                } else {
-                    None
-                }
+                    .ok()
             })
+            .collect::<Vec<DutyAndProof>>();
+
+        // Every managed validator with a known index is registered for proposer preparation.
+        let proposer_preparation_data = all_duties
+            .iter()
+            .filter_map(|duties| duties.duty.validator_index)
+            .map(|validator_index| ProposerPreparationData { validator_index })
             .collect::<Vec<_>>();
 
-        if invalid > 0 {
-            error!(
+        if update_proposers {
+            let changed = self.proposers.update(
+                epoch,
+                dependent_roots.proposer_dependent_root,
+                &all_duties,
+                E::slots_per_epoch(),
+            );
+
+            debug!(
                 log,
-                "Received invalid duties from beacon node";
-                "bad_duty_count" => invalid,
-                "info" => "Duties are from wrong epoch."
-            )
+                "Updated proposer duties";
+                "dependent_root" => format!("{:?}", dependent_roots.proposer_dependent_root),
+                "epoch" => epoch.as_u64(),
+            );
+
+            if changed > 0 {
+                warn!(
+                    log,
+                    "Proposer duties changed during routine update";
+                    "info" => "Chain re-org likely occurred",
+                    "changed_slots" => changed,
+                    "epoch" => epoch.as_u64(),
+                )
+            }
         }
 
-        trace!(
-            log,
-            "Performed duties update";
-            "identical" => identical,
-            "new_epoch" => new_epoch,
-            "new_proposal_slots" => new_proposal_slots,
-            "new_validator" => new_validator,
-            "replaced" => replaced,
-            "epoch" => format!("{}", epoch)
-        );
+        let validator_subscriptions = if update_attesters {
+            let previous_root = self.attesters.dependent_root(epoch);
+            let outcome = self.attesters.update(
+                epoch,
+                dependent_roots.attester_dependent_root,
+                all_duties,
+                &self.validator_store,
+            );
+
+            for e in outcome.errors {
+                error!(
+                    log,
+                    "Unable to store duties";
+                    "error" => e
+                );
+            }
+
+            if outcome.invalid > 0 {
+                error!(
+                    log,
+                    "Received invalid duties from beacon node";
+                    "bad_duty_count" => outcome.invalid,
+                    "info" => "Duties are from wrong epoch."
+                )
+            }
 
-        if replaced > 0 {
-            warn!(
+            debug!(
                 log,
-                "Duties changed during routine update";
-                "info" => "Chain re-org likely occurred",
-                "replaced" => replaced,
-            )
+                "Updated attester duties";
+                "dependent_root" => format!("{:?}", dependent_roots.attester_dependent_root),
+                "previous_dependent_root" => format!("{:?}", previous_root),
+                "epoch" => epoch.as_u64(),
+            );
+
+            if outcome.changed > 0 {
+                warn!(
+                    log,
+                    "Duties changed during routine update";
+                    "info" => "Chain re-org likely occurred",
+                    "replaced" => outcome.changed,
+                    "epoch" => epoch.as_u64(),
+                )
+            }
+
+            outcome.subscriptions
+        } else {
+            vec![]
+        };
+
+        if !proposer_preparation_data.is_empty() {
+            if let Err(e) = self
+                .beacon_node
+                .http
+                .validator()
+                .prepare_beacon_proposer(proposer_preparation_data)
+                .await
+            {
+                warn!(
+                    log,
+                    "Failed to register proposer preparation";
+                    "error" => format!("{:?}", e)
+                );
+            }
         }
 
-        let log = self.context.log().clone();
         let count = validator_subscriptions.len();
 
         if count == 0 {
@@ -720,15 +603,3 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
         }
     }
 }
-
-/// Returns `true` if the slots in the `duties` are from the given `epoch`
-fn duties_match_epoch(duties: &ValidatorDuty, epoch: Epoch, slots_per_epoch: u64) -> bool {
-    duties
-        .attestation_slot
-        .map_or(true, |slot| slot.epoch(slots_per_epoch) == epoch)
-        && duties.block_proposal_slots.as_ref().map_or(true, |slots| {
-            slots
-                .iter()
-                .all(|slot| slot.epoch(slots_per_epoch) == epoch)
-        })
-}
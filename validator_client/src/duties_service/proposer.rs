@@ -0,0 +1,224 @@
+//! Stores the block proposal duties of the managed validators.
+//!
+//! The proposer shuffling of epoch `N` is decided by the block at the last slot of epoch `N - 1`
+//! (the "dependent root"), so proposal duties can only be known once epoch `N` has begun. As with
+//! attestation duties, the proposals of each epoch are stored alongside their dependent root.
+
+use super::DutyAndProof;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+/// The block proposal duties of the managed validators in some epoch.
+struct EpochProposers {
+    /// The root of the block which decided the proposer shuffling of the epoch.
+    dependent_root: Hash256,
+    /// The validators whose proposal duties are known, whether or not they are due to propose.
+    validators: HashSet<PublicKey>,
+    /// The managed validators which are due to propose, keyed by slot.
+    proposers: HashMap<Slot, Vec<PublicKey>>,
+}
+
+/// Stores the block proposal duties of the managed validators, keyed by epoch.
+#[derive(Default)]
+pub struct ProposerDuties {
+    epochs: RwLock<HashMap<Epoch, EpochProposers>>,
+}
+
+impl ProposerDuties {
+    /// Returns `true` if the proposals of `epoch` are unknown, were decided by a block other than
+    /// `dependent_root` or are missing any of `pubkeys`.
+    pub fn requires_update(
+        &self,
+        epoch: Epoch,
+        dependent_root: Hash256,
+        pubkeys: &[PublicKey],
+    ) -> bool {
+        self.epochs.read().get(&epoch).map_or(true, |known| {
+            known.dependent_root != dependent_root
+                || pubkeys
+                    .iter()
+                    .any(|pubkey| !known.validators.contains(pubkey))
+        })
+    }
+
+    /// Replaces the proposals of `epoch` with those in `duties`, which were decided by
+    /// `dependent_root`.
+    ///
+    /// Validators without a known index are considered to have no proposals. Validators with an
+    /// index but without proposal slots are not stored, so their proposals are downloaded again.
+    /// Proposal slots outside of `epoch` are ignored.
+    ///
+    /// Returns the number of proposal slots which differ from those previously known for the
+    /// epoch.
+    pub fn update(
+        &self,
+        epoch: Epoch,
+        dependent_root: Hash256,
+        duties: &[DutyAndProof],
+        slots_per_epoch: u64,
+    ) -> usize {
+        let mut validators = HashSet::with_capacity(duties.len());
+        let mut proposers: HashMap<Slot, Vec<PublicKey>> = HashMap::new();
+
+        for duty in duties {
+            match (&duty.duty.validator_index, &duty.duty.block_proposal_slots) {
+                (None, _) => {}
+                (Some(_), Some(slots)) => {
+                    for slot in slots {
+                        if slot.epoch(slots_per_epoch) == epoch {
+                            proposers
+                                .entry(*slot)
+                                .or_default()
+                                .push(duty.validator_pubkey().clone());
+                        }
+                    }
+                }
+                (Some(_), None) => continue,
+            }
+
+            validators.insert(duty.validator_pubkey().clone());
+        }
+
+        let mut epochs = self.epochs.write();
+
+        let changed = epochs.get(&epoch).map_or(0, |known| {
+            epoch
+                .slot_iter(slots_per_epoch)
+                .filter(|slot| {
+                    let previous = known.proposers.get(slot).into_iter().flatten();
+                    let current = proposers.get(slot).into_iter().flatten();
+                    previous.collect::<HashSet<_>>() != current.collect::<HashSet<_>>()
+                })
+                .count()
+        });
+
+        epochs.insert(
+            epoch,
+            EpochProposers {
+                dependent_root,
+                validators,
+                proposers,
+            },
+        );
+
+        changed
+    }
+
+    /// Returns the pubkeys of the validators which are assigned to propose in the given slot.
+    pub fn block_proposers(&self, slot: Slot, slots_per_epoch: u64) -> Vec<PublicKey> {
+        self.epochs
+            .read()
+            .get(&slot.epoch(slots_per_epoch))
+            .and_then(|known| known.proposers.get(&slot))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the earliest known proposal slot of `pubkey` which is not prior to `from`.
+    pub fn next_proposal_slot(&self, pubkey: &PublicKey, from: Slot) -> Option<Slot> {
+        self.epochs
+            .read()
+            .values()
+            .flat_map(|known| known.proposers.iter())
+            .filter(|(slot, proposers)| **slot >= from && proposers.contains(pubkey))
+            .map(|(slot, _)| *slot)
+            .min()
+    }
+
+    /// Returns the total number of validators that should propose in the given epoch.
+    pub fn proposer_count(&self, epoch: Epoch) -> usize {
+        self.epochs.read().get(&epoch).map_or(0, |known| {
+            known
+                .proposers
+                .values()
+                .flatten()
+                .collect::<HashSet<_>>()
+                .len()
+        })
+    }
+
+    /// Removes the proposals of all epochs prior to `prior_to`.
+    pub fn prune(&self, prior_to: Epoch) {
+        self.epochs.write().retain(|epoch, _| *epoch >= prior_to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rest_types::ValidatorDuty;
+    use types::{test_utils::generate_deterministic_keypair, EthSpec, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn duty(validator_index: u64, block_proposal_slots: Option<Vec<Slot>>) -> DutyAndProof {
+        DutyAndProof {
+            duty: ValidatorDuty {
+                validator_pubkey: generate_deterministic_keypair(validator_index as usize).pk,
+                validator_index: Some(validator_index),
+                attestation_slot: None,
+                attestation_committee_index: None,
+                attestation_committee_position: None,
+                committee_count_at_slot: None,
+                block_proposal_slots,
+                aggregator_modulo: None,
+            },
+            selection_proof: None,
+        }
+    }
+
+    #[test]
+    fn requires_update_on_dependent_root_change() {
+        let store = ProposerDuties::default();
+        let epoch = Epoch::new(1);
+        let slot = epoch.start_slot(E::slots_per_epoch()) + 2;
+        let root_a = Hash256::from_low_u64_be(1);
+        let root_b = Hash256::from_low_u64_be(2);
+        let duties = vec![duty(0, Some(vec![slot])), duty(1, Some(vec![]))];
+        let pubkeys = duties
+            .iter()
+            .map(|duty| duty.validator_pubkey().clone())
+            .collect::<Vec<_>>();
+
+        assert!(store.requires_update(epoch, root_a, &pubkeys));
+        assert_eq!(
+            store.update(epoch, root_a, &duties, E::slots_per_epoch()),
+            0
+        );
+        assert!(!store.requires_update(epoch, root_a, &pubkeys));
+        assert!(store.requires_update(epoch, root_b, &pubkeys));
+        assert_eq!(
+            store.block_proposers(slot, E::slots_per_epoch()),
+            vec![pubkeys[0].clone()]
+        );
+        assert_eq!(store.proposer_count(epoch), 1);
+        assert_eq!(store.next_proposal_slot(&pubkeys[0], slot), Some(slot));
+        assert_eq!(store.next_proposal_slot(&pubkeys[0], slot + 1), None);
+        assert_eq!(store.next_proposal_slot(&pubkeys[1], slot), None);
+
+        // A re-org moves the proposal to the other validator.
+        let duties = vec![duty(0, Some(vec![])), duty(1, Some(vec![slot]))];
+        assert_eq!(
+            store.update(epoch, root_b, &duties, E::slots_per_epoch()),
+            1
+        );
+        assert!(!store.requires_update(epoch, root_b, &pubkeys));
+        assert_eq!(
+            store.block_proposers(slot, E::slots_per_epoch()),
+            vec![pubkeys[1].clone()]
+        );
+    }
+
+    #[test]
+    fn unknown_proposals_are_not_stored() {
+        let store = ProposerDuties::default();
+        let epoch = Epoch::new(1);
+        let root = Hash256::from_low_u64_be(1);
+        let duties = vec![duty(0, None)];
+        let pubkeys = vec![duties[0].validator_pubkey().clone()];
+
+        store.update(epoch, root, &duties, E::slots_per_epoch());
+        assert!(store.requires_update(epoch, root, &pubkeys));
+    }
+}
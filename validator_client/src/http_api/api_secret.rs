@@ -0,0 +1,155 @@
+use hyper::header::HeaderValue;
+use rand::Rng;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The name of the file which stores the API token.
+pub const API_TOKEN_FILENAME: &str = "api-token.txt";
+
+/// All API tokens start with this prefix, to make them easy to identify.
+const TOKEN_PREFIX: &str = "api-token-0x";
+
+/// The number of random bytes in an API token.
+const TOKEN_BYTES_LEN: usize = 32;
+
+/// The secret token which must be supplied in the `Authorization: Bearer <token>` header of every
+/// request to the validator client HTTP API.
+///
+/// The token is stored in plain-text in `API_TOKEN_FILENAME` so that other local applications
+/// (e.g., a user interface) can read it.
+pub struct ApiSecret {
+    token: String,
+    path: PathBuf,
+}
+
+impl ApiSecret {
+    /// Reads the token from `API_TOKEN_FILENAME` in `dir`, creating a file with a new random token
+    /// if it does not yet exist.
+    pub fn create_or_open<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
+        let path = dir.as_ref().join(API_TOKEN_FILENAME);
+
+        if !path.exists() {
+            let bytes: [u8; TOKEN_BYTES_LEN] = rand::thread_rng().gen();
+            let token = format!("{}{}", TOKEN_PREFIX, hex::encode(bytes));
+            write_token_to_file(&path, &token)?;
+        }
+
+        let token = fs::read_to_string(&path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+            .trim()
+            .to_string();
+
+        if !token.starts_with(TOKEN_PREFIX) || token.len() <= TOKEN_PREFIX.len() {
+            return Err(format!(
+                "Invalid API token in {:?}, it must start with {}. Delete the file to generate \
+                a new token.",
+                path, TOKEN_PREFIX
+            ));
+        }
+
+        Ok(Self { token, path })
+    }
+
+    /// Returns the API token.
+    pub fn api_token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the path of the file containing the API token.
+    pub fn api_token_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the value of the `Authorization` header which is accepted by `is_authorized`.
+    pub fn authorization_header_value(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    /// Returns `true` if `header` is a valid `Authorization` header value.
+    pub fn is_authorized(&self, header: Option<&HeaderValue>) -> bool {
+        header
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| {
+                let mut parts = header.trim().splitn(2, ' ');
+                match (parts.next(), parts.next()) {
+                    (Some(scheme), Some(token)) if scheme.eq_ignore_ascii_case("bearer") => {
+                        Some(token.trim())
+                    }
+                    _ => None,
+                }
+            })
+            .map_or(false, |token| {
+                constant_time_eq(token.as_bytes(), self.token.as_bytes())
+            })
+    }
+}
+
+/// Compares `a` and `b` in time that depends only on their lengths, to avoid leaking the token via
+/// a timing side-channel.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Creates a file with `600 (-rw-------)` permissions.
+fn write_token_to_file(path: &Path, token: &str) -> Result<(), String> {
+    let mut file =
+        File::create(path).map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut perm = file
+            .metadata()
+            .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+            .permissions();
+        perm.set_mode(0o600);
+        file.set_permissions(perm)
+            .map_err(|e| format!("Unable to set permissions on {:?}: {:?}", path, e))?;
+    }
+
+    file.write_all(token.as_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn create_and_reopen() {
+        let dir = TempDir::new("api_secret").unwrap();
+
+        let secret = ApiSecret::create_or_open(dir.path()).unwrap();
+        assert!(secret.api_token().starts_with(TOKEN_PREFIX));
+        assert_eq!(secret.api_token_path(), dir.path().join(API_TOKEN_FILENAME));
+
+        let reopened = ApiSecret::create_or_open(dir.path()).unwrap();
+        assert_eq!(secret.api_token(), reopened.api_token());
+    }
+
+    #[test]
+    fn invalid_token_file() {
+        let dir = TempDir::new("api_secret").unwrap();
+        fs::write(dir.path().join(API_TOKEN_FILENAME), "hunter2").unwrap();
+
+        assert!(ApiSecret::create_or_open(dir.path()).is_err());
+    }
+
+    #[test]
+    fn authorization() {
+        let dir = TempDir::new("api_secret").unwrap();
+        let secret = ApiSecret::create_or_open(dir.path()).unwrap();
+
+        let header = |s: &str| HeaderValue::from_str(s).unwrap();
+
+        assert!(secret.is_authorized(Some(&header(&secret.authorization_header_value()))));
+        assert!(secret.is_authorized(Some(&header(&format!("bearer {}", secret.api_token())))));
+        assert!(!secret.is_authorized(None));
+        assert!(!secret.is_authorized(Some(&header(secret.api_token()))));
+        assert!(!secret.is_authorized(Some(&header("Bearer api-token-0x00"))));
+        assert!(!secret.is_authorized(Some(&header(&format!("Basic {}", secret.api_token())))));
+    }
+}
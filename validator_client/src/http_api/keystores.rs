@@ -0,0 +1,295 @@
+//! Implements the keymanager API, which adds validators to and removes validators from a running
+//! validator client.
+//!
+//! Validators are written to (and deleted from) the validator client's data directory, so that
+//! the change persists across restarts.
+
+use super::Context;
+use eth2_keystore::Keystore;
+use rest_types::{
+    DeleteKeystoreStatus, DeleteKeystoreStatusData, DeleteKeystoresRequest, ExportKeystoreData,
+    ExportKeystoresResponse, ImportKeystoreStatus, ImportKeystoreStatusData,
+    ImportKeystoresRequest,
+};
+use slashing_protection::ValidatorRecords;
+use slog::{info, warn};
+use slot_clock::SlotClock;
+use std::convert::TryInto;
+use std::fs;
+use std::path::PathBuf;
+use types::{EthSpec, PublicKey, PublicKeyBytes};
+use validator_dir::{Builder as ValidatorDirBuilder, VOTING_KEYSTORE_FILE};
+
+/// Imports the keystores in `request`, returning the result for each of them.
+///
+/// The slashing protection history in `request` is imported before any of the keystores, so
+/// that an imported validator can never sign without its history. An `Err` is returned (and no
+/// keystores are imported) if the request itself is invalid.
+pub fn import<T: SlotClock + 'static, E: EthSpec>(
+    ctx: &Context<T, E>,
+    request: ImportKeystoresRequest,
+) -> Result<Vec<ImportKeystoreStatusData>, String> {
+    let _keystores_lock = ctx.keystores_lock.lock();
+
+    if request.keystores.len() != request.passwords.len() {
+        return Err(format!(
+            "{} keystores were supplied with {} passwords",
+            request.keystores.len(),
+            request.passwords.len()
+        ));
+    }
+
+    if let Some(slashing_protection) = &request.slashing_protection {
+        let records: Vec<ValidatorRecords> = serde_json::from_str(slashing_protection)
+            .map_err(|e| format!("Invalid slashing protection: {:?}", e))?;
+        ctx.validator_store.import_slashing_protection(&records)?;
+    }
+
+    Ok(request
+        .keystores
+        .iter()
+        .zip(request.passwords.iter())
+        .map(
+            |(keystore, password)| match import_keystore(ctx, keystore, password) {
+                Ok(status) => ImportKeystoreStatusData {
+                    status,
+                    message: None,
+                },
+                Err(e) => ImportKeystoreStatusData {
+                    status: ImportKeystoreStatus::Error,
+                    message: Some(e),
+                },
+            },
+        )
+        .collect())
+}
+
+/// Decrypts `keystore`, writes it (and `password`) to the data directory and starts performing
+/// the duties of its validator.
+fn import_keystore<T: SlotClock + 'static, E: EthSpec>(
+    ctx: &Context<T, E>,
+    keystore: &str,
+    password: &str,
+) -> Result<ImportKeystoreStatus, String> {
+    let keystore =
+        Keystore::from_json_str(keystore).map_err(|e| format!("Invalid keystore: {:?}", e))?;
+    let voting_keypair = keystore
+        .decrypt_keypair(password.as_bytes())
+        .map_err(|e| format!("Unable to decrypt keystore: {:?}", e))?;
+    let pubkey = voting_keypair.pk.clone();
+
+    if ctx.validator_store.has_validator(&pubkey) {
+        return Ok(ImportKeystoreStatus::Duplicate);
+    }
+
+    // Refuse to touch files which we did not create, so that they can be removed if the import
+    // fails.
+    let (validator_dir_path, password_path) = validator_paths(ctx, &pubkey);
+    for path in &[&validator_dir_path, &password_path] {
+        if path.exists() {
+            return Err(format!(
+                "{:?} already exists but the validator is not active",
+                path
+            ));
+        }
+    }
+
+    let result = ValidatorDirBuilder::new(ctx.validators_dir.clone(), ctx.secrets_dir.clone())
+        .voting_keystore(keystore, password.as_bytes())
+        .store_withdrawal_keystore(false)
+        .build()
+        .map_err(|e| format!("Unable to create validator directory: {:?}", e))
+        .and_then(|validator_dir| {
+            ctx.validator_store
+                .add_validator(voting_keypair, validator_dir)
+        });
+
+    if let Err(e) = result {
+        return Err(match remove_validator_files(ctx, &pubkey) {
+            Ok(()) => e,
+            Err(cleanup_error) => format!("{}. {}", e, cleanup_error),
+        });
+    }
+
+    info!(
+        ctx.log,
+        "Imported validator";
+        "voting_pubkey" => pubkey.as_hex_string(),
+    );
+
+    Ok(ImportKeystoreStatus::Imported)
+}
+
+/// Deletes the validators in `request`, returning the result for each of them along with their
+/// slashing protection history.
+///
+/// If `export == true`, the keystore and password of each deleted validator is also returned.
+pub fn delete<T: SlotClock + 'static, E: EthSpec>(
+    ctx: &Context<T, E>,
+    request: &DeleteKeystoresRequest,
+    export: bool,
+) -> Result<ExportKeystoresResponse, String> {
+    let _keystores_lock = ctx.keystores_lock.lock();
+    let mut records = vec![];
+
+    let data = request
+        .pubkeys
+        .iter()
+        .map(|pubkey| {
+            delete_keystore(ctx, pubkey, export, &mut records).unwrap_or_else(|e| {
+                ExportKeystoreData {
+                    status: DeleteKeystoreStatusData {
+                        status: DeleteKeystoreStatus::Error,
+                        message: Some(e),
+                    },
+                    validating_keystore: None,
+                    validating_keystore_password: None,
+                }
+            })
+        })
+        .collect();
+
+    let slashing_protection = serde_json::to_string(&records)
+        .map_err(|e| format!("Unable to serialize slashing protection: {:?}", e))?;
+
+    Ok(ExportKeystoresResponse {
+        data,
+        slashing_protection,
+    })
+}
+
+/// Stops the validator with `pubkey_bytes` from performing its duties and deletes it from the
+/// data directory, pushing its slashing protection history to `records`.
+///
+/// The validator is removed from the `ValidatorStore` before its slashing protection history is
+/// exported, so that the exported history is complete. If the validator cannot be exported it is
+/// restored, so an `Err` leaves the validator active unless its files could not be deleted.
+fn delete_keystore<T: SlotClock + 'static, E: EthSpec>(
+    ctx: &Context<T, E>,
+    pubkey_bytes: &PublicKeyBytes,
+    export: bool,
+    records: &mut Vec<ValidatorRecords>,
+) -> Result<ExportKeystoreData, String> {
+    let pubkey: PublicKey = pubkey_bytes
+        .try_into()
+        .map_err(|e| format!("Invalid pubkey: {:?}", e))?;
+
+    let (voting_keypair, validator_dir) = match ctx.validator_store.remove_validator(&pubkey) {
+        Some(validator) => validator,
+        None => {
+            let status = match ctx.validator_store.export_slashing_protection(&pubkey)? {
+                Some(validator_records) => {
+                    records.push(validator_records);
+                    DeleteKeystoreStatus::NotActive
+                }
+                None => DeleteKeystoreStatus::NotFound,
+            };
+
+            return Ok(ExportKeystoreData {
+                status: DeleteKeystoreStatusData {
+                    status,
+                    message: None,
+                },
+                validating_keystore: None,
+                validating_keystore_password: None,
+            });
+        }
+    };
+
+    let exported = ctx
+        .validator_store
+        .export_slashing_protection(&pubkey)
+        .and_then(|validator_records| {
+            let validator_records =
+                validator_records.unwrap_or_else(|| ValidatorRecords::empty(pubkey.clone()));
+
+            if export {
+                let (validator_dir_path, password_path) = validator_paths(ctx, &pubkey);
+                let keystore_path = validator_dir_path.join(VOTING_KEYSTORE_FILE);
+                let keystore = fs::read_to_string(&keystore_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", keystore_path, e))?;
+                let password = fs::read_to_string(&password_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", password_path, e))?;
+                Ok((validator_records, Some(keystore), Some(password)))
+            } else {
+                Ok((validator_records, None, None))
+            }
+        });
+
+    let (validator_records, keystore, password) = match exported {
+        Ok(exported) => exported,
+        Err(e) => {
+            ctx.validator_store
+                .add_validator(voting_keypair, validator_dir)
+                .map_err(|restore_error| {
+                    format!(
+                        "{}. Unable to restore the validator, restart the validator client: {}",
+                        e, restore_error
+                    )
+                })?;
+            return Err(e);
+        }
+    };
+
+    records.push(validator_records);
+
+    // Release the lockfile before deleting the directory which contains it.
+    drop(validator_dir);
+    remove_validator_files(ctx, &pubkey).map_err(|e| {
+        warn!(
+            ctx.log,
+            "Removed validator could not be deleted";
+            "voting_pubkey" => pubkey.as_hex_string(),
+            "error" => &e,
+        );
+        format!(
+            "The validator has stopped, but will be loaded again when the validator client \
+            restarts. {}",
+            e
+        )
+    })?;
+
+    info!(
+        ctx.log,
+        "Deleted validator";
+        "voting_pubkey" => pubkey.as_hex_string(),
+    );
+
+    Ok(ExportKeystoreData {
+        status: DeleteKeystoreStatusData {
+            status: DeleteKeystoreStatus::Deleted,
+            message: None,
+        },
+        validating_keystore: keystore,
+        validating_keystore_password: password,
+    })
+}
+
+/// Returns the paths of the validator directory and voting keystore password of the validator
+/// with `pubkey`.
+fn validator_paths<T, E: EthSpec>(ctx: &Context<T, E>, pubkey: &PublicKey) -> (PathBuf, PathBuf) {
+    (
+        ctx.validators_dir.join(pubkey.as_hex_string()),
+        ctx.secrets_dir.join(pubkey.as_hex_string()),
+    )
+}
+
+/// Removes the validator directory and voting keystore password of the validator with `pubkey`,
+/// if they exist.
+fn remove_validator_files<T, E: EthSpec>(
+    ctx: &Context<T, E>,
+    pubkey: &PublicKey,
+) -> Result<(), String> {
+    let (validator_dir_path, password_path) = validator_paths(ctx, pubkey);
+
+    if validator_dir_path.exists() {
+        fs::remove_dir_all(&validator_dir_path)
+            .map_err(|e| format!("Unable to delete {:?}: {:?}", validator_dir_path, e))?;
+    }
+    if password_path.exists() {
+        fs::remove_file(&password_path)
+            .map_err(|e| format!("Unable to delete {:?}: {:?}", password_path, e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! A HTTP server which exposes information about the validator client to local applications.
+//!
+//! Every request must carry the token stored in `api_secret::API_TOKEN_FILENAME` via an
+//! `Authorization: Bearer <token>` header.
+//!
+//! The keymanager API (`/eth/v1/keystores` and `/lighthouse/keystores`) adds and removes
+//! validators while the validator client is running.
+
+mod api_secret;
+mod keystores;
+
+pub use api_secret::{ApiSecret, API_TOKEN_FILENAME};
+
+use crate::{duties_service::DutiesService, metrics, validator_store::ValidatorStore};
+use environment::TaskExecutor;
+use futures::future::TryFutureExt;
+use http_tls::TlsConfig;
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderValue};
+use hyper::service::service_fn;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use metrics::{Encoder, TextEncoder};
+use parking_lot::Mutex;
+use rest_types::{
+    DeleteKeystoresRequest, DeleteKeystoresResponse, GenericResponse, ImportKeystoresRequest,
+    ValidatorStatus,
+};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use slog::{debug, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use types::{ChainSpec, EthSpec, PublicKeyBytes, Slot};
+
+/// The default port for the validator client HTTP API.
+pub const DEFAULT_PORT: u16 = 5062;
+
+/// The maximum size of a request body. Requests with a larger body are rejected.
+pub const MAX_REQUEST_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Configuration for the validator client HTTP API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    pub listen_addr: Ipv4Addr,
+    pub listen_port: u16,
+    /// If `Some`, the server terminates TLS using the given certificate and key.
+    pub tls_config: Option<TlsConfig>,
+    /// If `Some`, the server listens on a unix domain socket at this path instead of
+    /// `listen_addr` and `listen_port`.
+    pub socket_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: Ipv4Addr::new(127, 0, 0, 1),
+            listen_port: DEFAULT_PORT,
+            tls_config: None,
+            socket_path: None,
+        }
+    }
+}
+
+/// The state shared by all requests to the HTTP API.
+pub struct Context<T, E: EthSpec> {
+    pub api_secret: ApiSecret,
+    pub validator_store: ValidatorStore<T, E>,
+    pub duties_service: DutiesService<T, E>,
+    /// The directory into which imported validators are written.
+    pub validators_dir: PathBuf,
+    /// The directory into which the passwords of imported validators are written.
+    pub secrets_dir: PathBuf,
+    /// Held while validators are imported or deleted, so that concurrent requests cannot modify
+    /// the files of the same validator.
+    pub keystores_lock: Mutex<()>,
+    pub spec: ChainSpec,
+    pub log: Logger,
+}
+
+/// Returned by `GET /lighthouse/version`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionData {
+    pub version: String,
+}
+
+/// An item in the list returned by `GET /lighthouse/validators`.
+///
+/// The index, balance and status are read from the beacon node and are `None` if the validator is
+/// unknown to the beacon node or the beacon node could not be reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorData {
+    pub voting_pubkey: PublicKeyBytes,
+    /// Whether the validator client is performing the duties of the validator. This is always
+    /// `true`, since all validators loaded by the validator client perform their duties.
+    pub enabled: bool,
+    pub validator_index: Option<usize>,
+    pub balance: Option<u64>,
+    pub status: Option<ValidatorStatus>,
+    /// The next slot, from the current slot onwards, in which the validator is known to attest.
+    pub next_attestation_slot: Option<Slot>,
+    /// The next slot, from the current slot onwards, in which the validator is known to propose.
+    pub next_proposal_slot: Option<Slot>,
+}
+
+/// Starts the HTTP API server, returning the address it is listening on (or `None` if it is
+/// listening on a unix socket).
+pub fn serve<T: SlotClock + 'static, E: EthSpec>(
+    ctx: Arc<Context<T, E>>,
+    config: &Config,
+    executor: TaskExecutor,
+) -> Result<Option<SocketAddr>, String> {
+    let log = ctx.log.clone();
+
+    let new_service = move || {
+        let ctx = ctx.clone();
+        service_fn(move |req: Request<Body>| {
+            let ctx = ctx.clone();
+            async move { Ok::<_, hyper::Error>(route(req, &ctx).await) }
+        })
+    };
+
+    let bind_addr = SocketAddr::from((config.listen_addr, config.listen_port));
+
+    let exit = executor.exit();
+    let inner_log = log.clone();
+    let shutdown = async move {
+        let _ = exit.await;
+        info!(inner_log, "HTTP API shutdown");
+    };
+
+    let (actual_listen_addr, server_future) = http_tls::serve(
+        bind_addr,
+        config.socket_path.as_deref(),
+        config.tls_config.as_ref(),
+        new_service,
+        shutdown,
+        &executor,
+    )
+    .map_err(|e| format!("Unable to start HTTP API: {}", e))?;
+
+    let server_future = server_future
+        .map_err(move |e| warn!(log, "HTTP API failed"; "error" => format!("{:?}", e)))
+        .unwrap_or_else(|_| ());
+
+    executor.spawn_without_exit(server_future, "http_api");
+
+    Ok(actual_listen_addr)
+}
+
+/// Authorizes `req` and then dispatches it to the appropriate handler.
+async fn route<T: SlotClock + 'static, E: EthSpec>(
+    req: Request<Body>,
+    ctx: &Context<T, E>,
+) -> Response<Body> {
+    debug!(
+        ctx.log,
+        "Processing HTTP API request";
+        "method" => req.method().as_str(),
+        "path" => req.uri().path(),
+    );
+
+    let authorization = req.headers().get(header::AUTHORIZATION);
+    if authorization.is_none() {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "Missing Authorization: Bearer header",
+        );
+    } else if !ctx.api_secret.is_authorized(authorization) {
+        return error_response(StatusCode::FORBIDDEN, "Invalid API token");
+    }
+
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    match (&method, path.as_str()) {
+        (&Method::GET, "/lighthouse/version") => {
+            json_response(&GenericResponse::from(VersionData {
+                version: version::version(),
+            }))
+        }
+        (&Method::GET, "/lighthouse/validators") => {
+            json_response(&GenericResponse::from(validators(ctx).await))
+        }
+        (&Method::GET, "/metrics") => metrics_response(),
+        (&Method::POST, "/eth/v1/keystores") => {
+            match read_json_body::<ImportKeystoresRequest>(req.into_body()).await {
+                Ok(request) => match keystores::import(ctx, request) {
+                    Ok(data) => json_response(&GenericResponse::from(data)),
+                    Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+                },
+                Err(response) => response,
+            }
+        }
+        (&Method::DELETE, "/eth/v1/keystores") => {
+            match read_json_body::<DeleteKeystoresRequest>(req.into_body()).await {
+                Ok(request) => match keystores::delete(ctx, &request, false) {
+                    Ok(response) => json_response(&DeleteKeystoresResponse {
+                        data: response.data.into_iter().map(|data| data.status).collect(),
+                        slashing_protection: response.slashing_protection,
+                    }),
+                    Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+                },
+                Err(response) => response,
+            }
+        }
+        (&Method::DELETE, "/lighthouse/keystores") => {
+            match read_json_body::<DeleteKeystoresRequest>(req.into_body()).await {
+                Ok(request) => match keystores::delete(ctx, &request, true) {
+                    Ok(response) => json_response(&response),
+                    Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+                },
+                Err(response) => response,
+            }
+        }
+        _ => error_response(
+            StatusCode::NOT_FOUND,
+            "Request path and/or method not found.",
+        ),
+    }
+}
+
+/// Returns a summary of each validator managed by the validator client.
+async fn validators<T: SlotClock + 'static, E: EthSpec>(ctx: &Context<T, E>) -> Vec<ValidatorData> {
+    let pubkeys = ctx.validator_store.voting_pubkeys();
+    let current_slot = ctx.duties_service.slot_clock.now();
+
+    let responses = match ctx
+        .duties_service
+        .beacon_node
+        .http
+        .beacon()
+        .get_validators(pubkeys.clone(), None)
+        .await
+    {
+        Ok(responses) => responses,
+        Err(e) => {
+            warn!(
+                ctx.log,
+                "Unable to get validators from beacon node";
+                "error" => format!("{:?}", e),
+            );
+            vec![]
+        }
+    };
+
+    pubkeys
+        .into_iter()
+        .map(|pubkey| {
+            let voting_pubkey = PublicKeyBytes::from(pubkey.clone());
+            let response = responses
+                .iter()
+                .find(|response| response.pubkey == voting_pubkey);
+            let balance = response.and_then(|response| response.balance);
+            let status = response
+                .and_then(|response| response.validator.as_ref())
+                .and_then(|validator| {
+                    let epoch = current_slot?.epoch(E::slots_per_epoch());
+                    Some(ValidatorStatus::from_validator(
+                        validator,
+                        balance.unwrap_or(0),
+                        epoch,
+                        ctx.spec.far_future_epoch,
+                    ))
+                });
+
+            ValidatorData {
+                voting_pubkey,
+                enabled: true,
+                validator_index: response.and_then(|response| response.validator_index),
+                balance,
+                status,
+                next_attestation_slot: current_slot
+                    .and_then(|slot| ctx.duties_service.next_attestation_slot(&pubkey, slot)),
+                next_proposal_slot: current_slot
+                    .and_then(|slot| ctx.duties_service.next_proposal_slot(&pubkey, slot)),
+            }
+        })
+        .collect()
+}
+
+/// Reads a JSON object from `body`, returning an error response if `body` is invalid or larger
+/// than `MAX_REQUEST_BODY_BYTES`.
+async fn read_json_body<T: DeserializeOwned>(mut body: Body) -> Result<T, Response<Body>> {
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Unable to read request body: {:?}", e),
+            )
+        })?;
+        if bytes.len() + chunk.len() > MAX_REQUEST_BODY_BYTES {
+            return Err(error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!(
+                    "Request body is larger than {} bytes",
+                    MAX_REQUEST_BODY_BYTES
+                ),
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    serde_json::from_slice(&bytes).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Unable to parse request body: {:?}", e),
+        )
+    })
+}
+
+fn json_response<T: serde::Serialize>(item: &T) -> Response<Body> {
+    match serde_json::to_vec(item) {
+        Ok(body) => Response::builder()
+            .status(StatusCode::OK)
+            .header(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )
+            .body(Body::from(body))
+            .expect("Response should always be created."),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &format!("Unable to serialize response: {:?}", e),
+        ),
+    }
+}
+
+fn metrics_response() -> Response<Body> {
+    let mut buffer = vec![];
+    match TextEncoder::new().encode(&metrics::gather(), &mut buffer) {
+        Ok(()) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"))
+            .body(Body::from(buffer))
+            .expect("Response should always be created."),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &format!("Unable to encode metrics: {:?}", e),
+        ),
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"))
+        .body(Body::from(message.to_string()))
+        .expect("Response should always be created.")
+}
@@ -1,10 +1,16 @@
+#[macro_use]
+extern crate lazy_static;
+
 mod attestation_service;
 mod block_service;
 mod cli;
 mod config;
 mod duties_service;
 mod fork_service;
+mod http_api;
 mod is_synced;
+mod metrics;
+mod monitoring;
 mod notifier;
 mod validator_store;
 
@@ -19,11 +25,16 @@ use duties_service::{DutiesService, DutiesServiceBuilder};
 use environment::RuntimeContext;
 use fork_service::{ForkService, ForkServiceBuilder};
 use futures::channel::mpsc;
+use http_api::ApiSecret;
+use monitoring::spawn_monitoring_client;
 use notifier::spawn_notifier;
-use remote_beacon_node::RemoteBeaconNode;
+use parking_lot::Mutex;
+use remote_beacon_node::{Encoding, RemoteBeaconNode};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
+use std::fs;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{delay_for, Duration};
 use types::EthSpec;
@@ -36,12 +47,13 @@ const RETRY_DELAY: Duration = Duration::from_secs(2);
 /// The global timeout for HTTP requests to the beacon node.
 const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
 
-pub struct ProductionValidatorClient<T: EthSpec> {
+pub struct ProductionValidatorClient<T: EthSpec, S: SlotClock = SystemTimeSlotClock> {
     context: RuntimeContext<T>,
-    duties_service: DutiesService<SystemTimeSlotClock, T>,
-    fork_service: ForkService<SystemTimeSlotClock, T>,
-    block_service: BlockService<SystemTimeSlotClock, T>,
-    attestation_service: AttestationService<SystemTimeSlotClock, T>,
+    duties_service: DutiesService<S, T>,
+    fork_service: ForkService<S, T>,
+    block_service: BlockService<S, T>,
+    attestation_service: AttestationService<S, T>,
+    validator_store: ValidatorStore<S, T>,
     config: Config,
 }
 
@@ -59,13 +71,28 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
     /// Instantiates the validator client, _without_ starting the timers to trigger block
     /// and attestation production.
-    pub async fn new(mut context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+    pub async fn new(context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+        Self::new_with_slot_clock(context, config).await
+    }
+}
+
+impl<T: EthSpec, S: SlotClock + Clone + 'static> ProductionValidatorClient<T, S> {
+    /// Instantiates the validator client with a slot clock of type `S`, _without_ starting the
+    /// timers to trigger block and attestation production.
+    ///
+    /// Tests may use a `ManualSlotClock` in order to control time deterministically, see
+    /// `Self::slot_clock`.
+    pub async fn new_with_slot_clock(
+        mut context: RuntimeContext<T>,
+        config: Config,
+    ) -> Result<Self, String> {
         let log = context.log().clone();
 
         info!(
             log,
             "Starting validator client";
             "beacon_node" => &config.http_server,
+            "fallback_beacon_nodes" => config.fallback_http_servers.len(),
             "datadir" => format!("{:?}", config.data_dir),
         );
 
@@ -95,9 +122,12 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "count" => validators.len(),
         );
 
-        let beacon_node =
-            RemoteBeaconNode::new_with_timeout(config.http_server.clone(), HTTP_TIMEOUT)
-                .map_err(|e| format!("Unable to init beacon node http client: {}", e))?;
+        let beacon_node = init_beacon_node(&config.http_server, &config)?;
+        let fallback_beacon_nodes = config
+            .fallback_http_servers
+            .iter()
+            .map(|http_server| init_beacon_node(http_server, &config))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // TODO: check if all logs in wait_for_node are produed while awaiting
         let beacon_node = wait_for_node(beacon_node, &log).await?;
@@ -167,7 +197,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // for Lighthouse.
         context.eth2_config = eth2_config;
 
-        let slot_clock = SystemTimeSlotClock::new(
+        let slot_clock = S::new(
             context.eth2_config.spec.genesis_slot,
             Duration::from_secs(genesis_time),
             Duration::from_millis(context.eth2_config.spec.milliseconds_per_slot),
@@ -179,7 +209,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .runtime_context(context.service_context("fork".into()))
             .build()?;
 
-        let validator_store: ValidatorStore<SystemTimeSlotClock, T> = ValidatorStore::new(
+        let validator_store: ValidatorStore<S, T> = ValidatorStore::new(
             validators,
             &config,
             genesis_validators_root,
@@ -212,13 +242,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
             .beacon_node(beacon_node.clone())
+            .fallback_beacon_nodes(fallback_beacon_nodes)
             .runtime_context(context.service_context("block".into()))
             .build()?;
 
         let attestation_service = AttestationServiceBuilder::new()
             .duties_service(duties_service.clone())
             .slot_clock(slot_clock)
-            .validator_store(validator_store)
+            .validator_store(validator_store.clone())
             .beacon_node(beacon_node)
             .runtime_context(context.service_context("attestation".into()))
             .build()?;
@@ -229,10 +260,16 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             fork_service,
             block_service,
             attestation_service,
+            validator_store,
             config,
         })
     }
 
+    /// Returns the slot clock shared by all of the validator client services.
+    pub fn slot_clock(&self) -> &S {
+        &self.duties_service.slot_clock
+    }
+
     pub fn start_service(&mut self) -> Result<(), String> {
         // We use `SLOTS_PER_EPOCH` as the capacity of the block notification channel, because
         // we don't except notifications to be delayed by more than a single slot, let alone a
@@ -262,10 +299,72 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        if let Some(monitoring_config) = &self.config.monitoring_api {
+            spawn_monitoring_client(self, monitoring_config)
+                .map_err(|e| format!("Failed to start monitoring client: {}", e))?;
+        }
+
+        if self.config.http_api.enabled {
+            let log = self.context.log().clone();
+            let api_secret = ApiSecret::create_or_open(&self.config.data_dir)?;
+            let token_path = api_secret.api_token_path().to_path_buf();
+
+            let ctx = Arc::new(http_api::Context {
+                api_secret,
+                validator_store: self.validator_store.clone(),
+                duties_service: self.duties_service.clone(),
+                validators_dir: self.config.data_dir.clone(),
+                secrets_dir: self.config.secrets_dir.clone(),
+                keystores_lock: Mutex::new(()),
+                spec: self.context.eth2_config.spec.clone(),
+                log: log.clone(),
+            });
+
+            let listen_addr =
+                http_api::serve(ctx, &self.config.http_api, self.context.executor.clone())?;
+
+            if let Some(listen_addr) = listen_addr {
+                info!(
+                    log,
+                    "HTTP API started";
+                    "address" => format!("{}", listen_addr),
+                    "tls" => self.config.http_api.tls_config.is_some(),
+                    "api_token_file" => format!("{:?}", token_path),
+                );
+            } else {
+                info!(
+                    log,
+                    "HTTP API started";
+                    "socket_path" => format!("{:?}", self.config.http_api.socket_path),
+                    "api_token_file" => format!("{:?}", token_path),
+                );
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Creates a client for the beacon node at `http_server`, using the TLS and encoding settings in
+/// `config`.
+fn init_beacon_node<E: EthSpec>(
+    http_server: &str,
+    config: &Config,
+) -> Result<RemoteBeaconNode<E>, String> {
+    let mut beacon_node = if let Some(ca_cert_path) = &config.beacon_node_tls_ca_cert {
+        let ca_cert = fs::read(ca_cert_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", ca_cert_path, e))?;
+        RemoteBeaconNode::new_with_ca_cert(http_server.to_string(), HTTP_TIMEOUT, &ca_cert)
+    } else {
+        RemoteBeaconNode::new_with_timeout(http_server.to_string(), HTTP_TIMEOUT)
+    }
+    .map_err(|e| format!("Unable to init beacon node http client: {}", e))?;
+    if config.beacon_node_ssz {
+        beacon_node.http = beacon_node.http.with_encoding(Encoding::Ssz);
+    }
+    Ok(beacon_node)
+}
+
 /// Request the version from the node, looping back and trying again on failure. Exit once the node
 /// has been contacted.
 async fn wait_for_node<E: EthSpec>(
@@ -0,0 +1,19 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    /*
+     * Block proposals
+     */
+    pub static ref BLOCK_PROPOSAL_FALLBACK_ATTEMPTS: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposal_fallback_attempts_total",
+        "Count of attempts to produce or publish a block using a fallback beacon node"
+    );
+    pub static ref BLOCK_PROPOSALS_RECOVERED: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposals_recovered_total",
+        "Count of block proposals which succeeded using a fallback beacon node"
+    );
+    pub static ref BLOCK_PROPOSALS_FAILED: Result<IntCounter> = try_create_int_counter(
+        "vc_block_proposals_failed_total",
+        "Count of block proposals which could not be produced or published by any beacon node"
+    );
+}
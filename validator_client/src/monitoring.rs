@@ -0,0 +1,65 @@
+use crate::ProductionValidatorClient;
+use monitoring_api::{MonitoringHttpClient, ProcessData, ProcessType, ValidatorData};
+use slot_clock::SlotClock;
+use types::EthSpec;
+
+/// Spawns a service which periodically sends a summary of the validators managed by this client
+/// to the remote monitoring endpoint given in the config.
+pub fn spawn_monitoring_client<T: EthSpec, S: SlotClock + 'static>(
+    client: &ProductionValidatorClient<T, S>,
+    config: &monitoring_api::Config,
+) -> Result<(), String> {
+    let context = client.context.service_context("monitoring_api".into());
+    let duties_service = client.duties_service.clone();
+    let validator_store = client.validator_store.clone();
+
+    let monitoring_client =
+        MonitoringHttpClient::new(config, ProcessType::Validator, context.log().clone())?;
+
+    monitoring_client.auto_update(context.executor, move || {
+        let duties_service = duties_service.clone();
+        let validator_store = validator_store.clone();
+
+        async move {
+            let epoch = duties_service
+                .slot_clock
+                .now()
+                .ok_or_else(|| "Unable to read slot clock".to_string())?
+                .epoch(T::slots_per_epoch());
+
+            let pubkeys = validator_store.voting_pubkeys();
+            let validator_total = pubkeys.len() as u64;
+
+            let validators = duties_service
+                .beacon_node
+                .http
+                .beacon()
+                .get_validators(pubkeys, None)
+                .await
+                .map_err(|e| format!("Unable to get validators from beacon node: {:?}", e))?;
+
+            let validator_active = validators
+                .iter()
+                .filter(|response| {
+                    response
+                        .validator
+                        .as_ref()
+                        .map_or(false, |validator| validator.is_active_at(epoch))
+                })
+                .count() as u64;
+
+            let validator_balance_total_gwei = validators
+                .iter()
+                .filter_map(|response| response.balance)
+                .sum();
+
+            Ok(ProcessData::Validator(ValidatorData {
+                validator_total,
+                validator_active,
+                validator_balance_total_gwei,
+            }))
+        }
+    });
+
+    Ok(())
+}
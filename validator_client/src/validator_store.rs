@@ -1,7 +1,7 @@
 use crate::config::SLASHING_PROTECTION_FILENAME;
 use crate::{config::Config, fork_service::ForkService};
 use parking_lot::RwLock;
-use slashing_protection::{NotSafe, Safe, SlashingDatabase};
+use slashing_protection::{NotSafe, Safe, SigningRequest, SlashingDatabase, ValidatorRecords};
 use slog::{crit, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -114,6 +114,102 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().len()
     }
 
+    /// Returns `true` if the validator with `validator_pubkey` is performing its duties.
+    pub fn has_validator(&self, validator_pubkey: &PublicKey) -> bool {
+        self.validators.read().contains_key(validator_pubkey)
+    }
+
+    /// Adds a validator, which will start performing its duties from the next duties update.
+    ///
+    /// The validator is registered with the slashing protection database (if it is not already)
+    /// before it is added, so it can never sign without slashing protection.
+    pub fn add_validator(
+        &self,
+        voting_keypair: Keypair,
+        validator_dir: ValidatorDir,
+    ) -> Result<(), String> {
+        let pubkey = voting_keypair.pk.clone();
+
+        self.slashing_protection
+            .import_validator_records(&ValidatorRecords::empty(pubkey.clone()))
+            .map_err(|e| format!("Unable to register validator: {:?}", e))?;
+
+        let mut validators = self.validators.write();
+        if validators.contains_key(&pubkey) {
+            return Err(format!(
+                "Validator {} already exists",
+                pubkey.as_hex_string()
+            ));
+        }
+        validators.insert(
+            pubkey,
+            LocalValidator {
+                validator_dir,
+                voting_keypair,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes a validator so that it no longer signs any messages, returning its keypair and
+    /// directory (or `None` if the validator is unknown).
+    ///
+    /// Every message signed by the validator is recorded in the slashing protection database
+    /// before it is signed, so the slashing protection history exported after this call is
+    /// complete.
+    pub fn remove_validator(
+        &self,
+        validator_pubkey: &PublicKey,
+    ) -> Option<(Keypair, ValidatorDir)> {
+        self.validators
+            .write()
+            .remove(validator_pubkey)
+            .map(|local_validator| {
+                (
+                    local_validator.voting_keypair,
+                    local_validator.validator_dir,
+                )
+            })
+    }
+
+    /// Imports slashing protection history, registering any unknown validators.
+    pub fn import_slashing_protection(&self, records: &[ValidatorRecords]) -> Result<(), String> {
+        for records in records {
+            self.slashing_protection
+                .import_validator_records(records)
+                .map_err(|e| {
+                    format!(
+                        "Unable to import slashing protection for {}: {:?}",
+                        records.pubkey.as_hex_string(),
+                        e
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Exports the slashing protection history of the validator with `validator_pubkey`, returning
+    /// `None` if it is not registered with the slashing protection database.
+    pub fn export_slashing_protection(
+        &self,
+        validator_pubkey: &PublicKey,
+    ) -> Result<Option<ValidatorRecords>, String> {
+        match self
+            .slashing_protection
+            .export_validator_records(validator_pubkey)
+        {
+            Ok(records) => Ok(Some(records)),
+            Err(NotSafe::UnregisteredValidator(_)) => Ok(None),
+            Err(e) => Err(format!(
+                "Unable to export slashing protection for {}: {:?}",
+                validator_pubkey.as_hex_string(),
+                e
+            )),
+        }
+    }
+
     fn fork(&self) -> Option<Fork> {
         if self.fork_service.fork().is_none() {
             error!(
@@ -124,6 +220,69 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.fork_service.fork()
     }
 
+    /// Checks a block proposal or attestation against the slashing protection database, recording
+    /// it if it is safe.
+    ///
+    /// This is the only gate through which blocks and attestations pass before signing. Returns
+    /// `true` only if the message has not been signed before and is not slashable.
+    fn check_slashing_protection<'a>(
+        &self,
+        validator_pubkey: &PublicKey,
+        request: impl Into<SigningRequest<'a>>,
+        domain: Hash256,
+    ) -> bool {
+        let request = request.into();
+        let kind = match request {
+            SigningRequest::BlockProposal(_) => "block",
+            SigningRequest::Attestation(_) => "attestation",
+        };
+
+        match self
+            .slashing_protection
+            .check_and_insert(validator_pubkey, request, domain)
+        {
+            // We can safely sign this message.
+            Ok(Safe::Valid) => true,
+            Ok(Safe::SameData) => {
+                warn!(
+                    self.log,
+                    "Skipping signing of previously signed message";
+                    "kind" => kind,
+                );
+                false
+            }
+            Err(NotSafe::UnregisteredValidator(pk)) => {
+                warn!(
+                    self.log,
+                    "Not signing message for unregistered validator";
+                    "msg" => "Carefully consider running with --auto-register (see --help)",
+                    "kind" => kind,
+                    "public_key" => format!("{:?}", pk)
+                );
+                false
+            }
+            Err(e) if e.is_slashable() => {
+                crit!(
+                    self.log,
+                    "Not signing slashable message";
+                    "kind" => kind,
+                    "request" => format!("{:?}", request),
+                    "error" => format!("{:?}", e)
+                );
+                false
+            }
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Unable to check message for slashing safety";
+                    "kind" => kind,
+                    "error" => format!("{:?}", e)
+                );
+                false
+            }
+        }
+    }
+
     pub fn randao_reveal(&self, validator_pubkey: &PublicKey, epoch: Epoch) -> Option<Signature> {
         // TODO: check this against the slot clock to make sure it's not an early reveal?
         self.validators
@@ -169,51 +328,21 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             self.genesis_validators_root,
         );
 
-        let slashing_status = self.slashing_protection.check_and_insert_block_proposal(
-            validator_pubkey,
-            &block.block_header(),
-            domain,
-        );
+        let block_header = block.block_header();
+        if !self.check_slashing_protection(validator_pubkey, &block_header, domain) {
+            return None;
+        }
 
-        match slashing_status {
-            // We can safely sign this block.
-            Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let validator = validators.get(validator_pubkey)?;
-                let voting_keypair = &validator.voting_keypair;
+        let validators = self.validators.read();
+        let validator = validators.get(validator_pubkey)?;
+        let voting_keypair = &validator.voting_keypair;
 
-                Some(block.sign(
-                    &voting_keypair.sk,
-                    &fork,
-                    self.genesis_validators_root,
-                    &self.spec,
-                ))
-            }
-            Ok(Safe::SameData) => {
-                warn!(
-                    self.log,
-                    "Skipping signing of previously signed block";
-                );
-                None
-            }
-            Err(NotSafe::UnregisteredValidator(pk)) => {
-                warn!(
-                    self.log,
-                    "Not signing block for unregistered validator";
-                    "msg" => "Carefully consider running with --auto-register (see --help)",
-                    "public_key" => format!("{:?}", pk)
-                );
-                None
-            }
-            Err(e) => {
-                crit!(
-                    self.log,
-                    "Not signing slashable block";
-                    "error" => format!("{:?}", e)
-                );
-                None
-            }
-        }
+        Some(block.sign(
+            &voting_keypair.sk,
+            &fork,
+            self.genesis_validators_root,
+            &self.spec,
+        ))
     }
 
     pub fn sign_attestation(
@@ -237,64 +366,32 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             &fork,
             self.genesis_validators_root,
         );
-        let slashing_status = self.slashing_protection.check_and_insert_attestation(
-            validator_pubkey,
-            &attestation.data,
-            domain,
-        );
+        if !self.check_slashing_protection(validator_pubkey, &attestation.data, domain) {
+            return None;
+        }
 
-        match slashing_status {
-            // We can safely sign this attestation.
-            Ok(Safe::Valid) => {
-                let validators = self.validators.read();
-                let validator = validators.get(validator_pubkey)?;
-                let voting_keypair = &validator.voting_keypair;
-
-                attestation
-                    .sign(
-                        &voting_keypair.sk,
-                        validator_committee_position,
-                        &fork,
-                        self.genesis_validators_root,
-                        &self.spec,
-                    )
-                    .map_err(|e| {
-                        error!(
-                            self.log,
-                            "Error whilst signing attestation";
-                            "error" => format!("{:?}", e)
-                        )
-                    })
-                    .ok()?;
-
-                Some(())
-            }
-            Ok(Safe::SameData) => {
-                warn!(
-                    self.log,
-                    "Skipping signing of previously signed attestation"
-                );
-                None
-            }
-            Err(NotSafe::UnregisteredValidator(pk)) => {
-                warn!(
-                    self.log,
-                    "Not signing attestation for unregistered validator";
-                    "msg" => "Carefully consider running with --auto-register (see --help)",
-                    "public_key" => format!("{:?}", pk)
-                );
-                None
-            }
-            Err(e) => {
-                crit!(
+        let validators = self.validators.read();
+        let validator = validators.get(validator_pubkey)?;
+        let voting_keypair = &validator.voting_keypair;
+
+        attestation
+            .sign(
+                &voting_keypair.sk,
+                validator_committee_position,
+                &fork,
+                self.genesis_validators_root,
+                &self.spec,
+            )
+            .map_err(|e| {
+                error!(
                     self.log,
-                    "Not signing slashable attestation";
-                    "attestation" => format!("{:?}", attestation.data),
+                    "Error whilst signing attestation";
                     "error" => format!("{:?}", e)
-                );
-                None
-            }
-        }
+                )
+            })
+            .ok()?;
+
+        Some(())
     }
 
     /// Signs an `AggregateAndProof` for a given validator.
@@ -0,0 +1,344 @@
+use bls::PublicKey;
+use eth2_keystore::Keystore;
+use serde_derive::{Deserialize, Serialize};
+use slashing_protection::{SlashingDatabase, ValidatorRecords, SLASHING_PROTECTION_FILENAME};
+use std::ffi::OsStr;
+use std::fs::{self, create_dir_all, File};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::{ChainSpec, DepositData};
+use validator_dir::{ValidatorDir, VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE};
+
+/// The lockfile created by `ValidatorDir::open`. It must never be copied between directories.
+const LOCK_FILE: &str = ".lock";
+
+/// The directories used by a single validator client.
+pub struct ValidatorClientDirs {
+    /// Contains one `ValidatorDir` per validator and the slashing protection database.
+    pub validators_dir: PathBuf,
+    /// Contains the passwords for the keystores in `validators_dir`.
+    pub secrets_dir: PathBuf,
+}
+
+impl ValidatorClientDirs {
+    pub fn slashing_protection_path(&self) -> PathBuf {
+        self.validators_dir.join(SLASHING_PROTECTION_FILENAME)
+    }
+
+    /// Opens the slashing protection database, creating it if it does not exist.
+    pub fn open_or_create_slashing_protection(&self) -> Result<SlashingDatabase, String> {
+        let path = self.slashing_protection_path();
+        SlashingDatabase::open_or_create(&path)
+            .map_err(|e| format!("Unable to open slashing protection DB {:?}: {:?}", path, e))
+    }
+
+    /// Opens the slashing protection database, returning `None` if it does not exist.
+    pub fn open_slashing_protection(&self) -> Result<Option<SlashingDatabase>, String> {
+        let path = self.slashing_protection_path();
+        if path.exists() {
+            SlashingDatabase::open(&path)
+                .map(Some)
+                .map_err(|e| format!("Unable to open slashing protection DB {:?}: {:?}", path, e))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// A deposit in the JSON format produced by the `eth2.0-deposit-cli` and consumed by the Eth2
+/// launchpad.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct StandardDepositDataJson {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+}
+
+impl StandardDepositDataJson {
+    pub fn new(deposit_data: &DepositData, spec: &ChainSpec) -> Self {
+        Self {
+            pubkey: hex::encode(deposit_data.pubkey.as_bytes()),
+            withdrawal_credentials: hex::encode(deposit_data.withdrawal_credentials.as_bytes()),
+            amount: deposit_data.amount,
+            signature: hex::encode(deposit_data.signature.as_bytes()),
+            deposit_message_root: hex::encode(
+                deposit_data
+                    .as_deposit_message()
+                    .tree_hash_root()
+                    .as_bytes(),
+            ),
+            deposit_data_root: hex::encode(deposit_data.tree_hash_root().as_bytes()),
+            fork_version: hex::encode(&spec.genesis_fork_version),
+        }
+    }
+}
+
+pub fn ensure_dir_exists<P: AsRef<Path>>(path: P) -> Result<(), String> {
+    let path = path.as_ref();
+
+    if !path.exists() {
+        create_dir_all(path).map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+    }
+
+    Ok(())
+}
+
+/// Reads the keystore at `path` and returns the public key it claims to hold.
+///
+/// The keystore is not decrypted, so the public key is not verified against the secret key.
+fn read_keystore_pubkey(path: &Path) -> Result<PublicKey, String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+    let keystore = Keystore::from_json_reader(file)
+        .map_err(|e| format!("Unable to parse keystore {:?}: {:?}", path, e))?;
+    let bytes = hex::decode(keystore.pubkey())
+        .map_err(|e| format!("Invalid pubkey hex in {:?}: {:?}", path, e))?;
+    PublicKey::from_bytes(&bytes).map_err(|e| format!("Invalid pubkey in {:?}: {:?}", path, e))
+}
+
+/// Returns the voting public key of the validator in `validator_dir`.
+pub fn voting_pubkey(validator_dir: &ValidatorDir) -> Result<PublicKey, String> {
+    read_keystore_pubkey(&validator_dir.dir().join(VOTING_KEYSTORE_FILE))
+}
+
+/// The paths created in the destination by `transfer_validator`, which are removed if it fails.
+#[derive(Default)]
+struct CreatedPaths(Vec<PathBuf>);
+
+impl CreatedPaths {
+    /// Removes every created path (most recent first), returning `error` extended with a
+    /// description of what remains in the destination.
+    fn roll_back(self, error: String, pubkey: &PublicKey) -> String {
+        let leftover = self
+            .0
+            .iter()
+            .rev()
+            .filter_map(|path| {
+                let result = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                result.err().map(|e| format!("{:?} ({:?})", path, e))
+            })
+            .collect::<Vec<_>>();
+
+        let files = if leftover.is_empty() {
+            "All files written to the destination were removed".to_string()
+        } else {
+            format!(
+                "These files could not be removed from the destination and must be removed by \
+                hand before retrying: {}",
+                leftover.join(", ")
+            )
+        };
+
+        format!(
+            "{}. {} was not transferred. {}. Its slashing protection history was left in the \
+            destination slashing protection database, which only prevents it from signing \
+            messages which conflict with that history.",
+            error,
+            pubkey.as_hex_string(),
+            files
+        )
+    }
+}
+
+/// Returns a path for `name` in the parent of `validators_dir`, so that a validator client will
+/// never attempt to load a validator from it.
+fn staging_path(validators_dir: &Path, prefix: &str, name: &OsStr) -> PathBuf {
+    validators_dir
+        .parent()
+        .unwrap_or(validators_dir)
+        .join(format!("{}{}", prefix, name.to_string_lossy()))
+}
+
+/// Removes `path` if it has been left behind by an earlier, interrupted transfer.
+fn remove_stale(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        fs::remove_dir_all(path).map_err(|e| format!("Unable to remove stale {:?}: {:?}", path, e))
+    } else {
+        Ok(())
+    }
+}
+
+/// Moves or copies a single validator from `source` to `dest`.
+///
+/// The steps are ordered so that the validator can never be active in `dest` without its
+/// slashing protection history:
+///
+/// 1. The slashing protection history from `source_db` (if any) is imported into `dest_db`. The
+///    validator is always registered in `dest_db`, even if there is no history.
+/// 2. The keystore passwords are copied into `dest.secrets_dir`.
+/// 3. The validator directory is copied to a temporary location and then renamed into
+///    `dest.validators_dir`.
+/// 4. If `remove_source == true`, the validator directory is renamed out of
+///    `source.validators_dir` and then deleted, along with its passwords.
+///
+/// If any of steps 2-4 fail before the validator has left `source`, every file written to `dest`
+/// is removed so that the validator only exists in `source`. The history imported in step 1 is
+/// kept, since `dest_db` may have held history for the validator already. Once the source
+/// directory has been renamed the validator only exists in `dest`, so a failure to delete it is
+/// reported along with the paths which remain.
+///
+/// The caller must hold `validator_dir` open (and therefore locked) for the duration of this
+/// call; it is consumed so that its lockfile is released before the source is removed.
+pub fn transfer_validator(
+    validator_dir: ValidatorDir,
+    source: &ValidatorClientDirs,
+    dest: &ValidatorClientDirs,
+    source_db: Option<&SlashingDatabase>,
+    dest_db: &SlashingDatabase,
+    remove_source: bool,
+) -> Result<PublicKey, String> {
+    let source_dir = validator_dir.dir().clone();
+    let dir_name = source_dir
+        .file_name()
+        .ok_or_else(|| format!("Invalid validator directory {:?}", source_dir))?
+        .to_os_string();
+    let dest_dir = dest.validators_dir.join(&dir_name);
+
+    if dest_dir.exists() {
+        return Err(format!("Destination {:?} already exists", dest_dir));
+    }
+
+    let pubkey = voting_pubkey(&validator_dir)?;
+
+    let mut password_files = vec![pubkey.as_hex_string()];
+    let withdrawal_keystore = source_dir.join(WITHDRAWAL_KEYSTORE_FILE);
+    if withdrawal_keystore.exists() {
+        password_files.push(read_keystore_pubkey(&withdrawal_keystore)?.as_hex_string());
+    }
+    for name in &password_files {
+        let to = dest.secrets_dir.join(name);
+        if source.secrets_dir.join(name).exists() && to.exists() {
+            return Err(format!("Password file {:?} already exists", to));
+        }
+    }
+
+    // The temporary directory is a sibling of `dest.validators_dir` (rather than a child) so
+    // that a validator client will never attempt to load a partially-copied validator.
+    let tmp_dir = staging_path(&dest.validators_dir, ".vm-tmp-", &dir_name);
+    let removed_dir = staging_path(&source.validators_dir, ".vm-removed-", &dir_name);
+    remove_stale(&tmp_dir)?;
+    if remove_source {
+        remove_stale(&removed_dir)?;
+    }
+
+    // 1. Slashing protection.
+    let records = match source_db {
+        Some(db) => match db.export_validator_records(&pubkey) {
+            Ok(records) => records,
+            Err(slashing_protection::NotSafe::UnregisteredValidator(_)) => {
+                ValidatorRecords::empty(pubkey.clone())
+            }
+            Err(e) => {
+                return Err(format!(
+                    "Unable to export slashing protection for {}: {:?}",
+                    pubkey.as_hex_string(),
+                    e
+                ))
+            }
+        },
+        None => ValidatorRecords::empty(pubkey.clone()),
+    };
+    dest_db.import_validator_records(&records).map_err(|e| {
+        format!(
+            "Unable to import slashing protection for {}: {:?}",
+            pubkey.as_hex_string(),
+            e
+        )
+    })?;
+
+    let mut created = CreatedPaths::default();
+
+    // 2. Passwords.
+    for name in &password_files {
+        let from = source.secrets_dir.join(name);
+        let to = dest.secrets_dir.join(name);
+        if !from.exists() {
+            continue;
+        }
+        created.0.push(to.clone());
+        if let Err(e) = fs::copy(&from, &to) {
+            return Err(created.roll_back(format!("Unable to copy {:?}: {:?}", from, e), &pubkey));
+        }
+    }
+
+    // 3. Validator directory.
+    created.0.push(tmp_dir.clone());
+    if let Err(e) = copy_validator_files(&source_dir, &tmp_dir) {
+        return Err(created.roll_back(e, &pubkey));
+    }
+    if let Err(e) = fs::rename(&tmp_dir, &dest_dir) {
+        return Err(created.roll_back(
+            format!("Unable to move {:?} to {:?}: {:?}", tmp_dir, dest_dir, e),
+            &pubkey,
+        ));
+    }
+    created.0.pop();
+    created.0.push(dest_dir);
+
+    // 4. Cleanup.
+    drop(validator_dir);
+    if remove_source {
+        // Renaming is atomic, so the validator is never in both `source` and `dest` once this
+        // succeeds, even if deleting it fails.
+        if let Err(e) = fs::rename(&source_dir, &removed_dir) {
+            return Err(created.roll_back(
+                format!(
+                    "Unable to move {:?} to {:?}: {:?}",
+                    source_dir, removed_dir, e
+                ),
+                &pubkey,
+            ));
+        }
+
+        let mut leftover = vec![];
+        if let Err(e) = fs::remove_dir_all(&removed_dir) {
+            leftover.push(format!("{:?} ({:?})", removed_dir, e));
+        }
+        for name in &password_files {
+            let path = source.secrets_dir.join(name);
+            if path.exists() {
+                if let Err(e) = fs::remove_file(&path) {
+                    leftover.push(format!("{:?} ({:?})", path, e));
+                }
+            }
+        }
+
+        if !leftover.is_empty() {
+            return Err(format!(
+                "{} was moved, but these files could not be removed from the source and must be \
+                removed by hand: {}",
+                pubkey.as_hex_string(),
+                leftover.join(", ")
+            ));
+        }
+    }
+
+    Ok(pubkey)
+}
+
+/// Copies the files in `source_dir` (except its lockfile) into a new directory at `dir`.
+fn copy_validator_files(source_dir: &Path, dir: &Path) -> Result<(), String> {
+    create_dir_all(dir).map_err(|e| format!("Unable to create {:?}: {:?}", dir, e))?;
+    for entry in
+        fs::read_dir(source_dir).map_err(|e| format!("Unable to read {:?}: {:?}", source_dir, e))?
+    {
+        let path = entry
+            .map_err(|e| format!("Unable to read {:?}: {:?}", source_dir, e))?
+            .path();
+        match path.file_name() {
+            Some(name) if path.is_file() && name != LOCK_FILE => {
+                fs::copy(&path, dir.join(name))
+                    .map_err(|e| format!("Unable to copy {:?}: {:?}", path, e))?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use crate::common::{ensure_dir_exists, StandardDepositDataJson};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder, PlainText};
+use eth2_wallet::{
+    bip39::{Language, Mnemonic, Seed as Bip39Seed},
+    recover_validator_secret_from_seed, KeyType,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use types::EthSpec;
+use validator_dir::Builder as ValidatorDirBuilder;
+
+pub const CMD: &str = "create";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const COUNT_FLAG: &str = "count";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
+pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
+
+/// The directory in `--output-path` where the validator directories are created.
+pub const VALIDATORS_DIR: &str = "validators";
+/// The directory in `--output-path` where the keystore passwords are stored.
+pub const SECRETS_DIR: &str = "secrets";
+/// The file in `--output-path` where the deposit data is stored.
+pub const DEPOSITS_FILENAME: &str = "deposits.json";
+
+/// See `account_manager::common::random_password` for the rationale of this length.
+const DEFAULT_PASSWORD_LEN: usize = 48;
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates validators from a BIP-39 mnemonic using the EIP-2334 derivation path. \
+            Produces a directory of keystores and passwords that can be imported into a \
+            validator client, plus a deposits.json file that can be submitted via the Eth2 \
+            launchpad.",
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("A path to a file containing a BIP-39 mnemonic.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The first EIP-2334 index to derive a validator from.")
+                .default_value("0")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("VALIDATOR_COUNT")
+                .help("The number of validators to create, starting at --first-index.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("DIRECTORY")
+                .help(
+                    "The path to a directory where the validators, secrets and deposits will \
+                    be stored. It will be created if it does not exist.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_GWEI_FLAG)
+                .long(DEPOSIT_GWEI_FLAG)
+                .value_name("DEPOSIT_GWEI")
+                .help(
+                    "The GWEI value of the deposit amount. Defaults to the minimum amount \
+                    required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STORE_WITHDRAW_FLAG)
+                .long(STORE_WITHDRAW_FLAG)
+                .help(
+                    "If present, the withdrawal keystore will be stored alongside the voting \
+                    keypair. It is generally recommended to *not* store the withdrawal key and \
+                    instead generate them from the mnemonic when required.",
+                ),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
+
+    let mnemonic_path: PathBuf = clap_utils::parse_required(matches, MNEMONIC_FLAG)?;
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_PATH_FLAG)?;
+    let deposit_gwei = clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
+        .unwrap_or_else(|| spec.max_effective_balance);
+    let store_withdrawal_keystore = matches.is_present(STORE_WITHDRAW_FLAG);
+
+    let last_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} + --{} overflows", FIRST_INDEX_FLAG, COUNT_FLAG))?;
+
+    let deposits_path = output_path.join(DEPOSITS_FILENAME);
+    if deposits_path.exists() {
+        return Err(format!(
+            "{:?} already exists, refusing to overwrite",
+            deposits_path
+        ));
+    }
+
+    let validators_dir = output_path.join(VALIDATORS_DIR);
+    let secrets_dir = output_path.join(SECRETS_DIR);
+    ensure_dir_exists(&validators_dir)?;
+    ensure_dir_exists(&secrets_dir)?;
+
+    let phrase = fs::read_to_string(&mnemonic_path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", mnemonic_path, e))?;
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|e| format!("Unable to parse mnemonic: {:?}", e))?;
+    // TODO: `bip39` does not use zeroize. Perhaps we should make a PR upstream?
+    let seed = Bip39Seed::new(&mnemonic, "");
+
+    let mut deposits = Vec::with_capacity(count as usize);
+
+    for index in first_index..last_index {
+        let (voting_keystore, voting_password) =
+            derive_keystore(seed.as_bytes(), index, KeyType::Voting)?;
+        let (withdrawal_keystore, withdrawal_password) =
+            derive_keystore(seed.as_bytes(), index, KeyType::Withdrawal)?;
+
+        let voting_pubkey = voting_keystore.pubkey().to_string();
+
+        let validator_dir = ValidatorDirBuilder::new(validators_dir.clone(), secrets_dir.clone())
+            .voting_keystore(voting_keystore, voting_password.as_bytes())
+            .withdrawal_keystore(withdrawal_keystore, withdrawal_password.as_bytes())
+            .create_eth1_tx_data(deposit_gwei, &spec)
+            .store_withdrawal_keystore(store_withdrawal_keystore)
+            .build()
+            .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
+
+        let deposit_data = validator_dir
+            .eth1_deposit_data()
+            .map_err(|e| format!("Unable to read deposit data: {:?}", e))?
+            .ok_or_else(|| format!("Missing deposit data for 0x{}", voting_pubkey))?
+            .deposit_data;
+
+        deposits.push(StandardDepositDataJson::new(&deposit_data, &spec));
+
+        println!(
+            "{}/{}\t{}\t0x{}",
+            index - first_index + 1,
+            count,
+            index,
+            voting_pubkey
+        );
+    }
+
+    let file = File::create(&deposits_path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", deposits_path, e))?;
+    serde_json::to_writer_pretty(file, &deposits)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", deposits_path, e))?;
+
+    println!("Deposit data written to {:?}", deposits_path);
+
+    Ok(())
+}
+
+/// Derives the `key_type` key for the validator at `index` from `seed`, returning it as a keystore
+/// encrypted with a new random password.
+fn derive_keystore(
+    seed: &[u8],
+    index: u32,
+    key_type: KeyType,
+) -> Result<(Keystore, PlainText), String> {
+    let (secret, path) = recover_validator_secret_from_seed(seed, index, key_type)
+        .map_err(|e| format!("Unable to derive validator {}: {:?}", index, e))?;
+    let keypair = keypair_from_secret(secret.as_bytes())
+        .map_err(|e| format!("Unable to create keypair for {}: {:?}", index, e))?;
+
+    let password: PlainText = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(DEFAULT_PASSWORD_LEN)
+        .collect::<String>()
+        .into_bytes()
+        .into();
+
+    let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), format!("{}", path))
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Unable to build keystore for {}: {:?}", index, e))?;
+
+    Ok((keystore, password))
+}
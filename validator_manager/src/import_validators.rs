@@ -0,0 +1,158 @@
+use crate::common::{transfer_validator, voting_pubkey, ValidatorClientDirs};
+use crate::create_validators::{SECRETS_DIR, VALIDATORS_DIR};
+use crate::move_validators::{select_validators, DEST_SECRETS_DIR_FLAG, DEST_VALIDATORS_DIR_FLAG};
+use crate::vc_client::ValidatorClientHttpClient;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use rest_types::{ImportKeystoreStatus, ImportKeystoresRequest};
+use std::fs;
+use std::path::PathBuf;
+use types::EthSpec;
+use validator_dir::{ValidatorDir, VOTING_KEYSTORE_FILE};
+
+pub const CMD: &str = "import";
+pub const INPUT_PATH_FLAG: &str = "input-path";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Imports validators produced by the 'create' command into a validator client, \
+            registering each of them with its slashing protection database. With --vc-url the \
+            validators are imported into a running validator client through its keymanager \
+            API. Otherwise they are written to the data directory of a stopped validator \
+            client. The input directory is left unmodified.",
+        )
+        .arg(
+            Arg::with_name(INPUT_PATH_FLAG)
+                .long(INPUT_PATH_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --output-path given to the 'create' command.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(DEST_VALIDATORS_DIR_FLAG)
+                .long(DEST_VALIDATORS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --datadir of the validator client to import validators into.")
+                .takes_value(true)
+                .required_unless(VC_URL_FLAG)
+                .conflicts_with(VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(DEST_SECRETS_DIR_FLAG)
+                .long(DEST_SECRETS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --secrets-dir of the validator client to import validators into.")
+                .takes_value(true)
+                .required_unless(VC_URL_FLAG)
+                .conflicts_with(VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("URL")
+                .help(
+                    "The URL of the HTTP API of a running validator client to import validators \
+                    into, e.g. http://localhost:5062",
+                )
+                .takes_value(true)
+                .requires(VC_TOKEN_FLAG),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The api-token.txt file of the validator client given by --vc-url.")
+                .takes_value(true)
+                .requires(VC_URL_FLAG),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let input_path: PathBuf = clap_utils::parse_required(matches, INPUT_PATH_FLAG)?;
+    let source = ValidatorClientDirs {
+        validators_dir: input_path.join(VALIDATORS_DIR),
+        secrets_dir: input_path.join(SECRETS_DIR),
+    };
+
+    if let Some(vc_url) = clap_utils::parse_optional::<String>(matches, VC_URL_FLAG)? {
+        let token_path: PathBuf = clap_utils::parse_required(matches, VC_TOKEN_FLAG)?;
+        let client = ValidatorClientHttpClient::new(&vc_url, &token_path)?;
+        return env.runtime().block_on(import_via_api(&source, &client));
+    }
+
+    let dest = ValidatorClientDirs {
+        validators_dir: clap_utils::parse_required(matches, DEST_VALIDATORS_DIR_FLAG)?,
+        secrets_dir: clap_utils::parse_required(matches, DEST_SECRETS_DIR_FLAG)?,
+    };
+
+    let paths = select_validators(&source.validators_dir, "all")?;
+    let dest_db = dest.open_or_create_slashing_protection()?;
+
+    let n = paths.len();
+    for (i, path) in paths.into_iter().enumerate() {
+        let validator_dir =
+            ValidatorDir::open(&path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        let pubkey = transfer_validator(validator_dir, &source, &dest, None, &dest_db, false)?;
+        println!("{}/{}\t{}", i + 1, n, pubkey.as_hex_string());
+    }
+
+    Ok(())
+}
+
+/// Imports each validator in `source` into the validator client served by `client`, one at a
+/// time.
+async fn import_via_api(
+    source: &ValidatorClientDirs,
+    client: &ValidatorClientHttpClient,
+) -> Result<(), String> {
+    let paths = select_validators(&source.validators_dir, "all")?;
+
+    let n = paths.len();
+    for (i, path) in paths.into_iter().enumerate() {
+        let validator_dir =
+            ValidatorDir::open(&path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        let pubkey = voting_pubkey(&validator_dir)?;
+
+        let keystore_path = path.join(VOTING_KEYSTORE_FILE);
+        let keystore = fs::read_to_string(&keystore_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", keystore_path, e))?;
+        let password_path = source.secrets_dir.join(pubkey.as_hex_string());
+        let password = fs::read_to_string(&password_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", password_path, e))?;
+
+        let status = client
+            .import_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await?
+            .pop()
+            .ok_or_else(|| format!("No result for {}", pubkey.as_hex_string()))?;
+
+        match status.status {
+            ImportKeystoreStatus::Imported => {
+                println!("{}/{}\t{}", i + 1, n, pubkey.as_hex_string())
+            }
+            ImportKeystoreStatus::Duplicate => println!(
+                "{}/{}\t{}\talready exists, skipped",
+                i + 1,
+                n,
+                pubkey.as_hex_string()
+            ),
+            ImportKeystoreStatus::Error => {
+                return Err(format!(
+                    "Unable to import {}: {}. Validators before it were imported.",
+                    pubkey.as_hex_string(),
+                    status.message.unwrap_or_default()
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
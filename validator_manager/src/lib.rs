@@ -0,0 +1,42 @@
+mod common;
+pub mod create_validators;
+pub mod import_validators;
+pub mod move_validators;
+mod vc_client;
+
+use clap::App;
+use clap::ArgMatches;
+use environment::Environment;
+use types::EthSpec;
+
+pub const CMD: &str = "validator_manager";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .visible_aliases(&["vm", "validator-manager", CMD])
+        .about(
+            "Utilities for managing a large number of validators at once. Creates validators \
+            in bulk from a mnemonic and moves them (with their slashing protection history) \
+            between validator clients.",
+        )
+        .subcommand(create_validators::cli_app())
+        .subcommand(import_validators::cli_app())
+        .subcommand(move_validators::cli_app())
+}
+
+/// Run the validator manager, returning an error if the operation did not succeed.
+pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
+    match matches.subcommand() {
+        (create_validators::CMD, Some(matches)) => create_validators::cli_run(matches, env)?,
+        (import_validators::CMD, Some(matches)) => import_validators::cli_run(matches, env)?,
+        (move_validators::CMD, Some(matches)) => move_validators::cli_run(matches, env)?,
+        (unknown, _) => {
+            return Err(format!(
+                "{} is not a valid {} command. See --help.",
+                unknown, CMD
+            ));
+        }
+    }
+
+    Ok(())
+}
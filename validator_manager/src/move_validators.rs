@@ -0,0 +1,424 @@
+use crate::common::{transfer_validator, ValidatorClientDirs};
+use crate::vc_client::ValidatorClientHttpClient;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use rest_types::{
+    DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus, ImportKeystoresRequest,
+};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use types::{EthSpec, PublicKeyBytes};
+use validator_dir::Manager as ValidatorManager;
+
+pub const CMD: &str = "move";
+pub const SRC_VALIDATORS_DIR_FLAG: &str = "src-validators-dir";
+pub const SRC_SECRETS_DIR_FLAG: &str = "src-secrets-dir";
+pub const DEST_VALIDATORS_DIR_FLAG: &str = "dest-validators-dir";
+pub const DEST_SECRETS_DIR_FLAG: &str = "dest-secrets-dir";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const SRC_VC_URL_FLAG: &str = "src-vc-url";
+pub const SRC_VC_TOKEN_FLAG: &str = "src-vc-token";
+pub const DEST_VC_URL_FLAG: &str = "dest-vc-url";
+pub const DEST_VC_TOKEN_FLAG: &str = "dest-vc-token";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Moves validators from one validator client to another, along with their \
+            slashing protection history. With --src-vc-url and --dest-vc-url the validators \
+            are moved between running validator clients through their keymanager APIs; each \
+            validator is stopped in the source before it is started in the destination. \
+            Otherwise both validator clients must be stopped; this is enforced by the \
+            validator lockfiles and the exclusive lock on the slashing protection databases. \
+            A validator is only removed from the source once it has been fully written to \
+            the destination.",
+        )
+        .arg(
+            Arg::with_name(SRC_VALIDATORS_DIR_FLAG)
+                .long(SRC_VALIDATORS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --datadir of the validator client to move validators from.")
+                .takes_value(true)
+                .required_unless(SRC_VC_URL_FLAG)
+                .conflicts_with(SRC_VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(SRC_SECRETS_DIR_FLAG)
+                .long(SRC_SECRETS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --secrets-dir of the validator client to move validators from.")
+                .takes_value(true)
+                .required_unless(SRC_VC_URL_FLAG)
+                .conflicts_with(SRC_VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(DEST_VALIDATORS_DIR_FLAG)
+                .long(DEST_VALIDATORS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --datadir of the validator client to move validators to.")
+                .takes_value(true)
+                .required_unless(DEST_VC_URL_FLAG)
+                .conflicts_with(DEST_VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(DEST_SECRETS_DIR_FLAG)
+                .long(DEST_SECRETS_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help("The --secrets-dir of the validator client to move validators to.")
+                .takes_value(true)
+                .required_unless(DEST_VC_URL_FLAG)
+                .conflicts_with(DEST_VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("PUBKEYS")
+                .help(
+                    "A comma-separated list of 0x-prefixed validator public keys to move. \
+                    Set to 'all' to move every validator.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(SRC_VC_URL_FLAG)
+                .long(SRC_VC_URL_FLAG)
+                .value_name("URL")
+                .help(
+                    "The URL of the HTTP API of the running validator client to move validators \
+                    from, e.g. http://localhost:5062",
+                )
+                .takes_value(true)
+                .requires_all(&[SRC_VC_TOKEN_FLAG, DEST_VC_URL_FLAG]),
+        )
+        .arg(
+            Arg::with_name(SRC_VC_TOKEN_FLAG)
+                .long(SRC_VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The api-token.txt file of the validator client given by --src-vc-url.")
+                .takes_value(true)
+                .requires(SRC_VC_URL_FLAG),
+        )
+        .arg(
+            Arg::with_name(DEST_VC_URL_FLAG)
+                .long(DEST_VC_URL_FLAG)
+                .value_name("URL")
+                .help(
+                    "The URL of the HTTP API of the running validator client to move validators \
+                    to.",
+                )
+                .takes_value(true)
+                .requires_all(&[DEST_VC_TOKEN_FLAG, SRC_VC_URL_FLAG]),
+        )
+        .arg(
+            Arg::with_name(DEST_VC_TOKEN_FLAG)
+                .long(DEST_VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The api-token.txt file of the validator client given by --dest-vc-url.")
+                .takes_value(true)
+                .requires(DEST_VC_URL_FLAG),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    if let Some(src_url) = clap_utils::parse_optional::<String>(matches, SRC_VC_URL_FLAG)? {
+        let src_token: PathBuf = clap_utils::parse_required(matches, SRC_VC_TOKEN_FLAG)?;
+        let dest_url: String = clap_utils::parse_required(matches, DEST_VC_URL_FLAG)?;
+        let dest_token: PathBuf = clap_utils::parse_required(matches, DEST_VC_TOKEN_FLAG)?;
+        let selection: String = clap_utils::parse_required(matches, VALIDATORS_FLAG)?;
+
+        let source = ValidatorClientHttpClient::new(&src_url, &src_token)?;
+        let dest = ValidatorClientHttpClient::new(&dest_url, &dest_token)?;
+        return env
+            .runtime()
+            .block_on(move_via_api(&source, &dest, &selection));
+    }
+
+    let source = ValidatorClientDirs {
+        validators_dir: clap_utils::parse_required(matches, SRC_VALIDATORS_DIR_FLAG)?,
+        secrets_dir: clap_utils::parse_required(matches, SRC_SECRETS_DIR_FLAG)?,
+    };
+    let dest = ValidatorClientDirs {
+        validators_dir: clap_utils::parse_required(matches, DEST_VALIDATORS_DIR_FLAG)?,
+        secrets_dir: clap_utils::parse_required(matches, DEST_SECRETS_DIR_FLAG)?,
+    };
+    let selection: String = clap_utils::parse_required(matches, VALIDATORS_FLAG)?;
+
+    for dir in &[
+        &source.validators_dir,
+        &source.secrets_dir,
+        &dest.validators_dir,
+        &dest.secrets_dir,
+    ] {
+        if !dir.exists() {
+            return Err(format!("{:?} does not exist", dir));
+        }
+    }
+
+    let paths = select_validators(&source.validators_dir, &selection)?;
+
+    // Open (and hence lock) every validator before making any changes, so we fail early if the
+    // source validator client is running.
+    let manager = ValidatorManager::open(&source.validators_dir)
+        .map_err(|e| format!("Unable to read --{}: {:?}", SRC_VALIDATORS_DIR_FLAG, e))?;
+    let validator_dirs = paths
+        .iter()
+        .map(|path| {
+            manager.open_validator(path).map_err(|e| {
+                format!(
+                    "Unable to open {:?}, is the source validator client running? {:?}",
+                    path, e
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let source_db = source.open_slashing_protection()?;
+    let dest_db = dest.open_or_create_slashing_protection()?;
+
+    let n = validator_dirs.len();
+    for (i, validator_dir) in validator_dirs.into_iter().enumerate() {
+        let pubkey = transfer_validator(
+            validator_dir,
+            &source,
+            &dest,
+            source_db.as_ref(),
+            &dest_db,
+            true,
+        )?;
+        println!("{}/{}\t{}", i + 1, n, pubkey.as_hex_string());
+    }
+
+    Ok(())
+}
+
+/// Returns the paths of the validator directories in `validators_dir` named by `selection`.
+///
+/// `selection` is either `"all"` or a comma-separated list of 0x-prefixed public keys.
+pub fn select_validators(validators_dir: &Path, selection: &str) -> Result<Vec<PathBuf>, String> {
+    if selection == "all" {
+        let mut paths = ValidatorManager::open(validators_dir)
+            .and_then(|manager| manager.directory_names())
+            .map_err(|e| format!("Unable to read {:?}: {:?}", validators_dir, e))?
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    } else {
+        selection
+            .split(',')
+            .map(str::trim)
+            .map(|pubkey| {
+                let path = validators_dir.join(pubkey);
+                if path.is_dir() {
+                    Ok(path)
+                } else {
+                    Err(format!(
+                        "Unknown validator {} in {:?}",
+                        pubkey, validators_dir
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Moves the validators named by `selection` from the validator client served by `source` to
+/// the one served by `dest`, one at a time.
+///
+/// Each validator is deleted from `source` (which returns its keystore, password and complete
+/// slashing protection history) before it is imported into `dest`, so it is never active in both.
+/// If it cannot be imported into `dest` it is imported back into `source`. If that also fails,
+/// the exported validator is written to a recovery file in the current directory so that it is
+/// never lost.
+async fn move_via_api(
+    source: &ValidatorClientHttpClient,
+    dest: &ValidatorClientHttpClient,
+    selection: &str,
+) -> Result<(), String> {
+    let pubkeys = if selection == "all" {
+        source.voting_pubkeys().await?
+    } else {
+        selection
+            .split(',')
+            .map(str::trim)
+            .map(|pubkey| {
+                hex::decode(pubkey.trim_start_matches("0x"))
+                    .map_err(|e| format!("{:?}", e))
+                    .and_then(|bytes| {
+                        PublicKeyBytes::from_bytes(&bytes).map_err(|e| format!("{:?}", e))
+                    })
+                    .map_err(|e| format!("Invalid pubkey {}: {}", pubkey, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let n = pubkeys.len();
+    for (i, pubkey) in pubkeys.into_iter().enumerate() {
+        let pubkey_str = format!("0x{}", hex::encode(pubkey.as_slice()));
+        let not_moved = if i == 0 {
+            "No validators were moved.".to_string()
+        } else {
+            format!("The {} validators before it were moved.", i)
+        };
+
+        let exported = match source
+            .export_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkey.clone()],
+            })
+            .await
+        {
+            Ok(exported) => exported,
+            Err(e) => {
+                // The request may have failed after the validator was deleted, in which case its
+                // keystore has been lost.
+                let state = match source.voting_pubkeys().await {
+                    Ok(active) if active.contains(&pubkey) => {
+                        "It is still active in the source validator client".to_string()
+                    }
+                    Ok(_) => "It is no longer active in the source validator client and may \
+                        have been deleted, in which case it must be re-imported from its \
+                        mnemonic along with its slashing protection history"
+                        .to_string(),
+                    Err(check_error) => format!(
+                        "It is unknown whether it is still active in the source validator \
+                        client: {}",
+                        check_error
+                    ),
+                };
+                return Err(format!(
+                    "Unable to remove {} from the source validator client: {}. {}. {}",
+                    pubkey_str, e, state, not_moved
+                ));
+            }
+        };
+        let data = exported
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("No result for {}. {}", pubkey_str, not_moved))?;
+
+        let (keystore, password) = match (
+            data.status.status,
+            data.validating_keystore,
+            data.validating_keystore_password,
+        ) {
+            (DeleteKeystoreStatus::Deleted, Some(keystore), Some(password)) => (keystore, password),
+            (DeleteKeystoreStatus::Deleted, _, _) => {
+                return Err(format!(
+                    "{} was deleted from the source validator client, which did not return its \
+                    keystore. It must be re-imported from its mnemonic. {}",
+                    pubkey_str, not_moved
+                ))
+            }
+            (status, _, _) => {
+                return Err(format!(
+                    "Unable to remove {} from the source validator client: {:?} {}. {}",
+                    pubkey_str,
+                    status,
+                    data.status.message.unwrap_or_default(),
+                    not_moved
+                ))
+            }
+        };
+
+        let request = ImportKeystoresRequest {
+            keystores: vec![keystore],
+            passwords: vec![password],
+            slashing_protection: Some(exported.slashing_protection),
+        };
+
+        if let Err(e) = import_one(dest, &request).await {
+            // The request may have failed after the validator was imported, in which case it must
+            // not also be restored to the source.
+            let active_in_dest = match dest.voting_pubkeys().await {
+                Ok(active) => active.contains(&pubkey),
+                Err(check_error) => {
+                    let recovery = write_recovery_file(&pubkey_str, &request);
+                    return Err(format!(
+                        "Unable to import {} into the destination validator client: {}. It is \
+                        unknown whether it is active there ({}), so it was not restored to the \
+                        source validator client. {}. {}",
+                        pubkey_str, e, check_error, recovery, not_moved
+                    ));
+                }
+            };
+
+            if !active_in_dest {
+                return Err(match import_one(source, &request).await {
+                    Ok(()) => format!(
+                        "Unable to import {} into the destination validator client: {}. It was \
+                        restored to the source validator client. {}",
+                        pubkey_str, e, not_moved
+                    ),
+                    Err(restore_error) => {
+                        let recovery = write_recovery_file(&pubkey_str, &request);
+                        format!(
+                            "Unable to import {} into the destination validator client: {}. It \
+                            could not be restored to the source validator client either: {}. \
+                            It is not active in either validator client. {}. {}",
+                            pubkey_str, e, restore_error, recovery, not_moved
+                        )
+                    }
+                });
+            }
+        }
+
+        println!("{}/{}\t{}", i + 1, n, pubkey_str);
+    }
+
+    Ok(())
+}
+
+/// Imports the single keystore in `request` into `client`, returning an `Err` unless the
+/// validator is active in `client` afterwards.
+async fn import_one(
+    client: &ValidatorClientHttpClient,
+    request: &ImportKeystoresRequest,
+) -> Result<(), String> {
+    let status = client
+        .import_keystores(request)
+        .await?
+        .pop()
+        .ok_or_else(|| "No result".to_string())?;
+
+    match status.status {
+        ImportKeystoreStatus::Imported | ImportKeystoreStatus::Duplicate => Ok(()),
+        ImportKeystoreStatus::Error => Err(status.message.unwrap_or_default()),
+    }
+}
+
+/// Writes `request` (including the keystore password) to a file in the current directory which is
+/// only readable by the current user, returning a description of the outcome.
+///
+/// The file can be given to `POST /eth/v1/keystores` to import the validator.
+fn write_recovery_file(pubkey: &str, request: &ImportKeystoresRequest) -> String {
+    let path = PathBuf::from(format!("vm-move-recovery-{}.json", pubkey));
+
+    let result = serde_json::to_vec(request)
+        .map_err(|e| format!("{:?}", e))
+        .and_then(|bytes| {
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(&path)
+                .and_then(|mut file| file.write_all(&bytes))
+                .map_err(|e| format!("{:?}", e))
+        });
+
+    match result {
+        Ok(()) => format!(
+            "Its keystore, password and slashing protection history were written to {:?}",
+            path
+        ),
+        Err(e) => format!(
+            "Unable to write its keystore to {:?}: {}. It must be re-imported from its mnemonic \
+            along with its slashing protection history",
+            path, e
+        ),
+    }
+}
@@ -0,0 +1,125 @@
+//! A client for the keymanager API served by a running validator client.
+
+use reqwest::{header, Client, Method, RequestBuilder, Response};
+use rest_types::{
+    DeleteKeystoresRequest, ExportKeystoresResponse, GenericResponse, ImportKeystoreStatusData,
+    ImportKeystoresRequest,
+};
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use std::fs;
+use std::path::Path;
+use types::PublicKeyBytes;
+use url::Url;
+
+/// The fields of an item returned by `GET /lighthouse/validators` which are used here.
+#[derive(Deserialize)]
+struct ValidatorData {
+    voting_pubkey: PublicKeyBytes,
+}
+
+/// Connects to the HTTP API of a validator client, authenticating with its API token.
+pub struct ValidatorClientHttpClient {
+    client: Client,
+    url: Url,
+    authorization: String,
+}
+
+impl ValidatorClientHttpClient {
+    /// Creates a client for the validator client at `url`, authenticating with the token in
+    /// `token_path` (the validator client's `api-token.txt`).
+    pub fn new(url: &str, token_path: &Path) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| format!("Invalid URL {}: {:?}", url, e))?;
+        let token = fs::read_to_string(token_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", token_path, e))?;
+
+        Ok(Self {
+            client: Client::new(),
+            url,
+            authorization: format!("Bearer {}", token.trim()),
+        })
+    }
+
+    /// `GET /lighthouse/validators`
+    ///
+    /// Returns the voting public keys of the validators which are active in the validator client.
+    pub async fn voting_pubkeys(&self) -> Result<Vec<PublicKeyBytes>, String> {
+        let response = self
+            .request(Method::GET, "lighthouse/validators")?
+            .send()
+            .await
+            .map_err(|e| format!("Unable to get validators: {:?}", e))?;
+
+        json::<GenericResponse<Vec<ValidatorData>>>(response)
+            .await
+            .map(|response| {
+                response
+                    .data
+                    .into_iter()
+                    .map(|validator| validator.voting_pubkey)
+                    .collect()
+            })
+    }
+
+    /// `POST /eth/v1/keystores`
+    pub async fn import_keystores(
+        &self,
+        request: &ImportKeystoresRequest,
+    ) -> Result<Vec<ImportKeystoreStatusData>, String> {
+        let response = self
+            .request(Method::POST, "eth/v1/keystores")?
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| format!("Unable to import keystores: {:?}", e))?;
+
+        json::<GenericResponse<_>>(response)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// `DELETE /lighthouse/keystores`
+    ///
+    /// Deletes the validators from the validator client, returning their keystores, passwords and
+    /// slashing protection history.
+    pub async fn export_keystores(
+        &self,
+        request: &DeleteKeystoresRequest,
+    ) -> Result<ExportKeystoresResponse, String> {
+        let response = self
+            .request(Method::DELETE, "lighthouse/keystores")?
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| format!("Unable to export keystores: {:?}", e))?;
+
+        json(response).await
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, String> {
+        let url = self
+            .url
+            .join(path)
+            .map_err(|e| format!("Invalid path {}: {:?}", path, e))?;
+
+        Ok(self
+            .client
+            .request(method, url)
+            .header(header::AUTHORIZATION, self.authorization.as_str()))
+    }
+}
+
+/// Parses the JSON body of `response`, returning an error containing the body if the request did
+/// not succeed.
+async fn json<T: DeserializeOwned>(response: Response) -> Result<T, String> {
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Unable to parse response: {:?}", e))
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("Request failed with {}: {}", status, body))
+    }
+}